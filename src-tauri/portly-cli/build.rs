@@ -0,0 +1,18 @@
+#[path = "src/cli_spec.rs"]
+mod cli_spec;
+
+/// 生成 portly-cli 的 man page，`portly-cli --man` 会直接输出此文件
+fn main() {
+    println!("cargo:rerun-if-changed=src/cli_spec.rs");
+
+    let command = cli_spec::command();
+    command.clone().debug_assert();
+
+    let mut buffer = Vec::new();
+    clap_mangen::Man::new(command)
+        .render(&mut buffer)
+        .expect("failed to render man page");
+
+    let out_dir = std::path::PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR not set"));
+    std::fs::write(out_dir.join("portly-cli.1"), buffer).expect("failed to write man page");
+}
@@ -0,0 +1,545 @@
+//! portly-cli 的命令行定义
+//!
+//! CLI 的参数由 `src/main.rs` 手动解析；这里用 clap 描述同一组选项，
+//! 供 `build.rs` 生成 roff man page（`portly-cli --man` 输出的内容）。
+//! 修改 CLI 选项时需要同步更新此文件
+
+use clap::{Arg, ArgAction, Command};
+
+fn flag(id: &'static str, short: Option<char>, help: &'static str) -> Arg {
+    let arg = Arg::new(id).long(id).action(ArgAction::SetTrue).help(help);
+    match short {
+        Some(c) => arg.short(c),
+        None => arg,
+    }
+}
+
+fn value(id: &'static str, short: Option<char>, name: &'static str, help: &'static str) -> Arg {
+    let arg = Arg::new(id).long(id).value_name(name).help(help);
+    match short {
+        Some(c) => arg.short(c),
+        None => arg,
+    }
+}
+
+fn output_args() -> [Arg; 6] {
+    [
+        flag("json", Some('j'), "JSON output (currently json-v1)"),
+        value(
+            "output",
+            Some('o'),
+            "FORMAT",
+            "Output format: table, porcelain, json, json-v1, csv, ndjson",
+        ),
+        flag("csv", None, "CSV output"),
+        flag("ndjson", None, "Newline-delimited JSON output"),
+        flag(
+            "porcelain",
+            Some('q'),
+            "Tab-separated fields without banners (default when stdout is not a TTY)",
+        ),
+        flag(
+            "no-color",
+            None,
+            "Disable colors (also disabled by NO_COLOR)",
+        ),
+    ]
+}
+
+pub fn command() -> Command {
+    Command::new("portly-cli")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Cross-platform port scanner")
+        .long_about(
+            "List listening ports with their owning processes, scan remote hosts and free \
+             ports held by other processes.",
+        )
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .disable_help_subcommand(true)
+        .args(output_args())
+        .args([
+            flag("group", Some('g'), "Group ports by application"),
+            flag("command", Some('c'), "Show the process command line"),
+            flag("exclude-system", Some('x'), "Exclude system processes"),
+            flag("udp", Some('u'), "Also list UDP sockets (list mode)"),
+            flag("no-udp", None, "Only list TCP ports, overriding defaults.udp"),
+            flag(
+                "dual-stack",
+                None,
+                "Merge IPv4 and IPv6 listeners of the same process into one row",
+            ),
+            flag(
+                "connections",
+                None,
+                "List TCP connections with remote address, state, PID and process",
+            ),
+            value("filter", Some('f'), "APP", "Filter by application name"),
+            value("port", Some('p'), "PORT", "Filter by port"),
+            value(
+                "columns",
+                None,
+                "LIST",
+                "Table columns in order: port, protocol, address, pid, process, user, command",
+            ),
+            value(
+                "lang",
+                None,
+                "zh|en",
+                "Output language (detected from LC_ALL / LC_MESSAGES / LANG by default)",
+            ),
+            flag(
+                "demo",
+                None,
+                "Use bundled demo data instead of scanning; nothing is killed (or set PORTLY_DEMO=1)",
+            ),
+            flag(
+                "check-update",
+                None,
+                "Check GitHub releases for a newer version (JSON with -j)",
+            ),
+            flag(
+                "rpc",
+                None,
+                "Serve JSON-RPC 2.0 on stdin/stdout, one request per line \
+                 (ports.list, ports.grouped, ports.filter, scan, discover, kill)",
+            ),
+            flag("man", None, "Print this man page in roff format"),
+            flag("help", Some('h'), "Show help"),
+        ])
+        .subcommand(
+            Command::new("scan")
+                .about("Scan TCP ports on a remote host")
+                .disable_help_flag(true)
+                .arg(
+                    Arg::new("target")
+                        .value_name("TARGET")
+                        .required(true)
+                        .help("Host name, IP address or hostname pattern such as node{01..20}.lan"),
+                )
+                .args([
+                    value(
+                        "ports",
+                        None,
+                        "SPEC",
+                        "Ports: top100 (default), all, 1-1024, 22,80,443 or a config profile name",
+                    ),
+                    value(
+                        "timeout",
+                        None,
+                        "MS",
+                        "Connect timeout in milliseconds (default 500)",
+                    ),
+                    value(
+                        "address",
+                        None,
+                        "ADDR",
+                        "Which resolved address to scan: one of its IPs, ipv4 or ipv6",
+                    ),
+                    value(
+                        "tag",
+                        None,
+                        "TAG",
+                        "Only scan hosts carrying this inventory tag",
+                    ),
+                    flag("all", Some('a'), "Include closed ports"),
+                ])
+                .args(output_args())
+                .arg(flag("help", Some('h'), "Show help")),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Show or edit the shared config file")
+                .disable_help_subcommand(true)
+                .subcommand(Command::new("show").about("Show the current config"))
+                .subcommand(Command::new("path").about("Print the config file path"))
+                .subcommand(
+                    Command::new("get")
+                        .about("Read a value by dotted key, e.g. timeouts.scan_ms")
+                        .arg(Arg::new("key").value_name("KEY").required(true)),
+                )
+                .subcommand(
+                    Command::new("set")
+                        .about("Set a value using TOML syntax")
+                        .arg(Arg::new("key").value_name("KEY").required(true))
+                        .arg(Arg::new("value").value_name("VALUE").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("free")
+                .about("Kill whatever holds a port and verify it is released")
+                .disable_help_flag(true)
+                .arg(
+                    Arg::new("port")
+                        .value_name("PORT")
+                        .required(true)
+                        .help("Port to free"),
+                )
+                .args([
+                    flag("yes", Some('y'), "Kill without asking"),
+                    flag("force", None, "Force kill (SIGKILL / taskkill /F)"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about(
+                    "Run as a REST API daemon for dashboards and remote GUIs; \
+                     monitor events are pushed over a WebSocket at /api/events",
+                )
+                .disable_help_flag(true)
+                .args([
+                    value(
+                        "listen",
+                        None,
+                        "ADDR",
+                        "Address to listen on (default 127.0.0.1:7070)",
+                    ),
+                    value(
+                        "token",
+                        None,
+                        "TOKEN",
+                        "Bearer token required by every endpoint except /api/health \
+                         (default PORTLY_TOKEN, otherwise generated and printed)",
+                    ),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("audit")
+                .about(
+                    "Audit exposed ports, the Docker API, weak services and the firewall; \
+                     exits 1 when there are high or critical findings",
+                )
+                .disable_help_flag(true)
+                .args([
+                    flag("json", Some('j'), "JSON output"),
+                    value(
+                        "export",
+                        None,
+                        "FILE",
+                        "Export the report; format from the extension (csv, json, ndjson, txt, md, xlsx)",
+                    ),
+                    flag("no-color", None, "Disable colors"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("exposure")
+                .about(
+                    "Check which exposed services LAN hosts can reach, \
+                     from the firewall's inbound rules",
+                )
+                .disable_help_flag(true)
+                .args([
+                    flag(
+                        "self-scan",
+                        None,
+                        "Connect to every exposed port from this host's LAN address",
+                    ),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("no-color", None, "Disable colors"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("ports")
+                .about("View or customize the common ports used by quick scans and service names")
+                .disable_help_subcommand(true)
+                .subcommand(
+                    Command::new("list")
+                        .about("List the merged port database")
+                        .arg(flag("json", Some('j'), "JSON output")),
+                )
+                .subcommand(Command::new("path").about("Print the custom ports file path"))
+                .subcommand(
+                    Command::new("add")
+                        .about("Add or rename a port")
+                        .arg(Arg::new("port").value_name("PORT").required(true))
+                        .arg(Arg::new("name").value_name("NAME").required(true))
+                        .arg(value("category", None, "CAT", "Group, e.g. database or iot")),
+                )
+                .subcommand(
+                    Command::new("remove")
+                        .about("Delete a custom entry or remove a built-in port")
+                        .arg(Arg::new("port").value_name("PORT").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("subnet")
+                .about(
+                    "Subnet calculator: network, broadcast, host range and count, \
+                     netmask in dotted, hex, binary and wildcard form",
+                )
+                .disable_help_flag(true)
+                .arg(
+                    Arg::new("cidr")
+                        .value_name("CIDR")
+                        .required(true)
+                        .help("Address with a prefix length or dotted netmask, e.g. 10.0.5.9/22"),
+                )
+                .args([
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("portcheck")
+                .about(
+                    "Ask a reflector outside the NAT to connect back to a public port, \
+                     verifying that a router port-forward works end to end",
+                )
+                .disable_help_flag(true)
+                .arg(
+                    Arg::new("port")
+                        .value_name("PORT")
+                        .required(true)
+                        .help("TCP port forwarded to this machine"),
+                )
+                .args([
+                    value(
+                        "reflector",
+                        None,
+                        "URL",
+                        "Reflector URL, e.g. a portly-cli serve daemon outside the NAT",
+                    ),
+                    value("token", None, "TOKEN", "Reflector access token"),
+                    value(
+                        "host",
+                        None,
+                        "HOST",
+                        "Address to connect back to (default: the caller's public address)",
+                    ),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("neighbors")
+                .about(
+                    "Listen for LLDP/CDP announcements and show the switch name, \
+                     port ID and VLAN of the local uplink",
+                )
+                .disable_help_flag(true)
+                .args([
+                    value(
+                        "interface",
+                        Some('i'),
+                        "IF",
+                        "Interface to listen on (default: first Ethernet interface that is up)",
+                    ),
+                    value(
+                        "timeout",
+                        Some('t'),
+                        "SECS",
+                        "Maximum listen time in seconds, 1-180 (default 35)",
+                    ),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("discover")
+                .about(
+                    "Discover LAN devices, optionally on a given interface or 802.1Q VLAN \
+                     for machines on trunk ports",
+                )
+                .disable_help_flag(true)
+                .arg(
+                    Arg::new("cidr")
+                        .value_name("CIDR")
+                        .help("Subnet to scan (default: the local subnet)"),
+                )
+                .args([
+                    value(
+                        "interface",
+                        Some('i'),
+                        "IF",
+                        "NIC or VLAN subinterface to scan from",
+                    ),
+                    value("vlan", None, "ID", "802.1Q VLAN ID (1-4094)"),
+                    value("tag", None, "TAG", "Only list devices carrying this inventory tag"),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("dhcp")
+                .about(
+                    "Broadcast a DHCPDISCOVER and list every DHCP server that answers; \
+                     more than one server points to a rogue DHCP server",
+                )
+                .disable_help_flag(true)
+                .args([
+                    value("interface", Some('i'), "IF", "Interface to use"),
+                    value(
+                        "timeout",
+                        Some('t'),
+                        "SECS",
+                        "How long to wait for replies in seconds, 1-180 (default 5)",
+                    ),
+                    flag("passive", None, "Only listen, send no DHCPDISCOVER"),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("ntp")
+                .about(
+                    "Query NTP servers and report the local clock offset; defaults to the \
+                     servers the system is configured with",
+                )
+                .disable_help_flag(true)
+                .arg(
+                    Arg::new("servers")
+                        .value_name("SERVER")
+                        .action(ArgAction::Append)
+                        .help("NTP servers to query"),
+                )
+                .args([
+                    value(
+                        "timeout",
+                        Some('t'),
+                        "MS",
+                        "Timeout per server in milliseconds (default 2000)",
+                    ),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("shares")
+                .about(
+                    "List the SMB shares and NFS exports visible on a host and flag the ones \
+                     open to everyone",
+                )
+                .disable_help_flag(true)
+                .arg(Arg::new("host").value_name("HOST").required(true))
+                .args([
+                    flag("smb", None, "Only list SMB shares"),
+                    flag("nfs", None, "Only list NFS exports"),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("printer")
+                .about("Show a printer's make and model, state and supply levels over IPP or PJL")
+                .disable_help_flag(true)
+                .arg(Arg::new("host").value_name("HOST").required(true))
+                .args([
+                    value(
+                        "port",
+                        Some('p'),
+                        "PORT",
+                        "631 for IPP (default) or 9100 for PJL",
+                    ),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("deps")
+                .about("Show which local services connect to each other, from established connections")
+                .disable_help_flag(true)
+                .args([
+                    flag("json", Some('j'), "JSON output (nodes and edges)"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("arp")
+                .about(
+                    "Show the ARP / neighbor cache with MAC vendors and flush stale entries",
+                )
+                .disable_help_subcommand(true)
+                .args([
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ])
+                .subcommand(
+                    Command::new("flush")
+                        .about("Flush the ARP cache, or delete a single entry")
+                        .arg(Arg::new("ip").value_name("IP").help("Only delete this entry"))
+                        .arg(flag("yes", Some('y'), "Do not ask for confirmation")),
+                ),
+        )
+        .subcommand(
+            Command::new("routes")
+                .about("Show the routing table and default gateway, or the route to a target")
+                .disable_help_flag(true)
+                .args([
+                    value("to", Some('t'), "TARGET", "Only show the route to this host or IP"),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("ifstats")
+                .about("Show interface link speed, MTU and counters, or sample live throughput")
+                .disable_help_flag(true)
+                .args([
+                    flag("watch", Some('w'), "Keep sampling and show live rates"),
+                    value("interval", None, "SECS", "Sampling interval, 1-60 seconds"),
+                    value("count", Some('n'), "COUNT", "Exit after this many samples"),
+                    flag("json", Some('j'), "JSON output"),
+                    flag("help", Some('h'), "Show help"),
+                ]),
+        )
+        .subcommand(
+            Command::new("tag")
+                .about("Tag remote hosts and LAN devices and keep notes on them")
+                .disable_help_subcommand(true)
+                .subcommand(
+                    Command::new("list")
+                        .about("List the inventory")
+                        .arg(flag("json", Some('j'), "JSON output")),
+                )
+                .subcommand(Command::new("path").about("Print the inventory file path"))
+                .subcommand(
+                    Command::new("set")
+                        .about("Replace a target's tags and note; with neither it is removed")
+                        .arg(
+                            Arg::new("target")
+                                .value_name("TARGET")
+                                .required(true)
+                                .help("IP address, host name or MAC address"),
+                        )
+                        .arg(Arg::new("tags").value_name("TAG").num_args(0..))
+                        .arg(value("note", None, "TEXT", "Free-form note")),
+                )
+                .subcommand(
+                    Command::new("remove")
+                        .about("Delete a target's tags and note")
+                        .arg(Arg::new("target").value_name("TARGET").required(true)),
+                ),
+        )
+        .subcommand(
+            Command::new("backup")
+                .about(
+                    "Export or restore settings, config, rules, scheduled jobs, custom ports, \
+                     export presets, tagged targets and scan history as one gzip-compressed file",
+                )
+                .disable_help_subcommand(true)
+                .subcommand(
+                    Command::new("export")
+                        .about("Write the current state to a file")
+                        .arg(Arg::new("file").value_name("FILE").required(true)),
+                )
+                .subcommand(
+                    Command::new("import")
+                        .about("Restore a backup; scan history is merged, everything else replaced")
+                        .arg(Arg::new("file").value_name("FILE").required(true))
+                        .arg(flag(
+                            "with-hooks",
+                            None,
+                            "Also install the backup's script hooks, listing their commands first",
+                        )),
+                ),
+        )
+        .after_help(
+            "Exit codes: 0 results found, 1 no match, 2 usage error, \
+             3 permission problem (process info hidden).\n\
+             Config file: ~/.config/portly/config.toml (override with PORTLY_CONFIG).",
+        )
+}
@@ -0,0 +1,3219 @@
+//! Portly CLI - 命令行端口扫描器
+
+// 引用核心库（不依赖 Tauri）
+use portly_core::{
+    advanced_scan, arp, audit, backup, config_path, daemon, demo, dependencies, dhcp, export_audit,
+    exposure, get_config_value, host_pattern, iface_stats, inventory, kill_process, known_ports,
+    lldp, load_config, network, ntp, parse_json_schema_version, parse_port_spec, port_forward,
+    printer, render_config, resolve_target, rpc, save_config, scan_ports, scan_ports_async,
+    scan_ports_grouped, set_config_value, set_lang, settings, shares, tr, tr_args,
+    try_get_connections, update, write_ndjson, write_ports_csv, write_records_csv, AppGroup,
+    ConnectionInfo, ExcludeOptions, ExportFormat, ExportRecord, JsonGroupsOutput, JsonPortsOutput,
+    JsonRemoteScanOutput, Lang, Msg, PortColumn, PortInfo, PortlyConfig, PortlyErrorKind,
+    RemotePort, RemotePortRecord, ResolveResult, JSON_SCHEMA_VERSION,
+};
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::io::{BufRead, IsTerminal, Write};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// 表格默认列（与原固定布局一致）
+const DEFAULT_TABLE_COLUMNS: [PortColumn; 6] = [
+    PortColumn::Port,
+    PortColumn::Protocol,
+    PortColumn::Address,
+    PortColumn::Pid,
+    PortColumn::Process,
+    PortColumn::User,
+];
+
+/// 列宽收缩的下限
+const MIN_COLUMN_WIDTH: usize = 6;
+
+/// 系统进程（-x 排除，表格中弱化显示）
+#[cfg(target_os = "macos")]
+const SYSTEM_PROCESSES: &[&str] = &["controlce", "rapportd", "netdisk_s", "mds", "launchd"];
+#[cfg(target_os = "linux")]
+const SYSTEM_PROCESSES: &[&str] = &["systemd", "sshd", "dbus", "networkmanager"];
+#[cfg(target_os = "windows")]
+const SYSTEM_PROCESSES: &[&str] = &["system", "svchost", "lsass", "services"];
+
+/// Docker / 容器运行时的端口转发进程
+const DOCKER_PROCESSES: &[&str] = &[
+    "containerd",
+    "vpnkit",
+    "vpnkit-bridge",
+    "rootlessport",
+    "gvproxy",
+    "podman",
+];
+
+/// ANSI 颜色
+const COLOR_BOLD: &str = "1";
+const COLOR_DIM: &str = "2";
+const COLOR_RED: &str = "31";
+const COLOR_GREEN: &str = "32";
+const COLOR_YELLOW: &str = "33";
+const COLOR_CYAN: &str = "36";
+
+/// 退出码：找到结果
+const EXIT_OK: i32 = 0;
+/// 退出码：过滤条件（-p / -f）没有匹配，或远程扫描没有开放端口
+const EXIT_NO_MATCH: i32 = 1;
+/// 退出码：参数错误
+const EXIT_USAGE: i32 = 2;
+/// 退出码：权限不足，部分进程信息不可见导致无法匹配
+const EXIT_PERMISSION: i32 = 3;
+
+/// build.rs 根据 src/cli_spec.rs 生成的 man page
+const MAN_PAGE: &str = include_str!(concat!(env!("OUT_DIR"), "/portly-cli.1"));
+
+/// 界面语言，由 --lang、配置文件或 LANG 决定
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+fn lang() -> Lang {
+    *LANG.get_or_init(Lang::detect)
+}
+
+fn t(msg: Msg) -> &'static str {
+    tr(lang(), msg)
+}
+
+fn t_args(msg: Msg, args: &[&dyn Display]) -> String {
+    tr_args(lang(), msg, args)
+}
+
+/// 输出格式
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// 未指定：stdout 是终端时为表格，否则为 porcelain
+    Auto,
+    Table,
+    /// 制表符分隔的精简字段，无标题和边框，便于管道处理
+    Porcelain,
+    /// 带 schema_version 的 JSON 文档，见 output_schema 模块
+    Json,
+    Csv,
+    Ndjson,
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Result<Self, String> {
+        if let Some(version) = parse_json_schema_version(name) {
+            // 目前只有 v1，版本号校验后即可使用同一结构
+            return version.map(|_| OutputFormat::Json);
+        }
+        match name.trim().to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "porcelain" => Ok(OutputFormat::Porcelain),
+            "csv" => Ok(OutputFormat::Csv),
+            "ndjson" | "jsonl" => Ok(OutputFormat::Ndjson),
+            other => Err(t_args(
+                Msg::UnknownOutputFormat,
+                &[&other, &JSON_SCHEMA_VERSION],
+            )),
+        }
+    }
+
+    /// 确定最终格式：未指定时根据 stdout 是否为终端选择表格或 porcelain
+    fn resolve(self) -> Self {
+        match self {
+            OutputFormat::Auto if std::io::stdout().is_terminal() => OutputFormat::Table,
+            OutputFormat::Auto => OutputFormat::Porcelain,
+            other => other,
+        }
+    }
+}
+
+/// 解析 `-o/--output <FORMAT>` 的值
+fn parse_output_arg(value: Option<&String>) -> Result<OutputFormat, String> {
+    match value {
+        Some(name) => OutputFormat::from_name(name),
+        None => Err(t(Msg::OutputNeedsValue).to_string()),
+    }
+}
+
+/// 终端配色；stdout 不是终端、设置了 NO_COLOR 或传入 --no-color 时不输出颜色
+#[derive(Clone, Copy)]
+struct Palette {
+    enabled: bool,
+}
+
+impl Palette {
+    fn detect(no_color: bool) -> Self {
+        let env_disabled = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self {
+            enabled: !no_color && !env_disabled && std::io::stdout().is_terminal(),
+        }
+    }
+
+    fn paint(&self, text: &str, color: Option<&str>) -> String {
+        match color {
+            Some(code) if self.enabled => format!("\x1b[{}m{}\x1b[0m", code, text),
+            _ => text.to_string(),
+        }
+    }
+}
+
+fn is_system_process(process: &str) -> bool {
+    SYSTEM_PROCESSES.contains(&process.to_lowercase().as_str())
+}
+
+fn is_docker_process(process: &str) -> bool {
+    let process = process.to_lowercase();
+    process.contains("docker") || DOCKER_PROCESSES.contains(&process.as_str())
+}
+
+/// 监听所有网卡的地址（对外暴露）
+fn is_wildcard_address(address: &str) -> bool {
+    matches!(address, "*" | "0.0.0.0" | "::" | "[::]")
+}
+
+/// 单元格颜色：端口为绿色，对外暴露的地址为黄色，Docker 进程为青色，系统进程整行弱化
+fn cell_color(port: &PortInfo, column: PortColumn) -> Option<&'static str> {
+    if is_system_process(&port.process) {
+        return Some(COLOR_DIM);
+    }
+    match column {
+        PortColumn::Port => Some(COLOR_GREEN),
+        PortColumn::Address if is_wildcard_address(&port.address) => Some(COLOR_YELLOW),
+        PortColumn::Process if is_docker_process(&port.process) => Some(COLOR_CYAN),
+        _ => None,
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = std::env::args().collect();
+
+    // --lang 对所有子命令生效，先取出再解析其他参数
+    if let Some(pos) = args.iter().position(|arg| arg == "--lang") {
+        let Some(lang) = args.get(pos + 1).and_then(|name| Lang::from_name(name)) else {
+            std::process::exit(usage_error(t(Msg::LangNeedsValue)));
+        };
+        let _ = LANG.set(lang);
+        args.drain(pos..pos + 2);
+    } else if let Some(name) = load_config().ok().and_then(|c| c.defaults.lang) {
+        match Lang::from_name(&name) {
+            Some(lang) => {
+                let _ = LANG.set(lang);
+            }
+            None => eprintln!("⚠️  {}", t_args(Msg::ConfigInvalidLang, &[&name])),
+        }
+    }
+
+    // 错误、终止进程和导出结果等后端消息也使用界面语言
+    set_lang(lang());
+
+    // --demo 同样对所有子命令生效：扫描返回内置示例数据，不终止任何进程
+    if let Some(pos) = args.iter().position(|arg| arg == "--demo") {
+        args.remove(pos);
+        demo::set_enabled(true);
+    }
+
+    std::process::exit(run(&args));
+}
+
+fn run(args: &[String]) -> i32 {
+    match args.get(1).map(String::as_str) {
+        Some("scan") => return run_scan(&args[2..]),
+        Some("config") => return run_config(&args[2..]),
+        Some("free") => return run_free(&args[2..]),
+        Some("serve") => return run_serve(&args[2..]),
+        Some("audit") => return run_audit(&args[2..]),
+        Some("exposure") => return run_exposure(&args[2..]),
+        Some("ports") => return run_ports(&args[2..]),
+        Some("backup") => return run_backup(&args[2..]),
+        Some("subnet") => return run_subnet(&args[2..]),
+        Some("portcheck") => return run_portcheck(&args[2..]),
+        Some("neighbors") => return run_neighbors(&args[2..]),
+        Some("discover") => return run_discover(&args[2..]),
+        Some("dhcp") => return run_dhcp(&args[2..]),
+        Some("arp") => return run_arp(&args[2..]),
+        Some("routes") => return run_routes(&args[2..]),
+        Some("ifstats") => return run_ifstats(&args[2..]),
+        Some("ntp") => return run_ntp(&args[2..]),
+        Some("shares") => return run_shares(&args[2..]),
+        Some("printer") => return run_printer(&args[2..]),
+        Some("deps") => return run_deps(&args[2..]),
+        Some("tag") => return run_tag(&args[2..]),
+        _ => {}
+    }
+
+    // 配置文件中的默认参数，命令行选项在此基础上叠加
+    let config = load_cli_config();
+    let mut output = default_output(&config);
+    let mut grouped = config.defaults.group;
+    let mut show_command = config.defaults.show_command;
+    let mut app_filter: Option<String> = None;
+    let mut port_filter: Option<u16> = None;
+    let mut exclude_system = config.defaults.exclude_system;
+    let mut include_udp = config.defaults.udp;
+    let mut dual_stack = config.defaults.dual_stack;
+    let mut connections = false;
+    let mut columns: Vec<PortColumn> = DEFAULT_TABLE_COLUMNS.to_vec();
+    let mut no_color = false;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => output = OutputFormat::Json,
+            "--csv" => output = OutputFormat::Csv,
+            "--ndjson" => output = OutputFormat::Ndjson,
+            "-q" | "--porcelain" => output = OutputFormat::Porcelain,
+            "-o" | "--output" => {
+                output = match parse_output_arg(args.get(i + 1)) {
+                    Ok(output) => output,
+                    Err(e) => return usage_error(&e),
+                };
+                i += 1;
+            }
+            "-g" | "--group" => grouped = true,
+            "-c" | "--command" => show_command = true,
+            "-x" | "--exclude-system" => exclude_system = true,
+            "-u" | "--udp" => include_udp = true,
+            "--no-udp" => include_udp = false,
+            "--dual-stack" => dual_stack = true,
+            "--connections" => connections = true,
+            "--no-color" => no_color = true,
+            "-f" | "--filter" => {
+                let Some(value) = args.get(i + 1) else {
+                    return usage_error(t(Msg::FilterNeedsValue));
+                };
+                app_filter = Some(value.clone());
+                i += 1;
+            }
+            "-p" | "--port" => {
+                match args.get(i + 1).map(|value| value.parse::<u16>()) {
+                    Some(Ok(port)) if port > 0 => port_filter = Some(port),
+                    _ => return usage_error(t(Msg::PortNeedsValue)),
+                }
+                i += 1;
+            }
+            "--columns" => {
+                let Some(value) = args.get(i + 1) else {
+                    return usage_error(t(Msg::ColumnsNeedsValue));
+                };
+                columns = match parse_columns(value) {
+                    Ok(columns) => columns,
+                    Err(e) => return usage_error(&e),
+                };
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_help();
+                return EXIT_OK;
+            }
+            "--man" => {
+                write_stdout(|out| out.write_all(MAN_PAGE.as_bytes()));
+                return EXIT_OK;
+            }
+            "--check-update" => return run_check_update(output),
+            "--rpc" => return run_rpc(),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    let filtering = port_filter.is_some() || app_filter.is_some();
+    let palette = Palette::detect(no_color);
+    let output = output.resolve();
+
+    if connections {
+        return run_connections(output, port_filter, &app_filter, exclude_system, palette);
+    }
+    if grouped {
+        let groups = scan_ports_grouped();
+        let owner_hidden = groups.iter().any(|g| g.pid == "-");
+        let mut filtered = apply_filter_groups(groups, &app_filter, exclude_system);
+        apply_exclusions_groups(&mut filtered, &config.exclude);
+
+        match output {
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&JsonGroupsOutput::new(&filtered)).unwrap()
+            ),
+            OutputFormat::Csv => write_stdout(|out| write_records_csv(out, &filtered)),
+            OutputFormat::Ndjson => {
+                write_stdout(|out| write_ndjson(out, &filtered, &mut |_, _| {}))
+            }
+            OutputFormat::Table | OutputFormat::Auto => print_groups(&filtered, palette),
+            OutputFormat::Porcelain => write_stdout(|out| write_porcelain_groups(out, &filtered)),
+        }
+        match_exit_code(filtered.is_empty(), filtering, owner_hidden)
+    } else {
+        let mut result = scan_ports(
+            show_command || columns.contains(&PortColumn::Command),
+            include_udp,
+        );
+        if dual_stack {
+            result = result.merge_dual_stack();
+        }
+        let owner_hidden = result.ports.iter().any(|p| p.pid == "-");
+        let mut filtered =
+            apply_filter_ports(result.ports, port_filter, &app_filter, exclude_system);
+        filtered.retain(|p| !config.exclude.is_excluded(&p.process, p.port));
+
+        match output {
+            OutputFormat::Json => {
+                let output = JsonPortsOutput::new(&result.scan_time, &filtered);
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            }
+            OutputFormat::Csv => {
+                write_stdout(|out| write_ports_csv(out, &filtered, &mut |_, _| {}))
+            }
+            OutputFormat::Ndjson => {
+                write_stdout(|out| write_ndjson(out, &filtered, &mut |_, _| {}))
+            }
+            OutputFormat::Table | OutputFormat::Auto => print_table(
+                &filtered,
+                &columns,
+                show_command,
+                &result.scan_time,
+                palette,
+            ),
+            OutputFormat::Porcelain => {
+                write_stdout(|out| write_porcelain_ports(out, &filtered, &columns))
+            }
+        }
+        match_exit_code(filtered.is_empty(), filtering, owner_hidden)
+    }
+}
+
+/// `portly-cli --connections`: 本机 TCP 连接；`-p` 匹配本地或对端端口
+fn run_connections(
+    output: OutputFormat,
+    port_filter: Option<u16>,
+    app_filter: &Option<String>,
+    exclude_system: bool,
+    palette: Palette,
+) -> i32 {
+    let mut connections = match try_get_connections() {
+        Ok(connections) => connections,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return EXIT_NO_MATCH;
+        }
+    };
+    let owner_hidden = connections.iter().any(|c| c.pid == "-");
+    if let Some(pf) = port_filter {
+        connections.retain(|c| c.local_port == pf || c.remote_port == pf);
+    }
+    if let Some(ref af) = app_filter {
+        let af_lower = af.to_lowercase();
+        connections.retain(|c| c.process.to_lowercase().contains(&af_lower));
+    }
+    if exclude_system {
+        connections.retain(|c| !is_system_process(&c.process));
+    }
+
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&connections).unwrap()),
+        OutputFormat::Csv => write_stdout(|out| write_records_csv(out, &connections)),
+        OutputFormat::Ndjson => write_stdout(|out| write_ndjson(out, &connections, &mut |_, _| {})),
+        OutputFormat::Table | OutputFormat::Auto => print_connections(&connections, palette),
+        OutputFormat::Porcelain => write_stdout(|out| {
+            for c in &connections {
+                let fields: Vec<String> = c.row().iter().map(|f| porcelain_field(f)).collect();
+                writeln!(out, "{}", fields.join("\t"))?;
+            }
+            Ok(())
+        }),
+    }
+    let filtering = port_filter.is_some() || app_filter.is_some();
+    match_exit_code(connections.is_empty(), filtering, owner_hidden)
+}
+
+fn print_connections(connections: &[ConnectionInfo], palette: Palette) {
+    let titles = [
+        t(Msg::ColumnProtocol),
+        t(Msg::ColumnLocal),
+        t(Msg::ColumnRemote),
+        t(Msg::ScanColumnState),
+        t(Msg::ColumnPid),
+        t(Msg::ColumnProcess),
+    ];
+    let rows: Vec<Vec<String>> = connections.iter().map(ExportRecord::row).collect();
+    let widths: Vec<usize> = (0..titles.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].width())
+                .chain([titles[i].width()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let format_row = |cells: &[&str], color: &dyn Fn(usize) -> Option<&'static str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| palette.paint(&pad_to_width(cell, *width, i == 4), color(i)))
+            .collect();
+        format!("  {}", cells.join("  ").trim_end())
+    };
+    let established = connections
+        .iter()
+        .filter(|c| c.state == "ESTABLISHED")
+        .count();
+    let rule_width = terminal_width().unwrap_or(79).min(79);
+
+    println!();
+    println!("{}", "═".repeat(rule_width));
+    println!("  🔗 Portly - {}", t(Msg::ConnectionsTitle));
+    println!("{}", "═".repeat(rule_width));
+    println!();
+    println!(
+        "  📊 {}",
+        t_args(Msg::ConnectionsSummary, &[&connections.len(), &established])
+    );
+    println!();
+    println!("{}", format_row(&titles, &|_| Some(COLOR_BOLD)));
+    println!(
+        "  {}",
+        "─".repeat(widths.iter().sum::<usize>() + 2 * (widths.len() - 1))
+    );
+    for (c, row) in connections.iter().zip(&rows) {
+        let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+        let state_color = match c.state.as_str() {
+            "ESTABLISHED" => Some(COLOR_GREEN),
+            "SYN_SENT" | "SYN_RECV" | "SYN_RECEIVED" => Some(COLOR_YELLOW),
+            _ => Some(COLOR_DIM),
+        };
+        println!(
+            "{}",
+            format_row(&cells, &|i| if i == 3 { state_color } else { None })
+        );
+    }
+    println!();
+    println!("{}", "═".repeat(rule_width));
+}
+
+/// `portly-cli free <port>`: 终止占用端口的进程并确认端口已释放
+fn run_free(args: &[String]) -> i32 {
+    let mut port: Option<u16> = None;
+    let mut assume_yes = false;
+    let mut force = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "-y" | "--yes" => assume_yes = true,
+            "--force" => force = true,
+            "-h" | "--help" => {
+                print_free_help();
+                return EXIT_OK;
+            }
+            value if !value.starts_with('-') && port.is_none() => match value.parse::<u16>() {
+                Ok(p) if p > 0 => port = Some(p),
+                _ => return usage_error(t(Msg::FreePortInvalid)),
+            },
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+    }
+
+    let Some(port) = port else {
+        print_free_help();
+        return EXIT_USAGE;
+    };
+
+    let holders: Vec<PortInfo> = scan_ports(true, false)
+        .ports
+        .into_iter()
+        .filter(|p| p.port == port)
+        .collect();
+    if holders.is_empty() {
+        println!("✅ {}", t_args(Msg::FreeAlreadyFree, &[&port]));
+        return EXIT_OK;
+    }
+
+    let mut pids: Vec<u32> = holders.iter().filter_map(|p| p.pid.parse().ok()).collect();
+    pids.sort_unstable();
+    pids.dedup();
+    if pids.is_empty() {
+        eprintln!("⚠️  {}", t_args(Msg::FreeOwnerHidden, &[&port]));
+        return EXIT_PERMISSION;
+    }
+
+    println!("{}", t_args(Msg::FreeHeldBy, &[&port]));
+    let mut seen = HashSet::new();
+    for holder in holders.iter().filter(|h| seen.insert(h.pid.clone())) {
+        println!(
+            "  {} (PID: {}) {} {}",
+            holder.process,
+            holder.pid,
+            holder.user,
+            holder.command.as_deref().unwrap_or("")
+        );
+    }
+
+    if !assume_yes && !confirm(&t_args(Msg::FreeConfirm, &[&pids.len()])) {
+        println!("{}", t(Msg::FreeCancelled));
+        return EXIT_NO_MATCH;
+    }
+
+    for pid in &pids {
+        let result = kill_process(*pid, force);
+        if result.success {
+            println!("✅ {}", result.message);
+        } else {
+            eprintln!("❌ {}", result.message);
+        }
+    }
+
+    // 进程退出和端口释放可能有延迟，轮询确认
+    if wait_port_free(port, Duration::from_secs(3)) {
+        println!("✅ {}", t_args(Msg::FreeReleased, &[&port]));
+        EXIT_OK
+    } else {
+        eprintln!("❌ {}", t_args(Msg::FreeStillInUse, &[&port]));
+        if !force {
+            eprintln!("   {}", t(Msg::FreeForceHint));
+        }
+        EXIT_NO_MATCH
+    }
+}
+
+/// `portly-cli serve`: 以 REST API 守护进程运行，直到进程被终止
+fn run_serve(args: &[String]) -> i32 {
+    let mut listen = daemon::DEFAULT_LISTEN.to_string();
+    let mut token = std::env::var("PORTLY_TOKEN")
+        .ok()
+        .filter(|token| !token.trim().is_empty());
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--listen" => {
+                let Some(value) = args.get(i + 1) else {
+                    return usage_error(t(Msg::ServeListenInvalid));
+                };
+                listen = value.clone();
+                i += 1;
+            }
+            "--token" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => token = Some(value.clone()),
+                    _ => return usage_error(t(Msg::ServeTokenNeedsValue)),
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_serve_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    let Ok(listen) = listen.parse::<SocketAddr>() else {
+        return usage_error(t(Msg::ServeListenInvalid));
+    };
+    let token = token.unwrap_or_else(|| {
+        let token = daemon::generate_token();
+        println!("🔑 {}", t_args(Msg::ServeTokenGenerated, &[&token]));
+        token
+    });
+    if !listen.ip().is_loopback() {
+        eprintln!("⚠️  {}", t(Msg::ServeExposed));
+    }
+
+    println!("🌐 {}", t_args(Msg::ServeListening, &[&listen]));
+    let config = daemon::DaemonConfig { listen, token };
+    match daemon::serve(&config, &AtomicBool::new(false)) {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::ServeFailed, &[&e]));
+            EXIT_NO_MATCH
+        }
+    }
+}
+
+/// `portly-cli audit`: 安全审计，存在 high / critical 发现时返回 1
+fn run_audit(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut no_color = false;
+    let mut export_path: Option<String> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "--no-color" => no_color = true,
+            "--export" => {
+                let Some(value) = args.get(i + 1) else {
+                    return usage_error(t(Msg::AuditExportNeedsValue));
+                };
+                export_path = Some(value.clone());
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_audit_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    // 先确定导出格式，避免审计完成后才报参数错误
+    let export = match export_path {
+        Some(path) => {
+            let format = std::path::Path::new(&path)
+                .extension()
+                .and_then(|ext| ExportFormat::from_name(&ext.to_string_lossy()));
+            match format {
+                Some(format) => Some((path, format)),
+                None => return usage_error(&t_args(Msg::AuditExportUnknownFormat, &[&path])),
+            }
+        }
+        None => None,
+    };
+
+    let report = match audit::run_audit() {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::AuditFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_audit_report(&report, Palette::detect(no_color));
+    }
+    if let Some((path, format)) = export {
+        let result = export_audit(&report, &format, &path);
+        if result.success {
+            eprintln!("✅ {}", result.message);
+        } else {
+            eprintln!("❌ {}", result.message);
+            return EXIT_NO_MATCH;
+        }
+    }
+
+    if report.counts.critical + report.counts.high > 0 {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+fn print_audit_report(report: &audit::AuditReport, palette: Palette) {
+    let firewall_backend = if report.firewall.backend.is_empty() {
+        "-"
+    } else {
+        report.firewall.backend.as_str()
+    };
+    println!(
+        "\n🛡️  {}",
+        palette.paint(
+            &t_args(Msg::AuditSummary, &[&report.score, &report.findings.len()]),
+            Some(COLOR_BOLD)
+        )
+    );
+    println!(
+        "   {}\n",
+        t_args(
+            Msg::AuditFirewall,
+            &[&report.firewall.state.name(), &firewall_backend]
+        )
+    );
+    if report.findings.is_empty() {
+        println!("✅ {}", t(Msg::AuditNoFindings));
+        return;
+    }
+
+    for finding in &report.findings {
+        let color = match finding.severity {
+            audit::Severity::Critical | audit::Severity::High => COLOR_RED,
+            audit::Severity::Medium => COLOR_YELLOW,
+            audit::Severity::Low => COLOR_CYAN,
+            audit::Severity::Info => COLOR_DIM,
+        };
+        let label = format!("[{}]", finding.severity.name().to_uppercase());
+        println!(
+            "{} {}",
+            palette.paint(&format!("{:<10}", label), Some(color)),
+            finding.title
+        );
+        println!("           {}", finding.detail);
+        println!(
+            "           {}",
+            palette.paint(&format!("→ {}", finding.remediation), Some(COLOR_GREEN))
+        );
+    }
+}
+
+fn run_exposure(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut no_color = false;
+    let mut self_scan = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "-j" | "--json" => json = true,
+            "--no-color" => no_color = true,
+            "--self-scan" => self_scan = true,
+            "-h" | "--help" => {
+                print_exposure_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+    }
+
+    let report = match exposure::run_exposure_report(self_scan) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::ExposureFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print_exposure_report(&report, Palette::detect(no_color));
+    }
+    EXIT_OK
+}
+
+fn print_exposure_report(report: &exposure::ExposureReport, palette: Palette) {
+    let firewall_backend = if report.firewall.backend.is_empty() {
+        "-"
+    } else {
+        report.firewall.backend.as_str()
+    };
+    println!(
+        "\n🌐 {}",
+        palette.paint(
+            &t_args(
+                Msg::ExposureSummary,
+                &[&report.services.len(), &report.reachable]
+            ),
+            Some(COLOR_BOLD)
+        )
+    );
+    println!(
+        "   {}",
+        t_args(
+            Msg::AuditFirewall,
+            &[&report.firewall.state.name(), &firewall_backend]
+        )
+    );
+    if !report.rules.known {
+        println!(
+            "   {}",
+            palette.paint(
+                &t_args(Msg::ExposureRulesUnknown, &[&report.rules.detail]),
+                Some(COLOR_YELLOW)
+            )
+        );
+    }
+    if let Some(address) = &report.lan_address {
+        println!("   {}", t_args(Msg::ExposureSelfScan, &[address]));
+    }
+    println!();
+    if report.services.is_empty() {
+        println!("✅ {}", t(Msg::ExposureNone));
+        return;
+    }
+
+    for service in &report.services {
+        let color = match service.reachability {
+            exposure::Reachability::Reachable => COLOR_RED,
+            exposure::Reachability::Blocked => COLOR_GREEN,
+            exposure::Reachability::NotListening => COLOR_DIM,
+            exposure::Reachability::Unknown => COLOR_YELLOW,
+        };
+        let label = format!("[{}]", service.reachability.name().to_uppercase());
+        println!(
+            "{} {:>5}  {} (PID {})  {}",
+            palette.paint(&format!("{:<16}", label), Some(color)),
+            service.port,
+            service.process,
+            service.pid,
+            service.addresses.join(", ")
+        );
+    }
+}
+
+/// 询问确认；stdin 不是终端时视为拒绝，需要使用 --yes
+fn confirm(question: &str) -> bool {
+    if !std::io::stdin().is_terminal() {
+        eprintln!("⚠️  {}", t(Msg::ConfirmNeedsYes));
+        return false;
+    }
+    print!("{} [y/N] ", question);
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    if std::io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn wait_port_free(port: u16, timeout: Duration) -> bool {
+    let started = Instant::now();
+    loop {
+        if !scan_ports(false, false)
+            .ports
+            .iter()
+            .any(|p| p.port == port)
+        {
+            return true;
+        }
+        if started.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+/// 读取配置文件；配置无效时提示并使用默认值
+fn load_cli_config() -> PortlyConfig {
+    load_config().unwrap_or_else(|e| {
+        eprintln!("⚠️  {} ({})", e, config_path().display());
+        PortlyConfig::default()
+    })
+}
+
+fn default_output(config: &PortlyConfig) -> OutputFormat {
+    let Some(name) = config.defaults.output.as_deref() else {
+        return OutputFormat::Auto;
+    };
+    OutputFormat::from_name(name).unwrap_or_else(|e| {
+        eprintln!("⚠️  {}", t_args(Msg::ConfigInvalidOutput, &[&e]));
+        OutputFormat::Auto
+    })
+}
+
+/// 按配置的排除列表过滤分组：排除进程，并移除被排除的端口
+fn apply_exclusions_groups(groups: &mut Vec<AppGroup>, exclude: &ExcludeOptions) {
+    for group in groups.iter_mut() {
+        group
+            .ports
+            .retain(|port| !exclude.is_excluded(&group.process, *port));
+    }
+    groups.retain(|g| !g.ports.is_empty());
+}
+
+/// `portly-cli ports`: 查看和维护常用端口库（快速扫描端口和服务名称）
+fn run_ports(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let parse_port = |value: &str| match value.parse::<u16>() {
+        Ok(port) if port > 0 => Some(port),
+        _ => None,
+    };
+    let result = match args.as_slice() {
+        [] | ["list"] => {
+            print_known_ports(&known_ports::current(), false);
+            return EXIT_OK;
+        }
+        ["-j" | "--json"] | ["list", "-j" | "--json"] => {
+            print_known_ports(&known_ports::current(), true);
+            return EXIT_OK;
+        }
+        ["path"] => {
+            println!("{}", known_ports::user_ports_path().display());
+            return EXIT_OK;
+        }
+        ["add", port, name] | ["add", port, name, "--category", _] => {
+            let Some(port) = parse_port(port) else {
+                return usage_error(t(Msg::PortsPortInvalid));
+            };
+            let entry = known_ports::PortEntry {
+                port,
+                name: name.to_string(),
+                category: args.get(4).map(|category| category.to_string()),
+                disabled: false,
+            };
+            known_ports::upsert_port(entry).map(|_| t_args(Msg::PortsSaved, &[&port, name]))
+        }
+        ["remove", port] => {
+            let Some(port) = parse_port(port) else {
+                return usage_error(t(Msg::PortsPortInvalid));
+            };
+            known_ports::delete_port(port).map(|_| t_args(Msg::PortsRemoved, &[&port]))
+        }
+        ["-h"] | ["--help"] => {
+            print_ports_help();
+            return EXIT_OK;
+        }
+        _ => {
+            print_ports_help();
+            return EXIT_USAGE;
+        }
+    };
+    match result {
+        Ok(message) => {
+            println!("✅ {}", message);
+            EXIT_OK
+        }
+        Err(e) => usage_error(&e.to_string()),
+    }
+}
+
+/// `portly-cli tag`: 管理远程主机和局域网设备的标签与备注
+fn run_tag(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        [] | ["list"] => {
+            print_inventory(&inventory::current(), false);
+            return EXIT_OK;
+        }
+        ["-j" | "--json"] | ["list", "-j" | "--json"] => {
+            print_inventory(&inventory::current(), true);
+            return EXIT_OK;
+        }
+        ["path"] => {
+            println!("{}", inventory::inventory_path().display());
+            return EXIT_OK;
+        }
+        ["set", target, rest @ ..] => {
+            let mut tags = Vec::new();
+            let mut note = None;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                match *arg {
+                    "--note" => match rest.next() {
+                        Some(value) => note = Some(*value),
+                        None => return usage_error(t(Msg::TagNoteNeedsValue)),
+                    },
+                    other if other.starts_with('-') => {
+                        return usage_error(&t_args(Msg::UnknownArgument, &[&other]))
+                    }
+                    tag => tags.push(tag.to_string()),
+                }
+            }
+            inventory::set_target(target, &tags, note).map(|_| {
+                let saved = inventory::lookup(&[*target]);
+                match (saved.tags.is_empty(), saved.note.is_none()) {
+                    (true, true) => t_args(Msg::TagRemoved, &[target]),
+                    (true, false) => t_args(Msg::TagSaved, &[target, &"-"]),
+                    (false, _) => t_args(Msg::TagSaved, &[target, &saved.tags.join(", ")]),
+                }
+            })
+        }
+        ["remove", target] => {
+            inventory::remove_target(target).map(|_| t_args(Msg::TagRemoved, &[target]))
+        }
+        ["-h"] | ["--help"] => {
+            print_tag_help();
+            return EXIT_OK;
+        }
+        _ => {
+            print_tag_help();
+            return EXIT_USAGE;
+        }
+    };
+    match result {
+        Ok(message) => {
+            println!("✅ {}", message);
+            EXIT_OK
+        }
+        Err(e) => usage_error(&e.to_string()),
+    }
+}
+
+fn print_inventory(entries: &[inventory::InventoryEntry], json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(entries).unwrap());
+        return;
+    }
+    if entries.is_empty() {
+        println!("{}", t(Msg::TagListEmpty));
+        return;
+    }
+    for entry in entries {
+        let tags = if entry.tags.is_empty() {
+            "-".to_string()
+        } else {
+            entry.tags.join(", ")
+        };
+        let line = format!(
+            "{:<24} {:<30} {}",
+            entry.target,
+            tags,
+            entry.note.as_deref().unwrap_or("")
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+fn print_known_ports(ports: &[known_ports::KnownPort], json: bool) {
+    if json {
+        println!("{}", serde_json::to_string_pretty(ports).unwrap());
+        return;
+    }
+    for port in ports {
+        let source = match port.source {
+            known_ports::PortSource::User => t(Msg::PortsUserSource),
+            known_ports::PortSource::Builtin => "",
+        };
+        let line = format!(
+            "{:>5}  {:<20} {:<10} {}",
+            port.port,
+            port.name,
+            port.category.as_deref().unwrap_or("-"),
+            source
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+/// `portly-cli subnet`: 子网计算；掩码也可以作为第二个参数
+fn run_subnet(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut parts = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "-j" | "--json" => json = true,
+            "-h" | "--help" => {
+                print_subnet_help();
+                return EXIT_OK;
+            }
+            other => parts.push(other),
+        }
+    }
+    if parts.is_empty() || parts.len() > 2 {
+        print_subnet_help();
+        return EXIT_USAGE;
+    }
+
+    let info = match network::subnet_info(&parts.join(" ")) {
+        Ok(info) => info,
+        Err(e) => return usage_error(&e.to_string()),
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info).unwrap());
+    } else {
+        println!(
+            "{}",
+            t_args(
+                Msg::SubnetSummary,
+                &[
+                    &info.cidr,
+                    &info.address,
+                    &info.netmask,
+                    &info.prefix_len,
+                    &info.netmask_hex,
+                    &info.wildcard,
+                    &info.netmask_binary,
+                    &info.broadcast,
+                    &info.first_host,
+                    &info.last_host,
+                    &info.host_count,
+                    &info.total_addresses,
+                ]
+            )
+        );
+    }
+    EXIT_OK
+}
+
+/// `portly-cli portcheck <PORT>`: 请 NAT 外的反射服务回连公网端口，检测路由器端口转发；
+/// 可以从公网连通时返回 0
+fn run_portcheck(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut port: Option<u16> = None;
+    let mut host: Option<String> = None;
+    // 默认使用桌面端设置中的反射服务
+    let mut reflector = settings::load_settings()
+        .map(|saved| saved.reflector)
+        .unwrap_or_else(|e| {
+            eprintln!("⚠️  {} ({})", e, settings::settings_path().display());
+            settings::ReflectorSettings::default()
+        });
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "--reflector" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => reflector.url = Some(value.clone()),
+                    _ => return usage_error(t(Msg::PortcheckReflectorNeedsValue)),
+                }
+                i += 1;
+            }
+            "--token" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => {
+                        reflector.token = Some(value.clone())
+                    }
+                    _ => return usage_error(t(Msg::ServeTokenNeedsValue)),
+                }
+                i += 1;
+            }
+            "--host" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => host = Some(value.clone()),
+                    _ => return usage_error(t(Msg::PortcheckHostNeedsValue)),
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_portcheck_help();
+                return EXIT_OK;
+            }
+            arg if !arg.starts_with('-') && port.is_none() => match arg.parse::<u16>() {
+                Ok(value) if value > 0 => port = Some(value),
+                _ => return usage_error(t(Msg::PortcheckPortInvalid)),
+            },
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+    let Some(port) = port else {
+        print_portcheck_help();
+        return EXIT_USAGE;
+    };
+
+    let check = match port_forward::check_port_forward(port, host.as_deref(), &reflector) {
+        Ok(check) => check,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::PortcheckFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&check).unwrap());
+    } else {
+        print_portcheck(&check);
+    }
+    if check.reachable {
+        EXIT_OK
+    } else {
+        EXIT_NO_MATCH
+    }
+}
+
+fn print_portcheck(check: &port_forward::PortForwardCheck) {
+    if check.reachable {
+        let rtt = format!("{:.1}", check.rtt_ms.unwrap_or_default());
+        println!(
+            "✅ {}",
+            t_args(
+                Msg::PortcheckReachable,
+                &[&check.port, &check.reflector, &check.public_host, &rtt]
+            )
+        );
+        return;
+    }
+    let reason = check.error.as_deref().unwrap_or("-");
+    println!(
+        "❌ {}",
+        t_args(
+            Msg::PortcheckUnreachable,
+            &[&check.port, &check.reflector, &check.public_host, &reason]
+        )
+    );
+    let process = check.listener.as_deref().unwrap_or("-");
+    let addresses = check.listener_addresses.join(", ");
+    let hint = match check.verdict {
+        port_forward::PortForwardVerdict::NoListener => {
+            t_args(Msg::PortcheckNoListener, &[&check.port])
+        }
+        port_forward::PortForwardVerdict::LoopbackOnly => {
+            t_args(Msg::PortcheckLoopbackOnly, &[&process, &addresses])
+        }
+        port_forward::PortForwardVerdict::Blocked | port_forward::PortForwardVerdict::Reachable => {
+            t_args(Msg::PortcheckBlocked, &[&process, &addresses])
+        }
+    };
+    println!("   {}", hint);
+}
+
+/// `portly-cli neighbors`: 监听 LLDP/CDP 通告，显示本机所接交换机的名称、端口和 VLAN
+fn run_neighbors(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut interface: Option<String> = None;
+    let mut timeout = lldp::DEFAULT_LISTEN_SECS;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "-i" | "--interface" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => interface = Some(value.clone()),
+                    _ => return usage_error(t(Msg::NeighborsInterfaceNeedsValue)),
+                }
+                i += 1;
+            }
+            "-t" | "--timeout" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u64>().ok()) {
+                    Some(value) if (1..=lldp::MAX_LISTEN_SECS).contains(&value) => timeout = value,
+                    _ => {
+                        return usage_error(&t_args(
+                            Msg::NeighborsTimeoutInvalid,
+                            &[&lldp::MAX_LISTEN_SECS],
+                        ))
+                    }
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_neighbors_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    if !json {
+        eprintln!("📡 {}", t_args(Msg::NeighborsListening, &[&timeout]));
+    }
+    let report = match lldp::discover_neighbors(interface.as_deref(), timeout) {
+        Ok(report) => report,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::NeighborsFailed, &[&e]));
+            return match e.kind() {
+                PortlyErrorKind::PermissionDenied => EXIT_PERMISSION,
+                _ => EXIT_NO_MATCH,
+            };
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else if report.neighbors.is_empty() {
+        println!(
+            "{}",
+            t_args(
+                Msg::NeighborsNone,
+                &[&report.interface, &report.listened_secs]
+            )
+        );
+    } else {
+        for neighbor in &report.neighbors {
+            print_neighbor(neighbor);
+        }
+    }
+    if report.neighbors.is_empty() {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+/// `portly-cli discover <CIDR>`: 发现局域网设备，可指定接口或 802.1Q VLAN
+fn run_discover(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut subnet: Option<&str> = None;
+    let mut options = advanced_scan::ArpScanOptions::default();
+    let mut tag: Option<&str> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "-i" | "--interface" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => {
+                        options.interface = Some(value.clone())
+                    }
+                    _ => return usage_error(t(Msg::NeighborsInterfaceNeedsValue)),
+                }
+                i += 1;
+            }
+            "--vlan" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u16>().ok()) {
+                    Some(value) if (1..=4094).contains(&value) => options.vlan = Some(value),
+                    _ => return usage_error(t(Msg::DiscoverVlanInvalid)),
+                }
+                i += 1;
+            }
+            "--tag" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => tag = Some(value),
+                    _ => return usage_error(t(Msg::TagNeedsValue)),
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_discover_help();
+                return EXIT_OK;
+            }
+            arg if !arg.starts_with('-') && subnet.is_none() => subnet = Some(arg),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+    let Some(subnet) = subnet
+        .map(String::from)
+        .or_else(network::get_current_subnet)
+    else {
+        print_discover_help();
+        return EXIT_USAGE;
+    };
+
+    let mut result = match advanced_scan::smart_scan_with(&subnet, &options) {
+        Ok(result) => result,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::DiscoverFailed, &[&e]));
+            return match e.kind() {
+                PortlyErrorKind::PermissionDenied => EXIT_PERMISSION,
+                _ => EXIT_NO_MATCH,
+            };
+        }
+    };
+    if let Some(tag) = tag {
+        result
+            .devices
+            .retain(|device| inventory::has_tag(&device.tags, tag));
+    }
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result).unwrap());
+    } else {
+        println!(
+            "🔍 {}",
+            t_args(
+                Msg::DiscoverSummary,
+                &[
+                    &subnet,
+                    &result.devices.len(),
+                    &result.scan_method,
+                    &result.interface.as_deref().unwrap_or("-"),
+                    &result.scan_time_ms,
+                ]
+            )
+        );
+        for device in &result.devices {
+            let mut line = format!(
+                "{}\t{}\t{}",
+                device.ip,
+                device.mac.as_deref().unwrap_or("-"),
+                device.hostname.as_deref().unwrap_or("")
+            );
+            if !device.tags.is_empty() {
+                line.push('\t');
+                line.push_str(&device.tags.join(","));
+            }
+            println!("{}", line);
+        }
+    }
+    if result.devices.is_empty() {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+/// `portly-cli dhcp`: 列出局域网内的 DHCP 服务器，发现多台时提示私设 DHCP
+fn run_dhcp(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut passive = false;
+    let mut interface: Option<String> = None;
+    let mut timeout = dhcp::DEFAULT_LISTEN_SECS;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "--passive" => passive = true,
+            "-i" | "--interface" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => interface = Some(value.clone()),
+                    _ => return usage_error(t(Msg::NeighborsInterfaceNeedsValue)),
+                }
+                i += 1;
+            }
+            "-t" | "--timeout" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u64>().ok()) {
+                    Some(value) if (1..=dhcp::MAX_LISTEN_SECS).contains(&value) => timeout = value,
+                    _ => {
+                        return usage_error(&t_args(
+                            Msg::NeighborsTimeoutInvalid,
+                            &[&dhcp::MAX_LISTEN_SECS],
+                        ))
+                    }
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_dhcp_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    if !json {
+        let msg = if passive {
+            Msg::DhcpPassiveListening
+        } else {
+            Msg::DhcpListening
+        };
+        eprintln!("🏷️ {}", t_args(msg, &[&timeout]));
+    }
+    let report = match dhcp::discover_dhcp_servers(interface.as_deref(), timeout, passive) {
+        Ok(report) => report,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::DhcpFailed, &[&e]));
+            return match e.kind() {
+                PortlyErrorKind::PermissionDenied => EXIT_PERMISSION,
+                _ => EXIT_NO_MATCH,
+            };
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else if report.servers.is_empty() {
+        println!(
+            "{}",
+            t_args(Msg::DhcpNone, &[&report.interface, &report.listened_secs])
+        );
+    } else {
+        for server in &report.servers {
+            print_dhcp_server(server);
+        }
+        if report.rogue_suspected {
+            println!();
+            println!("⚠️  {}", t_args(Msg::DhcpRogue, &[&report.servers.len()]));
+        }
+    }
+    if report.servers.len() == 1 {
+        EXIT_OK
+    } else {
+        EXIT_NO_MATCH
+    }
+}
+
+fn print_dhcp_server(server: &dhcp::DhcpOffer) {
+    println!(
+        "🏷️ {}",
+        t_args(
+            Msg::DhcpServer,
+            &[&server.server_id, &server.server_mac, &server.offered_ip]
+        )
+    );
+    let join = |values: &[String]| (!values.is_empty()).then(|| values.join(", "));
+    let lease = server.lease_secs.map(|secs| secs.to_string());
+    let details = [
+        (Msg::DhcpNetwork, &server.network),
+        (Msg::DhcpRouters, &join(&server.routers)),
+        (Msg::DhcpDns, &join(&server.dns_servers)),
+        (Msg::DhcpDomain, &server.domain),
+        (Msg::DhcpLease, &lease),
+    ];
+    for (msg, value) in details {
+        if let Some(value) = value {
+            println!("   {}", t_args(msg, &[&value]));
+        }
+    }
+}
+
+/// `portly-cli ntp [SERVER...]`: 查询 NTP 服务器，检测本机时钟偏差
+fn run_ntp(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut servers: Vec<String> = Vec::new();
+    let mut timeout = ntp::DEFAULT_TIMEOUT_MS;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "-t" | "--timeout" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u64>().ok()) {
+                    Some(value) if value > 0 => timeout = value,
+                    _ => return usage_error(t(Msg::NtpTimeoutInvalid)),
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_ntp_help();
+                return EXIT_OK;
+            }
+            arg if !arg.starts_with('-') => servers.push(arg.to_string()),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    let report = match ntp::check_time_drift(&servers, timeout) {
+        Ok(report) => report,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        for server in &report.servers {
+            print_ntp_server(server);
+        }
+        println!();
+        match (report.drift_ms, report.level) {
+            (Some(drift), level) => {
+                let (icon, msg) = match level {
+                    ntp::DriftLevel::Ok => ("✅", Msg::NtpDriftOk),
+                    ntp::DriftLevel::Warning => ("⚠️ ", Msg::NtpDriftWarning),
+                    _ => ("❌", Msg::NtpDriftCritical),
+                };
+                println!("{} {}", icon, t_args(msg, &[&format_drift(drift)]));
+            }
+            (None, _) => println!("❌ {}", t(Msg::NtpNoReply)),
+        }
+    }
+    if report.level == ntp::DriftLevel::Ok {
+        EXIT_OK
+    } else {
+        EXIT_NO_MATCH
+    }
+}
+
+fn print_ntp_server(server: &ntp::NtpServerResult) {
+    let address = match &server.address {
+        Some(address) if *address != server.server => format!("{} ({})", server.server, address),
+        _ => server.server.clone(),
+    };
+    match (server.offset_ms, server.delay_ms) {
+        (Some(offset), Some(delay)) => println!(
+            "🕒 {}",
+            t_args(
+                Msg::NtpServerReply,
+                &[
+                    &address,
+                    &format_drift(offset),
+                    &format!("{:.1}", delay),
+                    &server.stratum.unwrap_or_default(),
+                    &server.reference_id.as_deref().unwrap_or("-"),
+                ]
+            )
+        ),
+        _ => println!(
+            "❌ {}",
+            t_args(
+                Msg::NtpServerFailed,
+                &[&address, &server.error.as_deref().unwrap_or("-")]
+            )
+        ),
+    }
+}
+
+/// 偏差带符号显示，超过 1 秒时以秒为单位
+fn format_drift(offset_ms: f64) -> String {
+    if offset_ms.abs() >= 1000.0 {
+        format!("{:+.3} s", offset_ms / 1000.0)
+    } else {
+        format!("{:+.1} ms", offset_ms)
+    }
+}
+
+/// `portly-cli shares <HOST>`: 列出 SMB 共享和 NFS 导出，标出公开的共享
+fn run_shares(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut host: Option<&str> = None;
+    let (mut smb, mut nfs) = (false, false);
+
+    for arg in args {
+        match arg.as_str() {
+            "-j" | "--json" => json = true,
+            "--smb" => smb = true,
+            "--nfs" => nfs = true,
+            "-h" | "--help" => {
+                print_shares_help();
+                return EXIT_OK;
+            }
+            arg if !arg.starts_with('-') && host.is_none() => host = Some(arg),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+    }
+    let Some(host) = host else {
+        print_shares_help();
+        return EXIT_USAGE;
+    };
+    // 都未指定时两种都查
+    if !smb && !nfs {
+        (smb, nfs) = (true, true);
+    }
+
+    let listing = match shares::list_shares(host, smb, nfs) {
+        Ok(listing) => listing,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&listing).unwrap());
+    } else {
+        for share in &listing.smb_shares {
+            let marker = if share.public { "⚠️ " } else { "  " };
+            println!(
+                "{} smb://{}/{}\t{}",
+                marker, listing.host, share.name, share.comment
+            );
+        }
+        for export in &listing.nfs_exports {
+            let marker = if export.public { "⚠️ " } else { "  " };
+            println!(
+                "{} nfs://{}{}\t{}",
+                marker,
+                listing.host,
+                export.path,
+                export.clients.join(",")
+            );
+        }
+        for error in &listing.errors {
+            eprintln!("❌ {}", error);
+        }
+        if listing.has_public() {
+            println!();
+            println!("⚠️  {}", t(Msg::SharesPublic));
+        } else if listing.smb_shares.is_empty()
+            && listing.nfs_exports.is_empty()
+            && listing.errors.is_empty()
+        {
+            println!("{}", t_args(Msg::SharesNone, &[&listing.host]));
+        }
+    }
+    let failed = listing.smb_shares.is_empty()
+        && listing.nfs_exports.is_empty()
+        && !listing.errors.is_empty();
+    if listing.has_public() || failed {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+/// `portly-cli printer <HOST>`: 查询打印机型号、状态和耗材余量
+fn run_printer(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut host: Option<&str> = None;
+    let mut port = printer::IPP_PORT;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => json = true,
+            "-p" | "--port" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u16>().ok()) {
+                    Some(value @ (printer::IPP_PORT | printer::JETDIRECT_PORT)) => port = value,
+                    _ => return usage_error(t(Msg::PrinterPortInvalid)),
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_printer_help();
+                return EXIT_OK;
+            }
+            arg if !arg.starts_with('-') && host.is_none() => host = Some(arg),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+    let Some(host) = host else {
+        print_printer_help();
+        return EXIT_USAGE;
+    };
+
+    let info = match printer::query_printer(host, port) {
+        Ok(info) => info,
+        Err(e) if e.kind() == PortlyErrorKind::Validation => return usage_error(&e.to_string()),
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&info).unwrap());
+        return EXIT_OK;
+    }
+
+    let state = match info.state {
+        printer::PrinterState::Idle => t(Msg::PrinterStateIdle),
+        printer::PrinterState::Processing => t(Msg::PrinterStateProcessing),
+        printer::PrinterState::Stopped => t(Msg::PrinterStateStopped),
+        printer::PrinterState::Unknown => t(Msg::PrinterStateUnknown),
+    };
+    let status = match &info.status_message {
+        Some(message) => format!("{} ({})", state, message),
+        None => state.to_string(),
+    };
+    let supplies: Vec<String> = info
+        .markers
+        .iter()
+        .map(|marker| match marker.level {
+            Some(level) => format!("{} {}%", marker.name, level),
+            None => format!("{} ?", marker.name),
+        })
+        .collect();
+    let rows = [
+        (Msg::PrinterModel, info.make_and_model.clone()),
+        (Msg::PrinterName, info.name.clone()),
+        (Msg::PrinterLocation, info.location.clone()),
+        (Msg::PrinterDescription, info.info.clone()),
+        (Msg::PrinterStatus, Some(status)),
+        (
+            Msg::PrinterReasons,
+            Some(info.state_reasons.join(", ")).filter(|reasons| !reasons.is_empty()),
+        ),
+        (
+            Msg::PrinterSupplies,
+            Some(supplies.join(", ")).filter(|supplies| !supplies.is_empty()),
+        ),
+    ];
+    println!("🖨  {}:{}", info.host, info.port);
+    for (label, value) in rows {
+        if let Some(value) = value {
+            println!("  {}: {}", t(label), value);
+        }
+    }
+    EXIT_OK
+}
+
+/// `portly-cli arp`: 列出 ARP 缓存，`arp flush [IP]` 清空缓存或删除单个条目
+fn run_arp(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut flush = false;
+    let mut assume_yes = false;
+    let mut ip: Option<&str> = None;
+    for (index, arg) in args.iter().enumerate() {
+        match arg.as_str() {
+            "flush" if index == 0 => flush = true,
+            "-j" | "--json" => json = true,
+            "-y" | "--yes" if flush => assume_yes = true,
+            "-h" | "--help" => {
+                print_arp_help();
+                return EXIT_OK;
+            }
+            other if flush && ip.is_none() && !other.starts_with('-') => ip = Some(other),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+    }
+
+    if flush {
+        let question = match ip {
+            Some(ip) => t_args(Msg::ArpFlushOneConfirm, &[&ip]),
+            None => t(Msg::ArpFlushConfirm).to_string(),
+        };
+        if !assume_yes && !confirm(&question) {
+            return EXIT_USAGE;
+        }
+        return match arp::flush_arp_cache(ip) {
+            Ok(()) => {
+                match ip {
+                    Some(ip) => println!("✅ {}", t_args(Msg::ArpEntryDeleted, &[&ip])),
+                    None => println!("✅ {}", t(Msg::ArpFlushed)),
+                }
+                EXIT_OK
+            }
+            Err(e) if e.kind() == PortlyErrorKind::Validation => usage_error(&e.to_string()),
+            Err(e) => {
+                eprintln!("❌ {}", t_args(Msg::ArpFlushFailed, &[&e]));
+                match e.kind() {
+                    PortlyErrorKind::PermissionDenied => EXIT_PERMISSION,
+                    _ => EXIT_NO_MATCH,
+                }
+            }
+        };
+    }
+
+    let entries = match arp::arp_table() {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::ArpFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+    } else if entries.is_empty() {
+        println!("{}", t(Msg::ArpNone));
+    } else {
+        let ip_width = entries
+            .iter()
+            .map(|entry| entry.ip.len())
+            .max()
+            .unwrap_or(0);
+        for entry in &entries {
+            print_arp_entry(entry, ip_width);
+        }
+    }
+    if entries.is_empty() {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+fn print_arp_entry(entry: &arp::ArpEntry, ip_width: usize) {
+    let kind = match entry.kind {
+        arp::ArpEntryKind::Dynamic => "dynamic",
+        arp::ArpEntryKind::Static => "static",
+        arp::ArpEntryKind::Incomplete => "incomplete",
+    };
+    let vendor = match (&entry.vendor, entry.randomized) {
+        (Some(vendor), _) => vendor.clone(),
+        (None, true) => t(Msg::ArpRandomized).to_string(),
+        (None, false) => "-".to_string(),
+    };
+    let line = format!(
+        "{:<ip_width$}  {:<17}  {:<8} {:<10} {:<10} {:<20} {}",
+        entry.ip,
+        entry.mac.as_deref().unwrap_or("-"),
+        entry.interface.as_deref().unwrap_or("-"),
+        kind,
+        entry.state.as_deref().unwrap_or("-"),
+        vendor,
+        entry.hostname.as_deref().unwrap_or("")
+    );
+    println!("{}", line.trim_end());
+}
+
+/// `portly-cli routes`: 列出路由表和默认网关，`--to` 查询到某个目标实际走的路由
+fn run_routes(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut target: Option<&str> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-t" | "--to" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => target = Some(value),
+                    _ => return usage_error(t(Msg::RoutesTargetNeedsValue)),
+                }
+                i += 1;
+            }
+            "-j" | "--json" => json = true,
+            "-h" | "--help" => {
+                print_routes_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    if let Some(target) = target {
+        let lookup = match network::route_to(target) {
+            Ok(lookup) => lookup,
+            Err(e) if e.kind() == PortlyErrorKind::Validation => {
+                return usage_error(&e.to_string())
+            }
+            Err(e) => {
+                eprintln!("❌ {}", e);
+                return EXIT_NO_MATCH;
+            }
+        };
+        if json {
+            println!("{}", serde_json::to_string_pretty(&lookup).unwrap());
+        }
+        let Some(route) = &lookup.route else {
+            if !json {
+                println!("{}", t_args(Msg::RoutesNoMatch, &[&lookup.ip]));
+            }
+            return EXIT_NO_MATCH;
+        };
+        if !json {
+            let gateway = route.gateway.as_deref().unwrap_or(t(Msg::RoutesOnLink));
+            println!(
+                "{}",
+                t_args(
+                    Msg::RoutesLookup,
+                    &[&lookup.ip, &route.destination, &gateway, &route.interface]
+                )
+            );
+        }
+        return EXIT_OK;
+    }
+
+    let routes = match network::get_routes() {
+        Ok(routes) => routes,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::RoutesFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&routes).unwrap());
+    } else if routes.is_empty() {
+        println!("{}", t(Msg::RoutesNone));
+    } else {
+        let gateway = |route: &network::RouteEntry| {
+            route
+                .gateway
+                .clone()
+                .unwrap_or_else(|| t(Msg::RoutesOnLink).to_string())
+        };
+        let destination_width = routes
+            .iter()
+            .map(|r| r.destination.len())
+            .max()
+            .unwrap_or(0);
+        let gateway_width = routes.iter().map(|r| gateway(r).width()).max().unwrap_or(0);
+        for route in &routes {
+            let metric = route.metric.map(|m| m.to_string());
+            let vpn = route.interface_kind == network::InterfaceKind::Vpn;
+            let line = format!(
+                "{:<destination_width$}  {}  {:<10} {:<6} {}",
+                route.destination,
+                pad_to_width(&gateway(route), gateway_width, false),
+                route.interface,
+                metric.as_deref().unwrap_or("-"),
+                if vpn { "VPN" } else { "" }
+            );
+            println!("{}", line.trim_end());
+        }
+        if let Some(route) = network::default_gateway(&routes) {
+            let gateway = route.gateway.as_deref().unwrap_or(t(Msg::RoutesOnLink));
+            println!();
+            println!(
+                "{}",
+                t_args(Msg::RoutesDefaultGateway, &[&gateway, &route.interface])
+            );
+        }
+    }
+    if routes.is_empty() {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+/// `portly-cli ifstats`: 网卡链路信息和收发计数，`--watch` 按间隔采样实时吞吐量
+fn run_ifstats(args: &[String]) -> i32 {
+    let mut json = false;
+    let mut watch = false;
+    let mut interval_secs = iface_stats::DEFAULT_INTERVAL_SECS;
+    let mut count: Option<u64> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-w" | "--watch" => watch = true,
+            "--interval" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u64>().ok()) {
+                    Some(value) if iface_stats::interval(value).is_ok() => interval_secs = value,
+                    _ => return usage_error(t(Msg::IfstatsIntervalInvalid)),
+                }
+                i += 1;
+            }
+            "-n" | "--count" => {
+                match args.get(i + 1).and_then(|value| value.parse::<u64>().ok()) {
+                    Some(value) if value > 0 => count = Some(value),
+                    _ => return usage_error(t(Msg::IfstatsCountInvalid)),
+                }
+                i += 1;
+            }
+            "-j" | "--json" => json = true,
+            "-h" | "--help" => {
+                print_ifstats_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    if watch {
+        let Ok(interval) = iface_stats::interval(interval_secs) else {
+            return usage_error(t(Msg::IfstatsIntervalInvalid));
+        };
+        // 第一次采样只建立基线；采够 --count 次后置位停止标志
+        let stop = AtomicBool::new(false);
+        let mut printed = 0;
+        iface_stats::watch_interfaces(&stop, interval, |sample| {
+            if sample.elapsed_ms == 0 {
+                return;
+            }
+            if json {
+                println!("{}", serde_json::to_string(sample).unwrap());
+            } else {
+                print_interface_rates(sample);
+            }
+            let _ = std::io::stdout().flush();
+            printed += 1;
+            if count.is_some_and(|count| printed >= count) {
+                stop.store(true, Ordering::Relaxed);
+            }
+        });
+        return EXIT_OK;
+    }
+
+    let stats = match iface_stats::interface_stats() {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::IfstatsFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+    } else if stats.is_empty() {
+        println!("{}", t(Msg::IfstatsNone));
+    } else {
+        let name_width = stats.iter().map(|s| s.name.width()).max().unwrap_or(0);
+        let state = |up: bool| t(if up { Msg::IfstatsUp } else { Msg::IfstatsDown });
+        let state_width = state(true).width().max(state(false).width());
+        for stat in &stats {
+            let speed = stat.speed_mbps.map(format_link_speed);
+            let mtu = stat.mtu.map(|mtu| format!("MTU {}", mtu));
+            let errors = stat.counters.rx_errors + stat.counters.tx_errors;
+            let vpn = stat.kind == network::InterfaceKind::Vpn;
+            let line = format!(
+                "{}  {}  {:<9}  {:<8}  ↓ {:>10}  ↑ {:>10}  {}  {}",
+                pad_to_width(&stat.name, name_width, false),
+                pad_to_width(state(stat.is_up), state_width, false),
+                speed.as_deref().unwrap_or("-"),
+                mtu.as_deref().unwrap_or("-"),
+                format_bytes(stat.counters.rx_bytes as f64),
+                format_bytes(stat.counters.tx_bytes as f64),
+                if errors > 0 {
+                    t_args(Msg::IfstatsErrors, &[&errors])
+                } else {
+                    String::new()
+                },
+                if vpn { "VPN" } else { "" }
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+    if stats.is_empty() {
+        EXIT_NO_MATCH
+    } else {
+        EXIT_OK
+    }
+}
+
+/// 一次采样中已连接网卡的每秒收发速率
+fn print_interface_rates(sample: &iface_stats::InterfaceSample) {
+    let up: Vec<_> = sample.interfaces.iter().filter(|r| r.stats.is_up).collect();
+    let name_width = up.iter().map(|r| r.stats.name.width()).max().unwrap_or(0);
+    println!("[{}]", sample.sampled_at);
+    for rate in up {
+        let per_sec = |value: Option<f64>| match value {
+            Some(value) => format!("{}/s", format_bytes(value)),
+            None => "-".to_string(),
+        };
+        let utilization = rate.utilization_percent.map(|p| format!("{:.0}%", p));
+        let vpn = rate.stats.kind == network::InterfaceKind::Vpn;
+        let line = format!(
+            "{}  ↓ {:>12}  ↑ {:>12}  {:>4}  {}  {}",
+            pad_to_width(&rate.stats.name, name_width, false),
+            per_sec(rate.rx_bytes_per_sec),
+            per_sec(rate.tx_bytes_per_sec),
+            utilization.as_deref().unwrap_or(""),
+            if vpn { "VPN" } else { "" },
+            if rate.new_errors > 0 {
+                t_args(Msg::IfstatsNewErrors, &[&rate.new_errors])
+            } else {
+                String::new()
+            }
+        );
+        println!("{}", line.trim_end());
+    }
+}
+
+/// 字节数按 1024 进位显示
+fn format_bytes(bytes: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{:.0} B", value)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// 链路速率，整 Gbit/s 以 G 显示
+fn format_link_speed(mbps: u64) -> String {
+    if mbps >= 1000 && mbps.is_multiple_of(1000) {
+        format!("{} Gb/s", mbps / 1000)
+    } else {
+        format!("{} Mb/s", mbps)
+    }
+}
+
+/// `portly-cli deps`: 按已建立的连接列出本机服务之间的依赖
+fn run_deps(args: &[String]) -> i32 {
+    let mut json = false;
+    for arg in args {
+        match arg.as_str() {
+            "-j" | "--json" => json = true,
+            "-h" | "--help" => {
+                print_deps_help();
+                return EXIT_OK;
+            }
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+    }
+
+    let graph = match dependencies::dependency_graph() {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return EXIT_NO_MATCH;
+        }
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(&graph).unwrap());
+        return if graph.edges.is_empty() {
+            EXIT_NO_MATCH
+        } else {
+            EXIT_OK
+        };
+    }
+    if graph.edges.is_empty() {
+        println!("{}", t(Msg::DepsNone));
+        return EXIT_NO_MATCH;
+    }
+
+    let label = |id: &str| match graph.nodes.iter().find(|node| node.id == id) {
+        Some(node) if node.kind == dependencies::DependencyNodeKind::Process => {
+            let ports: Vec<String> = node.ports.iter().map(|port| format!(":{}", port)).collect();
+            format!(
+                "{} ({}) {}",
+                node.label,
+                node.pid.as_deref().unwrap_or("-"),
+                ports.join(" ")
+            )
+            .trim_end()
+            .to_string()
+        }
+        Some(node) => node.label.clone(),
+        None => id.to_string(),
+    };
+    let mut current: Option<&str> = None;
+    for edge in &graph.edges {
+        if current != Some(edge.from.as_str()) {
+            current = Some(edge.from.as_str());
+            println!("🔗 {}", label(&edge.from));
+        }
+        let target = graph
+            .nodes
+            .iter()
+            .find(|node| node.id == edge.to)
+            .map(|node| node.label.as_str())
+            .unwrap_or(&edge.to);
+        let service = edge
+            .service
+            .as_deref()
+            .map(|service| format!(" {}", service))
+            .unwrap_or_default();
+        println!(
+            "   → {}:{}{}  ({})",
+            target,
+            edge.port,
+            service,
+            t_args(Msg::DepsConnections, &[&edge.connections])
+        );
+    }
+    EXIT_OK
+}
+
+fn print_neighbor(neighbor: &lldp::Neighbor) {
+    let protocol = match neighbor.protocol {
+        lldp::NeighborProtocol::Lldp => "LLDP",
+        lldp::NeighborProtocol::Cdp => "CDP",
+    };
+    let switch = neighbor
+        .system_name
+        .as_deref()
+        .or(neighbor.chassis_id.as_deref())
+        .unwrap_or(&neighbor.source_mac);
+    println!(
+        "🔌 {} [{}]",
+        t_args(
+            Msg::NeighborsFound,
+            &[
+                &neighbor.interface,
+                &switch,
+                &neighbor.port_id.as_deref().unwrap_or("-")
+            ]
+        ),
+        protocol
+    );
+    let vlan = neighbor.vlan.map(|vlan| vlan.to_string());
+    let details = [
+        (Msg::NeighborsVlan, &vlan),
+        (Msg::NeighborsPortDescription, &neighbor.port_description),
+        (Msg::NeighborsManagement, &neighbor.management_address),
+        (Msg::NeighborsPlatform, &neighbor.platform),
+    ];
+    for (msg, value) in details {
+        if let Some(value) = value {
+            println!("   {}", t_args(msg, &[&value]));
+        }
+    }
+}
+
+/// `portly-cli backup`: 导出 / 恢复完整状态
+fn run_backup(args: &[String]) -> i32 {
+    let with_hooks = args.iter().any(|arg| arg == "--with-hooks");
+    let args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--with-hooks")
+        .collect();
+    let result = match args.as_slice() {
+        ["export", path] => backup::export_backup(path).map(|summary| {
+            t_args(
+                Msg::BackupExported,
+                &[
+                    path,
+                    &summary.rules,
+                    &summary.schedules,
+                    &summary.known_ports,
+                    &summary.inventory,
+                    &summary.history,
+                ],
+            )
+        }),
+        ["import", path] => backup::preview_backup(path)
+            .and_then(|preview| {
+                print_backup_hooks(&preview.hooks, with_hooks);
+                backup::import_backup(path, with_hooks)
+            })
+            .map(|result| {
+                t_args(
+                    Msg::BackupImported,
+                    &[
+                        &result.created_at,
+                        &result.summary.rules,
+                        &result.summary.schedules,
+                        &result.summary.known_ports,
+                        &result.summary.inventory,
+                        &result.history_imported,
+                        &result.history_skipped,
+                    ],
+                )
+            }),
+        ["-h"] | ["--help"] => {
+            print_backup_help();
+            return EXIT_OK;
+        }
+        _ => {
+            print_backup_help();
+            return EXIT_USAGE;
+        }
+    };
+    match result {
+        Ok(message) => {
+            println!("✅ {}", message);
+            EXIT_OK
+        }
+        Err(e) => usage_error(&e.to_string()),
+    }
+}
+
+/// 导入前列出备份中的脚本钩子命令，未加 `--with-hooks` 时说明它们不会被安装
+fn print_backup_hooks(hooks: &[settings::ScriptHookSettings], with_hooks: bool) {
+    if hooks.is_empty() {
+        return;
+    }
+    let msg = if with_hooks {
+        Msg::BackupHooksInstalling
+    } else {
+        Msg::BackupHooksSkipped
+    };
+    eprintln!("⚠️  {}", t_args(msg, &[&hooks.len()]));
+    for hook in hooks {
+        if hook.name.is_empty() {
+            eprintln!("    {}", hook.command);
+        } else {
+            eprintln!("    {}: {}", hook.name, hook.command);
+        }
+    }
+}
+
+/// `portly-cli config`: 查看 / 修改共享配置文件
+fn run_config(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] | ["show"] => {
+            let config = load_cli_config();
+            println!("# {}", config_path().display());
+            match render_config(&config) {
+                Ok(content) => print!("{}", content),
+                Err(e) => eprintln!("❌ {}", e),
+            }
+            EXIT_OK
+        }
+        ["path"] => {
+            println!("{}", config_path().display());
+            EXIT_OK
+        }
+        ["get", key] => match get_config_value(&load_cli_config(), key) {
+            Some(value) => {
+                println!("{}", value);
+                EXIT_OK
+            }
+            None => {
+                eprintln!("{}", t_args(Msg::ConfigNotSet, &[key]));
+                EXIT_NO_MATCH
+            }
+        },
+        ["set", key, value] => {
+            let config = match load_config() {
+                Ok(config) => config,
+                Err(e) => return usage_error(&e.to_string()),
+            };
+            match set_config_value(&config, key, value).and_then(|c| save_config(&c)) {
+                Ok(()) => {
+                    println!("✅ {} = {}", key, value);
+                    EXIT_OK
+                }
+                Err(e) => usage_error(&e.to_string()),
+            }
+        }
+        ["-h"] | ["--help"] => {
+            print_config_help();
+            EXIT_OK
+        }
+        _ => {
+            print_config_help();
+            EXIT_USAGE
+        }
+    }
+}
+
+/// porcelain 字段：制表符和换行替换为空格，空值输出 "-"
+fn porcelain_field(value: &str) -> String {
+    let value = value.replace(['\t', '\n', '\r'], " ");
+    if value.is_empty() {
+        "-".to_string()
+    } else {
+        value
+    }
+}
+
+fn write_porcelain_ports(
+    out: &mut impl Write,
+    ports: &[PortInfo],
+    columns: &[PortColumn],
+) -> std::io::Result<()> {
+    for port in ports {
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|column| porcelain_field(&column.value(port)))
+            .collect();
+        writeln!(out, "{}", fields.join("\t"))?;
+    }
+    Ok(())
+}
+
+fn write_porcelain_groups(out: &mut impl Write, groups: &[AppGroup]) -> std::io::Result<()> {
+    for group in groups {
+        let ports: Vec<String> = group.ports.iter().map(u16::to_string).collect();
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}",
+            porcelain_field(&group.process),
+            porcelain_field(&group.pid),
+            porcelain_field(&ports.join(",")),
+            porcelain_field(group.command.as_deref().unwrap_or_default())
+        )?;
+    }
+    Ok(())
+}
+
+/// 多主机扫描时每行以主机名开头
+fn write_porcelain_scan(
+    out: &mut impl Write,
+    host: Option<&str>,
+    results: &[RemotePort],
+) -> std::io::Result<()> {
+    for result in results {
+        if let Some(host) = host {
+            write!(out, "{}\t", porcelain_field(host))?;
+        }
+        writeln!(
+            out,
+            "{}\t{}\t{}",
+            result.port,
+            if result.is_open { "open" } else { "closed" },
+            porcelain_field(result.service.as_deref().unwrap_or_default())
+        )?;
+    }
+    Ok(())
+}
+
+/// 写入 stdout；管道提前关闭（如 `| head`）时静默忽略
+fn write_stdout(write: impl FnOnce(&mut std::io::StdoutLock) -> std::io::Result<()>) {
+    let _ = write(&mut std::io::stdout().lock());
+}
+
+/// `portly-cli --rpc`: 在 stdin / stdout 上提供 JSON-RPC 接口，直到 stdin 关闭
+fn run_rpc() -> i32 {
+    let stdin = std::io::stdin();
+    match rpc::serve_stdio(stdin.lock(), std::io::stdout().lock()) {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::RpcFailed, &[&e]));
+            EXIT_NO_MATCH
+        }
+    }
+}
+
+/// 查询 GitHub 最新发布，有新版本时显示下载地址和更新内容
+fn run_check_update(output: OutputFormat) -> i32 {
+    let info = match update::check_update(env!("CARGO_PKG_VERSION")) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::UpdateCheckFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&info).unwrap());
+        return EXIT_OK;
+    }
+
+    if !info.update_available {
+        println!("✅ {}", t_args(Msg::UpdateLatest, &[&info.current_version]));
+        return EXIT_OK;
+    }
+    println!(
+        "⬆️  {}",
+        t_args(
+            Msg::UpdateAvailable,
+            &[&info.latest_version, &info.current_version]
+        )
+    );
+    let url = info.download_url.as_deref().unwrap_or(&info.release_url);
+    println!("   {}", t_args(Msg::UpdateDownload, &[&url]));
+    if !info.changelog.is_empty() {
+        println!("\n{}", t(Msg::UpdateChangelog));
+        for line in info.changelog.lines() {
+            println!("  {}", line);
+        }
+    }
+    EXIT_OK
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("❌ {}", message);
+    eprintln!("{}", t(Msg::SeeHelp));
+    EXIT_USAGE
+}
+
+/// 根据过滤结果计算退出码；没有 root 权限时其他用户的进程信息显示为 "-"
+fn match_exit_code(is_empty: bool, filtering: bool, owner_hidden: bool) -> i32 {
+    if !is_empty {
+        return EXIT_OK;
+    }
+    if filtering && owner_hidden {
+        eprintln!("⚠️  {}", t(Msg::OwnerHidden));
+        return EXIT_PERMISSION;
+    }
+    EXIT_NO_MATCH
+}
+
+/// `portly-cli scan <target>`: 远程主机端口扫描
+fn run_scan(args: &[String]) -> i32 {
+    let mut target: Option<String> = None;
+    let mut port_spec = "top100".to_string();
+    let config = load_cli_config();
+    let mut timeout_ms: u64 = config.timeouts.scan_ms;
+    let mut output = OutputFormat::Auto;
+    let mut show_closed = false;
+    let mut no_color = false;
+    let mut address: Option<String> = None;
+    let mut tag: Option<String> = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-j" | "--json" => output = OutputFormat::Json,
+            "--csv" => output = OutputFormat::Csv,
+            "--ndjson" => output = OutputFormat::Ndjson,
+            "-q" | "--porcelain" => output = OutputFormat::Porcelain,
+            "-o" | "--output" => {
+                output = match parse_output_arg(args.get(i + 1)) {
+                    Ok(output) => output,
+                    Err(e) => return usage_error(&e),
+                };
+                i += 1;
+            }
+            "-a" | "--all" => show_closed = true,
+            "--no-color" => no_color = true,
+            "--ports" => {
+                let Some(value) = args.get(i + 1) else {
+                    return usage_error(t(Msg::ScanPortsNeedsValue));
+                };
+                port_spec = value.clone();
+                i += 1;
+            }
+            "--timeout" => {
+                match args.get(i + 1).map(|value| value.parse::<u64>()) {
+                    Some(Ok(ms)) if (1..=60_000).contains(&ms) => timeout_ms = ms,
+                    _ => return usage_error(t(Msg::ScanTimeoutInvalid)),
+                }
+                i += 1;
+            }
+            "--address" => {
+                let Some(value) = args.get(i + 1) else {
+                    return usage_error(t(Msg::ScanAddressNeedsValue));
+                };
+                address = Some(value.clone());
+                i += 1;
+            }
+            "--tag" => {
+                match args.get(i + 1) {
+                    Some(value) if !value.trim().is_empty() => tag = Some(value.clone()),
+                    _ => return usage_error(t(Msg::TagNeedsValue)),
+                }
+                i += 1;
+            }
+            "-h" | "--help" => {
+                print_scan_help();
+                return EXIT_OK;
+            }
+            arg if !arg.starts_with('-') && target.is_none() => target = Some(arg.to_string()),
+            other => return usage_error(&t_args(Msg::UnknownArgument, &[&other])),
+        }
+        i += 1;
+    }
+
+    let Some(target) = target else {
+        print_scan_help();
+        return EXIT_USAGE;
+    };
+
+    // 主机名模板（如 node{01..20}.lan）展开后逐台扫描，解析失败的主机只给出警告
+    let is_pattern = host_pattern::is_host_pattern(&target);
+    let mut hosts: Vec<(String, ResolveResult)> = if is_pattern {
+        let hosts = match host_pattern::resolve_host_pattern(&target) {
+            Ok(hosts) => hosts,
+            Err(e) => return usage_error(&e.to_string()),
+        };
+        let mut resolved_hosts = Vec::new();
+        for host in hosts {
+            let resolved = match (host.resolved, &address) {
+                (Some(resolved), Some(address)) => {
+                    resolved.select_address(address).map_err(|e| e.to_string())
+                }
+                (Some(resolved), None) => Ok(resolved),
+                (None, _) => Err(host.error.unwrap_or_default()),
+            };
+            match resolved {
+                Ok(resolved) => resolved_hosts.push((host.name, resolved)),
+                Err(e) => eprintln!("{}", t_args(Msg::ScanPatternUnresolved, &[&host.name, &e])),
+            }
+        }
+        if resolved_hosts.is_empty() {
+            return usage_error(&t_args(Msg::ScanPatternEmpty, &[&target]));
+        }
+        resolved_hosts
+    } else {
+        let resolved = match resolve_target(&target) {
+            Ok(resolved) => match &address {
+                Some(address) => match resolved.select_address(address) {
+                    Ok(resolved) => resolved,
+                    Err(e) => return usage_error(&e.to_string()),
+                },
+                None => resolved,
+            },
+            Err(e) => return usage_error(&e.to_string()),
+        };
+        vec![(target.clone(), resolved)]
+    };
+    // 只扫描资产清单中带指定标签的主机
+    if let Some(tag) = &tag {
+        hosts.retain(|(name, resolved)| {
+            inventory::has_tag(&inventory::lookup(&[name, &resolved.ip]).tags, tag)
+        });
+        if hosts.is_empty() {
+            eprintln!("{}", t_args(Msg::TagNoTargets, &[tag]));
+            return EXIT_NO_MATCH;
+        }
+    }
+    // 端口描述可以是配置中的端口组合名
+    let port_spec = config
+        .port_profiles
+        .get(&port_spec)
+        .cloned()
+        .unwrap_or(port_spec);
+    let ports = match parse_port_spec(&port_spec) {
+        Ok(ports) => ports,
+        Err(e) => return usage_error(&e),
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ {}", t_args(Msg::RuntimeFailed, &[&e]));
+            return EXIT_NO_MATCH;
+        }
+    };
+    let scans: Vec<HostScan> = hosts
+        .into_iter()
+        .map(|(name, resolved)| {
+            let started = Instant::now();
+            let mut results = runtime.block_on(scan_ports_async(&resolved.ip, &ports, timeout_ms));
+            let elapsed_ms = started.elapsed().as_millis();
+            let open_ports = results.iter().filter(|r| r.is_open).count();
+            if !show_closed {
+                results.retain(|r| r.is_open);
+            }
+            let annotation = inventory::lookup(&[&name, &resolved.ip]);
+            HostScan {
+                annotation,
+                name,
+                resolved,
+                results,
+                open_ports,
+                elapsed_ms,
+            }
+        })
+        .collect();
+
+    match output.resolve() {
+        OutputFormat::Json => {
+            let outputs: Vec<JsonRemoteScanOutput> = scans
+                .iter()
+                .map(|scan| JsonRemoteScanOutput {
+                    schema_version: JSON_SCHEMA_VERSION,
+                    target: &scan.name,
+                    ip: &scan.resolved.ip,
+                    scanned_ports: ports.len(),
+                    open_ports: scan.open_ports,
+                    elapsed_ms: scan.elapsed_ms,
+                    ports: &scan.results,
+                    tags: &scan.annotation.tags,
+                })
+                .collect();
+            // 单个目标保持原来的对象格式，模板输出每台主机一个对象的数组
+            let json = if is_pattern {
+                serde_json::to_string_pretty(&outputs)
+            } else {
+                serde_json::to_string_pretty(&outputs[0])
+            };
+            println!("{}", json.unwrap());
+        }
+        OutputFormat::Csv if is_pattern => {
+            write_stdout(|out| write_records_csv(out, &host_records(&scans)))
+        }
+        OutputFormat::Ndjson if is_pattern => {
+            write_stdout(|out| write_ndjson(out, &host_records(&scans), &mut |_, _| {}))
+        }
+        OutputFormat::Porcelain if is_pattern => write_stdout(|out| {
+            scans
+                .iter()
+                .try_for_each(|scan| write_porcelain_scan(out, Some(&scan.name), &scan.results))
+        }),
+        OutputFormat::Csv => write_stdout(|out| write_records_csv(out, &scans[0].results)),
+        OutputFormat::Ndjson => {
+            write_stdout(|out| write_ndjson(out, &scans[0].results, &mut |_, _| {}))
+        }
+        OutputFormat::Porcelain => {
+            write_stdout(|out| write_porcelain_scan(out, None, &scans[0].results))
+        }
+        OutputFormat::Table | OutputFormat::Auto => {
+            let palette = Palette::detect(no_color);
+            for scan in &scans {
+                print_scan_table(
+                    &scan.name,
+                    &scan.resolved,
+                    &scan.annotation,
+                    ports.len(),
+                    &scan.results,
+                    scan.elapsed_ms,
+                    palette,
+                )
+            }
+        }
+    }
+
+    if scans.iter().any(|scan| scan.open_ports > 0) {
+        EXIT_OK
+    } else {
+        EXIT_NO_MATCH
+    }
+}
+
+/// 一台主机的扫描结果
+struct HostScan {
+    name: String,
+    resolved: ResolveResult,
+    /// 资产清单中的标签和备注
+    annotation: inventory::TargetAnnotation,
+    results: Vec<RemotePort>,
+    open_ports: usize,
+    elapsed_ms: u128,
+}
+
+/// 多台主机的结果摊平成带主机名的记录，CSV / NDJSON 与导出文件的格式一致
+fn host_records(scans: &[HostScan]) -> Vec<RemotePortRecord<'_>> {
+    scans
+        .iter()
+        .flat_map(|scan| {
+            scan.results.iter().map(|port| RemotePortRecord {
+                host: &scan.name,
+                port,
+                tags: &scan.annotation.tags,
+            })
+        })
+        .collect()
+}
+
+fn apply_filter_ports(
+    mut ports: Vec<PortInfo>,
+    port_filter: Option<u16>,
+    app_filter: &Option<String>,
+    exclude_system: bool,
+) -> Vec<PortInfo> {
+    if let Some(pf) = port_filter {
+        ports.retain(|p| p.port == pf);
+    }
+    if let Some(ref af) = app_filter {
+        let af_lower = af.to_lowercase();
+        ports.retain(|p| p.process.to_lowercase().contains(&af_lower));
+    }
+    if exclude_system {
+        ports.retain(|p| !is_system_process(&p.process));
+    }
+    ports
+}
+
+fn apply_filter_groups(
+    mut groups: Vec<AppGroup>,
+    app_filter: &Option<String>,
+    exclude_system: bool,
+) -> Vec<AppGroup> {
+    if let Some(ref af) = app_filter {
+        let af_lower = af.to_lowercase();
+        groups.retain(|g| g.process.to_lowercase().contains(&af_lower));
+    }
+    if exclude_system {
+        groups.retain(|g| !is_system_process(&g.process));
+    }
+    groups
+}
+
+fn print_help() {
+    println!("{}", t(Msg::HelpMain));
+}
+
+fn print_config_help() {
+    println!("{}", t(Msg::HelpConfig));
+}
+
+fn print_ports_help() {
+    println!("{}", t(Msg::HelpPorts));
+}
+
+fn print_tag_help() {
+    println!("{}", t(Msg::HelpTag));
+}
+
+fn print_subnet_help() {
+    println!("{}", t(Msg::HelpSubnet));
+}
+
+fn print_portcheck_help() {
+    println!("{}", t(Msg::HelpPortcheck));
+}
+
+fn print_arp_help() {
+    println!("{}", t(Msg::HelpArp));
+}
+
+fn print_routes_help() {
+    println!("{}", t(Msg::HelpRoutes));
+}
+
+fn print_ifstats_help() {
+    println!("{}", t(Msg::HelpIfstats));
+}
+
+fn print_neighbors_help() {
+    println!("{}", t(Msg::HelpNeighbors));
+}
+
+fn print_discover_help() {
+    println!("{}", t(Msg::HelpDiscover));
+}
+
+fn print_dhcp_help() {
+    println!("{}", t(Msg::HelpDhcp));
+}
+
+fn print_ntp_help() {
+    println!("{}", t(Msg::HelpNtp));
+}
+
+fn print_shares_help() {
+    println!("{}", t(Msg::HelpShares));
+}
+
+fn print_printer_help() {
+    println!("{}", t(Msg::HelpPrinter));
+}
+
+fn print_deps_help() {
+    println!("{}", t(Msg::HelpDeps));
+}
+
+fn print_backup_help() {
+    println!("{}", t(Msg::HelpBackup));
+}
+
+fn print_free_help() {
+    println!("{}", t(Msg::HelpFree));
+}
+
+fn print_scan_help() {
+    println!("{}", t(Msg::HelpScan));
+}
+
+fn print_serve_help() {
+    println!("{}", t(Msg::HelpServe));
+}
+
+fn print_audit_help() {
+    println!("{}", t(Msg::HelpAudit));
+}
+
+fn print_exposure_help() {
+    println!("{}", t(Msg::HelpExposure));
+}
+
+fn print_scan_table(
+    target: &str,
+    resolved: &ResolveResult,
+    annotation: &inventory::TargetAnnotation,
+    scanned: usize,
+    results: &[RemotePort],
+    elapsed_ms: u128,
+    palette: Palette,
+) {
+    let open = results.iter().filter(|r| r.is_open).count();
+    let ip = resolved.ip.as_str();
+    let host = if target == ip {
+        ip.to_string()
+    } else {
+        format!("{} ({})", target, ip)
+    };
+
+    println!();
+    println!("═══════════════════════════════════════════════════════════════════════════════");
+    println!("  🔍 Portly - {}", host);
+    println!("═══════════════════════════════════════════════════════════════════════════════");
+    println!();
+    if !annotation.tags.is_empty() || annotation.note.is_some() {
+        let tags = annotation
+            .tags
+            .iter()
+            .map(|tag| format!("#{}", tag))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let line = format!("{}  {}", tags, annotation.note.as_deref().unwrap_or(""));
+        println!("  🏷️  {}", palette.paint(line.trim(), Some(COLOR_CYAN)));
+    }
+    println!(
+        "  📊 {}",
+        t_args(Msg::ScanSummary, &[&scanned, &open, &elapsed_ms])
+    );
+    if resolved.addresses.len() > 1 {
+        let addresses: Vec<&str> = resolved.addresses.iter().map(|a| a.ip.as_str()).collect();
+        println!(
+            "  {}",
+            palette.paint(
+                &t_args(Msg::ScanAddresses, &[&addresses.join(", ")]),
+                Some(COLOR_DIM)
+            )
+        );
+    }
+    println!();
+    println!(
+        "  {}",
+        palette.paint(
+            &format!(
+                "{}  {}  {}",
+                pad_to_width(t(Msg::ColumnPort), 6, true),
+                pad_to_width(t(Msg::ScanColumnState), 6, false),
+                t(Msg::ScanColumnService)
+            ),
+            Some(COLOR_BOLD)
+        )
+    );
+    println!("  {}", "─".repeat(40));
+
+    for r in results {
+        let (status, color) = if r.is_open {
+            (t(Msg::PortOpen), COLOR_GREEN)
+        } else {
+            (t(Msg::PortClosed), COLOR_RED)
+        };
+        println!(
+            "  {:>6}  {}  {}",
+            r.port,
+            palette.paint(&pad_to_width(status, 6, false), Some(color)),
+            r.service.as_deref().unwrap_or("-")
+        );
+        if let Some(policy) = &r.policy {
+            println!(
+                "          {}",
+                palette.paint(
+                    &t_args(
+                        Msg::ScanPolicyHit,
+                        &[&policy.severity.name(), &policy.policy]
+                    ),
+                    Some(COLOR_RED)
+                )
+            );
+        }
+    }
+
+    println!();
+    println!("═══════════════════════════════════════════════════════════════════════════════");
+}
+
+fn parse_columns(value: &str) -> Result<Vec<PortColumn>, String> {
+    let columns = value
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(|name| {
+            PortColumn::from_name(name).ok_or_else(|| t_args(Msg::UnknownColumn, &[&name.trim()]))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if columns.is_empty() {
+        return Err(t(Msg::ColumnsEmpty).to_string());
+    }
+    Ok(columns)
+}
+
+/// 终端宽度；输出不是终端时优先使用 COLUMNS，否则不限制
+fn terminal_width() -> Option<usize> {
+    if std::io::stdout().is_terminal() {
+        if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+            return Some(width as usize);
+        }
+    }
+    std::env::var("COLUMNS").ok()?.parse().ok()
+}
+
+fn column_title(column: PortColumn) -> &'static str {
+    t(match column {
+        PortColumn::Port => Msg::ColumnPort,
+        PortColumn::Protocol => Msg::ColumnProtocol,
+        PortColumn::Address => Msg::ColumnAddress,
+        PortColumn::Pid => Msg::ColumnPid,
+        PortColumn::Process => Msg::ColumnProcess,
+        PortColumn::User => Msg::ColumnUser,
+        PortColumn::Command => Msg::ColumnCommand,
+    })
+}
+
+/// 按显示宽度截断，超出部分用 "..." 表示
+fn truncate_to_width(value: &str, width: usize) -> String {
+    if value.width() <= width {
+        return value.to_string();
+    }
+    let mut result = String::new();
+    let mut used = 0;
+    for c in value.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 3 > width {
+            break;
+        }
+        result.push(c);
+        used += w;
+    }
+    result.push_str(&".".repeat(width.saturating_sub(used).min(3)));
+    result
+}
+
+/// 按显示宽度补齐（中文字符占两列）
+fn pad_to_width(value: &str, width: usize, right_align: bool) -> String {
+    let padding = " ".repeat(width.saturating_sub(value.width()));
+    if right_align {
+        format!("{}{}", padding, value)
+    } else {
+        format!("{}{}", value, padding)
+    }
+}
+
+/// 计算列宽：取内容最大宽度，总宽度超出终端时依次收缩最宽的文本列
+fn layout_columns(
+    columns: &[PortColumn],
+    rows: &[Vec<String>],
+    max_width: Option<usize>,
+) -> Vec<usize> {
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            rows.iter()
+                .map(|row| row[i].width())
+                .max()
+                .unwrap_or(0)
+                .max(column_title(*column).width())
+        })
+        .collect();
+
+    let Some(max_width) = max_width else {
+        return widths;
+    };
+    // 两个空格缩进 + 列间两个空格
+    let available = max_width.saturating_sub(2 + 2 * columns.len().saturating_sub(1));
+    while widths.iter().sum::<usize>() > available {
+        let shrinkable = columns
+            .iter()
+            .enumerate()
+            .filter(|(i, column)| {
+                !matches!(column, PortColumn::Port | PortColumn::Pid)
+                    && widths[*i] > MIN_COLUMN_WIDTH
+            })
+            .max_by_key(|(i, _)| widths[*i])
+            .map(|(i, _)| i);
+        match shrinkable {
+            Some(i) => widths[i] -= 1,
+            None => break,
+        }
+    }
+    widths
+}
+
+fn print_table(
+    ports: &[PortInfo],
+    columns: &[PortColumn],
+    show_command: bool,
+    scan_time: &str,
+    palette: Palette,
+) {
+    let unique_apps: HashSet<_> = ports
+        .iter()
+        .map(|p| format!("{}:{}", p.process, p.pid))
+        .collect();
+
+    let max_width = terminal_width();
+    let rows: Vec<Vec<String>> = ports
+        .iter()
+        .map(|p| columns.iter().map(|c| c.value(p)).collect())
+        .collect();
+    let widths = layout_columns(columns, &rows, max_width);
+    let rule_width = max_width.unwrap_or(79).min(79);
+    let right_align = |column: &PortColumn| matches!(column, PortColumn::Port | PortColumn::Pid);
+    // 先按宽度补齐再着色，避免转义序列影响对齐
+    let format_row = |cells: Vec<String>, color: &dyn Fn(PortColumn) -> Option<&'static str>| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(columns)
+            .zip(&widths)
+            .map(|((cell, column), width)| {
+                let cell = pad_to_width(
+                    &truncate_to_width(cell, *width),
+                    *width,
+                    right_align(column),
+                );
+                palette.paint(&cell, color(*column))
+            })
+            .collect();
+        format!("  {}", cells.join("  ").trim_end())
+    };
+
+    println!();
+    println!("{}", "═".repeat(rule_width));
+    println!("  🔍 Portly - {}", scan_time);
+    println!("{}", "═".repeat(rule_width));
+    println!();
+    println!(
+        "  📊 {}",
+        t_args(Msg::TableSummary, &[&unique_apps.len(), &ports.len()])
+    );
+    println!();
+    println!(
+        "{}",
+        format_row(
+            columns
+                .iter()
+                .map(|c| column_title(*c).to_string())
+                .collect(),
+            &|_| Some(COLOR_BOLD)
+        )
+    );
+    println!(
+        "  {}",
+        "─".repeat(widths.iter().sum::<usize>() + 2 * (widths.len().saturating_sub(1)))
+    );
+
+    // 已选择命令列时不再单独显示命令行
+    let command_line = show_command && !columns.contains(&PortColumn::Command);
+    let command_width = max_width.unwrap_or(79).saturating_sub(12).max(20);
+    for (p, row) in ports.iter().zip(rows) {
+        println!("{}", format_row(row, &|column| cell_color(p, column)));
+
+        if command_line {
+            if let Some(ref cmd) = p.command {
+                println!(
+                    "         └─ {}",
+                    palette.paint(&truncate_to_width(cmd, command_width), Some(COLOR_DIM))
+                );
+            }
+        }
+    }
+
+    println!();
+    println!("{}", "═".repeat(rule_width));
+}
+
+fn print_groups(groups: &[AppGroup], palette: Palette) {
+    println!();
+    println!("═══════════════════════════════════════════════════════════════════════════════");
+    println!("  🔍 Portly - {}", t(Msg::GroupedTitle));
+    println!("═══════════════════════════════════════════════════════════════════════════════");
+    println!();
+
+    for g in groups {
+        let ports_str: String = g
+            .ports
+            .iter()
+            .map(|p: &u16| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let process_color = if is_system_process(&g.process) {
+            Some(COLOR_DIM)
+        } else if is_docker_process(&g.process) {
+            Some(COLOR_CYAN)
+        } else {
+            Some(COLOR_BOLD)
+        };
+        println!(
+            "  📦 {} (PID: {})",
+            palette.paint(&g.process, process_color),
+            g.pid
+        );
+        println!(
+            "     └─ {}: {}",
+            t(Msg::GroupPorts),
+            palette.paint(&ports_str, Some(COLOR_GREEN))
+        );
+        if let Some(ref cmd) = g.command {
+            let cmd_display: String = if cmd.len() > 60 {
+                format!("{}...", &cmd[..57])
+            } else {
+                cmd.clone()
+            };
+            println!("     └─ {}: {}", t(Msg::GroupCommand), cmd_display);
+        }
+        println!();
+    }
+
+    println!("═══════════════════════════════════════════════════════════════════════════════");
+}
@@ -0,0 +1,682 @@
+//! Portly 高级网络扫描模块
+//!
+//! 在 macOS/Linux 上使用 pnet 进行底层扫描
+//! 在 Windows 上回退到基础扫描方法
+//!
+//! 高级 ARP 扫描可以指定接口和 802.1Q VLAN：系统已为该 VLAN 建立子接口（如 Linux 的
+//! `eth0.20`、macOS 的 `vlan0`）时直接在子接口上收发，由系统打标签；没有子接口时在父接口上
+//! 发送带 VLAN 标签的帧，让 trunk 口上的实验机从一块网卡逐个扫描各 VLAN
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Instant;
+
+use crate::app_error::{AppError, AppResult};
+use crate::network::{discover_devices, subnet_host_addresses, NetworkDevice};
+
+pub use crate::capabilities::PrivilegeLevel;
+
+/// 高级扫描结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvancedScanResult {
+    pub devices: Vec<NetworkDevice>,
+    pub scan_method: String,
+    pub scan_time_ms: u64,
+    pub has_permission: bool,
+    /// 高级扫描使用的接口
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// 在父接口上直接打标签时的 VLAN ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vlan: Option<u16>,
+}
+
+/// 高级 ARP 扫描的接口与 VLAN 选项，均为空时使用默认接口
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArpScanOptions {
+    /// 网卡或 VLAN 子接口名称，例如 `eth0`、`eth0.20`
+    #[serde(default)]
+    pub interface: Option<String>,
+    /// 802.1Q VLAN ID（1-4094）
+    #[serde(default)]
+    pub vlan: Option<u16>,
+}
+
+impl ArpScanOptions {
+    pub fn is_default(&self) -> bool {
+        self.interface.is_none() && self.vlan.is_none()
+    }
+}
+
+/// 一次高级 ARP 扫描的结果
+#[derive(Debug, Clone)]
+pub struct ArpScan {
+    pub devices: Vec<NetworkDevice>,
+    pub interface: String,
+    /// 在父接口上打的 VLAN 标签；使用子接口或未指定 VLAN 时为空
+    pub tag: Option<u16>,
+}
+
+/// 系统中的 VLAN 子接口
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VlanSubinterface {
+    pub name: String,
+    pub vlan: u16,
+    pub parent: String,
+}
+
+/// 解析 Linux `/proc/net/vlan/config`：`eth0.20 | 20 | eth0`
+pub fn parse_proc_vlan_config(text: &str) -> Vec<VlanSubinterface> {
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('|').map(str::trim).collect();
+            match fields.as_slice() {
+                [name, vlan, parent] => Some(VlanSubinterface {
+                    name: name.to_string(),
+                    vlan: vlan.parse().ok()?,
+                    parent: parent.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// 解析 macOS / BSD `ifconfig -a` 中的 `vlan: 20 parent interface: en0`
+pub fn parse_ifconfig_vlans(text: &str) -> Vec<VlanSubinterface> {
+    let mut current = "";
+    let mut subinterfaces = Vec::new();
+    for line in text.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            current = line.split(':').next().unwrap_or_default();
+            continue;
+        }
+        let line = line.trim();
+        let Some(rest) = line.strip_prefix("vlan:") else {
+            continue;
+        };
+        let vlan = rest.split_whitespace().next().and_then(|v| v.parse().ok());
+        let parent = line
+            .split_once("parent interface:")
+            .map(|(_, parent)| parent.trim())
+            .filter(|parent| !parent.is_empty() && *parent != "<none>");
+        if let (Some(vlan), Some(parent)) = (vlan, parent) {
+            subinterfaces.push(VlanSubinterface {
+                name: current.to_string(),
+                vlan,
+                parent: parent.to_string(),
+            });
+        }
+    }
+    subinterfaces
+}
+
+/// 读取系统中的 VLAN 子接口；无法读取时返回空列表
+pub fn vlan_subinterfaces() -> Vec<VlanSubinterface> {
+    if cfg!(target_os = "linux") {
+        std::fs::read_to_string("/proc/net/vlan/config")
+            .map(|text| parse_proc_vlan_config(&text))
+            .unwrap_or_default()
+    } else if cfg!(target_os = "windows") {
+        Vec::new()
+    } else {
+        crate::command_exec::run_command_with_timeout(
+            "ifconfig",
+            "VLAN 子接口读取",
+            |cmd| {
+                cmd.arg("-a");
+            },
+            std::time::Duration::from_secs(5),
+        )
+        .map(|output| parse_ifconfig_vlans(&output.stdout))
+        .unwrap_or_default()
+    }
+}
+
+/// 决定在哪个接口上收发：`base` 本身就是该 VLAN 的子接口，或存在以 `base` 为父接口的
+/// 子接口时使用子接口，否则在 `base` 上打标签。返回接口名和需要打的标签
+pub fn plan_vlan_interface(
+    base: &str,
+    vlan: Option<u16>,
+    subinterfaces: &[VlanSubinterface],
+) -> (String, Option<u16>) {
+    let Some(vlan) = vlan else {
+        return (base.to_string(), None);
+    };
+    subinterfaces
+        .iter()
+        .find(|sub| sub.vlan == vlan && (sub.name == base || sub.parent == base))
+        .map(|sub| (sub.name.clone(), None))
+        .unwrap_or_else(|| (base.to_string(), Some(vlan)))
+}
+
+/// 原始套接字能力报告：区分"缺少抓包驱动"与"权限不足"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityReport {
+    /// 抓包驱动是否就绪（macOS BPF 设备 / Windows Npcap / Linux AF_PACKET）
+    pub driver_present: bool,
+    pub privilege_level: PrivilegeLevel,
+    /// 用于高级扫描的网络接口
+    pub interface: Option<String>,
+    /// 是否能够打开原始以太网通道
+    pub has_permission: bool,
+    /// 面向用户的说明（需要如何修复）
+    pub reason: String,
+}
+
+/// 根据检测结果生成原因说明
+fn capability_reason(
+    driver_present: bool,
+    privilege_level: PrivilegeLevel,
+    interface: Option<&str>,
+    has_permission: bool,
+) -> String {
+    if has_permission {
+        return format!("可使用接口 {} 进行高级 ARP 扫描", interface.unwrap_or("-"));
+    }
+    if !driver_present {
+        return if cfg!(target_os = "windows") {
+            "未检测到 Npcap 驱动，请安装 Npcap 后重试".to_string()
+        } else {
+            "未检测到可用的抓包设备（BPF/AF_PACKET），请检查系统抓包支持".to_string()
+        };
+    }
+    if interface.is_none() {
+        return "未找到处于启用状态且带有 IPv4 地址的网络接口".to_string();
+    }
+    match privilege_level {
+        PrivilegeLevel::Elevated => {
+            "已具备管理员权限，但仍无法打开原始套接字，接口可能不支持以太网抓包".to_string()
+        }
+        _ => {
+            if cfg!(target_os = "windows") {
+                "权限不足，请以管理员身份运行 Portly".to_string()
+            } else {
+                "权限不足，请使用 sudo 运行或为抓包设备授予读取权限".to_string()
+            }
+        }
+    }
+}
+
+// ============================================================
+// Non-Windows implementation (uses pnet for ARP scanning)
+// ============================================================
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::*;
+    use crate::capabilities::{capture_driver_present, capture_error, privilege_level};
+    use pnet::datalink::{self, Channel, NetworkInterface};
+    use pnet::packet::arp::{ArpHardwareTypes, ArpOperations, ArpPacket, MutableArpPacket};
+    use pnet::packet::ethernet::{EtherTypes, EthernetPacket, MutableEthernetPacket};
+    use pnet::packet::vlan::{MutableVlanPacket, VlanPacket};
+    use pnet::packet::Packet;
+    use pnet::util::MacAddr;
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    /// 检查原始套接字能力
+    pub fn check_raw_socket_capability() -> CapabilityReport {
+        let driver_present = capture_driver_present();
+        let privilege_level = privilege_level();
+        let interface = get_default_interface();
+        let has_permission = match &interface {
+            Some(iface) if driver_present => matches!(
+                datalink::channel(iface, Default::default()),
+                Ok(Channel::Ethernet(_, _))
+            ),
+            _ => false,
+        };
+        let interface_name = interface.map(|iface| iface.name);
+
+        CapabilityReport {
+            driver_present,
+            privilege_level,
+            reason: capability_reason(
+                driver_present,
+                privilege_level,
+                interface_name.as_deref(),
+                has_permission,
+            ),
+            interface: interface_name,
+            has_permission,
+        }
+    }
+
+    /// 获取默认网络接口
+    fn get_default_interface() -> Option<NetworkInterface> {
+        let interfaces = datalink::interfaces();
+
+        interfaces.into_iter().find(|iface| {
+            !iface.is_loopback()
+                && iface.is_up()
+                && iface.mac.is_some()
+                && iface.ips.iter().any(|ip| ip.is_ipv4())
+        })
+    }
+
+    /// 抓包使用的接口：指定名称时按名称查找，否则使用默认接口
+    pub fn capture_interface(name: Option<&str>) -> AppResult<NetworkInterface> {
+        match name.map(str::trim).filter(|name| !name.is_empty()) {
+            Some(name) => datalink::interfaces()
+                .into_iter()
+                .find(|iface| iface.name == name)
+                .ok_or_else(|| AppError::validation("网络接口", format!("找不到接口 {}", name))),
+            None => get_default_interface().ok_or_else(|| {
+                AppError::validation("网络接口", "未找到处于启用状态且带有 IPv4 地址的网络接口")
+            }),
+        }
+    }
+
+    /// 高级 ARP 扫描（需要权限），可指定接口 / VLAN
+    pub fn arp_scan_on(subnet: &str, options: &ArpScanOptions) -> AppResult<ArpScan> {
+        let base = capture_interface(options.interface.as_deref())?;
+        let subinterfaces = if options.vlan.is_some() {
+            vlan_subinterfaces()
+        } else {
+            Vec::new()
+        };
+        let (name, tag) = plan_vlan_interface(&base.name, options.vlan, &subinterfaces);
+        let interface = datalink::interfaces()
+            .into_iter()
+            .find(|iface| iface.name == name)
+            .unwrap_or(base);
+
+        // 读超时让没有应答时也能按时结束
+        let config = datalink::Config {
+            read_timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let (mut tx, mut rx) = match datalink::channel(&interface, config) {
+            Ok(Channel::Ethernet(tx, rx)) => (tx, rx),
+            Ok(_) => return Err(AppError::internal("不支持的链路层通道类型")),
+            Err(err) => return Err(capture_error("高级 ARP 扫描", err)),
+        };
+        let source_mac = interface
+            .mac
+            .ok_or_else(|| AppError::validation("网络接口", "没有 MAC 地址"))?;
+
+        let hosts = subnet_host_addresses(subnet, 1022).unwrap_or_default();
+        if hosts.is_empty() {
+            return Err(AppError::validation(
+                "子网",
+                "需要 CIDR 格式，例如 192.168.1.0/24",
+            ));
+        }
+        let host_set: HashSet<String> = hosts.iter().cloned().collect();
+        // 优先使用接口在目标网段内的地址；打标签扫描其他 VLAN 时本机通常没有该网段的地址，
+        // 以 0.0.0.0 发送 ARP 探测（RFC 5227），主机同样会应答
+        let ipv4s: Vec<Ipv4Addr> = interface
+            .ips
+            .iter()
+            .filter_map(|ip| match ip {
+                pnet::ipnetwork::IpNetwork::V4(net) => Some(net.ip()),
+                _ => None,
+            })
+            .collect();
+        let source_ip = ipv4s
+            .iter()
+            .find(|ip| host_set.contains(&ip.to_string()))
+            .or(if tag.is_none() { ipv4s.first() } else { None })
+            .copied()
+            .unwrap_or(Ipv4Addr::UNSPECIFIED);
+
+        let mut devices = Vec::new();
+        let start = Instant::now();
+
+        for target in hosts {
+            let Ok(target_ip) = target.parse::<Ipv4Addr>() else {
+                continue;
+            };
+            send_arp_request(&mut tx, source_mac, source_ip, target_ip, tag);
+        }
+
+        let timeout = Duration::from_secs(3);
+        while start.elapsed() < timeout {
+            let Ok(packet) = rx.next() else { continue };
+            let Some((ip, mac)) = parse_arp_reply(packet, tag) else {
+                continue;
+            };
+            if !host_set.contains(&ip) || devices.iter().any(|d: &NetworkDevice| d.ip == ip) {
+                continue;
+            }
+            devices.push(NetworkDevice {
+                ip,
+                mac: Some(mac),
+                hostname: None,
+                is_online: true,
+                interface_index: None,
+                rtt_ms: None,
+                tags: Vec::new(),
+                note: None,
+            });
+        }
+
+        devices.sort_by(|a, b| {
+            let a_num: u32 =
+                a.ip.split('.')
+                    .next_back()
+                    .unwrap_or("0")
+                    .parse()
+                    .unwrap_or(0);
+            let b_num: u32 =
+                b.ip.split('.')
+                    .next_back()
+                    .unwrap_or("0")
+                    .parse()
+                    .unwrap_or(0);
+            a_num.cmp(&b_num)
+        });
+
+        Ok(ArpScan {
+            devices,
+            interface: interface.name,
+            tag,
+        })
+    }
+
+    /// 解析 ARP 应答，返回发送方 IP 和 MAC。打标签扫描时接受相同 VLAN 的带标签帧；
+    /// 网卡剥离 VLAN 标签（硬件卸载）后交上来的不带标签帧也接受
+    fn parse_arp_reply(packet: &[u8], tag: Option<u16>) -> Option<(String, String)> {
+        let ethernet = EthernetPacket::new(packet)?;
+        let (ethertype, payload) = match ethernet.get_ethertype() {
+            EtherTypes::Vlan => {
+                let vlan = VlanPacket::new(ethernet.payload())?;
+                if tag.is_some_and(|tag| vlan.get_vlan_identifier() != tag) {
+                    return None;
+                }
+                (vlan.get_ethertype(), vlan.payload().to_vec())
+            }
+            ethertype => (ethertype, ethernet.payload().to_vec()),
+        };
+        if ethertype != EtherTypes::Arp {
+            return None;
+        }
+        let arp = ArpPacket::new(&payload)?;
+        (arp.get_operation() == ArpOperations::Reply).then(|| {
+            (
+                arp.get_sender_proto_addr().to_string(),
+                arp.get_sender_hw_addr().to_string(),
+            )
+        })
+    }
+
+    /// 发送单个 ARP 请求；`tag` 不为空时插入 802.1Q 标签
+    fn send_arp_request(
+        tx: &mut Box<dyn datalink::DataLinkSender>,
+        source_mac: MacAddr,
+        source_ip: Ipv4Addr,
+        target_ip: Ipv4Addr,
+        tag: Option<u16>,
+    ) {
+        let mut ethernet_buffer = [0u8; 46];
+        let frame_len = if tag.is_some() { 46 } else { 42 };
+        let mut ethernet_packet =
+            MutableEthernetPacket::new(&mut ethernet_buffer[..frame_len]).unwrap();
+
+        ethernet_packet.set_destination(MacAddr::broadcast());
+        ethernet_packet.set_source(source_mac);
+
+        let mut arp_buffer = [0u8; 28];
+        let mut arp_packet = MutableArpPacket::new(&mut arp_buffer).unwrap();
+
+        arp_packet.set_hardware_type(ArpHardwareTypes::Ethernet);
+        arp_packet.set_protocol_type(EtherTypes::Ipv4);
+        arp_packet.set_hw_addr_len(6);
+        arp_packet.set_proto_addr_len(4);
+        arp_packet.set_operation(ArpOperations::Request);
+        arp_packet.set_sender_hw_addr(source_mac);
+        arp_packet.set_sender_proto_addr(source_ip);
+        arp_packet.set_target_hw_addr(MacAddr::zero());
+        arp_packet.set_target_proto_addr(target_ip);
+
+        match tag {
+            Some(tag) => {
+                let mut vlan_buffer = [0u8; 32];
+                let mut vlan_packet = MutableVlanPacket::new(&mut vlan_buffer).unwrap();
+                vlan_packet.set_vlan_identifier(tag);
+                vlan_packet.set_ethertype(EtherTypes::Arp);
+                vlan_packet.set_payload(arp_packet.packet());
+                ethernet_packet.set_ethertype(EtherTypes::Vlan);
+                ethernet_packet.set_payload(vlan_packet.packet());
+            }
+            None => {
+                ethernet_packet.set_ethertype(EtherTypes::Arp);
+                ethernet_packet.set_payload(arp_packet.packet());
+            }
+        }
+
+        let _ = tx.send_to(ethernet_packet.packet(), None);
+    }
+}
+
+// ============================================================
+// Windows implementation (fallback without pnet)
+// ============================================================
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+    use crate::capabilities::{capture_driver_present, privilege_level};
+
+    /// Windows 上暂不使用原始套接字，仅报告驱动与权限状态
+    pub fn check_raw_socket_capability() -> CapabilityReport {
+        let driver_present = capture_driver_present();
+        let privilege_level = privilege_level();
+        CapabilityReport {
+            driver_present,
+            privilege_level,
+            interface: None,
+            has_permission: false,
+            reason: if driver_present {
+                "Windows 版本暂未启用原始套接字扫描，将使用基础扫描".to_string()
+            } else {
+                capability_reason(driver_present, privilege_level, None, false)
+            },
+        }
+    }
+
+    /// Windows 上不可用（[`smart_scan`] 会回退到基础扫描）
+    pub fn arp_scan_on(_subnet: &str, _options: &ArpScanOptions) -> AppResult<ArpScan> {
+        Err(AppError::command_unavailable(
+            "Npcap",
+            "高级 ARP 扫描",
+            "Windows 版本暂未启用原始套接字扫描",
+        ))
+    }
+}
+
+// ============================================================
+// Public API (platform-agnostic)
+// ============================================================
+
+#[cfg(not(target_os = "windows"))]
+pub(crate) use platform::capture_interface;
+
+/// 检查是否有 raw socket 权限
+pub fn check_raw_socket_permission() -> bool {
+    platform::check_raw_socket_capability().has_permission
+}
+
+/// 获取原始套接字能力报告（驱动、权限级别、接口与原因）
+pub fn check_raw_socket_capability() -> CapabilityReport {
+    platform::check_raw_socket_capability()
+}
+
+/// 智能扫描：优先使用高级扫描，失败时回退到基础扫描
+#[tracing::instrument(level = "info")]
+pub fn smart_scan(subnet: &str) -> AdvancedScanResult {
+    let start = Instant::now();
+
+    // 尝试高级 ARP 扫描（演示模式下直接使用基础扫描返回的示例设备）
+    let advanced = if crate::demo::is_enabled() {
+        None
+    } else {
+        platform::arp_scan_on(subnet, &ArpScanOptions::default()).ok()
+    };
+    if let Some(scan) = advanced {
+        tracing::info!(devices = scan.devices.len(), "高级 ARP 扫描完成");
+        return advanced_result(scan, start);
+    }
+
+    // 回退到基础扫描
+    tracing::info!("高级 ARP 扫描不可用，回退到基础扫描");
+    let devices = discover_devices(subnet);
+
+    AdvancedScanResult {
+        devices,
+        scan_method: "Ping/ARP (基础)".to_string(),
+        scan_time_ms: start.elapsed().as_millis() as u64,
+        has_permission: false,
+        interface: None,
+        vlan: None,
+    }
+}
+
+/// 在指定接口 / VLAN 上扫描。选项为空时与 [`smart_scan`] 相同；指定了接口或 VLAN 时
+/// 基础扫描无法满足，高级扫描不可用直接返回错误
+#[tracing::instrument(level = "info")]
+pub fn smart_scan_with(subnet: &str, options: &ArpScanOptions) -> AppResult<AdvancedScanResult> {
+    if options.vlan.is_some_and(|vlan| !(1..=4094).contains(&vlan)) {
+        return Err(AppError::validation("VLAN", "需要 1-4094 的 VLAN ID"));
+    }
+    if subnet_host_addresses(subnet, crate::network::MAX_DISCOVER_HOSTS).is_none() {
+        return Err(AppError::validation(
+            "子网",
+            "需要 /22 到 /24 的 IPv4 网段，例如 192.168.1.0/24",
+        ));
+    }
+    if options.is_default() || crate::demo::is_enabled() {
+        return Ok(smart_scan(subnet));
+    }
+    let start = Instant::now();
+    let scan = platform::arp_scan_on(subnet, options)?;
+    tracing::info!(
+        devices = scan.devices.len(),
+        interface = %scan.interface,
+        tag = ?scan.tag,
+        "VLAN ARP 扫描完成"
+    );
+    Ok(advanced_result(scan, start))
+}
+
+fn advanced_result(scan: ArpScan, start: Instant) -> AdvancedScanResult {
+    let scan_method = match scan.tag {
+        Some(tag) => format!("ARP (高级, 802.1Q VLAN {})", tag),
+        None => "ARP (高级)".to_string(),
+    };
+    let mut devices = scan.devices;
+    crate::inventory::annotate_devices(&mut devices);
+    AdvancedScanResult {
+        devices,
+        scan_method,
+        scan_time_ms: start.elapsed().as_millis() as u64,
+        has_permission: true,
+        interface: Some(scan.interface),
+        vlan: scan.tag,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capability_reason_missing_driver() {
+        let reason = capability_reason(false, PrivilegeLevel::Elevated, Some("en0"), false);
+        assert!(reason.contains("Npcap") || reason.contains("抓包设备"));
+    }
+
+    #[test]
+    fn test_capability_reason_insufficient_privilege() {
+        let reason = capability_reason(true, PrivilegeLevel::Standard, Some("en0"), false);
+        assert!(reason.contains("权限不足"));
+    }
+
+    #[test]
+    fn test_capability_reason_no_interface() {
+        let reason = capability_reason(true, PrivilegeLevel::Elevated, None, false);
+        assert!(reason.contains("网络接口"));
+    }
+
+    #[test]
+    fn test_capability_reason_ok_mentions_interface() {
+        let reason = capability_reason(true, PrivilegeLevel::Elevated, Some("eth0"), true);
+        assert!(reason.contains("eth0"));
+    }
+
+    #[test]
+    fn test_check_raw_socket_capability_consistent() {
+        let report = check_raw_socket_capability();
+        assert!(!report.reason.is_empty());
+        if report.has_permission {
+            assert!(report.driver_present);
+            assert!(report.interface.is_some());
+        }
+        assert_eq!(report.has_permission, check_raw_socket_permission());
+    }
+
+    #[test]
+    fn test_vlan_subinterfaces_and_plan() {
+        let proc = "VLAN Dev name	 | VLAN ID\nName-Type: VLAN_NAME_TYPE_RAW_PLUS_VID_NO_PAD\n\
+                    eth0.20        | 20  | eth0\nlab30          | 30  | eth1\n";
+        let linux = parse_proc_vlan_config(proc);
+        assert_eq!(
+            linux,
+            vec![
+                VlanSubinterface {
+                    name: "eth0.20".to_string(),
+                    vlan: 20,
+                    parent: "eth0".to_string(),
+                },
+                VlanSubinterface {
+                    name: "lab30".to_string(),
+                    vlan: 30,
+                    parent: "eth1".to_string(),
+                },
+            ]
+        );
+
+        let ifconfig = "en0: flags=8863<UP,BROADCAST> mtu 1500\n\tether 3c:22:fb:00:00:01\n\
+                        vlan0: flags=8843<UP,BROADCAST,RUNNING> mtu 1500\n\
+                        \tvlan: 40 parent interface: en0\n\
+                        vlan1: flags=8842<BROADCAST> mtu 1500\n\tvlan: 0 parent interface: <none>\n";
+        let macos = parse_ifconfig_vlans(ifconfig);
+        assert_eq!(macos.len(), 1);
+        assert_eq!(macos[0].name, "vlan0");
+        assert_eq!(macos[0].vlan, 40);
+        assert_eq!(macos[0].parent, "en0");
+
+        // 有子接口时使用子接口，否则在父接口上打标签
+        assert_eq!(
+            plan_vlan_interface("eth0", Some(20), &linux),
+            ("eth0.20".to_string(), None)
+        );
+        assert_eq!(
+            plan_vlan_interface("eth0.20", Some(20), &linux),
+            ("eth0.20".to_string(), None)
+        );
+        assert_eq!(
+            plan_vlan_interface("eth0", Some(30), &linux),
+            ("eth0".to_string(), Some(30))
+        );
+        assert_eq!(
+            plan_vlan_interface("eth1", None, &linux),
+            ("eth1".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_smart_scan_with_rejects_invalid_vlan() {
+        let options = ArpScanOptions {
+            interface: None,
+            vlan: Some(4095),
+        };
+        let err = smart_scan_with("10.0.0.0/24", &options).unwrap_err();
+        assert_eq!(err.kind(), crate::app_error::PortlyErrorKind::Validation);
+    }
+
+    #[test]
+    fn test_privilege_level_serialization() {
+        let text = serde_json::to_string(&PrivilegeLevel::Elevated).unwrap();
+        assert_eq!(text, "\"elevated\"");
+    }
+}
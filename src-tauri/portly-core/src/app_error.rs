@@ -0,0 +1,421 @@
+use crate::i18n::{current_lang, term, tr_args, Lang, Msg};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+/// 旧名称，保留给尚未迁移的调用方
+pub type AppError = PortlyError;
+
+pub type AppResult<T> = Result<T, PortlyError>;
+
+/// 前端用于区分错误类别的稳定标识
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortlyErrorKind {
+    Validation,
+    ToolMissing,
+    PermissionDenied,
+    Timeout,
+    HostUnreachable,
+    CommandFailed,
+    Cancelled,
+    Internal,
+}
+
+/// 错误消息按 [`crate::i18n::current_lang`] 生成，见 [`PortlyError::message`]
+#[derive(Debug, Clone)]
+pub enum PortlyError {
+    Validation {
+        field: &'static str,
+        message: String,
+    },
+    CommandUnavailable {
+        command: String,
+        context: String,
+        details: String,
+    },
+    CommandExecutionFailed {
+        command: String,
+        context: String,
+        details: String,
+    },
+    CommandPermissionDenied {
+        command: String,
+        context: String,
+        details: String,
+    },
+    CommandTimeout {
+        command: String,
+        context: String,
+        details: String,
+    },
+    CommandFailed {
+        command: String,
+        context: String,
+        exit_code: i32,
+        stderr: String,
+    },
+    HostUnreachable {
+        host: String,
+        details: String,
+    },
+    Cancelled {
+        task: String,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+impl PortlyError {
+    pub fn validation(field: &'static str, message: impl Into<String>) -> Self {
+        Self::Validation {
+            field,
+            message: message.into(),
+        }
+    }
+
+    pub fn command_unavailable(
+        command: &str,
+        context: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        Self::CommandUnavailable {
+            command: command.to_string(),
+            context: context.into(),
+            details: details.into(),
+        }
+    }
+
+    pub fn command_execution_failed(
+        command: &str,
+        context: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        Self::CommandExecutionFailed {
+            command: command.to_string(),
+            context: context.into(),
+            details: details.into(),
+        }
+    }
+
+    pub fn command_permission_denied(
+        command: &str,
+        context: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        Self::CommandPermissionDenied {
+            command: command.to_string(),
+            context: context.into(),
+            details: details.into(),
+        }
+    }
+
+    pub fn command_timeout(
+        command: &str,
+        context: impl Into<String>,
+        details: impl Into<String>,
+    ) -> Self {
+        Self::CommandTimeout {
+            command: command.to_string(),
+            context: context.into(),
+            details: details.into(),
+        }
+    }
+
+    pub fn command_failed(
+        command: &str,
+        context: impl Into<String>,
+        exit_code: i32,
+        stderr: impl Into<String>,
+    ) -> Self {
+        let stderr = stderr.into();
+        let hint = stderr.to_ascii_lowercase();
+        if hint.contains("permission denied") || hint.contains("access is denied") {
+            Self::command_permission_denied(command, context, stderr)
+        } else if hint.contains("timed out") || hint.contains("timeout") {
+            Self::command_timeout(command, context, stderr)
+        } else {
+            Self::CommandFailed {
+                command: command.to_string(),
+                context: context.into(),
+                exit_code,
+                stderr,
+            }
+        }
+    }
+
+    pub fn host_unreachable(host: &str, details: impl Into<String>) -> Self {
+        Self::HostUnreachable {
+            host: host.to_string(),
+            details: details.into(),
+        }
+    }
+
+    pub fn cancelled(task: impl Into<String>) -> Self {
+        Self::Cancelled { task: task.into() }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal {
+            message: message.into(),
+        }
+    }
+
+    /// 按指定语言生成错误消息；调用方传入的中文字段名、场景名按术语表翻译
+    pub fn message(&self, lang: Lang) -> String {
+        match self {
+            Self::Validation { field, message } => tr_args(
+                lang,
+                Msg::ErrValidation,
+                &[&term(lang, field), &term(lang, message)],
+            ),
+            Self::CommandUnavailable {
+                command,
+                context,
+                details,
+            } => command_message(lang, Msg::ErrCommandUnavailable, command, context, details),
+            Self::CommandExecutionFailed {
+                command,
+                context,
+                details,
+            } => command_message(
+                lang,
+                Msg::ErrCommandExecutionFailed,
+                command,
+                context,
+                details,
+            ),
+            Self::CommandPermissionDenied {
+                command,
+                context,
+                details,
+            } => command_message(
+                lang,
+                Msg::ErrCommandPermissionDenied,
+                command,
+                context,
+                details,
+            ),
+            Self::CommandTimeout {
+                command,
+                context,
+                details,
+            } => command_message(lang, Msg::ErrCommandTimeout, command, context, details),
+            Self::CommandFailed {
+                command,
+                context,
+                exit_code,
+                stderr,
+            } if stderr.is_empty() => tr_args(
+                lang,
+                Msg::ErrCommandExitCode,
+                &[command, &term(lang, context), exit_code],
+            ),
+            Self::CommandFailed {
+                command,
+                context,
+                exit_code,
+                stderr,
+            } => tr_args(
+                lang,
+                Msg::ErrCommandExitStderr,
+                &[command, &term(lang, context), exit_code, stderr],
+            ),
+            Self::HostUnreachable { host, details } => {
+                tr_args(lang, Msg::ErrHostUnreachable, &[host, &term(lang, details)])
+            }
+            Self::Cancelled { task } => tr_args(lang, Msg::ErrCancelled, &[&term(lang, task)]),
+            Self::Internal { message } => tr_args(lang, Msg::ErrInternal, &[message]),
+        }
+    }
+
+    pub fn as_tauri_message(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn kind(&self) -> PortlyErrorKind {
+        match self {
+            Self::Validation { .. } => PortlyErrorKind::Validation,
+            Self::CommandUnavailable { .. } => PortlyErrorKind::ToolMissing,
+            Self::CommandPermissionDenied { .. } => PortlyErrorKind::PermissionDenied,
+            Self::CommandTimeout { .. } => PortlyErrorKind::Timeout,
+            Self::HostUnreachable { .. } => PortlyErrorKind::HostUnreachable,
+            Self::CommandExecutionFailed { .. } | Self::CommandFailed { .. } => {
+                PortlyErrorKind::CommandFailed
+            }
+            Self::Cancelled { .. } => PortlyErrorKind::Cancelled,
+            Self::Internal { .. } => PortlyErrorKind::Internal,
+        }
+    }
+
+    /// 出错的外部命令（校验/内部错误没有）
+    pub fn command(&self) -> Option<&str> {
+        match self {
+            Self::CommandUnavailable { command, .. }
+            | Self::CommandExecutionFailed { command, .. }
+            | Self::CommandPermissionDenied { command, .. }
+            | Self::CommandTimeout { command, .. }
+            | Self::CommandFailed { command, .. } => Some(command),
+            _ => None,
+        }
+    }
+}
+
+fn command_message(lang: Lang, msg: Msg, command: &str, context: &str, details: &str) -> String {
+    tr_args(
+        lang,
+        msg,
+        &[&command, &term(lang, context), &term(lang, details)],
+    )
+}
+
+impl fmt::Display for PortlyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(current_lang()))
+    }
+}
+
+impl std::error::Error for PortlyError {}
+
+impl From<String> for PortlyError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+/// 序列化为 `{ kind, message, command? }`，前端按 `kind` 区分错误类别
+impl Serialize for PortlyError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let command = self.command();
+        let mut state =
+            serializer.serialize_struct("PortlyError", if command.is_some() { 3 } else { 2 })?;
+        state.serialize_field("kind", &self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some(command) = command {
+            state.serialize_field("command", command)?;
+        }
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_display() {
+        let err = AppError::validation("目标地址", "不能为空");
+        assert_eq!(err.to_string(), "目标地址 校验失败：不能为空");
+    }
+
+    #[test]
+    fn test_command_unavailable_display() {
+        let err = AppError::command_unavailable("ping", "Ping 测试", "未找到可执行文件");
+        let text = err.to_string();
+        assert!(text.contains("ping"));
+        assert!(text.contains("Ping 测试"));
+        assert!(text.contains("未找到可执行文件"));
+    }
+
+    #[test]
+    fn test_command_failed_may_be_permission_denied() {
+        let err = AppError::command_failed("ping", "Ping 测试", 1, "Permission denied");
+        assert!(matches!(err, AppError::CommandPermissionDenied { .. }));
+    }
+
+    #[test]
+    fn test_command_failed_may_be_timeout() {
+        let err = AppError::command_failed("ping", "Ping 测试", 1, "Operation timed out");
+        assert!(matches!(err, AppError::CommandTimeout { .. }));
+    }
+
+    #[test]
+    fn test_command_failed_default_keeps_exit_code() {
+        let err = AppError::command_failed("ping", "Ping 测试", 2, "some output");
+        match err {
+            AppError::CommandFailed {
+                exit_code,
+                command,
+                context,
+                stderr,
+            } => {
+                assert_eq!(exit_code, 2);
+                assert_eq!(command, "ping");
+                assert_eq!(context, "Ping 测试");
+                assert_eq!(stderr, "some output");
+            }
+            _ => panic!("expect CommandFailed variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_timeout_constructor() {
+        let err = AppError::command_timeout("ping", "Ping 测试", "timed out");
+        match err {
+            AppError::CommandTimeout {
+                command,
+                context,
+                details,
+            } => {
+                assert_eq!(command, "ping");
+                assert_eq!(context, "Ping 测试");
+                assert_eq!(details, "timed out");
+            }
+            _ => panic!("expect CommandTimeout variant"),
+        }
+    }
+
+    #[test]
+    fn test_internal_error_to_string() {
+        let err = AppError::internal("内部异常");
+        assert_eq!(err.to_string(), "内部错误：内部异常");
+    }
+
+    #[test]
+    fn test_as_tauri_message_alias() {
+        let err = AppError::validation("目标地址", "不能为空");
+        assert_eq!(err.as_tauri_message(), "目标地址 校验失败：不能为空");
+    }
+
+    #[test]
+    fn test_kind_distinguishes_failure_causes() {
+        let missing = AppError::command_unavailable("docker", "容器列表", "not found");
+        let denied = AppError::command_failed("lsof", "端口扫描", 1, "Permission denied");
+        let unreachable = PortlyError::host_unreachable("10.0.0.1", "No route to host");
+        assert_eq!(missing.kind(), PortlyErrorKind::ToolMissing);
+        assert_eq!(denied.kind(), PortlyErrorKind::PermissionDenied);
+        assert_eq!(unreachable.kind(), PortlyErrorKind::HostUnreachable);
+        assert_eq!(unreachable.to_string(), "10.0.0.1 不可达：No route to host");
+    }
+
+    #[test]
+    fn test_serialize_includes_kind_and_message() {
+        let err = AppError::command_unavailable("docker", "容器列表", "not found");
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["kind"], "tool_missing");
+        assert_eq!(value["command"], "docker");
+        assert_eq!(value["message"], err.to_string());
+
+        let value = serde_json::to_value(AppError::validation("端口", "必须大于 0")).unwrap();
+        assert_eq!(value["kind"], "validation");
+        assert!(value.get("command").is_none());
+    }
+
+    #[test]
+    fn test_message_in_english() {
+        let err = AppError::validation("目标地址", "不能为空");
+        assert_eq!(err.message(Lang::En), "Invalid target: must not be empty");
+        assert_eq!(err.message(Lang::Zh), "目标地址 校验失败：不能为空");
+
+        let err = AppError::command_failed("lsof", "端口扫描", 2, "");
+        assert_eq!(
+            err.message(Lang::En),
+            "lsof failed during port scan with exit status 2"
+        );
+        let err = PortlyError::host_unreachable("10.0.0.1", "No route to host");
+        assert_eq!(
+            err.message(Lang::En),
+            "10.0.0.1 is unreachable: No route to host"
+        );
+    }
+}
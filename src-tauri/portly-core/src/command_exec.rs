@@ -1,7 +1,9 @@
 use crate::app_error::{AppError, AppResult};
+use std::collections::HashMap;
 use std::io::ErrorKind;
-use std::io::Read;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::process::{Command, Stdio};
+use std::sync::Mutex;
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -36,8 +38,9 @@ where
     let mut command_builder = Command::new(command);
     configure(&mut command_builder);
 
+    let start = Instant::now();
     let output = command_builder.output().map_err(|err| {
-        if err.kind() == ErrorKind::NotFound {
+        let err = if err.kind() == ErrorKind::NotFound {
             AppError::command_unavailable(command, context, err.to_string())
         } else if err.kind() == ErrorKind::PermissionDenied {
             AppError::command_permission_denied(command, context, err.to_string())
@@ -45,14 +48,35 @@ where
             AppError::command_timeout(command, context, err.to_string())
         } else {
             AppError::command_execution_failed(command, context, err.to_string())
-        }
+        };
+        tracing::warn!(command, context, error = %err, "外部命令启动失败");
+        err
     })?;
 
-    Ok(CommandOutput {
+    let output = CommandOutput {
         status: output.status.code().unwrap_or(-1),
         stdout: String::from_utf8_lossy(&output.stdout).to_string(),
         stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-    })
+    };
+    log_command_output(command, context, &output, start);
+    Ok(output)
+}
+
+/// 记录外部命令结果；非零退出码附带 stderr 便于排查“扫描结果为空”
+fn log_command_output(command: &str, context: &str, output: &CommandOutput, start: Instant) {
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    if output.status == 0 {
+        tracing::debug!(command, context, elapsed_ms, "外部命令执行完成");
+    } else {
+        tracing::info!(
+            command,
+            context,
+            status = output.status,
+            elapsed_ms,
+            stderr = output.stderr.trim(),
+            "外部命令返回非零状态码"
+        );
+    }
 }
 
 pub fn run_command_required<F, T>(
@@ -83,11 +107,41 @@ pub fn run_command_with_timeout<F, T>(
     configure: F,
     timeout: Duration,
 ) -> AppResult<CommandOutput>
+where
+    F: FnOnce(&mut Command) -> T,
+{
+    run_with_timeout(command, context, configure, None, timeout)
+}
+
+/// 执行外部命令并把 `input` 写入 stdin，超时后终止子进程；用于事件脚本等从 stdin 读取数据的命令
+pub fn run_command_with_input<F, T>(
+    command: &str,
+    context: &str,
+    configure: F,
+    input: &str,
+    timeout: Duration,
+) -> AppResult<CommandOutput>
+where
+    F: FnOnce(&mut Command) -> T,
+{
+    run_with_timeout(command, context, configure, Some(input), timeout)
+}
+
+fn run_with_timeout<F, T>(
+    command: &str,
+    context: &str,
+    configure: F,
+    input: Option<&str>,
+    timeout: Duration,
+) -> AppResult<CommandOutput>
 where
     F: FnOnce(&mut Command) -> T,
 {
     let mut command_builder = Command::new(command);
     configure(&mut command_builder);
+    if input.is_some() {
+        command_builder.stdin(Stdio::piped());
+    }
     let mut child = command_builder
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -102,6 +156,14 @@ where
             }
         })?;
 
+    // 在单独线程写入 stdin，避免子进程输出填满管道时互相等待；写完后关闭 stdin
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        let input = input.to_string();
+        thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+
     let start = Instant::now();
     loop {
         match child.try_wait().map_err(|err| {
@@ -120,16 +182,19 @@ where
 
                 let _ = child.wait();
 
-                return Ok(CommandOutput {
+                let output = CommandOutput {
                     status: status.code().unwrap_or(-1),
                     stdout,
                     stderr,
-                });
+                };
+                log_command_output(command, context, &output, start);
+                return Ok(output);
             }
             None => {
                 if start.elapsed() >= timeout {
                     let _ = child.kill();
                     let _ = child.wait();
+                    tracing::warn!(command, context, ?timeout, "外部命令执行超时，已终止");
                     return Err(AppError::command_timeout(
                         command,
                         context,
@@ -142,6 +207,207 @@ where
     }
 }
 
+/// 执行外部命令并逐行回调 stdout，用于 ping 等持续输出的命令；
+/// 回调返回错误（如任务被取消）时终止子进程并返回该错误
+pub fn run_command_streaming<F, T, L>(
+    command: &str,
+    context: &str,
+    configure: F,
+    mut on_line: L,
+) -> AppResult<CommandOutput>
+where
+    F: FnOnce(&mut Command) -> T,
+    L: FnMut(&str) -> AppResult<()>,
+{
+    let mut command_builder = Command::new(command);
+    configure(&mut command_builder);
+    let mut child = command_builder
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| {
+            if err.kind() == ErrorKind::NotFound {
+                AppError::command_unavailable(command, context, err.to_string())
+            } else if err.kind() == ErrorKind::PermissionDenied {
+                AppError::command_permission_denied(command, context, err.to_string())
+            } else {
+                AppError::command_execution_failed(command, context, err.to_string())
+            }
+        })?;
+
+    let start = Instant::now();
+    // stderr 在单独线程读取，避免缓冲区写满阻塞子进程
+    let stderr_reader = child.stderr.take().map(|mut err| {
+        thread::spawn(move || {
+            let mut stderr = String::new();
+            err.read_to_string(&mut stderr).unwrap_or_default();
+            stderr
+        })
+    });
+
+    let mut stdout = String::new();
+    if let Some(out) = child.stdout.take() {
+        for line in BufReader::new(out).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Err(err) = on_line(&line) {
+                let _ = child.kill();
+                let _ = child.wait();
+                tracing::debug!(command, context, error = %err, "外部命令被提前终止");
+                return Err(err);
+            }
+            stdout.push_str(&line);
+            stdout.push('\n');
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|err| AppError::command_execution_failed(command, context, err.to_string()))?;
+    let output = CommandOutput {
+        status: status.code().unwrap_or(-1),
+        stdout,
+        stderr: stderr_reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default(),
+    };
+    log_command_output(command, context, &output, start);
+    Ok(output)
+}
+
+/// 外部命令及其输出格式所属的平台
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    MacOs,
+    Linux,
+    Windows,
+}
+
+impl Platform {
+    /// 当前编译目标的平台；macOS / Windows 以外按 Linux 处理
+    pub fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::MacOs
+        } else {
+            Platform::Linux
+        }
+    }
+}
+
+/// Windows 下不为子进程弹出控制台窗口，其他平台不做处理
+pub fn hide_console_window(cmd: &mut Command) {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW
+        cmd.creation_flags(0x08000000);
+    }
+    #[cfg(not(target_os = "windows"))]
+    let _ = cmd;
+}
+
+/// 执行外部命令的方式
+///
+/// 解析 lsof / ss / netstat / arp / docker 等输出的逻辑通过它执行命令，
+/// 生产环境使用 [`SystemRunner`]，测试使用 [`FixtureRunner`] 回放录制的输出
+pub trait CommandRunner: Send + Sync {
+    fn run(
+        &self,
+        command: &str,
+        context: &str,
+        configure: &mut dyn FnMut(&mut Command),
+    ) -> AppResult<CommandOutput>;
+}
+
+/// 直接执行系统命令，等同于 [`run_command`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(
+        &self,
+        command: &str,
+        context: &str,
+        configure: &mut dyn FnMut(&mut Command),
+    ) -> AppResult<CommandOutput> {
+        run_command(command, context, configure)
+    }
+}
+
+/// 回放录制输出的命令执行器
+///
+/// 按完整命令行（程序名和参数以空格连接，如 `lsof -i -P -n`）匹配，
+/// 没有录制的命令视为未安装，便于覆盖回退路径
+#[derive(Debug, Default)]
+pub struct FixtureRunner {
+    outputs: HashMap<String, CommandOutput>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl FixtureRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 录制一条成功执行的命令
+    pub fn with_stdout(self, command_line: &str, stdout: &str) -> Self {
+        self.with_output(command_line, 0, stdout, "")
+    }
+
+    /// 录制一条命令的退出码和输出
+    pub fn with_output(
+        mut self,
+        command_line: &str,
+        status: i32,
+        stdout: &str,
+        stderr: &str,
+    ) -> Self {
+        self.outputs.insert(
+            command_line.to_string(),
+            CommandOutput {
+                status,
+                stdout: stdout.to_string(),
+                stderr: stderr.to_string(),
+            },
+        );
+        self
+    }
+
+    /// 已执行的命令行，按调用顺序
+    pub fn calls(&self) -> Vec<String> {
+        self.calls.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+}
+
+impl CommandRunner for FixtureRunner {
+    fn run(
+        &self,
+        command: &str,
+        context: &str,
+        configure: &mut dyn FnMut(&mut Command),
+    ) -> AppResult<CommandOutput> {
+        let mut builder = Command::new(command);
+        configure(&mut builder);
+        let command_line = std::iter::once(command.to_string())
+            .chain(
+                builder
+                    .get_args()
+                    .map(|arg| arg.to_string_lossy().to_string()),
+            )
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(command_line.clone());
+        }
+        self.outputs.get(&command_line).cloned().ok_or_else(|| {
+            AppError::command_unavailable(command, context, format!("未录制: {command_line}"))
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -149,6 +415,30 @@ mod tests {
     #[cfg(unix)]
     use std::os::unix::fs::PermissionsExt;
 
+    #[test]
+    fn test_fixture_runner_replays_recorded_output() {
+        let runner = FixtureRunner::new()
+            .with_stdout("lsof -i -P -n", "COMMAND PID\n")
+            .with_output("ss -tlnp", 1, "", "Permission denied");
+
+        let output = runner
+            .run("lsof", "测试", &mut |cmd| {
+                cmd.args(["-i", "-P", "-n"]);
+            })
+            .unwrap();
+        assert_eq!(output.stdout, "COMMAND PID\n");
+        let output = runner
+            .run("ss", "测试", &mut |cmd| {
+                cmd.arg("-tlnp");
+            })
+            .unwrap();
+        assert_eq!(output.status, 1);
+
+        let err = runner.run("netstat", "测试", &mut |_| {}).unwrap_err();
+        assert_eq!(err.kind(), crate::PortlyErrorKind::ToolMissing);
+        assert_eq!(runner.calls(), vec!["lsof -i -P -n", "ss -tlnp", "netstat"]);
+    }
+
     #[test]
     fn test_ensure_command_available_not_found() {
         let err = ensure_command_available("definitely_not_exists_cmd_12345").unwrap_err();
@@ -333,6 +623,55 @@ mod tests {
         assert_eq!(output.stdout, "hello");
     }
 
+    #[cfg(not(windows))]
+    #[test]
+    fn test_run_command_with_input_writes_stdin() {
+        let output = run_command_with_input(
+            "sh",
+            "stdin 测试",
+            |cmd| {
+                cmd.args(["-c", "tr a-z A-Z"]);
+            },
+            "hello\n",
+            Duration::from_secs(5),
+        )
+        .unwrap();
+        assert_eq!(output.status, 0);
+        assert_eq!(output.stdout, "HELLO\n");
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_run_command_streaming_lines_and_stop() {
+        let mut lines = Vec::new();
+        let output = run_command_streaming(
+            "sh",
+            "流式输出测试",
+            |cmd| {
+                cmd.args(["-c", "echo one; echo two >&2; echo three"]);
+            },
+            |line| {
+                lines.push(line.to_string());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(lines, ["one", "three"]);
+        assert_eq!(output.stdout, "one\nthree\n");
+        assert_eq!(output.stderr.trim(), "two");
+
+        let err = run_command_streaming(
+            "sh",
+            "流式输出测试",
+            |cmd| {
+                cmd.args(["-c", "echo one; sleep 5; echo two"]);
+            },
+            |_| Err(AppError::cancelled("测试")),
+        )
+        .unwrap_err();
+        assert!(matches!(err, AppError::Cancelled { .. }));
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_run_command_stdout() {
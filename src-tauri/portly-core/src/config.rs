@@ -0,0 +1,253 @@
+//! 共享配置文件模块
+//!
+//! CLI 与桌面端共用 `~/.config/portly/config.toml`（Windows 为 `%APPDATA%\portly\config.toml`），
+//! 可通过环境变量 `PORTLY_CONFIG` 指定其他路径。文件不存在时使用默认值
+
+use crate::app_error::{AppError, AppResult};
+use crate::policy::PortPolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// 指定配置文件路径的环境变量
+pub const CONFIG_PATH_ENV: &str = "PORTLY_CONFIG";
+
+/// Portly 配置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortlyConfig {
+    pub defaults: DefaultOptions,
+    pub exclude: ExcludeOptions,
+    pub timeouts: TimeoutOptions,
+    /// 命名端口组合，如 `web = "80,443,8000-8100"`，可用于 `scan --ports web`
+    pub port_profiles: BTreeMap<String, String>,
+    /// 端口策略，见 [`crate::policy`]
+    pub policies: Vec<PortPolicy>,
+}
+
+/// 默认参数（相当于每次都传入的命令行选项）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultOptions {
+    pub show_command: bool,
+    pub exclude_system: bool,
+    /// 列表中同时显示 UDP 套接字
+    pub udp: bool,
+    /// 同一进程在同一端口上的 IPv4 / IPv6 监听合并为一行
+    pub dual_stack: bool,
+    pub group: bool,
+    /// table / porcelain / json / json-v1 / csv / ndjson
+    pub output: Option<String>,
+    /// CLI 语言: zh / en，未设置时按 LANG 检测
+    pub lang: Option<String>,
+}
+
+/// 排除列表
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExcludeOptions {
+    /// 进程名（不区分大小写，完全匹配）
+    pub processes: Vec<String>,
+    pub ports: Vec<u16>,
+}
+
+/// 超时设置（毫秒）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutOptions {
+    /// 远程端口扫描的连接超时
+    pub scan_ms: u64,
+}
+
+impl Default for TimeoutOptions {
+    fn default() -> Self {
+        Self { scan_ms: 500 }
+    }
+}
+
+impl ExcludeOptions {
+    /// 判断端口是否被排除
+    pub fn is_excluded(&self, process: &str, port: u16) -> bool {
+        self.ports.contains(&port)
+            || self
+                .processes
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(process))
+    }
+}
+
+/// 配置文件路径
+pub fn config_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(CONFIG_PATH_ENV).filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
+
+    #[cfg(target_os = "windows")]
+    let base = dirs::config_dir();
+    #[cfg(not(target_os = "windows"))]
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")));
+
+    base.unwrap_or_else(|| PathBuf::from("."))
+        .join("portly")
+        .join("config.toml")
+}
+
+/// 读取配置；文件不存在时返回默认配置
+pub fn load_config() -> AppResult<PortlyConfig> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(PortlyConfig::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::internal(format!("读取配置文件失败: {}", e)))?;
+    parse_config(&content)
+}
+
+/// 解析 TOML 配置
+pub fn parse_config(content: &str) -> AppResult<PortlyConfig> {
+    let config: PortlyConfig = toml::from_str(content)
+        .map_err(|e| AppError::validation("配置文件", e.message().to_string()))?;
+    for policy in &config.policies {
+        policy.validate()?;
+    }
+    Ok(config)
+}
+
+/// 保存配置
+pub fn save_config(config: &PortlyConfig) -> AppResult<()> {
+    let path = config_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建配置目录失败: {}", e)))?;
+    }
+    let content = render_config(config)?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入配置文件失败: {}", e)))
+}
+
+/// 以 TOML 文本形式输出配置
+pub fn render_config(config: &PortlyConfig) -> AppResult<String> {
+    toml::to_string_pretty(config).map_err(|e| AppError::internal(format!("序列化配置失败: {}", e)))
+}
+
+/// 按点分隔的键读取配置值，如 `timeouts.scan_ms`
+pub fn get_config_value(config: &PortlyConfig, key: &str) -> Option<String> {
+    let value = toml::Value::try_from(config).ok()?;
+    let found = key
+        .split('.')
+        .try_fold(&value, |current, part| current.get(part))?;
+    Some(match found {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// 按点分隔的键修改配置值；值按 TOML 语法解析，无法解析时视为字符串
+pub fn set_config_value(config: &PortlyConfig, key: &str, raw: &str) -> AppResult<PortlyConfig> {
+    let mut root = toml::Value::try_from(config)
+        .map_err(|e| AppError::internal(format!("序列化配置失败: {}", e)))?;
+
+    let parts: Vec<&str> = key.split('.').filter(|p| !p.is_empty()).collect();
+    let Some((last, parents)) = parts.split_last() else {
+        return Err(AppError::validation("配置项", "键不能为空"));
+    };
+
+    let mut table = root
+        .as_table_mut()
+        .ok_or_else(|| AppError::internal("配置根节点不是表"))?;
+    for part in parents {
+        table = table
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(Default::default()))
+            .as_table_mut()
+            .ok_or_else(|| AppError::validation("配置项", format!("{} 不是配置分组", part)))?;
+    }
+    table.insert(last.to_string(), parse_toml_value(raw));
+
+    let updated: PortlyConfig = root
+        .try_into()
+        .map_err(|e: toml::de::Error| AppError::validation("配置项", e.message().to_string()))?;
+    // 未知键会被 serde 忽略，这里要求写入后能读回同一个值
+    if get_config_value(&updated, key).is_none() {
+        return Err(AppError::validation(
+            "配置项",
+            format!("未知的配置项: {}", key),
+        ));
+    }
+    Ok(updated)
+}
+
+fn parse_toml_value(raw: &str) -> toml::Value {
+    format!("value = {}", raw)
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_with_defaults() {
+        let config = parse_config(
+            r#"
+[defaults]
+exclude_system = true
+
+[exclude]
+processes = ["Dropbox"]
+
+[port_profiles]
+web = "80,443"
+
+[[policies]]
+name = "Postgres on all interfaces"
+service = "postgres"
+scope = "wildcard"
+severity = "high"
+"#,
+        )
+        .unwrap();
+
+        assert!(config.defaults.exclude_system);
+        assert!(!config.defaults.show_command);
+        assert_eq!(config.timeouts.scan_ms, 500);
+        assert!(config.exclude.is_excluded("dropbox", 17500));
+        assert_eq!(config.port_profiles["web"], "80,443");
+        assert_eq!(
+            config.policies[0].scope,
+            crate::policy::PolicyScope::Wildcard
+        );
+        assert!(parse_config("[timeouts]\nscan_ms = \"fast\"").is_err());
+        assert!(parse_config("[[policies]]\nname = \"x\"\nseverity = \"high\"").is_err());
+    }
+
+    #[test]
+    fn test_get_and_set_config_value() {
+        let config = PortlyConfig::default();
+        assert_eq!(
+            get_config_value(&config, "timeouts.scan_ms").as_deref(),
+            Some("500")
+        );
+
+        let config = set_config_value(&config, "timeouts.scan_ms", "1500").unwrap();
+        assert_eq!(config.timeouts.scan_ms, 1500);
+
+        let config = set_config_value(&config, "exclude.ports", "[22, 631]").unwrap();
+        assert_eq!(config.exclude.ports, vec![22, 631]);
+
+        let config = set_config_value(&config, "port_profiles.db", "5432,3306").unwrap();
+        assert_eq!(config.port_profiles["db"], "5432,3306");
+
+        let config = set_config_value(&config, "defaults.output", "csv").unwrap();
+        assert_eq!(config.defaults.output.as_deref(), Some("csv"));
+
+        assert!(set_config_value(&config, "timeouts.scan_ms", "soon").is_err());
+        assert!(set_config_value(&config, "defaults.colour", "true").is_err());
+    }
+}
@@ -0,0 +1,1686 @@
+//! Portly 核心库 - 跨平台端口扫描器
+//!
+//! 支持 macOS, Linux, Windows
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::{
+    hide_console_window, run_command, CommandOutput, CommandRunner, Platform, SystemRunner,
+};
+
+/// 端口信息结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortInfo {
+    pub port: u16,
+    /// TCP 监听端口为 `IPv4` / `IPv6`，UDP 套接字为 `UDP` / `UDP6`
+    pub protocol: String,
+    pub address: String,
+    pub pid: String,
+    pub process: String,
+    pub user: String,
+    pub command: Option<String>,
+}
+
+/// 扫描结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub scan_time: String,
+    pub total_ports: usize,
+    pub unique_apps: usize,
+    pub ports: Vec<PortInfo>,
+}
+
+impl ScanResult {
+    /// 合并双栈监听（见 [`merge_dual_stack`]），并重新统计端口数
+    pub fn merge_dual_stack(mut self) -> Self {
+        self.ports = merge_dual_stack(self.ports);
+        self.total_ports = self.ports.len();
+        self
+    }
+}
+
+/// 单个端口的刷新结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortRefresh {
+    pub port: u16,
+    pub scan_time: String,
+    pub ports: Vec<PortInfo>,
+}
+
+/// TCP 连接信息（不含 LISTEN 状态的套接字）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionInfo {
+    pub protocol: String,
+    pub local_address: String,
+    pub local_port: u16,
+    pub remote_address: String,
+    pub remote_port: u16,
+    /// 统一为 ESTABLISHED、TIME_WAIT、CLOSE_WAIT 等形式
+    pub state: String,
+    pub pid: String,
+    pub process: String,
+}
+
+impl ConnectionInfo {
+    /// 本地端点，如 `127.0.0.1:52110`、`[::1]:53020`
+    pub fn local_endpoint(&self) -> String {
+        format!("{}:{}", self.local_address, self.local_port)
+    }
+
+    /// 对端端点
+    pub fn remote_endpoint(&self) -> String {
+        format!("{}:{}", self.remote_address, self.remote_port)
+    }
+}
+
+/// 按应用分组的结果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AppGroup {
+    pub process: String,
+    pub pid: String,
+    pub ports: Vec<u16>,
+    pub command: Option<String>,
+}
+
+/// 跨平台获取监听端口（失败时返回空列表）
+pub fn get_listening_ports_raw() -> Vec<PortInfo> {
+    try_get_listening_ports().unwrap_or_default()
+}
+
+/// 跨平台获取监听端口，保留命令缺失/权限不足等失败原因
+pub fn try_get_listening_ports() -> AppResult<Vec<PortInfo>> {
+    if crate::demo::is_enabled() {
+        return Ok(crate::demo::listening_ports());
+    }
+    try_get_listening_ports_with(&SystemRunner, Platform::current())
+}
+
+/// 用指定的命令执行器按 `platform` 的方式获取监听端口，测试时可回放录制的命令输出
+pub fn try_get_listening_ports_with(
+    runner: &dyn CommandRunner,
+    platform: Platform,
+) -> AppResult<Vec<PortInfo>> {
+    let result = match platform {
+        Platform::MacOs => get_ports_macos(runner),
+        Platform::Linux => get_ports_linux(runner),
+        Platform::Windows => get_ports_windows(runner),
+    };
+
+    match &result {
+        Ok(ports) => tracing::info!(count = ports.len(), "本机监听端口扫描完成"),
+        Err(err) => tracing::warn!(error = %err, kind = ?err.kind(), "本机监听端口扫描失败"),
+    }
+    result
+}
+
+/// 跨平台获取本机 UDP 套接字（`protocol` 为 `UDP` / `UDP6`）；UDP 没有监听状态，
+/// 只列出未连接到固定对端的套接字，即 DNS、mDNS、游戏服务器这类等待数据报的服务
+pub fn try_get_udp_ports() -> AppResult<Vec<PortInfo>> {
+    if crate::demo::is_enabled() {
+        return Ok(crate::demo::udp_ports());
+    }
+    try_get_udp_ports_with(&SystemRunner, Platform::current())
+}
+
+/// 用指定的命令执行器按 `platform` 的方式获取 UDP 套接字
+pub fn try_get_udp_ports_with(
+    runner: &dyn CommandRunner,
+    platform: Platform,
+) -> AppResult<Vec<PortInfo>> {
+    let result = match platform {
+        Platform::MacOs => get_udp_ports_macos(runner),
+        Platform::Linux => get_udp_ports_linux(runner),
+        Platform::Windows => get_udp_ports_windows(runner),
+    };
+
+    match &result {
+        Ok(ports) => tracing::info!(count = ports.len(), "本机 UDP 端口扫描完成"),
+        Err(err) => tracing::warn!(error = %err, kind = ?err.kind(), "本机 UDP 端口扫描失败"),
+    }
+    result
+}
+
+/// 读取扫描命令输出；非零退出码仅在有 stderr 时视为失败
+/// （lsof 没有匹配项时也会返回 1）
+fn require_scan_output(
+    command: &str,
+    context: &str,
+    output: CommandOutput,
+) -> AppResult<CommandOutput> {
+    if output.status != 0 && !output.stderr.trim().is_empty() {
+        return Err(AppError::command_failed(
+            command,
+            context,
+            output.status,
+            output.stderr.trim(),
+        ));
+    }
+    Ok(output)
+}
+
+/// macOS: 使用 lsof
+fn get_ports_macos(runner: &dyn CommandRunner) -> AppResult<Vec<PortInfo>> {
+    let context = "端口扫描 (macOS lsof)";
+    let output = runner.run("lsof", context, &mut |cmd| {
+        cmd.args(["-i", "-P", "-n"]);
+    })?;
+    let output = require_scan_output("lsof", context, output)?;
+
+    let mut ports = parse_lsof_output(&output.stdout);
+
+    // 获取完整进程名称（lsof 会截断进程名），所有 PID 一次查询
+    let pids: Vec<&str> = ports.iter().map(|p| p.pid.as_str()).collect();
+    let names = ps_column(runner, &pids, "comm", "进程名称读取");
+    for port in &mut ports {
+        if let Some(name) = names.get(&port.pid) {
+            port.process = name.clone();
+        }
+    }
+
+    Ok(ports)
+}
+
+/// 去重并只保留数字 PID（`ss` 无权限时 PID 为 `-`）
+fn unique_pids<'a>(pids: &[&'a str]) -> Vec<&'a str> {
+    let mut pids: Vec<&str> = pids
+        .iter()
+        .copied()
+        .filter(|pid| !pid.is_empty() && pid.bytes().all(|b| b.is_ascii_digit()))
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
+    pids
+}
+
+/// 用一次 `ps` 调用读取多个进程的某一列（`comm`、`command` 等），返回 PID 到内容的映射；
+/// 失败时返回空映射，调用方保留原值
+fn ps_column(
+    runner: &dyn CommandRunner,
+    pids: &[&str],
+    column: &str,
+    context: &str,
+) -> HashMap<String, String> {
+    let pids = unique_pids(pids);
+    if pids.is_empty() {
+        return HashMap::new();
+    }
+    let list = pids.join(",");
+    let field = format!("{column}=");
+    // 部分 PID 已退出时 ps 返回 1，但仍会输出其余进程，因此不检查状态码
+    match runner.run("ps", context, &mut |cmd| {
+        cmd.args(["-p", list.as_str(), "-o", "pid=", "-o", field.as_str()]);
+    }) {
+        Ok(output) => parse_ps_columns(&output.stdout),
+        Err(err) => {
+            tracing::debug!(error = %err, "批量读取进程信息失败");
+            HashMap::new()
+        }
+    }
+}
+
+/// 解析 `ps -o pid= -o <column>=` 的输出：每行 PID 后为该列内容
+fn parse_ps_columns(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (pid, value) = line.trim_start().split_once(char::is_whitespace)?;
+            let value = value.trim();
+            (!value.is_empty()).then(|| (pid.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Linux: 使用 ss 或 lsof
+fn get_ports_linux(runner: &dyn CommandRunner) -> AppResult<Vec<PortInfo>> {
+    let ss_error = match runner.run("ss", "端口扫描 (Linux ss)", &mut |cmd| {
+        cmd.args(["-tlnp"]);
+    }) {
+        Ok(o) if o.status == 0 => return Ok(parse_ss_output(&o.stdout)),
+        Ok(o) => AppError::command_failed("ss", "端口扫描 (Linux ss)", o.status, o.stderr.trim()),
+        Err(err) => err,
+    };
+    tracing::debug!(error = %ss_error, "ss 不可用，回退到 lsof");
+
+    let context = "端口扫描 (Linux lsof)";
+    match runner.run("lsof", context, &mut |cmd| {
+        cmd.args(["-i", "-P", "-n"]);
+    }) {
+        Ok(output) => {
+            let output = require_scan_output("lsof", context, output)?;
+            Ok(parse_lsof_output(&output.stdout))
+        }
+        // 两个工具都不可用时报告 ss 的失败原因，避免掩盖权限问题
+        Err(AppError::CommandUnavailable { .. }) => Err(ss_error),
+        Err(err) => Err(err),
+    }
+}
+
+/// Windows: 使用 netstat
+fn get_ports_windows(runner: &dyn CommandRunner) -> AppResult<Vec<PortInfo>> {
+    let context = "端口扫描 (Windows netstat)";
+    let output = runner.run("netstat", context, &mut |cmd| {
+        hide_console_window(cmd.args(["-ano"]));
+    })?;
+    let output = require_scan_output("netstat", context, output)?;
+    Ok(parse_netstat_windows(
+        &output.stdout,
+        &get_process_names_windows(runner),
+    ))
+}
+
+/// macOS: 使用 lsof，进程名由 ps 补全
+fn get_udp_ports_macos(runner: &dyn CommandRunner) -> AppResult<Vec<PortInfo>> {
+    let context = "UDP 端口扫描 (macOS lsof)";
+    let output = runner.run("lsof", context, &mut |cmd| {
+        cmd.args(["-i", "UDP", "-P", "-n"]);
+    })?;
+    let output = require_scan_output("lsof", context, output)?;
+
+    let mut ports = parse_lsof_udp(&output.stdout);
+    let pids: Vec<&str> = ports.iter().map(|p| p.pid.as_str()).collect();
+    let names = ps_column(runner, &pids, "comm", "进程名称读取");
+    for port in &mut ports {
+        if let Some(name) = names.get(&port.pid) {
+            port.process = name.clone();
+        }
+    }
+    Ok(ports)
+}
+
+/// Linux: 使用 ss 或 lsof
+fn get_udp_ports_linux(runner: &dyn CommandRunner) -> AppResult<Vec<PortInfo>> {
+    let ss_error = match runner.run("ss", "UDP 端口扫描 (Linux ss)", &mut |cmd| {
+        cmd.args(["-ulnp"]);
+    }) {
+        Ok(o) if o.status == 0 => return Ok(into_udp(parse_ss_output(&o.stdout))),
+        Ok(o) => {
+            AppError::command_failed("ss", "UDP 端口扫描 (Linux ss)", o.status, o.stderr.trim())
+        }
+        Err(err) => err,
+    };
+    tracing::debug!(error = %ss_error, "ss 不可用，回退到 lsof");
+
+    let context = "UDP 端口扫描 (Linux lsof)";
+    match runner.run("lsof", context, &mut |cmd| {
+        cmd.args(["-i", "UDP", "-P", "-n"]);
+    }) {
+        Ok(output) => {
+            let output = require_scan_output("lsof", context, output)?;
+            Ok(parse_lsof_udp(&output.stdout))
+        }
+        Err(AppError::CommandUnavailable { .. }) => Err(ss_error),
+        Err(err) => Err(err),
+    }
+}
+
+/// Windows: 使用 netstat
+fn get_udp_ports_windows(runner: &dyn CommandRunner) -> AppResult<Vec<PortInfo>> {
+    let context = "UDP 端口扫描 (Windows netstat)";
+    let output = runner.run("netstat", context, &mut |cmd| {
+        hide_console_window(cmd.args(["-ano"]));
+    })?;
+    let output = require_scan_output("netstat", context, output)?;
+    Ok(parse_netstat_udp(
+        &output.stdout,
+        &get_process_names_windows(runner),
+    ))
+}
+
+/// 把按 TCP 解析出的 `IPv4` / `IPv6` 改为 `UDP` / `UDP6`
+fn into_udp(mut ports: Vec<PortInfo>) -> Vec<PortInfo> {
+    for port in &mut ports {
+        let ipv6 = port.protocol == "IPv6";
+        port.protocol = if ipv6 { "UDP6" } else { "UDP" }.to_string();
+    }
+    ports
+}
+
+/// 解析 lsof 输出中的 UDP 套接字；`->` 表示已连接到固定对端的客户端套接字，跳过
+fn parse_lsof_udp(stdout: &str) -> Vec<PortInfo> {
+    let mut ports: Vec<PortInfo> = stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 9 || parts[7] != "UDP" || parts[8].contains("->") {
+                return None;
+            }
+            let (address, port) = split_endpoint(parts[8])?;
+            let ipv6 = parts[4].contains('6');
+            Some(PortInfo {
+                port,
+                protocol: if ipv6 { "UDP6" } else { "UDP" }.to_string(),
+                address: if address == "*" { "*" } else { address }.to_string(),
+                pid: parts[1].to_string(),
+                process: parts[0].to_string(),
+                user: parts[2].to_string(),
+                command: None,
+            })
+        })
+        .collect();
+    // 同一套接字在多个文件描述符上各出现一次
+    let mut seen = std::collections::HashSet::new();
+    ports.retain(|p| seen.insert((p.port, p.protocol.clone(), p.address.clone(), p.pid.clone())));
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+/// 解析 `netstat -ano` 中的 UDP 行：协议、本地地址、`*:*`、PID
+fn parse_netstat_udp(stdout: &str, names: &HashMap<String, String>) -> Vec<PortInfo> {
+    let mut ports: Vec<PortInfo> = stdout
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 4 || !parts[0].starts_with("UDP") {
+                return None;
+            }
+            let (address, port) = split_endpoint(parts[1])?;
+            let pid = parts[3];
+            // netstat 的协议列对 IPv6 也只写 UDP，按地址判断
+            let ipv6 = address.starts_with('[');
+            Some(PortInfo {
+                port,
+                protocol: if ipv6 { "UDP6" } else { "UDP" }.to_string(),
+                address: match address {
+                    "0.0.0.0" | "[::]" | "*" => "*".to_string(),
+                    address => address.to_string(),
+                },
+                pid: pid.to_string(),
+                process: names.get(pid).cloned().unwrap_or_else(|| pid.to_string()),
+                user: "-".to_string(),
+                command: None,
+            })
+        })
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    ports.retain(|p| seen.insert((p.port, p.protocol.clone(), p.address.clone())));
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+/// 解析 lsof 输出
+fn parse_lsof_output(stdout: &str) -> Vec<PortInfo> {
+    let mut ports = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for line in stdout.lines().skip(1) {
+        if !line.contains("LISTEN") {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 9 {
+            continue;
+        }
+
+        let process_name = parts[0].to_string();
+        let pid = parts[1].to_string();
+        let user = parts[2].to_string();
+        let addr_port = parts[8];
+
+        let Some((address, port, ipv6)) = parse_listen_address(addr_port) else {
+            continue;
+        };
+
+        let fd_type = if parts.len() > 4 { parts[4] } else { "" };
+        let protocol = if ipv6 || fd_type.contains('6') {
+            "IPv6".to_string()
+        } else {
+            "IPv4".to_string()
+        };
+
+        let key = format!("{}:{}:{}:{}:{}", process_name, pid, port, address, protocol);
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.insert(key);
+
+        ports.push(PortInfo {
+            port,
+            protocol,
+            address,
+            pid,
+            process: process_name,
+            user,
+            command: None,
+        });
+    }
+
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+fn parse_ss_output(stdout: &str) -> Vec<PortInfo> {
+    let mut ports = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for line in stdout.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 {
+            continue;
+        }
+
+        let Some((address, port, ipv6)) = parse_listen_address(parts[3]) else {
+            continue;
+        };
+
+        let (process, pid) = if parts.len() > 5 {
+            parse_ss_process_info(parts[5])
+        } else {
+            ("-".to_string(), "-".to_string())
+        };
+
+        let protocol = if ipv6 { "IPv6" } else { "IPv4" }.to_string();
+
+        let key = format!("{}:{}:{}", port, address, protocol);
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.insert(key);
+
+        ports.push(PortInfo {
+            port,
+            protocol,
+            address,
+            pid,
+            process,
+            user: "-".to_string(),
+            command: None,
+        });
+    }
+
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+fn parse_ss_process_info(info: &str) -> (String, String) {
+    if let Some(start) = info.find("((\"") {
+        if let Some(end) = info[start + 3..].find("\"") {
+            let process = &info[start + 3..start + 3 + end];
+            if let Some(pid_start) = info.find("pid=") {
+                if let Some(pid_end) = info[pid_start + 4..].find(',') {
+                    let pid = &info[pid_start + 4..pid_start + 4 + pid_end];
+                    return (process.to_string(), pid.to_string());
+                }
+            }
+            return (process.to_string(), "-".to_string());
+        }
+    }
+    ("-".to_string(), "-".to_string())
+}
+
+/// 拆分监听地址和端口，支持 `127.0.0.1:80`、`*:80`、`[::1]:80`、`[fe80::1%en0]:80`、ss 的
+/// `[fe80::1]%eth0:80` 和旧版工具不带方括号的 `:::80`、`::1:80`
+///
+/// 返回统一后的地址（通配地址为 `*`，IPv6 地址带方括号）、端口和地址是否为 IPv6
+fn parse_listen_address(endpoint: &str) -> Option<(String, u16, bool)> {
+    let (host, port) = endpoint.rsplit_once(':')?;
+    let port: u16 = port.parse().ok()?;
+    let host = match host.strip_prefix('[') {
+        Some(rest) => {
+            let (inner, zone) = rest.split_once(']')?;
+            format!("{}{}", inner, zone)
+        }
+        None => host.to_string(),
+    };
+    let ipv6 = host.contains(':');
+    let address = match host.as_str() {
+        "" | "*" | "0.0.0.0" | "::" => "*".to_string(),
+        _ if ipv6 => format!("[{}]", host),
+        _ => host,
+    };
+    Some((address, port, ipv6))
+}
+
+/// 把同一进程在同一端口上的 IPv4 和 IPv6 监听合并为一行
+///
+/// 合并后协议为 `IPv4/IPv6`（UDP 为 `UDP/UDP6`），地址按 IPv4、IPv6 顺序列出，如
+/// `127.0.0.1, [::1]`，两边都是通配地址时仍为 `*`。只有一个地址族的端口保持不变
+pub fn merge_dual_stack(ports: Vec<PortInfo>) -> Vec<PortInfo> {
+    let is_udp = |p: &PortInfo| p.protocol.starts_with("UDP");
+    let is_ipv6 = |p: &PortInfo| p.protocol.ends_with('6');
+    let mut groups: Vec<Vec<PortInfo>> = Vec::new();
+    for port in ports {
+        let group = groups.iter_mut().find(|group| {
+            let first = &group[0];
+            first.port == port.port
+                && first.pid == port.pid
+                && first.process == port.process
+                && is_udp(first) == is_udp(&port)
+        });
+        match group {
+            Some(group) => group.push(port),
+            None => groups.push(vec![port]),
+        }
+    }
+
+    let mut merged = Vec::new();
+    for group in groups {
+        if !(group.iter().any(is_ipv6) && group.iter().any(|p| !is_ipv6(p))) {
+            merged.extend(group);
+            continue;
+        }
+        let (v6, v4): (Vec<&PortInfo>, Vec<&PortInfo>) = group.iter().partition(|p| is_ipv6(p));
+        let mut addresses: Vec<&str> = Vec::new();
+        for port in v4.into_iter().chain(v6) {
+            if !addresses.contains(&port.address.as_str()) {
+                addresses.push(&port.address);
+            }
+        }
+        let first = &group[0];
+        let protocol = if is_udp(first) {
+            "UDP/UDP6"
+        } else {
+            "IPv4/IPv6"
+        };
+        merged.push(PortInfo {
+            protocol: protocol.to_string(),
+            address: addresses.join(", "),
+            command: group.iter().find_map(|p| p.command.clone()),
+            ..first.clone()
+        });
+    }
+    merged
+}
+
+fn parse_netstat_windows(stdout: &str, names: &HashMap<String, String>) -> Vec<PortInfo> {
+    let mut ports = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+
+    for line in stdout.lines() {
+        if !line.contains("LISTENING") {
+            continue;
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 5 {
+            continue;
+        }
+
+        let pid = parts[4];
+        let Some((address, port, ipv6)) = parse_listen_address(parts[1]) else {
+            continue;
+        };
+
+        // netstat -ano 的协议列对 IPv6 也只写 TCP，按地址判断
+        let protocol = if ipv6 || parts[0].contains('6') {
+            "IPv6"
+        } else {
+            "IPv4"
+        }
+        .to_string();
+
+        let process = names.get(pid).cloned().unwrap_or_else(|| pid.to_string());
+        let key = format!("{}:{}:{}", port, address, protocol);
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.insert(key);
+
+        ports.push(PortInfo {
+            port,
+            protocol,
+            address,
+            pid: pid.to_string(),
+            process,
+            user: "-".to_string(),
+            command: None,
+        });
+    }
+
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+/// 用一次 `tasklist` 读取所有进程名，返回 PID 到进程名的映射
+fn get_process_names_windows(runner: &dyn CommandRunner) -> HashMap<String, String> {
+    let output = match runner.run("tasklist", "进程名读取", &mut |cmd| {
+        hide_console_window(cmd.args(["/FO", "CSV", "/NH"]));
+    }) {
+        Ok(output) if output.status == 0 => output,
+        _ => return HashMap::new(),
+    };
+
+    // "name.exe","1234","Console","1","12,345 K"
+    output
+        .stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split("\",\"");
+            let name = fields.next()?.trim_start_matches('"');
+            let pid = fields.next()?;
+            Some((pid.to_string(), name.to_string()))
+        })
+        .collect()
+}
+
+/// 本机 TCP 连接（失败时返回空列表）：对端地址和端口、状态（ESTABLISHED、TIME_WAIT 等）、
+/// PID 和进程名
+pub fn scan_connections() -> Vec<ConnectionInfo> {
+    try_get_connections().unwrap_or_default()
+}
+
+/// 跨平台获取本机 TCP 连接，保留命令缺失/权限不足等失败原因
+pub fn try_get_connections() -> AppResult<Vec<ConnectionInfo>> {
+    if crate::demo::is_enabled() {
+        return Ok(crate::demo::connections());
+    }
+    try_get_connections_with(&SystemRunner, Platform::current())
+}
+
+/// 用指定的命令执行器按 `platform` 的方式获取 TCP 连接
+pub fn try_get_connections_with(
+    runner: &dyn CommandRunner,
+    platform: Platform,
+) -> AppResult<Vec<ConnectionInfo>> {
+    let result = match platform {
+        Platform::MacOs => get_connections_macos(runner),
+        Platform::Linux => get_connections_linux(runner),
+        Platform::Windows => get_connections_windows(runner),
+    };
+    match &result {
+        Ok(connections) => tracing::info!(count = connections.len(), "本机连接扫描完成"),
+        Err(err) => tracing::warn!(error = %err, kind = ?err.kind(), "本机连接扫描失败"),
+    }
+    result
+}
+
+/// macOS: 使用 lsof，进程名由 ps 补全
+fn get_connections_macos(runner: &dyn CommandRunner) -> AppResult<Vec<ConnectionInfo>> {
+    let context = "连接扫描 (macOS lsof)";
+    let output = runner.run("lsof", context, &mut |cmd| {
+        cmd.args(["-i", "-P", "-n"]);
+    })?;
+    let output = require_scan_output("lsof", context, output)?;
+
+    let mut connections = parse_lsof_connections(&output.stdout);
+    let pids: Vec<&str> = connections.iter().map(|c| c.pid.as_str()).collect();
+    let names = ps_column(runner, &pids, "comm", "进程名称读取");
+    for connection in &mut connections {
+        if let Some(name) = names.get(&connection.pid) {
+            connection.process = name.clone();
+        }
+    }
+    Ok(connections)
+}
+
+/// Linux: 使用 ss 或 lsof
+fn get_connections_linux(runner: &dyn CommandRunner) -> AppResult<Vec<ConnectionInfo>> {
+    let ss_error = match runner.run("ss", "连接扫描 (Linux ss)", &mut |cmd| {
+        cmd.args(["-tanp"]);
+    }) {
+        Ok(o) if o.status == 0 => return Ok(parse_ss_connections(&o.stdout)),
+        Ok(o) => AppError::command_failed("ss", "连接扫描 (Linux ss)", o.status, o.stderr.trim()),
+        Err(err) => err,
+    };
+    tracing::debug!(error = %ss_error, "ss 不可用，回退到 lsof");
+
+    let context = "连接扫描 (Linux lsof)";
+    match runner.run("lsof", context, &mut |cmd| {
+        cmd.args(["-i", "-P", "-n"]);
+    }) {
+        Ok(output) => {
+            let output = require_scan_output("lsof", context, output)?;
+            Ok(parse_lsof_connections(&output.stdout))
+        }
+        Err(AppError::CommandUnavailable { .. }) => Err(ss_error),
+        Err(err) => Err(err),
+    }
+}
+
+/// Windows: 使用 netstat
+fn get_connections_windows(runner: &dyn CommandRunner) -> AppResult<Vec<ConnectionInfo>> {
+    let context = "连接扫描 (Windows netstat)";
+    let output = runner.run("netstat", context, &mut |cmd| {
+        hide_console_window(cmd.args(["-ano"]));
+    })?;
+    let output = require_scan_output("netstat", context, output)?;
+    Ok(parse_netstat_connections(
+        &output.stdout,
+        &get_process_names_windows(runner),
+    ))
+}
+
+/// 拆分 `127.0.0.1:5432`、`[::1]:5432` 形式的地址和端口
+fn split_endpoint(endpoint: &str) -> Option<(&str, u16)> {
+    let pos = endpoint.rfind(':')?;
+    Some((&endpoint[..pos], endpoint[pos + 1..].parse().ok()?))
+}
+
+/// 统一各工具的状态名：ss 的 `ESTAB`、`TIME-WAIT` 转为 `ESTABLISHED`、`TIME_WAIT`
+fn normalize_tcp_state(state: &str) -> String {
+    match state.trim_matches(|c| c == '(' || c == ')') {
+        "ESTAB" => "ESTABLISHED".to_string(),
+        state => state.to_ascii_uppercase().replace('-', "_"),
+    }
+}
+
+fn is_listen_state(state: &str) -> bool {
+    matches!(state, "LISTEN" | "LISTENING")
+}
+
+/// 解析 lsof 输出中的 TCP 连接，如 `TCP 127.0.0.1:3000->127.0.0.1:61022 (ESTABLISHED)`
+fn parse_lsof_connections(stdout: &str) -> Vec<ConnectionInfo> {
+    let mut connections: Vec<ConnectionInfo> = stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 10 || parts[7] != "TCP" {
+                return None;
+            }
+            let (local, remote) = parts[8].split_once("->")?;
+            let (local_address, local_port) = split_endpoint(local)?;
+            let (remote_address, remote_port) = split_endpoint(remote)?;
+            let state = normalize_tcp_state(parts[9]);
+            let ipv6 = parts[4].contains('6');
+            Some(ConnectionInfo {
+                protocol: if ipv6 { "IPv6" } else { "IPv4" }.to_string(),
+                local_address: local_address.to_string(),
+                local_port,
+                remote_address: remote_address.to_string(),
+                remote_port,
+                state,
+                pid: parts[1].to_string(),
+                process: parts[0].to_string(),
+            })
+        })
+        .collect();
+    connections.dedup();
+    connections
+}
+
+/// 解析 `ss -tanp`：状态、队列、本地地址、对端地址、进程列
+fn parse_ss_connections(stdout: &str) -> Vec<ConnectionInfo> {
+    stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 5 {
+                return None;
+            }
+            let state = normalize_tcp_state(parts[0]);
+            if is_listen_state(&state) {
+                return None;
+            }
+            let (local_address, local_port) = split_endpoint(parts[3])?;
+            let (remote_address, remote_port) = split_endpoint(parts[4])?;
+            let (process, pid) = match parts.get(5) {
+                Some(info) => parse_ss_process_info(info),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            let ipv6 = local_address.contains(':');
+            Some(ConnectionInfo {
+                protocol: if ipv6 { "IPv6" } else { "IPv4" }.to_string(),
+                local_address: local_address.to_string(),
+                local_port,
+                remote_address: remote_address.to_string(),
+                remote_port,
+                state,
+                pid,
+                process,
+            })
+        })
+        .collect()
+}
+
+/// 解析 `netstat -ano` 中的 TCP 连接
+fn parse_netstat_connections(stdout: &str, names: &HashMap<String, String>) -> Vec<ConnectionInfo> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 5 || !parts[0].starts_with("TCP") {
+                return None;
+            }
+            let state = normalize_tcp_state(parts[3]);
+            if is_listen_state(&state) {
+                return None;
+            }
+            let (local_address, local_port) = split_endpoint(parts[1])?;
+            let (remote_address, remote_port) = split_endpoint(parts[2])?;
+            let pid = parts[4];
+            let ipv6 = local_address.starts_with('[');
+            Some(ConnectionInfo {
+                protocol: if ipv6 { "IPv6" } else { "IPv4" }.to_string(),
+                local_address: local_address.to_string(),
+                local_port,
+                remote_address: remote_address.to_string(),
+                remote_port,
+                state,
+                pid: pid.to_string(),
+                process: names.get(pid).cloned().unwrap_or_else(|| pid.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// 批量获取进程的完整命令行，返回 PID 到命令行的映射；查不到的 PID 不在结果中
+pub fn get_process_commands(pids: &[&str]) -> HashMap<String, String> {
+    if crate::demo::is_enabled() {
+        return crate::demo::process_commands(pids);
+    }
+    get_process_commands_with(&SystemRunner, Platform::current(), pids)
+}
+
+/// 用指定的命令执行器批量获取进程命令行
+pub fn get_process_commands_with(
+    runner: &dyn CommandRunner,
+    platform: Platform,
+    pids: &[&str],
+) -> HashMap<String, String> {
+    if platform != Platform::Windows {
+        return ps_column(runner, pids, "command", "进程命令行读取");
+    }
+
+    let pids = unique_pids(pids);
+    if pids.is_empty() {
+        return HashMap::new();
+    }
+    let filter = pids
+        .iter()
+        .map(|pid| format!("ProcessId={pid}"))
+        .collect::<Vec<_>>()
+        .join(" or ");
+    let output = match runner.run("wmic", "进程命令行读取", &mut |cmd| {
+        hide_console_window(cmd.args([
+            "process",
+            "where",
+            &filter,
+            "get",
+            "CommandLine,ProcessId",
+            "/value",
+        ]));
+    }) {
+        Ok(output) if output.status == 0 => output,
+        _ => return HashMap::new(),
+    };
+    parse_wmic_commands(&output.stdout)
+}
+
+/// 解析 `wmic ... get CommandLine,ProcessId /value`：每个进程输出 CommandLine=... 和 ProcessId=... 两行
+fn parse_wmic_commands(stdout: &str) -> HashMap<String, String> {
+    let mut commands = HashMap::new();
+    let mut command = None;
+    for line in stdout.lines().map(str::trim) {
+        if let Some(value) = line.strip_prefix("CommandLine=") {
+            command = Some(value.to_string()).filter(|c| !c.is_empty());
+        } else if let Some(pid) = line.strip_prefix("ProcessId=") {
+            if let Some(command) = command.take() {
+                commands.insert(pid.to_string(), command);
+            }
+        }
+    }
+    commands
+}
+
+/// 获取进程的完整命令行
+pub fn get_process_command(pid: &str) -> Option<String> {
+    if crate::demo::is_enabled() {
+        return crate::demo::process_commands(&[pid]).remove(pid);
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let output = run_command("wmic", "进程命令行读取", |cmd| {
+            cmd.args([
+                "process",
+                "where",
+                &format!("ProcessId={}", pid),
+                "get",
+                "CommandLine",
+                "/value",
+            ]);
+        })
+        .ok()?;
+        if output.status != 0 {
+            return None;
+        }
+        let stdout = output.stdout;
+        for line in stdout.lines() {
+            if line.starts_with("CommandLine=") {
+                return Some(line[12..].trim().to_string());
+            }
+        }
+        None
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let output = run_command("ps", "进程命令行读取", |cmd| {
+            cmd.args(["-p", pid, "-o", "command="]);
+        })
+        .ok()?;
+        if output.status != 0 {
+            return None;
+        }
+        let cmd = output.stdout.trim().to_string();
+        if cmd.is_empty() {
+            None
+        } else {
+            Some(cmd)
+        }
+    }
+}
+
+/// 扫描端口（带命令行选项，失败时返回空结果）
+pub fn scan_ports(include_command: bool, include_udp: bool) -> ScanResult {
+    try_scan_ports(include_command, include_udp)
+        .unwrap_or_else(|_| build_scan_result(Vec::new(), false))
+}
+
+/// 扫描端口，保留失败原因供前端区分；`include_udp` 时一并列出 UDP 套接字
+pub fn try_scan_ports(include_command: bool, include_udp: bool) -> AppResult<ScanResult> {
+    let mut ports = try_get_listening_ports()?;
+    if include_udp {
+        ports.extend(try_get_udp_ports()?);
+        ports.sort_by_key(|p| p.port);
+    }
+    Ok(build_scan_result(ports, include_command))
+}
+
+/// 重新读取单个端口的监听情况，`ports` 为空表示该端口已不再监听；`include_udp` 时
+/// 一并列出该端口的 UDP 套接字
+pub fn try_refresh_port(
+    port: u16,
+    include_command: bool,
+    include_udp: bool,
+) -> AppResult<PortRefresh> {
+    let mut ports = try_get_listening_ports()?;
+    if include_udp {
+        ports.extend(try_get_udp_ports()?);
+    }
+    ports.retain(|p| p.port == port);
+    let result = build_scan_result(ports, include_command);
+    Ok(PortRefresh {
+        port,
+        scan_time: result.scan_time,
+        ports: result.ports,
+    })
+}
+
+fn build_scan_result(mut ports: Vec<PortInfo>, include_command: bool) -> ScanResult {
+    if include_command {
+        let pids: Vec<&str> = ports.iter().map(|p| p.pid.as_str()).collect();
+        let commands = get_process_commands(&pids);
+        for port in &mut ports {
+            port.command = commands.get(&port.pid).cloned();
+        }
+    }
+
+    let unique_apps: std::collections::HashSet<_> = ports
+        .iter()
+        .map(|p| format!("{}:{}", p.process, p.pid))
+        .collect();
+
+    ScanResult {
+        scan_time: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        total_ports: ports.len(),
+        unique_apps: unique_apps.len(),
+        ports,
+    }
+}
+
+/// 按应用分组（失败时返回空列表）
+pub fn scan_ports_grouped() -> Vec<AppGroup> {
+    try_scan_ports_grouped().unwrap_or_default()
+}
+
+/// 按应用分组，保留失败原因供前端区分
+pub fn try_scan_ports_grouped() -> AppResult<Vec<AppGroup>> {
+    let ports = try_get_listening_ports()?;
+    let mut groups: HashMap<(String, String), Vec<u16>> = HashMap::new();
+
+    for port in &ports {
+        groups
+            .entry((port.process.clone(), port.pid.clone()))
+            .or_default()
+            .push(port.port);
+    }
+
+    let pids: Vec<&str> = groups.keys().map(|(_, pid)| pid.as_str()).collect();
+    let commands = get_process_commands(&pids);
+
+    let mut result: Vec<AppGroup> = groups
+        .into_iter()
+        .map(|((process, pid), mut port_list)| {
+            port_list.sort();
+            port_list.dedup();
+            let command = commands.get(&pid).cloned();
+            AppGroup {
+                process,
+                pid,
+                ports: port_list,
+                command,
+            }
+        })
+        .collect();
+
+    result.sort_by_key(|g| g.ports.first().copied().unwrap_or(0));
+    Ok(result)
+}
+
+/// 过滤端口
+pub fn filter_ports(
+    port_filter: Option<u16>,
+    app_filter: Option<String>,
+    exclude_system: bool,
+) -> Vec<PortInfo> {
+    let mut ports = get_listening_ports_raw();
+
+    if let Some(pf) = port_filter {
+        ports.retain(|p| p.port == pf);
+    }
+    if let Some(ref af) = app_filter {
+        let af_lower = af.to_lowercase();
+        ports.retain(|p| p.process.to_lowercase().contains(&af_lower));
+    }
+    if exclude_system {
+        #[cfg(target_os = "macos")]
+        let system_procs = ["controlce", "rapportd", "netdisk_s", "mds", "launchd"];
+        #[cfg(target_os = "linux")]
+        let system_procs = ["systemd", "sshd", "dbus", "networkmanager"];
+        #[cfg(target_os = "windows")]
+        let system_procs = ["system", "svchost", "lsass", "services"];
+        ports.retain(|p| !system_procs.contains(&p.process.to_lowercase().as_str()));
+    }
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+
+    #[test]
+    fn test_scan_ports_returns_valid_structure() {
+        let result = scan_ports(false, false);
+        assert_eq!(result.ports.len(), result.total_ports);
+        assert!(!result.scan_time.is_empty());
+    }
+
+    #[test]
+    fn test_scan_ports_with_command() {
+        let result = scan_ports(true, false);
+        // 检查返回的结构
+        assert!(!result.ports.is_empty() || result.ports.is_empty());
+    }
+
+    #[test]
+    fn test_refresh_port_only_returns_that_port() {
+        let Some(port) = scan_ports(false, false).ports.first().map(|p| p.port) else {
+            return;
+        };
+        let refresh = try_refresh_port(port, false, false).unwrap();
+        assert_eq!(refresh.port, port);
+        assert!(refresh.ports.iter().all(|p| p.port == port));
+    }
+
+    #[test]
+    fn test_parse_ps_columns() {
+        let stdout = "  812 /usr/sbin/sshd -D\n 1042 node server.js --port 3000\n 77 \n";
+        let columns = parse_ps_columns(stdout);
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns["812"], "/usr/sbin/sshd -D");
+        assert_eq!(columns["1042"], "node server.js --port 3000");
+    }
+
+    #[test]
+    fn test_get_process_commands_batches_pids() {
+        assert!(get_process_commands(&["-", ""]).is_empty());
+        let pid = std::process::id().to_string();
+        let commands = get_process_commands(&[pid.as_str(), pid.as_str(), "-"]);
+        assert!(commands.len() <= 1);
+        assert_eq!(unique_pids(&["42", "-", "7", "42"]), vec!["42", "7"]);
+    }
+
+    #[test]
+    fn test_scan_macos_fixture() {
+        let runner = FixtureRunner::new()
+            .with_stdout("lsof -i -P -n", include_str!("../fixtures/macos/lsof.txt"))
+            .with_stdout(
+                "ps -p 48213,512,601,733 -o pid= -o comm=",
+                include_str!("../fixtures/macos/ps_comm.txt"),
+            );
+        let ports = try_get_listening_ports_with(&runner, Platform::MacOs).unwrap();
+
+        let summary: Vec<(u16, &str, &str)> = ports
+            .iter()
+            .map(|p| (p.port, p.address.as_str(), p.protocol.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3000, "127.0.0.1", "IPv4"),
+                (5432, "[::1]", "IPv6"),
+                (5432, "127.0.0.1", "IPv4"),
+                (7000, "*", "IPv4"),
+                (7000, "*", "IPv6"),
+                (49152, "*", "IPv4"),
+                (49152, "*", "IPv6"),
+            ]
+        );
+        // lsof 截断的进程名由 ps 补全
+        let control = ports.iter().find(|p| p.port == 7000).unwrap();
+        assert!(control.process.ends_with("ControlCenter"));
+        assert_eq!(control.user, "alice");
+        assert_eq!(runner.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_scan_linux_ss_fixture() {
+        let runner =
+            FixtureRunner::new().with_stdout("ss -tlnp", include_str!("../fixtures/linux/ss.txt"));
+        let ports = try_get_listening_ports_with(&runner, Platform::Linux).unwrap();
+
+        assert_eq!(ports.len(), 6);
+        let resolver = ports.iter().find(|p| p.port == 53).unwrap();
+        assert_eq!(resolver.address, "127.0.0.53%lo");
+        assert_eq!(resolver.process, "systemd-resolve");
+        assert_eq!(resolver.pid, "612");
+        let sshd: Vec<&PortInfo> = ports.iter().filter(|p| p.port == 22).collect();
+        assert_eq!(sshd.len(), 2);
+        assert!(sshd.iter().all(|p| p.address == "*" && p.process == "sshd"));
+        // 无权限时 ss 不输出进程列
+        let hidden = ports.iter().find(|p| p.port == 9000).unwrap();
+        assert_eq!(
+            (hidden.pid.as_str(), hidden.protocol.as_str()),
+            ("-", "IPv6")
+        );
+        assert_eq!(runner.calls(), vec!["ss -tlnp"]);
+    }
+
+    #[test]
+    fn test_scan_linux_falls_back_to_lsof() {
+        let runner = FixtureRunner::new()
+            .with_output(
+                "ss -tlnp",
+                1,
+                "",
+                "Cannot open netlink socket: Permission denied",
+            )
+            .with_stdout("lsof -i -P -n", include_str!("../fixtures/linux/lsof.txt"));
+        let ports = try_get_listening_ports_with(&runner, Platform::Linux).unwrap();
+        let summary: Vec<(u16, &str)> =
+            ports.iter().map(|p| (p.port, p.process.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (22, "sshd"),
+                (22, "sshd"),
+                (6379, "redis-ser"),
+                (8080, "node")
+            ]
+        );
+        assert_eq!(runner.calls(), vec!["ss -tlnp", "lsof -i -P -n"]);
+
+        // 两个工具都没有时报告 ss 的失败原因
+        let err = try_get_listening_ports_with(&FixtureRunner::new(), Platform::Linux).unwrap_err();
+        assert_eq!(err.kind(), crate::PortlyErrorKind::ToolMissing);
+        assert!(err.to_string().contains("ss"));
+    }
+
+    #[test]
+    fn test_scan_windows_fixture() {
+        let crlf = |text: &str| text.replace('\n', "\r\n");
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                "netstat -ano",
+                &crlf(include_str!("../fixtures/windows/netstat.txt")),
+            )
+            .with_stdout(
+                "tasklist /FO CSV /NH",
+                &crlf(include_str!("../fixtures/windows/tasklist.txt")),
+            );
+        let ports = try_get_listening_ports_with(&runner, Platform::Windows).unwrap();
+
+        let summary: Vec<(u16, &str, &str)> = ports
+            .iter()
+            .map(|p| (p.port, p.address.as_str(), p.process.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (135, "*", "svchost.exe"),
+                (135, "*", "svchost.exe"),
+                (139, "192.168.1.40", "System"),
+                (445, "*", "System"),
+                (445, "*", "System"),
+                (5040, "*", "svchost.exe"),
+                (5432, "127.0.0.1", "postgres.exe"),
+                (5432, "[::1]", "postgres.exe"),
+            ]
+        );
+        // netstat 的协议列对 IPv6 也只写 TCP
+        let protocols: Vec<&str> = ports.iter().map(|p| p.protocol.as_str()).collect();
+        assert_eq!(
+            protocols,
+            vec!["IPv4", "IPv6", "IPv4", "IPv4", "IPv6", "IPv4", "IPv4", "IPv6"]
+        );
+
+        let merged = merge_dual_stack(ports);
+        let summary: Vec<(u16, &str, &str)> = merged
+            .iter()
+            .map(|p| (p.port, p.address.as_str(), p.protocol.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (135, "*", "IPv4/IPv6"),
+                (139, "192.168.1.40", "IPv4"),
+                (445, "*", "IPv4/IPv6"),
+                (5040, "*", "IPv4"),
+                (5432, "127.0.0.1, [::1]", "IPv4/IPv6"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_listen_address_ipv6_forms() {
+        let parse = |endpoint: &str| parse_listen_address(endpoint);
+        assert_eq!(
+            parse("127.0.0.1:5432"),
+            Some(("127.0.0.1".into(), 5432, false))
+        );
+        assert_eq!(parse("*:8080"), Some(("*".into(), 8080, false)));
+        assert_eq!(parse("[::]:22"), Some(("*".into(), 22, true)));
+        assert_eq!(parse(":::22"), Some(("*".into(), 22, true)));
+        assert_eq!(parse("[::1]:8080"), Some(("[::1]".into(), 8080, true)));
+        assert_eq!(parse("::1:631"), Some(("[::1]".into(), 631, true)));
+        assert_eq!(
+            parse("[fe80::1%en0]:5000"),
+            Some(("[fe80::1%en0]".into(), 5000, true))
+        );
+        assert_eq!(
+            parse("[fe80::1]%eth0:123"),
+            Some(("[fe80::1%eth0]".into(), 123, true))
+        );
+        assert_eq!(
+            parse("127.0.0.53%lo:53"),
+            Some(("127.0.0.53%lo".into(), 53, false))
+        );
+        assert_eq!(parse("[::1]:*"), None);
+        assert_eq!(parse("localhost"), None);
+
+        // 只有 IPv6 的监听和不同进程的监听不合并
+        let lsof = "COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME\n\
+            node 10 dev 21u IPv4 1 0t0 TCP 127.0.0.1:3000 (LISTEN)\n\
+            node 10 dev 22u IPv6 2 0t0 TCP [::1]:3000 (LISTEN)\n\
+            vite 11 dev 23u IPv6 3 0t0 TCP [::1]:5173 (LISTEN)\n\
+            java 12 dev 24u IPv4 4 0t0 TCP *:8080 (LISTEN)\n\
+            nginx 13 root 6u IPv6 5 0t0 TCP *:8080 (LISTEN)\n";
+        let merged = merge_dual_stack(parse_lsof_output(lsof));
+        let summary: Vec<(u16, &str, &str)> = merged
+            .iter()
+            .map(|p| (p.port, p.address.as_str(), p.protocol.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3000, "127.0.0.1, [::1]", "IPv4/IPv6"),
+                (5173, "[::1]", "IPv6"),
+                (8080, "*", "IPv4"),
+                (8080, "*", "IPv6"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_udp_fixtures() {
+        let runner = FixtureRunner::new()
+            .with_stdout("ss -ulnp", include_str!("../fixtures/linux/ss_udp.txt"));
+        let ports = try_get_udp_ports_with(&runner, Platform::Linux).unwrap();
+        let summary: Vec<(u16, &str, &str, &str)> = ports
+            .iter()
+            .map(|p| {
+                (
+                    p.port,
+                    p.protocol.as_str(),
+                    p.address.as_str(),
+                    p.process.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (53, "UDP", "127.0.0.53%lo", "systemd-resolve"),
+                (5353, "UDP", "*", "avahi-daemon"),
+                (5353, "UDP6", "*", "avahi-daemon"),
+                (27015, "UDP", "*", "srcds_linux"),
+                (41641, "UDP", "*", "-"),
+            ]
+        );
+
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                "lsof -i UDP -P -n",
+                include_str!("../fixtures/macos/lsof_udp.txt"),
+            )
+            .with_stdout(
+                "ps -p 2044,355 -o pid= -o comm=",
+                " 355 /usr/sbin/mDNSResponder\n2044 bedrock_server\n",
+            );
+        let ports = try_get_udp_ports_with(&runner, Platform::MacOs).unwrap();
+        let summary: Vec<(u16, &str, &str)> = ports
+            .iter()
+            .map(|p| (p.port, p.protocol.as_str(), p.process.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (5353, "UDP", "/usr/sbin/mDNSResponder"),
+                (5353, "UDP6", "/usr/sbin/mDNSResponder"),
+                (19132, "UDP", "bedrock_server"),
+            ]
+        );
+
+        let crlf = |text: &str| text.replace('\n', "\r\n");
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                "netstat -ano",
+                &crlf(include_str!("../fixtures/windows/netstat.txt")),
+            )
+            .with_stdout(
+                "tasklist /FO CSV /NH",
+                &crlf(include_str!("../fixtures/windows/tasklist.txt")),
+            );
+        let ports = try_get_udp_ports_with(&runner, Platform::Windows).unwrap();
+        let summary: Vec<(u16, &str, &str, &str)> = ports
+            .iter()
+            .map(|p| {
+                (
+                    p.port,
+                    p.protocol.as_str(),
+                    p.address.as_str(),
+                    p.process.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (53, "UDP", "127.0.0.1", "3120"),
+                (5353, "UDP", "*", "mDNSResponder.exe"),
+                (5353, "UDP6", "*", "mDNSResponder.exe"),
+            ]
+        );
+        // TCP 监听端口不受 UDP 行影响
+        let tcp = try_get_listening_ports_with(&runner, Platform::Windows).unwrap();
+        assert!(tcp.iter().all(|p| !p.protocol.starts_with("UDP")));
+    }
+
+    #[test]
+    fn test_scan_connections_fixtures() {
+        let runner = FixtureRunner::new().with_stdout(
+            "ss -tanp",
+            include_str!("../fixtures/linux/ss_connections.txt"),
+        );
+        let connections = try_get_connections_with(&runner, Platform::Linux).unwrap();
+        let summary: Vec<(&str, u16, &str)> = connections
+            .iter()
+            .map(|c| (c.state.as_str(), c.remote_port, c.pid.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("ESTABLISHED", 5432, "2231"),
+                ("ESTABLISHED", 41822, "3011"),
+                ("ESTABLISHED", 6379, "2231"),
+                ("ESTABLISHED", 443, "4410"),
+                ("TIME_WAIT", 5432, "-"),
+            ]
+        );
+        assert_eq!(
+            connections[3].remote_endpoint(),
+            "[2606:4700::6810:85e5]:443"
+        );
+        assert_eq!(connections[3].protocol, "IPv6");
+        assert_eq!(connections[1].process, "postgres");
+
+        let runner = FixtureRunner::new()
+            .with_stdout("lsof -i -P -n", include_str!("../fixtures/macos/lsof.txt"))
+            .with_stdout("ps -p 1088,48213 -o pid= -o comm=", "1088 Google Chrome\n");
+        let connections = try_get_connections_with(&runner, Platform::MacOs).unwrap();
+        assert_eq!(connections.len(), 2);
+        assert_eq!(connections[0].process, "Google Chrome");
+        assert_eq!(
+            (connections[1].local_port, connections[1].remote_port),
+            (3000, 61022)
+        );
+
+        let names = HashMap::from([("7712".to_string(), "Code.exe".to_string())]);
+        let netstat = include_str!("../fixtures/windows/netstat.txt").replace('\n', "\r\n");
+        let connections = parse_netstat_connections(&netstat, &names);
+        assert_eq!(connections.len(), 1);
+        assert_eq!(connections[0].remote_address, "20.42.65.92");
+        assert_eq!(connections[0].process, "Code.exe");
+    }
+
+    #[test]
+    fn test_get_process_commands_windows_fixture() {
+        let stdout = "\r\n\r\nCommandLine=\"C:\\Program Files\\PostgreSQL\\16\\bin\\postgres.exe\" -D data\r\nProcessId=3876\r\n\r\nCommandLine=\r\nProcessId=4\r\n";
+        let runner = FixtureRunner::new().with_stdout(
+            "wmic process where ProcessId=3876 or ProcessId=4 get CommandLine,ProcessId /value",
+            stdout,
+        );
+        let commands = get_process_commands_with(&runner, Platform::Windows, &["4", "3876", "-"]);
+        assert_eq!(commands.len(), 1);
+        assert!(commands["3876"].ends_with("postgres.exe\" -D data"));
+    }
+
+    #[test]
+    fn test_scan_ports_grouped_returns_valid_structure() {
+        let groups = scan_ports_grouped();
+        // 检查返回非空或有效的空结果
+        // 每个组应有进程名、PID和端口列表
+        for group in &groups {
+            assert!(!group.process.is_empty());
+            assert!(!group.pid.is_empty());
+            assert!(!group.ports.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_filter_ports_by_port() {
+        // 创建模拟数据
+        let _ports = vec![
+            PortInfo {
+                port: 8080,
+                protocol: "tcp".to_string(),
+                address: "127.0.0.1".to_string(),
+                pid: "1234".to_string(),
+                process: "node".to_string(),
+                user: "user".to_string(),
+                command: Some("node /app".to_string()),
+            },
+            PortInfo {
+                port: 3000,
+                protocol: "tcp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "5678".to_string(),
+                process: "node".to_string(),
+                user: "user".to_string(),
+                command: None,
+            },
+        ];
+
+        // 测试按端口过滤 - 使用实际系统数据
+        let all_ports = get_listening_ports_raw();
+        if !all_ports.is_empty() {
+            let target_port = all_ports[0].port;
+            let filtered = filter_ports(Some(target_port), None, false);
+            assert!(!filtered.is_empty());
+            assert!(filtered.iter().all(|p| p.port == target_port));
+        }
+    }
+
+    #[test]
+    fn test_filter_ports_by_app() {
+        // 使用实际系统数据测试应用过滤
+        let all_ports = get_listening_ports_raw();
+        if !all_ports.is_empty() {
+            let target_process = all_ports[0].process.clone();
+            let filtered = filter_ports(None, Some(target_process.clone()), false);
+            // 验证结果包含目标进程名
+            assert!(filtered.iter().all(|p| p.process.to_lowercase().contains(&target_process.to_lowercase())));
+        }
+    }
+
+    #[test]
+    fn test_filter_ports_exclude_system() {
+        // 测试系统进程过滤逻辑
+        let _ports = vec![
+            PortInfo {
+                port: 80,
+                protocol: "tcp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "1".to_string(),
+                process: "launchd".to_string(),
+                user: "root".to_string(),
+                command: None,
+            },
+        ];
+
+        // 在macOS上测试系统进程过滤
+        #[cfg(target_os = "macos")]
+        {
+            let _all_ports = get_listening_ports_raw();
+            let filtered = filter_ports(None, None, true);
+            // 验证系统进程被过滤
+            assert!(!filtered.iter().any(|p| {
+                ["controlce", "rapportd", "netdisk_s", "mds", "launchd"]
+                    .contains(&p.process.to_lowercase().as_str())
+            }));
+        }
+    }
+
+    #[test]
+    fn test_port_info_serialization() {
+        let port = PortInfo {
+            port: 8080,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "test".to_string(),
+            user: "user".to_string(),
+            command: Some("command".to_string()),
+        };
+
+        // 测试序列化和反序列化
+        let serialized = serde_json::to_string(&port).unwrap();
+        let deserialized: PortInfo = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.port, port.port);
+        assert_eq!(deserialized.process, port.process);
+    }
+
+    #[test]
+    fn test_scan_result_serialization() {
+        let result = ScanResult {
+            scan_time: "2024-01-01 12:00:00".to_string(),
+            total_ports: 2,
+            unique_apps: 1,
+            ports: vec![
+                PortInfo {
+                    port: 8080,
+                    protocol: "tcp".to_string(),
+                    address: "127.0.0.1".to_string(),
+                    pid: "1234".to_string(),
+                    process: "test".to_string(),
+                    user: "user".to_string(),
+                    command: None,
+                },
+            ],
+        };
+
+        let serialized = serde_json::to_string(&result).unwrap();
+        let deserialized: ScanResult = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.total_ports, 2);
+        assert_eq!(deserialized.unique_apps, 1);
+    }
+
+    #[test]
+    fn test_app_group_serialization() {
+        let group = AppGroup {
+            process: "test".to_string(),
+            pid: "1234".to_string(),
+            ports: vec![8080, 3000],
+            command: Some("test command".to_string()),
+        };
+
+        let serialized = serde_json::to_string(&group).unwrap();
+        let deserialized: AppGroup = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.process, "test");
+        assert_eq!(deserialized.ports.len(), 2);
+    }
+
+    #[test]
+    fn test_port_info_clone() {
+        let port = PortInfo {
+            port: 8080,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "test".to_string(),
+            user: "user".to_string(),
+            command: Some("command".to_string()),
+        };
+
+        let cloned = port.clone();
+        assert_eq!(cloned.port, port.port);
+        assert_eq!(cloned.process, port.process);
+        assert_eq!(cloned.command, port.command);
+    }
+
+    #[test]
+    fn test_get_listening_ports_raw_returns_vec() {
+        let ports = get_listening_ports_raw();
+        // 验证返回的是Vec类型
+        let _ports: Vec<PortInfo> = ports;
+        // 如果有端口，验证其结构
+        let ports = get_listening_ports_raw();
+        for port in &ports {
+            assert!(port.port > 0);
+            assert!(!port.protocol.is_empty());
+            assert!(!port.pid.is_empty());
+            assert!(!port.process.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_filter_ports_empty_result() {
+        // 测试过滤不存在的端口
+        let filtered = filter_ports(Some(60000), None, false);
+        assert_eq!(filtered.len(), 0);
+    }
+
+    #[test]
+    fn test_filter_ports_no_filters() {
+        // 不带任何过滤条件
+        let ports = get_listening_ports_raw();
+        let filtered = filter_ports(None, None, false);
+        assert_eq!(filtered.len(), ports.len());
+    }
+
+    #[test]
+    fn test_app_group_structure() {
+        let groups = scan_ports_grouped();
+        for group in &groups {
+            // 验证每个组都有有效的进程名和PID
+            assert!(!group.process.is_empty());
+            assert!(!group.pid.is_empty());
+            // 验证端口列表已排序且去重
+            let mut sorted_ports = group.ports.clone();
+            sorted_ports.sort();
+            sorted_ports.dedup();
+            assert_eq!(group.ports, sorted_ports);
+        }
+    }
+
+    #[test]
+    fn test_unique_apps_count() {
+        let result = scan_ports(false, false);
+        let unique_pids: std::collections::HashSet<_> =
+            result.ports.iter().map(|p| &p.pid).collect();
+        assert_eq!(result.unique_apps, unique_pids.len());
+    }
+}
@@ -0,0 +1,825 @@
+//! 无界面守护进程：REST API
+//!
+//! `portly-cli serve --listen 127.0.0.1:7070` 启动的 HTTP/1.1 服务，把本机端口扫描、过滤、
+//! 远程扫描、Docker 和扫描历史以 JSON 暴露给家庭服务器上的仪表盘或远程 GUI。
+//! 与 Docker Engine API 客户端一样只用标准库实现：每个连接一个线程，处理一个请求后关闭。
+//! 同时存活的连接数有上限（WebSocket 长连接也占名额），超出的连接直接返回 503。
+//!
+//! 除 `GET /api/health` 外的接口都要求 `Authorization: Bearer <token>`。
+//!
+//! 守护进程同时运行后台监控，`GET /api/events` 升级为 WebSocket 后实时推送监控事件
+//! （见 [`crate::websocket`]）；浏览器无法为 WebSocket 设置请求头，该接口也接受 `?token=`
+
+use crate::app_error::{AppError, AppResult, PortlyErrorKind};
+use crate::output_schema::{JsonRemoteScanOutput, JSON_SCHEMA_VERSION};
+use crate::websocket::{self, EventHub};
+use crate::{config, core, docker, export, latency, monitor, network, ownership, port_forward};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 默认监听地址，只接受本机连接
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7070";
+
+/// 请求体上限
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// 请求头行数上限
+const MAX_HEADER_LINES: usize = 100;
+/// 读取请求的超时
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// 同时处理的连接上限
+const MAX_CONNECTIONS: usize = 64;
+/// 拒绝连接时写入 503 响应的超时，避免接受线程被慢客户端拖住
+const REJECT_WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// 没有新连接时检查停止标志的间隔
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// WebSocket 连接没有事件时发送 Ping 的间隔，及时发现已断开的客户端
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// WebSocket 推送事件的路径
+const EVENTS_PATH: &str = "/api/events";
+
+/// 推送的事件名，与桌面端事件一致
+const MONITOR_UPDATE_EVENT: &str = "monitor-update";
+const MONITOR_ALERT_EVENT: &str = "monitor-alert";
+const RULE_HIT_EVENT: &str = "rule-hit";
+
+/// 需要鉴权的接口，用于区分 404 和 405
+const ROUTES: &[(&str, &str)] = &[
+    ("GET", "/api/ports"),
+    ("GET", "/api/ports/grouped"),
+    ("GET", "/api/ports/filter"),
+    ("GET", "/api/ports/ownership"),
+    ("GET", "/api/scan"),
+    ("GET", "/api/docker/containers"),
+    ("GET", "/api/docker/compose"),
+    ("GET", "/api/history"),
+    ("GET", "/api/history/entry"),
+    ("POST", "/api/history/search"),
+    ("GET", "/api/history/timeline"),
+    ("GET", "/api/monitor"),
+    ("GET", "/api/devices/latency"),
+    ("GET", "/api/reflect"),
+    ("GET", EVENTS_PATH),
+];
+
+/// 守护进程配置
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    pub listen: SocketAddr,
+    pub token: String,
+}
+
+/// 解析后的 HTTP 请求
+#[derive(Debug, Default)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    /// 请求头，名称统一为小写
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+    /// 请求方地址，端口转发检测默认回连该地址
+    pub peer: Option<IpAddr>,
+}
+
+impl Request {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .get(name)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// 布尔查询参数：`1` / `true` / `yes` 为真
+    fn flag(&self, name: &str) -> bool {
+        matches!(
+            self.param(name).map(str::to_ascii_lowercase).as_deref(),
+            Some("1" | "true" | "yes")
+        )
+    }
+}
+
+/// JSON 响应
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+/// 非 `PortlyError` 的错误响应体，与 `PortlyError` 的序列化结构一致
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    kind: &'a str,
+    message: String,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self { status, body },
+            Err(err) => Self::from_error(&AppError::internal(format!("响应序列化失败：{err}"))),
+        }
+    }
+
+    fn ok<T: Serialize>(value: &T) -> Self {
+        Self::json(200, value)
+    }
+
+    fn error(status: u16, kind: &str, message: impl Into<String>) -> Self {
+        Self::json(
+            status,
+            &ErrorBody {
+                kind,
+                message: message.into(),
+            },
+        )
+    }
+
+    fn from_error(err: &AppError) -> Self {
+        let status = match err.kind() {
+            PortlyErrorKind::Validation => 400,
+            PortlyErrorKind::PermissionDenied => 403,
+            PortlyErrorKind::Cancelled => 409,
+            PortlyErrorKind::HostUnreachable => 502,
+            PortlyErrorKind::ToolMissing => 503,
+            PortlyErrorKind::Timeout => 504,
+            PortlyErrorKind::CommandFailed | PortlyErrorKind::Internal => 500,
+        };
+        let body = serde_json::to_string(err).unwrap_or_default();
+        Self { status, body }
+    }
+}
+
+/// 监听并处理请求，直到 `stop` 被置位；同时运行后台监控，把每轮结果推送给 WebSocket 客户端
+pub fn serve(config: &DaemonConfig, stop: &AtomicBool) -> AppResult<()> {
+    let listener = TcpListener::bind(config.listen)
+        .map_err(|err| AppError::internal(format!("无法监听 {}：{err}", config.listen)))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|err| AppError::internal(format!("无法监听 {}：{err}", config.listen)))?;
+    tracing::info!(listen = %config.listen, "REST API 已启动");
+
+    let token: Arc<str> = Arc::from(config.token.as_str());
+    let hub = Arc::new(EventHub::new());
+    let limit = Arc::new(ConnectionLimit::new(MAX_CONNECTIONS));
+    std::thread::scope(|scope| {
+        scope.spawn(|| monitor::watch(stop, |tick| publish_tick(&hub, tick)));
+        while !stop.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, peer)) => match limit.try_acquire() {
+                    Some(permit) => {
+                        let (token, hub) = (token.clone(), hub.clone());
+                        std::thread::spawn(move || {
+                            let _permit = permit;
+                            handle_connection(stream, peer, &token, &hub)
+                        });
+                    }
+                    None => reject_connection(stream, peer),
+                },
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(err) => {
+                    tracing::warn!(error = %err, "接受连接失败");
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+    });
+    hub.close();
+    tracing::info!("REST API 已停止");
+    Ok(())
+}
+
+/// 计数信号量，限制同时存活的连接线程数
+struct ConnectionLimit {
+    active: AtomicUsize,
+    max: usize,
+}
+
+impl ConnectionLimit {
+    fn new(max: usize) -> Self {
+        Self {
+            active: AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    /// 还有名额时占用一个，名额在许可释放时归还
+    fn try_acquire(self: &Arc<Self>) -> Option<ConnectionPermit> {
+        self.active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < self.max).then_some(active + 1)
+            })
+            .ok()
+            .map(|_| ConnectionPermit(self.clone()))
+    }
+}
+
+/// 连接名额，连接线程结束时释放
+struct ConnectionPermit(Arc<ConnectionLimit>);
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// 连接数已达上限：在接受线程上直接返回 503，不再为其创建线程
+fn reject_connection(mut stream: TcpStream, peer: SocketAddr) {
+    tracing::warn!(%peer, max = MAX_CONNECTIONS, "连接数已达上限，拒绝连接");
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_write_timeout(Some(REJECT_WRITE_TIMEOUT));
+    let response = Response::error(503, "unavailable", "连接数已达上限，请稍后重试");
+    let _ = write_response(&mut stream, &response);
+}
+
+/// 生成随机访问令牌（32 位十六进制）
+///
+/// `RandomState` 的密钥取自系统随机源，两次 SipHash 输出拼成 128 位
+pub fn generate_token() -> String {
+    let state = std::collections::hash_map::RandomState::new();
+    let mut token = String::with_capacity(32);
+    for salt in 0..2u8 {
+        let mut hasher = state.build_hasher();
+        hasher.write_u8(salt);
+        hasher.write_u128(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default(),
+        );
+        token.push_str(&format!("{:016x}", hasher.finish()));
+    }
+    token
+}
+
+/// 把一轮监控结果发布为快照、提醒和规则触发事件
+fn publish_tick(hub: &EventHub, tick: &monitor::MonitorTick) {
+    hub.publish(MONITOR_UPDATE_EVENT, &tick.snapshot);
+    for alert in &tick.alerts {
+        hub.publish(MONITOR_ALERT_EVENT, alert);
+    }
+    for hit in &tick.rule_hits {
+        hub.publish(RULE_HIT_EVENT, hit);
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, peer: SocketAddr, token: &str, hub: &EventHub) {
+    // BSD 系统上 accept 得到的连接会继承监听套接字的非阻塞模式
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let start = Instant::now();
+    let request = match stream.try_clone() {
+        Ok(reader) => parse_request(&mut BufReader::new(reader)).map(|request| Request {
+            peer: Some(peer.ip()),
+            ..request
+        }),
+        Err(err) => Err(err.to_string()),
+    };
+    let (method, path, response) = match request {
+        Ok(request)
+            if request.method == "GET"
+                && request.path == EVENTS_PATH
+                && websocket::is_upgrade(&request.headers)
+                && is_authorized(&request, token) =>
+        {
+            return stream_events(stream, peer, &request, hub);
+        }
+        Ok(request) => {
+            let response = handle(&request, token);
+            (request.method, request.path, response)
+        }
+        Err(message) => (
+            String::new(),
+            String::new(),
+            Response::error(400, "bad_request", message),
+        ),
+    };
+    tracing::debug!(
+        %peer,
+        method,
+        path,
+        status = response.status,
+        elapsed_ms = start.elapsed().as_millis() as u64,
+        "REST 请求"
+    );
+    if let Err(err) = write_response(&mut stream, &response) {
+        tracing::debug!(%peer, error = %err, "REST 响应写入失败");
+    }
+}
+
+/// 完成 WebSocket 握手后持续推送事件，直到客户端关闭连接或写入失败
+fn stream_events(mut stream: TcpStream, peer: SocketAddr, request: &Request, hub: &EventHub) {
+    let key = request
+        .headers
+        .get("sec-websocket-key")
+        .map(String::as_str)
+        .unwrap_or_default();
+    if let Err(err) = websocket::write_handshake(&mut stream, key) {
+        tracing::debug!(%peer, error = %err, "WebSocket 握手失败");
+        return;
+    }
+    let events = hub.subscribe();
+    tracing::info!(%peer, subscribers = hub.subscriber_count(), "WebSocket 已连接");
+
+    // 读取线程只用于发现客户端关闭；服务端不需要读取业务数据
+    let closed = Arc::new(AtomicBool::new(false));
+    let _ = stream.set_read_timeout(None);
+    if let Ok(mut reader) = stream.try_clone() {
+        let closed = closed.clone();
+        std::thread::spawn(move || {
+            while let Ok((opcode, _)) = websocket::read_frame(&mut reader) {
+                if opcode == websocket::OPCODE_CLOSE {
+                    break;
+                }
+            }
+            closed.store(true, Ordering::Relaxed);
+        });
+    }
+
+    // 新连接先收到最近一轮的快照，不必等到下一轮监控
+    let initial = monitor::latest_snapshot()
+        .and_then(|snapshot| websocket::event_message(MONITOR_UPDATE_EVENT, &snapshot));
+    if let Some(message) = initial {
+        let _ = websocket::write_frame(&mut stream, websocket::OPCODE_TEXT, message.as_bytes());
+    }
+    let mut last_sent = Instant::now();
+    while !closed.load(Ordering::Relaxed) {
+        let sent = match events.recv_timeout(ACCEPT_POLL_INTERVAL) {
+            Ok(message) => {
+                websocket::write_frame(&mut stream, websocket::OPCODE_TEXT, message.as_bytes())
+            }
+            Err(RecvTimeoutError::Timeout) if last_sent.elapsed() >= PING_INTERVAL => {
+                websocket::write_frame(&mut stream, websocket::OPCODE_PING, &[])
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if sent.is_err() {
+            break;
+        }
+        last_sent = Instant::now();
+    }
+    let _ = websocket::write_frame(&mut stream, websocket::OPCODE_CLOSE, &[]);
+    let _ = stream.shutdown(std::net::Shutdown::Both);
+    tracing::info!(%peer, "WebSocket 已断开");
+}
+
+/// 解析请求行、请求头和请求体
+fn parse_request(reader: &mut impl BufRead) -> Result<Request, String> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|err| format!("读取请求失败：{err}"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err("请求行格式不正确".to_string());
+    };
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = Request {
+        method: method.to_ascii_uppercase(),
+        path: percent_decode(path),
+        query: parse_query(query),
+        ..Default::default()
+    };
+
+    for _ in 0..MAX_HEADER_LINES {
+        line.clear();
+        reader
+            .read_line(&mut line)
+            .map_err(|err| format!("读取请求头失败：{err}"))?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            request
+                .headers
+                .insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = match request.headers.get("content-length") {
+        Some(value) => value
+            .parse::<usize>()
+            .map_err(|_| "Content-Length 不正确".to_string())?,
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(format!("请求体超过 {MAX_BODY_BYTES} 字节"));
+    }
+    request.body = vec![0; length];
+    reader
+        .read_exact(&mut request.body)
+        .map_err(|err| format!("读取请求体失败：{err}"))?;
+    Ok(request)
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+/// 解码 `%XX` 和表示空格的 `+`
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = |b: u8| (b as char).to_digit(16);
+                match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                    (Some(high), Some(low)) => {
+                        decoded.push((high * 16 + low) as u8);
+                        i += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn write_response(stream: &mut TcpStream, response: &Response) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nAccess-Control-Allow-Headers: Authorization, Content-Type\r\n\
+         Access-Control-Allow-Methods: GET, POST, OPTIONS\r\nConnection: close\r\n\r\n",
+        response.status,
+        status_text(response.status),
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(response.body.as_bytes())?;
+    stream.flush()
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Internal Server Error",
+    }
+}
+
+/// 处理一个请求：健康检查和 CORS 预检无需令牌，其余接口先校验令牌
+pub fn handle(request: &Request, token: &str) -> Response {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("OPTIONS", _) => {
+            return Response {
+                status: 204,
+                body: String::new(),
+            }
+        }
+        ("GET", "/api/health") => {
+            return Response::ok(&serde_json::json!({
+                "status": "ok",
+                "version": env!("CARGO_PKG_VERSION"),
+            }))
+        }
+        _ => {}
+    }
+
+    if !ROUTES.iter().any(|(_, path)| *path == request.path) {
+        return Response::error(404, "not_found", format!("未知接口：{}", request.path));
+    }
+    if !is_authorized(request, token) {
+        return Response::error(401, "unauthorized", "缺少或错误的访问令牌");
+    }
+    if !ROUTES.contains(&(request.method.as_str(), request.path.as_str())) {
+        return Response::error(
+            405,
+            "method_not_allowed",
+            format!("{} 不支持 {}", request.path, request.method),
+        );
+    }
+
+    route(request).unwrap_or_else(|err| Response::from_error(&err))
+}
+
+/// 比较 `Authorization: Bearer <token>`（事件接口也可用 `?token=`），逐字节异或避免按前缀提前返回
+fn is_authorized(request: &Request, token: &str) -> bool {
+    let Some(provided) = request
+        .headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or_else(|| {
+            (request.path == EVENTS_PATH)
+                .then(|| request.query.get("token").map(String::as_str))
+                .flatten()
+        })
+    else {
+        return false;
+    };
+    let (provided, expected) = (provided.trim().as_bytes(), token.as_bytes());
+    !expected.is_empty()
+        && provided.len() == expected.len()
+        && provided
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn route(request: &Request) -> AppResult<Response> {
+    let response = match request.path.as_str() {
+        "/api/ports" => Response::ok(&core::try_scan_ports(
+            request.flag("command"),
+            request.flag("udp"),
+        )?),
+        "/api/ports/grouped" => Response::ok(&core::try_scan_ports_grouped()?),
+        "/api/ports/ownership" => Response::ok(&ownership::listener_ownership()?),
+        "/api/ports/filter" => {
+            let port = match request.param("port") {
+                Some(value) => Some(parse_port(value)?),
+                None => None,
+            };
+            Response::ok(&core::filter_ports(
+                port,
+                request.param("app").map(str::to_string),
+                request.flag("exclude_system"),
+            ))
+        }
+        "/api/scan" => remote_scan(request)?,
+        "/api/monitor" => Response::ok(&monitor::latest_snapshot()),
+        "/api/devices/latency" => Response::ok(&latency::device_latency()),
+        "/api/reflect" => reflect(request)?,
+        EVENTS_PATH => Response::error(400, "bad_request", "需要 WebSocket 升级请求"),
+        "/api/docker/containers" => Response::ok(&docker::try_get_docker_containers()?),
+        "/api/docker/compose" => Response::ok(&docker::get_compose_groups()),
+        "/api/history" => Response::ok(&export::get_history_summary()),
+        "/api/history/entry" => {
+            let timestamp = request
+                .param("timestamp")
+                .ok_or_else(|| AppError::validation("timestamp", "不能为空"))?;
+            match export::load_history_entry(timestamp) {
+                Ok(entry) => Response::ok(&entry),
+                Err(message) => Response::error(404, "not_found", message),
+            }
+        }
+        "/api/history/timeline" => {
+            let port = request
+                .param("port")
+                .ok_or_else(|| AppError::validation("port", "不能为空"))?;
+            Response::ok(&export::port_timeline(parse_port(port)?))
+        }
+        "/api/history/search" => {
+            let query: export::HistorySearchQuery = serde_json::from_slice(&request.body)
+                .map_err(|err| AppError::validation("请求体", err.to_string()))?;
+            let matches = export::search_history(&query)
+                .map_err(|message| AppError::validation("查询条件", message))?;
+            Response::ok(&matches)
+        }
+        _ => Response::error(404, "not_found", format!("未知接口：{}", request.path)),
+    };
+    Ok(response)
+}
+
+fn parse_port(value: &str) -> AppResult<u16> {
+    match value.parse::<u16>() {
+        Ok(port) if port > 0 => Ok(port),
+        _ => Err(AppError::validation("port", "需要 1-65535 的端口号")),
+    }
+}
+
+/// `GET /api/reflect?port=&host=&timeout=`：从守护进程一侧回连 `host:port`，未给出 host 时
+/// 回连请求方地址，供 NAT 内的 Portly 检测端口转发
+fn reflect(request: &Request) -> AppResult<Response> {
+    let port = request
+        .param("port")
+        .ok_or_else(|| AppError::validation("port", "不能为空"))?;
+    let port = parse_port(port)?;
+    let host = match request.param("host") {
+        Some(host) if !host.trim().is_empty() => host.trim().to_string(),
+        _ => request
+            .peer
+            .map(|ip| ip.to_canonical().to_string())
+            .ok_or_else(|| AppError::validation("host", "不能为空"))?,
+    };
+    let timeout_ms = match request.param("timeout") {
+        Some(value) => match value.parse::<u64>() {
+            Ok(ms) if (1..=port_forward::MAX_REFLECT_TIMEOUT_MS).contains(&ms) => ms,
+            _ => return Err(AppError::validation("timeout", "需要 1-10000 的毫秒数")),
+        },
+        None => port_forward::DEFAULT_REFLECT_TIMEOUT_MS,
+    };
+    let result = port_forward::reflect(&host, port, timeout_ms);
+    Ok(Response::ok(&result))
+}
+
+/// `GET /api/scan?target=&ports=&timeout=&all=`，返回与 `portly-cli scan -j` 相同的结构
+fn remote_scan(request: &Request) -> AppResult<Response> {
+    let target = request
+        .param("target")
+        .ok_or_else(|| AppError::validation("target", "不能为空"))?;
+    let timeout_ms = match request.param("timeout") {
+        Some(value) => Some(
+            value
+                .parse::<u64>()
+                .map_err(|_| AppError::validation("timeout", "需要 1-60000 的毫秒数"))?,
+        ),
+        None => None,
+    };
+    let output = remote_scan_output(
+        target,
+        request.param("ports"),
+        timeout_ms,
+        request.flag("all"),
+    )?;
+    Ok(Response::ok(&output))
+}
+
+/// 远程端口扫描，返回与 `portly-cli scan -j` 相同结构的 JSON；REST 和 JSON-RPC 接口共用。
+/// 端口描述默认 `top100`，也可以是配置中的端口组合名；超时默认取配置
+pub(crate) fn remote_scan_output(
+    target: &str,
+    spec: Option<&str>,
+    timeout_ms: Option<u64>,
+    all: bool,
+) -> AppResult<serde_json::Value> {
+    let config = config::load_config().unwrap_or_default();
+    let timeout_ms = match timeout_ms {
+        Some(ms) if (1..=60_000).contains(&ms) => ms,
+        Some(_) => return Err(AppError::validation("timeout", "需要 1-60000 的毫秒数")),
+        None => config.timeouts.scan_ms,
+    };
+    let spec = spec.unwrap_or("top100");
+    let spec = config
+        .port_profiles
+        .get(spec)
+        .map(String::as_str)
+        .unwrap_or(spec);
+    let ports =
+        network::parse_port_spec(spec).map_err(|message| AppError::validation("ports", message))?;
+    let resolved = network::resolve_target(target)?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| AppError::internal(format!("无法启动异步运行时：{err}")))?;
+    let started = Instant::now();
+    let mut results = runtime.block_on(network::scan_ports_async(&resolved.ip, &ports, timeout_ms));
+    let elapsed_ms = started.elapsed().as_millis();
+    let open_ports = results.iter().filter(|r| r.is_open).count();
+    if !all {
+        results.retain(|r| r.is_open);
+    }
+
+    let tags = crate::inventory::lookup(&[target, &resolved.ip]).tags;
+    serde_json::to_value(JsonRemoteScanOutput {
+        schema_version: JSON_SCHEMA_VERSION,
+        target,
+        ip: &resolved.ip,
+        scanned_ports: ports.len(),
+        open_ports,
+        elapsed_ms,
+        ports: &results,
+        tags: &tags,
+    })
+    .map_err(|err| AppError::internal(format!("响应序列化失败：{err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, path: &str, token: Option<&str>) -> Request {
+        let mut request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        };
+        if let Some(token) = token {
+            request
+                .headers
+                .insert("authorization".to_string(), format!("Bearer {token}"));
+        }
+        request
+    }
+
+    #[test]
+    fn test_parse_request_reads_query_headers_and_body() {
+        let raw = "POST /api/history/search?app=my%20app&all=1 HTTP/1.1\r\n\
+                   Host: localhost\r\nAuthorization: Bearer abc\r\nContent-Length: 13\r\n\r\n\
+                   {\"port\":8080}";
+        let request = parse_request(&mut std::io::Cursor::new(raw)).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/api/history/search");
+        assert_eq!(request.param("app"), Some("my app"));
+        assert!(request.flag("all"));
+        assert_eq!(request.headers["authorization"], "Bearer abc");
+        assert_eq!(request.body, b"{\"port\":8080}");
+
+        assert!(parse_request(&mut std::io::Cursor::new("\r\n")).is_err());
+    }
+
+    #[test]
+    fn test_handle_requires_token() {
+        let health = handle(&request("GET", "/api/health", None), "secret");
+        assert_eq!(health.status, 200);
+        assert!(health.body.contains("\"ok\""));
+
+        assert_eq!(
+            handle(&request("GET", "/api/ports", None), "secret").status,
+            401
+        );
+        assert_eq!(
+            handle(&request("GET", "/api/ports", Some("secreT")), "secret").status,
+            401
+        );
+        assert_eq!(
+            handle(&request("GET", "/api/ports", Some("")), "").status,
+            401
+        );
+        assert_eq!(
+            handle(&request("GET", "/api/nope", Some("secret")), "secret").status,
+            404
+        );
+        assert_eq!(
+            handle(&request("DELETE", "/api/ports", Some("secret")), "secret").status,
+            405
+        );
+    }
+
+    #[test]
+    fn test_handle_maps_validation_errors_to_400() {
+        let response = handle(&request("GET", "/api/scan", Some("secret")), "secret");
+        assert_eq!(response.status, 400);
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["kind"], "validation");
+
+        let mut filter = request("GET", "/api/ports/filter", Some("secret"));
+        filter.query.insert("port".to_string(), "0".to_string());
+        assert_eq!(handle(&filter, "secret").status, 400);
+
+        let timeline = request("GET", "/api/history/timeline", Some("secret"));
+        assert_eq!(handle(&timeline, "secret").status, 400);
+    }
+
+    #[test]
+    fn test_reflect_connects_back_to_peer() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = server.local_addr().unwrap().port();
+        let mut reflect = request("GET", "/api/reflect", Some("secret"));
+        reflect.query.insert("port".to_string(), port.to_string());
+        assert_eq!(handle(&reflect, "secret").status, 400);
+
+        reflect.peer = Some("127.0.0.1".parse().unwrap());
+        let response = handle(&reflect, "secret");
+        assert_eq!(response.status, 200);
+        let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(body["host"], "127.0.0.1");
+        assert_eq!(body["reachable"], true);
+
+        reflect.query.insert("timeout".to_string(), "0".to_string());
+        assert_eq!(handle(&reflect, "secret").status, 400);
+    }
+
+    #[test]
+    fn test_connection_limit_releases_permits_on_drop() {
+        let limit = Arc::new(ConnectionLimit::new(2));
+        let first = limit.try_acquire().unwrap();
+        let _second = limit.try_acquire().unwrap();
+        assert!(limit.try_acquire().is_none());
+
+        drop(first);
+        assert!(limit.try_acquire().is_some());
+    }
+
+    #[test]
+    fn test_generate_token_is_random_hex() {
+        let token = generate_token();
+        assert_eq!(token.len(), 32);
+        assert!(token.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(token, generate_token());
+    }
+}
@@ -0,0 +1,551 @@
+//! 演示模式
+//!
+//! 开启后本机端口扫描、本机连接、网络接口、局域网发现、远程端口扫描、Ping、Traceroute、服务探测、
+//! 主机名解析、容器列表和防火墙状态都返回 `fixtures/demo/*.json` 中打包的示例数据，
+//! 不执行系统命令也不建立网络连接，便于演示、截图和在没有网络的 CI 机器上做界面测试。
+//! 终止进程在演示模式下不会执行，只返回提示。
+//!
+//! 设置环境变量 `PORTLY_DEMO=1`、使用 CLI 的 `--demo`，或在桌面端调用
+//! `tauri_set_demo_mode` 开启
+
+use crate::core::{ConnectionInfo, PortInfo};
+use crate::docker::{ContainerRuntime, DockerContainer, RuntimeKind};
+use crate::firewall::FirewallStatus;
+use crate::i18n::{current_lang, tr_args, Msg};
+use crate::iface_stats::InterfaceStats;
+use crate::network::{
+    NetworkDevice, NetworkInterface, PingOneResult, PingResult, RemotePort, RouteEntry,
+    ServiceInfo, TraceHop, TracerouteResult,
+};
+use crate::process::KillResult;
+use crate::vpn::VpnPeer;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// 开启演示模式的环境变量
+pub const DEMO_ENV: &str = "PORTLY_DEMO";
+
+const UNSET: u8 = 0;
+const OFF: u8 = 1;
+const ON: u8 = 2;
+
+/// 显式设置的开关；未设置时按环境变量判断
+static STATE: AtomicU8 = AtomicU8::new(UNSET);
+
+static DATA: OnceLock<DemoData> = OnceLock::new();
+
+/// 打包的示例数据
+struct DemoData {
+    ports: Vec<PortInfo>,
+    connections: Vec<ConnectionInfo>,
+    network: DemoNetwork,
+    containers: Vec<DockerContainer>,
+    security: DemoSecurity,
+}
+
+#[derive(Debug, Deserialize)]
+struct DemoNetwork {
+    interfaces: Vec<NetworkInterface>,
+    hosts: Vec<DemoHost>,
+    #[serde(default)]
+    vpn_peers: Vec<VpnPeer>,
+    #[serde(default)]
+    routes: Vec<RouteEntry>,
+    #[serde(default)]
+    interface_stats: Vec<DemoInterfaceStats>,
+}
+
+/// 示例网卡统计；计数器按 `*_per_sec` 随时间增长，采样时能看到吞吐量
+#[derive(Debug, Deserialize)]
+struct DemoInterfaceStats {
+    #[serde(flatten)]
+    stats: InterfaceStats,
+    #[serde(default)]
+    rx_per_sec: u64,
+    #[serde(default)]
+    tx_per_sec: u64,
+}
+
+/// 示例主机；有 MAC 地址的是局域网设备，其余只用于远程扫描、Ping 和 Traceroute
+#[derive(Debug, Deserialize)]
+struct DemoHost {
+    ip: String,
+    #[serde(default)]
+    hostname: Option<String>,
+    #[serde(default)]
+    mac: Option<String>,
+    latency_ms: f32,
+    #[serde(default)]
+    open_ports: Vec<u16>,
+    #[serde(default)]
+    services: Vec<ServiceInfo>,
+}
+
+/// 安全审计用到的示例状态
+#[derive(Debug, Deserialize)]
+struct DemoSecurity {
+    firewall: FirewallStatus,
+    /// 无需认证即可访问的本机端口
+    #[serde(default)]
+    unauthenticated_ports: Vec<u16>,
+}
+
+fn data() -> &'static DemoData {
+    DATA.get_or_init(|| DemoData {
+        ports: parse_fixture("ports.json", include_str!("../fixtures/demo/ports.json")),
+        connections: parse_fixture(
+            "connections.json",
+            include_str!("../fixtures/demo/connections.json"),
+        ),
+        network: parse_fixture(
+            "network.json",
+            include_str!("../fixtures/demo/network.json"),
+        ),
+        containers: parse_fixture(
+            "containers.json",
+            include_str!("../fixtures/demo/containers.json"),
+        ),
+        security: parse_fixture(
+            "security.json",
+            include_str!("../fixtures/demo/security.json"),
+        ),
+    })
+}
+
+fn parse_fixture<T: serde::de::DeserializeOwned>(name: &str, json: &str) -> T {
+    serde_json::from_str(json).unwrap_or_else(|e| panic!("演示数据 {name} 无效: {e}"))
+}
+
+/// 是否处于演示模式
+pub fn is_enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        ON => true,
+        OFF => false,
+        _ => env_enabled(),
+    }
+}
+
+fn env_enabled() -> bool {
+    static ENV: OnceLock<bool> = OnceLock::new();
+    *ENV.get_or_init(|| {
+        std::env::var(DEMO_ENV)
+            .map(|value| !matches!(value.trim(), "" | "0" | "false" | "off"))
+            .unwrap_or(false)
+    })
+}
+
+/// 开启或关闭演示模式；切换后清空查询缓存，避免真实结果和示例数据混在一起
+pub fn set_enabled(enabled: bool) {
+    let state = if enabled { ON } else { OFF };
+    if STATE.swap(state, Ordering::Relaxed) != state {
+        crate::cache::shared().clear(None);
+    }
+    tracing::info!(enabled, "演示模式已切换");
+}
+
+/// 示例端口中 TCP（`udp` 为 false）或 UDP 的部分；命令行通过 [`process_commands`] 单独提供
+fn ports_of(udp: bool) -> Vec<PortInfo> {
+    data()
+        .ports
+        .iter()
+        .filter(|port| port.protocol.starts_with("UDP") == udp)
+        .cloned()
+        .map(|port| PortInfo {
+            command: None,
+            ..port
+        })
+        .collect()
+}
+
+/// 本机 TCP 监听端口
+pub fn listening_ports() -> Vec<PortInfo> {
+    ports_of(false)
+}
+
+/// 本机 UDP 套接字
+pub fn udp_ports() -> Vec<PortInfo> {
+    ports_of(true)
+}
+
+/// 本机 TCP 连接
+pub fn connections() -> Vec<ConnectionInfo> {
+    data().connections.clone()
+}
+
+/// 示例进程的命令行
+pub fn process_commands(pids: &[&str]) -> HashMap<String, String> {
+    data()
+        .ports
+        .iter()
+        .filter(|port| pids.contains(&port.pid.as_str()))
+        .filter_map(|port| Some((port.pid.clone(), port.command.clone()?)))
+        .collect()
+}
+
+/// 演示模式下不终止任何进程
+pub fn kill_process(pid: u32) -> KillResult {
+    KillResult {
+        success: false,
+        pid,
+        message: tr_args(current_lang(), Msg::KillDemoSkipped, &[&pid]),
+    }
+}
+
+/// 演示模式下按示例端口找到占用进程，但不终止
+pub fn kill_port_process(port: u16) -> KillResult {
+    match data()
+        .ports
+        .iter()
+        .find(|p| p.port == port)
+        .and_then(|p| p.pid.parse().ok())
+    {
+        Some(pid) => kill_process(pid),
+        None => KillResult {
+            success: false,
+            pid: 0,
+            message: tr_args(current_lang(), Msg::KillNoProcessOnPort, &[&port]),
+        },
+    }
+}
+
+pub fn interfaces() -> Vec<NetworkInterface> {
+    data().network.interfaces.clone()
+}
+
+pub fn current_subnet() -> Option<String> {
+    data().network.interfaces.first().map(|i| i.subnet.clone())
+}
+
+pub fn vpn_peers() -> Vec<VpnPeer> {
+    data().network.vpn_peers.clone()
+}
+
+pub fn routes() -> Vec<RouteEntry> {
+    data().network.routes.clone()
+}
+
+/// 示例网卡统计，计数器从第一次读取起按示例速率增长
+pub fn interface_stats() -> Vec<InterfaceStats> {
+    static STARTED: OnceLock<Instant> = OnceLock::new();
+    let elapsed = STARTED.get_or_init(Instant::now).elapsed().as_secs_f64();
+    data()
+        .network
+        .interface_stats
+        .iter()
+        .map(|demo| {
+            let mut stats = demo.stats.clone();
+            stats.counters.rx_bytes += (demo.rx_per_sec as f64 * elapsed) as u64;
+            stats.counters.tx_bytes += (demo.tx_per_sec as f64 * elapsed) as u64;
+            stats.counters.rx_packets += (demo.rx_per_sec as f64 * elapsed / 1200.0) as u64;
+            stats.counters.tx_packets += (demo.tx_per_sec as f64 * elapsed / 1200.0) as u64;
+            stats
+        })
+        .collect()
+}
+
+/// `targets` 中的局域网示例设备
+pub fn devices(targets: &HashSet<String>) -> Vec<NetworkDevice> {
+    data()
+        .network
+        .hosts
+        .iter()
+        .filter(|host| host.mac.is_some() && targets.contains(&host.ip))
+        .map(|host| NetworkDevice {
+            ip: host.ip.clone(),
+            mac: host.mac.clone(),
+            hostname: host.hostname.clone(),
+            is_online: true,
+            interface_index: None,
+            rtt_ms: Some(host.latency_ms),
+            tags: Vec::new(),
+            note: None,
+        })
+        .collect()
+}
+
+fn host(ip: &str) -> Option<&'static DemoHost> {
+    data().network.hosts.iter().find(|host| host.ip == ip)
+}
+
+pub fn hostname(ip: &str) -> Option<String> {
+    host(ip)?.hostname.clone()
+}
+
+/// 按示例主机名解析 IP
+pub fn resolve(name: &str) -> Option<String> {
+    data()
+        .network
+        .hosts
+        .iter()
+        .find(|host| {
+            host.hostname
+                .as_deref()
+                .is_some_and(|h| h.eq_ignore_ascii_case(name))
+        })
+        .map(|host| host.ip.clone())
+}
+
+/// 远程端口扫描；未知主机的端口全部关闭
+pub fn scan_ports(ip: &str, ports: &[u16]) -> Vec<RemotePort> {
+    let open = host(ip)
+        .map(|h| h.open_ports.as_slice())
+        .unwrap_or_default();
+    let policies = crate::policy::current();
+    ports
+        .iter()
+        .map(|&port| crate::network::remote_port(port, open.contains(&port), &policies))
+        .collect()
+}
+
+/// 服务探测；没有录制时按端口推断
+pub fn service(ip: &str, port: u16) -> ServiceInfo {
+    host(ip)
+        .and_then(|h| h.services.iter().find(|s| s.port == port).cloned())
+        .unwrap_or_else(|| ServiceInfo {
+            port,
+            service: crate::network::get_service_name(port)
+                .unwrap_or_else(|| "Unknown".to_string()),
+            service_type: crate::network::infer_service_type(port),
+            server: None,
+            content_type: None,
+            version_hints: Vec::new(),
+            security_headers: None,
+        })
+}
+
+/// 单次 Ping；延迟在示例值附近按序号小幅波动
+pub fn ping_one(ip: &str, seq: u32) -> PingOneResult {
+    match host(ip) {
+        Some(host) => {
+            let time = jitter(host.latency_ms, seq);
+            let ttl = if is_local(ip) { 64 } else { 54 };
+            PingOneResult {
+                ip: ip.to_string(),
+                seq,
+                success: true,
+                time_ms: Some(time),
+                ttl: Some(ttl),
+                line: format!("64 bytes from {ip}: icmp_seq={seq} ttl={ttl} time={time:.3} ms"),
+            }
+        }
+        None => PingOneResult {
+            ip: ip.to_string(),
+            seq,
+            success: false,
+            time_ms: None,
+            ttl: None,
+            line: "Request timeout".to_string(),
+        },
+    }
+}
+
+/// 连续 Ping，返回每次回复和汇总结果
+pub fn ping(ip: &str, count: u32) -> (Vec<PingOneResult>, PingResult) {
+    let replies: Vec<PingOneResult> = (1..=count)
+        .map(|seq| ping_one(ip, seq))
+        .filter(|reply| reply.success)
+        .collect();
+    let times: Vec<f32> = replies.iter().filter_map(|r| r.time_ms).collect();
+    let received = replies.len() as u32;
+    let mut raw_output: Vec<String> = replies.iter().map(|r| r.line.clone()).collect();
+    raw_output.push(format!(
+        "{count} packets transmitted, {received} packets received"
+    ));
+
+    let result = PingResult {
+        ip: ip.to_string(),
+        is_reachable: received > 0,
+        packets_sent: count,
+        packets_received: received,
+        packet_loss: if count == 0 {
+            0.0
+        } else {
+            (count - received) as f32 * 100.0 / count as f32
+        },
+        min_ms: times.iter().copied().reduce(f32::min),
+        avg_ms: (!times.is_empty()).then(|| times.iter().sum::<f32>() / times.len() as f32),
+        max_ms: times.iter().copied().reduce(f32::max),
+        raw_output: raw_output.join("\n"),
+    };
+    (replies, result)
+}
+
+/// Traceroute：局域网主机直达，其他主机经过网关和两跳运营商路由
+pub fn traceroute(ip: &str) -> TracerouteResult {
+    let mut hops = Vec::new();
+    if let Some(target) = host(ip) {
+        if !is_local(ip) {
+            let gateway = gateway();
+            let route = [
+                (
+                    gateway.map(|g| g.ip.as_str()),
+                    gateway.map(|g| g.latency_ms),
+                ),
+                (Some("100.64.0.1"), Some(8.4)),
+                (Some("72.14.215.85"), Some(target.latency_ms * 0.6)),
+            ];
+            for (ip, time) in route {
+                hops.push(TraceHop {
+                    hop: hops.len() as u32 + 1,
+                    ip: ip.map(String::from),
+                    hostname: None,
+                    time_ms: time,
+                });
+            }
+        }
+        hops.push(TraceHop {
+            hop: hops.len() as u32 + 1,
+            ip: Some(ip.to_string()),
+            hostname: target.hostname.clone(),
+            time_ms: Some(target.latency_ms),
+        });
+    } else {
+        hops.extend((1..=3).map(|hop| TraceHop {
+            hop,
+            ip: None,
+            hostname: None,
+            time_ms: None,
+        }));
+    }
+
+    let raw_output = hops
+        .iter()
+        .map(|hop| match (&hop.ip, hop.time_ms) {
+            (Some(ip), Some(time)) => format!("{:>2}  {ip}  {time:.3} ms", hop.hop),
+            _ => format!("{:>2}  *", hop.hop),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    TracerouteResult {
+        target: ip.to_string(),
+        hops,
+        raw_output,
+    }
+}
+
+pub fn containers() -> Vec<DockerContainer> {
+    data().containers.clone()
+}
+
+pub fn container_runtime() -> ContainerRuntime {
+    ContainerRuntime {
+        kind: RuntimeKind::DockerDesktop,
+        endpoint: "demo".to_string(),
+    }
+}
+
+pub fn firewall_status() -> FirewallStatus {
+    data().security.firewall.clone()
+}
+
+/// 示例本机端口上的服务是否无需认证
+pub fn accepts_unauthenticated(port: u16) -> bool {
+    data().security.unauthenticated_ports.contains(&port)
+}
+
+/// 是否在示例网卡的网段内
+fn is_local(ip: &str) -> bool {
+    data().network.interfaces.iter().any(|interface| {
+        crate::network::subnet_host_addresses(&interface.subnet, usize::MAX)
+            .is_some_and(|hosts| hosts.iter().any(|h| h == ip))
+    })
+}
+
+/// 第一个网段的 `.1` 主机视为网关
+fn gateway() -> Option<&'static DemoHost> {
+    let subnet = current_subnet()?;
+    let prefix = subnet.rsplit_once('.')?.0;
+    host(&format!("{prefix}.1"))
+}
+
+fn jitter(latency_ms: f32, seq: u32) -> f32 {
+    latency_ms * (0.92 + (seq * 7 % 5) as f32 * 0.04)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixtures_are_consistent() {
+        let ports = listening_ports();
+        assert!(ports.iter().all(|p| p.command.is_none()));
+        // 容器映射的端口都在本机监听端口中
+        for container in containers() {
+            for mapping in &container.ports {
+                assert!(ports.iter().any(|p| p.port == mapping.host_port));
+            }
+        }
+        let commands = process_commands(&["733", "999"]);
+        assert_eq!(commands.len(), 1);
+        assert!(commands["733"].contains("postgres"));
+        assert_eq!(current_subnet().as_deref(), Some("192.168.1.0/24"));
+        // 无需认证的示例服务只在本机回环上监听
+        assert!(accepts_unauthenticated(6379));
+        assert!(ports
+            .iter()
+            .filter(|p| p.port == 6379)
+            .all(|p| p.address == "127.0.0.1"));
+    }
+
+    #[test]
+    fn test_devices_and_remote_hosts() {
+        let targets: HashSet<String> =
+            crate::network::subnet_host_addresses("192.168.1.0/24", 1022)
+                .unwrap()
+                .into_iter()
+                .collect();
+        let devices = devices(&targets);
+        assert!(devices.len() >= 5);
+        assert!(devices.iter().all(|d| d.ip.starts_with("192.168.1.")));
+        assert_eq!(resolve("NAS.lan").as_deref(), Some("192.168.1.20"));
+
+        let ports = scan_ports("93.184.215.14", &[22, 80, 443]);
+        let open: Vec<u16> = ports.iter().filter(|p| p.is_open).map(|p| p.port).collect();
+        assert_eq!(open, vec![80, 443]);
+        assert!(scan_ports("10.9.9.9", &[80]).iter().all(|p| !p.is_open));
+        assert_eq!(service("192.168.1.88", 8123).service, "Home Assistant");
+        assert_eq!(service("10.9.9.9", 5432).service, "PostgreSQL");
+
+        // 示例 VPN 节点在线时也能作为远程主机扫描
+        let peers = vpn_peers();
+        assert_eq!(peers.len(), 3);
+        assert!(peers
+            .iter()
+            .filter(|p| p.online && p.hostname.is_some())
+            .all(|p| host(&p.ip).is_some()));
+    }
+
+    #[test]
+    fn test_ping_and_traceroute() {
+        let (replies, result) = ping("192.168.1.20", 4);
+        assert_eq!(replies.len(), 4);
+        assert!(result.is_reachable);
+        assert_eq!(result.packet_loss, 0.0);
+        assert!(result.min_ms <= result.avg_ms && result.avg_ms <= result.max_ms);
+
+        let (replies, result) = ping("10.9.9.9", 3);
+        assert!(replies.is_empty());
+        assert_eq!(result.packet_loss, 100.0);
+
+        assert_eq!(traceroute("192.168.1.20").hops.len(), 1);
+        let trace = traceroute("203.0.113.10");
+        assert_eq!(trace.hops.len(), 4);
+        assert_eq!(trace.hops[0].ip.as_deref(), Some("192.168.1.1"));
+        assert_eq!(
+            trace.hops.last().unwrap().hostname.as_deref(),
+            Some("staging.acme.test")
+        );
+    }
+
+    #[test]
+    fn test_kill_is_skipped() {
+        let result = kill_port_process(3000);
+        assert!(!result.success);
+        assert_eq!(result.pid, 48213);
+        assert_eq!(kill_port_process(1).pid, 0);
+    }
+}
@@ -0,0 +1,341 @@
+//! 本机服务依赖关系
+//!
+//! 把已建立的 TCP 连接和监听端口对应起来，得到“3000 上的 node 连接了 5432 和 6379”这样的
+//! 依赖图，供前端绘制节点和连线。连接的对端是本机监听端口时连到对应进程；对端在本机之外时
+//! 只记录本机服务（有监听端口的进程）发出的连接，浏览器等客户端程序的外连不计入
+
+use crate::app_error::AppResult;
+use crate::core::{ConnectionInfo, PortInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::net::IpAddr;
+
+/// 节点类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyNodeKind {
+    /// 本机进程
+    Process,
+    /// 本机之外的服务
+    External,
+}
+
+/// 依赖图中的节点
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyNode {
+    /// 进程为 `pid:<PID>`，外部服务为 `remote:<地址>:<端口>`
+    pub id: String,
+    pub kind: DependencyNodeKind,
+    /// 进程名或外部地址
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<String>,
+    /// 进程的监听端口或外部服务的端口，升序
+    pub ports: Vec<u16>,
+}
+
+/// 一条依赖：`from` 连接了 `to` 的 `port` 端口
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyEdge {
+    pub from: String,
+    pub to: String,
+    pub port: u16,
+    /// 端口对应的常见服务名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    /// 合并的连接数
+    pub connections: usize,
+}
+
+/// 本机服务依赖图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyGraph {
+    pub generated_at: String,
+    pub nodes: Vec<DependencyNode>,
+    /// 按起点、终点和端口排列
+    pub edges: Vec<DependencyEdge>,
+}
+
+/// 读取本机监听端口和 TCP 连接，生成依赖图
+#[tracing::instrument(level = "info")]
+pub fn dependency_graph() -> AppResult<DependencyGraph> {
+    let ports = crate::core::try_get_listening_ports()?;
+    let connections = crate::core::try_get_connections()?;
+    let graph = build_graph(&ports, &connections);
+    tracing::info!(
+        nodes = graph.nodes.len(),
+        edges = graph.edges.len(),
+        "依赖图生成完成"
+    );
+    Ok(graph)
+}
+
+fn is_loopback(address: &str) -> bool {
+    let address = address.trim_start_matches('[').trim_end_matches(']');
+    address.eq_ignore_ascii_case("localhost")
+        || address.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+fn process_id(pid: &str) -> String {
+    format!("pid:{}", pid)
+}
+
+/// 按监听端口和已建立的连接生成依赖图
+pub fn build_graph(ports: &[PortInfo], connections: &[ConnectionInfo]) -> DependencyGraph {
+    let listeners: HashMap<u16, &PortInfo> = ports
+        .iter()
+        .filter(|p| p.pid != "-")
+        .map(|p| (p.port, p))
+        .collect();
+    let listening_pids: HashSet<&str> = ports.iter().map(|p| p.pid.as_str()).collect();
+    // 连接中出现过的本机地址，用于识别连到本机网卡地址（而非回环地址）的连接
+    let local_addresses: HashSet<&str> = connections
+        .iter()
+        .map(|c| c.local_address.as_str())
+        .collect();
+
+    let mut nodes: BTreeMap<String, DependencyNode> = BTreeMap::new();
+    let mut edges: BTreeMap<(String, String, u16), usize> = BTreeMap::new();
+    let mut process_node = |pid: &str, process: &str| {
+        let id = process_id(pid);
+        nodes.entry(id.clone()).or_insert_with(|| DependencyNode {
+            id: id.clone(),
+            kind: DependencyNodeKind::Process,
+            label: process.to_string(),
+            pid: Some(pid.to_string()),
+            ports: ports
+                .iter()
+                .filter(|p| p.pid == pid)
+                .map(|p| p.port)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        });
+        id
+    };
+
+    let mut external = Vec::new();
+    for connection in connections {
+        if connection.state != "ESTABLISHED" || connection.pid == "-" {
+            continue;
+        }
+        // 服务端一侧的套接字，由客户端一侧记录
+        if listeners
+            .get(&connection.local_port)
+            .is_some_and(|owner| owner.pid == connection.pid)
+        {
+            continue;
+        }
+        let local_target = is_loopback(&connection.remote_address)
+            || local_addresses.contains(connection.remote_address.as_str());
+        if local_target {
+            let Some(owner) = listeners.get(&connection.remote_port) else {
+                continue;
+            };
+            if owner.pid == connection.pid {
+                continue;
+            }
+            let from = process_node(&connection.pid, &connection.process);
+            let to = process_node(&owner.pid, &owner.process);
+            *edges.entry((from, to, connection.remote_port)).or_default() += 1;
+        } else if listening_pids.contains(connection.pid.as_str()) {
+            let from = process_node(&connection.pid, &connection.process);
+            let to = format!(
+                "remote:{}:{}",
+                connection.remote_address, connection.remote_port
+            );
+            external.push((to.clone(), connection));
+            *edges.entry((from, to, connection.remote_port)).or_default() += 1;
+        }
+    }
+    for (id, connection) in external {
+        nodes.entry(id.clone()).or_insert_with(|| DependencyNode {
+            id,
+            kind: DependencyNodeKind::External,
+            label: connection.remote_address.clone(),
+            pid: None,
+            ports: vec![connection.remote_port],
+        });
+    }
+
+    DependencyGraph {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        nodes: nodes.into_values().collect(),
+        edges: edges
+            .into_iter()
+            .map(|((from, to, port), connections)| DependencyEdge {
+                from,
+                to,
+                port,
+                service: crate::network::get_service_name(port),
+                connections,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listener(port: u16, pid: &str, process: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "IPv4".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: pid.to_string(),
+            process: process.to_string(),
+            user: "alice".to_string(),
+            command: None,
+        }
+    }
+
+    fn connection(
+        local: (&str, u16),
+        remote: (&str, u16),
+        state: &str,
+        pid: &str,
+        process: &str,
+    ) -> ConnectionInfo {
+        ConnectionInfo {
+            protocol: "IPv4".to_string(),
+            local_address: local.0.to_string(),
+            local_port: local.1,
+            remote_address: remote.0.to_string(),
+            remote_port: remote.1,
+            state: state.to_string(),
+            pid: pid.to_string(),
+            process: process.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_build_graph_links_local_services() {
+        let ports = vec![
+            listener(3000, "48213", "node"),
+            listener(5432, "733", "postgres"),
+            listener(6379, "801", "redis-server"),
+        ];
+        let connections = vec![
+            connection(
+                ("127.0.0.1", 52110),
+                ("127.0.0.1", 5432),
+                "ESTABLISHED",
+                "48213",
+                "node",
+            ),
+            connection(
+                ("127.0.0.1", 52111),
+                ("127.0.0.1", 5432),
+                "ESTABLISHED",
+                "48213",
+                "node",
+            ),
+            connection(
+                ("127.0.0.1", 5432),
+                ("127.0.0.1", 52110),
+                "ESTABLISHED",
+                "733",
+                "postgres",
+            ),
+            connection(
+                ("127.0.0.1", 5432),
+                ("127.0.0.1", 52111),
+                "ESTABLISHED",
+                "733",
+                "postgres",
+            ),
+            connection(
+                ("127.0.0.1", 52140),
+                ("127.0.0.1", 6379),
+                "ESTABLISHED",
+                "48213",
+                "node",
+            ),
+            connection(
+                ("127.0.0.1", 52150),
+                ("127.0.0.1", 6379),
+                "TIME_WAIT",
+                "48213",
+                "node",
+            ),
+            connection(
+                ("127.0.0.1", 6379),
+                ("127.0.0.1", 52140),
+                "ESTABLISHED",
+                "801",
+                "redis-server",
+            ),
+        ];
+        let graph = build_graph(&ports, &connections);
+
+        let edges: Vec<(&str, &str, u16, usize)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.port, e.connections))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("pid:48213", "pid:733", 5432, 2),
+                ("pid:48213", "pid:801", 6379, 1),
+            ]
+        );
+        assert_eq!(graph.nodes.len(), 3);
+        let node = graph.nodes.iter().find(|n| n.id == "pid:48213").unwrap();
+        assert_eq!(node.ports, vec![3000]);
+        assert_eq!(node.label, "node");
+    }
+
+    #[test]
+    fn test_build_graph_external_only_from_services() {
+        let ports = vec![listener(3000, "48213", "node")];
+        let connections = vec![
+            // 本机服务调用外部 API
+            connection(
+                ("192.168.1.23", 52344),
+                ("140.82.112.6", 443),
+                "ESTABLISHED",
+                "48213",
+                "node",
+            ),
+            // 浏览器的外连不计入
+            connection(
+                ("192.168.1.23", 52400),
+                ("142.250.72.14", 443),
+                "ESTABLISHED",
+                "1088",
+                "Google Chrome",
+            ),
+            // 通过网卡地址访问本机的 3000
+            connection(
+                ("192.168.1.23", 53001),
+                ("192.168.1.23", 3000),
+                "ESTABLISHED",
+                "2210",
+                "curl",
+            ),
+            connection(
+                ("192.168.1.23", 3000),
+                ("192.168.1.23", 53001),
+                "ESTABLISHED",
+                "48213",
+                "node",
+            ),
+        ];
+        let graph = build_graph(&ports, &connections);
+
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].from, "pid:2210");
+        assert_eq!(graph.edges[0].to, "pid:48213");
+        assert_eq!(graph.edges[1].to, "remote:140.82.112.6:443");
+        let remote = graph
+            .nodes
+            .iter()
+            .find(|n| n.kind == DependencyNodeKind::External)
+            .unwrap();
+        assert_eq!(remote.label, "140.82.112.6");
+        assert_eq!(remote.ports, vec![443]);
+        assert!(graph.nodes.iter().all(|n| n.label != "Google Chrome"));
+    }
+}
@@ -0,0 +1,443 @@
+//! DHCP 服务器发现与私设 DHCP 检测
+//!
+//! 在接口上广播一个 DHCPDISCOVER，收集局域网内所有 DHCP 服务器的 OFFER：服务器标识、
+//! 提供的地址和网段、网关、DNS、域名和租期。也可以只被动监听其他客户端引出的 OFFER / ACK。
+//! 同一网段出现多台服务器通常意味着有人私接了路由器（私设 DHCP），会导致部分设备拿到错误的
+//! 网关或地址。收发使用原始以太网通道：DHCP 客户端端口 68 往往已被系统的 DHCP 客户端占用，
+//! 与 [`crate::lldp`] 一样需要抓包驱动和 root / 管理员权限；Windows 版本暂不支持
+
+use crate::app_error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// 默认等待时长
+pub const DEFAULT_LISTEN_SECS: u64 = 5;
+/// 等待时长上限
+pub const MAX_LISTEN_SECS: u64 = 180;
+
+const DHCP_SERVER_PORT: u16 = 67;
+const DHCP_CLIENT_PORT: u16 = 68;
+const MAGIC_COOKIE: [u8; 4] = [0x63, 0x82, 0x53, 0x63];
+/// BOOTP 固定部分的长度（不含 magic cookie）
+const BOOTP_LEN: usize = 236;
+
+/// DHCP 报文类型（选项 53）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DhcpMessageType {
+    Offer,
+    Ack,
+}
+
+/// 一台 DHCP 服务器的应答
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DhcpOffer {
+    pub message_type: DhcpMessageType,
+    /// 服务器标识（选项 54），缺失时为应答的源地址
+    pub server_id: String,
+    /// 应答帧的源 MAC
+    pub server_mac: String,
+    /// 提供给客户端的地址
+    pub offered_ip: String,
+    pub subnet_mask: Option<String>,
+    /// 由提供的地址和掩码推算出的网段，例如 `192.168.1.0/24`
+    pub network: Option<String>,
+    pub routers: Vec<String>,
+    pub dns_servers: Vec<String>,
+    pub domain: Option<String>,
+    pub lease_secs: Option<u32>,
+    /// 发现请求的事务号；被动监听到的其他客户端的应答不一致
+    #[serde(skip)]
+    pub xid: u32,
+}
+
+/// 一次 DHCP 服务器发现的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DhcpReport {
+    pub interface: String,
+    pub listened_secs: u64,
+    /// 是否只被动监听
+    pub passive: bool,
+    /// 按服务器标识去重
+    pub servers: Vec<DhcpOffer>,
+    /// 出现多台 DHCP 服务器，疑似私设 DHCP
+    pub rogue_suspected: bool,
+}
+
+/// 构造 DHCPDISCOVER 报文（UDP 载荷）：设置广播标志，让服务器以广播方式回应
+pub fn build_discover(mac: [u8; 6], xid: u32) -> Vec<u8> {
+    let mut message = vec![0u8; BOOTP_LEN];
+    message[0] = 1; // BOOTREQUEST
+    message[1] = 1; // 以太网
+    message[2] = 6;
+    message[4..8].copy_from_slice(&xid.to_be_bytes());
+    message[10] = 0x80; // 广播标志
+    message[28..34].copy_from_slice(&mac);
+    message.extend(MAGIC_COOKIE);
+    // 报文类型 DISCOVER；请求掩码、网关、DNS、域名、租期和服务器标识
+    message.extend([53, 1, 1]);
+    message.extend([55, 6, 1, 3, 6, 15, 51, 54]);
+    message.push(255);
+    // BOOTP 报文至少 300 字节，部分服务器会丢弃更短的请求
+    message.resize(message.len().max(300), 0);
+    message
+}
+
+/// 把 DHCPDISCOVER 封装为以太网广播帧：0.0.0.0:68 → 255.255.255.255:67
+pub fn build_discover_frame(mac: [u8; 6], xid: u32) -> Vec<u8> {
+    let payload = build_discover(mac, xid);
+    let udp_len = 8 + payload.len();
+    let ip_len = 20 + udp_len;
+
+    let mut frame = vec![0xff; 6];
+    frame.extend(mac);
+    frame.extend([0x08, 0x00]);
+
+    let mut ip = vec![0x45, 0x00];
+    ip.extend((ip_len as u16).to_be_bytes());
+    ip.extend([0, 0, 0, 0, 64, 17, 0, 0]);
+    ip.extend(Ipv4Addr::UNSPECIFIED.octets());
+    ip.extend(Ipv4Addr::BROADCAST.octets());
+    let checksum = ipv4_checksum(&ip);
+    ip[10..12].copy_from_slice(&checksum.to_be_bytes());
+    frame.extend(ip);
+
+    frame.extend(DHCP_CLIENT_PORT.to_be_bytes());
+    frame.extend(DHCP_SERVER_PORT.to_be_bytes());
+    frame.extend((udp_len as u16).to_be_bytes());
+    // IPv4 上 UDP 校验和可以为 0（不校验）
+    frame.extend([0, 0]);
+    frame.extend(payload);
+    frame
+}
+
+fn ipv4_checksum(header: &[u8]) -> u16 {
+    let mut sum: u32 = header
+        .chunks(2)
+        .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// 解析以太网帧中的 DHCP 服务器应答（UDP 67 → 68 的 OFFER / ACK）
+pub fn parse_frame(frame: &[u8]) -> Option<DhcpOffer> {
+    if frame.len() < 14 || frame[12..14] != [0x08, 0x00] {
+        return None;
+    }
+    let ip = &frame[14..];
+    let ihl = (*ip.first()? & 0x0f) as usize * 4;
+    if ip.first()? >> 4 != 4 || *ip.get(9)? != 17 || ihl < 20 {
+        return None;
+    }
+    let source = Ipv4Addr::new(*ip.get(12)?, ip[13], ip[14], ip[15]);
+    let udp = ip.get(ihl..)?;
+    let ports = (
+        u16::from_be_bytes([*udp.first()?, *udp.get(1)?]),
+        u16::from_be_bytes([*udp.get(2)?, *udp.get(3)?]),
+    );
+    if ports != (DHCP_SERVER_PORT, DHCP_CLIENT_PORT) {
+        return None;
+    }
+    let mut offer = parse_reply(udp.get(8..)?)?;
+    offer.server_mac = format_mac(&frame[6..12]);
+    if offer.server_id.is_empty() {
+        offer.server_id = source.to_string();
+    }
+    Some(offer)
+}
+
+/// 解析 BOOTREPLY；不是 OFFER / ACK 时返回 `None`
+fn parse_reply(message: &[u8]) -> Option<DhcpOffer> {
+    if message.len() < BOOTP_LEN + 4 || message[0] != 2 || message[236..240] != MAGIC_COOKIE {
+        return None;
+    }
+    let xid = u32::from_be_bytes(message[4..8].try_into().ok()?);
+    let offered = Ipv4Addr::new(message[16], message[17], message[18], message[19]);
+
+    let mut message_type = None;
+    let mut server_id = String::new();
+    let mut subnet_mask = None;
+    let mut routers = Vec::new();
+    let mut dns_servers = Vec::new();
+    let mut domain = None;
+    let mut lease_secs = None;
+
+    let mut options = &message[240..];
+    while let Some((&code, rest)) = options.split_first() {
+        match code {
+            0 => {
+                options = rest;
+                continue;
+            }
+            255 => break,
+            _ => {}
+        }
+        let len = *rest.first()? as usize;
+        let value = rest.get(1..1 + len)?;
+        options = &rest[1 + len..];
+        match code {
+            53 => {
+                message_type = match value.first() {
+                    Some(2) => Some(DhcpMessageType::Offer),
+                    Some(5) => Some(DhcpMessageType::Ack),
+                    _ => None,
+                }
+            }
+            54 => server_id = addresses(value).into_iter().next().unwrap_or_default(),
+            1 => subnet_mask = addresses(value).into_iter().next(),
+            3 => routers = addresses(value),
+            6 => dns_servers = addresses(value),
+            15 => {
+                domain = Some(
+                    String::from_utf8_lossy(value)
+                        .trim_matches('\0')
+                        .to_string(),
+                )
+                .filter(|domain| !domain.is_empty())
+            }
+            51 if len == 4 => lease_secs = Some(u32::from_be_bytes(value.try_into().ok()?)),
+            _ => {}
+        }
+    }
+
+    let network = subnet_mask
+        .as_deref()
+        .and_then(|mask| mask.parse::<Ipv4Addr>().ok())
+        .map(|mask| {
+            let prefix = u32::from(mask).leading_ones();
+            let network = u32::from(offered) & u32::from(mask);
+            format!("{}/{}", Ipv4Addr::from(network), prefix)
+        });
+    Some(DhcpOffer {
+        message_type: message_type?,
+        server_id,
+        server_mac: String::new(),
+        offered_ip: offered.to_string(),
+        subnet_mask,
+        network,
+        routers,
+        dns_servers,
+        domain,
+        lease_secs,
+        xid,
+    })
+}
+
+/// 选项中的 IPv4 地址列表
+fn addresses(value: &[u8]) -> Vec<String> {
+    value
+        .chunks_exact(4)
+        .map(|octets| Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]).to_string())
+        .collect()
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// 记录一条应答：同一服务器只保留一条，OFFER 优先于 ACK（OFFER 针对本机的发现请求）
+fn record(servers: &mut Vec<DhcpOffer>, offer: DhcpOffer) {
+    match servers.iter_mut().find(|s| s.server_id == offer.server_id) {
+        Some(existing) if existing.message_type == DhcpMessageType::Ack => *existing = offer,
+        Some(_) => {}
+        None => servers.push(offer),
+    }
+}
+
+/// 广播 DHCPDISCOVER（`passive` 时只监听）并收集 `listen_secs` 秒内的服务器应答；
+/// `interface` 为空时使用默认接口
+#[tracing::instrument(level = "info")]
+pub fn discover_dhcp_servers(
+    interface: Option<&str>,
+    listen_secs: u64,
+    passive: bool,
+) -> AppResult<DhcpReport> {
+    if listen_secs == 0 || listen_secs > MAX_LISTEN_SECS {
+        return Err(AppError::validation(
+            "监听时长",
+            format!("需要在 1-{} 秒之间", MAX_LISTEN_SECS),
+        ));
+    }
+    let (interface, servers) = platform::listen(interface, listen_secs, passive)?;
+    let report = DhcpReport {
+        interface,
+        listened_secs: listen_secs,
+        passive,
+        rogue_suspected: servers.len() > 1,
+        servers,
+    };
+    if report.rogue_suspected {
+        tracing::warn!(
+            servers = report.servers.len(),
+            "发现多台 DHCP 服务器，疑似私设 DHCP"
+        );
+    }
+    tracing::info!(servers = report.servers.len(), "DHCP 服务器发现完成");
+    Ok(report)
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::*;
+    use crate::advanced_scan::capture_interface;
+    use crate::capabilities::capture_error;
+    use pnet::datalink::{self, Channel, Config};
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    pub fn listen(
+        interface: Option<&str>,
+        listen_secs: u64,
+        passive: bool,
+    ) -> AppResult<(String, Vec<DhcpOffer>)> {
+        let iface = capture_interface(interface)?;
+        let config = Config {
+            read_timeout: Some(Duration::from_millis(200)),
+            ..Config::default()
+        };
+        let (mut tx, mut rx) = match datalink::channel(&iface, config) {
+            Ok(Channel::Ethernet(tx, rx)) => (tx, rx),
+            Ok(_) => return Err(AppError::internal("不支持的链路层通道类型")),
+            Err(err) => return Err(capture_error("DHCP 服务器发现", err)),
+        };
+
+        let xid = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos() ^ elapsed.as_secs() as u32)
+            .unwrap_or(0x5054_4c59);
+        if !passive {
+            let mac = iface
+                .mac
+                .ok_or_else(|| AppError::validation("网络接口", "没有 MAC 地址"))?;
+            let frame = build_discover_frame(mac.octets(), xid);
+            match tx.send_to(&frame, None) {
+                Some(Ok(())) => {}
+                Some(Err(err)) => return Err(capture_error("DHCP 服务器发现", err)),
+                None => return Err(AppError::internal("发送 DHCPDISCOVER 失败")),
+            }
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(listen_secs);
+        let mut servers = Vec::new();
+        while Instant::now() < deadline {
+            // 读超时返回错误，继续等待直到截止时间
+            let Ok(frame) = rx.next() else { continue };
+            if let Some(offer) = parse_frame(frame) {
+                // 主动发现时只统计针对本次请求的 OFFER，以及其他客户端的 ACK
+                if passive || offer.xid == xid || offer.message_type == DhcpMessageType::Ack {
+                    record(&mut servers, offer);
+                }
+            }
+        }
+        Ok((iface.name, servers))
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+
+    pub fn listen(
+        _interface: Option<&str>,
+        _listen_secs: u64,
+        _passive: bool,
+    ) -> AppResult<(String, Vec<DhcpOffer>)> {
+        Err(AppError::command_unavailable(
+            "Npcap",
+            "DHCP 服务器发现",
+            "Windows 版本暂未启用链路层抓包",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_MAC: [u8; 6] = [0x02, 0x00, 0x00, 0xaa, 0xbb, 0xcc];
+
+    /// 模拟服务器对 `discover` 的应答帧
+    fn offer_frame(discover: &[u8], server: [u8; 4], server_mac: [u8; 6], kind: u8) -> Vec<u8> {
+        let mut message = discover[..BOOTP_LEN].to_vec();
+        message[0] = 2;
+        message[16..20].copy_from_slice(&[192, 168, 1, 57]);
+        message.extend(MAGIC_COOKIE);
+        message.extend([53, 1, kind]);
+        message.extend([54, 4]);
+        message.extend(server);
+        message.extend([1, 4, 255, 255, 255, 0]);
+        message.extend([3, 4, 192, 168, 1, 1]);
+        message.extend([6, 8, 192, 168, 1, 1, 1, 1, 1, 1]);
+        message.extend([51, 4, 0, 0, 0x0e, 0x10]);
+        message.extend([15, 4]);
+        message.extend(b"lan\0");
+        message.push(255);
+
+        let mut frame = vec![0xff; 6];
+        frame.extend(server_mac);
+        frame.extend([0x08, 0x00]);
+        let mut ip = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17, 0, 0];
+        ip.extend(server);
+        ip.extend([255, 255, 255, 255]);
+        frame.extend(ip);
+        frame.extend(DHCP_SERVER_PORT.to_be_bytes());
+        frame.extend(DHCP_CLIENT_PORT.to_be_bytes());
+        frame.extend(((8 + message.len()) as u16).to_be_bytes());
+        frame.extend([0, 0]);
+        frame.extend(message);
+        frame
+    }
+
+    #[test]
+    fn test_build_discover_frame() {
+        let frame = build_discover_frame(CLIENT_MAC, 0x1234_5678);
+        assert_eq!(&frame[..6], &[0xff; 6]);
+        assert_eq!(&frame[6..12], &CLIENT_MAC);
+        // IPv4 头校验和正确时整体求和为 0
+        assert_eq!(ipv4_checksum(&frame[14..34]), 0);
+        let udp = &frame[34..];
+        assert_eq!(u16::from_be_bytes([udp[0], udp[1]]), DHCP_CLIENT_PORT);
+        assert_eq!(u16::from_be_bytes([udp[2], udp[3]]), DHCP_SERVER_PORT);
+        let dhcp = &udp[8..];
+        assert!(dhcp.len() >= 300);
+        assert_eq!(&dhcp[4..8], &0x1234_5678u32.to_be_bytes());
+        assert_eq!(&dhcp[28..34], &CLIENT_MAC);
+        assert_eq!(&dhcp[236..243], &[0x63, 0x82, 0x53, 0x63, 53, 1, 1]);
+        // 本机发出的请求不是服务器应答
+        assert_eq!(parse_frame(&frame), None);
+    }
+
+    #[test]
+    fn test_parse_offers_and_flag_rogue_server() {
+        let discover = build_discover(CLIENT_MAC, 42);
+        let router = [0x00, 0x11, 0x32, 0x00, 0x00, 0x01];
+        let offer = parse_frame(&offer_frame(&discover, [192, 168, 1, 1], router, 2)).unwrap();
+        assert_eq!(offer.message_type, DhcpMessageType::Offer);
+        assert_eq!(offer.xid, 42);
+        assert_eq!(offer.server_id, "192.168.1.1");
+        assert_eq!(offer.server_mac, "00:11:32:00:00:01");
+        assert_eq!(offer.offered_ip, "192.168.1.57");
+        assert_eq!(offer.network.as_deref(), Some("192.168.1.0/24"));
+        assert_eq!(offer.routers, vec!["192.168.1.1"]);
+        assert_eq!(offer.dns_servers, vec!["192.168.1.1", "1.1.1.1"]);
+        assert_eq!(offer.domain.as_deref(), Some("lan"));
+        assert_eq!(offer.lease_secs, Some(3600));
+
+        let mut servers = Vec::new();
+        let rogue_mac = [0x02, 0x42, 0x00, 0x00, 0x00, 0x09];
+        let ack = parse_frame(&offer_frame(&discover, [192, 168, 1, 1], router, 5)).unwrap();
+        record(&mut servers, ack);
+        record(&mut servers, offer);
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].message_type, DhcpMessageType::Offer);
+        let rogue = parse_frame(&offer_frame(&discover, [192, 168, 1, 254], rogue_mac, 2));
+        record(&mut servers, rogue.unwrap());
+        assert_eq!(servers.len(), 2);
+    }
+}
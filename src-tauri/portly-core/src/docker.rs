@@ -0,0 +1,2322 @@
+//! Docker 容器端口集成模块
+//!
+//! 优先通过 Docker Engine API（Unix 套接字 / Windows 命名管道）读取容器信息，
+//! 套接字不可用时回退到 `docker` 命令行，再回退到 containerd 的 `nerdctl`
+//! （包括 Lima 虚拟机内的 `lima nerdctl`）
+//!
+//! 远程主机：支持 DOCKER_HOST、docker context 以及手动指定的 `tcp://` / `ssh://` 端点，
+//! `ssh://` 端点经由 `docker -H` 命令行访问
+
+use crate::app_error::{AppError, AppResult, PortlyErrorKind};
+use crate::command_exec::{hide_console_window, run_command, CommandRunner, SystemRunner};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Engine API 版本前缀（Docker 20.10+）
+const ENGINE_API_VERSION: &str = "v1.41";
+
+/// Engine API 读写超时
+const ENGINE_API_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 容器列表缓存有效期，避免逐端口查询时反复调用 Docker
+pub(crate) const CONTAINER_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// 事件流断开后的重连间隔
+const EVENT_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// 会影响端口列表的容器事件
+const WATCHED_CONTAINER_ACTIONS: &[&str] = &[
+    "create",
+    "start",
+    "restart",
+    "stop",
+    "die",
+    "kill",
+    "pause",
+    "unpause",
+    "destroy",
+    "rename",
+    "health_status",
+];
+
+/// Docker 容器信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerContainer {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub status: String,
+    pub ports: Vec<DockerPort>,
+    /// 运行状态（running / exited / paused ...）
+    #[serde(default)]
+    pub state: String,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    /// 容器所在的 Docker 网络
+    #[serde(default)]
+    pub networks: Vec<String>,
+    /// 仅在容器内部监听、未映射到宿主机的端口（EXPOSE）
+    #[serde(default)]
+    pub exposed_ports: Vec<ExposedPort>,
+    /// 容器在 Docker 网络中的 IP 地址
+    #[serde(default)]
+    pub ip_address: Option<String>,
+    /// 健康检查状态（未配置 HEALTHCHECK 时为空）
+    #[serde(default)]
+    pub health: Option<HealthStatus>,
+}
+
+/// 容器健康检查状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Unhealthy,
+    Starting,
+}
+
+/// Docker 端口映射
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerPort {
+    pub host_port: u16,
+    pub container_port: u16,
+    pub protocol: String,
+    pub host_ip: String,
+}
+
+/// 未发布到宿主机的容器端口，只能通过 Docker 网络访问
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExposedPort {
+    pub container_port: u16,
+    pub protocol: String,
+}
+
+/// Compose 项目标签
+pub const COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+/// Compose 服务标签
+pub const COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+/// Compose 项目目录标签
+pub const COMPOSE_WORKING_DIR_LABEL: &str = "com.docker.compose.project.working_dir";
+/// Compose 配置文件标签（多个文件以逗号分隔）
+pub const COMPOSE_CONFIG_FILES_LABEL: &str = "com.docker.compose.project.config_files";
+
+impl DockerContainer {
+    /// 所属 Compose 项目名
+    pub fn compose_project(&self) -> Option<&str> {
+        self.labels
+            .get(COMPOSE_PROJECT_LABEL)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// 所属 Compose 服务名
+    pub fn compose_service(&self) -> Option<&str> {
+        self.labels
+            .get(COMPOSE_SERVICE_LABEL)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Compose 项目所在目录
+    pub fn compose_working_dir(&self) -> Option<&str> {
+        self.labels
+            .get(COMPOSE_WORKING_DIR_LABEL)
+            .map(String::as_str)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// 定义该容器的 Compose 配置文件
+    pub fn compose_config_files(&self) -> Vec<String> {
+        self.labels
+            .get(COMPOSE_CONFIG_FILES_LABEL)
+            .map(|files| {
+                files
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Compose 服务（可能包含多个副本容器）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposeService {
+    pub name: String,
+    pub containers: Vec<String>,
+    pub ports: Vec<DockerPort>,
+}
+
+/// Compose 项目：项目 → 服务 → 端口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposeProject {
+    pub name: String,
+    /// 项目目录（docker compose 运行时的 working_dir）
+    pub working_dir: Option<String>,
+    pub config_files: Vec<String>,
+    pub services: Vec<ComposeService>,
+}
+
+/// 端口对应的 Compose 项目位置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComposeLocation {
+    pub container: String,
+    pub project: String,
+    pub service: Option<String>,
+    pub working_dir: Option<String>,
+    pub config_files: Vec<String>,
+}
+
+/// 按 Compose 项目分组后的容器视图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerGroups {
+    pub projects: Vec<ComposeProject>,
+    /// 不属于任何 Compose 项目的容器
+    pub standalone: Vec<DockerContainer>,
+}
+
+/// Engine API `/containers/json` 返回的容器条目
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EngineContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+    #[serde(default)]
+    image: String,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    ports: Vec<EnginePort>,
+    #[serde(default)]
+    labels: Option<HashMap<String, String>>,
+    #[serde(default)]
+    network_settings: Option<EngineNetworkSettings>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EnginePort {
+    #[serde(rename = "IP", default)]
+    ip: Option<String>,
+    private_port: u16,
+    #[serde(default)]
+    public_port: Option<u16>,
+    #[serde(rename = "Type", default)]
+    protocol: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EngineNetworkSettings {
+    #[serde(default)]
+    networks: Option<HashMap<String, EngineEndpoint>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EngineEndpoint {
+    #[serde(rename = "IPAddress", default)]
+    ip_address: Option<String>,
+}
+
+/// 容器运行时类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuntimeKind {
+    /// 由 DOCKER_HOST 环境变量指定
+    DockerHost,
+    DockerDesktop,
+    OrbStack,
+    Colima,
+    Lima,
+    RancherDesktop,
+    /// rootless Docker（`$XDG_RUNTIME_DIR/docker.sock`）
+    Rootless,
+    /// 系统级 Docker 守护进程（`/var/run/docker.sock` / `docker_engine` 管道）
+    System,
+    /// 仅 docker 命令行可用
+    DockerCli,
+    /// containerd + nerdctl
+    Nerdctl,
+    /// 远程 Docker 主机（`tcp://` / `ssh://`）
+    Remote,
+}
+
+/// Docker 主机配置来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DockerHostSource {
+    /// 通过 `set_docker_host` 手动指定
+    Override,
+    /// DOCKER_HOST 环境变量
+    Environment,
+    /// docker context（DOCKER_CONTEXT 或 config.json 中的 currentContext）
+    Context,
+}
+
+/// 当前生效的 Docker 主机配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerHostConfig {
+    pub host: String,
+    pub source: DockerHostSource,
+    pub context: Option<String>,
+    pub remote: bool,
+}
+
+/// 单个运行时端点的探测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeProbe {
+    pub kind: RuntimeKind,
+    pub endpoint: String,
+    pub available: bool,
+}
+
+/// 当前生效的容器运行时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerRuntime {
+    pub kind: RuntimeKind,
+    pub endpoint: String,
+}
+
+/// 最近一次探测成功的运行时，后续请求优先使用
+static ACTIVE_RUNTIME: std::sync::Mutex<Option<ContainerRuntime>> = std::sync::Mutex::new(None);
+
+/// 带有效期的缓存
+struct TtlCache<T> {
+    ttl: Duration,
+    entry: Option<(Instant, T)>,
+}
+
+impl<T: Clone> TtlCache<T> {
+    const fn new(ttl: Duration) -> Self {
+        Self { ttl, entry: None }
+    }
+
+    fn get(&self, now: Instant) -> Option<T> {
+        self.entry
+            .as_ref()
+            .filter(|(stored, _)| now.duration_since(*stored) < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn put(&mut self, now: Instant, value: T) {
+        self.entry = Some((now, value));
+    }
+
+    fn invalidate(&mut self) {
+        self.entry = None;
+    }
+}
+
+/// 最近一次读取的容器列表
+static CONTAINER_CACHE: Mutex<TtlCache<Vec<DockerContainer>>> =
+    Mutex::new(TtlCache::new(CONTAINER_CACHE_TTL));
+
+/// 清空容器列表缓存（容器事件或 Docker 主机变化时调用）
+pub fn invalidate_container_cache() {
+    if let Ok(mut cache) = CONTAINER_CACHE.lock() {
+        cache.invalidate();
+    }
+}
+
+/// 容器列表缓存是否未过期
+pub(crate) fn container_cache_fresh() -> bool {
+    CONTAINER_CACHE
+        .lock()
+        .is_ok_and(|cache| cache.get(Instant::now()).is_some())
+}
+
+/// 读取容器列表，缓存未过期时直接返回缓存
+fn cached_docker_containers() -> Vec<DockerContainer> {
+    if let Some(containers) = CONTAINER_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(Instant::now()))
+    {
+        return containers;
+    }
+    get_docker_containers()
+}
+
+/// 手动指定的 Docker 主机，优先于 DOCKER_HOST 和 docker context
+static DOCKER_HOST_OVERRIDE: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+
+/// 手动指定 Docker 主机（`None` 恢复为 DOCKER_HOST / docker context / 本地套接字）
+pub fn set_docker_host(host: Option<String>) -> AppResult<()> {
+    let host = match host.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(raw) => Some(normalize_docker_host(raw)?),
+    };
+
+    if let Ok(mut guard) = DOCKER_HOST_OVERRIDE.lock() {
+        *guard = host;
+    }
+    // 主机变化后需要重新探测运行时
+    record_active_runtime(None);
+    invalidate_container_cache();
+    Ok(())
+}
+
+/// 校验 Docker 主机地址，支持 unix:// / npipe:// / tcp:// / ssh://
+fn normalize_docker_host(raw: &str) -> AppResult<String> {
+    const SCHEMES: &[&str] = &["unix://", "npipe://", "tcp://", "ssh://"];
+
+    let Some(scheme) = SCHEMES.iter().find(|scheme| raw.starts_with(*scheme)) else {
+        return Err(AppError::validation(
+            "Docker 主机",
+            "仅支持 unix:// npipe:// tcp:// ssh:// 地址",
+        ));
+    };
+    let rest = &raw[scheme.len()..];
+    if rest.is_empty() || rest.chars().any(char::is_whitespace) {
+        return Err(AppError::validation("Docker 主机", "格式不正确"));
+    }
+
+    Ok(raw.trim_end_matches('/').to_string())
+}
+
+/// 获取当前生效的 Docker 主机配置：手动指定 → DOCKER_HOST → docker context
+pub fn get_docker_host_config() -> Option<DockerHostConfig> {
+    let override_host = DOCKER_HOST_OVERRIDE.lock().ok().and_then(|g| g.clone());
+    let (host, source, context) = if let Some(host) = override_host {
+        (host, DockerHostSource::Override, None)
+    } else if let Some(host) = std::env::var("DOCKER_HOST").ok().filter(|h| !h.is_empty()) {
+        (host, DockerHostSource::Environment, None)
+    } else {
+        let (context, host) = current_context_host()?;
+        (host, DockerHostSource::Context, Some(context))
+    };
+
+    Some(DockerHostConfig {
+        remote: is_remote_host(&host),
+        host,
+        source,
+        context,
+    })
+}
+
+fn is_remote_host(host: &str) -> bool {
+    host.starts_with("tcp://") || host.starts_with("ssh://")
+}
+
+/// 当前配置的远程主机（仅 `tcp://` / `ssh://`）
+fn remote_docker_host() -> Option<String> {
+    get_docker_host_config()
+        .filter(|config| config.remote)
+        .map(|config| config.host)
+}
+
+/// Docker 配置目录（DOCKER_CONFIG 或 `~/.docker`）
+fn docker_config_dir() -> Option<std::path::PathBuf> {
+    std::env::var_os("DOCKER_CONFIG")
+        .map(std::path::PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".docker")))
+}
+
+/// 读取当前 docker context 及其 Docker 端点
+fn current_context_host() -> Option<(String, String)> {
+    let config_dir = docker_config_dir()?;
+    let context = std::env::var("DOCKER_CONTEXT")
+        .ok()
+        .filter(|c| !c.is_empty())
+        .or_else(|| {
+            let raw = std::fs::read_to_string(config_dir.join("config.json")).ok()?;
+            parse_current_context(&raw)
+        })?;
+    if context == "default" {
+        return None;
+    }
+
+    // 元数据目录名是 context 名称的 SHA-256，这里直接遍历匹配 Name 字段
+    let meta_root = config_dir.join("contexts").join("meta");
+    let host = std::fs::read_dir(meta_root)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| std::fs::read_to_string(entry.path().join("meta.json")).ok())
+        .find_map(|raw| parse_context_meta(&raw, &context))?;
+    Some((context, host))
+}
+
+/// 解析 `config.json` 中的 currentContext
+fn parse_current_context(raw: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(raw).ok()?;
+    value
+        .get("currentContext")?
+        .as_str()
+        .filter(|c| !c.is_empty())
+        .map(String::from)
+}
+
+/// 解析 context 的 `meta.json`，名称匹配时返回 Docker 端点
+fn parse_context_meta(raw: &str, context: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(raw).ok()?;
+    if value.get("Name")?.as_str()? != context {
+        return None;
+    }
+    value
+        .get("Endpoints")?
+        .get("docker")?
+        .get("Host")?
+        .as_str()
+        .filter(|h| !h.is_empty())
+        .map(String::from)
+}
+
+/// 候选的 Engine API 端点（按优先级）：
+/// DOCKER_HOST / context → Docker Desktop → OrbStack → Colima → Lima → Rancher Desktop → rootless → 系统套接字
+///
+/// 配置了远程主机时只返回该主机，避免误把本机容器当作远程容器
+fn engine_socket_candidates() -> Vec<(RuntimeKind, String)> {
+    let mut candidates = Vec::new();
+
+    if let Some(config) = get_docker_host_config() {
+        let host = config.host;
+        if let Some(path) = host.strip_prefix("unix://") {
+            candidates.push((RuntimeKind::DockerHost, path.to_string()));
+        } else if let Some(pipe) = host.strip_prefix("npipe://") {
+            candidates.push((RuntimeKind::DockerHost, pipe.replace('/', "\\")));
+        } else if host.starts_with("tcp://") {
+            // Engine API 直连不支持 TLS，启用 TLS 的端点交给 docker 命令行处理
+            if is_tls_endpoint(&host) {
+                return Vec::new();
+            }
+            return vec![(RuntimeKind::Remote, host)];
+        } else if host.starts_with("ssh://") {
+            // ssh 端点没有可直连的 Engine API，交给 docker 命令行处理
+            return Vec::new();
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        if let Some(home) = dirs::home_dir() {
+            candidates.extend(user_socket_candidates(&home));
+        }
+        if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+            candidates.push((
+                RuntimeKind::Rootless,
+                std::path::Path::new(&runtime_dir)
+                    .join("docker.sock")
+                    .to_string_lossy()
+                    .to_string(),
+            ));
+        }
+        candidates.push((RuntimeKind::System, "/var/run/docker.sock".to_string()));
+    }
+
+    #[cfg(windows)]
+    candidates.push((RuntimeKind::System, r"\\.\pipe\docker_engine".to_string()));
+
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|(_, endpoint)| seen.insert(endpoint.clone()));
+    candidates
+}
+
+/// 用户目录下各桌面运行时 / 虚拟机转发的 Docker 套接字
+///
+/// - Docker Desktop: `~/.docker/run/docker.sock`
+/// - OrbStack: `~/.orbstack/run/docker.sock`
+/// - Colima: `~/.colima/<profile>/docker.sock`（旧版本为 `~/.colima/docker.sock`）
+/// - Lima (docker 模板): `~/.lima/<instance>/sock/docker.sock`
+/// - Rancher Desktop: `~/.rd/docker.sock`
+#[cfg(unix)]
+fn user_socket_candidates(home: &std::path::Path) -> Vec<(RuntimeKind, String)> {
+    let mut sockets = vec![
+        (
+            RuntimeKind::DockerDesktop,
+            home.join(".docker/run/docker.sock"),
+        ),
+        (
+            RuntimeKind::OrbStack,
+            home.join(".orbstack/run/docker.sock"),
+        ),
+    ];
+
+    let colima_dir = home.join(".colima");
+    sockets.push((RuntimeKind::Colima, colima_dir.join("docker.sock")));
+    for profile in list_subdirs(&colima_dir) {
+        sockets.push((RuntimeKind::Colima, profile.join("docker.sock")));
+    }
+
+    for instance in list_subdirs(&home.join(".lima")) {
+        sockets.push((RuntimeKind::Lima, instance.join("sock").join("docker.sock")));
+    }
+
+    sockets.push((RuntimeKind::RancherDesktop, home.join(".rd/docker.sock")));
+
+    sockets
+        .into_iter()
+        .filter(|(_, path)| path.exists())
+        .map(|(kind, path)| (kind, path.to_string_lossy().to_string()))
+        .collect()
+}
+
+/// 列出目录下的子目录（按名称排序，"default" 优先）
+#[cfg(unix)]
+fn list_subdirs(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let mut dirs: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort_by_key(|p| {
+        let name = p
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        (name != "default", name)
+    });
+    dirs
+}
+
+/// 打开 Engine API 连接
+fn connect_engine(endpoint: &str) -> Option<Box<dyn EngineStream>> {
+    match endpoint.strip_prefix("tcp://") {
+        Some(addr) => connect_tcp(addr),
+        None => connect_local(endpoint),
+    }
+}
+
+/// 连接远程 Engine API（未启用 TLS 的 `tcp://` 端点）
+fn connect_tcp(addr: &str) -> Option<Box<dyn EngineStream>> {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let socket_addr = tcp_socket_addr(addr).to_socket_addrs().ok()?.next()?;
+    let stream = TcpStream::connect_timeout(&socket_addr, ENGINE_API_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(ENGINE_API_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(ENGINE_API_TIMEOUT)).ok()?;
+    Some(Box::new(stream))
+}
+
+/// `tcp://` 之后的地址补全默认端口 2375，IPv6 地址需要方括号：
+/// `host` → `host:2375`，`[::1]` / `::1` → `[::1]:2375`，已有端口时原样返回
+fn tcp_socket_addr(addr: &str) -> String {
+    let addr = addr.trim_end_matches('/');
+    if let Some(rest) = addr.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((_, port)) if port.starts_with(':') => addr.to_string(),
+            _ => format!("{addr}:2375"),
+        };
+    }
+    match addr.matches(':').count() {
+        0 => format!("{addr}:2375"),
+        1 => addr.to_string(),
+        // 不带方括号的 IPv6 地址
+        _ => format!("[{addr}]:2375"),
+    }
+}
+
+/// `tcp://` 端点是否启用 TLS：设置了 DOCKER_TLS_VERIFY / DOCKER_TLS，或使用 TLS 端口 2376
+fn is_tls_endpoint(host: &str) -> bool {
+    let env_set = |name: &str| std::env::var(name).is_ok_and(|v| !v.is_empty());
+    if env_set("DOCKER_TLS_VERIFY") || env_set("DOCKER_TLS") {
+        return true;
+    }
+    let addr = tcp_socket_addr(host.strip_prefix("tcp://").unwrap_or(host));
+    matches!(addr.rsplit_once(':'), Some((_, "2376")))
+}
+
+/// 打开本地 Engine API 连接（Unix 套接字）
+#[cfg(unix)]
+fn connect_local(endpoint: &str) -> Option<Box<dyn EngineStream>> {
+    use std::os::unix::net::UnixStream;
+
+    let stream = UnixStream::connect(endpoint).ok()?;
+    stream.set_read_timeout(Some(ENGINE_API_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(ENGINE_API_TIMEOUT)).ok()?;
+    Some(Box::new(stream))
+}
+
+/// 打开本地 Engine API 连接（命名管道）
+#[cfg(windows)]
+fn connect_local(endpoint: &str) -> Option<Box<dyn EngineStream>> {
+    let pipe = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(endpoint)
+        .ok()?;
+    Some(Box::new(pipe))
+}
+
+trait EngineStream: Read + Write + Send {}
+impl<T: Read + Write + Send> EngineStream for T {}
+
+/// 对指定端点发起 GET 请求，返回 2xx 响应体
+fn engine_get_from(endpoint: &str, path: &str) -> Option<String> {
+    let mut stream = connect_engine(endpoint)?;
+    // HTTP/1.0：服务端发送完毕后关闭连接，且不会使用 chunked 编码
+    let request = format!(
+        "GET /{}{} HTTP/1.0\r\nHost: docker\r\nUser-Agent: Portly\r\n\r\n",
+        ENGINE_API_VERSION, path
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).ok()?;
+    parse_http_response(&String::from_utf8_lossy(&raw))
+}
+
+/// 请求 Engine API：优先使用已记录的运行时，失败后重新探测
+fn engine_api_get(path: &str) -> Option<String> {
+    let active = ACTIVE_RUNTIME.lock().ok().and_then(|guard| guard.clone());
+    if let Some(runtime) = active {
+        if runtime.kind != RuntimeKind::DockerCli
+            && runtime.kind != RuntimeKind::Nerdctl
+            && !runtime.endpoint.starts_with("ssh://")
+        {
+            if let Some(body) = engine_get_from(&runtime.endpoint, path) {
+                return Some(body);
+            }
+        }
+    }
+
+    let runtime = detect_engine_runtime()?;
+    engine_get_from(&runtime.endpoint, path)
+}
+
+/// 按优先级探测 Engine API 套接字，并记录第一个可用的运行时
+fn detect_engine_runtime() -> Option<ContainerRuntime> {
+    let runtime = engine_socket_candidates()
+        .into_iter()
+        .find(|(_, endpoint)| engine_get_from(endpoint, "/_ping").is_some())
+        .map(|(kind, endpoint)| ContainerRuntime { kind, endpoint })?;
+    record_active_runtime(Some(runtime.clone()));
+    Some(runtime)
+}
+
+fn record_active_runtime(runtime: Option<ContainerRuntime>) {
+    if let Ok(mut guard) = ACTIVE_RUNTIME.lock() {
+        *guard = runtime;
+    }
+}
+
+/// 探测所有候选运行时（用于诊断展示）
+pub fn probe_container_runtimes() -> Vec<RuntimeProbe> {
+    let mut probes: Vec<RuntimeProbe> = engine_socket_candidates()
+        .into_iter()
+        .map(|(kind, endpoint)| RuntimeProbe {
+            available: engine_get_from(&endpoint, "/_ping").is_some(),
+            kind,
+            endpoint,
+        })
+        .collect();
+
+    if let Some(host) = remote_docker_host().filter(|h| h.starts_with("ssh://")) {
+        probes.push(RuntimeProbe {
+            kind: RuntimeKind::Remote,
+            available: docker_cli_available(),
+            endpoint: host,
+        });
+        return probes;
+    }
+
+    probes.push(RuntimeProbe {
+        kind: RuntimeKind::DockerCli,
+        endpoint: "docker".to_string(),
+        available: docker_cli_available(),
+    });
+    let nerdctl = nerdctl_command();
+    probes.push(RuntimeProbe {
+        kind: RuntimeKind::Nerdctl,
+        endpoint: nerdctl
+            .map(|(program, prefix)| {
+                std::iter::once(program)
+                    .chain(prefix.iter().copied())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_else(|| "nerdctl".to_string()),
+        available: nerdctl.is_some(),
+    });
+
+    probes
+}
+
+/// 检测当前生效的容器运行时并记录
+pub fn detect_container_runtime() -> Option<ContainerRuntime> {
+    if crate::demo::is_enabled() {
+        return Some(crate::demo::container_runtime());
+    }
+    if let Some(runtime) = detect_engine_runtime() {
+        return Some(runtime);
+    }
+
+    let remote = remote_docker_host();
+    let runtime = if docker_cli_available() {
+        Some(match remote {
+            Some(host) => ContainerRuntime {
+                kind: RuntimeKind::Remote,
+                endpoint: host,
+            },
+            None => ContainerRuntime {
+                kind: RuntimeKind::DockerCli,
+                endpoint: "docker".to_string(),
+            },
+        })
+    } else if remote.is_some() {
+        None
+    } else {
+        nerdctl_command().map(|(program, _)| ContainerRuntime {
+            kind: RuntimeKind::Nerdctl,
+            endpoint: program.to_string(),
+        })
+    };
+    record_active_runtime(runtime.clone());
+    runtime
+}
+
+/// 获取最近一次记录的运行时（不触发探测）
+pub fn active_container_runtime() -> Option<ContainerRuntime> {
+    ACTIVE_RUNTIME.lock().ok().and_then(|guard| guard.clone())
+}
+
+/// 解析 HTTP 响应，仅在状态码为 2xx 时返回响应体
+pub(crate) fn parse_http_response(raw: &str) -> Option<String> {
+    let (head, body) = raw.split_once("\r\n\r\n")?;
+    let status: u16 = head
+        .lines()
+        .next()?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()?;
+    if (200..300).contains(&status) {
+        Some(body.to_string())
+    } else {
+        None
+    }
+}
+
+/// 检查 Docker 是否可用
+pub fn is_docker_available() -> bool {
+    detect_container_runtime().is_some()
+}
+
+fn docker_cli_available() -> bool {
+    // 远程主机需要确认服务端可达，本地只需确认命令行存在
+    let format = if remote_docker_host().is_some() {
+        "{{.Server.Version}}"
+    } else {
+        "{{.Client.Version}}"
+    };
+    run_command("docker", "Docker 可用性检测", |cmd| {
+        cmd.args(docker_host_args())
+            .args(["version", "--format", format]);
+    })
+    .map(|o| o.status == 0)
+    .unwrap_or(false)
+}
+
+/// 手动指定主机时传给 docker 命令行的 `-H` 参数
+/// （DOCKER_HOST 和 docker context 由命令行自行处理）
+fn docker_host_args() -> Vec<String> {
+    DOCKER_HOST_OVERRIDE
+        .lock()
+        .ok()
+        .and_then(|g| g.clone())
+        .map(|host| vec!["-H".to_string(), host])
+        .unwrap_or_default()
+}
+
+/// 探测可用的 nerdctl 调用方式：宿主机 `nerdctl` 或 Lima 中的 `lima nerdctl`
+fn nerdctl_command() -> Option<(&'static str, &'static [&'static str])> {
+    const CANDIDATES: &[(&str, &[&str])] = &[("nerdctl", &[]), ("lima", &["nerdctl"])];
+
+    CANDIDATES.iter().copied().find(|(program, prefix)| {
+        run_command(program, "nerdctl 可用性检测", |cmd| {
+            cmd.args(*prefix).arg("version");
+        })
+        .map(|o| o.status == 0)
+        .unwrap_or(false)
+    })
+}
+
+/// 获取所有运行中的 Docker 容器（失败时返回空列表，同时刷新缓存）
+pub fn get_docker_containers() -> Vec<DockerContainer> {
+    try_get_docker_containers().unwrap_or_default()
+}
+
+/// 获取所有运行中的容器，保留 docker 未安装、守护进程不可达等失败原因
+pub fn try_get_docker_containers() -> AppResult<Vec<DockerContainer>> {
+    let result = fetch_docker_containers();
+    match &result {
+        Ok(containers) => tracing::debug!(count = containers.len(), "容器列表读取完成"),
+        Err(err) => tracing::warn!(error = %err, kind = ?err.kind(), "容器列表读取失败"),
+    }
+    if let Ok(mut cache) = CONTAINER_CACHE.lock() {
+        cache.put(Instant::now(), result.clone().unwrap_or_default());
+    }
+    result
+}
+
+fn fetch_docker_containers() -> AppResult<Vec<DockerContainer>> {
+    if crate::demo::is_enabled() {
+        return Ok(crate::demo::containers());
+    }
+    if let Some(body) = engine_api_get("/containers/json") {
+        if let Some(containers) = parse_engine_containers(&body) {
+            return Ok(containers);
+        }
+    }
+
+    // docker 可用时以它的结果为准，没有运行中的容器也不再尝试 nerdctl；
+    // 只有 docker 未安装或守护进程不可达时才回退
+    let cli_error = match get_docker_containers_cli(&SystemRunner) {
+        Ok(containers) => return Ok(containers),
+        Err(err) => err,
+    };
+    if let Some(host) = remote_docker_host() {
+        // TLS 端点只能通过 docker 命令行访问，命令行不可用时说明原因
+        if is_tls_endpoint(&host) && cli_error.kind() == PortlyErrorKind::ToolMissing {
+            return Err(AppError::host_unreachable(
+                &host,
+                "启用 TLS 的 Docker 主机需要安装 docker 命令行",
+            ));
+        }
+        return Err(cli_error);
+    }
+
+    let containers = get_nerdctl_containers();
+    // docker 失败且没有 nerdctl 可以兜底时，把 docker 的失败原因交给调用方
+    if containers.is_empty() && nerdctl_command().is_none() {
+        return Err(cli_error);
+    }
+    Ok(containers)
+}
+
+/// 通过 nerdctl（containerd）获取容器列表
+pub fn get_nerdctl_containers() -> Vec<DockerContainer> {
+    let Some((program, prefix)) = nerdctl_command() else {
+        return Vec::new();
+    };
+
+    let output = match run_command(program, "nerdctl 容器列表读取", |cmd| {
+        cmd.args(prefix).args([
+            "ps",
+            "--format",
+            "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.Ports}}",
+        ]);
+    }) {
+        Ok(o) if o.status == 0 => o,
+        _ => return Vec::new(),
+    };
+
+    parse_docker_ps_lines(&output.stdout)
+}
+
+/// 解析 Engine API `/containers/json` 响应
+fn parse_engine_containers(body: &str) -> Option<Vec<DockerContainer>> {
+    let entries: Vec<EngineContainer> = serde_json::from_str(body).ok()?;
+
+    Some(
+        entries
+            .into_iter()
+            .map(|entry| {
+                let mut ports: Vec<DockerPort> = Vec::new();
+                let mut exposed_ports: Vec<ExposedPort> = Vec::new();
+                for port in &entry.ports {
+                    let protocol = port.protocol.clone().unwrap_or_else(|| "tcp".to_string());
+                    let Some(host_port) = port.public_port else {
+                        push_exposed_port(&mut exposed_ports, port.private_port, protocol);
+                        continue;
+                    };
+                    let host_ip = port.ip.clone().unwrap_or_else(|| "0.0.0.0".to_string());
+                    // 跳过 IPv6 重复映射
+                    if host_ip.contains(':') {
+                        continue;
+                    }
+                    if ports
+                        .iter()
+                        .any(|p| p.host_port == host_port && p.protocol == protocol)
+                    {
+                        continue;
+                    }
+                    ports.push(DockerPort {
+                        host_port,
+                        container_port: port.private_port,
+                        protocol,
+                        host_ip,
+                    });
+                }
+                ports.sort_by_key(|p| p.host_port);
+                exposed_ports.retain(|e| {
+                    !ports
+                        .iter()
+                        .any(|p| p.container_port == e.container_port && p.protocol == e.protocol)
+                });
+                exposed_ports.sort_by_key(|e| e.container_port);
+
+                let endpoints = entry
+                    .network_settings
+                    .and_then(|n| n.networks)
+                    .unwrap_or_default();
+                let mut networks: Vec<String> = endpoints.keys().cloned().collect();
+                networks.sort();
+                let ip_address = networks
+                    .iter()
+                    .filter_map(|n| endpoints.get(n)?.ip_address.clone())
+                    .find(|ip| !ip.is_empty());
+
+                DockerContainer {
+                    id: entry.id.chars().take(12).collect(),
+                    name: entry
+                        .names
+                        .first()
+                        .map(|n| n.trim_start_matches('/').to_string())
+                        .unwrap_or_default(),
+                    image: entry.image,
+                    ports,
+                    state: entry.state,
+                    labels: entry.labels.unwrap_or_default(),
+                    networks,
+                    exposed_ports,
+                    ip_address,
+                    health: parse_health_status(&entry.status),
+                    status: entry.status,
+                }
+            })
+            .collect(),
+    )
+}
+
+/// `docker ps` 的输出格式，由 [`parse_docker_ps_lines`] 解析
+const DOCKER_PS_FORMAT: &str =
+    "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.Ports}}\t{{.State}}\t{{.Labels}}\t{{.Networks}}";
+
+/// `docker inspect` 的输出格式，由 [`parse_docker_inspect_ips`] 解析
+const DOCKER_INSPECT_IP_FORMAT: &str =
+    "{{.Name}}\t{{range .NetworkSettings.Networks}}{{.IPAddress}} {{end}}";
+
+/// 通过 `docker ps` 获取容器列表（Engine API 不可用时的回退）
+fn get_docker_containers_cli(runner: &dyn CommandRunner) -> AppResult<Vec<DockerContainer>> {
+    let context = "Docker 容器列表读取";
+    let output = runner.run("docker", context, &mut |cmd| {
+        cmd.args(docker_host_args())
+            .args(["ps", "--format", DOCKER_PS_FORMAT]);
+    })?;
+    if output.status != 0 {
+        let stderr = output.stderr.trim();
+        return Err(docker_cli_error(context, output.status, stderr));
+    }
+
+    let mut containers = parse_docker_ps_lines(&output.stdout);
+    fill_container_ips_cli(runner, &mut containers);
+    Ok(containers)
+}
+
+/// 区分守护进程不可达与其他 docker 命令失败
+fn docker_cli_error(context: &str, exit_code: i32, stderr: &str) -> AppError {
+    if stderr.to_ascii_lowercase().contains("cannot connect") {
+        let host = remote_docker_host().unwrap_or_else(|| "Docker daemon".to_string());
+        AppError::host_unreachable(&host, stderr)
+    } else {
+        AppError::command_failed("docker", context, exit_code, stderr)
+    }
+}
+
+/// 通过 `docker inspect` 补全容器 IP（`docker ps` 不输出 IP）
+fn fill_container_ips_cli(runner: &dyn CommandRunner, containers: &mut [DockerContainer]) {
+    if containers.is_empty() {
+        return;
+    }
+
+    let output = match runner.run("docker", "Docker 容器 IP 读取", &mut |cmd| {
+        cmd.args(docker_host_args())
+            .args(["inspect", "--format", DOCKER_INSPECT_IP_FORMAT])
+            .args(containers.iter().map(|c| c.id.as_str()));
+    }) {
+        Ok(o) if o.status == 0 => o,
+        _ => return,
+    };
+
+    let ips = parse_docker_inspect_ips(&output.stdout);
+    for container in containers.iter_mut() {
+        if let Some(ip) = ips.get(&container.name) {
+            container.ip_address = Some(ip.clone());
+        }
+    }
+}
+
+/// 解析 `docker inspect` 输出，格式: "/name\t172.17.0.2 10.0.0.3 "
+fn parse_docker_inspect_ips(stdout: &str) -> HashMap<String, String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let (name, ips) = line.split_once('\t')?;
+            let ip = ips.split_whitespace().next()?;
+            Some((
+                name.trim().trim_start_matches('/').to_string(),
+                ip.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// 解析 `docker ps --format` 的制表符分隔输出
+fn parse_docker_ps_lines(stdout: &str) -> Vec<DockerContainer> {
+    let mut containers = Vec::new();
+
+    for line in stdout.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() >= 4 {
+            let (ports, exposed_ports) = if parts.len() >= 5 {
+                (parse_docker_ports(parts[4]), parse_exposed_ports(parts[4]))
+            } else {
+                (Vec::new(), Vec::new())
+            };
+
+            containers.push(DockerContainer {
+                id: parts[0].to_string(),
+                name: parts[1].to_string(),
+                image: parts[2].to_string(),
+                status: parts[3].to_string(),
+                ports,
+                state: parts.get(5).map(|s| s.to_string()).unwrap_or_default(),
+                labels: parts
+                    .get(6)
+                    .map(|s| parse_docker_labels(s))
+                    .unwrap_or_default(),
+                networks: parts
+                    .get(7)
+                    .map(|s| {
+                        s.split(',')
+                            .map(str::trim)
+                            .filter(|n| !n.is_empty())
+                            .map(String::from)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                exposed_ports,
+                ip_address: None,
+                health: parse_health_status(parts[3]),
+            });
+        }
+    }
+
+    containers
+}
+
+/// 解析 `{{.Labels}}` 输出，格式: "k1=v1,k2=v2"
+fn parse_docker_labels(raw: &str) -> HashMap<String, String> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                None
+            } else {
+                Some((key.to_string(), value.trim().to_string()))
+            }
+        })
+        .collect()
+}
+
+/// 解析 Docker 端口字符串
+/// 格式: "0.0.0.0:5432->5432/tcp, [::]:5432->5432/tcp"
+fn parse_docker_ports(port_str: &str) -> Vec<DockerPort> {
+    let mut ports = Vec::new();
+
+    for part in port_str.split(", ") {
+        if let Some(port) = parse_single_port_mapping(part) {
+            // 避免重复（IPv4 和 IPv6 可能重复）
+            if !ports
+                .iter()
+                .any(|p: &DockerPort| p.host_port == port.host_port && p.protocol == port.protocol)
+            {
+                ports.push(port);
+            }
+        }
+    }
+
+    ports
+}
+
+/// 从状态文本中解析健康检查状态
+/// 格式: "Up 2 hours (healthy)"、"Up 5 seconds (health: starting)"
+fn parse_health_status(status: &str) -> Option<HealthStatus> {
+    let status = status.to_lowercase();
+    if status.contains("(unhealthy)") {
+        Some(HealthStatus::Unhealthy)
+    } else if status.contains("(healthy)") {
+        Some(HealthStatus::Healthy)
+    } else if status.contains("(health: starting)") {
+        Some(HealthStatus::Starting)
+    } else {
+        None
+    }
+}
+
+/// 解析仅暴露未映射的端口，如 "5432/tcp" 或 "8000-8001/tcp"
+fn parse_exposed_ports(port_str: &str) -> Vec<ExposedPort> {
+    let mut exposed = Vec::new();
+
+    for part in port_str.split(", ").map(str::trim) {
+        if part.is_empty() || part.contains("->") {
+            continue;
+        }
+        let (port_part, protocol) = part.split_once('/').unwrap_or((part, "tcp"));
+        let (start, end) = match port_part.split_once('-') {
+            Some((start, end)) => (start.parse::<u16>(), end.parse::<u16>()),
+            None => (port_part.parse::<u16>(), port_part.parse::<u16>()),
+        };
+        let (Ok(start), Ok(end)) = (start, end) else {
+            continue;
+        };
+        for port in start..=end {
+            push_exposed_port(&mut exposed, port, protocol.to_string());
+        }
+    }
+
+    exposed
+}
+
+fn push_exposed_port(exposed: &mut Vec<ExposedPort>, container_port: u16, protocol: String) {
+    if !exposed
+        .iter()
+        .any(|e| e.container_port == container_port && e.protocol == protocol)
+    {
+        exposed.push(ExposedPort {
+            container_port,
+            protocol,
+        });
+    }
+}
+
+/// 解析单个端口映射
+/// 格式: "0.0.0.0:5432->5432/tcp" 或 "5432/tcp" (仅暴露不映射)
+fn parse_single_port_mapping(s: &str) -> Option<DockerPort> {
+    // 跳过 IPv6 格式
+    if s.starts_with("[::]:") || s.contains(":::") {
+        return None;
+    }
+
+    // 解析 host:port->container_port/protocol
+    if let Some(arrow_pos) = s.find("->") {
+        let host_part = &s[..arrow_pos];
+        let container_part = &s[arrow_pos + 2..];
+
+        // 解析 host_ip:host_port
+        let (host_ip, host_port_str) = if let Some(colon_pos) = host_part.rfind(':') {
+            (&host_part[..colon_pos], &host_part[colon_pos + 1..])
+        } else {
+            ("0.0.0.0", host_part)
+        };
+
+        // 解析端口范围
+        let host_port: u16 = if host_port_str.contains('-') {
+            // 端口范围，取第一个
+            host_port_str.split('-').next()?.parse().ok()?
+        } else {
+            host_port_str.parse().ok()?
+        };
+
+        // 解析 container_port/protocol
+        let (container_port_str, protocol) = if let Some(slash_pos) = container_part.find('/') {
+            (
+                &container_part[..slash_pos],
+                &container_part[slash_pos + 1..],
+            )
+        } else {
+            (container_part, "tcp")
+        };
+
+        let container_port: u16 = if container_port_str.contains('-') {
+            container_port_str.split('-').next()?.parse().ok()?
+        } else {
+            container_port_str.parse().ok()?
+        };
+
+        return Some(DockerPort {
+            host_port,
+            container_port,
+            protocol: protocol.to_string(),
+            host_ip: host_ip.to_string(),
+        });
+    }
+
+    None
+}
+
+/// 按 Compose 项目 / 服务对容器分组
+pub fn group_by_compose_project(containers: &[DockerContainer]) -> ContainerGroups {
+    let mut projects: HashMap<String, HashMap<String, ComposeService>> = HashMap::new();
+    let mut locations: HashMap<String, (Option<String>, Vec<String>)> = HashMap::new();
+    let mut standalone = Vec::new();
+
+    for container in containers {
+        let Some(project) = container.compose_project() else {
+            standalone.push(container.clone());
+            continue;
+        };
+        let service_name = container.compose_service().unwrap_or(&container.name);
+
+        let location = locations.entry(project.to_string()).or_default();
+        if location.0.is_none() {
+            location.0 = container.compose_working_dir().map(String::from);
+        }
+        if location.1.is_empty() {
+            location.1 = container.compose_config_files();
+        }
+
+        let service = projects
+            .entry(project.to_string())
+            .or_default()
+            .entry(service_name.to_string())
+            .or_insert_with(|| ComposeService {
+                name: service_name.to_string(),
+                containers: Vec::new(),
+                ports: Vec::new(),
+            });
+        service.containers.push(container.name.clone());
+        for port in &container.ports {
+            if !service
+                .ports
+                .iter()
+                .any(|p| p.host_port == port.host_port && p.protocol == port.protocol)
+            {
+                service.ports.push(port.clone());
+            }
+        }
+    }
+
+    let mut projects: Vec<ComposeProject> = projects
+        .into_iter()
+        .map(|(name, services)| {
+            let mut services: Vec<ComposeService> = services
+                .into_values()
+                .map(|mut service| {
+                    service.containers.sort();
+                    service.ports.sort_by_key(|p| p.host_port);
+                    service
+                })
+                .collect();
+            services.sort_by(|a, b| a.name.cmp(&b.name));
+            let (working_dir, config_files) = locations.remove(&name).unwrap_or_default();
+            ComposeProject {
+                name,
+                working_dir,
+                config_files,
+                services,
+            }
+        })
+        .collect();
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+    ContainerGroups {
+        projects,
+        standalone,
+    }
+}
+
+/// 获取按 Compose 项目分组的容器视图
+pub fn get_compose_groups() -> ContainerGroups {
+    group_by_compose_project(&get_docker_containers())
+}
+
+/// 查找发布了指定宿主机端口的 Compose 容器及其项目位置
+pub fn find_compose_location(containers: &[DockerContainer], port: u16) -> Option<ComposeLocation> {
+    let container = containers
+        .iter()
+        .find(|c| c.ports.iter().any(|p| p.host_port == port))?;
+    let project = container.compose_project()?;
+
+    Some(ComposeLocation {
+        container: container.name.clone(),
+        project: project.to_string(),
+        service: container.compose_service().map(String::from),
+        working_dir: container.compose_working_dir().map(String::from),
+        config_files: container.compose_config_files(),
+    })
+}
+
+/// 获取端口对应的 Compose 项目目录和配置文件
+pub fn get_compose_location(port: u16) -> Option<ComposeLocation> {
+    find_compose_location(&cached_docker_containers(), port)
+}
+
+/// 获取端口到容器的映射表
+pub fn get_port_to_container_map() -> HashMap<u16, String> {
+    let containers = cached_docker_containers();
+    let mut map = HashMap::new();
+
+    for container in containers {
+        for port in &container.ports {
+            map.insert(port.host_port, format!("🐳 {}", container.name));
+        }
+    }
+
+    map
+}
+
+/// 获取容器端口详细信息：容器名、镜像和健康检查状态
+pub fn get_docker_port_info(port: u16) -> Option<(String, String, Option<HealthStatus>)> {
+    let containers = cached_docker_containers();
+
+    for container in containers {
+        for p in &container.ports {
+            if p.host_port == port {
+                return Some((
+                    container.name.clone(),
+                    container.image.clone(),
+                    container.health,
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// 从容器列表中获取端口信息（用于测试）
+pub fn get_docker_port_info_from_containers(
+    containers: &[DockerContainer],
+    port: u16,
+) -> Option<(String, DockerPort, Option<HealthStatus>)> {
+    for container in containers {
+        for p in &container.ports {
+            if p.host_port == port {
+                return Some((
+                    container.name.clone(),
+                    DockerPort {
+                        host_port: p.host_port,
+                        container_port: p.container_port,
+                        protocol: p.protocol.clone(),
+                        host_ip: p.host_ip.clone(),
+                    },
+                    container.health,
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// Docker 容器事件（启动 / 停止 / 销毁等）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerEvent {
+    pub action: String,
+    pub container_id: String,
+    pub name: String,
+    pub image: String,
+    /// Unix 时间戳（秒）
+    pub time: i64,
+}
+
+/// Engine API `/events` 及 `docker events --format '{{json .}}'` 的事件条目
+#[derive(Debug, Deserialize)]
+struct EngineEvent {
+    #[serde(rename = "Type", default)]
+    kind: String,
+    #[serde(rename = "Action", default)]
+    action: String,
+    #[serde(rename = "Actor", default)]
+    actor: Option<EngineEventActor>,
+    #[serde(default)]
+    time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EngineEventActor {
+    #[serde(rename = "ID", default)]
+    id: String,
+    #[serde(default)]
+    attributes: HashMap<String, String>,
+}
+
+/// 解析单行事件 JSON，仅保留会影响端口列表的容器事件
+fn parse_docker_event(line: &str) -> Option<DockerEvent> {
+    let event: EngineEvent = serde_json::from_str(line.trim()).ok()?;
+    if event.kind != "container" {
+        return None;
+    }
+    // 部分事件带参数，如 "exec_start: sh"
+    let action = event.action.split(':').next()?.trim().to_string();
+    if !WATCHED_CONTAINER_ACTIONS.contains(&action.as_str()) {
+        return None;
+    }
+
+    let actor = event.actor?;
+    Some(DockerEvent {
+        action,
+        container_id: actor.id.chars().take(12).collect(),
+        name: actor.attributes.get("name").cloned().unwrap_or_default(),
+        image: actor.attributes.get("image").cloned().unwrap_or_default(),
+        time: event.time,
+    })
+}
+
+/// 订阅 Docker 容器事件，阻塞直到 `stop` 被置位
+///
+/// 优先读取 Engine API `/events` 流，不可用时回退到 `docker events` 命令行；
+/// 连接断开（如 Docker 重启）后自动重连
+pub fn watch_docker_events<F>(stop: &AtomicBool, mut on_event: F)
+where
+    F: FnMut(DockerEvent),
+{
+    while !stop.load(Ordering::Relaxed) {
+        if !stream_engine_events(stop, &mut on_event) {
+            stream_cli_events(stop, &mut on_event);
+        }
+
+        let mut waited = Duration::ZERO;
+        while waited < EVENT_RECONNECT_DELAY && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(200));
+            waited += Duration::from_millis(200);
+        }
+    }
+}
+
+/// 读取 Engine API 事件流；未能建立连接时返回 false
+///
+/// 套接字设置了读超时，空闲时会周期性返回以检查停止标志
+fn stream_engine_events(stop: &AtomicBool, on_event: &mut dyn FnMut(DockerEvent)) -> bool {
+    let runtime = active_container_runtime()
+        .filter(|r| {
+            r.kind != RuntimeKind::DockerCli
+                && r.kind != RuntimeKind::Nerdctl
+                && !r.endpoint.starts_with("ssh://")
+        })
+        .or_else(detect_engine_runtime);
+    let Some(mut stream) = runtime.and_then(|r| connect_engine(&r.endpoint)) else {
+        return false;
+    };
+
+    // filters={"type":["container"]}
+    let request = format!(
+        "GET /{}/events?filters=%7B%22type%22%3A%5B%22container%22%5D%7D HTTP/1.0\r\nHost: docker\r\nUser-Agent: Portly\r\n\r\n",
+        ENGINE_API_VERSION
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut reader = BufReader::new(stream);
+    let mut buf = Vec::new();
+    let mut status_checked = false;
+    let mut in_body = false;
+    while !stop.load(Ordering::Relaxed) {
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buf).to_string();
+                buf.clear();
+                if !status_checked {
+                    let ok = line
+                        .split_whitespace()
+                        .nth(1)
+                        .and_then(|code| code.parse::<u16>().ok())
+                        .is_some_and(|code| (200..300).contains(&code));
+                    if !ok {
+                        return false;
+                    }
+                    status_checked = true;
+                } else if !in_body {
+                    in_body = line.trim().is_empty();
+                } else if let Some(event) = parse_docker_event(&line) {
+                    invalidate_container_cache();
+                    on_event(event);
+                }
+            }
+            // 读超时：保留已读取的半行数据，继续等待
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) => {}
+            Err(_) => break,
+        }
+    }
+
+    true
+}
+
+/// 通过 `docker events` 命令行读取事件流；命令无法启动时返回 false
+fn stream_cli_events(stop: &AtomicBool, on_event: &mut dyn FnMut(DockerEvent)) -> bool {
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::{self, RecvTimeoutError};
+
+    let mut command = Command::new("docker");
+    command
+        .args(docker_host_args())
+        .args([
+            "events",
+            "--format",
+            "{{json .}}",
+            "--filter",
+            "type=container",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    hide_console_window(&mut command);
+
+    let Ok(mut child) = command.spawn() else {
+        return false;
+    };
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        let _ = child.wait();
+        return false;
+    };
+
+    // 读取放在独立线程，主循环得以按时检查停止标志
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    while !stop.load(Ordering::Relaxed) {
+        match rx.recv_timeout(Duration::from_millis(500)) {
+            Ok(line) => {
+                if let Some(event) = parse_docker_event(&line) {
+                    invalidate_container_cache();
+                    on_event(event);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+    true
+}
+
+/// 解析 Docker ps 输出（用于测试）
+pub fn parse_docker_ps_output(output: &str) -> Vec<DockerContainer> {
+    let mut containers = Vec::new();
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let parts: Vec<&str> = line.split('\n').collect();
+        if parts.len() >= 4 {
+            let ports = if parts.len() >= 5 {
+                parse_docker_ports(parts[4])
+            } else {
+                Vec::new()
+            };
+
+            containers.push(DockerContainer {
+                id: parts[0].to_string(),
+                name: parts[1].to_string(),
+                image: parts[2].to_string(),
+                status: parts[3].to_string(),
+                ports,
+                state: String::new(),
+                labels: HashMap::new(),
+                networks: Vec::new(),
+                exposed_ports: Vec::new(),
+                ip_address: None,
+                health: None,
+            });
+        }
+    }
+
+    containers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+    use crate::app_error::PortlyErrorKind;
+
+    #[test]
+    fn test_docker_port_serialization() {
+        let port = DockerPort {
+            host_port: 8080,
+            container_port: 80,
+            protocol: "tcp".to_string(),
+            host_ip: "0.0.0.0".to_string(),
+        };
+
+        let serialized = serde_json::to_string(&port).unwrap();
+        let deserialized: DockerPort = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.host_port, 8080);
+        assert_eq!(deserialized.container_port, 80);
+    }
+
+    #[test]
+    fn test_docker_container_full_serialization() {
+        let container = DockerContainer {
+            id: "abc123".to_string(),
+            name: "test-container".to_string(),
+            image: "nginx:latest".to_string(),
+            status: "running".to_string(),
+            state: "running".to_string(),
+            labels: HashMap::new(),
+            networks: Vec::new(),
+            exposed_ports: Vec::new(),
+            ip_address: None,
+            health: None,
+            ports: vec![],
+        };
+
+        let serialized = serde_json::to_string(&container).unwrap();
+        let deserialized: DockerContainer = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.name, "test-container");
+        assert_eq!(deserialized.image, "nginx:latest");
+    }
+
+    #[test]
+    fn test_tcp_socket_addr_default_port() {
+        assert_eq!(tcp_socket_addr("10.0.0.5"), "10.0.0.5:2375");
+        assert_eq!(tcp_socket_addr("docker.lan:2375/"), "docker.lan:2375");
+        assert_eq!(tcp_socket_addr("[::1]"), "[::1]:2375");
+        assert_eq!(tcp_socket_addr("[::1]:2376"), "[::1]:2376");
+        assert_eq!(tcp_socket_addr("fd00::5"), "[fd00::5]:2375");
+        assert!(is_tls_endpoint("tcp://[fd00::5]:2376"));
+    }
+
+    #[test]
+    fn test_parse_docker_ports_empty() {
+        let ports = parse_docker_ports("");
+        assert!(ports.is_empty());
+    }
+
+    #[test]
+    fn test_parse_docker_ports_single_mapping() {
+        let ports = parse_docker_ports("0.0.0.0:8080->80/tcp");
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].host_port, 8080);
+        assert_eq!(ports[0].container_port, 80);
+        assert_eq!(ports[0].protocol, "tcp");
+        assert_eq!(ports[0].host_ip, "0.0.0.0");
+    }
+
+    #[test]
+    fn test_parse_docker_ports_multiple_mappings() {
+        let ports = parse_docker_ports("0.0.0.0:8080->80/tcp, 0.0.0.0:8443->443/tcp");
+        assert_eq!(ports.len(), 2);
+        assert_eq!(ports[0].host_port, 8080);
+        assert_eq!(ports[1].host_port, 8443);
+    }
+
+    #[test]
+    fn test_parse_docker_ports_ipv6_filtered() {
+        let ports = parse_docker_ports("[::]:8080->80/tcp");
+        // IPv6 格式应该被过滤掉
+        assert_eq!(ports.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_docker_ports_default_host_ip() {
+        let ports = parse_docker_ports("8080->80/tcp");
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].host_ip, "0.0.0.0");
+    }
+
+    #[test]
+    fn test_parse_docker_ports_udp_protocol() {
+        let ports = parse_docker_ports("0.0.0.0:53->53/udp");
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].protocol, "udp");
+    }
+
+    #[test]
+    fn test_parse_docker_ports_no_protocol() {
+        let ports = parse_docker_ports("8080->80");
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].protocol, "tcp");
+    }
+
+    #[test]
+    fn test_get_docker_port_info_from_containers_found() {
+        let containers = vec![DockerContainer {
+            id: "test".to_string(),
+            name: "nginx".to_string(),
+            image: "nginx:latest".to_string(),
+            status: "running".to_string(),
+            state: "running".to_string(),
+            labels: HashMap::new(),
+            networks: Vec::new(),
+            exposed_ports: Vec::new(),
+            ip_address: None,
+            health: None,
+            ports: vec![DockerPort {
+                host_port: 8080,
+                container_port: 80,
+                protocol: "tcp".to_string(),
+                host_ip: "0.0.0.0".to_string(),
+            }],
+        }];
+
+        let result = get_docker_port_info_from_containers(&containers, 8080);
+        assert!(result.is_some());
+        let (name, port, health) = result.unwrap();
+        assert_eq!(name, "nginx");
+        assert_eq!(port.host_port, 8080);
+        assert_eq!(health, None);
+    }
+
+    #[test]
+    fn test_get_docker_port_info_from_containers_not_found() {
+        let containers = vec![];
+        let result = get_docker_port_info_from_containers(&containers, 9999);
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_get_docker_port_info_from_containers_multiple_containers() {
+        let containers = vec![
+            DockerContainer {
+                id: "1".to_string(),
+                name: "nginx".to_string(),
+                image: "nginx:latest".to_string(),
+                status: "running".to_string(),
+                state: "running".to_string(),
+                labels: HashMap::new(),
+                networks: Vec::new(),
+                exposed_ports: Vec::new(),
+                ip_address: None,
+                health: None,
+                ports: vec![DockerPort {
+                    host_port: 80,
+                    container_port: 80,
+                    protocol: "tcp".to_string(),
+                    host_ip: "0.0.0.0".to_string(),
+                }],
+            },
+            DockerContainer {
+                id: "2".to_string(),
+                name: "postgres".to_string(),
+                image: "postgres:15".to_string(),
+                status: "running".to_string(),
+                state: "running".to_string(),
+                labels: HashMap::new(),
+                networks: Vec::new(),
+                exposed_ports: Vec::new(),
+                ip_address: None,
+                health: Some(HealthStatus::Unhealthy),
+                ports: vec![DockerPort {
+                    host_port: 5432,
+                    container_port: 5432,
+                    protocol: "tcp".to_string(),
+                    host_ip: "0.0.0.0".to_string(),
+                }],
+            },
+        ];
+
+        let result = get_docker_port_info_from_containers(&containers, 5432);
+        assert!(result.is_some());
+        let (name, port, health) = result.unwrap();
+        assert_eq!(name, "postgres");
+        assert_eq!(port.container_port, 5432);
+        // 端口表据此提示映射端口背后的服务健康检查失败
+        assert_eq!(health, Some(HealthStatus::Unhealthy));
+    }
+
+    #[test]
+    fn test_docker_port_with_range() {
+        let ports = parse_docker_ports("0.0.0.0:8000-8005->80/tcp");
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].host_port, 8000);
+        assert_eq!(ports[0].container_port, 80);
+    }
+
+    #[test]
+    fn test_parse_docker_ports_duplicate_filtered() {
+        // IPv4 和 IPv6 可能产生相同的端口映射，应该去重
+        let ports = parse_docker_ports("0.0.0.0:8080->80/tcp, 0.0.0.0:8080->80/tcp");
+        assert_eq!(ports.len(), 1);
+    }
+
+    #[test]
+    fn test_docker_container_with_multiple_ports() {
+        let container = DockerContainer {
+            id: "abc123".to_string(),
+            name: "multi-port".to_string(),
+            image: "test:latest".to_string(),
+            status: "running".to_string(),
+            state: "running".to_string(),
+            labels: HashMap::new(),
+            networks: Vec::new(),
+            exposed_ports: Vec::new(),
+            ip_address: None,
+            health: None,
+            ports: vec![
+                DockerPort {
+                    host_port: 80,
+                    container_port: 80,
+                    protocol: "tcp".to_string(),
+                    host_ip: "0.0.0.0".to_string(),
+                },
+                DockerPort {
+                    host_port: 443,
+                    container_port: 443,
+                    protocol: "tcp".to_string(),
+                    host_ip: "0.0.0.0".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(container.ports.len(), 2);
+        assert_eq!(container.ports[0].host_port, 80);
+        assert_eq!(container.ports[1].host_port, 443);
+    }
+
+    #[test]
+    fn test_parse_http_response_ok() {
+        let body = parse_http_response("HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\n\r\nOK");
+        assert_eq!(body.as_deref(), Some("OK"));
+    }
+
+    #[test]
+    fn test_parse_http_response_error_status() {
+        assert!(parse_http_response("HTTP/1.0 404 Not Found\r\n\r\n{}").is_none());
+        assert!(parse_http_response("garbage").is_none());
+    }
+
+    #[test]
+    fn test_parse_engine_containers() {
+        let body = r#"[{
+            "Id": "0123456789abcdef0123",
+            "Names": ["/web"],
+            "Image": "nginx:latest",
+            "State": "running",
+            "Status": "Up 2 hours",
+            "Ports": [
+                {"IP": "0.0.0.0", "PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"},
+                {"IP": "::", "PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"},
+                {"PrivatePort": 443, "Type": "tcp"}
+            ],
+            "Labels": {"com.docker.compose.project": "shop"},
+            "NetworkSettings": {"Networks": {"shop_default": {"IPAddress": "172.18.0.2"}}}
+        }]"#;
+
+        let containers = parse_engine_containers(body).unwrap();
+        assert_eq!(containers.len(), 1);
+        let c = &containers[0];
+        assert_eq!(c.id, "0123456789ab");
+        assert_eq!(c.name, "web");
+        assert_eq!(c.state, "running");
+        assert_eq!(c.ports.len(), 1);
+        assert_eq!(c.ports[0].host_port, 8080);
+        assert_eq!(c.ports[0].container_port, 80);
+        assert_eq!(
+            c.labels
+                .get("com.docker.compose.project")
+                .map(String::as_str),
+            Some("shop")
+        );
+        assert_eq!(c.networks, vec!["shop_default".to_string()]);
+        assert_eq!(
+            c.exposed_ports,
+            vec![ExposedPort {
+                container_port: 443,
+                protocol: "tcp".to_string()
+            }]
+        );
+        assert_eq!(c.ip_address.as_deref(), Some("172.18.0.2"));
+        assert_eq!(c.health, None);
+    }
+
+    #[test]
+    fn test_parse_health_status() {
+        assert_eq!(
+            parse_health_status("Up 2 hours (healthy)"),
+            Some(HealthStatus::Healthy)
+        );
+        assert_eq!(
+            parse_health_status("Up 3 minutes (unhealthy)"),
+            Some(HealthStatus::Unhealthy)
+        );
+        assert_eq!(
+            parse_health_status("Up 5 seconds (health: starting)"),
+            Some(HealthStatus::Starting)
+        );
+        assert_eq!(parse_health_status("Up 2 hours"), None);
+        assert_eq!(
+            serde_json::to_string(&HealthStatus::Unhealthy).unwrap(),
+            "\"unhealthy\""
+        );
+    }
+
+    #[test]
+    fn test_parse_exposed_ports() {
+        let exposed = parse_exposed_ports("0.0.0.0:8080->80/tcp, 5432/tcp, 9000-9001/udp");
+        assert_eq!(exposed.len(), 3);
+        assert_eq!(exposed[0].container_port, 5432);
+        assert_eq!(exposed[1].container_port, 9000);
+        assert_eq!(exposed[2].container_port, 9001);
+        assert_eq!(exposed[2].protocol, "udp");
+        assert!(parse_exposed_ports("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_docker_inspect_ips() {
+        let ips = parse_docker_inspect_ips("/web\t172.17.0.2 10.0.0.3 \n/isolated\t\n");
+        assert_eq!(ips.get("web").map(String::as_str), Some("172.17.0.2"));
+        assert!(!ips.contains_key("isolated"));
+    }
+
+    #[test]
+    fn test_parse_engine_containers_null_labels() {
+        let body = r#"[{"Id": "abc", "Names": ["/x"], "Image": "i", "State": "running", "Status": "Up", "Ports": [], "Labels": null}]"#;
+        let containers = parse_engine_containers(body).unwrap();
+        assert!(containers[0].labels.is_empty());
+        assert!(parse_engine_containers("not json").is_none());
+    }
+
+    #[test]
+    fn test_parse_docker_ps_lines_with_labels_and_networks() {
+        let stdout = "abc123\tweb\tnginx\tUp 1 minute\t0.0.0.0:8080->80/tcp\trunning\tcom.docker.compose.project=shop,com.docker.compose.service=web\tshop_default\n";
+        let containers = parse_docker_ps_lines(stdout);
+        assert_eq!(containers.len(), 1);
+        let c = &containers[0];
+        assert_eq!(c.ports[0].host_port, 8080);
+        assert_eq!(c.state, "running");
+        assert_eq!(
+            c.labels
+                .get("com.docker.compose.service")
+                .map(String::as_str),
+            Some("web")
+        );
+        assert_eq!(c.networks, vec!["shop_default".to_string()]);
+    }
+
+    #[test]
+    fn test_get_docker_containers_cli_fixture() {
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                &format!("docker ps --format {DOCKER_PS_FORMAT}"),
+                include_str!("../fixtures/linux/docker_ps.txt"),
+            )
+            .with_stdout(
+                &format!(
+                    "docker inspect --format {DOCKER_INSPECT_IP_FORMAT} 3f4e5d6c7b8a 9a8b7c6d5e4f"
+                ),
+                include_str!("../fixtures/linux/docker_inspect.txt"),
+            );
+        let containers = get_docker_containers_cli(&runner).unwrap();
+
+        assert_eq!(containers.len(), 2);
+        let web = &containers[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.ports.len(), 1);
+        assert_eq!(web.ports[0].host_port, 8080);
+        assert_eq!(web.health, Some(HealthStatus::Healthy));
+        assert_eq!(web.compose_project(), Some("shop"));
+        assert_eq!(web.ip_address.as_deref(), Some("172.18.0.3"));
+        let db = &containers[1];
+        assert!(db.ports.is_empty());
+        assert_eq!(db.exposed_ports.len(), 1);
+        assert_eq!(db.ip_address.as_deref(), Some("172.18.0.2"));
+
+        let runner = FixtureRunner::new().with_output(
+            &format!("docker ps --format {DOCKER_PS_FORMAT}"),
+            1,
+            "",
+            "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?",
+        );
+        let err = get_docker_containers_cli(&runner).unwrap_err();
+        assert_eq!(err.kind(), crate::PortlyErrorKind::HostUnreachable);
+    }
+
+    #[test]
+    fn test_parse_docker_ps_lines_legacy_format() {
+        let containers = parse_docker_ps_lines("abc\tdb\tpostgres\tUp\t\n");
+        assert_eq!(containers.len(), 1);
+        assert!(containers[0].ports.is_empty());
+        assert!(containers[0].labels.is_empty());
+    }
+
+    #[test]
+    fn test_docker_container_deserialize_without_new_fields() {
+        let json = r#"{"id":"a","name":"b","image":"c","status":"Up","ports":[]}"#;
+        let container: DockerContainer = serde_json::from_str(json).unwrap();
+        assert!(container.state.is_empty());
+        assert!(container.networks.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_user_socket_candidates_order() {
+        use std::fs;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let home = std::env::temp_dir().join(format!("portly_vm_sock_{suffix}"));
+        for dir in [
+            ".colima/default",
+            ".colima/work",
+            ".lima/docker/sock",
+            ".orbstack/run",
+            ".rd",
+        ] {
+            fs::create_dir_all(home.join(dir)).unwrap();
+        }
+        for sock in [
+            ".colima/default/docker.sock",
+            ".colima/work/docker.sock",
+            ".lima/docker/sock/docker.sock",
+            ".orbstack/run/docker.sock",
+            ".rd/docker.sock",
+        ] {
+            fs::write(home.join(sock), "").unwrap();
+        }
+
+        let sockets = user_socket_candidates(&home);
+        let _ = fs::remove_dir_all(&home);
+
+        let kinds: Vec<RuntimeKind> = sockets.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                RuntimeKind::OrbStack,
+                RuntimeKind::Colima,
+                RuntimeKind::Colima,
+                RuntimeKind::Lima,
+                RuntimeKind::RancherDesktop,
+            ]
+        );
+        assert!(sockets[1].1.ends_with(".colima/default/docker.sock"));
+        assert!(sockets[2].1.ends_with(".colima/work/docker.sock"));
+    }
+
+    #[test]
+    fn test_engine_socket_candidates_unique() {
+        let candidates = engine_socket_candidates();
+        let unique: std::collections::HashSet<_> = candidates.iter().map(|(_, e)| e).collect();
+        assert_eq!(unique.len(), candidates.len());
+    }
+
+    #[test]
+    fn test_runtime_kind_serialization() {
+        assert_eq!(
+            serde_json::to_string(&RuntimeKind::RancherDesktop).unwrap(),
+            "\"rancher-desktop\""
+        );
+    }
+
+    #[test]
+    fn test_probe_container_runtimes_includes_cli_fallbacks() {
+        let probes = probe_container_runtimes();
+        assert!(probes.iter().any(|p| p.kind == RuntimeKind::DockerCli));
+        assert!(probes.iter().any(|p| p.kind == RuntimeKind::Nerdctl));
+    }
+
+    #[test]
+    fn test_parse_nerdctl_ps_lines() {
+        let stdout = "f00dbeef1234\tredis\tdocker.io/library/redis:7\tUp\t0.0.0.0:6379->6379/tcp\n";
+        let containers = parse_docker_ps_lines(stdout);
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].image, "docker.io/library/redis:7");
+        assert_eq!(containers[0].ports[0].host_port, 6379);
+    }
+
+    fn compose_container(
+        name: &str,
+        project: Option<&str>,
+        service: Option<&str>,
+        host_port: u16,
+    ) -> DockerContainer {
+        let mut labels = HashMap::new();
+        if let Some(project) = project {
+            labels.insert(COMPOSE_PROJECT_LABEL.to_string(), project.to_string());
+        }
+        if let Some(service) = service {
+            labels.insert(COMPOSE_SERVICE_LABEL.to_string(), service.to_string());
+        }
+        DockerContainer {
+            id: name.to_string(),
+            name: name.to_string(),
+            image: "img".to_string(),
+            status: "Up".to_string(),
+            ports: vec![DockerPort {
+                host_port,
+                container_port: 80,
+                protocol: "tcp".to_string(),
+                host_ip: "0.0.0.0".to_string(),
+            }],
+            state: "running".to_string(),
+            labels,
+            networks: Vec::new(),
+            exposed_ports: Vec::new(),
+            ip_address: None,
+            health: None,
+        }
+    }
+
+    #[test]
+    fn test_group_by_compose_project() {
+        let containers = vec![
+            compose_container("shop-web-1", Some("shop"), Some("web"), 8080),
+            compose_container("shop-web-2", Some("shop"), Some("web"), 8081),
+            compose_container("shop-db-1", Some("shop"), Some("db"), 5432),
+            compose_container("blog-app-1", Some("blog"), Some("app"), 3000),
+            compose_container("lonely", None, None, 9000),
+        ];
+
+        let groups = group_by_compose_project(&containers);
+        assert_eq!(groups.projects.len(), 2);
+        assert_eq!(groups.projects[0].name, "blog");
+        let shop = &groups.projects[1];
+        assert_eq!(shop.services.len(), 2);
+        assert_eq!(shop.services[0].name, "db");
+        let web = &shop.services[1];
+        assert_eq!(web.containers, vec!["shop-web-1", "shop-web-2"]);
+        assert_eq!(web.ports.len(), 2);
+        assert_eq!(groups.standalone.len(), 1);
+        assert_eq!(groups.standalone[0].name, "lonely");
+    }
+
+    #[test]
+    fn test_compose_location_from_labels() {
+        let mut container = compose_container("shop-web-1", Some("shop"), Some("web"), 8080);
+        container.labels.insert(
+            COMPOSE_WORKING_DIR_LABEL.to_string(),
+            "/home/dev/shop".to_string(),
+        );
+        container.labels.insert(
+            COMPOSE_CONFIG_FILES_LABEL.to_string(),
+            "/home/dev/shop/compose.yaml,/home/dev/shop/compose.override.yaml".to_string(),
+        );
+        let containers = vec![container, compose_container("lonely", None, None, 9000)];
+
+        let location = find_compose_location(&containers, 8080).unwrap();
+        assert_eq!(location.project, "shop");
+        assert_eq!(location.service.as_deref(), Some("web"));
+        assert_eq!(location.working_dir.as_deref(), Some("/home/dev/shop"));
+        assert_eq!(location.config_files.len(), 2);
+        assert!(find_compose_location(&containers, 9000).is_none());
+        assert!(find_compose_location(&containers, 1).is_none());
+
+        let groups = group_by_compose_project(&containers);
+        assert_eq!(
+            groups.projects[0].working_dir.as_deref(),
+            Some("/home/dev/shop")
+        );
+        assert_eq!(
+            groups.projects[0].config_files[0],
+            "/home/dev/shop/compose.yaml"
+        );
+    }
+
+    #[test]
+    fn test_compose_service_falls_back_to_container_name() {
+        let containers = vec![compose_container("custom", Some("proj"), None, 1234)];
+        let groups = group_by_compose_project(&containers);
+        assert_eq!(groups.projects[0].services[0].name, "custom");
+    }
+
+    #[test]
+    fn test_normalize_docker_host() {
+        assert_eq!(
+            normalize_docker_host("ssh://admin@10.0.0.5/").unwrap(),
+            "ssh://admin@10.0.0.5"
+        );
+        assert!(normalize_docker_host("tcp://10.0.0.5:2375").is_ok());
+        assert!(normalize_docker_host("http://10.0.0.5").is_err());
+        assert!(normalize_docker_host("tcp://").is_err());
+        assert!(set_docker_host(Some("10.0.0.5".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_is_remote_host() {
+        assert!(is_remote_host("tcp://10.0.0.5:2375"));
+        assert!(is_remote_host("ssh://admin@server"));
+        assert!(!is_remote_host("unix:///var/run/docker.sock"));
+    }
+
+    #[test]
+    fn test_parse_current_context() {
+        assert_eq!(
+            parse_current_context(r#"{"auths": {}, "currentContext": "prod"}"#),
+            Some("prod".to_string())
+        );
+        assert_eq!(parse_current_context(r#"{"auths": {}}"#), None);
+        assert_eq!(parse_current_context(r#"{"currentContext": ""}"#), None);
+    }
+
+    #[test]
+    fn test_parse_context_meta() {
+        let raw = r#"{"Name":"prod","Metadata":{},"Endpoints":{"docker":{"Host":"ssh://deploy@prod.example.com","SkipTLSVerify":false}}}"#;
+        assert_eq!(
+            parse_context_meta(raw, "prod"),
+            Some("ssh://deploy@prod.example.com".to_string())
+        );
+        assert_eq!(parse_context_meta(raw, "staging"), None);
+    }
+
+    #[test]
+    fn test_parse_docker_event_start() {
+        let line = r#"{"status":"start","id":"0123456789abcdef","from":"nginx","Type":"container","Action":"start","Actor":{"ID":"0123456789abcdef","Attributes":{"image":"nginx","name":"web"}},"scope":"local","time":1700000000,"timeNano":1700000000000000000}"#;
+        let event = parse_docker_event(line).unwrap();
+        assert_eq!(event.action, "start");
+        assert_eq!(event.container_id, "0123456789ab");
+        assert_eq!(event.name, "web");
+        assert_eq!(event.image, "nginx");
+        assert_eq!(event.time, 1700000000);
+    }
+
+    #[test]
+    fn test_parse_docker_event_ignores_irrelevant() {
+        let exec = r#"{"Type":"container","Action":"exec_start: sh","Actor":{"ID":"abc","Attributes":{}},"time":1}"#;
+        assert!(parse_docker_event(exec).is_none());
+        let network = r#"{"Type":"network","Action":"connect","Actor":{"ID":"abc","Attributes":{}},"time":1}"#;
+        assert!(parse_docker_event(network).is_none());
+        assert!(parse_docker_event("not json").is_none());
+
+        let health = r#"{"Type":"container","Action":"health_status: unhealthy","Actor":{"ID":"abc","Attributes":{"name":"db"}},"time":1}"#;
+        assert_eq!(parse_docker_event(health).unwrap().action, "health_status");
+    }
+
+    #[test]
+    fn test_ttl_cache() {
+        let start = Instant::now();
+        let mut cache = TtlCache::new(Duration::from_secs(5));
+        assert_eq!(cache.get(start), None::<u32>);
+
+        cache.put(start, 7);
+        assert_eq!(cache.get(start + Duration::from_secs(4)), Some(7));
+        assert_eq!(cache.get(start + Duration::from_secs(5)), None);
+
+        cache.put(start, 8);
+        cache.invalidate();
+        assert_eq!(cache.get(start), None);
+    }
+
+    #[test]
+    fn test_docker_cli_error_classifies_daemon_unreachable() {
+        let err = docker_cli_error(
+            "Docker 容器列表读取",
+            1,
+            "Cannot connect to the Docker daemon at unix:///var/run/docker.sock. Is the docker daemon running?",
+        );
+        assert_eq!(err.kind(), PortlyErrorKind::HostUnreachable);
+
+        let stderr = "permission denied while trying to connect";
+        let err = docker_cli_error("Docker 容器列表读取", 1, stderr);
+        assert_eq!(err.kind(), PortlyErrorKind::PermissionDenied);
+    }
+}
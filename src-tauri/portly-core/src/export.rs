@@ -0,0 +1,3163 @@
+//! Export module for Portly
+//! Provides data export functionality in multiple formats (CSV, JSON, NDJSON, TXT, Markdown, XLSX)
+
+use age::secrecy::SecretString;
+use chrono::Local;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rust_xlsxwriter::{Color, Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::audit::{AuditFinding, AuditReport};
+use crate::core::{AppGroup, ConnectionInfo, PortInfo, ScanResult};
+use crate::docker::DockerContainer;
+use crate::i18n::{current_lang, tr_args, Msg};
+use crate::network::{NetworkDevice, PingResult, RemotePort, TraceHop, TracerouteResult};
+
+/// Export format options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Txt,
+    Markdown,
+    Xlsx,
+    Ndjson,
+}
+
+/// Export result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportResult {
+    pub success: bool,
+    pub path: Option<String>,
+    pub message: String,
+    pub record_count: usize,
+}
+
+/// Scan history entry for persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanHistoryEntry {
+    pub timestamp: String,
+    pub port_count: usize,
+    pub scan_duration_ms: u64,
+    pub ports: Vec<PortInfo>,
+}
+
+/// Progress callback invoked as `(written, total)` while records are written
+pub type ProgressFn<'a> = dyn FnMut(usize, usize) + 'a;
+
+/// Progress is reported every this many records (and once at the end)
+const PROGRESS_INTERVAL: usize = 1000;
+
+/// Progress payload emitted to the frontend during long exports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub path: String,
+    pub written: usize,
+    pub total: usize,
+}
+
+fn report_progress(progress: &mut ProgressFn, written: usize, total: usize) {
+    if written.is_multiple_of(PROGRESS_INTERVAL) || written == total {
+        progress(written, total);
+    }
+}
+
+/// Stream an export through a buffered file writer instead of building it in memory
+fn write_streamed(
+    path: &str,
+    record_count: usize,
+    label: &str,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> ExportResult {
+    let result = File::create(path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        write(&mut writer)?;
+        writer.flush()
+    });
+
+    match result {
+        Ok(_) => ExportResult {
+            success: true,
+            path: Some(path.to_string()),
+            message: tr_args(current_lang(), Msg::ExportDone, &[&record_count, &label]),
+            record_count,
+        },
+        Err(e) => ExportResult {
+            success: false,
+            path: None,
+            message: tr_args(current_lang(), Msg::ExportFailed, &[&label, &e]),
+            record_count: 0,
+        },
+    }
+}
+
+/// Export port data to CSV format
+pub fn export_to_csv(ports: &[PortInfo], path: &str) -> ExportResult {
+    export_to_csv_with_progress(ports, path, &mut |_, _| {})
+}
+
+/// Export port data to CSV, reporting progress while rows are written
+pub fn export_to_csv_with_progress(
+    ports: &[PortInfo],
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    write_streamed(path, ports.len(), "CSV", |out| {
+        write_ports_csv(out, ports, progress)
+    })
+}
+
+/// Write port data as CSV to any writer (file, stdout, ...)
+pub fn write_ports_csv(
+    out: &mut impl Write,
+    ports: &[PortInfo],
+    progress: &mut ProgressFn,
+) -> std::io::Result<()> {
+    // Header
+    writeln!(out, "Port,Protocol,Address,PID,Process,User,Command")?;
+
+    // Data rows
+    for (i, port) in ports.iter().enumerate() {
+        let command = port.command.as_deref().unwrap_or("");
+        // Escape commas and quotes in command
+        let escaped_command = if command.contains(',') || command.contains('"') {
+            format!("\"{}\"", command.replace('"', "\"\""))
+        } else {
+            command.to_string()
+        };
+
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            port.port,
+            port.protocol,
+            port.address,
+            port.pid,
+            port.process,
+            port.user,
+            escaped_command
+        )?;
+        report_progress(progress, i + 1, ports.len());
+    }
+    Ok(())
+}
+
+/// Borrowed JSON export document, serialized straight to the file
+#[derive(Serialize)]
+struct JsonScanDocument<'a> {
+    export_time: String,
+    scan_time: &'a str,
+    total_ports: usize,
+    ports: &'a [PortInfo],
+}
+
+/// Export port data to JSON format
+pub fn export_to_json(scan_result: &ScanResult, path: &str) -> ExportResult {
+    write_streamed(path, scan_result.total_ports, "JSON", |out| {
+        write_scan_json(out, scan_result)
+    })
+}
+
+/// Export port data to JSON; the document is serialized in one go, so progress is
+/// reported once when it is written
+pub fn export_to_json_with_progress(
+    scan_result: &ScanResult,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let result = export_to_json(scan_result, path);
+    progress(result.record_count, scan_result.total_ports);
+    result
+}
+
+fn write_scan_json(out: &mut impl Write, scan_result: &ScanResult) -> std::io::Result<()> {
+    let document = JsonScanDocument {
+        export_time: Local::now().to_rfc3339(),
+        scan_time: &scan_result.scan_time,
+        total_ports: scan_result.total_ports,
+        ports: &scan_result.ports,
+    };
+    serde_json::to_writer_pretty(out, &document).map_err(std::io::Error::from)
+}
+
+/// Export port data as newline-delimited JSON (one record per line)
+pub fn export_to_ndjson(ports: &[PortInfo], path: &str) -> ExportResult {
+    export_to_ndjson_with_progress(ports, path, &mut |_, _| {})
+}
+
+/// Export port data as NDJSON, reporting progress while lines are written
+pub fn export_to_ndjson_with_progress(
+    ports: &[PortInfo],
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    write_streamed(path, ports.len(), "NDJSON", |out| {
+        write_ndjson(out, ports, progress)
+    })
+}
+
+/// Remote port record tagged with the scanned host and its inventory tags
+#[derive(Serialize)]
+pub struct RemotePortRecord<'a> {
+    pub host: &'a str,
+    #[serde(flatten)]
+    pub port: &'a RemotePort,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    pub tags: &'a [String],
+}
+
+/// Export remote scan results as newline-delimited JSON, each line tagged with the host
+pub fn export_remote_to_ndjson(host: &str, ports: &[RemotePort], path: &str) -> ExportResult {
+    export_remote_ports_with_progress(host, ports, &ExportFormat::Ndjson, path, &mut |_, _| {})
+}
+
+/// Export remote scan results in any format; every record carries the scanned host so
+/// JSON / NDJSON lines match `export_remote_to_ndjson` and tables get a Host column
+pub fn export_remote_ports_with_progress(
+    host: &str,
+    ports: &[RemotePort],
+    format: &ExportFormat,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let tags = crate::inventory::lookup(&[host]).tags;
+    let records: Vec<RemotePortRecord> = ports
+        .iter()
+        .map(|port| RemotePortRecord {
+            host,
+            port,
+            tags: &tags,
+        })
+        .collect();
+    let title = format!("远程端口扫描报告 ({})", host);
+    export_records_with_progress(&records, format, &title, path, progress)
+}
+
+/// Write records as compact JSON, one per line
+pub fn write_ndjson<T: Serialize>(
+    out: &mut impl Write,
+    records: &[T],
+    progress: &mut ProgressFn,
+) -> std::io::Result<()> {
+    for (i, record) in records.iter().enumerate() {
+        serde_json::to_writer(&mut *out, record)?;
+        out.write_all(b"\n")?;
+        report_progress(progress, i + 1, records.len());
+    }
+    Ok(())
+}
+
+/// Export port data to plain text format (human-readable)
+pub fn export_to_txt(ports: &[PortInfo], path: &str) -> ExportResult {
+    export_to_txt_with_progress(ports, path, &mut |_, _| {})
+}
+
+/// Export port data to plain text, reporting progress while entries are written
+pub fn export_to_txt_with_progress(
+    ports: &[PortInfo],
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    write_streamed(path, ports.len(), "文本文件", |out| {
+        write_ports_txt(out, ports, progress)
+    })
+}
+
+fn write_ports_txt(
+    out: &mut impl Write,
+    ports: &[PortInfo],
+    progress: &mut ProgressFn,
+) -> std::io::Result<()> {
+    // Header with timestamp
+    writeln!(
+        out,
+        "Portly 端口扫描报告\n导出时间: {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    )?;
+    writeln!(out, "共计 {} 个端口", ports.len())?;
+    writeln!(out, "{}\n", "=".repeat(80))?;
+
+    // Port entries
+    for (i, port) in ports.iter().enumerate() {
+        writeln!(
+            out,
+            "端口: {:<6} | {} | {} | PID: {}",
+            port.port, port.protocol, port.process, port.pid
+        )?;
+        writeln!(out, "  地址: {} | 用户: {}", port.address, port.user)?;
+        if let Some(cmd) = &port.command {
+            writeln!(out, "  命令: {}", cmd)?;
+        }
+        writeln!(out)?;
+        report_progress(progress, i + 1, ports.len());
+    }
+    Ok(())
+}
+
+/// Export port data as a GitHub-flavored Markdown table
+pub fn export_to_markdown(ports: &[PortInfo], path: &str) -> ExportResult {
+    write_export_result(path, &ports_markdown(ports), ports.len(), "Markdown")
+}
+
+/// Export port data as Markdown, reporting progress once the table is written
+pub fn export_to_markdown_with_progress(
+    ports: &[PortInfo],
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let result = export_to_markdown(ports, path);
+    progress(result.record_count, ports.len());
+    result
+}
+
+fn ports_markdown(ports: &[PortInfo]) -> String {
+    let rows: Vec<Vec<String>> = ports
+        .iter()
+        .map(|port| {
+            vec![
+                port.port.to_string(),
+                port.protocol.clone(),
+                port.address.clone(),
+                port.pid.clone(),
+                port.process.clone(),
+                port.user.clone(),
+                port.command.clone().unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    format!(
+        "# Portly 端口扫描报告\n\n导出时间: {}\n\n共计 {} 个端口\n\n{}",
+        Local::now().format("%Y-%m-%d %H:%M:%S"),
+        ports.len(),
+        markdown_table(
+            &["Port", "Protocol", "Address", "PID", "Process", "User", "Command"],
+            &rows
+        )
+    )
+}
+
+/// Export discovered LAN devices as a GitHub-flavored Markdown table
+pub fn export_devices_to_markdown(devices: &[NetworkDevice], path: &str) -> ExportResult {
+    export_records(devices, &ExportFormat::Markdown, "局域网设备报告", path)
+}
+
+/// Render a Markdown table; pipes and line breaks inside cells are escaped
+fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut table = format!("| {} |\n", headers.join(" | "));
+    table.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|cell| escape_markdown_cell(cell)).collect();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    table
+}
+
+fn escape_markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace(['\n', '\r'], " ")
+}
+
+/// Cell value for spreadsheet export
+enum XlsxCell {
+    Text(String),
+    Number(f64),
+}
+
+impl XlsxCell {
+    /// Plain decimal cells (ports, PIDs, latencies) become numbers; anything else stays
+    /// text, so host or service names such as "nan" or "inf" are never coerced
+    fn from_cell(cell: String) -> Self {
+        let decimal = cell.bytes().any(|b| b.is_ascii_digit())
+            && cell
+                .bytes()
+                .enumerate()
+                .all(|(i, b)| b.is_ascii_digit() || b == b'.' || (i == 0 && b == b'-'));
+        match cell.parse::<f64>() {
+            Ok(number) if decimal && number.is_finite() => XlsxCell::Number(number),
+            _ => XlsxCell::Text(cell),
+        }
+    }
+}
+
+impl From<String> for XlsxCell {
+    fn from(value: String) -> Self {
+        XlsxCell::Text(value)
+    }
+}
+
+impl From<u16> for XlsxCell {
+    fn from(value: u16) -> Self {
+        XlsxCell::Number(f64::from(value))
+    }
+}
+
+impl ExportFormat {
+    /// Parse a user-facing format name such as "csv", "md" or "jsonl"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            "txt" => Some(ExportFormat::Txt),
+            "md" | "markdown" => Some(ExportFormat::Markdown),
+            "xlsx" | "excel" => Some(ExportFormat::Xlsx),
+            "ndjson" | "jsonl" => Some(ExportFormat::Ndjson),
+            _ => None,
+        }
+    }
+}
+
+/// A tabular record that can be written in every export format
+pub trait ExportRecord: Serialize {
+    /// Column headers for table-based formats (CSV, Markdown, TXT, XLSX)
+    const HEADERS: &'static [&'static str];
+
+    /// Cell values in the same order as `HEADERS`
+    fn row(&self) -> Vec<String>;
+}
+
+fn optional_ms(value: Option<f32>) -> String {
+    value.map(|v| format!("{:.2}", v)).unwrap_or_default()
+}
+
+impl ExportRecord for NetworkDevice {
+    const HEADERS: &'static [&'static str] = &["IP", "MAC", "Hostname", "Status", "Tags"];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.ip.clone(),
+            self.mac.clone().unwrap_or_default(),
+            self.hostname.clone().unwrap_or_default(),
+            if self.is_online { "在线" } else { "离线" }.to_string(),
+            self.tags.join(" "),
+        ]
+    }
+}
+
+impl ExportRecord for AppGroup {
+    const HEADERS: &'static [&'static str] = &["Process", "PID", "Ports", "Command"];
+
+    fn row(&self) -> Vec<String> {
+        let ports: Vec<String> = self.ports.iter().map(u16::to_string).collect();
+        vec![
+            self.process.clone(),
+            self.pid.clone(),
+            ports.join(" "),
+            self.command.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl ExportRecord for ConnectionInfo {
+    const HEADERS: &'static [&'static str] =
+        &["Protocol", "Local", "Remote", "State", "PID", "Process"];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.protocol.clone(),
+            self.local_endpoint(),
+            self.remote_endpoint(),
+            self.state.clone(),
+            self.pid.clone(),
+            self.process.clone(),
+        ]
+    }
+}
+
+impl ExportRecord for RemotePort {
+    const HEADERS: &'static [&'static str] = &["Port", "Status", "Service"];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.port.to_string(),
+            if self.is_open { "开放" } else { "关闭" }.to_string(),
+            self.service.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl ExportRecord for RemotePortRecord<'_> {
+    const HEADERS: &'static [&'static str] = &["Host", "Port", "Status", "Service", "Tags"];
+
+    fn row(&self) -> Vec<String> {
+        let mut row = vec![self.host.to_string()];
+        row.extend(self.port.row());
+        row.push(self.tags.join(" "));
+        row
+    }
+}
+
+impl ExportRecord for PingResult {
+    const HEADERS: &'static [&'static str] = &[
+        "IP",
+        "Reachable",
+        "Sent",
+        "Received",
+        "Loss %",
+        "Min ms",
+        "Avg ms",
+        "Max ms",
+    ];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.ip.clone(),
+            if self.is_reachable { "是" } else { "否" }.to_string(),
+            self.packets_sent.to_string(),
+            self.packets_received.to_string(),
+            format!("{:.1}", self.packet_loss),
+            optional_ms(self.min_ms),
+            optional_ms(self.avg_ms),
+            optional_ms(self.max_ms),
+        ]
+    }
+}
+
+impl ExportRecord for TraceHop {
+    const HEADERS: &'static [&'static str] = &["Hop", "IP", "Hostname", "Time ms"];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.hop.to_string(),
+            self.ip.clone().unwrap_or_else(|| "*".to_string()),
+            self.hostname.clone().unwrap_or_default(),
+            optional_ms(self.time_ms),
+        ]
+    }
+}
+
+impl ExportRecord for AuditFinding {
+    const HEADERS: &'static [&'static str] = &[
+        "Severity",
+        "Category",
+        "Port",
+        "Process",
+        "Title",
+        "Detail",
+        "Remediation",
+    ];
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.severity.name().to_string(),
+            self.category.name().to_string(),
+            self.port.map(|port| port.to_string()).unwrap_or_default(),
+            self.process.clone().unwrap_or_default(),
+            self.title.clone(),
+            self.detail.clone(),
+            self.remediation.clone(),
+        ]
+    }
+}
+
+/// Export any tabular records (devices, remote ports, ping results, trace hops, audit findings)
+pub fn export_records<T: ExportRecord>(
+    records: &[T],
+    format: &ExportFormat,
+    title: &str,
+    path: &str,
+) -> ExportResult {
+    export_records_with_progress(records, format, title, path, &mut |_, _| {})
+}
+
+/// Export tabular records, reporting progress while rows are written
+pub fn export_records_with_progress<T: ExportRecord>(
+    records: &[T],
+    format: &ExportFormat,
+    title: &str,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    export_table(
+        T::HEADERS,
+        records,
+        ExportRecord::row,
+        format,
+        title,
+        path,
+        progress,
+    )
+}
+
+/// Write tabular records as CSV to any writer (file, stdout, ...)
+pub fn write_records_csv<T: ExportRecord>(
+    out: &mut impl Write,
+    records: &[T],
+) -> std::io::Result<()> {
+    write_table_csv(out, T::HEADERS, records, ExportRecord::row, &mut |_, _| {})
+}
+
+fn write_table_csv<R, H: AsRef<str>>(
+    out: &mut impl Write,
+    headers: &[H],
+    records: &[R],
+    row: impl Fn(&R) -> Vec<String>,
+    progress: &mut ProgressFn,
+) -> std::io::Result<()> {
+    let headers: Vec<&str> = headers.iter().map(AsRef::as_ref).collect();
+    writeln!(out, "{}", headers.join(","))?;
+    for (i, record) in records.iter().enumerate() {
+        let cells: Vec<String> = row(record).iter().map(|cell| csv_escape(cell)).collect();
+        writeln!(out, "{}", cells.join(","))?;
+        report_progress(progress, i + 1, records.len());
+    }
+    Ok(())
+}
+
+/// Borrowed JSON document for table exports
+#[derive(Serialize)]
+struct JsonTableDocument<'a, R> {
+    export_time: String,
+    title: &'a str,
+    total: usize,
+    records: &'a [R],
+}
+
+/// Render a table in the requested format; `records` backs the JSON/NDJSON output
+/// and `row` produces the cells for the table formats
+fn export_table<R: Serialize, H: AsRef<str>>(
+    headers: &[H],
+    records: &[R],
+    row: impl Fn(&R) -> Vec<String>,
+    format: &ExportFormat,
+    title: &str,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let headers: Vec<&str> = headers.iter().map(AsRef::as_ref).collect();
+    let exported_at = Local::now();
+    let total = records.len();
+    let label = format_label(format);
+
+    match format {
+        ExportFormat::Csv => write_streamed(path, total, label, |out| {
+            write_table_csv(out, &headers, records, row, progress)
+        }),
+        ExportFormat::Json => {
+            let document = JsonTableDocument {
+                export_time: exported_at.to_rfc3339(),
+                title,
+                total,
+                records,
+            };
+            write_streamed(path, total, label, |out| {
+                serde_json::to_writer_pretty(out, &document).map_err(std::io::Error::from)
+            })
+        }
+        ExportFormat::Ndjson => write_streamed(path, total, label, |out| {
+            write_ndjson(out, records, progress)
+        }),
+        ExportFormat::Markdown => write_streamed(path, total, label, |out| {
+            write!(
+                out,
+                "# Portly {}\n\n导出时间: {}\n\n共计 {} 条记录\n\n",
+                title,
+                exported_at.format("%Y-%m-%d %H:%M:%S"),
+                total
+            )?;
+            writeln!(out, "| {} |", headers.join(" | "))?;
+            writeln!(out, "|{}", " --- |".repeat(headers.len()))?;
+            for (i, record) in records.iter().enumerate() {
+                let cells: Vec<String> = row(record)
+                    .iter()
+                    .map(|cell| escape_markdown_cell(cell))
+                    .collect();
+                writeln!(out, "| {} |", cells.join(" | "))?;
+                report_progress(progress, i + 1, total);
+            }
+            Ok(())
+        }),
+        ExportFormat::Txt => write_streamed(path, total, label, |out| {
+            writeln!(
+                out,
+                "Portly {}\n导出时间: {}\n共计 {} 条记录\n{}\n",
+                title,
+                exported_at.format("%Y-%m-%d %H:%M:%S"),
+                total,
+                "=".repeat(80)
+            )?;
+            for (i, record) in records.iter().enumerate() {
+                let fields: Vec<String> = headers
+                    .iter()
+                    .zip(row(record))
+                    .map(|(header, value)| format!("{}: {}", header, value))
+                    .collect();
+                writeln!(out, "{}", fields.join(" | "))?;
+                report_progress(progress, i + 1, total);
+            }
+            Ok(())
+        }),
+        ExportFormat::Xlsx => {
+            let rows = records
+                .iter()
+                .map(|record| row(record).into_iter().map(XlsxCell::from_cell).collect());
+            let mut workbook = Workbook::new();
+            let result = write_xlsx_sheet(&mut workbook, true, "Records", &headers, rows, progress)
+                .and_then(|_| workbook.save(path));
+            match result {
+                Ok(_) => ExportResult {
+                    success: true,
+                    path: Some(path.to_string()),
+                    message: tr_args(current_lang(), Msg::ExportDone, &[&total, &"Excel"]),
+                    record_count: total,
+                },
+                Err(e) => ExportResult {
+                    success: false,
+                    path: None,
+                    message: tr_args(current_lang(), Msg::ExportFailed, &[&"Excel", &e]),
+                    record_count: 0,
+                },
+            }
+        }
+    }
+}
+
+// ===== Column Selection & Presets =====
+
+/// Selectable port columns for customised exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortColumn {
+    Port,
+    Protocol,
+    Address,
+    Pid,
+    Process,
+    User,
+    Command,
+}
+
+impl PortColumn {
+    /// All columns in the default export order
+    pub const ALL: [PortColumn; 7] = [
+        PortColumn::Port,
+        PortColumn::Protocol,
+        PortColumn::Address,
+        PortColumn::Pid,
+        PortColumn::Process,
+        PortColumn::User,
+        PortColumn::Command,
+    ];
+
+    /// Parse a column name such as "port", "proc" or "cmd"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "port" => Some(PortColumn::Port),
+            "protocol" | "proto" => Some(PortColumn::Protocol),
+            "address" | "addr" => Some(PortColumn::Address),
+            "pid" => Some(PortColumn::Pid),
+            "process" | "proc" | "app" => Some(PortColumn::Process),
+            "user" => Some(PortColumn::User),
+            "command" | "cmd" => Some(PortColumn::Command),
+            _ => None,
+        }
+    }
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            PortColumn::Port => "Port",
+            PortColumn::Protocol => "Protocol",
+            PortColumn::Address => "Address",
+            PortColumn::Pid => "PID",
+            PortColumn::Process => "Process",
+            PortColumn::User => "User",
+            PortColumn::Command => "Command",
+        }
+    }
+
+    /// Key used in JSON / NDJSON output (matches the `PortInfo` field name)
+    pub fn key(&self) -> &'static str {
+        match self {
+            PortColumn::Port => "port",
+            PortColumn::Protocol => "protocol",
+            PortColumn::Address => "address",
+            PortColumn::Pid => "pid",
+            PortColumn::Process => "process",
+            PortColumn::User => "user",
+            PortColumn::Command => "command",
+        }
+    }
+
+    pub fn value(&self, port: &PortInfo) -> String {
+        match self {
+            PortColumn::Port => port.port.to_string(),
+            PortColumn::Protocol => port.protocol.clone(),
+            PortColumn::Address => port.address.clone(),
+            PortColumn::Pid => port.pid.clone(),
+            PortColumn::Process => port.process.clone(),
+            PortColumn::User => port.user.clone(),
+            PortColumn::Command => port.command.clone().unwrap_or_default(),
+        }
+    }
+
+    fn json_value(&self, port: &PortInfo) -> serde_json::Value {
+        match self {
+            PortColumn::Port => serde_json::Value::from(port.port),
+            PortColumn::Command => port
+                .command
+                .clone()
+                .map(serde_json::Value::from)
+                .unwrap_or(serde_json::Value::Null),
+            _ => serde_json::Value::from(self.value(port)),
+        }
+    }
+}
+
+/// Named export preset: format, column selection/order and filename template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportPreset {
+    pub name: String,
+    pub format: ExportFormat,
+    pub columns: Vec<PortColumn>,
+    /// Supports `{date}`, `{time}`, `{count}` and `{format}` placeholders
+    pub filename_template: String,
+}
+
+/// Default filename template, equivalent to `generate_export_filename`
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "portly_export_{date}_{time}";
+
+/// Export ports with only the selected columns, in the given order
+pub fn export_with_columns(
+    ports: &[PortInfo],
+    columns: &[PortColumn],
+    format: &ExportFormat,
+    path: &str,
+) -> ExportResult {
+    export_with_columns_with_progress(ports, columns, format, path, &mut |_, _| {})
+}
+
+/// Column-selected export, reporting progress while rows are written
+pub fn export_with_columns_with_progress(
+    ports: &[PortInfo],
+    columns: &[PortColumn],
+    format: &ExportFormat,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let columns: &[PortColumn] = if columns.is_empty() {
+        &PortColumn::ALL
+    } else {
+        columns
+    };
+
+    let headers: Vec<&str> = columns.iter().map(PortColumn::header).collect();
+    let records: Vec<ColumnRecord> = ports
+        .iter()
+        .map(|port| ColumnRecord { port, columns })
+        .collect();
+
+    export_table(
+        &headers,
+        &records,
+        |record| columns.iter().map(|c| c.value(record.port)).collect(),
+        format,
+        "端口扫描报告",
+        path,
+        progress,
+    )
+}
+
+/// A port limited to the selected columns; serializes as a JSON object keyed by column
+struct ColumnRecord<'a> {
+    port: &'a PortInfo,
+    columns: &'a [PortColumn],
+}
+
+impl Serialize for ColumnRecord<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for column in self.columns {
+            map.serialize_entry(column.key(), &column.json_value(self.port))?;
+        }
+        map.end()
+    }
+}
+
+/// Render a filename template and append the format's extension
+pub fn render_filename_template(
+    template: &str,
+    format: &ExportFormat,
+    record_count: usize,
+    now: chrono::DateTime<Local>,
+) -> String {
+    let extension = export_extension(format);
+    let template = if template.trim().is_empty() {
+        DEFAULT_FILENAME_TEMPLATE
+    } else {
+        template.trim()
+    };
+
+    let rendered = template
+        .replace("{date}", &now.format("%Y%m%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{count}", &record_count.to_string())
+        .replace("{format}", extension);
+    // 模板只决定文件名，不允许跳出导出目录
+    let sanitized: String = rendered
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect();
+
+    if sanitized
+        .to_lowercase()
+        .ends_with(&format!(".{}", extension))
+    {
+        sanitized
+    } else {
+        format!("{}.{}", sanitized, extension)
+    }
+}
+
+/// Get the path to the export presets file
+fn get_presets_path() -> PathBuf {
+    get_data_dir().join("export_presets.json")
+}
+
+/// Load saved export presets
+pub fn load_export_presets() -> Vec<ExportPreset> {
+    std::fs::read_to_string(get_presets_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_export_presets(presets: &[ExportPreset]) -> Result<(), String> {
+    let path = get_presets_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建配置目录失败: {}", e))?;
+    }
+    let json =
+        serde_json::to_string_pretty(presets).map_err(|e| format!("序列化导出预设失败: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("写入导出预设失败: {}", e))
+}
+
+/// Save an export preset, replacing any existing preset with the same name
+pub fn save_export_preset(preset: ExportPreset) -> Result<(), String> {
+    if preset.name.trim().is_empty() {
+        return Err("预设名称不能为空".to_string());
+    }
+
+    let mut presets = load_export_presets();
+    presets.retain(|p| p.name != preset.name);
+    presets.push(preset);
+    write_export_presets(&presets)
+}
+
+/// Delete an export preset by name
+pub fn delete_export_preset(name: &str) -> Result<(), String> {
+    let mut presets = load_export_presets();
+    let before = presets.len();
+    presets.retain(|p| p.name != name);
+    if presets.len() == before {
+        return Err(tr_args(current_lang(), Msg::ExportPresetNotFound, &[&name]));
+    }
+    write_export_presets(&presets)
+}
+
+/// Export ports to the default export directory using a saved preset
+pub fn export_with_preset(ports: &[PortInfo], preset_name: &str) -> ExportResult {
+    let preset = match find_export_preset(preset_name) {
+        Ok(preset) => preset,
+        Err(message) => {
+            return ExportResult {
+                success: false,
+                path: None,
+                message,
+                record_count: 0,
+            }
+        }
+    };
+
+    let path = preset_export_path(&preset, ports.len());
+    export_with_columns(ports, &preset.columns, &preset.format, &path)
+}
+
+/// Look up a saved export preset by name
+pub fn find_export_preset(preset_name: &str) -> Result<ExportPreset, String> {
+    load_export_presets()
+        .into_iter()
+        .find(|p| p.name == preset_name)
+        .ok_or_else(|| tr_args(current_lang(), Msg::ExportPresetNotFound, &[&preset_name]))
+}
+
+/// Full path a preset export writes to in the default export directory
+pub fn preset_export_path(preset: &ExportPreset, record_count: usize) -> String {
+    let filename = render_filename_template(
+        &preset.filename_template,
+        &preset.format,
+        record_count,
+        Local::now(),
+    );
+    get_default_export_dir()
+        .join(filename)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Export a traceroute result; the target is recorded in the title
+pub fn export_traceroute(
+    result: &TracerouteResult,
+    format: &ExportFormat,
+    path: &str,
+) -> ExportResult {
+    export_traceroute_with_progress(result, format, path, &mut |_, _| {})
+}
+
+/// Traceroute export, reporting progress while hops are written
+pub fn export_traceroute_with_progress(
+    result: &TracerouteResult,
+    format: &ExportFormat,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    export_records_with_progress(
+        &result.hops,
+        format,
+        &format!("路由追踪报告 ({})", result.target),
+        path,
+        progress,
+    )
+}
+
+/// Export the findings of a security audit; the title carries the score
+pub fn export_audit(report: &AuditReport, format: &ExportFormat, path: &str) -> ExportResult {
+    export_audit_with_progress(report, format, path, &mut |_, _| {})
+}
+
+/// Audit export, reporting progress while findings are written
+pub fn export_audit_with_progress(
+    report: &AuditReport,
+    format: &ExportFormat,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    export_records_with_progress(
+        &report.findings,
+        format,
+        &format!("安全审计报告 (评分 {}/100)", report.score),
+        path,
+        progress,
+    )
+}
+
+fn format_label(format: &ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Csv => "CSV",
+        ExportFormat::Json => "JSON",
+        ExportFormat::Txt => "文本文件",
+        ExportFormat::Markdown => "Markdown",
+        ExportFormat::Xlsx => "Excel",
+        ExportFormat::Ndjson => "NDJSON",
+    }
+}
+
+/// Quote a CSV field when it contains separators, quotes or line breaks
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Export a scan to an Excel workbook with Ports, Apps and Docker sheets
+pub fn export_to_xlsx(
+    scan_result: &ScanResult,
+    groups: &[AppGroup],
+    containers: &[DockerContainer],
+    path: &str,
+) -> ExportResult {
+    export_to_xlsx_with_progress(scan_result, groups, containers, path, &mut |_, _| {})
+}
+
+/// Export a scan to Excel, reporting progress while port rows are written
+pub fn export_to_xlsx_with_progress(
+    scan_result: &ScanResult,
+    groups: &[AppGroup],
+    containers: &[DockerContainer],
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    match build_xlsx(scan_result, groups, containers, true, progress)
+        .and_then(|mut workbook| workbook.save(path))
+    {
+        Ok(_) => ExportResult {
+            success: true,
+            path: Some(path.to_string()),
+            message: tr_args(
+                current_lang(),
+                Msg::ExportDone,
+                &[&scan_result.ports.len(), &"Excel"],
+            ),
+            record_count: scan_result.ports.len(),
+        },
+        Err(e) => ExportResult {
+            success: false,
+            path: None,
+            message: tr_args(current_lang(), Msg::ExportFailed, &[&"Excel", &e]),
+            record_count: 0,
+        },
+    }
+}
+
+/// Build the scan workbook. With `constant_memory` the sheets are streamed through temp
+/// files and must be saved to a path; without it everything stays in memory
+fn build_xlsx(
+    scan_result: &ScanResult,
+    groups: &[AppGroup],
+    containers: &[DockerContainer],
+    constant_memory: bool,
+    progress: &mut ProgressFn,
+) -> Result<Workbook, XlsxError> {
+    let mut workbook = Workbook::new();
+
+    let port_rows = scan_result.ports.iter().map(|port| {
+        vec![
+            port.port.into(),
+            port.protocol.clone().into(),
+            port.address.clone().into(),
+            port.pid.clone().into(),
+            port.process.clone().into(),
+            port.user.clone().into(),
+            port.command.clone().unwrap_or_default().into(),
+        ]
+    });
+    write_xlsx_sheet(
+        &mut workbook,
+        constant_memory,
+        "Ports",
+        &[
+            "Port", "Protocol", "Address", "PID", "Process", "User", "Command",
+        ],
+        port_rows,
+        progress,
+    )?;
+
+    let group_rows = groups.iter().map(|group| {
+        let ports: Vec<String> = group.ports.iter().map(u16::to_string).collect();
+        vec![
+            group.process.clone().into(),
+            group.pid.clone().into(),
+            XlsxCell::Number(group.ports.len() as f64),
+            ports.join(", ").into(),
+            group.command.clone().unwrap_or_default().into(),
+        ]
+    });
+    write_xlsx_sheet(
+        &mut workbook,
+        constant_memory,
+        "Apps",
+        &["Process", "PID", "Port Count", "Ports", "Command"],
+        group_rows,
+        &mut |_, _| {},
+    )?;
+
+    let mut container_rows: Vec<Vec<XlsxCell>> = Vec::new();
+    for container in containers {
+        let base = |row: &mut Vec<XlsxCell>| {
+            row.push(container.name.clone().into());
+            row.push(container.image.clone().into());
+            row.push(container.status.clone().into());
+        };
+        if container.ports.is_empty() {
+            let mut row = Vec::new();
+            base(&mut row);
+            container_rows.push(row);
+        }
+        for port in &container.ports {
+            let mut row = Vec::new();
+            base(&mut row);
+            row.push(port.host_port.into());
+            row.push(port.container_port.into());
+            row.push(port.protocol.clone().into());
+            row.push(port.host_ip.clone().into());
+            container_rows.push(row);
+        }
+    }
+    write_xlsx_sheet(
+        &mut workbook,
+        constant_memory,
+        "Docker",
+        &[
+            "Container",
+            "Image",
+            "Status",
+            "Host Port",
+            "Container Port",
+            "Protocol",
+            "Host IP",
+        ],
+        container_rows.into_iter(),
+        &mut |_, _| {},
+    )?;
+
+    Ok(workbook)
+}
+
+/// Write one worksheet with a bold frozen header, autofilter and fitted column widths.
+/// Rows are written as they are produced so constant-memory sheets never hold the table
+fn write_xlsx_sheet(
+    workbook: &mut Workbook,
+    constant_memory: bool,
+    name: &str,
+    headers: &[&str],
+    rows: impl ExactSizeIterator<Item = Vec<XlsxCell>>,
+    progress: &mut ProgressFn,
+) -> Result<(), XlsxError> {
+    let header_format = Format::new()
+        .set_bold()
+        .set_background_color(Color::RGB(0xD9E1F2));
+
+    let sheet = if constant_memory {
+        workbook.add_worksheet_with_constant_memory()
+    } else {
+        workbook.add_worksheet()
+    };
+    sheet.set_name(name)?;
+    // 常量内存模式下 autofit 看不到已写出的行，列宽在写入时自行统计
+    let mut widths: Vec<usize> = headers
+        .iter()
+        .map(|header| xlsx_text_width(header))
+        .collect();
+    for (col, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *header, &header_format)?;
+    }
+    let total = rows.len();
+    for (index, row) in rows.enumerate() {
+        let row_num = index as u32 + 1;
+        for (col, cell) in row.iter().enumerate() {
+            let width = match cell {
+                XlsxCell::Text(text) => {
+                    sheet.write_string(row_num, col as u16, text)?;
+                    xlsx_text_width(text)
+                }
+                XlsxCell::Number(number) => {
+                    sheet.write_number(row_num, col as u16, *number)?;
+                    number.to_string().len()
+                }
+            };
+            if let Some(max) = widths.get_mut(col) {
+                *max = (*max).max(width);
+            }
+        }
+        report_progress(progress, index + 1, total);
+    }
+
+    for (col, width) in widths.into_iter().enumerate() {
+        sheet.set_column_width(col as u16, (width + 2).min(XLSX_MAX_COLUMN_WIDTH) as f64)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofilter(0, 0, total as u32, headers.len() as u16 - 1)?;
+    Ok(())
+}
+
+/// Excel's maximum column width in characters
+const XLSX_MAX_COLUMN_WIDTH: usize = 255;
+
+/// Approximate column width of a cell; CJK and other wide characters take two columns
+fn xlsx_text_width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+/// Group ports by process for export, without re-querying process command lines
+pub fn group_ports_for_export(ports: &[PortInfo]) -> Vec<AppGroup> {
+    let mut groups: HashMap<(String, String), AppGroup> = HashMap::new();
+    for port in ports {
+        let group = groups
+            .entry((port.process.clone(), port.pid.clone()))
+            .or_insert_with(|| AppGroup {
+                process: port.process.clone(),
+                pid: port.pid.clone(),
+                ports: Vec::new(),
+                command: port.command.clone(),
+            });
+        group.ports.push(port.port);
+    }
+
+    let mut groups: Vec<AppGroup> = groups
+        .into_values()
+        .map(|mut group| {
+            group.ports.sort_unstable();
+            group.ports.dedup();
+            group
+        })
+        .collect();
+    groups.sort_by_key(|g| g.ports.first().copied().unwrap_or(0));
+    groups
+}
+
+/// Write rendered content and build the matching ExportResult
+fn write_export_result(
+    path: &str,
+    content: &str,
+    record_count: usize,
+    label: &str,
+) -> ExportResult {
+    match write_file(path, content) {
+        Ok(_) => ExportResult {
+            success: true,
+            path: Some(path.to_string()),
+            message: tr_args(current_lang(), Msg::ExportDone, &[&record_count, &label]),
+            record_count,
+        },
+        Err(e) => ExportResult {
+            success: false,
+            path: None,
+            message: tr_args(current_lang(), Msg::ExportFailed, &[&label, &e]),
+            record_count: 0,
+        },
+    }
+}
+
+/// Get default export directory (user's Downloads folder)
+pub fn get_default_export_dir() -> PathBuf {
+    dirs::download_dir()
+        .or_else(dirs::document_dir)
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Generate default export filename with timestamp
+pub fn generate_export_filename(format: &ExportFormat) -> String {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    format!("portly_export_{}.{}", timestamp, export_extension(format))
+}
+
+/// File extension for an export format
+pub fn export_extension(format: &ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Json => "json",
+        ExportFormat::Txt => "txt",
+        ExportFormat::Markdown => "md",
+        ExportFormat::Xlsx => "xlsx",
+        ExportFormat::Ndjson => "ndjson",
+    }
+}
+
+/// Helper function to write content to file
+fn write_file(path: &str, content: &str) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+/// Export ports with auto-generated filename
+pub fn export_auto(
+    ports: &[PortInfo],
+    scan_result: &ScanResult,
+    format: ExportFormat,
+) -> ExportResult {
+    let path = auto_export_path(&format);
+    export_ports_with_progress(ports, scan_result, &format, &path, &mut |_, _| {})
+}
+
+/// Timestamped path in the default export directory, as used by `export_auto`
+pub fn auto_export_path(format: &ExportFormat) -> String {
+    get_default_export_dir()
+        .join(generate_export_filename(format))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Export a full scan result to the given path in any supported format
+pub fn export_scan(scan_result: &ScanResult, format: &ExportFormat, path: &str) -> ExportResult {
+    export_scan_with_progress(scan_result, format, path, &mut |_, _| {})
+}
+
+/// Export a full scan result, reporting progress for the streamed formats
+pub fn export_scan_with_progress(
+    scan_result: &ScanResult,
+    format: &ExportFormat,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    export_ports_with_progress(&scan_result.ports, scan_result, format, path, progress)
+}
+
+/// Export a subset of a scan's ports; the XLSX workbook still covers the whole scan
+pub fn export_ports_with_progress(
+    ports: &[PortInfo],
+    scan_result: &ScanResult,
+    format: &ExportFormat,
+    path: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let result = match format {
+        ExportFormat::Csv => return export_to_csv_with_progress(ports, path, progress),
+        ExportFormat::Txt => return export_to_txt_with_progress(ports, path, progress),
+        ExportFormat::Ndjson => return export_to_ndjson_with_progress(ports, path, progress),
+        ExportFormat::Json => export_to_json(scan_result, path),
+        ExportFormat::Markdown => export_to_markdown(ports, path),
+        ExportFormat::Xlsx => {
+            return export_to_xlsx_with_progress(
+                scan_result,
+                &group_ports_for_export(ports),
+                &crate::docker::get_docker_containers(),
+                path,
+                progress,
+            )
+        }
+    };
+    // 非流式格式只在完成时报告一次进度
+    progress(result.record_count, ports.len());
+    result
+}
+
+// ===== Encrypted Export =====
+
+/// Minimum passphrase length for encrypted exports
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Export a scan and encrypt it with a passphrase (age, scrypt-derived key).
+/// `.age` is appended to the path; the file can be opened with `age -d`.
+pub fn export_encrypted(
+    scan_result: &ScanResult,
+    format: &ExportFormat,
+    path: &str,
+    passphrase: &str,
+) -> ExportResult {
+    export_encrypted_with_progress(scan_result, format, path, passphrase, &mut |_, _| {})
+}
+
+/// Encrypted export, reporting progress while records are written
+pub fn export_encrypted_with_progress(
+    scan_result: &ScanResult,
+    format: &ExportFormat,
+    path: &str,
+    passphrase: &str,
+    progress: &mut ProgressFn,
+) -> ExportResult {
+    let failure = |message: String| ExportResult {
+        success: false,
+        path: None,
+        message,
+        record_count: 0,
+    };
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return failure(tr_args(
+            current_lang(),
+            Msg::ExportPassphraseTooShort,
+            &[&MIN_PASSPHRASE_LEN],
+        ));
+    }
+
+    let encrypted_path = if path.ends_with(".age") {
+        path.to_string()
+    } else {
+        format!("{}.age", path)
+    };
+    // 导出内容经 age 流式加密后直接写入 .age 文件，明文不落盘
+    let encryptor =
+        age::Encryptor::with_user_passphrase(SecretString::from(passphrase.to_string()));
+    let outcome = File::create(&encrypted_path)
+        .map_err(|e| e.to_string())
+        .and_then(|file| {
+            let mut writer = encryptor
+                .wrap_output(BufWriter::new(file))
+                .map_err(|e| format!("加密失败: {}", e))?;
+            let record_count = write_scan(&mut writer, scan_result, format, progress)?;
+            writer
+                .finish()
+                .and_then(|mut file| file.flush())
+                .map_err(|e| e.to_string())?;
+            Ok(record_count)
+        });
+
+    match outcome {
+        Ok(record_count) => ExportResult {
+            success: true,
+            path: Some(encrypted_path),
+            message: tr_args(current_lang(), Msg::ExportEncryptedDone, &[&record_count]),
+            record_count,
+        },
+        Err(e) => {
+            let _ = std::fs::remove_file(&encrypted_path);
+            failure(tr_args(
+                current_lang(),
+                Msg::ExportWriteEncryptedFailed,
+                &[&e],
+            ))
+        }
+    }
+}
+
+/// Render a full scan in the requested format into any writer; returns the record count
+fn write_scan(
+    out: &mut impl Write,
+    scan_result: &ScanResult,
+    format: &ExportFormat,
+    progress: &mut ProgressFn,
+) -> Result<usize, String> {
+    let ports = &scan_result.ports;
+    let written = match format {
+        ExportFormat::Csv => write_ports_csv(out, ports, progress),
+        ExportFormat::Txt => write_ports_txt(out, ports, progress),
+        ExportFormat::Ndjson => write_ndjson(out, ports, progress),
+        ExportFormat::Json => write_scan_json(out, scan_result),
+        ExportFormat::Markdown => out.write_all(ports_markdown(ports).as_bytes()),
+        ExportFormat::Xlsx => {
+            // 常量内存模式会把明文写入临时文件，加密导出只在内存中构建
+            let buffer = build_xlsx(
+                scan_result,
+                &group_ports_for_export(ports),
+                &crate::docker::get_docker_containers(),
+                false,
+                &mut |_, _| {},
+            )
+            .and_then(|mut workbook| workbook.save_to_buffer())
+            .map_err(|e| e.to_string())?;
+            out.write_all(&buffer)
+        }
+    };
+    written.map_err(|e| e.to_string())?;
+    progress(ports.len(), ports.len());
+    Ok(ports.len())
+}
+
+/// Decrypt an encrypted export back to its original bytes
+pub fn decrypt_export(path: &str, passphrase: &str) -> Result<Vec<u8>, String> {
+    let encrypted = std::fs::read(path).map_err(|e| format!("读取加密文件失败: {}", e))?;
+    let identity = age::scrypt::Identity::new(SecretString::from(passphrase.to_string()));
+    age::decrypt(&identity, &encrypted).map_err(|_| "解密失败：密码错误或文件已损坏".to_string())
+}
+
+// ===== Scan History Persistence =====
+//
+// History is stored as one gzip-compressed chunk per day under `history/`,
+// plus a small JSON index of summaries so listing scans never has to
+// decompress port data.
+
+/// Get Portly's local data directory
+pub(crate) fn get_data_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("portly")
+}
+
+/// Get the path to the legacy single-file history (migrated on first access)
+fn get_legacy_history_path() -> PathBuf {
+    get_data_dir().join("scan_history.json")
+}
+
+/// Maximum number of scans kept in history
+const MAX_HISTORY_ENTRIES: usize = 100;
+
+/// Chunked, compressed scan history store
+pub struct HistoryStore {
+    dir: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Open the default store, migrating the legacy history file if present
+    pub fn open() -> Self {
+        let store = Self::new(get_data_dir().join("history"));
+        store.migrate_legacy(&get_legacy_history_path());
+        store
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    fn chunk_path(&self, day: &str) -> PathBuf {
+        self.dir.join(format!("{}.json.gz", day))
+    }
+
+    /// Summaries of all stored scans, oldest first
+    pub fn index(&self) -> Vec<HistorySummary> {
+        std::fs::read_to_string(self.index_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load all scans stored for one day chunk
+    pub fn load_chunk(&self, day: &str) -> Vec<ScanHistoryEntry> {
+        File::open(self.chunk_path(day))
+            .ok()
+            .and_then(|file| serde_json::from_reader(GzDecoder::new(file)).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load every stored scan, oldest first
+    pub fn load_all(&self) -> Vec<ScanHistoryEntry> {
+        self.load_days(&self.index())
+    }
+
+    /// Load the chunks that contain the given summaries
+    fn load_days(&self, summaries: &[HistorySummary]) -> Vec<ScanHistoryEntry> {
+        let days: std::collections::BTreeSet<&str> =
+            summaries.iter().map(|s| chunk_day(&s.timestamp)).collect();
+        let mut entries: Vec<ScanHistoryEntry> = days
+            .into_iter()
+            .flat_map(|day| self.load_chunk(day))
+            .collect();
+        entries.sort_by_key(|entry| history_time(&entry.timestamp));
+        entries
+    }
+
+    /// Load a single scan by timestamp, decompressing only its day chunk
+    pub fn load_entry(&self, timestamp: &str) -> Result<ScanHistoryEntry, String> {
+        self.load_chunk(chunk_day(timestamp))
+            .into_iter()
+            .find(|entry| entry.timestamp == timestamp)
+            .ok_or_else(|| format!("未找到历史记录: {}", timestamp))
+    }
+
+    /// Add scans, skipping timestamps that are already stored.
+    /// Returns `(added, skipped)`.
+    pub fn insert(&self, entries: Vec<ScanHistoryEntry>) -> Result<(usize, usize), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("创建历史目录失败: {}", e))?;
+
+        let mut index = self.index();
+        let mut known: std::collections::HashSet<String> =
+            index.iter().map(|s| s.timestamp.clone()).collect();
+        let mut by_day: std::collections::BTreeMap<String, Vec<ScanHistoryEntry>> =
+            std::collections::BTreeMap::new();
+        let mut added = 0;
+        let mut skipped = 0;
+
+        for entry in entries {
+            if !known.insert(entry.timestamp.clone()) {
+                skipped += 1;
+                continue;
+            }
+            index.push(HistorySummary {
+                timestamp: entry.timestamp.clone(),
+                port_count: entry.port_count,
+                scan_duration_ms: entry.scan_duration_ms,
+            });
+            by_day
+                .entry(chunk_day(&entry.timestamp).to_string())
+                .or_default()
+                .push(entry);
+            added += 1;
+        }
+
+        for (day, new_entries) in by_day {
+            let mut chunk = self.load_chunk(&day);
+            chunk.extend(new_entries);
+            chunk.sort_by_key(|entry| history_time(&entry.timestamp));
+            self.write_chunk(&day, &chunk)?;
+        }
+
+        index.sort_by_key(|summary| history_time(&summary.timestamp));
+        if index.len() > MAX_HISTORY_ENTRIES {
+            let expired: Vec<HistorySummary> =
+                index.drain(..index.len() - MAX_HISTORY_ENTRIES).collect();
+            self.remove_entries(&expired)?;
+        }
+        self.write_index(&index)?;
+
+        Ok((added, skipped))
+    }
+
+    fn remove_entries(&self, expired: &[HistorySummary]) -> Result<(), String> {
+        let days: std::collections::BTreeSet<&str> =
+            expired.iter().map(|s| chunk_day(&s.timestamp)).collect();
+        for day in days {
+            let mut chunk = self.load_chunk(day);
+            chunk.retain(|entry| !expired.iter().any(|s| s.timestamp == entry.timestamp));
+            if chunk.is_empty() {
+                let _ = std::fs::remove_file(self.chunk_path(day));
+            } else {
+                self.write_chunk(day, &chunk)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_chunk(&self, day: &str, entries: &[ScanHistoryEntry]) -> Result<(), String> {
+        let file =
+            File::create(self.chunk_path(day)).map_err(|e| format!("写入历史文件失败: {}", e))?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        serde_json::to_writer(&mut encoder, entries)
+            .map_err(|e| format!("序列化历史数据失败: {}", e))?;
+        encoder
+            .finish()
+            .map_err(|e| format!("写入历史文件失败: {}", e))?;
+        Ok(())
+    }
+
+    fn write_index(&self, index: &[HistorySummary]) -> Result<(), String> {
+        let json =
+            serde_json::to_string(index).map_err(|e| format!("序列化历史索引失败: {}", e))?;
+        std::fs::write(self.index_path(), json).map_err(|e| format!("写入历史索引失败: {}", e))
+    }
+
+    /// Move scans from the old single JSON file into chunks, keeping a `.bak` copy
+    fn migrate_legacy(&self, legacy: &Path) {
+        if !legacy.exists() || self.index_path().exists() {
+            return;
+        }
+        let entries: Vec<ScanHistoryEntry> = std::fs::read_to_string(legacy)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        if self.insert(entries).is_ok() {
+            let _ = std::fs::rename(legacy, legacy.with_extension("json.bak"));
+        }
+    }
+}
+
+/// Day chunk a scan belongs to: the date part of its RFC 3339 timestamp
+fn chunk_day(timestamp: &str) -> &str {
+    timestamp
+        .get(..10)
+        .filter(|day| chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").is_ok())
+        .unwrap_or("undated")
+}
+
+/// Unix time of a history timestamp (unparseable timestamps sort first)
+fn history_time(timestamp: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|time| time.timestamp())
+        .unwrap_or_default()
+}
+
+/// Load the full scan history, oldest first
+pub fn load_scan_history() -> Vec<ScanHistoryEntry> {
+    HistoryStore::open().load_all()
+}
+
+/// Save a scan to history
+pub fn save_to_history(scan_result: &ScanResult) -> Result<(), String> {
+    let entry = ScanHistoryEntry {
+        timestamp: Local::now().to_rfc3339(),
+        port_count: scan_result.total_ports,
+        scan_duration_ms: 0, // Duration not tracked in current ScanResult
+        ports: scan_result.ports.clone(),
+    };
+
+    HistoryStore::open().insert(vec![entry]).map(|_| ())
+}
+
+// ===== History Search =====
+
+/// History search filters; all given filters must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistorySearchQuery {
+    /// Case-insensitive substring of the process name
+    pub process: Option<String>,
+    pub port: Option<u16>,
+    /// Case-insensitive substring of the full command line
+    pub command: Option<String>,
+    /// Inclusive lower bound (RFC 3339 or `YYYY-MM-DD`)
+    pub from: Option<String>,
+    /// Inclusive upper bound (RFC 3339 or `YYYY-MM-DD`, whole day)
+    pub to: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// A listener found in a saved scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMatch {
+    pub timestamp: String,
+    pub port: PortInfo,
+}
+
+/// Search stored scans, newest first; only day chunks inside the date range are loaded
+pub fn search_history(query: &HistorySearchQuery) -> Result<Vec<HistoryMatch>, String> {
+    let (from, to) = search_bounds(query)?;
+    let store = HistoryStore::open();
+    let summaries: Vec<HistorySummary> = store
+        .index()
+        .into_iter()
+        .filter(|summary| {
+            let time = history_time(&summary.timestamp);
+            from.is_none_or(|from| time >= from) && to.is_none_or(|to| time <= to)
+        })
+        .collect();
+    search_history_entries(&store.load_days(&summaries), query)
+}
+
+pub fn search_history_entries(
+    history: &[ScanHistoryEntry],
+    query: &HistorySearchQuery,
+) -> Result<Vec<HistoryMatch>, String> {
+    let (from, to) = search_bounds(query)?;
+    let process = query.process.as_ref().map(|p| p.to_lowercase());
+    let command = query.command.as_ref().map(|c| c.to_lowercase());
+
+    let mut entries: Vec<(i64, &ScanHistoryEntry)> = history
+        .iter()
+        .filter_map(|entry| {
+            let time = chrono::DateTime::parse_from_rfc3339(&entry.timestamp).ok()?;
+            Some((time.timestamp(), entry))
+        })
+        .filter(|(time, _)| from.is_none_or(|from| *time >= from))
+        .filter(|(time, _)| to.is_none_or(|to| *time <= to))
+        .collect();
+    entries.sort_by_key(|(time, _)| std::cmp::Reverse(*time));
+
+    let limit = query.limit.unwrap_or(usize::MAX);
+    let matches = entries
+        .into_iter()
+        .flat_map(|(_, entry)| {
+            entry.ports.iter().map(move |port| HistoryMatch {
+                timestamp: entry.timestamp.clone(),
+                port: port.clone(),
+            })
+        })
+        .filter(|m| query.port.is_none_or(|port| m.port.port == port))
+        .filter(|m| {
+            process
+                .as_deref()
+                .is_none_or(|p| m.port.process.to_lowercase().contains(p))
+        })
+        .filter(|m| {
+            command.as_deref().is_none_or(|c| {
+                m.port
+                    .command
+                    .as_deref()
+                    .is_some_and(|cmd| cmd.to_lowercase().contains(c))
+            })
+        })
+        .take(limit)
+        .collect();
+
+    Ok(matches)
+}
+
+fn search_bounds(query: &HistorySearchQuery) -> Result<(Option<i64>, Option<i64>), String> {
+    let from = query
+        .from
+        .as_deref()
+        .map(|value| parse_search_bound(value, false))
+        .transpose()?;
+    let to = query
+        .to
+        .as_deref()
+        .map(|value| parse_search_bound(value, true))
+        .transpose()?;
+    Ok((from, to))
+}
+
+/// Parse a search bound into a unix timestamp; bare dates cover the whole day
+fn parse_search_bound(value: &str, end_of_day: bool) -> Result<i64, String> {
+    let value = value.trim();
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(time.timestamp());
+    }
+
+    let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("无效的日期: {}", value))?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    time.and_then(|t| t.and_local_timezone(Local).earliest())
+        .map(|t| t.timestamp())
+        .ok_or_else(|| format!("无效的日期: {}", value))
+}
+
+// ===== Port Timeline =====
+
+/// A run of consecutive scans in which a port was held by the same processes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortTimelineSegment {
+    /// Timestamp of the first scan in the run
+    pub from: String,
+    /// Timestamp of the last scan in the run
+    pub to: String,
+    /// Sorted process names; empty while the port was free
+    pub processes: Vec<String>,
+    /// Every PID seen during the run, in order of appearance
+    pub pids: Vec<String>,
+    pub scans: usize,
+}
+
+/// Who held a port across the stored scans, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortTimeline {
+    pub port: u16,
+    pub segments: Vec<PortTimelineSegment>,
+}
+
+/// Build the timeline of one port from the stored history
+pub fn port_timeline(port: u16) -> PortTimeline {
+    build_port_timeline(&HistoryStore::open().load_all(), port)
+}
+
+/// Collapse scans (oldest first) into runs of the same owner; scans before the
+/// port was first seen are skipped so the timeline starts with its first owner
+pub fn build_port_timeline(history: &[ScanHistoryEntry], port: u16) -> PortTimeline {
+    let mut segments: Vec<PortTimelineSegment> = Vec::new();
+    for entry in history {
+        let mut processes: Vec<String> = Vec::new();
+        let mut pids: Vec<String> = Vec::new();
+        for listener in entry.ports.iter().filter(|p| p.port == port) {
+            processes.push(listener.process.clone());
+            if !pids.contains(&listener.pid) {
+                pids.push(listener.pid.clone());
+            }
+        }
+        processes.sort();
+        processes.dedup();
+        if segments.is_empty() && processes.is_empty() {
+            continue;
+        }
+
+        match segments.last_mut() {
+            Some(segment) if segment.processes == processes => {
+                segment.to = entry.timestamp.clone();
+                segment.scans += 1;
+                for pid in pids {
+                    if !segment.pids.contains(&pid) {
+                        segment.pids.push(pid);
+                    }
+                }
+            }
+            _ => segments.push(PortTimelineSegment {
+                from: entry.timestamp.clone(),
+                to: entry.timestamp.clone(),
+                processes,
+                pids,
+                scans: 1,
+            }),
+        }
+    }
+    PortTimeline { port, segments }
+}
+
+// ===== History Import =====
+
+/// Outcome of importing an exported JSON file into history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryImportResult {
+    pub imported: usize,
+    pub skipped: usize,
+    pub total_entries: usize,
+}
+
+/// Shape written by `export_to_json`
+#[derive(Deserialize)]
+struct JsonScanExport {
+    scan_time: String,
+    ports: Vec<PortInfo>,
+}
+
+/// Accepted import shapes: a JSON export, a single history entry or a history file
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HistoryImport {
+    Entries(Vec<ScanHistoryEntry>),
+    Entry(ScanHistoryEntry),
+    Export(JsonScanExport),
+}
+
+/// Parse and validate exported JSON into history entries
+pub fn parse_history_import(content: &str) -> Result<Vec<ScanHistoryEntry>, String> {
+    let parsed: HistoryImport =
+        serde_json::from_str(content).map_err(|_| "无法识别的导出文件格式".to_string())?;
+
+    let entries = match parsed {
+        HistoryImport::Entries(entries) => entries,
+        HistoryImport::Entry(entry) => vec![entry],
+        HistoryImport::Export(export) => vec![ScanHistoryEntry {
+            timestamp: export.scan_time,
+            port_count: export.ports.len(),
+            scan_duration_ms: 0,
+            ports: export.ports,
+        }],
+    };
+
+    entries
+        .into_iter()
+        .map(|mut entry| {
+            // 导出文件的扫描时间为 "%Y-%m-%d %H:%M:%S"，历史记录使用 RFC 3339
+            entry.timestamp = normalize_history_timestamp(&entry.timestamp)
+                .ok_or_else(|| format!("无效的扫描时间: {}", entry.timestamp))?;
+            if let Some(port) = entry.ports.iter().find(|p| p.port == 0) {
+                return Err(format!("无效的端口记录: {} {}", port.process, port.address));
+            }
+            entry.port_count = entry.ports.len();
+            Ok(entry)
+        })
+        .collect()
+}
+
+fn normalize_history_timestamp(timestamp: &str) -> Option<String> {
+    let timestamp = timestamp.trim();
+    if chrono::DateTime::parse_from_rfc3339(timestamp).is_ok() {
+        return Some(timestamp.to_string());
+    }
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .and_then(|naive| naive.and_local_timezone(Local).earliest())
+        .map(|time| time.to_rfc3339())
+}
+
+/// Import a previously exported JSON file into the history store
+pub fn import_history(path: &str) -> Result<HistoryImportResult, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取导入文件失败: {}", e))?;
+    let entries = parse_history_import(&content)?;
+
+    let store = HistoryStore::open();
+    let (imported, skipped) = store.insert(entries)?;
+    let total_entries = store.index().len();
+
+    Ok(HistoryImportResult {
+        imported,
+        skipped,
+        total_entries,
+    })
+}
+
+/// Get scan history summary (without full port data)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistorySummary {
+    pub timestamp: String,
+    pub port_count: usize,
+    pub scan_duration_ms: u64,
+}
+
+pub fn get_history_summary() -> Vec<HistorySummary> {
+    HistoryStore::open().index()
+}
+
+// ===== History Comparison =====
+
+/// A listener whose owning process changed between two scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortChange {
+    pub before: PortInfo,
+    pub after: PortInfo,
+}
+
+/// Differences between two saved scans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryDiff {
+    pub from: String,
+    pub to: String,
+    pub added_ports: Vec<PortInfo>,
+    pub removed_ports: Vec<PortInfo>,
+    pub changed_ports: Vec<PortChange>,
+    pub added_processes: Vec<String>,
+    pub removed_processes: Vec<String>,
+}
+
+/// Compare two history entries: ports are matched by protocol, address and port number
+pub fn diff_history_entries(a: &ScanHistoryEntry, b: &ScanHistoryEntry) -> HistoryDiff {
+    fn key(port: &PortInfo) -> (String, String, u16) {
+        (
+            port.protocol.to_lowercase(),
+            port.address.clone(),
+            port.port,
+        )
+    }
+
+    let before: HashMap<_, &PortInfo> = a.ports.iter().map(|p| (key(p), p)).collect();
+    let after: HashMap<_, &PortInfo> = b.ports.iter().map(|p| (key(p), p)).collect();
+
+    let mut added_ports: Vec<PortInfo> = after
+        .iter()
+        .filter(|(k, _)| !before.contains_key(*k))
+        .map(|(_, p)| (*p).clone())
+        .collect();
+    let mut removed_ports: Vec<PortInfo> = before
+        .iter()
+        .filter(|(k, _)| !after.contains_key(*k))
+        .map(|(_, p)| (*p).clone())
+        .collect();
+    let mut changed_ports: Vec<PortChange> = before
+        .iter()
+        .filter_map(|(k, old)| {
+            let new = after.get(k)?;
+            let changed = old.pid != new.pid
+                || old.process != new.process
+                || old.user != new.user
+                || old.command != new.command;
+            changed.then(|| PortChange {
+                before: (*old).clone(),
+                after: (*new).clone(),
+            })
+        })
+        .collect();
+    added_ports.sort_by_key(|p| p.port);
+    removed_ports.sort_by_key(|p| p.port);
+    changed_ports.sort_by_key(|c| c.after.port);
+
+    let processes = |entry: &ScanHistoryEntry| -> std::collections::BTreeSet<String> {
+        entry.ports.iter().map(|p| p.process.clone()).collect()
+    };
+    let (before_processes, after_processes) = (processes(a), processes(b));
+
+    HistoryDiff {
+        from: a.timestamp.clone(),
+        to: b.timestamp.clone(),
+        added_ports,
+        removed_ports,
+        changed_ports,
+        added_processes: after_processes
+            .difference(&before_processes)
+            .cloned()
+            .collect(),
+        removed_processes: before_processes
+            .difference(&after_processes)
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Compare two saved scans identified by their timestamps
+pub fn diff_history(from: &str, to: &str) -> Result<HistoryDiff, String> {
+    let store = HistoryStore::open();
+    Ok(diff_history_entries(
+        &store.load_entry(from)?,
+        &store.load_entry(to)?,
+    ))
+}
+
+/// Load a single saved scan (with full port data) by its timestamp
+pub fn load_history_entry(timestamp: &str) -> Result<ScanHistoryEntry, String> {
+    HistoryStore::open().load_entry(timestamp)
+}
+
+// ===== Unit Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::fs;
+
+    #[test]
+    fn test_export_format_enum() {
+        // 测试导出格式枚举
+        assert!(matches!(ExportFormat::Csv, ExportFormat::Csv));
+        assert!(matches!(ExportFormat::Json, ExportFormat::Json));
+        assert!(matches!(ExportFormat::Txt, ExportFormat::Txt));
+    }
+
+    #[test]
+    fn test_export_result_serialization() {
+        let result = ExportResult {
+            success: true,
+            path: Some("/test/path.csv".to_string()),
+            message: "Export successful".to_string(),
+            record_count: 10,
+        };
+
+        let serialized = serde_json::to_string(&result).unwrap();
+        let deserialized: ExportResult = serde_json::from_str(&serialized).unwrap();
+        assert!(deserialized.success);
+        assert_eq!(deserialized.record_count, 10);
+    }
+
+    #[test]
+    fn test_history_summary_serialization() {
+        let summary = HistorySummary {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            port_count: 100,
+            scan_duration_ms: 500,
+        };
+
+        let serialized = serde_json::to_string(&summary).unwrap();
+        let deserialized: HistorySummary = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.port_count, 100);
+    }
+
+    fn history_port(port: u16, pid: &str, process: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "tcp".to_string(),
+            address: "0.0.0.0".to_string(),
+            pid: pid.to_string(),
+            process: process.to_string(),
+            user: "user".to_string(),
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_history_entries() {
+        let a = ScanHistoryEntry {
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            port_count: 3,
+            scan_duration_ms: 0,
+            ports: vec![
+                history_port(22, "1", "sshd"),
+                history_port(80, "10", "nginx"),
+                history_port(5432, "20", "postgres"),
+            ],
+        };
+        let b = ScanHistoryEntry {
+            timestamp: "2024-01-02T00:00:00Z".to_string(),
+            port_count: 3,
+            scan_duration_ms: 0,
+            ports: vec![
+                history_port(22, "1", "sshd"),
+                history_port(80, "11", "caddy"),
+                history_port(3000, "30", "node"),
+            ],
+        };
+
+        let diff = diff_history_entries(&a, &b);
+        assert_eq!(diff.from, a.timestamp);
+        assert_eq!(diff.added_ports.len(), 1);
+        assert_eq!(diff.added_ports[0].port, 3000);
+        assert_eq!(diff.removed_ports.len(), 1);
+        assert_eq!(diff.removed_ports[0].port, 5432);
+        assert_eq!(diff.changed_ports.len(), 1);
+        assert_eq!(diff.changed_ports[0].before.process, "nginx");
+        assert_eq!(diff.changed_ports[0].after.process, "caddy");
+        assert_eq!(diff.added_processes, vec!["caddy", "node"]);
+        assert_eq!(diff.removed_processes, vec!["nginx", "postgres"]);
+    }
+
+    #[test]
+    fn test_diff_history_entries_identical() {
+        let entry = ScanHistoryEntry {
+            timestamp: "t".to_string(),
+            port_count: 1,
+            scan_duration_ms: 0,
+            ports: vec![history_port(22, "1", "sshd")],
+        };
+        let diff = diff_history_entries(&entry, &entry);
+        assert!(diff.added_ports.is_empty());
+        assert!(diff.removed_ports.is_empty());
+        assert!(diff.changed_ports.is_empty());
+        assert!(diff.added_processes.is_empty());
+    }
+
+    #[test]
+    fn test_export_to_csv_creates_valid_format() {
+        let ports = vec![PortInfo {
+            port: 8080,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "node".to_string(),
+            user: "user".to_string(),
+            command: Some("node app".to_string()),
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export.csv");
+
+        let result = export_to_csv(&ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        assert!(fs::metadata(&test_path).is_ok());
+
+        // 验证文件内容
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.contains("8080"));
+        assert!(content.contains("node"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_to_json_creates_valid_format() {
+        let ports = vec![];
+        let scan_result = ScanResult {
+            scan_time: "2024-01-01".to_string(),
+            total_ports: 0,
+            unique_apps: 0,
+            ports: ports.clone(),
+        };
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export.json");
+
+        let result = export_to_json(&scan_result, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        assert!(fs::metadata(&test_path).is_ok());
+
+        // 验证是有效 JSON
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(serde_json::from_str::<serde_json::Value>(&content).is_ok());
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_get_default_export_dir() {
+        let dir = get_default_export_dir();
+        assert!(!dir.as_os_str().is_empty());
+        // 验证路径存在或可以创建
+        assert!(dir.is_absolute());
+    }
+
+    #[test]
+    fn test_export_to_txt_creates_valid_format() {
+        let ports = vec![PortInfo {
+            port: 3000,
+            protocol: "tcp".to_string(),
+            address: "0.0.0.0".to_string(),
+            pid: "5678".to_string(),
+            process: "nginx".to_string(),
+            user: "root".to_string(),
+            command: Some("nginx: master process".to_string()),
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export.txt");
+
+        let result = export_to_txt(&ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        assert!(fs::metadata(&test_path).is_ok());
+
+        // 验证文件内容
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.contains("3000"));
+        assert!(content.contains("nginx"));
+        assert!(content.contains("Portly"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_generate_export_filename() {
+        let csv_filename = generate_export_filename(&ExportFormat::Csv);
+        assert!(csv_filename.ends_with(".csv"));
+        assert!(csv_filename.contains("portly_export_"));
+
+        let json_filename = generate_export_filename(&ExportFormat::Json);
+        assert!(json_filename.ends_with(".json"));
+
+        let txt_filename = generate_export_filename(&ExportFormat::Txt);
+        assert!(txt_filename.ends_with(".txt"));
+
+        let md_filename = generate_export_filename(&ExportFormat::Markdown);
+        assert!(md_filename.ends_with(".md"));
+
+        let xlsx_filename = generate_export_filename(&ExportFormat::Xlsx);
+        assert!(xlsx_filename.ends_with(".xlsx"));
+
+        let ndjson_filename = generate_export_filename(&ExportFormat::Ndjson);
+        assert!(ndjson_filename.ends_with(".ndjson"));
+    }
+
+    #[test]
+    fn test_export_to_ndjson_one_record_per_line() {
+        let ports = vec![
+            PortInfo {
+                port: 8080,
+                protocol: "tcp".to_string(),
+                address: "127.0.0.1".to_string(),
+                pid: "1234".to_string(),
+                process: "node".to_string(),
+                user: "user".to_string(),
+                command: Some("node\napp".to_string()),
+            },
+            PortInfo {
+                port: 53,
+                protocol: "udp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "1".to_string(),
+                process: "dnsmasq".to_string(),
+                user: "root".to_string(),
+                command: None,
+            },
+        ];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export.ndjson");
+
+        let result = export_to_ndjson(&ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        let content = fs::read_to_string(&test_path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: PortInfo = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first.port, 8080);
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_remote_to_ndjson_includes_host() {
+        let ports = vec![RemotePort {
+            port: 22,
+            is_open: true,
+            service: Some("SSH".to_string()),
+            policy: None,
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export_remote.ndjson");
+
+        let result = export_remote_to_ndjson("10.0.0.5", &ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        let content = fs::read_to_string(&test_path).unwrap();
+        let record: serde_json::Value = serde_json::from_str(content.trim()).unwrap();
+        assert_eq!(record["host"], "10.0.0.5");
+        assert_eq!(record["port"], 22);
+        assert_eq!(record["is_open"], true);
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_xlsx_cells_only_coerce_decimal_numbers() {
+        let is_number =
+            |cell: &str| matches!(XlsxCell::from_cell(cell.to_string()), XlsxCell::Number(_));
+        assert!(is_number("8080"));
+        assert!(is_number("12.50"));
+        assert!(is_number("-1"));
+        for text in [
+            "nan", "inf", "Infinity", "1e5", "10.0.0.1", "80 443", "", "-",
+        ] {
+            assert!(!is_number(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_export_remote_ports_tags_every_format_with_host() {
+        let ports = vec![RemotePort {
+            port: 22,
+            is_open: true,
+            service: Some("SSH".to_string()),
+            policy: None,
+        }];
+        let test_path = std::env::temp_dir().join("test_export_remote_host.csv");
+        let path = test_path.to_str().unwrap();
+
+        let result = export_remote_ports_with_progress(
+            "10.0.0.5",
+            &ports,
+            &ExportFormat::Csv,
+            path,
+            &mut |_, _| {},
+        );
+        assert!(result.success);
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.starts_with("Host,Port,Status,Service,Tags\n10.0.0.5,22,"));
+
+        export_remote_ports_with_progress(
+            "10.0.0.5",
+            &ports,
+            &ExportFormat::Json,
+            path,
+            &mut |_, _| {},
+        );
+        let document: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&test_path).unwrap()).unwrap();
+        assert_eq!(document["records"][0]["host"], "10.0.0.5");
+        assert_eq!(document["records"][0]["port"], 22);
+
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_to_xlsx_creates_workbook() {
+        let ports = vec![
+            PortInfo {
+                port: 8080,
+                protocol: "tcp".to_string(),
+                address: "127.0.0.1".to_string(),
+                pid: "1234".to_string(),
+                process: "node".to_string(),
+                user: "user".to_string(),
+                command: None,
+            },
+            PortInfo {
+                port: 3000,
+                protocol: "tcp".to_string(),
+                address: "127.0.0.1".to_string(),
+                pid: "1234".to_string(),
+                process: "node".to_string(),
+                user: "user".to_string(),
+                command: None,
+            },
+        ];
+        let scan_result = ScanResult {
+            scan_time: "2024-01-01".to_string(),
+            total_ports: ports.len(),
+            unique_apps: 1,
+            ports: ports.clone(),
+        };
+        let groups = group_ports_for_export(&ports);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].ports, vec![3000, 8080]);
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export.xlsx");
+
+        let result = export_to_xlsx(&scan_result, &groups, &[], test_path.to_str().unwrap());
+
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.record_count, 2);
+        // xlsx 是 zip 容器
+        let content = fs::read(&test_path).unwrap();
+        assert!(content.starts_with(b"PK"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_to_markdown_creates_table() {
+        let ports = vec![PortInfo {
+            port: 8080,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "node".to_string(),
+            user: "user".to_string(),
+            command: Some("grep a|b".to_string()),
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export.md");
+
+        let result = export_to_markdown(&ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.contains("| Port | Protocol | Address | PID | Process | User | Command |"));
+        assert!(content.contains("| --- | --- | --- | --- | --- | --- | --- |"));
+        // 单元格中的竖线需要转义
+        assert!(content.contains("| 8080 | tcp | 127.0.0.1 | 1234 | node | user | grep a\\|b |"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_format_from_name() {
+        assert!(matches!(
+            ExportFormat::from_name("MD"),
+            Some(ExportFormat::Markdown)
+        ));
+        assert!(matches!(
+            ExportFormat::from_name("jsonl"),
+            Some(ExportFormat::Ndjson)
+        ));
+        assert!(ExportFormat::from_name("pdf").is_none());
+    }
+
+    #[test]
+    fn test_export_records_remote_ports_csv() {
+        let ports = vec![
+            RemotePort {
+                port: 22,
+                is_open: true,
+                service: Some("SSH, OpenSSH".to_string()),
+                policy: None,
+            },
+            RemotePort {
+                port: 80,
+                is_open: false,
+                service: None,
+                policy: None,
+            },
+        ];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export_remote.csv");
+
+        let result = export_records(
+            &ports,
+            &ExportFormat::Csv,
+            "远程端口扫描",
+            test_path.to_str().unwrap(),
+        );
+
+        assert!(result.success);
+        assert_eq!(result.record_count, 2);
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.starts_with("Port,Status,Service\n"));
+        assert!(content.contains("22,开放,\"SSH, OpenSSH\""));
+        assert!(content.contains("80,关闭,"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_traceroute_json() {
+        let trace = TracerouteResult {
+            target: "example.com".to_string(),
+            hops: vec![
+                TraceHop {
+                    hop: 1,
+                    ip: Some("192.168.1.1".to_string()),
+                    hostname: None,
+                    time_ms: Some(1.5),
+                },
+                TraceHop {
+                    hop: 2,
+                    ip: None,
+                    hostname: None,
+                    time_ms: None,
+                },
+            ],
+            raw_output: String::new(),
+        };
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export_trace.json");
+
+        let result = export_traceroute(&trace, &ExportFormat::Json, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        let content = fs::read_to_string(&test_path).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(value["total"], 2);
+        assert!(value["title"].as_str().unwrap().contains("example.com"));
+        assert_eq!(value["records"][0]["ip"], "192.168.1.1");
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_ping_result_row() {
+        let ping = PingResult {
+            ip: "8.8.8.8".to_string(),
+            is_reachable: true,
+            packets_sent: 4,
+            packets_received: 3,
+            packet_loss: 25.0,
+            min_ms: Some(10.0),
+            avg_ms: Some(12.345),
+            max_ms: None,
+            raw_output: String::new(),
+        };
+        let row = ping.row();
+        assert_eq!(row.len(), PingResult::HEADERS.len());
+        assert_eq!(row[4], "25.0");
+        assert_eq!(row[6], "12.35");
+        assert_eq!(row[7], "");
+    }
+
+    #[test]
+    fn test_port_column_from_name() {
+        assert_eq!(PortColumn::from_name("port"), Some(PortColumn::Port));
+        assert_eq!(PortColumn::from_name(" Proc "), Some(PortColumn::Process));
+        assert_eq!(PortColumn::from_name("cmd"), Some(PortColumn::Command));
+        assert_eq!(PortColumn::from_name("size"), None);
+    }
+
+    #[test]
+    fn test_export_with_columns_selects_and_orders() {
+        let ports = vec![PortInfo {
+            port: 8080,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "node".to_string(),
+            user: "user".to_string(),
+            command: None,
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let csv_path = temp_dir.join("test_export_columns.csv");
+        let columns = [PortColumn::Process, PortColumn::Port];
+
+        let result = export_with_columns(
+            &ports,
+            &columns,
+            &ExportFormat::Csv,
+            csv_path.to_str().unwrap(),
+        );
+        assert!(result.success);
+        let content = fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(content, "Process,Port\nnode,8080\n");
+
+        let json_path = temp_dir.join("test_export_columns.json");
+        let result = export_with_columns(
+            &ports,
+            &columns,
+            &ExportFormat::Json,
+            json_path.to_str().unwrap(),
+        );
+        assert!(result.success);
+        let value: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(value["records"][0]["port"], 8080);
+        assert_eq!(value["records"][0]["process"], "node");
+        assert!(value["records"][0].get("pid").is_none());
+
+        // 清理
+        let _ = fs::remove_file(&csv_path);
+        let _ = fs::remove_file(&json_path);
+    }
+
+    #[test]
+    fn test_render_filename_template() {
+        let now = Local.with_ymd_and_hms(2024, 3, 5, 14, 7, 9).unwrap();
+        assert_eq!(
+            render_filename_template("{date}-ports-{count}", &ExportFormat::Csv, 12, now),
+            "20240305-ports-12.csv"
+        );
+        assert_eq!(
+            render_filename_template("", &ExportFormat::Json, 0, now),
+            "portly_export_20240305_140709.json"
+        );
+        assert_eq!(
+            render_filename_template("../etc/passwd.txt", &ExportFormat::Txt, 0, now),
+            ".._etc_passwd.txt"
+        );
+    }
+
+    #[test]
+    fn test_export_preset_serialization() {
+        let preset = ExportPreset {
+            name: "ops".to_string(),
+            format: ExportFormat::Csv,
+            columns: vec![PortColumn::Port, PortColumn::Command],
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+        };
+        let json = serde_json::to_string(&preset).unwrap();
+        assert!(json.contains("\"columns\":[\"port\",\"command\"]"));
+        let parsed: ExportPreset = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.format, ExportFormat::Csv);
+    }
+
+    #[test]
+    fn test_parse_history_import_from_json_export() {
+        let scan_result = ScanResult {
+            scan_time: "2024-03-05 14:07:09".to_string(),
+            total_ports: 1,
+            unique_apps: 1,
+            ports: vec![PortInfo {
+                port: 5432,
+                protocol: "tcp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "77".to_string(),
+                process: "postgres".to_string(),
+                user: "postgres".to_string(),
+                command: None,
+            }],
+        };
+
+        let path = std::env::temp_dir().join("test_history_import.json");
+        assert!(export_to_json(&scan_result, path.to_str().unwrap()).success);
+        let content = fs::read_to_string(&path).unwrap();
+        let entries = parse_history_import(&content).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].port_count, 1);
+        assert!(chrono::DateTime::parse_from_rfc3339(&entries[0].timestamp).is_ok());
+
+        // 已存在的时间戳会被跳过
+        let store = HistoryStore::new(std::env::temp_dir().join("test_history_import_store"));
+        let _ = fs::remove_dir_all(&store.dir);
+        assert_eq!(store.insert(entries.clone()).unwrap(), (1, 0));
+        assert_eq!(store.insert(entries).unwrap(), (0, 1));
+        let _ = fs::remove_dir_all(&store.dir);
+
+        assert!(parse_history_import("{\"foo\": 1}").is_err());
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_search_history_entries() {
+        let port = |port: u16, process: &str, command: Option<&str>| PortInfo {
+            port,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1".to_string(),
+            process: process.to_string(),
+            user: "user".to_string(),
+            command: command.map(str::to_string),
+        };
+        let entry = |timestamp: &str, ports: Vec<PortInfo>| ScanHistoryEntry {
+            timestamp: timestamp.to_string(),
+            port_count: ports.len(),
+            scan_duration_ms: 0,
+            ports,
+        };
+        let history = vec![
+            entry(
+                "2024-01-10T09:00:00+00:00",
+                vec![port(5432, "postgres", Some("/usr/bin/postgres -D /data"))],
+            ),
+            entry(
+                "2024-02-10T09:00:00+00:00",
+                vec![
+                    port(5432, "postgres", None),
+                    port(3000, "node", Some("node server.js")),
+                ],
+            ),
+        ];
+
+        let query = HistorySearchQuery {
+            port: Some(5432),
+            ..Default::default()
+        };
+        let matches = search_history_entries(&history, &query).unwrap();
+        assert_eq!(matches.len(), 2);
+        // 最新的记录排在最前
+        assert_eq!(matches[0].timestamp, "2024-02-10T09:00:00+00:00");
+
+        let query = HistorySearchQuery {
+            command: Some("POSTGRES -d".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search_history_entries(&history, &query).unwrap().len(), 1);
+
+        let query = HistorySearchQuery {
+            process: Some("node".to_string()),
+            from: Some("2024-02-01T00:00:00+00:00".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(search_history_entries(&history, &query).unwrap().len(), 1);
+
+        let query = HistorySearchQuery {
+            to: Some("2024-01-31T00:00:00+00:00".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let matches = search_history_entries(&history, &query).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].port.port, 5432);
+
+        let query = HistorySearchQuery {
+            from: Some("yesterday".to_string()),
+            ..Default::default()
+        };
+        assert!(search_history_entries(&history, &query).is_err());
+    }
+
+    #[test]
+    fn test_build_port_timeline() {
+        let port = |port: u16, process: &str, pid: &str| PortInfo {
+            port,
+            protocol: "tcp".to_string(),
+            address: "0.0.0.0".to_string(),
+            pid: pid.to_string(),
+            process: process.to_string(),
+            user: "user".to_string(),
+            command: None,
+        };
+        let entry = |hour: u32, ports: Vec<PortInfo>| ScanHistoryEntry {
+            timestamp: format!("2024-05-01T{hour:02}:00:00+00:00"),
+            port_count: ports.len(),
+            scan_duration_ms: 0,
+            ports,
+        };
+        let history = vec![
+            entry(8, vec![port(3000, "node", "9")]),
+            entry(9, vec![port(5432, "postgres", "70")]),
+            entry(13, vec![port(5432, "postgres", "71")]),
+            entry(17, vec![port(5432, "postgres", "71")]),
+            entry(18, Vec::new()),
+            entry(19, vec![port(5432, "docker-proxy", "300")]),
+            entry(20, vec![port(5432, "docker-proxy", "300")]),
+        ];
+
+        let timeline = build_port_timeline(&history, 5432);
+        assert_eq!(timeline.segments.len(), 3);
+        let held = &timeline.segments[0];
+        assert_eq!(held.processes, vec!["postgres".to_string()]);
+        assert_eq!(held.from, "2024-05-01T09:00:00+00:00");
+        assert_eq!(held.to, "2024-05-01T17:00:00+00:00");
+        assert_eq!(held.pids, vec!["70".to_string(), "71".to_string()]);
+        assert_eq!(held.scans, 3);
+        assert!(timeline.segments[1].processes.is_empty());
+        let reused = &timeline.segments[2];
+        assert_eq!(reused.processes, vec!["docker-proxy".to_string()]);
+        assert_eq!(reused.scans, 2);
+
+        assert!(build_port_timeline(&history, 22).segments.is_empty());
+    }
+
+    #[test]
+    fn test_history_store_chunks_and_trims() {
+        let store = HistoryStore::new(std::env::temp_dir().join("test_history_store_chunks"));
+        let _ = fs::remove_dir_all(&store.dir);
+
+        let entry = |timestamp: String| ScanHistoryEntry {
+            timestamp,
+            port_count: 0,
+            scan_duration_ms: 0,
+            ports: Vec::new(),
+        };
+        let entries: Vec<ScanHistoryEntry> = (0..MAX_HISTORY_ENTRIES + 2)
+            .map(|i| {
+                let day = 1 + i / 100;
+                entry(format!(
+                    "2024-01-{:02}T{:02}:{:02}:00+00:00",
+                    day,
+                    (i % 100) / 60,
+                    i % 60
+                ))
+            })
+            .collect();
+        let first = entries[0].timestamp.clone();
+        let newest = entries.last().unwrap().timestamp.clone();
+        assert_eq!(store.insert(entries).unwrap(), (MAX_HISTORY_ENTRIES + 2, 0));
+
+        // 超出上限时删除最旧的记录
+        let index = store.index();
+        assert_eq!(index.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(index.last().unwrap().timestamp, newest);
+        assert!(store.load_entry(&first).is_err());
+        assert!(store.load_entry(&newest).is_ok());
+
+        // 每天一个压缩分块，删除最旧的记录后只改写所在的分块
+        assert_eq!(store.load_chunk("2024-01-01").len(), 98);
+        assert_eq!(store.load_chunk("2024-01-02").len(), 2);
+
+        let _ = fs::remove_dir_all(&store.dir);
+    }
+
+    #[test]
+    fn test_history_store_migrates_legacy_file() {
+        let dir = std::env::temp_dir().join("test_history_store_legacy");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let legacy = dir.join("scan_history.json");
+        fs::write(
+            &legacy,
+            r#"[{"timestamp":"2024-01-01T00:00:00+00:00","port_count":0,"scan_duration_ms":0,"ports":[]}]"#,
+        )
+        .unwrap();
+
+        let store = HistoryStore::new(dir.join("history"));
+        store.migrate_legacy(&legacy);
+        assert_eq!(store.index().len(), 1);
+        assert!(!legacy.exists());
+        assert!(dir.join("scan_history.json.bak").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_export_encrypted_roundtrip() {
+        let scan_result = ScanResult {
+            scan_time: "2024-03-05 14:07:09".to_string(),
+            total_ports: 1,
+            unique_apps: 1,
+            ports: vec![PortInfo {
+                port: 22,
+                protocol: "tcp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "1".to_string(),
+                process: "sshd".to_string(),
+                user: "root".to_string(),
+                command: None,
+            }],
+        };
+        let path = std::env::temp_dir().join("test_export_encrypted.csv");
+        let path = path.to_str().unwrap();
+
+        let result = export_encrypted(&scan_result, &ExportFormat::Csv, path, "short");
+        assert!(!result.success);
+
+        let result = export_encrypted(
+            &scan_result,
+            &ExportFormat::Csv,
+            path,
+            "correct horse battery",
+        );
+        assert!(result.success);
+        let encrypted_path = result.path.unwrap();
+        assert!(encrypted_path.ends_with(".csv.age"));
+        assert!(!fs::read(&encrypted_path)
+            .unwrap()
+            .windows(4)
+            .any(|w| w == b"sshd"));
+
+        let plain = decrypt_export(&encrypted_path, "correct horse battery").unwrap();
+        assert!(String::from_utf8(plain)
+            .unwrap()
+            .contains("22,tcp,0.0.0.0,1,sshd"));
+        assert!(decrypt_export(&encrypted_path, "wrong passphrase").is_err());
+
+        let _ = fs::remove_file(&encrypted_path);
+    }
+
+    #[test]
+    fn test_export_encrypted_never_writes_plaintext() {
+        let scan_result = ScanResult {
+            scan_time: "2024-03-05 14:07:09".to_string(),
+            total_ports: 1,
+            unique_apps: 1,
+            ports: vec![PortInfo {
+                port: 22,
+                protocol: "tcp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "1".to_string(),
+                process: "sshd".to_string(),
+                user: "root".to_string(),
+                command: None,
+            }],
+        };
+        let dir = std::env::temp_dir().join("test_export_encrypted_no_plaintext");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ports.txt");
+
+        // 导出过程中和结束后，目录里只有 .age 文件，且任何文件都不含明文
+        let assert_no_plaintext = || {
+            for entry in fs::read_dir(&dir).unwrap() {
+                let entry = entry.unwrap().path();
+                assert_eq!(entry.extension().unwrap(), "age", "{}", entry.display());
+                let content = fs::read(&entry).unwrap();
+                assert!(!content.windows(4).any(|w| w == b"sshd"));
+            }
+        };
+        let mut checks = 0;
+        let result = export_encrypted_with_progress(
+            &scan_result,
+            &ExportFormat::Txt,
+            path.to_str().unwrap(),
+            "correct horse battery",
+            &mut |_, _| {
+                assert_no_plaintext();
+                checks += 1;
+            },
+        );
+        assert!(result.success);
+        assert!(checks > 0);
+        assert_no_plaintext();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_export_reports_progress() {
+        let ports: Vec<PortInfo> = (1..=2500u16)
+            .map(|port| PortInfo {
+                port,
+                protocol: "tcp".to_string(),
+                address: "0.0.0.0".to_string(),
+                pid: "1".to_string(),
+                process: "test".to_string(),
+                user: "user".to_string(),
+                command: None,
+            })
+            .collect();
+        let path = std::env::temp_dir().join("test_export_progress.csv");
+
+        let mut calls = Vec::new();
+        let result =
+            export_to_csv_with_progress(&ports, path.to_str().unwrap(), &mut |written, total| {
+                calls.push((written, total))
+            });
+        assert!(result.success);
+        assert_eq!(calls, vec![(1000, 2500), (2000, 2500), (2500, 2500)]);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2501);
+        let _ = fs::remove_file(&path);
+
+        // XLSX 逐行写入工作表，同样按批次报告
+        let scan_result = ScanResult {
+            scan_time: "2024-01-01".to_string(),
+            total_ports: ports.len(),
+            unique_apps: 1,
+            ports: ports.clone(),
+        };
+        let path = std::env::temp_dir().join("test_export_progress.xlsx");
+        let mut calls = Vec::new();
+        let result = export_to_xlsx_with_progress(
+            &scan_result,
+            &group_ports_for_export(&ports),
+            &[],
+            path.to_str().unwrap(),
+            &mut |written, total| calls.push((written, total)),
+        );
+        assert!(result.success, "{}", result.message);
+        assert_eq!(calls, vec![(1000, 2500), (2000, 2500), (2500, 2500)]);
+        assert!(fs::read(&path).unwrap().starts_with(b"PK"));
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_export_devices_to_markdown() {
+        let devices = vec![NetworkDevice {
+            ip: "192.168.1.10".to_string(),
+            mac: Some("aa:bb:cc:dd:ee:ff".to_string()),
+            hostname: None,
+            is_online: true,
+            interface_index: None,
+            rtt_ms: None,
+            tags: vec!["nas".to_string(), "backup".to_string()],
+            note: None,
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export_devices.md");
+
+        let result = export_devices_to_markdown(&devices, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        assert_eq!(result.record_count, 1);
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.contains("| 192.168.1.10 | aa:bb:cc:dd:ee:ff |  | 在线 | nas backup |"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_csv_with_special_characters() {
+        let ports = vec![PortInfo {
+            port: 8080,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "test".to_string(),
+            user: "user".to_string(),
+            command: Some("node app,with,commas".to_string()),
+        }];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export_special.csv");
+
+        let result = export_to_csv(&ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        let content = fs::read_to_string(&test_path).unwrap();
+        // 验证命令中的逗号被正确转义
+        assert!(content.contains("\"node app,with,commas\""));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+
+    #[test]
+    fn test_export_empty_ports() {
+        let ports = vec![];
+
+        let temp_dir = std::env::temp_dir();
+        let test_path = temp_dir.join("test_export_empty.csv");
+
+        let result = export_to_csv(&ports, test_path.to_str().unwrap());
+
+        assert!(result.success);
+        assert_eq!(result.record_count, 0);
+
+        // 验证文件仍包含表头
+        let content = fs::read_to_string(&test_path).unwrap();
+        assert!(content.contains("Port,Protocol"));
+
+        // 清理
+        let _ = fs::remove_file(&test_path);
+    }
+}
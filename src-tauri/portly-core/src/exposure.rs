@@ -0,0 +1,210 @@
+//! 局域网可达性
+//!
+//! 回答“局域网里的其他主机实际能连上本机哪些服务”：取监听在回环以外地址的端口，与
+//! [`crate::firewall::inbound_rules`] 读到的入站放行规则比对。可选的自扫描从本机的局域网地址
+//! 实际连接每个端口，确认服务在该地址上应答；本机发往自身地址的连接经回环接口，不经过防火墙的
+//! 入站过滤，所以自扫描只能排除“没有在该地址上应答”的服务，是否放行仍以防火墙规则为准
+
+use crate::app_error::AppResult;
+use crate::audit::listeners;
+use crate::core::PortInfo;
+use crate::firewall::{FirewallStatus, InboundRules};
+use crate::rules::{is_wildcard, parse_ip};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+/// 结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    /// 防火墙放行（或未开启），其他主机可以连接
+    Reachable,
+    /// 防火墙没有放行该端口
+    Blocked,
+    /// 自扫描时服务没有在局域网地址上应答
+    NotListening,
+    /// 无法读取防火墙规则
+    Unknown,
+}
+
+impl Reachability {
+    pub fn name(self) -> &'static str {
+        match self {
+            Reachability::Reachable => "reachable",
+            Reachability::Blocked => "blocked",
+            Reachability::NotListening => "not_listening",
+            Reachability::Unknown => "unknown",
+        }
+    }
+}
+
+/// 一个对外监听的服务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposedService {
+    pub port: u16,
+    pub process: String,
+    pub pid: String,
+    pub addresses: Vec<String>,
+    /// 防火墙是否放行；规则未知时为空
+    pub firewall_allows: Option<bool>,
+    /// 自扫描是否连上；未自扫描时为空
+    pub self_scan_open: Option<bool>,
+    pub reachability: Reachability,
+}
+
+/// 可达性报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureReport {
+    pub generated_at: String,
+    pub firewall: FirewallStatus,
+    pub rules: InboundRules,
+    /// 自扫描使用的本机局域网地址
+    pub lan_address: Option<String>,
+    /// 按端口排列
+    pub services: Vec<ExposedService>,
+    pub reachable: usize,
+}
+
+/// 对本机生成可达性报告；`self_scan` 为真时从本机局域网地址连接每个对外端口
+#[tracing::instrument(level = "info")]
+pub fn run_exposure_report(self_scan: bool) -> AppResult<ExposureReport> {
+    let ports = crate::core::try_get_listening_ports()?;
+    let firewall = crate::firewall::firewall_status();
+    let rules = crate::firewall::inbound_rules(&firewall);
+    let lan_address = if self_scan && !crate::demo::is_enabled() {
+        local_ip_address::local_ip().ok()
+    } else {
+        None
+    };
+    let timeout = Duration::from_millis(crate::settings::current().scan.connect_timeout_ms);
+    let report = exposure_report(&ports, firewall, rules, lan_address, &|addr| {
+        TcpStream::connect_timeout(&addr, timeout).is_ok()
+    });
+    tracing::info!(
+        services = report.services.len(),
+        reachable = report.reachable,
+        "局域网可达性检查完成"
+    );
+    Ok(report)
+}
+
+/// 根据输入生成报告；`lan_address` 为空时不自扫描，`probe` 判断能否连上某个地址
+pub fn exposure_report(
+    ports: &[PortInfo],
+    firewall: FirewallStatus,
+    rules: InboundRules,
+    lan_address: Option<IpAddr>,
+    probe: &dyn Fn(SocketAddr) -> bool,
+) -> ExposureReport {
+    let services: Vec<ExposedService> = listeners(ports)
+        .into_iter()
+        .filter(|listener| listener.exposed())
+        .map(|listener| {
+            let firewall_allows = rules.allows_tcp(listener.port);
+            // 监听所有网卡时连局域网地址，否则连第一个具体地址
+            let self_scan_open = lan_address.map(|lan| {
+                let target = if listener.addresses.iter().any(|a| is_wildcard(a)) {
+                    Some(lan)
+                } else {
+                    listener.addresses.iter().find_map(|a| parse_ip(a))
+                };
+                target.is_some_and(|ip| probe(SocketAddr::new(ip, listener.port)))
+            });
+            let reachability = match (self_scan_open, firewall_allows) {
+                (Some(false), _) => Reachability::NotListening,
+                (_, Some(true)) => Reachability::Reachable,
+                (_, Some(false)) => Reachability::Blocked,
+                (_, None) => Reachability::Unknown,
+            };
+            ExposedService {
+                port: listener.port,
+                process: listener.process.to_string(),
+                pid: listener.pid.to_string(),
+                addresses: listener.addresses.iter().map(|a| a.to_string()).collect(),
+                firewall_allows,
+                self_scan_open,
+                reachability,
+            }
+        })
+        .collect();
+    ExposureReport {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        firewall,
+        rules,
+        lan_address: lan_address.map(|ip| ip.to_string()),
+        reachable: services
+            .iter()
+            .filter(|s| s.reachability == Reachability::Reachable)
+            .count(),
+        services,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+    use crate::firewall::{inbound_rules_with, FirewallState};
+
+    fn port(port: u16, address: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "IPv4".to_string(),
+            address: address.to_string(),
+            pid: "7".to_string(),
+            process: "svc".to_string(),
+            user: "me".to_string(),
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_exposure_combines_firewall_rules_and_self_scan() {
+        let firewall = FirewallStatus {
+            backend: "ufw".to_string(),
+            state: FirewallState::Enabled,
+            detail: String::new(),
+            needs_elevation: false,
+        };
+        let runner = FixtureRunner::new().with_stdout(
+            "ufw status verbose",
+            "Status: active\nDefault: deny (incoming), allow (outgoing), disabled (routed)\n\n\
+             To                         Action      From\n\
+             --                         ------      ----\n\
+             22/tcp                     ALLOW IN    Anywhere\n\
+             8000:8100/tcp              ALLOW IN    192.168.1.0/24\n",
+        );
+        let rules = inbound_rules_with(&runner, &firewall);
+        let ports = vec![
+            port(22, "*"),
+            port(22, "[::]"),
+            port(5432, "0.0.0.0"),
+            port(8080, "192.168.1.20"),
+            port(8090, "0.0.0.0"),
+            port(631, "127.0.0.1"),
+        ];
+        let lan: IpAddr = "192.168.1.20".parse().unwrap();
+        let report = exposure_report(&ports, firewall, rules, Some(lan), &|addr| {
+            assert_eq!(addr.ip(), lan);
+            addr.port() != 8090
+        });
+
+        let verdicts: Vec<(u16, Reachability)> = report
+            .services
+            .iter()
+            .map(|s| (s.port, s.reachability))
+            .collect();
+        assert_eq!(
+            verdicts,
+            vec![
+                (22, Reachability::Reachable),
+                (5432, Reachability::Blocked),
+                (8080, Reachability::Reachable),
+                (8090, Reachability::NotListening),
+            ]
+        );
+        assert_eq!(report.reachable, 2);
+        assert_eq!(report.services[0].addresses, vec!["*", "[::]"]);
+    }
+}
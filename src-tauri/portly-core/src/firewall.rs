@@ -0,0 +1,724 @@
+//! 本机防火墙状态
+//!
+//! 通过系统自带的工具查询主机防火墙是否开启：macOS 使用 `socketfilterfw`，Linux 依次尝试
+//! `ufw`、`firewall-cmd` 和 `nft`，Windows 使用 `netsh advfirewall`。查询需要管理员权限或工具
+//! 未安装时返回 [`FirewallState::Unknown`]，是否缺少权限由 [`crate::capabilities`] 判断。
+//!
+//! [`inbound_rules`] 进一步读取放行入站连接的端口（ufw / firewalld / nftables / netsh），
+//! 供 [`crate::exposure`] 判断局域网主机能否连上本机服务。只解析常见的按端口放行写法，
+//! macOS 应用防火墙按程序放行，无法按端口判断
+
+use crate::command_exec::{hide_console_window, CommandRunner, Platform, SystemRunner};
+use serde::{Deserialize, Serialize};
+
+const SOCKETFILTERFW: &str = "/usr/libexec/ApplicationFirewall/socketfilterfw";
+
+/// 防火墙开关状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FirewallState {
+    Enabled,
+    Disabled,
+    Unknown,
+}
+
+impl FirewallState {
+    pub fn name(self) -> &'static str {
+        match self {
+            FirewallState::Enabled => "enabled",
+            FirewallState::Disabled => "disabled",
+            FirewallState::Unknown => "unknown",
+        }
+    }
+}
+
+/// 防火墙查询结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirewallStatus {
+    /// 查询使用的工具（socketfilterfw / ufw / firewalld / nftables / netsh），未查到时为空
+    pub backend: String,
+    pub state: FirewallState,
+    /// 工具输出的关键行或失败原因
+    pub detail: String,
+    /// 状态未知且当前不是管理员，提升权限后可能查到
+    #[serde(default)]
+    pub needs_elevation: bool,
+}
+
+impl FirewallStatus {
+    fn new(backend: &str, state: FirewallState, detail: impl Into<String>) -> Self {
+        Self {
+            backend: backend.to_string(),
+            state,
+            detail: detail.into(),
+            needs_elevation: false,
+        }
+    }
+
+    fn unknown(detail: impl Into<String>) -> Self {
+        Self::new("", FirewallState::Unknown, detail)
+    }
+}
+
+/// 防火墙放行的入站端口范围（含两端）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowRule {
+    pub start: u16,
+    pub end: u16,
+    /// tcp / udp / any
+    pub protocol: String,
+    /// 允许的来源，如 `Anywhere`、`192.168.1.0/24`
+    pub source: String,
+}
+
+/// 防火墙入站规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InboundRules {
+    pub backend: String,
+    /// 是否读取到了规则；为假时无法据此判断端口是否放行
+    pub known: bool,
+    /// 没有规则匹配时是否放行（防火墙关闭，或默认策略 / 链策略为 accept）
+    pub default_allow: bool,
+    pub allowed: Vec<AllowRule>,
+    /// 失败原因或未能解析的规则
+    pub detail: String,
+}
+
+impl InboundRules {
+    fn new(
+        backend: &str,
+        default_allow: bool,
+        allowed: Vec<AllowRule>,
+        skipped: &[String],
+    ) -> Self {
+        Self {
+            backend: backend.to_string(),
+            known: true,
+            default_allow,
+            allowed,
+            detail: if skipped.is_empty() {
+                String::new()
+            } else {
+                format!("unresolved: {}", skipped.join(", "))
+            },
+        }
+    }
+
+    fn unknown(backend: &str, detail: impl Into<String>) -> Self {
+        Self {
+            backend: backend.to_string(),
+            known: false,
+            default_allow: false,
+            allowed: Vec::new(),
+            detail: detail.into(),
+        }
+    }
+
+    /// TCP 端口是否放行；规则未知时为 None
+    pub fn allows_tcp(&self, port: u16) -> Option<bool> {
+        if !self.known {
+            return None;
+        }
+        Some(
+            self.default_allow
+                || self
+                    .allowed
+                    .iter()
+                    .any(|rule| rule.protocol != "udp" && (rule.start..=rule.end).contains(&port)),
+        )
+    }
+}
+
+impl AllowRule {
+    fn new(start: u16, end: u16, protocol: &str, source: &str) -> Self {
+        Self {
+            start,
+            end,
+            protocol: protocol.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    fn all_ports(protocol: &str, source: &str) -> Self {
+        Self::new(1, u16::MAX, protocol, source)
+    }
+}
+
+/// 查询本机防火墙状态
+pub fn firewall_status() -> FirewallStatus {
+    if crate::demo::is_enabled() {
+        return crate::demo::firewall_status();
+    }
+    let mut status = firewall_status_with(&SystemRunner, Platform::current());
+    status.needs_elevation =
+        status.state == FirewallState::Unknown && !crate::capabilities::is_elevated();
+    status
+}
+
+/// 使用指定的命令执行器查询，便于用录制的输出测试
+#[tracing::instrument(level = "debug", skip(runner))]
+pub fn firewall_status_with(runner: &dyn CommandRunner, platform: Platform) -> FirewallStatus {
+    let status = match platform {
+        Platform::MacOs => macos_status(runner),
+        Platform::Linux => linux_status(runner),
+        Platform::Windows => windows_status(runner),
+    };
+    tracing::debug!(backend = %status.backend, state = ?status.state, "防火墙状态");
+    status
+}
+
+/// 读取放行的入站端口；`status` 为 [`firewall_status`] 的结果，决定使用哪个工具
+pub fn inbound_rules(status: &FirewallStatus) -> InboundRules {
+    if crate::demo::is_enabled() && status.state == FirewallState::Enabled {
+        return InboundRules::unknown(&status.backend, "demo");
+    }
+    inbound_rules_with(&SystemRunner, status)
+}
+
+/// 使用指定的命令执行器读取入站规则
+#[tracing::instrument(level = "debug", skip(runner, status))]
+pub fn inbound_rules_with(runner: &dyn CommandRunner, status: &FirewallStatus) -> InboundRules {
+    let rules = match status.state {
+        FirewallState::Disabled => InboundRules::new(&status.backend, true, Vec::new(), &[]),
+        FirewallState::Unknown => InboundRules::unknown(&status.backend, status.detail.clone()),
+        FirewallState::Enabled => match status.backend.as_str() {
+            "ufw" => run_rules(
+                runner,
+                "ufw",
+                &["status", "verbose"],
+                "ufw",
+                parse_ufw_rules,
+            ),
+            "firewalld" => run_rules(
+                runner,
+                "firewall-cmd",
+                &["--list-all"],
+                "firewalld",
+                parse_firewalld_rules,
+            ),
+            "nftables" => run_rules(
+                runner,
+                "nft",
+                &["list", "ruleset"],
+                "nftables",
+                parse_nft_rules,
+            ),
+            "netsh" => run_rules(
+                runner,
+                "netsh",
+                &[
+                    "advfirewall",
+                    "firewall",
+                    "show",
+                    "rule",
+                    "name=all",
+                    "dir=in",
+                ],
+                "netsh",
+                parse_netsh_rules,
+            ),
+            backend => InboundRules::unknown(backend, "rules are per application, not per port"),
+        },
+    };
+    tracing::debug!(backend = %rules.backend, known = rules.known, allowed = rules.allowed.len(), "防火墙入站规则");
+    rules
+}
+
+fn run_rules(
+    runner: &dyn CommandRunner,
+    program: &str,
+    args: &[&str],
+    backend: &str,
+    parse: fn(&str) -> InboundRules,
+) -> InboundRules {
+    match runner.run(program, "防火墙规则", &mut |cmd| {
+        hide_console_window(cmd.args(args));
+    }) {
+        Ok(output) if output.status == 0 => parse(&output.stdout),
+        Ok(output) => InboundRules::unknown(backend, output.stderr.trim()),
+        Err(err) => InboundRules::unknown(backend, err.to_string()),
+    }
+}
+
+fn macos_status(runner: &dyn CommandRunner) -> FirewallStatus {
+    match runner.run(SOCKETFILTERFW, "防火墙状态 (macOS)", &mut |cmd| {
+        cmd.arg("--getglobalstate");
+    }) {
+        Ok(output) => parse_socketfilterfw(&output.stdout),
+        Err(err) => FirewallStatus::unknown(err.to_string()),
+    }
+}
+
+fn linux_status(runner: &dyn CommandRunner) -> FirewallStatus {
+    let mut errors = Vec::new();
+
+    match runner.run("ufw", "防火墙状态 (ufw)", &mut |cmd| {
+        cmd.arg("status");
+    }) {
+        Ok(output) if output.status == 0 => {
+            if let Some(status) = parse_ufw_status(&output.stdout) {
+                return status;
+            }
+        }
+        Ok(output) => errors.push(format!("ufw: {}", output.stderr.trim())),
+        Err(err) => errors.push(err.to_string()),
+    }
+
+    // firewall-cmd 未运行时返回非零状态码并输出 not running
+    match runner.run("firewall-cmd", "防火墙状态 (firewalld)", &mut |cmd| {
+        cmd.arg("--state");
+    }) {
+        Ok(output) => {
+            let state = output.stdout.trim();
+            match state {
+                "running" => {
+                    return FirewallStatus::new("firewalld", FirewallState::Enabled, state)
+                }
+                "not running" => {
+                    return FirewallStatus::new("firewalld", FirewallState::Disabled, state)
+                }
+                _ => errors.push(format!("firewall-cmd: {}", output.stderr.trim())),
+            }
+        }
+        Err(err) => errors.push(err.to_string()),
+    }
+
+    match runner.run("nft", "防火墙状态 (nftables)", &mut |cmd| {
+        cmd.args(["list", "ruleset"]);
+    }) {
+        Ok(output) if output.status == 0 => return parse_nft_ruleset(&output.stdout),
+        Ok(output) => errors.push(format!("nft: {}", output.stderr.trim())),
+        Err(err) => errors.push(err.to_string()),
+    }
+
+    FirewallStatus::unknown(errors.join("; "))
+}
+
+fn windows_status(runner: &dyn CommandRunner) -> FirewallStatus {
+    match runner.run("netsh", "防火墙状态 (Windows)", &mut |cmd| {
+        hide_console_window(cmd.args(["advfirewall", "show", "allprofiles", "state"]));
+    }) {
+        Ok(output) if output.status == 0 => parse_netsh_state(&output.stdout),
+        Ok(output) => FirewallStatus::unknown(output.stderr.trim()),
+        Err(err) => FirewallStatus::unknown(err.to_string()),
+    }
+}
+
+/// 解析 `socketfilterfw --getglobalstate`，如 `Firewall is enabled. (State = 1)`
+fn parse_socketfilterfw(stdout: &str) -> FirewallStatus {
+    let line = stdout
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or_default();
+    let lower = line.to_lowercase();
+    let state = if lower.contains("disabled") || lower.contains("state = 0") {
+        FirewallState::Disabled
+    } else if lower.contains("enabled") {
+        FirewallState::Enabled
+    } else {
+        FirewallState::Unknown
+    };
+    FirewallStatus::new("socketfilterfw", state, line)
+}
+
+/// 解析 `ufw status` 的 `Status: active / inactive` 行
+fn parse_ufw_status(stdout: &str) -> Option<FirewallStatus> {
+    let line = stdout
+        .lines()
+        .map(str::trim)
+        .find(|l| l.to_lowercase().starts_with("status:"))?;
+    let state = match line[7..].trim() {
+        "active" => FirewallState::Enabled,
+        "inactive" => FirewallState::Disabled,
+        _ => return None,
+    };
+    Some(FirewallStatus::new("ufw", state, line))
+}
+
+/// `nft list ruleset` 中有过滤链即视为开启
+fn parse_nft_ruleset(stdout: &str) -> FirewallStatus {
+    let chains = stdout
+        .lines()
+        .filter(|l| l.trim_start().starts_with("type filter hook input"))
+        .count();
+    if chains > 0 {
+        FirewallStatus::new(
+            "nftables",
+            FirewallState::Enabled,
+            format!("{chains} input filter chain(s)"),
+        )
+    } else {
+        FirewallStatus::new("nftables", FirewallState::Disabled, "empty ruleset")
+    }
+}
+
+/// 解析 `netsh advfirewall show allprofiles state`；任一配置文件关闭即视为未开启
+fn parse_netsh_state(stdout: &str) -> FirewallStatus {
+    let mut profile = "";
+    let mut profiles = Vec::new();
+    for line in stdout.lines().map(str::trim) {
+        if let Some(name) = line.strip_suffix("Profile Settings:") {
+            profile = name.trim();
+        } else if let Some(value) = line.strip_prefix("State") {
+            profiles.push((profile, value.trim().eq_ignore_ascii_case("ON")));
+        }
+    }
+    if profiles.is_empty() {
+        return FirewallStatus::new("netsh", FirewallState::Unknown, stdout.trim());
+    }
+    let off: Vec<&str> = profiles
+        .iter()
+        .filter(|(_, on)| !on)
+        .map(|(name, _)| *name)
+        .collect();
+    if off.is_empty() {
+        FirewallStatus::new("netsh", FirewallState::Enabled, "all profiles ON")
+    } else {
+        FirewallStatus::new(
+            "netsh",
+            FirewallState::Disabled,
+            format!("OFF: {}", off.join(", ")),
+        )
+    }
+}
+
+/// 解析端口列表，如 `22`、`80,443`、`8000:8100`、`9000-9100`；含非数字时返回 None
+fn parse_port_ranges(spec: &str) -> Option<Vec<(u16, u16)>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.split_once([':', '-']) {
+            Some((start, end)) => Some((start.trim().parse().ok()?, end.trim().parse().ok()?)),
+            None => part.parse().ok().map(|port| (port, port)),
+        })
+        .collect()
+}
+
+/// 解析 `ufw status verbose`：`Default:` 行给出入站默认策略，其后是 `To / Action / From` 表
+fn parse_ufw_rules(stdout: &str) -> InboundRules {
+    let default_allow = stdout
+        .lines()
+        .find(|l| l.trim_start().starts_with("Default:"))
+        .is_some_and(|l| l.contains("allow (incoming)"));
+    let mut allowed = Vec::new();
+    let mut skipped = Vec::new();
+    let rows = stdout
+        .lines()
+        .skip_while(|l| !l.trim_start().starts_with("--"))
+        .skip(1);
+    for row in rows {
+        let columns: Vec<&str> = row
+            .split("  ")
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        let [to, action, from, ..] = columns[..] else {
+            continue;
+        };
+        let action = action.to_uppercase();
+        if !(action.starts_with("ALLOW") || action.starts_with("LIMIT")) || action.ends_with("OUT")
+        {
+            continue;
+        }
+        let source = from.trim_end_matches(" (v6)");
+        let to = to.split_whitespace().next().unwrap_or_default();
+        // `ufw allow from 10.0.0.0/8` 放行该来源的所有端口
+        if to == "Anywhere" {
+            allowed.push(AllowRule::all_ports("any", source));
+            continue;
+        }
+        let (ports, protocol) = to.split_once('/').unwrap_or((to, "any"));
+        match parse_port_ranges(ports) {
+            Some(ranges) => allowed.extend(
+                ranges
+                    .into_iter()
+                    .map(|(start, end)| AllowRule::new(start, end, protocol, source)),
+            ),
+            // 应用配置（如 OpenSSH）不含端口号
+            None => skipped.push(to.to_string()),
+        }
+    }
+    InboundRules::new("ufw", default_allow, allowed, &skipped)
+}
+
+/// firewalld 预定义服务对应的端口
+const FIREWALLD_SERVICES: &[(&str, u16, &str)] = &[
+    ("ssh", 22, "tcp"),
+    ("http", 80, "tcp"),
+    ("https", 443, "tcp"),
+    ("dns", 53, "any"),
+    ("mdns", 5353, "udp"),
+    ("samba", 445, "tcp"),
+    ("cockpit", 9090, "tcp"),
+    ("mysql", 3306, "tcp"),
+    ("postgresql", 5432, "tcp"),
+    ("redis", 6379, "tcp"),
+    ("dhcpv6-client", 546, "udp"),
+    ("vnc-server", 5900, "tcp"),
+    ("rdp", 3389, "tcp"),
+];
+
+/// 解析 `firewall-cmd --list-all`（默认区域）的 services / ports / target
+fn parse_firewalld_rules(stdout: &str) -> InboundRules {
+    let field = |name: &str| {
+        stdout
+            .lines()
+            .map(str::trim)
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+    };
+    let default_allow = field("target")
+        .first()
+        .is_some_and(|t| t.eq_ignore_ascii_case("ACCEPT"));
+    let mut allowed = Vec::new();
+    let mut skipped = Vec::new();
+    for service in field("services") {
+        match FIREWALLD_SERVICES
+            .iter()
+            .find(|(name, _, _)| *name == service)
+        {
+            Some((_, port, protocol)) => {
+                allowed.push(AllowRule::new(*port, *port, protocol, "Anywhere"))
+            }
+            None => skipped.push(service.to_string()),
+        }
+    }
+    for spec in field("ports") {
+        let (ports, protocol) = spec.split_once('/').unwrap_or((spec, "any"));
+        match parse_port_ranges(ports) {
+            Some(ranges) => allowed.extend(
+                ranges
+                    .into_iter()
+                    .map(|(start, end)| AllowRule::new(start, end, protocol, "Anywhere")),
+            ),
+            None => skipped.push(spec.to_string()),
+        }
+    }
+    InboundRules::new("firewalld", default_allow, allowed, &skipped)
+}
+
+/// 解析 `nft list ruleset` 中 input 过滤链的 accept 规则；任一 input 链策略为 drop 时默认拒绝
+fn parse_nft_rules(stdout: &str) -> InboundRules {
+    let mut in_input = false;
+    let mut default_allow = true;
+    let mut allowed = Vec::new();
+    let mut skipped = Vec::new();
+    for line in stdout.lines().map(str::trim) {
+        if line.starts_with("chain ") {
+            in_input = false;
+            continue;
+        }
+        if line.starts_with("type filter hook input") {
+            in_input = true;
+            if line.contains("policy drop") || line.contains("policy reject") {
+                default_allow = false;
+            }
+            continue;
+        }
+        if !in_input || !line.ends_with("accept") {
+            continue;
+        }
+        // 已建立连接、ICMP 和回环接口的放行与端口无关
+        if line.contains("ct state") || line.contains("icmp") || line.contains("\"lo\"") {
+            continue;
+        }
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let source = tokens
+            .iter()
+            .position(|t| *t == "saddr" || *t == "iifname")
+            .and_then(|i| tokens.get(i + 1))
+            .map(|s| s.trim_matches('"'))
+            .unwrap_or("Anywhere");
+        let Some(dport) = tokens.iter().position(|t| *t == "dport") else {
+            allowed.push(AllowRule::all_ports("any", source));
+            continue;
+        };
+        let protocol = match dport.checked_sub(1).map(|i| tokens[i]) {
+            Some("tcp") => "tcp",
+            Some("udp") => "udp",
+            _ => "any",
+        };
+        // `tcp dport 22 accept` 或 `tcp dport { 22, 80-90 } accept`
+        let spec = if tokens.get(dport + 1) == Some(&"{") {
+            tokens[dport + 2..]
+                .iter()
+                .take_while(|t| **t != "}")
+                .map(|t| t.trim_end_matches(','))
+                .collect::<Vec<_>>()
+                .join(",")
+        } else {
+            tokens
+                .get(dport + 1)
+                .copied()
+                .unwrap_or_default()
+                .to_string()
+        };
+        match parse_port_ranges(&spec) {
+            Some(ranges) => allowed.extend(
+                ranges
+                    .into_iter()
+                    .map(|(start, end)| AllowRule::new(start, end, protocol, source)),
+            ),
+            None => skipped.push(spec),
+        }
+    }
+    InboundRules::new("nftables", default_allow, allowed, &skipped)
+}
+
+/// 解析 `netsh advfirewall firewall show rule name=all dir=in`；Windows 入站默认阻止。
+/// 本地端口为 Any 的规则通常只放行特定程序，不计入端口放行
+fn parse_netsh_rules(stdout: &str) -> InboundRules {
+    let mut allowed = Vec::new();
+    let mut program_rules = 0usize;
+    let mut parsed = 0usize;
+    for block in stdout.split("Rule Name:").skip(1) {
+        let field = |name: &str| {
+            block
+                .lines()
+                .map(str::trim)
+                .find_map(|l| l.strip_prefix(name)?.trim_start().strip_prefix(':'))
+                .map(str::trim)
+                .unwrap_or_default()
+        };
+        parsed += 1;
+        if !field("Enabled").eq_ignore_ascii_case("Yes")
+            || !field("Action").eq_ignore_ascii_case("Allow")
+        {
+            continue;
+        }
+        let protocol = field("Protocol").to_lowercase();
+        let protocol = if protocol == "tcp" || protocol == "udp" {
+            protocol
+        } else {
+            "any".to_string()
+        };
+        let source = match field("RemoteIP") {
+            "" | "Any" => "Anywhere",
+            remote => remote,
+        };
+        match parse_port_ranges(field("LocalPort")) {
+            Some(ranges) if !ranges.is_empty() => allowed.extend(
+                ranges
+                    .into_iter()
+                    .map(|(start, end)| AllowRule::new(start, end, &protocol, source)),
+            ),
+            _ => program_rules += 1,
+        }
+    }
+    if parsed == 0 {
+        return InboundRules::unknown("netsh", "no rules parsed");
+    }
+    let mut rules = InboundRules::new("netsh", false, allowed, &[]);
+    if program_rules > 0 {
+        rules.detail = format!("{program_rules} rule(s) without a local port not evaluated");
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+
+    #[test]
+    fn test_macos_and_windows_fixtures() {
+        let runner = FixtureRunner::new().with_stdout(
+            "/usr/libexec/ApplicationFirewall/socketfilterfw --getglobalstate",
+            include_str!("../fixtures/macos/socketfilterfw.txt"),
+        );
+        let status = firewall_status_with(&runner, Platform::MacOs);
+        assert_eq!(status.state, FirewallState::Disabled);
+        assert_eq!(status.backend, "socketfilterfw");
+
+        let runner = FixtureRunner::new().with_stdout(
+            "netsh advfirewall show allprofiles state",
+            &include_str!("../fixtures/windows/netsh_firewall.txt").replace('\n', "\r\n"),
+        );
+        let status = firewall_status_with(&runner, Platform::Windows);
+        assert_eq!(status.state, FirewallState::Disabled);
+        assert_eq!(status.detail, "OFF: Public");
+    }
+
+    #[test]
+    fn test_linux_falls_back_between_tools() {
+        let runner = FixtureRunner::new().with_stdout(
+            "ufw status",
+            include_str!("../fixtures/linux/ufw_status.txt"),
+        );
+        let status = firewall_status_with(&runner, Platform::Linux);
+        assert_eq!(status.state, FirewallState::Enabled);
+        assert_eq!(status.backend, "ufw");
+
+        // 非 root 时 ufw 报错，回退到 firewalld
+        let runner = FixtureRunner::new()
+            .with_output(
+                "ufw status",
+                1,
+                "",
+                "ERROR: You need to be root to run this script",
+            )
+            .with_output("firewall-cmd --state", 252, "not running\n", "");
+        let status = firewall_status_with(&runner, Platform::Linux);
+        assert_eq!(status.state, FirewallState::Disabled);
+        assert_eq!(status.backend, "firewalld");
+
+        let runner = FixtureRunner::new().with_stdout(
+            "nft list ruleset",
+            "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy drop;\n\t}\n}\n",
+        );
+        assert_eq!(
+            firewall_status_with(&runner, Platform::Linux).state,
+            FirewallState::Enabled
+        );
+
+        let status = firewall_status_with(&FixtureRunner::new(), Platform::Linux);
+        assert_eq!(status.state, FirewallState::Unknown);
+        assert!(status.backend.is_empty());
+    }
+
+    #[test]
+    fn test_inbound_rules_parsers() {
+        let enabled = |backend: &str| FirewallStatus::new(backend, FirewallState::Enabled, "");
+
+        let runner = FixtureRunner::new().with_stdout(
+            "firewall-cmd --list-all",
+            "public (active)\n  target: default\n  interfaces: eth0\n  services: ssh dhcpv6-client custom\n  ports: 8080/tcp 9000-9100/tcp\n",
+        );
+        let rules = inbound_rules_with(&runner, &enabled("firewalld"));
+        assert!(rules.known && !rules.default_allow);
+        assert_eq!(rules.allows_tcp(22), Some(true));
+        assert_eq!(rules.allows_tcp(9050), Some(true));
+        assert_eq!(rules.allows_tcp(5432), Some(false));
+        assert_eq!(rules.detail, "unresolved: custom");
+
+        let runner = FixtureRunner::new().with_stdout(
+            "nft list ruleset",
+            "table inet filter {\n\tchain input {\n\t\ttype filter hook input priority filter; policy drop;\n\t\tct state established,related accept\n\t\tiifname \"lo\" accept\n\t\ttcp dport { 22, 80-81 } accept\n\t\tip saddr 10.0.0.0/8 tcp dport 5432 accept\n\t\tudp dport 53 accept\n\t}\n\tchain output {\n\t\ttype filter hook output priority filter; policy accept;\n\t\ttcp dport 3306 accept\n\t}\n}\n",
+        );
+        let rules = inbound_rules_with(&runner, &enabled("nftables"));
+        assert!(!rules.default_allow);
+        assert_eq!(rules.allows_tcp(81), Some(true));
+        assert_eq!(rules.allows_tcp(53), Some(false));
+        assert_eq!(rules.allows_tcp(3306), Some(false));
+        let postgres = rules.allowed.iter().find(|r| r.start == 5432).unwrap();
+        assert_eq!(postgres.source, "10.0.0.0/8");
+
+        let runner = FixtureRunner::new().with_stdout(
+            "netsh advfirewall firewall show rule name=all dir=in",
+            "\nRule Name:                            Remote Desktop\n----------------------------------------------------------------------\nEnabled:                              Yes\nDirection:                            In\nProtocol:                             TCP\nLocalPort:                            3389\nRemoteIP:                             Any\nAction:                               Allow\n\nRule Name:                            Disabled web\n----------------------------------------------------------------------\nEnabled:                              No\nProtocol:                             TCP\nLocalPort:                            80\nAction:                               Allow\n\nRule Name:                            Some app\n----------------------------------------------------------------------\nEnabled:                              Yes\nProtocol:                             TCP\nLocalPort:                            Any\nAction:                               Allow\n",
+        );
+        let rules = inbound_rules_with(&runner, &enabled("netsh"));
+        assert_eq!(rules.allows_tcp(3389), Some(true));
+        assert_eq!(rules.allows_tcp(80), Some(false));
+        assert!(rules.detail.starts_with("1 rule"));
+
+        let disabled = FirewallStatus::new("ufw", FirewallState::Disabled, "");
+        let rules = inbound_rules_with(&FixtureRunner::new(), &disabled);
+        assert_eq!(rules.allows_tcp(5432), Some(true));
+        let rules = inbound_rules_with(&FixtureRunner::new(), &enabled("socketfilterfw"));
+        assert_eq!(rules.allows_tcp(22), None);
+    }
+}
@@ -0,0 +1,145 @@
+//! 事件脚本
+//!
+//! 后台监控事件（端口开启 / 关闭、新设备、端点状态变化、监控启停）发生时执行设置中的
+//! [`ScriptHookSettings`] 命令，用于接入 Home Assistant 或自定义脚本。事件与 Webhook
+//! 共用 [`WebhookPayload`]：JSON 写入脚本的 stdin，同时以 `PORTLY_EVENT`、`PORTLY_TITLE`、
+//! `PORTLY_BODY`、`PORTLY_TIME`、`PORTLY_PORT`、`PORTLY_HOST` 环境变量提供
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_input;
+use crate::settings::ScriptHookSettings;
+use crate::webhook::{WebhookEvent, WebhookPayload};
+use std::time::Duration;
+
+/// 单个脚本的超时
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl ScriptHookSettings {
+    /// 是否对该事件执行；测试事件总是执行
+    pub fn accepts(&self, event: WebhookEvent) -> bool {
+        event == WebhookEvent::Test || self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// 事件对应的环境变量
+pub fn hook_env(payload: &WebhookPayload) -> Vec<(&'static str, String)> {
+    let mut env = vec![
+        ("PORTLY_EVENT", payload.event.name().to_string()),
+        ("PORTLY_TITLE", payload.title.clone()),
+        ("PORTLY_BODY", payload.body.clone()),
+        ("PORTLY_TIME", payload.time.clone()),
+    ];
+    if let Some(port) = payload.port {
+        env.push(("PORTLY_PORT", port.to_string()));
+    }
+    if let Some(host) = &payload.host {
+        env.push(("PORTLY_HOST", host.clone()));
+    }
+    env
+}
+
+/// 执行一次脚本，退出码非 0 时返回错误
+#[tracing::instrument(level = "debug", skip(hook, payload), fields(name = %hook.name, event = payload.event.name()))]
+pub fn run(hook: &ScriptHookSettings, payload: &WebhookPayload) -> AppResult<()> {
+    let input = serde_json::to_string(payload)
+        .map_err(|err| AppError::internal(format!("事件序列化失败：{err}")))?;
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let output = run_command_with_input(
+        shell,
+        "事件脚本",
+        |cmd| {
+            cmd.args([flag, hook.command.as_str()])
+                .envs(hook_env(payload));
+        },
+        &input,
+        HOOK_TIMEOUT,
+    )?;
+    if output.status != 0 {
+        return Err(AppError::command_failed(
+            shell,
+            "事件脚本",
+            output.status,
+            output.stderr.trim(),
+        ));
+    }
+    tracing::debug!("事件脚本执行完成");
+    Ok(())
+}
+
+/// 在后台线程为所有启用且订阅了该事件的脚本执行事件，失败只记录日志
+pub fn dispatch(hooks: &[ScriptHookSettings], payloads: Vec<WebhookPayload>) {
+    let jobs: Vec<(ScriptHookSettings, WebhookPayload)> = hooks
+        .iter()
+        .filter(|hook| hook.enabled)
+        .flat_map(|hook| {
+            payloads
+                .iter()
+                .filter(|payload| hook.accepts(payload.event))
+                .map(|payload| (hook.clone(), payload.clone()))
+        })
+        .collect();
+    if jobs.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for (hook, payload) in jobs {
+            if let Err(err) = run(&hook, &payload) {
+                tracing::warn!(name = %hook.name, event = payload.event.name(), error = %err, "事件脚本执行失败");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload {
+            event: WebhookEvent::PortClosed,
+            title: "Port 8080 closed".to_string(),
+            body: "java (PID 7)".to_string(),
+            time: "2024-01-01 12:00:00".to_string(),
+            port: Some(8080),
+            host: None,
+        }
+    }
+
+    #[test]
+    fn test_hook_env() {
+        let env = hook_env(&payload());
+        assert!(env.contains(&("PORTLY_EVENT", "port_closed".to_string())));
+        assert!(env.contains(&("PORTLY_PORT", "8080".to_string())));
+        assert!(env.iter().all(|(key, _)| *key != "PORTLY_HOST"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_passes_event_on_stdin_and_env() {
+        let dir = std::env::temp_dir().join(format!("portly_hook_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("event.json");
+        let hook = ScriptHookSettings {
+            command: format!("cat > '{}' && [ \"$PORTLY_PORT\" = 8080 ]", out.display()),
+            ..Default::default()
+        };
+
+        run(&hook, &payload()).unwrap();
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(written["event"], "port_closed");
+        assert_eq!(written["port"], 8080);
+
+        let failing = ScriptHookSettings {
+            command: "exit 3".to_string(),
+            ..Default::default()
+        };
+        assert!(run(&failing, &payload()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,300 @@
+//! 主机名模板
+//!
+//! 家庭实验室和小集群常按名字而不是 IP 管理机器，这里把 `node{01..20}.lan`、
+//! `{web,db}-[1-3].lan` 这类模板展开成主机名列表并批量解析，作为扫描目标。
+//! 支持的写法：
+//! - `{01..20}`：数字范围，起始值带前导零时按起始值的位数补零；`{a..f}` 为字母范围
+//! - `{web,db,cache}`：枚举列表
+//! - `[1-5]`、`[abc]`、`[a-c0-9]`：单字符集合
+//!
+//! `*` 和 `?` 无法枚举，会直接报错
+
+use crate::app_error::{AppError, AppResult};
+use crate::network::ResolveResult;
+use serde::{Deserialize, Serialize};
+
+/// 单个模板最多展开的主机名数量
+pub const MAX_PATTERN_HOSTS: usize = 1024;
+
+/// 批量解析时的并发线程数
+const RESOLVE_CONCURRENCY: usize = 16;
+
+const FIELD: &str = "主机名模板";
+
+/// 模板中的一个片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Choices(Vec<String>),
+}
+
+/// 把括号内的内容解析为候选片段
+type ChoiceParser = fn(&str) -> AppResult<Vec<String>>;
+
+/// 模板中一个主机名的解析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternHost {
+    pub name: String,
+    /// 解析成功时的结果
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolved: Option<ResolveResult>,
+    /// 解析失败原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 目标是否是需要展开的主机名模板；带方括号的 IPv6 地址（如 `[::1]`）不算
+pub fn is_host_pattern(target: &str) -> bool {
+    let target = target.trim();
+    target.contains(['{', '*', '?']) || (target.contains('[') && !target.contains(':'))
+}
+
+/// 把主机名模板展开为主机名列表，按模板中从左到右的顺序排列
+pub fn expand_host_pattern(pattern: &str) -> AppResult<Vec<String>> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(AppError::validation(FIELD, "不能为空"));
+    }
+    if pattern.contains(['*', '?']) {
+        return Err(AppError::validation(
+            FIELD,
+            "* 和 ? 无法枚举，请使用 {01..20}、{a,b} 或 [1-5]",
+        ));
+    }
+
+    let mut names = vec![String::new()];
+    for segment in parse_segments(pattern)? {
+        match segment {
+            Segment::Literal(text) => names.iter_mut().for_each(|name| name.push_str(&text)),
+            Segment::Choices(choices) => {
+                if names.len() * choices.len() > MAX_PATTERN_HOSTS {
+                    return Err(too_many());
+                }
+                names = names
+                    .iter()
+                    .flat_map(|prefix| choices.iter().map(move |c| format!("{prefix}{c}")))
+                    .collect();
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// 展开模板并并发解析每个主机名，结果保持展开顺序；单个主机名解析失败不影响其他主机
+#[tracing::instrument(level = "info")]
+pub fn resolve_host_pattern(pattern: &str) -> AppResult<Vec<PatternHost>> {
+    let names = expand_host_pattern(pattern)?;
+    let chunk_size = names.len().div_ceil(RESOLVE_CONCURRENCY).max(1);
+    let hosts = std::thread::scope(|scope| {
+        let workers: Vec<_> = names
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|name| resolve_one(name))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+    Ok(hosts)
+}
+
+fn resolve_one(name: &str) -> PatternHost {
+    match crate::network::resolve_target(name) {
+        Ok(resolved) => PatternHost {
+            name: name.to_string(),
+            resolved: Some(resolved),
+            error: None,
+        },
+        Err(err) => PatternHost {
+            name: name.to_string(),
+            resolved: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+fn too_many() -> AppError {
+    AppError::validation(
+        FIELD,
+        format!("展开结果过多，最多 {} 个主机名", MAX_PATTERN_HOSTS),
+    )
+}
+
+fn parse_segments(pattern: &str) -> AppResult<Vec<Segment>> {
+    let unbalanced = || AppError::validation(FIELD, "括号不匹配");
+    let mut segments = Vec::new();
+    let mut rest = pattern;
+    while let Some(open) = rest.find(['{', '[', '}', ']']) {
+        let (close, parse): (char, ChoiceParser) = match rest[open..].chars().next() {
+            Some('{') => ('}', parse_braces),
+            Some('[') => (']', parse_class),
+            _ => return Err(unbalanced()),
+        };
+        let len = rest[open + 1..].find(close).ok_or_else(unbalanced)?;
+        let body = &rest[open + 1..open + 1 + len];
+        if body.contains(['{', '[', '}', ']']) {
+            return Err(AppError::validation(FIELD, "不支持嵌套括号"));
+        }
+        if open > 0 {
+            segments.push(Segment::Literal(rest[..open].to_string()));
+        }
+        segments.push(Segment::Choices(parse(body)?));
+        rest = &rest[open + len + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Literal(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+/// `{01..20}`、`{a..f}` 或 `{web,db}`
+fn parse_braces(body: &str) -> AppResult<Vec<String>> {
+    if let Some((start, end)) = body.split_once("..") {
+        return parse_range(start.trim(), end.trim());
+    }
+    let items: Vec<String> = body.split(',').map(|s| s.trim().to_string()).collect();
+    if items.len() < 2 || items.iter().any(|s| s.is_empty()) {
+        return Err(AppError::validation(
+            FIELD,
+            "{} 内需要范围（如 {01..20}）或逗号分隔的列表",
+        ));
+    }
+    Ok(items)
+}
+
+fn parse_range(start: &str, end: &str) -> AppResult<Vec<String>> {
+    let invalid = || AppError::validation(FIELD, "范围格式不正确，例如 {01..20} 或 {a..f}");
+    if let (Ok(from), Ok(to)) = (start.parse::<u64>(), end.parse::<u64>()) {
+        if from.abs_diff(to) as usize >= MAX_PATTERN_HOSTS {
+            return Err(too_many());
+        }
+        let width = if start.len() > 1 && start.starts_with('0') {
+            start.len()
+        } else {
+            0
+        };
+        let values: Vec<u64> = if from <= to {
+            (from..=to).collect()
+        } else {
+            (to..=from).rev().collect()
+        };
+        return Ok(values.into_iter().map(|n| format!("{n:0width$}")).collect());
+    }
+
+    match (single_char(start), single_char(end)) {
+        (Some(from), Some(to)) if same_kind(from, to) => {
+            let chars: Vec<char> = if from <= to {
+                (from..=to).collect()
+            } else {
+                (to..=from).rev().collect()
+            };
+            Ok(chars.into_iter().map(String::from).collect())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// `[1-5]`、`[abc]` 或 `[a-c0-9]`
+fn parse_class(body: &str) -> AppResult<Vec<String>> {
+    let chars: Vec<char> = body.chars().collect();
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if i + 2 < chars.len() && chars[i + 1] == '-' {
+            let (from, to) = (chars[i], chars[i + 2]);
+            if from > to || !same_kind(from, to) {
+                return Err(AppError::validation(
+                    FIELD,
+                    "字符范围需要从小到大，例如 [1-5]",
+                ));
+            }
+            items.extend(from..=to);
+            i += 3;
+        } else {
+            items.push(chars[i]);
+            i += 1;
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    items.retain(|c| seen.insert(*c));
+    if items.is_empty()
+        || items
+            .iter()
+            .any(|c| !c.is_ascii_alphanumeric() && *c != '-')
+    {
+        return Err(AppError::validation(
+            FIELD,
+            "[] 内只能是字母、数字或范围，例如 [1-5]",
+        ));
+    }
+    Ok(items.into_iter().map(String::from).collect())
+}
+
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+/// 字符范围两端必须同为数字、同为小写或同为大写字母，避免 `9..a` 混入标点
+fn same_kind(a: char, b: char) -> bool {
+    (a.is_ascii_digit() && b.is_ascii_digit())
+        || (a.is_ascii_lowercase() && b.is_ascii_lowercase())
+        || (a.is_ascii_uppercase() && b.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_host_pattern() {
+        let nodes = expand_host_pattern("node{01..12}.lan").unwrap();
+        assert_eq!(nodes.len(), 12);
+        assert_eq!(nodes[0], "node01.lan");
+        assert_eq!(nodes[11], "node12.lan");
+
+        assert_eq!(
+            expand_host_pattern("{web,db}-[1-2].lan").unwrap(),
+            vec!["web-1.lan", "web-2.lan", "db-1.lan", "db-2.lan"]
+        );
+        assert_eq!(
+            expand_host_pattern("rack{c..a}").unwrap(),
+            vec!["rackc", "rackb", "racka"]
+        );
+        assert_eq!(
+            expand_host_pattern("pi{8..10}").unwrap(),
+            vec!["pi8", "pi9", "pi10"]
+        );
+        assert_eq!(expand_host_pattern("nas.lan").unwrap(), vec!["nas.lan"]);
+
+        assert!(is_host_pattern("node{1..3}.lan"));
+        assert!(is_host_pattern("host[abc]"));
+        assert!(!is_host_pattern("[::1]"));
+        assert!(!is_host_pattern("example.com"));
+    }
+
+    #[test]
+    fn test_expand_host_pattern_rejects_invalid() {
+        for pattern in [
+            "node*.lan",
+            "node{1..3",
+            "node}1",
+            "node{a{b}}",
+            "node{1}",
+            "node{9..a}",
+            "node[5-1]",
+            "",
+        ] {
+            assert!(expand_host_pattern(pattern).is_err(), "{pattern}");
+        }
+        assert!(expand_host_pattern("n{1..2000}").is_err());
+        assert!(expand_host_pattern("{1..40}-{1..40}").is_err());
+    }
+}
@@ -0,0 +1,2026 @@
+//! 多语言文本
+//!
+//! 面向用户的文本集中在 [`Msg`] 消息表中，按 [`Lang`] 选择中文或英文。
+//! 文本中的 `{}` 为占位符，由 [`tr_args`] 按顺序填充。
+//!
+//! 后端返回的错误、终止进程和导出结果按进程级的消息语言（[`set_lang`]）生成：
+//! 桌面端随设置切换，CLI 启动时按 `--lang` / 配置 / 环境变量设置，未设置时为中文
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 当前消息语言，见 [`set_lang`]
+static CURRENT_LANG: AtomicU8 = AtomicU8::new(0);
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    Zh,
+    En,
+}
+
+impl Lang {
+    /// 解析语言名，支持 `zh` / `en` 以及 `zh_CN.UTF-8`、`en-US` 等 locale 形式
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        let primary = name.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        match primary {
+            "zh" | "cn" => Some(Lang::Zh),
+            "en" | "c" | "posix" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// 从 `LC_ALL` / `LC_MESSAGES` / `LANG` 推断语言；未设置时使用中文，其他语言回退到英文
+    pub fn detect() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| Self::from_name(&value).unwrap_or(Lang::En))
+            .unwrap_or_default()
+    }
+}
+
+/// 设置后端返回消息使用的语言
+pub fn set_lang(lang: Lang) {
+    CURRENT_LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// 后端返回消息使用的语言
+pub fn current_lang() -> Lang {
+    match CURRENT_LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+macro_rules! messages {
+    ($($name:ident => ($zh:expr, $en:expr),)*) => {
+        /// 消息键
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Msg {
+            $($name,)*
+        }
+
+        impl Msg {
+            /// 全部消息键
+            pub const ALL: &'static [Msg] = &[$(Msg::$name,)*];
+
+            /// 指定语言的文本
+            pub fn text(self, lang: Lang) -> &'static str {
+                match (self, lang) {
+                    $(
+                        (Msg::$name, Lang::Zh) => $zh,
+                        (Msg::$name, Lang::En) => $en,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+messages! {
+    // 通用
+    UnknownArgument => ("未知参数: {}", "Unknown argument: {}"),
+    SeeHelp => ("使用 --help 查看用法", "See --help for usage"),
+    LangNeedsValue => ("--lang 需要 zh 或 en", "--lang expects zh or en"),
+    OutputNeedsValue => ("-o/--output 需要输出格式", "-o/--output requires a format"),
+    UnknownOutputFormat => (
+        "未知输出格式: {} (可选 table, porcelain, json, json-v{}, csv, ndjson)",
+        "Unknown output format: {} (expected table, porcelain, json, json-v{}, csv, ndjson)"
+    ),
+    FilterNeedsValue => ("-f/--filter 需要应用名", "-f/--filter requires an app name"),
+    PortNeedsValue => ("-p/--port 需要 1-65535 的端口号", "-p/--port requires a port between 1 and 65535"),
+    ColumnsNeedsValue => ("--columns 需要列名列表", "--columns requires a list of column names"),
+    ColumnsEmpty => ("--columns 不能为空", "--columns must not be empty"),
+    UnknownColumn => (
+        "未知列: {} (可选 port,protocol,address,pid,process,user,command)",
+        "Unknown column: {} (expected port,protocol,address,pid,process,user,command)"
+    ),
+    OwnerHidden => (
+        "部分端口的进程信息不可见，请使用管理员权限重试",
+        "Some process info is hidden, retry with elevated privileges"
+    ),
+    RuntimeFailed => ("无法启动异步运行时: {}", "Failed to start the async runtime: {}"),
+
+    // 配置
+    ConfigInvalidOutput => ("配置中的输出格式无效: {}", "Invalid output format in config: {}"),
+    ConfigInvalidLang => ("配置中的语言无效: {}", "Invalid language in config: {}"),
+    ConfigNotSet => ("未设置: {}", "Not set: {}"),
+
+    // 表格
+    TableSummary => ("{} 个应用 | {} 个端口", "{} apps | {} ports"),
+    GroupedTitle => ("按应用分组", "Grouped by application"),
+    GroupPorts => ("端口", "Ports"),
+    GroupCommand => ("命令", "Command"),
+    ColumnPort => ("端口", "Port"),
+    ColumnProtocol => ("协议", "Proto"),
+    ColumnAddress => ("监听地址", "Address"),
+    ColumnPid => ("PID", "PID"),
+    ColumnProcess => ("应用程序", "Process"),
+    ColumnUser => ("用户", "User"),
+    ColumnCommand => ("命令", "Command"),
+    ConnectionsTitle => ("本机连接", "Connections"),
+    ConnectionsSummary => ("{} 个连接 | {} 个已建立", "{} connections | {} established"),
+    ColumnLocal => ("本地地址", "Local"),
+    ColumnRemote => ("对端地址", "Remote"),
+
+    // scan
+    ScanPortsNeedsValue => ("--ports 需要端口描述", "--ports requires a port list"),
+    ScanTimeoutInvalid => ("--timeout 需要 1-60000 的毫秒数", "--timeout requires 1-60000 milliseconds"),
+    ScanAddressNeedsValue => ("--address 需要 IP 地址、ipv4 或 ipv6", "--address requires an IP address, ipv4 or ipv6"),
+    ScanPatternUnresolved => ("⚠️  {} 解析失败: {}", "⚠️  {} could not be resolved: {}"),
+    ScanPatternEmpty => ("{} 展开后没有可扫描的主机", "{} expands to no resolvable hosts"),
+    ScanAddresses => ("解析到的地址: {}（可用 --address 选择）", "Resolved addresses: {} (pick one with --address)"),
+    ScanSummary => (
+        "扫描 {} 个端口 | {} 个开放 | 耗时 {} ms",
+        "{} ports scanned | {} open | {} ms"
+    ),
+    ScanColumnState => ("状态", "State"),
+    ScanColumnService => ("服务", "Service"),
+    ScanPolicyHit => ("命中端口策略 [{}] {}", "Port policy [{}] {}"),
+    PortOpen => ("开放", "open"),
+    PortClosed => ("关闭", "closed"),
+
+    // free
+    FreePortInvalid => ("free 需要 1-65535 的端口号", "free requires a port between 1 and 65535"),
+    FreeAlreadyFree => ("端口 {} 未被占用", "Port {} is free"),
+    FreeOwnerHidden => (
+        "端口 {} 被占用，但进程信息不可见，请使用管理员权限重试",
+        "Port {} is in use but the owner is hidden, retry with elevated privileges"
+    ),
+    FreeHeldBy => ("端口 {} 被以下进程占用:", "Port {} is held by:"),
+    FreeConfirm => ("终止 {} 个进程?", "Kill {} process(es)?"),
+    FreeCancelled => ("已取消", "Cancelled"),
+    FreeReleased => ("端口 {} 已释放", "Port {} is now free"),
+    FreeStillInUse => ("端口 {} 仍被占用", "Port {} is still in use"),
+    FreeForceHint => ("可使用 --force 强制终止", "Retry with --force to kill forcibly"),
+    ConfirmNeedsYes => ("非交互模式下请使用 --yes 确认", "Use --yes when not running interactively"),
+
+    // serve
+    ServeListenInvalid => (
+        "--listen 需要 地址:端口，如 127.0.0.1:7070",
+        "--listen expects ADDRESS:PORT, e.g. 127.0.0.1:7070"
+    ),
+    ServeTokenNeedsValue => ("--token 需要访问令牌", "--token requires a value"),
+    ServeListening => ("REST API 监听于 http://{}", "REST API listening on http://{}"),
+    ServeTokenGenerated => (
+        "访问令牌（请求头 Authorization: Bearer <令牌>）: {}",
+        "Access token (send Authorization: Bearer <token>): {}"
+    ),
+    ServeExposed => (
+        "正在监听非本机地址，请只在可信网络中使用并妥善保管令牌",
+        "Listening on a non-loopback address; use a trusted network and keep the token secret"
+    ),
+    ServeFailed => ("REST API 启动失败: {}", "Failed to start the REST API: {}"),
+
+    // 检查更新
+    UpdateAvailable => ("发现新版本 {}（当前 {}）", "New version {} is available (current {})"),
+    UpdateLatest => ("已是最新版本（{}）", "You are on the latest version ({})"),
+    UpdateDownload => ("下载: {}", "Download: {}"),
+    UpdateChangelog => ("更新内容:", "What's new:"),
+    UpdateCheckFailed => ("检查更新失败: {}", "Update check failed: {}"),
+
+    // 后台监控
+    MonitorNewPort => ("新的监听端口 {}", "New listening port {}"),
+    MonitorPortClosed => ("端口 {} 已关闭", "Port {} closed"),
+    MonitorPortOwner => ("{} (PID {})", "{} (PID {})"),
+    MonitorNewDevice => ("局域网出现新设备", "New device on the network"),
+    MonitorEndpointDown => ("{} 不可达", "{} is down"),
+    MonitorEndpointUp => ("{} 已恢复", "{} is back up"),
+    MonitorEndpointDetail => ("关注的端点连接失败", "A monitored endpoint stopped responding"),
+    MonitorEndpointRecovered => ("关注的端点已重新响应", "A monitored endpoint is responding again"),
+    MonitorFlapping => ("端口 {} 频繁重启", "Port {} is flapping"),
+    MonitorFlappingDetail => ("{} 重启了 {} 次（{} 秒内）", "{} restarted {} times within {} s"),
+    MonitorCertExpiring => ("{} 的证书将在 {} 天后过期", "Certificate for {} expires in {} days"),
+    MonitorCertExpired => ("{} 的证书已过期", "Certificate for {} has expired"),
+    MonitorCertDetail => ("{} 有效期至 {}", "{} is valid until {}"),
+
+    // 错误
+    ErrValidation => ("{} 校验失败：{}", "Invalid {}: {}"),
+    ErrCommandUnavailable => (
+        "{} 在 {} 场景下不可用（未安装或无执行权限）：{}",
+        "{} is unavailable for {} (not installed or not executable): {}"
+    ),
+    ErrCommandExecutionFailed => ("{} 在 {} 执行失败：{}", "{} failed during {}: {}"),
+    ErrCommandPermissionDenied => ("{} 在 {} 提示权限不足：{}", "{} was denied permission during {}: {}"),
+    ErrCommandTimeout => ("{} 在 {} 执行超时：{}", "{} timed out during {}: {}"),
+    ErrCommandExitCode => ("{} 在 {} 返回非零状态码：{}", "{} failed during {} with exit status {}"),
+    ErrCommandExitStderr => (
+        "{} 在 {} 返回非零状态码 {}：{}",
+        "{} failed during {} with exit status {}: {}"
+    ),
+    ErrHostUnreachable => ("{} 不可达：{}", "{} is unreachable: {}"),
+    ErrCancelled => ("{} 已取消", "{} cancelled"),
+    ErrInternal => ("内部错误：{}", "Internal error: {}"),
+
+    // 进程终止
+    KillProtected => (
+        "进程 '{}' (PID: {}) 是受保护的系统进程。如需强制终止，请使用强制模式。",
+        "Process '{}' (PID: {}) is a protected system process. Use force mode to override."
+    ),
+    KillDone => ("进程 {} 已终止", "Process {} terminated"),
+    KillForced => ("进程 {} 已强制终止", "Process {} terminated (forced)"),
+    KillFailed => ("终止进程失败: {}", "Failed to terminate process: {}"),
+    KillCommandFailed => ("执行 {} 命令失败: {}", "Failed to execute {}: {}"),
+    KillNoProcessOnPort => ("端口 {} 上未找到占用进程", "No process found on port {}"),
+    KillLookupFailed => ("查找端口进程失败: {}", "Failed to find port process: {}"),
+    KillDemoSkipped => ("演示模式下不会终止进程 {}", "Demo mode: process {} was not terminated"),
+    KillNeedsPrivilege => ("终止进程 {} 需要更高权限：{}", "Killing process {} needs elevated privileges: {}"),
+
+    // 导出
+    ExportDone => ("成功导出 {} 条记录到 {}", "Exported {} records to {}"),
+    ExportFailed => ("{} 导出失败: {}", "{} export failed: {}"),
+    ExportEncryptedDone => ("成功导出 {} 条记录（已加密）", "Exported {} records (encrypted)"),
+    ExportPassphraseTooShort => ("密码至少需要 {} 个字符", "The passphrase needs at least {} characters"),
+    ExportWriteEncryptedFailed => ("写入加密文件失败: {}", "Failed to write the encrypted file: {}"),
+    ExportPresetNotFound => ("未找到导出预设: {}", "Export preset not found: {}"),
+
+    // 规则
+    RuleTriggered => ("规则触发：{}", "Rule triggered: {}"),
+    RulePortListening => ("端口 {} 监听在 {} · {}", "Port {} listening on {} · {}"),
+    RuleLanExposed => ("端口 {} 可从局域网访问（监听在 {}）· {}", "Port {} is reachable from the LAN (bound to {}) · {}"),
+    RulePolicyViolation => ("端口 {} 命中端口策略「{}」（{}）· {}", "Port {} matches port policy \"{}\" ({}) · {}"),
+    RuleUnknownDevice => ("未知设备 {}", "Unknown device {}"),
+    RuleKillConfirmTitle => ("确认终止进程", "Confirm kill"),
+    RuleKillConfirm => ("规则「{}」请求终止进程：{}\n\n是否继续？", "Rule \"{}\" wants to kill a process: {}\n\nContinue?"),
+
+    // 权限与能力
+    CapElevated => ("当前以管理员权限运行", "Running with administrator privileges"),
+    CapKillStandard => ("普通用户只能终止自己的进程", "Standard users can only kill their own processes"),
+    CapOwnersVisible => ("可以看到所有进程占用的端口", "Ports of all processes are visible"),
+    CapOwnersHidden => (
+        "普通用户看不到其他用户进程的 PID 和名称",
+        "Standard users cannot see the PID and name of other users' processes"
+    ),
+    CapDriverPresent => ("抓包驱动已就绪", "The packet capture driver is available"),
+    CapDriverMissing => ("未检测到抓包驱动", "No packet capture driver was found"),
+    CapFirewallKnown => ("{} 报告防火墙状态为 {}", "{} reports the firewall as {}"),
+    CapFixElevateUnix => (
+        "使用 sudo 运行 portly-cli，或以管理员身份启动 Portly",
+        "Run portly-cli with sudo, or start Portly as an administrator"
+    ),
+    CapFixElevateWindows => (
+        "右键 Portly 选择“以管理员身份运行”",
+        "Right-click Portly and choose \"Run as administrator\""
+    ),
+    CapFixRawSocketMacos => (
+        "使用 sudo 运行，或为 /dev/bpf* 授予当前用户读写权限（如 Wireshark 附带的 ChmodBPF）",
+        "Run with sudo, or give your user read/write access to /dev/bpf* (e.g. Wireshark's ChmodBPF)"
+    ),
+    CapFixRawSocketLinux => (
+        "使用 sudo 运行，或执行 sudo setcap cap_net_raw,cap_net_admin=eip <Portly 可执行文件>",
+        "Run with sudo, or run sudo setcap cap_net_raw,cap_net_admin=eip <portly binary>"
+    ),
+    CapFixNpcap => ("从 https://npcap.com 安装 Npcap", "Install Npcap from https://npcap.com"),
+    CapFixBpf => ("未找到 /dev/bpf* 设备，请检查系统抓包支持", "No /dev/bpf* device was found; check the system's packet capture support"),
+    CapFixFirewallLinux => (
+        "使用 sudo 运行，或改用 firewalld（firewall-cmd --state 不需要 root）",
+        "Run with sudo, or use firewalld (firewall-cmd --state works without root)"
+    ),
+
+    // 安全审计
+    AuditClassDatabase => ("数据库", "Database"),
+    AuditClassCache => ("缓存 / 消息队列", "Cache / queue"),
+    AuditClassRemoteAccess => ("远程访问", "Remote access"),
+    AuditClassOrchestration => ("容器编排", "Orchestration"),
+    AuditClassDebug => ("调试", "Debugger"),
+    AuditClassDevServer => ("开发服务器", "Dev server"),
+    AuditClassOther => ("服务", "Service"),
+    AuditExposedTitle => ("{}端口 {} 对外监听", "{} port {} is exposed"),
+    AuditExposedDetail => ("{} 监听在 {}，网络中的其他主机可以连接", "{} listens on {} and is reachable from other hosts"),
+    AuditFixBindLoopback => (
+        "只在本机使用时改为监听 127.0.0.1，否则用防火墙限制来源地址",
+        "Bind to 127.0.0.1 if it is only used locally, otherwise restrict sources with the firewall"
+    ),
+    AuditDockerApiTitle => ("Docker API 在端口 {} 上未加密开放", "Docker API is served without TLS on port {}"),
+    AuditDockerApiDetail => (
+        "{} 监听在 {}，能连接到该端口即可完全控制宿主机",
+        "{} listens on {}; anyone who can connect controls the host"
+    ),
+    AuditFixDockerApi => (
+        "关闭 tcp://…:2375，改用 Unix socket，或在 2376 端口启用 TLS 并校验客户端证书",
+        "Remove tcp://…:2375 and use the Unix socket, or enable TLS on 2376 with client certificates"
+    ),
+    AuditDockerHostTitle => ("DOCKER_HOST 使用未加密的 TCP 连接", "DOCKER_HOST uses plain TCP"),
+    AuditDockerHostDetail => ("Docker 地址 {} 未启用 TLS", "Docker endpoint {} is used without TLS"),
+    AuditFixDockerHost => (
+        "设置 DOCKER_TLS_VERIFY=1 并使用 2376 端口，或改用 ssh:// 连接",
+        "Set DOCKER_TLS_VERIFY=1 and use port 2376, or connect over ssh://"
+    ),
+    AuditContainerPublishedTitle => ("容器 {} 在所有网卡上发布端口 {}（{}）", "Container {} publishes port {} ({}) on all interfaces"),
+    AuditContainerPublishedDetail => (
+        "{} 把容器端口 {} 映射到 0.0.0.0:{}；Docker 直接写入 iptables 规则，ufw / firewalld 不会拦截",
+        "{} maps container port {} to 0.0.0.0:{}; Docker writes iptables rules directly, so ufw / firewalld do not filter it"
+    ),
+    AuditFixContainerPublish => (
+        "改为 127.0.0.1:{}:{} 只在本机发布，或只通过 Docker 网络访问",
+        "Publish it as 127.0.0.1:{}:{} or reach it over a Docker network only"
+    ),
+    AuditPlaintextTitle => ("{} 使用明文协议（端口 {}）", "{} uses a plaintext protocol (port {})"),
+    AuditPlaintextDetail => ("{} 监听在 {}，账号密码和传输内容都不加密", "{} listens on {}; credentials and data are sent unencrypted"),
+    AuditFixPlaintext => ("停用 {}，改用 {}", "Disable {} and use {} instead"),
+    AuditNoAuthTitle => ("{} 无需认证即可访问（端口 {}）", "{} accepts unauthenticated access (port {})"),
+    AuditNoAuthDetail => ("{} 监听在 {}，不带凭据的请求得到了正常响应", "{} listens on {} and answered a request without credentials"),
+    AuditFixNoAuth => ("为 {} 开启认证（{}），并只监听需要的地址", "Enable authentication for {} ({}) and bind only where needed"),
+    AuditSmbShareTitle => ("SMB 共享 {} 无需账号即可看到", "SMB share {} is visible without an account"),
+    AuditSmbShareDetail => ("{} 监听在 {}，匿名列出共享时可以看到这个磁盘共享", "{} listens on {}; an anonymous share listing shows this disk share"),
+    AuditFixSmbShare => (
+        "确认是否需要共享 {}，关闭来宾访问（guest ok = no / restrict anonymous）并限制可访问的网段",
+        "Check whether {} needs to be shared, disable guest access (guest ok = no / restrict anonymous) and limit which networks can reach it"
+    ),
+    AuditNfsExportTitle => ("NFS 导出 {} 对所有主机开放", "NFS export {} is open to every host"),
+    AuditNfsExportDetail => ("{} 导出给 {}，任何能连到本机的主机都能挂载", "{} exports it to {}; any host that can reach this machine can mount it"),
+    AuditFixNfsExport => (
+        "在 /etc/exports 中把 {} 限定到需要的主机或网段，然后执行 exportfs -ra",
+        "Restrict {} to the hosts or subnets that need it in /etc/exports, then run exportfs -ra"
+    ),
+    AuditPolicyTitle => ("端口 {} 命中端口策略「{}」", "Port {} matches port policy \"{}\""),
+    AuditPolicyDetail => ("{} 监听在 {}", "{} listens on {}"),
+    AuditFixPolicy => (
+        "按策略停用该服务或限制监听地址；如属预期，请在配置文件中调整策略",
+        "Stop the service or restrict where it listens as the policy requires; adjust the policy in the config file if this is expected"
+    ),
+    AuditFirewallOffTitle => ("防火墙未开启", "The firewall is disabled"),
+    AuditFirewallOffDetail => (
+        "{} 报告防火墙已关闭，{} 个对外监听的端口没有额外保护",
+        "{} reports the firewall is off; {} exposed port(s) have no extra protection"
+    ),
+    AuditFixFirewall => ("开启系统防火墙，只放行需要对外提供的端口", "Enable the host firewall and only allow the ports you intend to serve"),
+    AuditFirewallUnknownTitle => ("无法确认防火墙状态", "Could not determine the firewall state"),
+    AuditFixFirewallUnknown => (
+        "使用管理员权限重新运行审计，或手动检查防火墙设置",
+        "Re-run the audit with elevated privileges or check the firewall settings manually"
+    ),
+    AuditSummary => ("安全评分 {}/100 · {} 项发现", "Security score {}/100 · {} findings"),
+    AuditFirewall => ("防火墙: {} ({})", "Firewall: {} ({})"),
+    AuditNoFindings => ("未发现问题", "No issues found"),
+    AuditExportNeedsValue => ("--export 需要文件路径", "--export requires a file path"),
+    AuditExportUnknownFormat => (
+        "无法从文件扩展名判断导出格式: {}（可选 csv/json/ndjson/txt/md/xlsx）",
+        "Cannot infer the export format from {} (use csv/json/ndjson/txt/md/xlsx)"
+    ),
+    AuditFailed => ("安全审计失败: {}", "Security audit failed: {}"),
+
+    // exposure
+    ExposureSummary => ("{} 个服务对外监听，其中 {} 个可从局域网访问", "{} services listen beyond loopback, {} reachable from the LAN"),
+    ExposureRulesUnknown => ("无法读取防火墙入站规则: {}", "Could not read the firewall's inbound rules: {}"),
+    ExposureSelfScan => ("自扫描地址: {}", "Self-scan from {}"),
+    ExposureNone => ("没有对外监听的服务", "No services listen beyond loopback"),
+    ExposureFailed => ("局域网可达性检查失败: {}", "LAN reachability check failed: {}"),
+
+    // 定时任务
+    JobPortScanDone => ("已把 {} 个监听端口写入扫描历史", "Saved {} listening ports to scan history"),
+    JobDiscoveryDone => ("{} 中发现 {} 台在线设备", "{}: found {} online devices"),
+    JobExportDone => ("已导出到 {}", "Exported to {}"),
+
+    // Webhook
+    WebhookMonitorStarted => ("后台监控已启动", "Background monitor started"),
+    WebhookMonitorStopped => ("后台监控已停止", "Background monitor stopped"),
+    WebhookMonitorDetail => ("Portly 将推送端口、设备和端点的变化", "Portly will push port, device and endpoint changes"),
+    WebhookTestTitle => ("Portly 测试消息", "Portly test message"),
+    WebhookTestBody => ("Webhook 配置可用", "The webhook is configured correctly"),
+    HookTestBody => ("事件脚本配置可用", "The script hook is configured correctly"),
+
+    // 系统托盘
+    TraySummary => ("{} 个监听端口 · {} 个运行中容器", "{} listening ports · {} running containers"),
+    TraySummaryNoContainers => ("{} 个监听端口", "{} listening ports"),
+    TrayMonitorStopped => ("后台监控未运行", "Background monitor is off"),
+    TrayRecentChanges => ("最近变化", "Recent changes"),
+    TrayNoChanges => ("暂无变化", "No changes yet"),
+    TrayKillPort => ("终止端口…", "Kill port…"),
+    TrayNoPorts => ("没有监听端口", "No listening ports"),
+    TrayKillPortTitle => ("终止端口 {}", "Kill port {}"),
+    TrayShowWindow => ("显示主窗口", "Show window"),
+    TrayStartMonitor => ("开启后台监控", "Start monitoring"),
+    TrayStopMonitor => ("停止后台监控", "Stop monitoring"),
+    TrayQuit => ("退出 Portly", "Quit Portly"),
+
+    // JSON-RPC
+    RpcFailed => ("JSON-RPC 读写失败：{}", "JSON-RPC I/O failed: {}"),
+
+    // 端口库
+    PortsPortInvalid => ("端口号必须在 1-65535 之间", "Port must be between 1 and 65535"),
+    PortsSaved => ("已保存端口 {}：{}", "Saved port {}: {}"),
+    PortsRemoved => ("已从端口库移除端口 {}", "Removed port {} from the port database"),
+    PortsUserSource => ("（自定义）", "(custom)"),
+    TagSaved => ("已保存 {} 的标签：{}", "Saved tags for {}: {}"),
+    TagRemoved => ("已清除 {} 的标签和备注", "Cleared the tags and note of {}"),
+    TagNoteNeedsValue => ("--note 需要备注内容", "--note requires text"),
+    TagNeedsValue => ("--tag 需要标签名", "--tag requires a tag name"),
+    TagListEmpty => (
+        "资产清单为空，可用 portly-cli tag set <目标> <标签>... 添加",
+        "The inventory is empty; add targets with portly-cli tag set <TARGET> <TAG>..."
+    ),
+    TagNoTargets => ("没有带标签 {} 的目标", "No target is tagged {}"),
+
+    // 子网计算
+    SubnetSummary => (
+        "网段          {}\n地址          {}\n子网掩码      {} (/{}, {})\n反掩码        {}\n二进制掩码    {}\n广播地址      {}\n主机范围      {} - {}\n可用主机数    {}（共 {} 个地址）",
+        "Network       {}\nAddress       {}\nNetmask       {} (/{}, {})\nWildcard      {}\nBinary mask   {}\nBroadcast     {}\nHost range    {} - {}\nUsable hosts  {} ({} addresses)"
+    ),
+
+    // 端口转发检测
+    PortcheckPortInvalid => ("portcheck 需要 1-65535 的端口号", "portcheck requires a port between 1 and 65535"),
+    PortcheckReflectorNeedsValue => ("--reflector 需要反射服务地址", "--reflector requires a reflector URL"),
+    PortcheckHostNeedsValue => ("--host 需要主机名或 IP 地址", "--host requires a host name or IP address"),
+    PortcheckFailed => ("端口转发检测失败：{}", "Port-forward check failed: {}"),
+    PortcheckReachable => (
+        "端口 {} 可以从公网访问：{} 回连 {} 成功（{} ms）",
+        "Port {} is reachable from the internet: {} connected back to {} in {} ms"
+    ),
+    PortcheckUnreachable => (
+        "端口 {} 无法从公网访问：{} 回连 {} 失败（{}）",
+        "Port {} is not reachable from the internet: {} could not connect back to {} ({})"
+    ),
+    PortcheckNoListener => (
+        "本机没有进程监听 TCP 端口 {}，请先启动服务再检测",
+        "Nothing listens on TCP port {} on this machine; start the service first"
+    ),
+    PortcheckLoopbackOnly => (
+        "{} 只监听回环地址（{}），外部连接无法到达，请改为监听 0.0.0.0 或局域网地址",
+        "{} only listens on loopback ({}), so outside connections cannot reach it; bind it to 0.0.0.0 or a LAN address"
+    ),
+    PortcheckBlocked => (
+        "{} 正在监听（{}），请检查路由器的端口转发是否指向本机、防火墙是否放行入站连接，以及运营商是否封锁该端口",
+        "{} is listening ({}); check that the router forwards the port to this machine, the firewall allows inbound connections and the ISP does not block the port"
+    ),
+
+    // LLDP/CDP 邻居发现
+    NeighborsInterfaceNeedsValue => ("--interface 需要网卡名称", "--interface requires an interface name"),
+    NeighborsTimeoutInvalid => ("--timeout 需要 1-{} 的秒数", "--timeout requires 1-{} seconds"),
+    NeighborsListening => ("正在监听 LLDP/CDP 通告（最长 {} 秒）…", "Listening for LLDP/CDP announcements (up to {} s)…"),
+    NeighborsFailed => ("邻居发现失败：{}", "Neighbor discovery failed: {}"),
+    NeighborsNone => (
+        "网卡 {} 在 {} 秒内没有收到 LLDP/CDP 通告：交换机可能未启用 LLDP/CDP，或本机没有直连交换机",
+        "No LLDP/CDP announcement on interface {} within {} s: the switch may not run LLDP/CDP, or this machine is not directly attached to it"
+    ),
+    NeighborsFound => ("{} 接在交换机 {} 的端口 {}", "{} is on switch {} port {}"),
+    NeighborsVlan => ("VLAN：{}", "VLAN: {}"),
+    NeighborsPortDescription => ("端口描述：{}", "Port description: {}"),
+    NeighborsManagement => ("管理地址：{}", "Management address: {}"),
+    NeighborsPlatform => ("型号：{}", "Platform: {}"),
+
+    // DHCP 服务器发现
+    DhcpListening => ("正在等待 DHCP 服务器应答（{} 秒）…", "Waiting for DHCP server replies ({} s)…"),
+    DhcpPassiveListening => (
+        "正在被动监听 DHCP 应答（{} 秒），其他设备申请地址时才会出现…",
+        "Passively listening for DHCP replies ({} s); they only appear when another device requests an address…"
+    ),
+    DhcpFailed => ("DHCP 服务器发现失败：{}", "DHCP server discovery failed: {}"),
+    DhcpNone => ("网卡 {} 在 {} 秒内没有收到 DHCP 应答", "No DHCP reply on interface {} within {} s"),
+    DhcpServer => ("DHCP 服务器 {}（MAC {}）提供 {}", "DHCP server {} (MAC {}) offers {}"),
+    DhcpNetwork => ("网段：{}", "Network: {}"),
+    DhcpRouters => ("网关：{}", "Gateway: {}"),
+    DhcpDns => ("DNS：{}", "DNS: {}"),
+    DhcpDomain => ("域名：{}", "Domain: {}"),
+    DhcpLease => ("租期：{} 秒", "Lease: {} s"),
+    DhcpRogue => (
+        "发现 {} 台 DHCP 服务器，疑似有人私接了路由器（私设 DHCP），部分设备可能拿到错误的网关或地址",
+        "Found {} DHCP servers: probably a rogue DHCP server (e.g. a plugged-in home router); some devices may get a wrong gateway or address"
+    ),
+
+    // NTP 时间偏差
+    NtpTimeoutInvalid => ("--timeout 需要大于 0 的毫秒数", "--timeout requires a positive number of milliseconds"),
+    NtpServerReply => (
+        "{}：偏差 {}，往返 {} ms，层级 {}，参考源 {}",
+        "{}: offset {}, round trip {} ms, stratum {}, reference {}"
+    ),
+    NtpServerFailed => ("{}：无应答（{}）", "{}: no reply ({})"),
+    NtpDriftOk => ("本机时钟偏差 {}，正常", "Local clock offset {}: OK"),
+    NtpDriftWarning => (
+        "本机时钟偏差 {}，建议检查时间同步服务",
+        "Local clock offset {}: check the time sync service"
+    ),
+    NtpDriftCritical => (
+        "本机时钟偏差 {}，TLS 证书校验、Kerberos 和 TOTP 验证码可能失败",
+        "Local clock offset {}: TLS certificate checks, Kerberos and TOTP codes may fail"
+    ),
+    NtpNoReply => ("没有 NTP 服务器应答，无法判断时钟偏差", "No NTP server replied; clock offset unknown"),
+
+    // SMB / NFS 共享
+    SharesNone => ("{} 上没有可见的共享", "No visible shares on {}"),
+    SharesPublic => (
+        "标记的共享无需账号即可看到或对所有主机开放，请确认是否有意公开",
+        "Marked shares are visible without an account or open to every host; make sure that is intended"
+    ),
+
+    // 打印机
+    PrinterPortInvalid => ("--port 需要 631 或 9100", "--port requires 631 or 9100"),
+    PrinterModel => ("型号", "Model"),
+    PrinterName => ("名称", "Name"),
+    PrinterLocation => ("位置", "Location"),
+    PrinterDescription => ("描述", "Description"),
+    PrinterStatus => ("状态", "Status"),
+    PrinterReasons => ("状态原因", "Reasons"),
+    PrinterSupplies => ("耗材", "Supplies"),
+    PrinterStateIdle => ("空闲", "idle"),
+    PrinterStateProcessing => ("正在打印", "printing"),
+    PrinterStateStopped => ("已停止", "stopped"),
+    PrinterStateUnknown => ("未知", "unknown"),
+
+    // 服务依赖
+    DepsNone => ("没有发现本机服务之间的连接", "No connections between local services found"),
+    DepsConnections => ("{} 个连接", "{} connections"),
+
+    // ARP 缓存
+    ArpNone => ("ARP 表为空", "The ARP table is empty"),
+    ArpFailed => ("读取 ARP 表失败：{}", "Failed to read the ARP table: {}"),
+    ArpRandomized => ("随机 MAC", "random MAC"),
+    ArpFlushConfirmTitle => ("清空 ARP 缓存", "Flush ARP cache"),
+    ArpFlushConfirm => (
+        "清空本机的 ARP 缓存？系统会在下次通信时重新学习，期间可能有短暂延迟",
+        "Flush the local ARP cache? Entries are relearned on the next packet, which may add a short delay"
+    ),
+    ArpFlushOneConfirm => ("从 ARP 缓存中删除 {}？", "Delete {} from the ARP cache?"),
+    ArpFlushed => ("ARP 缓存已清空", "ARP cache flushed"),
+    ArpEntryDeleted => ("已从 ARP 缓存中删除 {}", "Deleted {} from the ARP cache"),
+    ArpFlushFailed => ("清空 ARP 缓存失败：{}", "Failed to flush the ARP cache: {}"),
+
+    // 路由表
+    RoutesNone => ("路由表为空", "The routing table is empty"),
+    RoutesFailed => ("读取路由表失败：{}", "Failed to read the routing table: {}"),
+    RoutesOnLink => ("直连", "on-link"),
+    RoutesDefaultGateway => ("默认网关：{}（{}）", "Default gateway: {} ({})"),
+    RoutesLookup => ("到 {} 的流量走 {}，经 {} 从 {} 发出", "Traffic to {} uses {} via {} out of {}"),
+    RoutesNoMatch => ("没有到 {} 的路由", "No route to {}"),
+    RoutesTargetNeedsValue => ("--to 需要主机名或 IP", "--to requires a host name or IP"),
+
+    // 网卡统计
+    IfstatsNone => ("没有可统计的网卡", "No interfaces to report"),
+    IfstatsFailed => ("读取网卡统计失败：{}", "Failed to read interface statistics: {}"),
+    IfstatsUp => ("已连接", "up"),
+    IfstatsDown => ("未连接", "down"),
+    IfstatsErrors => ("错误 {}", "errors {}"),
+    IfstatsNewErrors => ("新增错误 {}", "+{} errors"),
+    IfstatsIntervalInvalid => ("--interval 需要 1-60 的秒数", "--interval requires 1-60 seconds"),
+    IfstatsCountInvalid => ("--count 需要大于 0 的次数", "--count requires a positive number"),
+
+    // 设备发现
+    DiscoverVlanInvalid => ("--vlan 需要 1-4094 的 VLAN ID", "--vlan requires a VLAN ID between 1 and 4094"),
+    DiscoverFailed => ("设备发现失败：{}", "Device discovery failed: {}"),
+    DiscoverSummary => (
+        "{}：发现 {} 台设备（{}，接口 {}，{} ms）",
+        "{}: {} devices found ({}, interface {}, {} ms)"
+    ),
+
+    // 备份
+    BackupExported => (
+        "已导出备份 {}：{} 条规则、{} 个定时任务、{} 个自定义端口、{} 个标记目标、{} 次历史扫描",
+        "Exported backup {}: {} rules, {} scheduled jobs, {} custom ports, {} tagged targets, {} history scans"
+    ),
+    BackupImported => (
+        "已从备份恢复（创建于 {}）：{} 条规则、{} 个定时任务、{} 个自定义端口、{} 个标记目标，新增 {} 次历史扫描，跳过 {} 次已有扫描",
+        "Restored backup created {}: {} rules, {} scheduled jobs, {} custom ports, {} tagged targets, {} history scans added, {} already present"
+    ),
+    BackupHooksSkipped => (
+        "备份包含 {} 个脚本钩子，未导入（确认以下命令可信后加 --with-hooks 重新导入）：",
+        "The backup contains {} script hooks that were not imported (re-run with --with-hooks once you trust these commands):"
+    ),
+    BackupHooksInstalling => (
+        "将安装备份中的 {} 个脚本钩子，事件触发时会通过 shell 执行：",
+        "Installing {} script hooks from the backup; they run through the shell when events fire:"
+    ),
+
+    // 帮助
+    HelpMain => (
+        r#"
+🔍 Portly CLI - 跨平台端口扫描器
+
+用法: portly-cli [选项]
+
+选项:
+  -j, --json           JSON 格式输出（当前为 json-v1）
+  -o, --output <FMT>   输出格式: table, porcelain, json, json-v1, csv, ndjson
+      --csv            CSV 格式输出
+      --ndjson         NDJSON 格式输出（每行一条）
+  -q, --porcelain      制表符分隔、无标题的精简输出（stdout 不是终端时默认使用）
+  -g, --group          按应用分组显示
+  -c, --command        显示进程命令行
+  -x, --exclude-system 排除系统进程
+  -u, --udp            同时列出 UDP 套接字（DNS、mDNS、游戏服务器等，不含 -g 分组）
+      --connections    列出本机 TCP 连接（对端地址、状态、PID），-p 匹配本地或对端端口
+      --no-udp         只列出 TCP 端口，覆盖配置中的 defaults.udp
+      --dual-stack     同一进程的 IPv4 / IPv6 监听合并为一行（也可设置 defaults.dual_stack）
+  -f, --filter <APP>   按应用名过滤
+  -p, --port <PORT>    按端口号过滤
+      --columns <LIST> 表格列及顺序，如 port,process,pid,address
+                       (port, protocol, address, pid, process, user, command)
+      --no-color       禁用颜色（也可设置 NO_COLOR）
+      --lang <zh|en>   界面语言（默认按 LANG 检测）
+      --demo           使用内置示例数据，不扫描也不终止进程（也可设置 PORTLY_DEMO=1）
+      --check-update   检查 GitHub 上是否有新版本（可与 -j 一起使用）
+      --rpc            在 stdin/stdout 上提供 JSON-RPC 接口（每行一个请求）
+      --man            输出 roff 格式的 man page
+  -h, --help           显示帮助信息
+
+子命令:
+  scan <TARGET>        扫描远程主机端口 (scan --help)
+  config               查看或修改配置文件 (config --help)
+  free <PORT>          终止占用端口的进程 (free --help)
+  serve                以 REST API 守护进程运行 (serve --help)
+  audit                安全审计并给出修复建议 (audit --help)
+  exposure             检查哪些服务可从局域网访问 (exposure --help)
+  ports                查看或自定义常用端口库 (ports --help)
+  subnet <CIDR>        子网计算：网段、广播地址、主机范围和掩码换算 (subnet --help)
+  portcheck <PORT>     请 NAT 外的反射服务回连，检测路由器端口转发 (portcheck --help)
+  neighbors            监听 LLDP/CDP，显示本机所接交换机的名称、端口和 VLAN (neighbors --help)
+  discover [CIDR]      发现局域网设备，可指定接口或 802.1Q VLAN (discover --help)
+  dhcp                 列出局域网内的 DHCP 服务器，检测私设 DHCP (dhcp --help)
+  arp                  查看 ARP 缓存中的 IP、MAC 和厂商，清空过期条目 (arp --help)
+  routes               查看路由表和默认网关，查询到某个目标走哪个接口 (routes --help)
+  ifstats              网卡链路速率、MTU、收发计数，按间隔采样实时吞吐量 (ifstats --help)
+  ntp [SERVER...]      查询 NTP 服务器，检测本机时钟偏差 (ntp --help)
+  shares <HOST>        列出 SMB 共享和 NFS 导出，标出公开的共享 (shares --help)
+  printer <HOST>       查询打印机型号、状态和耗材余量 (printer --help)
+  deps                 按已建立的连接列出本机服务之间的依赖 (deps --help)
+  tag                  给远程主机和局域网设备打标签、写备注 (tag --help)
+  backup               导出或恢复完整状态备份 (backup --help)
+
+示例:
+  portly-cli                    # 列出所有端口
+  portly-cli -g                 # 按应用分组
+  portly-cli -j                 # JSON 输出
+  portly-cli -f docker          # 过滤 docker 相关
+  portly-cli -p 8080            # 只显示端口 8080
+  portly-cli --csv > ports.csv  # 导出 CSV
+  portly-cli --columns port,process,pid   # 只显示部分列
+  portly-cli -p 8080 -q | cut -f4         # 取端口 8080 的 PID
+  portly-cli -c -x              # 显示命令行，排除系统进程
+  portly-cli --connections -f node   # node 连接了哪些地址
+  portly-cli scan 10.0.0.5      # 扫描远程主机
+  portly-cli free 3000 --yes    # 释放端口 3000
+  portly-cli serve --listen 0.0.0.0:7070   # 供局域网仪表盘查询
+  portly-cli audit --export audit.md       # 安全审计报告
+  portly-cli ports add 8123 "Home Assistant"   # 自定义端口名称
+  portly-cli subnet 10.0.5.9/22  # 计算子网范围
+  portly-cli portcheck 25565     # 游戏服务器能否从公网访问
+  sudo portly-cli neighbors      # 本机接在哪台交换机的哪个端口
+  sudo portly-cli discover 10.20.0.0/24 --vlan 20   # 从 trunk 口扫描 VLAN 20
+  sudo portly-cli dhcp           # 局域网里有几台 DHCP 服务器
+  sudo portly-cli arp flush 192.168.1.20   # 删除过期的 ARP 条目
+  portly-cli routes --to 10.20.3.4   # 到这个网段是否走 VPN
+  portly-cli ifstats --watch     # 各网卡实时收发速率
+  portly-cli ntp                 # 本机时钟准不准
+  portly-cli shares nas.lan      # NAS 上有哪些公开的共享
+  portly-cli printer 10.0.0.30   # 打印机型号和墨粉余量
+  portly-cli deps                # 本机服务都连了谁
+  portly-cli tag set 10.0.0.5 prod-db   # 给主机打标签
+  portly-cli backup export portly.backup   # 备份全部配置和历史
+
+退出码:
+  0  找到结果
+  1  没有匹配的端口
+  2  参数错误
+  3  权限不足，进程信息不可见
+"#,
+        r#"
+🔍 Portly CLI - Cross-platform port scanner
+
+Usage: portly-cli [OPTIONS]
+
+Options:
+  -j, --json           JSON output (currently json-v1)
+  -o, --output <FMT>   Output format: table, porcelain, json, json-v1, csv, ndjson
+      --csv            CSV output
+      --ndjson         Newline-delimited JSON
+  -q, --porcelain      Tab-separated fields without banners (default when stdout is not a TTY)
+  -g, --group          Group by application
+  -c, --command        Show command line
+  -x, --exclude-system Exclude system processes
+  -u, --udp            Also list UDP sockets (DNS, mDNS, game servers; not with -g)
+      --connections    List TCP connections (remote end, state, PID); -p matches either port
+      --no-udp         Only list TCP ports, overriding defaults.udp in the config
+      --dual-stack     Merge a process's IPv4 / IPv6 listeners into one row (or defaults.dual_stack)
+  -f, --filter <APP>   Filter by app name
+  -p, --port <PORT>    Filter by port
+      --columns <LIST> Table columns, e.g. port,process,pid,address
+                       (port, protocol, address, pid, process, user, command)
+      --no-color       Disable colors (or set NO_COLOR)
+      --lang <zh|en>   Output language (detected from LANG by default)
+      --demo           Use bundled demo data; nothing is scanned or killed (or PORTLY_DEMO=1)
+      --check-update   Check GitHub for a newer release (combine with -j for JSON)
+      --rpc            Serve JSON-RPC on stdin/stdout (one request per line)
+      --man            Print the man page in roff format
+  -h, --help           Show help
+
+Subcommands:
+  scan <TARGET>        Scan a remote host (scan --help)
+  config               View or edit the config file (config --help)
+  free <PORT>          Kill whatever holds a port (free --help)
+  serve                Run as a REST API daemon (serve --help)
+  audit                Security audit with remediation hints (audit --help)
+  exposure             Which services can LAN hosts really reach (exposure --help)
+  ports                View or customize the common ports database (ports --help)
+  subnet <CIDR>        Subnet calculator: network, broadcast, host range, masks (subnet --help)
+  portcheck <PORT>     Check a router port-forward via a reflector outside the NAT (portcheck --help)
+  neighbors            Listen for LLDP/CDP: switch name, port and VLAN of the uplink (neighbors --help)
+  discover [CIDR]      Discover LAN devices, optionally on an interface or 802.1Q VLAN (discover --help)
+  dhcp                 List the DHCP servers on the LAN and detect rogue DHCP (dhcp --help)
+  arp                  Show the ARP cache with MACs and vendors, flush stale entries (arp --help)
+  routes               Routing table, default gateway and the route a target takes (routes --help)
+  ifstats              Interface link speed, MTU and counters, live throughput sampling (ifstats --help)
+  ntp [SERVER...]      Query NTP servers and check the local clock offset (ntp --help)
+  shares <HOST>        List SMB shares and NFS exports, flag public ones (shares --help)
+  printer <HOST>       Show a printer's model, state and supply levels (printer --help)
+  deps                 Show which local services talk to each other (deps --help)
+  tag                  Tag remote hosts and LAN devices and keep notes on them (tag --help)
+  backup               Export or restore a full state backup (backup --help)
+
+Examples:
+  portly-cli                    # List all ports
+  portly-cli -g                 # Group by app
+  portly-cli -j                 # JSON output
+  portly-cli -f docker          # Filter docker
+  portly-cli -p 8080            # Show port 8080
+  portly-cli --csv > ports.csv  # Write CSV
+  portly-cli --columns port,process,pid   # Selected columns
+  portly-cli -p 8080 -q | cut -f4         # PID holding port 8080
+  portly-cli -c -x              # With command, no system processes
+  portly-cli --connections -f node   # Who is node talking to?
+  portly-cli scan 10.0.0.5      # Scan a remote host
+  portly-cli free 3000 --yes    # Free port 3000
+  portly-cli serve --listen 0.0.0.0:7070   # Serve dashboards on the LAN
+  portly-cli audit --export audit.md       # Security audit report
+  portly-cli ports add 8123 "Home Assistant"   # Name a custom port
+  portly-cli subnet 10.0.5.9/22  # Subnet range and masks
+  portly-cli portcheck 25565     # Is the game server reachable from the internet?
+  sudo portly-cli neighbors      # Which switch port am I on?
+  sudo portly-cli discover 10.20.0.0/24 --vlan 20   # Scan VLAN 20 from a trunk port
+  sudo portly-cli dhcp           # How many DHCP servers are on the LAN?
+  sudo portly-cli arp flush 192.168.1.20   # Drop a stale ARP entry
+  portly-cli routes --to 10.20.3.4   # Does this subnet go over the VPN?
+  portly-cli ifstats --watch     # Live throughput per interface
+  portly-cli ntp                 # Is the local clock right?
+  portly-cli shares nas.lan      # Which shares does the NAS expose?
+  portly-cli printer 10.0.0.30   # Printer model and toner levels
+  portly-cli deps                # What do my local services connect to?
+  portly-cli tag set 10.0.0.5 prod-db   # Tag a host
+  portly-cli backup export portly.backup   # Back up settings and history
+
+Exit codes:
+  0  Results found
+  1  No match for -p/-f
+  2  Usage error
+  3  Permission problem (process info hidden)
+"#
+    ),
+    HelpConfig => (
+        r#"
+🔍 Portly CLI - 配置文件
+
+用法: portly-cli config [show | path | get <KEY> | set <KEY> <VALUE>]
+
+  show                 显示当前配置
+  path                 显示配置文件路径
+  get <KEY>            读取配置项（如 timeouts.scan_ms）
+  set <KEY> <VALUE>    修改配置项（TOML 语法，如 '[22, 631]'）
+
+配置项:
+  defaults.show_command, defaults.exclude_system, defaults.group, defaults.output, defaults.lang
+  exclude.processes, exclude.ports, timeouts.scan_ms, port_profiles.<NAME>
+
+示例:
+  portly-cli config set exclude.processes '["Dropbox", "rapportd"]'
+  portly-cli config set port_profiles.web 80,443,8000-8100
+  portly-cli scan 10.0.0.5 --ports web
+"#,
+        r#"
+🔍 Portly CLI - Config file
+
+Usage: portly-cli config [show | path | get <KEY> | set <KEY> <VALUE>]
+
+  show                 Show the current config
+  path                 Print the config path
+  get <KEY>            Read a value (e.g. timeouts.scan_ms)
+  set <KEY> <VALUE>    Set a value (TOML syntax, e.g. '[22, 631]')
+
+Keys:
+  defaults.show_command, defaults.exclude_system, defaults.group, defaults.output, defaults.lang
+  exclude.processes, exclude.ports, timeouts.scan_ms, port_profiles.<NAME>
+
+Examples:
+  portly-cli config set exclude.processes '["Dropbox", "rapportd"]'
+  portly-cli config set port_profiles.web 80,443,8000-8100
+  portly-cli scan 10.0.0.5 --ports web
+"#
+    ),
+    HelpFree => (
+        r#"
+🔍 Portly CLI - 释放端口
+
+用法: portly-cli free <PORT> [选项]
+
+选项:
+  -y, --yes            不询问直接终止
+      --force          强制终止（SIGKILL / taskkill /F）
+  -h, --help           显示帮助信息
+
+退出码: 0 端口已空闲, 1 已取消或仍被占用, 2 参数错误, 3 进程信息不可见
+"#,
+        r#"
+🔍 Portly CLI - Free a port
+
+Usage: portly-cli free <PORT> [OPTIONS]
+
+Options:
+  -y, --yes            Kill without asking
+      --force          Force kill (SIGKILL / taskkill /F)
+  -h, --help           Show help
+
+Exit codes: 0 port is free, 1 cancelled or still in use, 2 usage error, 3 owner hidden
+"#
+    ),
+    HelpScan => (
+        r#"
+🔍 Portly CLI - 远程端口扫描
+
+用法: portly-cli scan <TARGET> [选项]
+
+选项:
+  --ports <SPEC>       端口范围: top100 (默认), all, 1-1024, 22,80,443, 配置中的组合名
+  --timeout <MS>       连接超时毫秒（默认 500，可在配置中修改）
+  --address <ADDR>     域名有多个地址时扫描哪一个: 解析结果中的 IP, ipv4 或 ipv6（默认系统优先的地址）
+  --tag <TAG>          只扫描资产清单中带此标签的主机（按主机名或 IP 匹配）
+  -a, --all            同时显示关闭的端口
+  -j, --json           JSON 格式输出（当前为 json-v1）
+  -o, --output <FMT>   输出格式: table, porcelain, json, json-v1, csv, ndjson
+      --csv            CSV 格式输出
+      --ndjson         NDJSON 格式输出
+  -q, --porcelain      输出 端口/状态/服务，制表符分隔（stdout 不是终端时默认使用）
+      --no-color       禁用颜色
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli scan 192.168.1.10                  # 扫描常用端口
+  portly-cli scan example.com --ports 1-1024    # 扫描端口范围
+  portly-cli scan 10.0.0.5 --ports 22,80 -j     # JSON 输出
+  portly-cli scan example.com --address ipv6    # 扫描域名的 IPv6 地址
+  portly-cli scan 'node{01..20}.lan' --ports 22 # 按主机名模板批量扫描
+  portly-cli scan 'db{1..4}.lan' --tag prod-db  # 只扫描打了 prod-db 标签的主机
+
+TARGET 可以是主机名模板：{01..20} 数字范围、{a..f} 字母范围、{web,db} 列表、[1-5] 字符集合，
+展开后逐台解析并扫描，解析失败的主机只给出警告。JSON 输出为每台主机一个对象的数组，
+CSV / NDJSON / porcelain 输出增加主机名列。
+
+退出码: 0 有开放端口, 1 无开放端口, 2 参数错误
+"#,
+        r#"
+🔍 Portly CLI - Remote port scan
+
+Usage: portly-cli scan <TARGET> [OPTIONS]
+
+Options:
+  --ports <SPEC>       Ports: top100 (default), all, 1-1024, 22,80,443, or a profile name from the config
+  --timeout <MS>       Connect timeout in ms (default 500, configurable)
+  --address <ADDR>     Which resolved address to scan: one of its IPs, ipv4 or ipv6 (default: the resolver's first)
+  --tag <TAG>          Only scan hosts carrying this inventory tag (matched by name or IP)
+  -a, --all            Include closed ports
+  -j, --json           JSON output (currently json-v1)
+  -o, --output <FMT>   Output format: table, porcelain, json, json-v1, csv, ndjson
+      --csv            CSV output
+      --ndjson         Newline-delimited JSON
+  -q, --porcelain      Port, state and service, tab-separated (default when stdout is not a TTY)
+      --no-color       Disable colors
+  -h, --help           Show help
+
+Examples:
+  portly-cli scan 192.168.1.10                  # Scan common ports
+  portly-cli scan example.com --ports 1-1024    # Scan a range
+  portly-cli scan 10.0.0.5 --ports 22,80 -j     # JSON output
+  portly-cli scan example.com --address ipv6    # Scan the host's IPv6 address
+  portly-cli scan 'node{01..20}.lan' --ports 22 # Scan every host in a name pattern
+  portly-cli scan 'db{1..4}.lan' --tag prod-db  # Only the hosts tagged prod-db
+
+TARGET may be a hostname pattern: {01..20} numeric ranges, {a..f} letter ranges, {web,db} lists
+and [1-5] character sets. Each expanded name is resolved and scanned; names that fail to resolve
+only produce a warning. JSON output becomes an array with one object per host, and CSV / NDJSON /
+porcelain output gain a host column.
+
+Exit codes: 0 open ports found, 1 none open, 2 usage error
+"#
+    ),
+    HelpServe => (
+        r#"
+🔍 Portly CLI - REST API 守护进程
+
+用法: portly-cli serve [选项]
+
+选项:
+  --listen <ADDR>      监听地址（默认 127.0.0.1:7070）
+  --token <TOKEN>      访问令牌（默认读取 PORTLY_TOKEN，未设置时随机生成并打印）
+  -h, --help           显示帮助信息
+
+接口（除 /api/health 外需要 Authorization: Bearer <TOKEN>）:
+  GET  /api/health                          健康检查
+  GET  /api/ports?command=1                 本机监听端口
+  GET  /api/ports/grouped                   按应用分组
+  GET  /api/ports/filter?port=&app=&exclude_system=1
+  GET  /api/scan?target=&ports=&timeout=&all=1   远程端口扫描（同 scan -j）
+  GET  /api/docker/containers               Docker 容器
+  GET  /api/docker/compose                  按 Compose 项目分组
+  GET  /api/history                         扫描历史列表
+  GET  /api/history/entry?timestamp=        单次历史扫描
+  POST /api/history/search                  搜索历史（JSON: process, port, command, from, to, limit）
+  GET  /api/monitor                         最近一轮后台监控快照
+  GET  /api/reflect?port=&host=&timeout=    回连 host:port（默认回连请求方），供 NAT 内检测端口转发
+  GET  /api/events?token=                   WebSocket 实时推送（monitor-update, monitor-alert, rule-hit）
+
+示例:
+  portly-cli serve --listen 0.0.0.0:7070 --token s3cret
+  curl -H 'Authorization: Bearer s3cret' http://server:7070/api/ports
+"#,
+        r#"
+🔍 Portly CLI - REST API daemon
+
+Usage: portly-cli serve [OPTIONS]
+
+Options:
+  --listen <ADDR>      Address to listen on (default 127.0.0.1:7070)
+  --token <TOKEN>      Access token (default PORTLY_TOKEN, otherwise generated and printed)
+  -h, --help           Show help
+
+Endpoints (all but /api/health need Authorization: Bearer <TOKEN>):
+  GET  /api/health                          Health check
+  GET  /api/ports?command=1                 Listening ports
+  GET  /api/ports/grouped                   Grouped by application
+  GET  /api/ports/filter?port=&app=&exclude_system=1
+  GET  /api/scan?target=&ports=&timeout=&all=1   Remote scan (same as scan -j)
+  GET  /api/docker/containers               Docker containers
+  GET  /api/docker/compose                  Grouped by Compose project
+  GET  /api/history                         Scan history
+  GET  /api/history/entry?timestamp=        One saved scan
+  POST /api/history/search                  Search history (JSON: process, port, command, from, to, limit)
+  GET  /api/monitor                         Latest background monitor snapshot
+  GET  /api/reflect?port=&host=&timeout=    Connect back to host:port (default: the caller) for port-forward checks
+  GET  /api/events?token=                   WebSocket push (monitor-update, monitor-alert, rule-hit)
+
+Examples:
+  portly-cli serve --listen 0.0.0.0:7070 --token s3cret
+  curl -H 'Authorization: Bearer s3cret' http://server:7070/api/ports
+"#
+    ),
+    HelpAudit => (
+        r#"
+🔍 Portly CLI - 安全审计
+
+用法: portly-cli audit [选项]
+
+检查对外监听的端口、Docker API 和容器端口发布、明文协议和无需认证的服务，
+并结合防火墙状态给出评分、严重程度和修复建议。
+
+选项:
+  -j, --json           JSON 格式输出
+      --export <FILE>  导出报告，格式由扩展名决定（csv/json/ndjson/txt/md/xlsx）
+      --no-color       禁用颜色
+  -h, --help           显示帮助信息
+
+退出码:
+  0  没有中高危发现
+  1  存在 high 或 critical 级别的发现
+
+示例:
+  portly-cli audit
+  portly-cli audit --export audit.md
+"#,
+        r#"
+🔍 Portly CLI - Security audit
+
+Usage: portly-cli audit [OPTIONS]
+
+Checks exposed listening ports, the Docker API and published container ports,
+plaintext protocols and services without authentication, correlates them with
+the firewall state and reports a score, severities and remediation hints.
+
+Options:
+  -j, --json           JSON output
+      --export <FILE>  Export the report; format from the extension (csv/json/ndjson/txt/md/xlsx)
+      --no-color       Disable colors
+  -h, --help           Show help
+
+Exit codes:
+  0  No high or critical findings
+  1  At least one high or critical finding
+
+Examples:
+  portly-cli audit
+  portly-cli audit --export audit.md
+"#
+    ),
+    HelpExposure => (
+        r#"
+🔍 Portly CLI - 局域网可达性
+
+用法: portly-cli exposure [选项]
+
+列出监听在回环以外地址的服务，并与防火墙的入站放行规则（ufw / firewalld / nftables /
+netsh）比对，判断局域网中的其他主机能否连上：reachable、blocked、unknown（无法读取规则）。
+加 --self-scan 时从本机局域网地址实际连接每个端口，没有应答的标为 not_listening；
+发往本机地址的连接不经过防火墙入站过滤，是否放行仍以规则为准。
+
+选项:
+      --self-scan      从本机局域网地址连接每个对外端口
+  -j, --json           JSON 格式输出
+      --no-color       禁用颜色
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli exposure
+  sudo portly-cli exposure --self-scan
+"#,
+        r#"
+🔍 Portly CLI - LAN reachability
+
+Usage: portly-cli exposure [OPTIONS]
+
+Lists services bound beyond loopback and checks them against the firewall's inbound
+allow rules (ufw / firewalld / nftables / netsh) to tell whether other LAN hosts can
+connect: reachable, blocked, or unknown when the rules cannot be read. With
+--self-scan each port is also connected to from this host's LAN address and services
+that do not answer are marked not_listening; connections to the host's own address
+bypass inbound filtering, so the firewall rules still decide reachability.
+
+Options:
+      --self-scan      Connect to every exposed port from this host's LAN address
+  -j, --json           JSON output
+      --no-color       Disable colors
+  -h, --help           Show help
+
+Examples:
+  portly-cli exposure
+  sudo portly-cli exposure --self-scan
+"#
+    ),
+    HelpPorts => (
+        r#"
+🔍 Portly CLI - 常用端口库
+
+用法: portly-cli ports [list [-j] | path | add <PORT> <NAME> [--category <CAT>] | remove <PORT>]
+
+快速扫描的端口列表和扫描结果中的服务名称来自内置端口库与自定义端口文件的合并结果。
+
+  list                 列出端口库（-j 输出 JSON），自定义条目带标记
+  path                 显示自定义端口文件路径
+  add <PORT> <NAME>    新增或修改端口名称，可用 --category 指定分组
+  remove <PORT>        删除自定义条目；内置端口会从端口库中移除
+
+示例:
+  portly-cli ports add 8123 "Home Assistant" --category iot
+  portly-cli ports add 9443 "内部网关"
+  portly-cli ports remove 5000
+"#,
+        r#"
+🔍 Portly CLI - Common ports database
+
+Usage: portly-cli ports [list [-j] | path | add <PORT> <NAME> [--category <CAT>] | remove <PORT>]
+
+Quick scans and the service names in scan results use the built-in port list
+merged with your custom ports file.
+
+  list                 List the port database (-j for JSON); custom entries are marked
+  path                 Print the custom ports file path
+  add <PORT> <NAME>    Add or rename a port; --category sets its group
+  remove <PORT>        Delete a custom entry; built-in ports are removed from the database
+
+Examples:
+  portly-cli ports add 8123 "Home Assistant" --category iot
+  portly-cli ports add 9443 "Internal gateway"
+  portly-cli ports remove 5000
+"#
+    ),
+    HelpTag => (
+        r#"
+🔍 Portly CLI - 目标标签与备注
+
+用法: portly-cli tag [list [-j] | path | set <TARGET> [TAG...] [--note <TEXT>] | remove <TARGET>]
+
+标签和备注按 IP、主机名或 MAC 地址保存在资产清单中。设备发现和远程扫描的结果会带上匹配的
+标签，导出文件增加 Tags 列，discover 和 scan 可用 --tag 过滤。
+
+  list                 列出资产清单（-j 输出 JSON）
+  path                 显示资产清单文件路径
+  set <TARGET> [TAG...] 替换目标的标签，--note 同时设置备注；标签和备注都为空时删除该目标
+  remove <TARGET>      删除目标的标签和备注
+
+示例:
+  portly-cli tag set 10.0.0.5 prod-db
+  portly-cli tag set aa:bb:cc:dd:ee:01 kids-tablet iot --note "客厅的 iPad"
+  portly-cli discover --tag iot
+  portly-cli tag remove 10.0.0.5
+"#,
+        r#"
+🔍 Portly CLI - Target tags and notes
+
+Usage: portly-cli tag [list [-j] | path | set <TARGET> [TAG...] [--note <TEXT>] | remove <TARGET>]
+
+Tags and notes are kept in the inventory by IP, hostname or MAC address. Device discovery and
+remote scans show the matching tags, exports gain a Tags column, and discover and scan filter
+with --tag.
+
+  list                 List the inventory (-j for JSON)
+  path                 Print the inventory file path
+  set <TARGET> [TAG...] Replace a target's tags, --note sets its note; with neither the target is removed
+  remove <TARGET>      Delete a target's tags and note
+
+Examples:
+  portly-cli tag set 10.0.0.5 prod-db
+  portly-cli tag set aa:bb:cc:dd:ee:01 kids-tablet iot --note "Living room iPad"
+  portly-cli discover --tag iot
+  portly-cli tag remove 10.0.0.5
+"#
+    ),
+    HelpSubnet => (
+        r#"
+🔍 Portly CLI - 子网计算
+
+用法: portly-cli subnet <CIDR> [-j]
+
+计算网段、广播地址、可用主机范围和数量，以及点分、十六进制、二进制和反掩码形式的子网掩码。
+地址可以是网段内的任意主机；掩码可写成前缀长度或点分形式，只写地址时按 /32 计算。
+/31 按点对点链路计算两端地址（RFC 3021）。
+
+选项:
+  -j, --json           JSON 格式输出
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli subnet 192.168.1.0/24
+  portly-cli subnet 10.0.5.9/22
+  portly-cli subnet 10.0.5.9 255.255.252.0
+"#,
+        r#"
+🔍 Portly CLI - Subnet calculator
+
+Usage: portly-cli subnet <CIDR> [-j]
+
+Shows the network and broadcast addresses, the usable host range and count, and the
+netmask in dotted, hex, binary and wildcard form. The address may be any host in the
+network; the mask may be a prefix length or dotted, and a bare address is treated as
+/32. A /31 is treated as a point-to-point link with two usable ends (RFC 3021).
+
+Options:
+  -j, --json           JSON output
+  -h, --help           Show help
+
+Examples:
+  portly-cli subnet 192.168.1.0/24
+  portly-cli subnet 10.0.5.9/22
+  portly-cli subnet 10.0.5.9 255.255.252.0
+"#
+    ),
+    HelpPortcheck => (
+        r#"
+🔍 Portly CLI - 端口转发检测
+
+用法: portly-cli portcheck <PORT> [选项]
+
+请 NAT 外的反射服务回连本机的公网地址和端口，验证路由器端口转发是否真的可用。
+反射服务可以是在云主机上运行的 portly-cli serve（接口 GET /api/reflect），
+默认使用设置文件中的 reflector.url / reflector.token。连不通时结合本机监听情况说明原因。
+
+选项:
+  --reflector <URL>    反射服务地址，例如 https://vps.example.com:7070
+  --token <TOKEN>      反射服务的访问令牌
+  --host <HOST>        回连的地址（默认为反射服务看到的本机公网地址）
+  -j, --json           JSON 格式输出
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli serve --listen 0.0.0.0:7070 --token s3cret      # 在 NAT 外的主机上运行
+  portly-cli portcheck 25565 --reflector http://vps:7070 --token s3cret
+
+退出码: 0 可以从公网访问, 1 无法访问或检测失败, 2 参数错误
+"#,
+        r#"
+🔍 Portly CLI - Port-forward check
+
+Usage: portly-cli portcheck <PORT> [OPTIONS]
+
+Asks a reflector outside the NAT to connect back to this machine's public address and port,
+verifying that the router port-forward works end to end. The reflector can be portly-cli serve
+running on a cloud host (endpoint GET /api/reflect); reflector.url / reflector.token from the
+settings file are used by default. When the port is unreachable, local listeners explain why.
+
+Options:
+  --reflector <URL>    Reflector URL, e.g. https://vps.example.com:7070
+  --token <TOKEN>      Reflector access token
+  --host <HOST>        Address to connect back to (default: the public address the reflector sees)
+  -j, --json           JSON output
+  -h, --help           Show help
+
+Examples:
+  portly-cli serve --listen 0.0.0.0:7070 --token s3cret      # on a host outside the NAT
+  portly-cli portcheck 25565 --reflector http://vps:7070 --token s3cret
+
+Exit codes: 0 reachable from the internet, 1 unreachable or check failed, 2 usage error
+"#
+    ),
+    HelpNeighbors => (
+        r#"
+📡 Portly CLI - LLDP/CDP 邻居发现
+
+用法: portly-cli neighbors [选项]
+
+在网卡上监听交换机发出的 LLDP / CDP 通告，显示本机所接交换机的名称、端口号、端口 VLAN、
+管理地址和型号。收到第一份通告后很快返回；LLDP 默认每 30 秒发送一次，CDP 每 60 秒。
+需要打开原始以太网通道：请使用 sudo 运行或为抓包设备授予读取权限，Windows 版本暂不支持。
+
+选项:
+  -i, --interface <IF>   监听的网卡（默认为第一个已启用的以太网接口）
+  -t, --timeout <SECS>   最长监听时长，1-180 秒（默认 35，只有 CDP 时建议 65）
+  -j, --json             JSON 格式输出
+  -h, --help             显示帮助信息
+
+示例:
+  sudo portly-cli neighbors
+  sudo portly-cli neighbors -i en0 -t 65 -j
+
+退出码: 0 发现邻居, 1 未收到通告或监听失败, 2 参数错误, 3 权限不足
+"#,
+        r#"
+📡 Portly CLI - LLDP/CDP neighbor discovery
+
+Usage: portly-cli neighbors [OPTIONS]
+
+Listens on an interface for the LLDP / CDP announcements switches send and shows the switch
+name, port ID, port VLAN, management address and platform of the uplink. Returns shortly after
+the first announcement; LLDP is sent every 30 s by default, CDP every 60 s. Opening a raw
+Ethernet channel needs privileges: run with sudo or grant read access to the capture device.
+Not available on Windows yet.
+
+Options:
+  -i, --interface <IF>   Interface to listen on (default: first Ethernet interface that is up)
+  -t, --timeout <SECS>   Maximum listen time, 1-180 s (default 35, use 65 for CDP-only switches)
+  -j, --json             JSON output
+  -h, --help             Show help
+
+Examples:
+  sudo portly-cli neighbors
+  sudo portly-cli neighbors -i en0 -t 65 -j
+
+Exit codes: 0 neighbor found, 1 no announcement or listen failed, 2 usage error, 3 permission denied
+"#
+    ),
+    HelpDhcp => (
+        r#"
+🏷️ Portly CLI - DHCP 服务器发现
+
+用法: portly-cli dhcp [选项]
+
+在网卡上广播一个 DHCPDISCOVER，列出所有应答的 DHCP 服务器：服务器地址和 MAC、提供的地址
+和网段、网关、DNS、域名和租期。只是询问，不会接受提供的地址。发现多台服务器时提示疑似私设
+DHCP（例如有人把家用路由器接进了办公网）。--passive 只监听，不发送任何请求。
+需要打开原始以太网通道：请使用 sudo 运行或为抓包设备授予读取权限，Windows 版本暂不支持。
+
+选项:
+  -i, --interface <IF>   使用的网卡（默认为第一个已启用的接口）
+  -t, --timeout <SECS>   等待应答的时长，1-180 秒（默认 5）
+  --passive              只被动监听其他设备引出的应答
+  -j, --json             JSON 格式输出
+  -h, --help             显示帮助信息
+
+示例:
+  sudo portly-cli dhcp
+  sudo portly-cli dhcp -i eth1 -t 10 -j
+  sudo portly-cli dhcp --passive -t 180
+
+退出码: 0 只有一台服务器, 1 没有应答、发现多台服务器或发现失败, 2 参数错误, 3 权限不足
+"#,
+        r#"
+🏷️ Portly CLI - DHCP server discovery
+
+Usage: portly-cli dhcp [OPTIONS]
+
+Broadcasts a DHCPDISCOVER on an interface and lists every DHCP server that answers: server
+address and MAC, offered address and network, gateway, DNS, domain and lease time. It only
+asks and never accepts an offer. More than one server points to a rogue DHCP server (e.g. a
+home router plugged into the office network). --passive only listens and sends nothing.
+Opening a raw Ethernet channel needs privileges: run with sudo or grant read access to the
+capture device. Not available on Windows yet.
+
+Options:
+  -i, --interface <IF>   Interface to use (default: first interface that is up)
+  -t, --timeout <SECS>   How long to wait for replies, 1-180 s (default 5)
+  --passive              Only listen for replies triggered by other devices
+  -j, --json             JSON output
+  -h, --help             Show help
+
+Examples:
+  sudo portly-cli dhcp
+  sudo portly-cli dhcp -i eth1 -t 10 -j
+  sudo portly-cli dhcp --passive -t 180
+
+Exit codes: 0 exactly one server, 1 no reply, several servers or discovery failed, 2 usage error, 3 permission denied
+"#
+    ),
+    HelpArp => (
+        r#"
+📋 Portly CLI - ARP 缓存
+
+用法: portly-cli arp [-j] | arp flush [IP] [-y]
+
+列出本机的 ARP / 邻居表：IP、MAC、厂商、接口、类型和状态（Linux 下来自 ip neigh，可看到
+STALE 等过期条目）。本地管理的随机 MAC 单独标出。设备显示在线实际已离线时，多半是缓存里
+的旧条目，可以用 flush 清空整个缓存或删除单个条目（需要管理员权限）。
+
+  flush [IP]           清空 ARP 缓存；给出 IP 时只删除该条目
+  -y, --yes            清空前不再询问（非交互运行时必需）
+  -j, --json           JSON 格式输出
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli arp
+  sudo portly-cli arp flush 192.168.1.20
+  sudo portly-cli arp flush --yes
+
+退出码: 0 成功, 1 表为空或操作失败, 2 参数错误, 3 权限不足
+"#,
+        r#"
+📋 Portly CLI - ARP cache
+
+Usage: portly-cli arp [-j] | arp flush [IP] [-y]
+
+Lists the local ARP / neighbor table: IP, MAC, vendor, interface, type and state (on Linux it
+comes from ip neigh, so stale entries show as STALE). Locally administered random MACs are
+flagged. A device that shows online but is gone is usually a stale cache entry; flush clears
+the whole cache or a single entry (needs administrator privileges).
+
+  flush [IP]           Flush the ARP cache; with an IP only that entry is deleted
+  -y, --yes            Do not ask before flushing (required when not interactive)
+  -j, --json           JSON output
+  -h, --help           Show help
+
+Examples:
+  portly-cli arp
+  sudo portly-cli arp flush 192.168.1.20
+  sudo portly-cli arp flush --yes
+
+Exit codes: 0 success, 1 empty table or failure, 2 usage error, 3 permission denied
+"#
+    ),
+    HelpRoutes => (
+        r#"
+🧭 Portly CLI - 路由表
+
+用法: portly-cli routes [--to <TARGET>] [-j]
+
+列出系统路由表：目标网段、网关、出接口和跃点数，出接口是 VPN 的路由单独标出，最后给出
+默认网关。用 --to 查询到某个主机或 IP 的流量实际走哪条路由（最长前缀匹配），可以看出
+某个网段为什么走了 VPN，以及扫描会从哪个接口发出。
+
+  -t, --to <TARGET>    只显示到该主机或 IP 的路由
+  -j, --json           JSON 格式输出
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli routes
+  portly-cli routes --to 10.20.3.4
+  portly-cli routes -j
+
+退出码: 0 成功, 1 读取失败或没有匹配的路由, 2 参数错误
+"#,
+        r#"
+🧭 Portly CLI - Routing table
+
+Usage: portly-cli routes [--to <TARGET>] [-j]
+
+Lists the system routing table: destination, gateway, outgoing interface and metric, with
+routes over a VPN interface flagged, followed by the default gateway. --to shows which route
+traffic to a host or IP actually takes (longest prefix match), so you can see why a subnet
+goes over the VPN and which interface a scan will use.
+
+  -t, --to <TARGET>    Only show the route to this host or IP
+  -j, --json           JSON output
+  -h, --help           Show help
+
+Examples:
+  portly-cli routes
+  portly-cli routes --to 10.20.3.4
+  portly-cli routes -j
+
+Exit codes: 0 success, 1 read failure or no matching route, 2 usage error
+"#
+    ),
+    HelpIfstats => (
+        r#"
+📶 Portly CLI - 网卡统计
+
+用法: portly-cli ifstats [-w] [--interval <SECS>] [-n <COUNT>] [-j]
+
+列出各网卡的连接状态、链路速率、MTU 和开机以来的收发字节数与错误数（不含回环接口）。
+--watch 按间隔采样，显示每秒收发速率、占链路速率的比例和新增的错误，直到按 Ctrl+C
+或采够 --count 次；与 -j 一起使用时每次采样输出一行 JSON。
+
+  -w, --watch          持续采样并显示实时速率
+      --interval <SECS>  采样间隔，1-60 秒（默认 2）
+  -n, --count <COUNT>  采样指定次数后退出（配合 --watch）
+  -j, --json           JSON 格式输出
+  -h, --help           显示帮助信息
+
+示例:
+  portly-cli ifstats
+  portly-cli ifstats --watch
+  portly-cli ifstats -w --interval 5 -n 12 -j > throughput.ndjson
+
+退出码: 0 成功, 1 读取失败或没有网卡, 2 参数错误
+"#,
+        r#"
+📶 Portly CLI - Interface statistics
+
+Usage: portly-cli ifstats [-w] [--interval <SECS>] [-n <COUNT>] [-j]
+
+Lists each interface's state, link speed, MTU and the bytes and errors received and sent
+since boot (loopback excluded). --watch samples at an interval and shows receive / transmit
+rates per second, the share of the link speed in use and new errors, until Ctrl+C or
+--count samples; with -j every sample is printed as one line of JSON.
+
+  -w, --watch          Keep sampling and show live rates
+      --interval <SECS>  Sampling interval, 1-60 seconds (default 2)
+  -n, --count <COUNT>  Exit after this many samples (with --watch)
+  -j, --json           JSON output
+  -h, --help           Show help
+
+Examples:
+  portly-cli ifstats
+  portly-cli ifstats --watch
+  portly-cli ifstats -w --interval 5 -n 12 -j > throughput.ndjson
+
+Exit codes: 0 success, 1 read failure or no interfaces, 2 usage error
+"#
+    ),
+    HelpNtp => (
+        r#"
+🕒 Portly CLI - NTP 时间偏差检测
+
+用法: portly-cli ntp [SERVER...] [选项]
+
+向 NTP 服务器（UDP 123）发送 SNTP 请求，显示每台服务器的时钟偏差、往返时延、层级和参考源，
+并以各服务器偏差的中位数判断本机时钟：超过 1 秒提示，超过 30 秒时 TLS 证书校验、Kerberos
+和 TOTP 验证码可能失败。未指定服务器时查询系统配置的 NTP 服务器（chrony、ntpd、
+systemd-timesyncd、Windows 时间服务），都没有时查询 pool.ntp.org。
+
+选项:
+  -t, --timeout <MS>     每台服务器的超时（默认 2000 毫秒）
+  -j, --json             JSON 格式输出
+  -h, --help             显示帮助信息
+
+示例:
+  portly-cli ntp
+  portly-cli ntp 192.168.1.1 time.cloudflare.com
+  portly-cli ntp ntp.lan -t 500 -j
+
+退出码: 0 偏差在 1 秒以内, 1 偏差过大或没有服务器应答, 2 参数错误
+"#,
+        r#"
+🕒 Portly CLI - NTP time drift check
+
+Usage: portly-cli ntp [SERVER...] [OPTIONS]
+
+Sends SNTP requests to NTP servers (UDP 123) and shows each server's clock offset, round trip,
+stratum and reference, then judges the local clock by the median offset: above 1 s it warns,
+above 30 s TLS certificate checks, Kerberos and TOTP codes may fail. Without servers it
+queries the ones the system is configured with (chrony, ntpd, systemd-timesyncd, Windows Time
+service), falling back to pool.ntp.org.
+
+Options:
+  -t, --timeout <MS>     Timeout per server (default 2000 ms)
+  -j, --json             JSON output
+  -h, --help             Show help
+
+Examples:
+  portly-cli ntp
+  portly-cli ntp 192.168.1.1 time.cloudflare.com
+  portly-cli ntp ntp.lan -t 500 -j
+
+Exit codes: 0 offset within 1 s, 1 offset too large or no server replied, 2 usage error
+"#
+    ),
+    HelpShares => (
+        r#"
+📂 Portly CLI - SMB / NFS 共享枚举
+
+用法: portly-cli shares <HOST> [选项]
+
+列出主机上可见的 SMB 共享（smbclient -L 匿名列出，Windows 上为 net view）和 NFS 导出
+（showmount -e），用 ⚠️ 标出无需账号即可看到的磁盘共享和对所有主机开放的 NFS 导出。
+适用于开放了 445 或 2049 端口的主机；安全审计会对本机自动做同样的检查。
+
+选项:
+  --smb                  只列出 SMB 共享
+  --nfs                  只列出 NFS 导出
+  -j, --json             JSON 格式输出
+  -h, --help             显示帮助信息
+
+示例:
+  portly-cli shares 192.168.1.20
+  portly-cli shares nas.lan --nfs -j
+
+退出码: 0 没有公开的共享, 1 存在公开的共享或枚举失败, 2 参数错误
+"#,
+        r#"
+📂 Portly CLI - SMB / NFS share enumeration
+
+Usage: portly-cli shares <HOST> [OPTIONS]
+
+Lists the SMB shares (anonymous smbclient -L, net view on Windows) and NFS exports
+(showmount -e) visible on a host and marks with ⚠️ disk shares visible without an account
+and NFS exports open to every host. Meant for hosts with port 445 or 2049 open; the security
+audit runs the same check against this machine.
+
+Options:
+  --smb                  Only list SMB shares
+  --nfs                  Only list NFS exports
+  -j, --json             JSON output
+  -h, --help             Show help
+
+Examples:
+  portly-cli shares 192.168.1.20
+  portly-cli shares nas.lan --nfs -j
+
+Exit codes: 0 no public share, 1 public shares found or enumeration failed, 2 usage error
+"#
+    ),
+    HelpPrinter => (
+        r#"
+🖨  Portly CLI - 打印机信息
+
+用法: portly-cli printer <HOST> [选项]
+
+查询打印机的型号、名称、位置、状态和耗材余量。默认通过 IPP（631 端口）发送
+Get-Printer-Attributes 请求；--port 9100 时改用 JetDirect 端口的 PJL 查询，只能读到型号
+和面板状态，且不支持 PJL 的打印机可能会把查询内容打印出来。
+
+选项:
+  -p, --port <PORT>      631（IPP，默认）或 9100（PJL）
+  -j, --json             JSON 格式输出
+  -h, --help             显示帮助信息
+
+示例:
+  portly-cli printer 10.0.0.30
+  portly-cli printer printer.lan -p 9100
+  portly-cli printer 10.0.0.30 -j
+
+退出码: 0 查询成功, 1 没有打印机应答, 2 参数错误
+"#,
+        r#"
+🖨  Portly CLI - printer details
+
+Usage: portly-cli printer <HOST> [OPTIONS]
+
+Shows a printer's make and model, name, location, state and supply levels. By default it
+sends an IPP Get-Printer-Attributes request to port 631; with --port 9100 it queries the
+JetDirect port over PJL instead, which only yields the model and panel status, and printers
+without PJL support may print the query.
+
+Options:
+  -p, --port <PORT>      631 (IPP, default) or 9100 (PJL)
+  -j, --json             JSON output
+  -h, --help             Show help
+
+Examples:
+  portly-cli printer 10.0.0.30
+  portly-cli printer printer.lan -p 9100
+  portly-cli printer 10.0.0.30 -j
+
+Exit codes: 0 success, 1 no printer replied, 2 usage error
+"#
+    ),
+    HelpDeps => (
+        r#"
+🔗 Portly CLI - 服务依赖
+
+用法: portly-cli deps [选项]
+
+把已建立的 TCP 连接和监听端口对应起来，列出本机服务之间的依赖，如 3000 上的 node
+连接了 5432 上的 postgres 和 6379 上的 redis。对端在本机之外时只列出本机服务（有监听
+端口的进程）发出的连接。
+
+选项:
+  -j, --json             JSON 格式输出（节点和连线，可直接用于绘图）
+  -h, --help             显示帮助信息
+
+示例:
+  portly-cli deps
+  portly-cli deps -j
+
+退出码: 0 找到依赖, 1 没有发现依赖或连接读取失败
+"#,
+        r#"
+🔗 Portly CLI - service dependencies
+
+Usage: portly-cli deps [OPTIONS]
+
+Matches established TCP connections to listening ports and lists which local services talk to
+each other, e.g. node on 3000 connecting to postgres on 5432 and redis on 6379. Connections to
+other hosts are only listed for local services (processes with a listening port).
+
+Options:
+  -j, --json             JSON output (nodes and edges, ready for graphing)
+  -h, --help             Show help
+
+Examples:
+  portly-cli deps
+  portly-cli deps -j
+
+Exit codes: 0 dependencies found, 1 none found or connections could not be read
+"#
+    ),
+    HelpDiscover => (
+        r#"
+🔍 Portly CLI - 局域网设备发现
+
+用法: portly-cli discover [CIDR] [选项]
+
+扫描网段内的在线设备（默认为本机所在网段）。有抓包权限时使用高级 ARP 扫描，否则回退到
+Ping/ARP 基础扫描。指定 --interface 或 --vlan 时只使用高级 ARP 扫描：
+系统已有该 VLAN 的子接口（如 eth0.20、vlan0）时在子接口上扫描，否则在网卡上发送带
+802.1Q 标签的 ARP 请求，trunk 口上的机器可以逐个扫描各 VLAN。
+
+选项:
+  -i, --interface <IF>   扫描使用的网卡或 VLAN 子接口（默认为第一个已启用的接口）
+  --vlan <ID>            802.1Q VLAN ID（1-4094）
+  --tag <TAG>            只列出资产清单中带此标签的设备
+  -j, --json             JSON 格式输出
+  -h, --help             显示帮助信息
+
+示例:
+  portly-cli discover 192.168.1.0/24
+  sudo portly-cli discover 10.20.0.0/24 --vlan 20
+  sudo portly-cli discover 10.30.0.0/24 -i eth1 --vlan 30 -j
+
+退出码: 0 发现设备, 1 没有发现设备或扫描失败, 2 参数错误, 3 权限不足
+"#,
+        r#"
+🔍 Portly CLI - LAN device discovery
+
+Usage: portly-cli discover [CIDR] [OPTIONS]
+
+Finds the devices online in a subnet (default: the local subnet). Uses the advanced ARP scan
+when packet capture is permitted and falls back to the Ping/ARP scan otherwise. With
+--interface or --vlan only the advanced ARP scan is used: an existing subinterface for the VLAN
+(e.g. eth0.20, vlan0) is scanned directly, otherwise ARP requests carry an 802.1Q tag on the
+NIC, so a machine on a trunk port can scan each VLAN in turn.
+
+Options:
+  -i, --interface <IF>   NIC or VLAN subinterface to scan from (default: first interface that is up)
+  --vlan <ID>            802.1Q VLAN ID (1-4094)
+  --tag <TAG>            Only list devices carrying this inventory tag
+  -j, --json             JSON output
+  -h, --help             Show help
+
+Examples:
+  portly-cli discover 192.168.1.0/24
+  sudo portly-cli discover 10.20.0.0/24 --vlan 20
+  sudo portly-cli discover 10.30.0.0/24 -i eth1 --vlan 30 -j
+
+Exit codes: 0 devices found, 1 nothing found or scan failed, 2 usage error, 3 permission denied
+"#
+    ),
+    HelpBackup => (
+        r#"
+🔍 Portly CLI - 备份与恢复
+
+用法: portly-cli backup [export <FILE> | import <FILE> [--with-hooks]]
+
+备份文件为 gzip 压缩的 JSON，包含设置、配置文件（含端口组合）、规则、定时任务、
+自定义端口、导出预设和扫描历史。插件脚本和日志不包含在备份中。
+
+  export <FILE>        把当前状态导出到文件
+  import <FILE>        从备份恢复：替换设置、配置、规则、定时任务、自定义端口和导出预设，
+                       合并扫描历史（已有的扫描跳过）；备份不合法时不修改任何内容
+  --with-hooks         同时安装备份中的脚本钩子（导入前列出命令）；默认忽略备份中的钩子，
+                       保留本机现有的钩子
+
+示例:
+  portly-cli backup export ~/portly-$(date +%F).backup
+  portly-cli backup import portly.backup
+"#,
+        r#"
+🔍 Portly CLI - Backup and restore
+
+Usage: portly-cli backup [export <FILE> | import <FILE> [--with-hooks]]
+
+A backup is gzip-compressed JSON holding the settings, the config file (including
+port profiles), rules, scheduled jobs, custom ports, export presets and scan history.
+Plugin scripts and logs are not included.
+
+  export <FILE>        Write the current state to a file
+  import <FILE>        Restore a backup: settings, config, rules, scheduled jobs, custom
+                       ports and export presets are replaced and scan history is merged
+                       (scans already present are skipped); nothing changes if the
+                       backup is invalid
+  --with-hooks         Also install the backup's script hooks (their commands are listed
+                       first); by default they are ignored and local hooks are kept
+
+Examples:
+  portly-cli backup export ~/portly-$(date +%F).backup
+  portly-cli backup import portly.backup
+"#
+    ),
+}
+
+/// 错误中常用的字段名、场景名和校验说明（中文, 英文）
+///
+/// 调用方以中文标签构造错误（如 `AppError::validation("端口", "必须大于 0")`），
+/// 生成英文消息时按此表翻译，表中没有的文本原样保留
+const TERMS: &[(&str, &str)] = &[
+    // 字段
+    ("目标地址", "target"),
+    ("端口", "port"),
+    ("端口范围", "port range"),
+    ("子网", "subnet"),
+    ("默认子网", "default subnet"),
+    ("任务", "task"),
+    ("导出格式", "export format"),
+    ("配置项", "config key"),
+    ("配置文件", "config file"),
+    ("设置文件", "settings file"),
+    ("语言", "language"),
+    ("监控间隔", "monitor interval"),
+    ("局域网监控间隔", "LAN monitor interval"),
+    ("重启统计窗口", "restart window"),
+    ("证书监控端点", "certificate watch endpoint"),
+    ("证书检查间隔", "certificate check interval"),
+    ("证书提醒阈值", "certificate warning days"),
+    ("监控端点", "monitored endpoint"),
+    ("日志行数", "log lines"),
+    ("查询条件", "query"),
+    ("请求体", "request body"),
+    ("Ping 次数", "ping count"),
+    ("Ping 并发数", "ping concurrency"),
+    ("Ping 目标", "ping target"),
+    ("Ping 间隔", "ping interval"),
+    ("Docker 主机", "Docker host"),
+    ("Webhook 地址", "webhook URL"),
+    ("Webhook 模板", "webhook template"),
+    ("事件脚本命令", "script hook command"),
+    ("规则名称", "rule name"),
+    ("规则动作", "rule actions"),
+    ("规则端口", "rule port"),
+    ("规则端口范围", "rule port range"),
+    ("规则监听地址", "rule bind address"),
+    ("规则文件", "rules file"),
+    ("规则 ID", "rule ID"),
+    ("任务名称", "job name"),
+    ("定时任务 ID", "job ID"),
+    ("定时任务文件", "schedules file"),
+    ("执行间隔", "interval"),
+    ("执行时间", "time of day"),
+    ("星期", "weekday"),
+    ("插件清单", "plugin manifest"),
+    ("插件输出", "plugin output"),
+    ("备份文件", "backup file"),
+    ("备份版本", "backup version"),
+    ("导出预设名称", "export preset name"),
+    ("策略名称", "policy name"),
+    ("策略端口", "policy port"),
+    ("端口策略", "port policy"),
+    ("手动子网", "manual subnet"),
+    ("代理配置路径", "proxy config path"),
+    ("代理管理接口", "proxy admin API"),
+    ("主机名模板", "hostname pattern"),
+    ("端口转发反射服务", "port-forward reflector"),
+    ("网络接口", "network interface"),
+    ("监听时长", "listen duration"),
+    ("NTP 服务器", "NTP server"),
+    ("NTP 超时", "NTP timeout"),
+    ("打印机端口", "printer port"),
+    ("标记目标", "tagged target"),
+    ("标签", "tag"),
+    ("备注", "note"),
+    ("资产清单文件", "inventory file"),
+    ("端口监听间隔", "port watch interval"),
+    ("ARP 缓存", "ARP cache"),
+    ("网卡采样间隔", "interface sampling interval"),
+    // 场景
+    ("端口扫描", "port scan"),
+    ("进程终止", "process termination"),
+    ("进程信息查询", "process lookup"),
+    ("进程名读取", "process name lookup"),
+    ("进程名称读取", "process name lookup"),
+    ("进程命令行读取", "process command line lookup"),
+    ("端口占用查询", "port owner lookup"),
+    ("单次 Ping 测试", "single ping"),
+    ("主机 Ping 检测", "host ping check"),
+    ("SSL 证书检查", "SSL certificate check"),
+    ("添加证书监控", "adding a certificate watch"),
+    ("移除证书监控", "removing a certificate watch"),
+    ("TLS 指纹", "TLS fingerprint"),
+    ("防火墙规则", "firewall rules"),
+    ("局域网可达性检查", "LAN reachability check"),
+    ("端口转发检测", "port-forward check"),
+    ("邻居发现", "neighbor discovery"),
+    ("DHCP 服务器发现", "DHCP server discovery"),
+    ("时间偏差检测", "time drift check"),
+    ("共享枚举", "share enumeration"),
+    ("SMB 共享枚举", "SMB share enumeration"),
+    ("NFS 导出枚举", "NFS export enumeration"),
+    ("打印机查询", "printer query"),
+    ("连接扫描", "connection scan"),
+    ("服务依赖图", "service dependency graph"),
+    ("读取资产清单", "loading the inventory"),
+    ("保存标签", "saving tags"),
+    ("删除标签", "removing tags"),
+    ("端口刷新", "port refresh"),
+    ("设备刷新", "device refresh"),
+    ("多目标 Ping 对比", "multi-target ping comparison"),
+    ("NTP 服务器读取", "NTP server lookup"),
+    ("高级 ARP 扫描", "advanced ARP scan"),
+    ("VLAN 子接口读取", "VLAN subinterface lookup"),
+    ("端口归属统计", "port ownership summary"),
+    ("目标解析", "target resolution"),
+    ("设备延迟历史", "device latency history"),
+    ("主机名模板解析", "hostname pattern resolution"),
+    ("添加手动子网", "adding a manual subnet"),
+    ("移除手动子网", "removing a manual subnet"),
+    ("网卡类型读取", "interface type lookup"),
+    ("VPN 节点读取", "VPN peer lookup"),
+    ("端口时间线", "port timeline"),
+    ("反向代理映射", "reverse proxy mapping"),
+    ("Tailscale 状态读取", "Tailscale status lookup"),
+    ("WireGuard 状态读取", "WireGuard status lookup"),
+    ("ARP 表读取", "ARP table read"),
+    ("ARP 缓存刷新", "ARP cache refresh"),
+    ("清空 ARP 缓存", "ARP cache flush"),
+    ("路由表读取", "routing table read"),
+    ("网卡统计读取", "interface statistics read"),
+    ("Docker 可用性检测", "Docker availability check"),
+    ("容器列表", "container listing"),
+    ("Webhook 推送", "webhook delivery"),
+    ("事件脚本", "script hook"),
+    ("服务探测插件", "probe plugin"),
+    // 校验说明
+    ("不能为空", "must not be empty"),
+    ("键不能为空", "key must not be empty"),
+    ("必须大于 0", "must be greater than 0"),
+    ("端口号必须大于 0", "port must be greater than 0"),
+    ("格式不正确", "has an invalid format"),
+    (
+        "仅支持 631（IPP）和 9100（JetDirect）",
+        "only 631 (IPP) and 9100 (JetDirect) are supported",
+    ),
+    (
+        "需要 1-65535 的端口号",
+        "expects a port between 1 and 65535",
+    ),
+    (
+        "起始端口不能大于结束端口",
+        "start port must not exceed end port",
+    ),
+    ("不是合法的 IP 地址", "is not a valid IP address"),
+    ("仅支持 zh / en", "only zh / en are supported"),
+    ("至少需要一个动作", "needs at least one action"),
+    ("需要指定端口或服务名", "needs a port or a service name"),
+    (
+        "需要 CIDR 格式，例如 192.168.1.0/24",
+        "expects CIDR notation, e.g. 192.168.1.0/24",
+    ),
+    ("不是 Portly 备份", "is not a Portly backup"),
+    (
+        "不受支持，请升级 Portly",
+        "is not supported, upgrade Portly",
+    ),
+    ("至少 1 分钟", "must be at least 1 minute"),
+    ("无法确定本机所在网段", "cannot determine the local subnet"),
+    (
+        "设备规则不支持终止进程",
+        "device rules cannot kill processes",
+    ),
+    (
+        "未配置，可在 NAT 外运行 portly-cli serve 并在设置中填写其地址",
+        "is not configured, run portly-cli serve outside the NAT and set its URL in settings",
+    ),
+    ("需要在 1-180 秒之间", "must be between 1 and 180 seconds"),
+    (
+        "需要 1-4094 的 VLAN ID",
+        "expects a VLAN ID between 1 and 4094",
+    ),
+    (
+        "需要 /22 到 /24 的 IPv4 网段，例如 192.168.1.0/24",
+        "expects an IPv4 subnet from /22 to /24, e.g. 192.168.1.0/24",
+    ),
+    ("没有 MAC 地址", "has no MAC address"),
+    ("最多 16 个", "allows at most 16"),
+    (
+        "未找到处于启用状态且带有 IPv4 地址的网络接口",
+        "found no interface that is up with an IPv4 address",
+    ),
+    ("括号不匹配", "has unbalanced brackets"),
+    ("不支持嵌套括号", "does not support nested brackets"),
+    (
+        "展开结果过多，最多 1024 个主机名",
+        "expands to too many names, at most 1024 hosts",
+    ),
+    (
+        "* 和 ? 无法枚举，请使用 {01..20}、{a,b} 或 [1-5]",
+        "cannot enumerate * or ?, use {01..20}, {a,b} or [1-5]",
+    ),
+    (
+        "{} 内需要范围（如 {01..20}）或逗号分隔的列表",
+        "expects a range like {01..20} or a comma-separated list inside {}",
+    ),
+    (
+        "范围格式不正确，例如 {01..20} 或 {a..f}",
+        "has an invalid range, e.g. {01..20} or {a..f}",
+    ),
+    (
+        "字符范围需要从小到大，例如 [1-5]",
+        "expects an ascending character range, e.g. [1-5]",
+    ),
+    (
+        "[] 内只能是字母、数字或范围，例如 [1-5]",
+        "only allows letters, digits or ranges inside [], e.g. [1-5]",
+    ),
+];
+
+/// 翻译错误中的中文标签；`端口扫描 (Linux ss)` 这类带括号说明的只翻译前半部分，
+/// 中文或表中没有的文本原样返回
+pub fn term(lang: Lang, text: &str) -> Cow<'_, str> {
+    if lang == Lang::Zh {
+        return Cow::Borrowed(text);
+    }
+    let lookup = |label: &str| TERMS.iter().find(|(zh, _)| *zh == label).map(|(_, en)| *en);
+    if let Some(en) = lookup(text) {
+        return Cow::Borrowed(en);
+    }
+    match text.split_once(" (") {
+        Some((label, rest)) => match lookup(label) {
+            Some(en) => Cow::Owned(format!("{en} ({rest}")),
+            None => Cow::Borrowed(text),
+        },
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// 取消息文本
+pub fn tr(lang: Lang, msg: Msg) -> &'static str {
+    msg.text(lang)
+}
+
+/// 取消息文本并按顺序填充 `{}` 占位符
+pub fn tr_args(lang: Lang, msg: Msg, args: &[&dyn Display]) -> String {
+    let mut parts = msg.text(lang).split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            result.push_str(&arg.to_string());
+        }
+        result.push_str(part);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_from_name() {
+        assert_eq!(Lang::from_name("zh"), Some(Lang::Zh));
+        assert_eq!(Lang::from_name("zh_CN.UTF-8"), Some(Lang::Zh));
+        assert_eq!(Lang::from_name("en-US"), Some(Lang::En));
+        assert_eq!(Lang::from_name("C.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_name("fr"), None);
+    }
+
+    #[test]
+    fn test_messages_have_matching_placeholders() {
+        for msg in Msg::ALL {
+            let zh = msg.text(Lang::Zh);
+            let en = msg.text(Lang::En);
+            assert!(!en.is_empty() && !zh.is_empty(), "{:?}", msg);
+            assert_eq!(
+                zh.matches("{}").count(),
+                en.matches("{}").count(),
+                "{:?}",
+                msg
+            );
+        }
+        assert_eq!(
+            tr_args(Lang::En, Msg::TableSummary, &[&2, &5]),
+            "2 apps | 5 ports"
+        );
+        assert_eq!(tr(Lang::Zh, Msg::FreeCancelled), "已取消");
+    }
+
+    #[test]
+    fn test_term_translates_known_labels() {
+        assert_eq!(term(Lang::En, "目标地址"), "target");
+        assert_eq!(term(Lang::En, "不能为空"), "must not be empty");
+        assert_eq!(term(Lang::Zh, "目标地址"), "目标地址");
+        assert_eq!(term(Lang::En, "10.0.0.1"), "10.0.0.1");
+        assert_eq!(
+            term(Lang::En, "端口扫描 (Linux ss)"),
+            "port scan (Linux ss)"
+        );
+        assert!(TERMS
+            .iter()
+            .all(|(zh, en)| !zh.is_empty() && !en.is_empty()));
+    }
+}
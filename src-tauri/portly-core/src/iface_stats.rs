@@ -0,0 +1,592 @@
+//! 网卡统计与链路信息
+//!
+//! 读取各网卡的启用状态、链路速率、MTU 和收发字节 / 包 / 错误计数：Linux 来自 `/proc/net/dev` 和
+//! `/sys/class/net`，macOS 来自 `netstat -ibn` 和 `ifconfig -a`，Windows 与接口列表一样通过 IP Helper
+//! 读取（`GetIfTable`）。计数器是开机以来的累计值，[`InterfaceSampler`] 按固定间隔采样，用相邻两次的
+//! 差值算出每秒吞吐量和新增的错误数，GUI 以 `interface-stats` 事件推送给网络页。跳过回环接口
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::CommandRunner;
+use crate::network::{classify_interface_name, InterfaceKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// 默认的采样间隔（秒）
+pub const DEFAULT_INTERVAL_SECS: u64 = 2;
+
+/// 采样间隔的范围（秒）
+const INTERVAL_RANGE: RangeInclusive<u64> = 1..=60;
+
+/// 等待下一次采样时检查停止请求的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 开机以来的累计收发计数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+}
+
+/// 一块网卡的链路信息和计数器
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub name: String,
+    #[serde(default)]
+    pub kind: InterfaceKind,
+    pub is_up: bool,
+    pub mtu: Option<u32>,
+    /// 链路速率（Mbit/s）；无线网卡、VPN 和未连接的网卡通常读不到
+    pub speed_mbps: Option<u64>,
+    #[serde(flatten)]
+    pub counters: InterfaceCounters,
+}
+
+/// 一块网卡在一次采样中的吞吐量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceRate {
+    #[serde(flatten)]
+    pub stats: InterfaceStats,
+    /// 每秒接收 / 发送的字节数；第一次采样、新出现的网卡或计数器回绕 / 重置时为空
+    pub rx_bytes_per_sec: Option<f64>,
+    pub tx_bytes_per_sec: Option<f64>,
+    /// 与上一次采样相比新增的收发错误
+    pub new_errors: u64,
+    /// 收发中较大的一方占链路速率的百分比，读不到链路速率时为空
+    pub utilization_percent: Option<f32>,
+}
+
+/// 一次采样的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceSample {
+    pub sampled_at: String,
+    /// 距上一次采样的毫秒数，第一次采样为 0
+    pub elapsed_ms: u64,
+    pub interfaces: Vec<InterfaceRate>,
+}
+
+/// 跨采样保存上一次的计数器
+#[derive(Debug, Default)]
+pub struct InterfaceSampler {
+    previous: Option<(Instant, HashMap<String, InterfaceCounters>)>,
+}
+
+impl InterfaceSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录 `at` 时刻的计数器，按与上一次采样的差值计算吞吐量
+    pub fn observe(&mut self, stats: Vec<InterfaceStats>, at: Instant) -> InterfaceSample {
+        let elapsed = self
+            .previous
+            .as_ref()
+            .map(|(previous_at, _)| at.saturating_duration_since(*previous_at))
+            .unwrap_or_default();
+        let secs = elapsed.as_secs_f64();
+        let previous = self.previous.as_ref().map(|(_, counters)| counters);
+        let interfaces = stats
+            .into_iter()
+            .map(|stats| {
+                let before = previous.and_then(|counters| counters.get(&stats.name));
+                let per_sec = |now: u64, before: Option<u64>| {
+                    let delta = now.checked_sub(before?)?;
+                    (secs > 0.0).then(|| delta as f64 / secs)
+                };
+                let rx_bytes_per_sec = per_sec(stats.counters.rx_bytes, before.map(|c| c.rx_bytes));
+                let tx_bytes_per_sec = per_sec(stats.counters.tx_bytes, before.map(|c| c.tx_bytes));
+                let new_errors = before.map_or(0, |c| {
+                    stats.counters.rx_errors.saturating_sub(c.rx_errors)
+                        + stats.counters.tx_errors.saturating_sub(c.tx_errors)
+                });
+                let utilization_percent = stats
+                    .speed_mbps
+                    .filter(|speed| *speed > 0)
+                    .zip(rx_bytes_per_sec.zip(tx_bytes_per_sec))
+                    .map(|(speed, (rx, tx))| {
+                        (rx.max(tx) * 8.0 / (speed as f64 * 1_000_000.0) * 100.0) as f32
+                    });
+                InterfaceRate {
+                    stats,
+                    rx_bytes_per_sec,
+                    tx_bytes_per_sec,
+                    new_errors,
+                    utilization_percent,
+                }
+            })
+            .collect::<Vec<_>>();
+        self.previous = Some((
+            at,
+            interfaces
+                .iter()
+                .map(|rate| (rate.stats.name.clone(), rate.stats.counters))
+                .collect(),
+        ));
+        InterfaceSample {
+            sampled_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            interfaces,
+        }
+    }
+}
+
+/// 校验采样间隔（秒）
+pub fn interval(secs: u64) -> AppResult<Duration> {
+    if !INTERVAL_RANGE.contains(&secs) {
+        return Err(AppError::validation(
+            "网卡采样间隔",
+            format!(
+                "应在 {}-{} 秒之间",
+                INTERVAL_RANGE.start(),
+                INTERVAL_RANGE.end()
+            ),
+        ));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// 读取各网卡的链路信息和计数器：已启用的在前，再按接口类型和名称排列
+pub fn interface_stats() -> AppResult<Vec<InterfaceStats>> {
+    if crate::demo::is_enabled() {
+        return Ok(crate::demo::interface_stats());
+    }
+    let mut stats = system_interface_stats()?;
+    // 接口列表能区分 Wi-Fi 和有线网卡，按名称取其中的类型
+    let interfaces = crate::network::get_local_interfaces();
+    for stat in &mut stats {
+        if let Some(iface) = interfaces.iter().find(|i| i.name == stat.name) {
+            stat.kind = iface.kind;
+        }
+    }
+    stats.sort_by(|a, b| (!a.is_up, a.kind, &a.name).cmp(&(!b.is_up, b.kind, &b.name)));
+    Ok(stats)
+}
+
+/// 按间隔采样网卡计数器，每次采样后调用 `on_sample`，阻塞直到 `stop` 被置位
+///
+/// 读取失败的一次跳过，不更新基线
+pub fn watch_interfaces<F>(stop: &AtomicBool, interval: Duration, mut on_sample: F)
+where
+    F: FnMut(&InterfaceSample),
+{
+    tracing::info!(interval_secs = interval.as_secs(), "网卡流量采样已启动");
+    let mut sampler = InterfaceSampler::new();
+    while !stop.load(Ordering::Relaxed) {
+        let started = Instant::now();
+        match interface_stats() {
+            Ok(stats) => on_sample(&sampler.observe(stats, started)),
+            Err(err) => tracing::warn!(error = %err, "网卡流量采样读取计数器失败"),
+        }
+
+        while started.elapsed() < interval && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(STOP_POLL_INTERVAL.min(interval.saturating_sub(started.elapsed())));
+        }
+    }
+    tracing::info!("网卡流量采样已停止");
+}
+
+fn is_loopback(name: &str) -> bool {
+    name.strip_prefix("lo")
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(target_os = "linux")]
+fn system_interface_stats() -> AppResult<Vec<InterfaceStats>> {
+    let text = std::fs::read_to_string("/proc/net/dev")
+        .map_err(|e| AppError::internal(format!("读取 /proc/net/dev 失败: {}", e)))?;
+    let mut stats = parse_proc_net_dev(&text);
+    for stat in &mut stats {
+        let sys = std::path::Path::new("/sys/class/net").join(&stat.name);
+        let read = |file: &str| {
+            std::fs::read_to_string(sys.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+        stat.mtu = read("mtu").and_then(|mtu| mtu.parse().ok());
+        // 未连接或驱动不报告时为 -1，部分虚拟网卡读取会直接报错
+        stat.speed_mbps = read("speed")
+            .and_then(|speed| speed.parse::<i64>().ok())
+            .filter(|speed| *speed > 0)
+            .map(|speed| speed as u64);
+        // tun / WireGuard 的 operstate 为 unknown，此时看 IFF_UP 标志
+        let flags = read("flags")
+            .and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok());
+        stat.is_up = match read("operstate").as_deref() {
+            Some("up") => true,
+            Some("unknown") => flags.is_some_and(|flags| flags & 0x1 != 0),
+            _ => false,
+        };
+    }
+    Ok(stats)
+}
+
+#[cfg(target_os = "macos")]
+fn system_interface_stats() -> AppResult<Vec<InterfaceStats>> {
+    macos_interface_stats_with(&crate::command_exec::SystemRunner)
+}
+
+#[cfg(windows)]
+fn system_interface_stats() -> AppResult<Vec<InterfaceStats>> {
+    let (table, names) = crate::network::windows_if_table()
+        .map_err(|code| AppError::internal(format!("GetIfTable 调用失败: {}", code)))?;
+    Ok(parse_if_table(&table, &names))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn system_interface_stats() -> AppResult<Vec<InterfaceStats>> {
+    Ok(Vec::new())
+}
+
+/// 解析 Linux `/proc/net/dev`：`eth0: <8 个接收计数> <8 个发送计数>`，接收依次为字节、包、错误，
+/// 发送同样。MTU、速率和状态由调用方从 `/sys/class/net` 补全
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_net_dev(text: &str) -> Vec<InterfaceStats> {
+    text.lines()
+        .filter_map(|line| {
+            let (name, values) = line.split_once(':')?;
+            let name = name.trim();
+            if name.contains('|') || is_loopback(name) {
+                return None;
+            }
+            let values: Vec<u64> = values
+                .split_whitespace()
+                .map(|value| value.parse().ok())
+                .collect::<Option<_>>()?;
+            if values.len() < 16 {
+                return None;
+            }
+            Some(InterfaceStats {
+                name: name.to_string(),
+                kind: classify_interface_name(name),
+                is_up: false,
+                mtu: None,
+                speed_mbps: None,
+                counters: InterfaceCounters {
+                    rx_bytes: values[0],
+                    rx_packets: values[1],
+                    rx_errors: values[2],
+                    tx_bytes: values[8],
+                    tx_packets: values[9],
+                    tx_errors: values[10],
+                },
+            })
+        })
+        .collect()
+}
+
+/// 用指定的命令执行器读取 macOS 网卡统计：计数器和 MTU 来自 `netstat -ibn`，链路速率和状态来自
+/// `ifconfig -a`；`ifconfig` 失败时只返回计数器
+pub fn macos_interface_stats_with(runner: &dyn CommandRunner) -> AppResult<Vec<InterfaceStats>> {
+    let output = runner.run("netstat", "网卡统计读取", &mut |cmd| {
+        cmd.arg("-ibn");
+    })?;
+    if output.status != 0 {
+        return Err(AppError::command_failed(
+            "netstat",
+            "网卡统计读取",
+            output.status,
+            output.stderr,
+        ));
+    }
+    let mut stats = parse_netstat_ibn(&output.stdout);
+    let links = match runner.run("ifconfig", "网卡统计读取", &mut |cmd| {
+        cmd.arg("-a");
+    }) {
+        Ok(output) if output.status == 0 => parse_ifconfig_links(&output.stdout),
+        Ok(_) | Err(_) => {
+            tracing::debug!("ifconfig 不可用，网卡统计缺少链路速率");
+            HashMap::new()
+        }
+    };
+    for stat in &mut stats {
+        if let Some(link) = links.get(&stat.name) {
+            stat.is_up = link.is_up;
+            stat.speed_mbps = link.speed_mbps;
+            stat.mtu = stat.mtu.or(link.mtu);
+        }
+    }
+    Ok(stats)
+}
+
+/// 解析 `netstat -ibn` 中 Network 列为 `<Link#N>` 的行；Address 列可能为空，计数从行尾往前取
+/// （Ipkts Ierrs Ibytes Opkts Oerrs Obytes Coll）。名称后的 `*` 表示未启用
+fn parse_netstat_ibn(stdout: &str) -> Vec<InterfaceStats> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if !parts.get(2)?.starts_with("<Link#") || parts.len() < 10 {
+                return None;
+            }
+            let name = parts[0].trim_end_matches('*');
+            if is_loopback(name) {
+                return None;
+            }
+            let count = |from_end: usize| parts[parts.len() - from_end].parse().unwrap_or(0);
+            Some(InterfaceStats {
+                name: name.to_string(),
+                kind: classify_interface_name(name),
+                is_up: !parts[0].ends_with('*'),
+                mtu: parts[1].parse().ok(),
+                speed_mbps: None,
+                counters: InterfaceCounters {
+                    rx_packets: count(7),
+                    rx_errors: count(6),
+                    rx_bytes: count(5),
+                    tx_packets: count(4),
+                    tx_errors: count(3),
+                    tx_bytes: count(2),
+                },
+            })
+        })
+        .collect()
+}
+
+/// `ifconfig` 中一块网卡的链路信息
+#[derive(Debug, Default, PartialEq, Eq)]
+struct LinkInfo {
+    is_up: bool,
+    mtu: Option<u32>,
+    speed_mbps: Option<u64>,
+}
+
+/// 解析 macOS / BSD `ifconfig -a`：首行的 UP 标志和 `mtu`，`media:` 括号中的速率（如
+/// `1000baseT`、`10Gbase-T`），`status: inactive` 视为未连接
+fn parse_ifconfig_links(text: &str) -> HashMap<String, LinkInfo> {
+    let mut links: HashMap<String, LinkInfo> = HashMap::new();
+    let mut current = None;
+    for line in text.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            let Some((name, rest)) = line.split_once(": ") else {
+                continue;
+            };
+            let flags = rest
+                .split_once('<')
+                .and_then(|(_, flags)| flags.split_once('>'))
+                .map(|(flags, _)| flags)
+                .unwrap_or_default();
+            let mtu = rest
+                .split_once("mtu ")
+                .and_then(|(_, mtu)| mtu.split_whitespace().next()?.parse().ok());
+            links.insert(
+                name.to_string(),
+                LinkInfo {
+                    is_up: flags.split(',').any(|flag| flag == "UP"),
+                    mtu,
+                    speed_mbps: None,
+                },
+            );
+            current = Some(name.to_string());
+            continue;
+        }
+        let Some(link) = current.as_ref().and_then(|name| links.get_mut(name)) else {
+            continue;
+        };
+        let line = line.trim();
+        if let Some(media) = line.strip_prefix("media:") {
+            link.speed_mbps = media
+                .split_once('(')
+                .and_then(|(_, media)| parse_media_speed(media));
+        } else if line == "status: inactive" {
+            link.is_up = false;
+        }
+    }
+    links
+}
+
+/// `1000baseT` → 1000，`10Gbase-T` → 10000，`2500Base-T` → 2500
+fn parse_media_speed(media: &str) -> Option<u64> {
+    let digits: String = media.chars().take_while(char::is_ascii_digit).collect();
+    let speed: u64 = digits.parse().ok()?;
+    let unit = media[digits.len()..].to_ascii_lowercase();
+    if unit.starts_with("gbase") {
+        Some(speed * 1000)
+    } else if unit.starts_with("base") {
+        Some(speed)
+    } else {
+        None
+    }
+}
+
+/// 解析 `GetIfTable` 返回的 MIB_IFTABLE：4 字节条目数之后是 860 字节的 MIB_IFROW（512 字节
+/// 接口名、索引、类型、MTU、以 bit/s 计的速率……、字节计数、错误计数、256 字节描述）。
+/// 同一适配器的 WFP / QoS 过滤层也会作为条目出现，能取到友好名称时只保留 `names` 中的索引
+#[cfg(any(windows, test))]
+fn parse_if_table(table: &[u8], names: &HashMap<u32, String>) -> Vec<InterfaceStats> {
+    const ROW_SIZE: usize = 860;
+    const IF_TYPE_SOFTWARE_LOOPBACK: u32 = 24;
+    const IF_OPER_STATUS_OPERATIONAL: u32 = 5;
+
+    let field = |bytes: &[u8], offset: usize| {
+        u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    };
+    if table.len() < 4 {
+        return Vec::new();
+    }
+    table[4..]
+        .chunks_exact(ROW_SIZE)
+        .take(field(table, 0) as usize)
+        .filter_map(|row| {
+            let index = field(row, 512);
+            let if_type = field(row, 516);
+            if if_type == IF_TYPE_SOFTWARE_LOOPBACK
+                || (!names.is_empty() && !names.contains_key(&index))
+            {
+                return None;
+            }
+            let description_len = (field(row, 600) as usize).min(256);
+            let description = String::from_utf8_lossy(&row[604..604 + description_len])
+                .trim_end_matches('\0')
+                .to_string();
+            let speed = field(row, 524) as u64 / 1_000_000;
+            Some(InterfaceStats {
+                name: names
+                    .get(&index)
+                    .cloned()
+                    .unwrap_or_else(|| description.clone()),
+                kind: crate::network::classify_windows_adapter(if_type, &description),
+                is_up: field(row, 544) == IF_OPER_STATUS_OPERATIONAL,
+                mtu: Some(field(row, 520)),
+                speed_mbps: (speed > 0).then_some(speed),
+                counters: InterfaceCounters {
+                    rx_bytes: field(row, 552) as u64,
+                    rx_packets: field(row, 556) as u64 + field(row, 560) as u64,
+                    rx_errors: field(row, 568) as u64,
+                    tx_bytes: field(row, 576) as u64,
+                    tx_packets: field(row, 580) as u64 + field(row, 584) as u64,
+                    tx_errors: field(row, 592) as u64,
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+
+    #[test]
+    fn test_parse_platform_counters_and_link_info() {
+        let linux = parse_proc_net_dev(include_str!("../fixtures/linux/proc_net_dev.txt"));
+        let names: Vec<&str> = linux.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["eth0", "wg0", "docker0"]);
+        assert_eq!(linux[0].counters.rx_bytes, 9_876_543_210);
+        assert_eq!(linux[0].counters.rx_errors, 3);
+        assert_eq!(linux[0].counters.tx_packets, 4_102_233);
+        assert_eq!(linux[1].counters.tx_errors, 5);
+        assert_eq!(linux[1].kind, InterfaceKind::Vpn);
+
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                "netstat -ibn",
+                include_str!("../fixtures/macos/netstat_ibn.txt"),
+            )
+            .with_stdout(
+                "ifconfig -a",
+                include_str!("../fixtures/macos/ifconfig.txt"),
+            );
+        let macos = macos_interface_stats_with(&runner).unwrap();
+        let names: Vec<&str> = macos.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["gif0", "en0", "en1", "en8", "utun4"]);
+        assert!(!macos[0].is_up);
+        assert_eq!(macos[1].counters, linux[0].counters);
+        assert_eq!(macos[1].speed_mbps, Some(1000));
+        assert!(macos[1].is_up);
+        assert!(!macos[2].is_up);
+        assert_eq!(macos[2].speed_mbps, None);
+        assert_eq!(macos[3].mtu, Some(9000));
+        assert_eq!(macos[3].speed_mbps, Some(10_000));
+        assert_eq!(macos[4].counters.tx_errors, 5);
+        assert!(macos[4].is_up);
+
+        let runner = FixtureRunner::new().with_output("netstat -ibn", 1, "", "boom");
+        assert!(macos_interface_stats_with(&runner).is_err());
+
+        let row = |index: u32, if_type: u32, status: u32, rx: u32, description: &str| {
+            let mut row = vec![0u8; 860];
+            let mut set = |offset: usize, value: u32| {
+                row[offset..offset + 4].copy_from_slice(&value.to_ne_bytes())
+            };
+            set(512, index);
+            set(516, if_type);
+            set(520, 1500);
+            set(524, 1_000_000_000);
+            set(544, status);
+            set(552, rx);
+            set(568, 2);
+            set(576, 4096);
+            set(600, description.len() as u32);
+            row[604..604 + description.len()].copy_from_slice(description.as_bytes());
+            row
+        };
+        let mut table = 3u32.to_ne_bytes().to_vec();
+        table.extend(row(7, 6, 5, 123_456, "Intel(R) Ethernet Connection I219-V"));
+        table.extend(row(
+            8,
+            6,
+            5,
+            99,
+            "Intel(R) Ethernet-WFP Native MAC Layer LightWeight Filter",
+        ));
+        table.extend(row(1, 24, 5, 0, "Software Loopback Interface 1"));
+        let names = HashMap::from([(7, "以太网".to_string())]);
+        let windows = parse_if_table(&table, &names);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].name, "以太网");
+        assert_eq!(windows[0].kind, InterfaceKind::Ethernet);
+        assert_eq!(windows[0].speed_mbps, Some(1000));
+        assert_eq!(windows[0].counters.rx_bytes, 123_456);
+        assert_eq!(windows[0].counters.rx_errors, 2);
+        // 取不到友好名称时用描述，只跳过回环
+        assert_eq!(parse_if_table(&table, &HashMap::new()).len(), 2);
+    }
+
+    #[test]
+    fn test_sampler_rates_and_interval() {
+        let stats = |rx_bytes: u64, tx_bytes: u64, rx_errors: u64| {
+            vec![InterfaceStats {
+                name: "eth0".to_string(),
+                kind: InterfaceKind::Ethernet,
+                is_up: true,
+                mtu: Some(1500),
+                speed_mbps: Some(100),
+                counters: InterfaceCounters {
+                    rx_bytes,
+                    tx_bytes,
+                    rx_errors,
+                    ..Default::default()
+                },
+            }]
+        };
+        let start = Instant::now();
+        let mut sampler = InterfaceSampler::new();
+        let first = sampler.observe(stats(1_000, 500, 1), start);
+        assert_eq!(first.elapsed_ms, 0);
+        assert_eq!(first.interfaces[0].rx_bytes_per_sec, None);
+        assert_eq!(first.interfaces[0].new_errors, 0);
+
+        let second = sampler.observe(stats(2_501_000, 100_500, 3), start + Duration::from_secs(2));
+        let eth0 = &second.interfaces[0];
+        assert_eq!(second.elapsed_ms, 2000);
+        assert_eq!(eth0.rx_bytes_per_sec, Some(1_250_000.0));
+        assert_eq!(eth0.tx_bytes_per_sec, Some(50_000.0));
+        assert_eq!(eth0.new_errors, 2);
+        // 1.25 MB/s = 10 Mbit/s，占 100 Mbit/s 链路的 10%
+        assert_eq!(eth0.utilization_percent, Some(10.0));
+
+        // 计数器重置（如网卡重新插拔）时不给出吞吐量
+        let reset = sampler.observe(stats(10, 10, 0), start + Duration::from_secs(4));
+        assert_eq!(reset.interfaces[0].rx_bytes_per_sec, None);
+
+        assert_eq!(interval(2).unwrap(), Duration::from_secs(2));
+        assert!(interval(0).is_err());
+        assert!(interval(61).is_err());
+    }
+}
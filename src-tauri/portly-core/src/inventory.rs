@@ -0,0 +1,317 @@
+//! 目标标签与备注
+//!
+//! 用户给远程主机和局域网设备打的标签（如 `prod-db`、`kids-tablet`）和备注保存在数据目录下的
+//! `inventory.json`，按 IP、主机名或 MAC 地址记录。设备发现的结果按 MAC、IP、主机名依次匹配，
+//! 命中的标签合并后附在设备上，导出和各网络视图的标签过滤都使用这些标签。用 MAC 记录的设备
+//! 在 DHCP 换了地址后仍能对上
+
+use crate::app_error::{AppError, AppResult};
+use crate::network::NetworkDevice;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// 当前生效的清单，首次读取时从磁盘加载
+static CURRENT: RwLock<Option<Vec<InventoryEntry>>> = RwLock::new(None);
+
+/// 单个目标最多的标签数
+const MAX_TAGS: usize = 16;
+
+/// 标签的最大长度
+const MAX_TAG_LEN: usize = 32;
+
+/// 备注的最大长度（字符）
+const MAX_NOTE_LEN: usize = 500;
+
+/// 一个目标的标签和备注
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    /// IP、主机名或 MAC 地址，保存时转为小写
+    pub target: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+impl InventoryEntry {
+    pub(crate) fn validate(&self) -> AppResult<()> {
+        let target = self.target.trim();
+        if target.is_empty() || target.len() > 253 || target.contains(char::is_whitespace) {
+            return Err(AppError::validation(
+                "标记目标",
+                "需要 IP、主机名或 MAC 地址",
+            ));
+        }
+        if self.tags.len() > MAX_TAGS {
+            return Err(AppError::validation(
+                "标签",
+                format!("每个目标最多 {} 个", MAX_TAGS),
+            ));
+        }
+        for tag in &self.tags {
+            let valid_char = |c: char| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '/');
+            if tag.is_empty() || tag.chars().count() > MAX_TAG_LEN || !tag.chars().all(valid_char) {
+                return Err(AppError::validation(
+                    "标签",
+                    format!(
+                        "“{}” 无效：最长 {} 个字符，只能包含字母、数字和 - _ . /",
+                        tag, MAX_TAG_LEN
+                    ),
+                ));
+            }
+        }
+        if self
+            .note
+            .as_ref()
+            .is_some_and(|note| note.chars().count() > MAX_NOTE_LEN)
+        {
+            return Err(AppError::validation(
+                "备注",
+                format!("最长 {} 个字符", MAX_NOTE_LEN),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 目标匹配到的标签和备注
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetAnnotation {
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+/// 清单文件路径
+pub fn inventory_path() -> PathBuf {
+    crate::export::get_data_dir().join("inventory.json")
+}
+
+/// 读取清单文件；文件不存在时返回空列表
+pub fn load_inventory() -> AppResult<Vec<InventoryEntry>> {
+    let path = inventory_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::internal(format!("读取资产清单失败: {}", e)))?;
+    serde_json::from_str(&content).map_err(|e| AppError::validation("资产清单文件", e.to_string()))
+}
+
+/// 校验并保存清单，成功后立即生效
+pub fn save_inventory(entries: &[InventoryEntry]) -> AppResult<()> {
+    for entry in entries {
+        entry.validate()?;
+    }
+    let path = inventory_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建资产清单目录失败: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| AppError::internal(format!("序列化资产清单失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入资产清单失败: {}", e)))?;
+    install(entries.to_vec());
+    tracing::info!(path = %path.display(), count = entries.len(), "资产清单已保存");
+    Ok(())
+}
+
+/// 当前生效的清单；文件无法读取时记录日志并视为空清单
+pub fn current() -> Vec<InventoryEntry> {
+    if let Some(entries) = CURRENT.read().ok().and_then(|guard| guard.clone()) {
+        return entries;
+    }
+    let entries = load_inventory().unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "读取资产清单失败");
+        Vec::new()
+    });
+    install(entries.clone());
+    entries
+}
+
+/// 设置目标的标签和备注（整体替换），返回保存后的清单；标签和备注都为空时删除该目标
+pub fn set_target(
+    target: &str,
+    tags: &[String],
+    note: Option<&str>,
+) -> AppResult<Vec<InventoryEntry>> {
+    let entry = InventoryEntry {
+        target: normalize_target(target),
+        tags: normalize_tags(tags),
+        note: note
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .map(String::from),
+        updated_at: chrono::Local::now().to_rfc3339(),
+    };
+    entry.validate()?;
+    let mut entries = load_inventory()?;
+    entries.retain(|e| e.target != entry.target);
+    if !entry.tags.is_empty() || entry.note.is_some() {
+        entries.push(entry);
+    }
+    save_inventory(&entries)?;
+    Ok(entries)
+}
+
+/// 删除目标的全部标签和备注
+pub fn remove_target(target: &str) -> AppResult<Vec<InventoryEntry>> {
+    let target = normalize_target(target);
+    let mut entries = load_inventory()?;
+    let before = entries.len();
+    entries.retain(|e| e.target != target);
+    if entries.len() == before {
+        return Err(AppError::validation(
+            "标记目标",
+            format!("不在资产清单中：{target}"),
+        ));
+    }
+    save_inventory(&entries)?;
+    Ok(entries)
+}
+
+/// 按 IP、主机名或 MAC 查找目标的标签和备注
+pub fn lookup(keys: &[&str]) -> TargetAnnotation {
+    annotation(&current(), keys)
+}
+
+/// 给发现的设备附上清单中的标签和备注
+pub fn annotate_devices(devices: &mut [NetworkDevice]) {
+    let entries = current();
+    if entries.is_empty() {
+        return;
+    }
+    for device in devices {
+        let keys: Vec<&str> = [
+            device.mac.as_deref(),
+            Some(&device.ip),
+            device.hostname.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let annotation = annotation(&entries, &keys);
+        device.tags = annotation.tags;
+        device.note = annotation.note;
+    }
+}
+
+/// 标签过滤，忽略大小写
+pub fn has_tag(tags: &[String], tag: &str) -> bool {
+    tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim()))
+}
+
+/// 按顺序匹配各个键：标签取并集，备注取第一个有备注的条目
+fn annotation(entries: &[InventoryEntry], keys: &[&str]) -> TargetAnnotation {
+    let mut result = TargetAnnotation::default();
+    for key in keys {
+        let key = normalize_target(key);
+        let Some(entry) = entries.iter().find(|e| e.target == key) else {
+            continue;
+        };
+        for tag in &entry.tags {
+            if !result.tags.contains(tag) {
+                result.tags.push(tag.clone());
+            }
+        }
+        if result.note.is_none() {
+            result.note = entry.note.clone();
+        }
+    }
+    result
+}
+
+/// 小写并去掉 IPv6 方括号；MAC 地址统一用冒号分隔
+fn normalize_target(target: &str) -> String {
+    let target = target
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_lowercase();
+    let is_mac = target.len() == 17
+        && target
+            .split(['-', ':'])
+            .all(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_hexdigit()));
+    if is_mac {
+        target.replace('-', ":")
+    } else {
+        target
+    }
+}
+
+/// 小写、去空白、去重，保持输入顺序
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+fn install(entries: Vec<InventoryEntry>) {
+    if let Ok(mut guard) = CURRENT.write() {
+        *guard = Some(entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(target: &str, tags: &[&str], note: Option<&str>) -> InventoryEntry {
+        InventoryEntry {
+            target: target.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            note: note.map(String::from),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_annotation_merges_mac_ip_and_hostname() {
+        let entries = vec![
+            entry("aa:bb:cc:dd:ee:01", &["kids-tablet"], Some("客厅的 iPad")),
+            entry("192.168.1.40", &["kids-tablet", "iot"], Some("旧备注")),
+            entry("db1.lan", &["prod-db"], None),
+        ];
+        let tablet = annotation(&entries, &["AA-BB-CC-DD-EE-01", "192.168.1.40"]);
+        assert_eq!(tablet.tags, vec!["kids-tablet", "iot"]);
+        assert_eq!(tablet.note.as_deref(), Some("客厅的 iPad"));
+
+        assert_eq!(annotation(&entries, &["DB1.lan"]).tags, vec!["prod-db"]);
+        assert_eq!(
+            annotation(&entries, &["10.0.0.1"]),
+            TargetAnnotation::default()
+        );
+        assert_eq!(normalize_target("[FE80::1]"), "fe80::1");
+        assert!(has_tag(&["prod-db".to_string()], " PROD-DB"));
+    }
+
+    #[test]
+    fn test_validate_tags_and_note() {
+        assert!(entry("10.0.0.5", &["prod-db", "rack/2"], Some("主库"))
+            .validate()
+            .is_ok());
+        assert!(entry("", &["x"], None).validate().is_err());
+        assert!(entry("10.0.0.5", &["has space"], None).validate().is_err());
+        assert!(entry("10.0.0.5", &["a".repeat(33).as_str()], None)
+            .validate()
+            .is_err());
+        let note = "很".repeat(MAX_NOTE_LEN + 1);
+        assert!(entry("10.0.0.5", &[], Some(&note)).validate().is_err());
+        assert_eq!(
+            normalize_tags(&[
+                " Prod-DB ".to_string(),
+                "prod-db".to_string(),
+                String::new()
+            ]),
+            vec!["prod-db"]
+        );
+    }
+}
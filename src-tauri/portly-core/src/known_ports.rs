@@ -0,0 +1,265 @@
+//! 常用端口库
+//!
+//! 内置的常用端口及服务名称打包在 `data/common_ports.json`，与数据目录下用户维护的
+//! `ports.json` 合并：用户条目按端口覆盖内置条目（如把 8123 标为 Home Assistant、补充
+//! 公司内部服务），`disabled` 的条目从合并结果中移除对应端口。快速扫描的端口列表和
+//! 服务名称都取自合并结果
+
+use crate::app_error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+const BUILTIN_PORTS: &str = include_str!("../data/common_ports.json");
+
+/// 合并后的端口库，首次读取时加载
+static CURRENT: RwLock<Option<Vec<KnownPort>>> = RwLock::new(None);
+
+/// 端口库条目（内置数据与用户文件共用的格式）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortEntry {
+    pub port: u16,
+    #[serde(default)]
+    pub name: String,
+    /// 分组，如 database、web、dev
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// 仅用于用户文件：从端口库中移除该端口
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+impl PortEntry {
+    pub(crate) fn validate(&self) -> AppResult<()> {
+        if self.port == 0 {
+            return Err(AppError::validation("端口", "必须大于 0"));
+        }
+        if !self.disabled && self.name.trim().is_empty() {
+            return Err(AppError::validation("服务名称", "不能为空"));
+        }
+        Ok(())
+    }
+}
+
+/// 条目来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortSource {
+    Builtin,
+    User,
+}
+
+/// 合并后的端口库条目
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownPort {
+    pub port: u16,
+    pub name: String,
+    pub category: Option<String>,
+    pub source: PortSource,
+}
+
+/// 内置端口库
+pub fn builtin() -> Vec<PortEntry> {
+    serde_json::from_str(BUILTIN_PORTS).expect("内置端口库格式错误")
+}
+
+/// 用户端口文件路径
+pub fn user_ports_path() -> PathBuf {
+    crate::export::get_data_dir().join("ports.json")
+}
+
+/// 读取用户端口文件；文件不存在时返回空列表
+pub fn load_user_ports() -> AppResult<Vec<PortEntry>> {
+    let path = user_ports_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::internal(format!("读取端口文件失败: {}", e)))?;
+    serde_json::from_str(&content).map_err(|e| AppError::validation("端口文件", e.to_string()))
+}
+
+/// 校验并保存用户端口文件，成功后立即生效
+pub fn save_user_ports(entries: &[PortEntry]) -> AppResult<()> {
+    for entry in entries {
+        entry.validate()?;
+    }
+    let path = user_ports_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建端口目录失败: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| AppError::internal(format!("序列化端口文件失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入端口文件失败: {}", e)))?;
+    install(merge(&builtin(), entries));
+    tracing::info!(path = %path.display(), count = entries.len(), "端口库已保存");
+    Ok(())
+}
+
+/// 当前生效的端口库；用户文件无法读取时记录日志并只使用内置端口
+pub fn current() -> Vec<KnownPort> {
+    if let Some(ports) = CURRENT.read().ok().and_then(|guard| guard.clone()) {
+        return ports;
+    }
+    let user = load_user_ports().unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "读取端口文件失败");
+        Vec::new()
+    });
+    let ports = merge(&builtin(), &user);
+    install(ports.clone());
+    ports
+}
+
+/// 端口对应的服务名称
+pub fn service_name(port: u16) -> Option<String> {
+    let find = |ports: &[KnownPort]| {
+        ports
+            .iter()
+            .find(|p| p.port == port)
+            .map(|p| p.name.clone())
+    };
+    // 扫描时逐个端口查询，已加载时直接在锁内查找，避免复制整个端口库
+    if let Some(name) = CURRENT
+        .read()
+        .ok()
+        .and_then(|guard| guard.as_deref().map(find))
+    {
+        return name;
+    }
+    find(&current())
+}
+
+/// 端口库中的全部端口，内置端口在前
+pub fn common_ports() -> Vec<u16> {
+    current().iter().map(|p| p.port).collect()
+}
+
+/// 新增或按端口替换一条用户条目，返回保存后的端口库
+pub fn upsert_port(entry: PortEntry) -> AppResult<Vec<KnownPort>> {
+    entry.validate()?;
+    let mut entries = load_user_ports()?;
+    match entries.iter_mut().find(|e| e.port == entry.port) {
+        Some(existing) => *existing = entry,
+        None => entries.push(entry),
+    }
+    save_user_ports(&entries)?;
+    Ok(current())
+}
+
+/// 删除端口：有用户条目时删除该条目（内置端口恢复原名称），否则把内置端口标记为移除
+pub fn delete_port(port: u16) -> AppResult<Vec<KnownPort>> {
+    let mut entries = load_user_ports()?;
+    let before = entries.len();
+    entries.retain(|e| e.port != port);
+    if entries.len() == before {
+        if !builtin().iter().any(|e| e.port == port) {
+            return Err(AppError::validation(
+                "端口",
+                format!("不在端口库中：{port}"),
+            ));
+        }
+        entries.push(PortEntry {
+            port,
+            name: String::new(),
+            category: None,
+            disabled: true,
+        });
+    }
+    save_user_ports(&entries)?;
+    Ok(current())
+}
+
+fn install(ports: Vec<KnownPort>) {
+    if let Ok(mut guard) = CURRENT.write() {
+        *guard = Some(ports);
+    }
+}
+
+/// 合并内置与用户条目：覆盖的端口保持内置顺序，新增端口按用户文件顺序排在后面
+fn merge(builtin: &[PortEntry], user: &[PortEntry]) -> Vec<KnownPort> {
+    let known = |entry: &PortEntry, source| KnownPort {
+        port: entry.port,
+        name: entry.name.trim().to_string(),
+        category: entry.category.clone(),
+        source,
+    };
+    // 同一端口出现多次时以最后一条为准
+    let user_entry = |port: u16| user.iter().rev().find(|e| e.port == port);
+
+    let mut ports: Vec<KnownPort> = builtin
+        .iter()
+        .filter_map(|entry| match user_entry(entry.port) {
+            Some(user) if user.disabled => None,
+            Some(user) => Some(known(user, PortSource::User)),
+            None => Some(known(entry, PortSource::Builtin)),
+        })
+        .collect();
+    for entry in user {
+        if entry.disabled || ports.iter().any(|p| p.port == entry.port) {
+            continue;
+        }
+        if let Some(last) = user_entry(entry.port) {
+            ports.push(known(last, PortSource::User));
+        }
+    }
+    ports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(port: u16, name: &str) -> PortEntry {
+        PortEntry {
+            port,
+            name: name.to_string(),
+            category: None,
+            disabled: false,
+        }
+    }
+
+    #[test]
+    fn test_builtin_ports_are_unique() {
+        let ports = builtin();
+        assert!(ports.len() >= 60);
+        for (i, entry) in ports.iter().enumerate() {
+            assert!(entry.validate().is_ok());
+            assert!(ports[i + 1..].iter().all(|e| e.port != entry.port));
+        }
+    }
+
+    #[test]
+    fn test_merge_user_overrides() {
+        let builtin = vec![
+            entry(22, "SSH"),
+            entry(3000, "Node/Dev"),
+            entry(8080, "HTTP-Alt"),
+        ];
+        let user = vec![
+            entry(8123, "Home Assistant"),
+            entry(3000, "Grafana"),
+            PortEntry {
+                disabled: true,
+                ..entry(8080, "")
+            },
+            entry(8123, "HASS"),
+        ];
+        let merged = merge(&builtin, &user);
+        let names: Vec<(u16, &str, PortSource)> = merged
+            .iter()
+            .map(|p| (p.port, p.name.as_str(), p.source))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (22, "SSH", PortSource::Builtin),
+                (3000, "Grafana", PortSource::User),
+                (8123, "HASS", PortSource::User),
+            ]
+        );
+        assert!(entry(0, "x").validate().is_err());
+        assert!(entry(9999, " ").validate().is_err());
+    }
+}
@@ -0,0 +1,642 @@
+//! Kubernetes 端口集成模块
+//!
+//! 通过 `kubectl` 读取当前 context 下的 Service / NodePort / Pod 容器端口，
+//! 并把 NodePort 与本机监听端口关联（kind / minikube 等本地集群常见的"神秘端口"）；
+//! 同时识别 `kubectl port-forward` 创建的本地监听端口
+
+use crate::command_exec::run_command;
+use crate::core::{get_listening_ports_raw, get_process_commands, PortInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// kubectl 请求超时，避免集群不可达时长时间阻塞
+const KUBECTL_REQUEST_TIMEOUT: &str = "--request-timeout=5s";
+
+/// Service 端口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeServicePort {
+    pub name: Option<String>,
+    pub port: u16,
+    /// 目标端口（可能是数字或容器端口名）
+    pub target_port: String,
+    pub node_port: Option<u16>,
+    pub protocol: String,
+}
+
+/// Kubernetes Service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeService {
+    pub namespace: String,
+    pub name: String,
+    /// ClusterIP / NodePort / LoadBalancer / ExternalName
+    pub service_type: String,
+    pub cluster_ip: Option<String>,
+    pub ports: Vec<KubeServicePort>,
+}
+
+/// Pod 容器声明的端口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeContainerPort {
+    pub container: String,
+    pub name: Option<String>,
+    pub container_port: u16,
+    pub host_port: Option<u16>,
+    pub protocol: String,
+}
+
+/// Kubernetes Pod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubePod {
+    pub namespace: String,
+    pub name: String,
+    pub node: Option<String>,
+    pub pod_ip: Option<String>,
+    pub phase: String,
+    pub ports: Vec<KubeContainerPort>,
+}
+
+/// NodePort 与本机监听进程的对应关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodePortBinding {
+    pub node_port: u16,
+    pub protocol: String,
+    pub namespace: String,
+    pub service: String,
+    /// 本机监听该端口的进程（未监听时为空）
+    pub pid: Option<String>,
+    pub process: Option<String>,
+}
+
+/// 当前 context 的 Kubernetes 端口总览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KubeOverview {
+    pub available: bool,
+    pub context: Option<String>,
+    pub services: Vec<KubeService>,
+    pub pods: Vec<KubePod>,
+    pub node_ports: Vec<NodePortBinding>,
+}
+
+/// `kubectl port-forward` 会话（对应一个本地监听端口）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortForwardSession {
+    pub pid: String,
+    pub local_port: u16,
+    pub namespace: Option<String>,
+    pub kube_context: Option<String>,
+    /// 转发目标，如 `pod/web-7d9f`、`svc/web`
+    pub target: String,
+    pub remote_port: String,
+}
+
+/// 从 `kubectl port-forward` 命令行解析出的转发配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PortForwardCommand {
+    namespace: Option<String>,
+    kube_context: Option<String>,
+    target: String,
+    /// (本地端口, 远端端口)；`:5000` 形式的本地端口为随机分配
+    mappings: Vec<(Option<u16>, String)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KubeList<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct KubeMetadata {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    namespace: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawService {
+    #[serde(default)]
+    metadata: KubeMetadata,
+    #[serde(default)]
+    spec: RawServiceSpec,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawServiceSpec {
+    #[serde(rename = "type", default)]
+    service_type: Option<String>,
+    #[serde(rename = "clusterIP", default)]
+    cluster_ip: Option<String>,
+    #[serde(default)]
+    ports: Vec<RawServicePort>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawServicePort {
+    #[serde(default)]
+    name: Option<String>,
+    port: u16,
+    #[serde(default)]
+    target_port: Option<serde_json::Value>,
+    #[serde(default)]
+    node_port: Option<u16>,
+    #[serde(default)]
+    protocol: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPod {
+    #[serde(default)]
+    metadata: KubeMetadata,
+    #[serde(default)]
+    spec: RawPodSpec,
+    #[serde(default)]
+    status: RawPodStatus,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPodSpec {
+    #[serde(default)]
+    node_name: Option<String>,
+    #[serde(default)]
+    containers: Vec<RawContainer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawContainer {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    ports: Vec<RawContainerPort>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawContainerPort {
+    #[serde(default)]
+    name: Option<String>,
+    container_port: u16,
+    #[serde(default)]
+    host_port: Option<u16>,
+    #[serde(default)]
+    protocol: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawPodStatus {
+    #[serde(default)]
+    phase: Option<String>,
+    #[serde(rename = "podIP", default)]
+    pod_ip: Option<String>,
+}
+
+/// 检查 kubectl 是否可用
+pub fn is_kubectl_available() -> bool {
+    run_command("kubectl", "kubectl 可用性检测", |cmd| {
+        cmd.args(["version", "--client"]);
+    })
+    .map(|o| o.status == 0)
+    .unwrap_or(false)
+}
+
+/// 获取当前 kubectl context 名称
+pub fn get_kube_context() -> Option<String> {
+    let output = run_command("kubectl", "kubectl context 读取", |cmd| {
+        cmd.args(["config", "current-context"]);
+    })
+    .ok()?;
+    if output.status != 0 {
+        return None;
+    }
+    let context = output.stdout.trim();
+    (!context.is_empty()).then(|| context.to_string())
+}
+
+/// 运行 `kubectl get <resource> -A -o json`
+fn kubectl_get_json(resource: &str, context: &str) -> Option<String> {
+    let output = run_command("kubectl", context, |cmd| {
+        cmd.args([
+            "get",
+            resource,
+            "--all-namespaces",
+            "-o",
+            "json",
+            KUBECTL_REQUEST_TIMEOUT,
+        ]);
+    })
+    .ok()?;
+    (output.status == 0).then_some(output.stdout)
+}
+
+/// 获取所有命名空间的 Service
+pub fn get_kube_services() -> Vec<KubeService> {
+    kubectl_get_json("services", "Kubernetes Service 读取")
+        .and_then(|body| parse_kube_services(&body))
+        .unwrap_or_default()
+}
+
+/// 获取所有命名空间的 Pod
+pub fn get_kube_pods() -> Vec<KubePod> {
+    kubectl_get_json("pods", "Kubernetes Pod 读取")
+        .and_then(|body| parse_kube_pods(&body))
+        .unwrap_or_default()
+}
+
+/// 获取当前 context 的端口总览，并关联本机监听的 NodePort
+pub fn get_kube_overview() -> KubeOverview {
+    if !is_kubectl_available() {
+        return KubeOverview {
+            available: false,
+            context: None,
+            services: Vec::new(),
+            pods: Vec::new(),
+            node_ports: Vec::new(),
+        };
+    }
+
+    let context = get_kube_context();
+    let services = get_kube_services();
+    let pods = get_kube_pods();
+    let node_ports = correlate_node_ports(&services, &get_listening_ports_raw());
+
+    KubeOverview {
+        available: true,
+        context,
+        services,
+        pods,
+        node_ports,
+    }
+}
+
+/// 识别本机由 `kubectl port-forward` 创建的监听端口
+pub fn get_port_forward_sessions() -> Vec<PortForwardSession> {
+    let listeners: Vec<PortInfo> = get_listening_ports_raw()
+        .into_iter()
+        .filter(|p| p.process.to_lowercase().contains("kubectl"))
+        .collect();
+
+    let pids: Vec<&str> = listeners.iter().map(|p| p.pid.as_str()).collect();
+    let commands: HashMap<String, PortForwardCommand> = get_process_commands(&pids)
+        .into_iter()
+        .filter_map(|(pid, cmdline)| Some((pid, parse_port_forward_command(&cmdline)?)))
+        .collect();
+    let mut sessions = Vec::new();
+    for listener in &listeners {
+        if let Some(command) = commands.get(&listener.pid) {
+            if let Some(session) = match_port_forward(command, listener) {
+                if !sessions.iter().any(|s: &PortForwardSession| {
+                    s.pid == session.pid && s.local_port == session.local_port
+                }) {
+                    sessions.push(session);
+                }
+            }
+        }
+    }
+
+    sessions.sort_by_key(|s| s.local_port);
+    sessions
+}
+
+/// 将监听端口与转发配置中的端口映射对应起来
+fn match_port_forward(
+    command: &PortForwardCommand,
+    listener: &PortInfo,
+) -> Option<PortForwardSession> {
+    let remote_port = command
+        .mappings
+        .iter()
+        .find(|(local, _)| *local == Some(listener.port))
+        .or_else(|| {
+            // 随机本地端口无法从命令行得知，仅在唯一时可以确定
+            let random: Vec<_> = command
+                .mappings
+                .iter()
+                .filter(|(l, _)| l.is_none())
+                .collect();
+            (random.len() == 1).then(|| random[0])
+        })
+        .map(|(_, remote)| remote.clone())?;
+
+    Some(PortForwardSession {
+        pid: listener.pid.clone(),
+        local_port: listener.port,
+        namespace: command.namespace.clone(),
+        kube_context: command.kube_context.clone(),
+        target: command.target.clone(),
+        remote_port,
+    })
+}
+
+/// 解析 `kubectl port-forward` 命令行
+///
+/// 支持 `-n/--namespace`、`--context` 等参数出现在任意位置，
+/// 端口格式支持 `8080:80`、`80`、`:80`
+fn parse_port_forward_command(cmdline: &str) -> Option<PortForwardCommand> {
+    let mut tokens = cmdline.split_whitespace();
+    let program = tokens.next()?;
+    if !program
+        .rsplit(['/', '\\'])
+        .next()?
+        .to_lowercase()
+        .starts_with("kubectl")
+    {
+        return None;
+    }
+
+    // 带参数值的选项，值不能当作位置参数
+    const VALUE_FLAGS: &[&str] = &[
+        "--address",
+        "--pod-running-timeout",
+        "--kubeconfig",
+        "--cluster",
+        "--user",
+        "-s",
+        "--server",
+    ];
+
+    let mut namespace = None;
+    let mut kube_context = None;
+    let mut positionals = Vec::new();
+    while let Some(token) = tokens.next() {
+        if token == "-n" || token == "--namespace" {
+            namespace = tokens.next().map(String::from);
+        } else if let Some(value) = token.strip_prefix("--namespace=") {
+            namespace = Some(value.to_string());
+        } else if token == "--context" {
+            kube_context = tokens.next().map(String::from);
+        } else if let Some(value) = token.strip_prefix("--context=") {
+            kube_context = Some(value.to_string());
+        } else if VALUE_FLAGS.contains(&token) {
+            tokens.next();
+        } else if let Some(value) = token.strip_prefix("-n").filter(|v| !v.starts_with('-')) {
+            // -nfoo
+            if !value.is_empty() {
+                namespace = Some(value.to_string());
+            }
+        } else if !token.starts_with('-') {
+            positionals.push(token);
+        }
+    }
+
+    let mut positionals = positionals.into_iter();
+    positionals.find(|t| *t == "port-forward")?;
+    let target = positionals.next()?;
+    let target = if target.contains('/') {
+        target.to_string()
+    } else {
+        format!("pod/{target}")
+    };
+
+    let mappings: Vec<(Option<u16>, String)> = positionals
+        .filter_map(|spec| match spec.split_once(':') {
+            Some(("", remote)) => Some((None, remote.to_string())),
+            Some((local, remote)) => Some((Some(local.parse().ok()?), remote.to_string())),
+            None => Some((Some(spec.parse().ok()?), spec.to_string())),
+        })
+        .collect();
+    if mappings.is_empty() {
+        return None;
+    }
+
+    Some(PortForwardCommand {
+        namespace,
+        kube_context,
+        target,
+        mappings,
+    })
+}
+
+/// 解析 `kubectl get services -o json`
+fn parse_kube_services(body: &str) -> Option<Vec<KubeService>> {
+    let list: KubeList<RawService> = serde_json::from_str(body).ok()?;
+
+    Some(
+        list.items
+            .into_iter()
+            .map(|raw| KubeService {
+                namespace: raw.metadata.namespace,
+                name: raw.metadata.name,
+                service_type: raw
+                    .spec
+                    .service_type
+                    .unwrap_or_else(|| "ClusterIP".to_string()),
+                cluster_ip: raw.spec.cluster_ip.filter(|ip| ip != "None"),
+                ports: raw
+                    .spec
+                    .ports
+                    .into_iter()
+                    .map(|port| KubeServicePort {
+                        name: port.name,
+                        port: port.port,
+                        target_port: match port.target_port {
+                            Some(serde_json::Value::String(name)) => name,
+                            Some(serde_json::Value::Number(n)) => n.to_string(),
+                            _ => port.port.to_string(),
+                        },
+                        node_port: port.node_port,
+                        protocol: port.protocol.unwrap_or_else(|| "TCP".to_string()),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+/// 解析 `kubectl get pods -o json`
+fn parse_kube_pods(body: &str) -> Option<Vec<KubePod>> {
+    let list: KubeList<RawPod> = serde_json::from_str(body).ok()?;
+
+    Some(
+        list.items
+            .into_iter()
+            .map(|raw| KubePod {
+                namespace: raw.metadata.namespace,
+                name: raw.metadata.name,
+                node: raw.spec.node_name,
+                pod_ip: raw.status.pod_ip,
+                phase: raw.status.phase.unwrap_or_else(|| "Unknown".to_string()),
+                ports: raw
+                    .spec
+                    .containers
+                    .into_iter()
+                    .flat_map(|container| {
+                        let container_name = container.name;
+                        container
+                            .ports
+                            .into_iter()
+                            .map(move |port| KubeContainerPort {
+                                container: container_name.clone(),
+                                name: port.name,
+                                container_port: port.container_port,
+                                host_port: port.host_port,
+                                protocol: port.protocol.unwrap_or_else(|| "TCP".to_string()),
+                            })
+                    })
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+/// 将 Service 的 NodePort 与本机监听端口关联
+fn correlate_node_ports(services: &[KubeService], listeners: &[PortInfo]) -> Vec<NodePortBinding> {
+    let mut bindings: Vec<NodePortBinding> = services
+        .iter()
+        .flat_map(|service| {
+            service.ports.iter().filter_map(move |port| {
+                let node_port = port.node_port?;
+                let listener = listeners.iter().find(|l| {
+                    l.port == node_port && l.protocol.eq_ignore_ascii_case(&port.protocol)
+                });
+                Some(NodePortBinding {
+                    node_port,
+                    protocol: port.protocol.clone(),
+                    namespace: service.namespace.clone(),
+                    service: service.name.clone(),
+                    pid: listener.map(|l| l.pid.clone()),
+                    process: listener.map(|l| l.process.clone()),
+                })
+            })
+        })
+        .collect();
+    bindings.sort_by_key(|b| b.node_port);
+    bindings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kube_services() {
+        let body = r#"{"items": [
+            {"metadata": {"name": "web", "namespace": "default"},
+             "spec": {"type": "NodePort", "clusterIP": "10.96.0.10",
+                      "ports": [{"name": "http", "port": 80, "targetPort": 8080, "nodePort": 30080, "protocol": "TCP"}]}},
+            {"metadata": {"name": "db", "namespace": "data"},
+             "spec": {"clusterIP": "None", "ports": [{"port": 5432, "targetPort": "pg"}]}}
+        ]}"#;
+
+        let services = parse_kube_services(body).unwrap();
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[0].service_type, "NodePort");
+        assert_eq!(services[0].ports[0].target_port, "8080");
+        assert_eq!(services[0].ports[0].node_port, Some(30080));
+        assert_eq!(services[1].service_type, "ClusterIP");
+        assert_eq!(services[1].cluster_ip, None);
+        assert_eq!(services[1].ports[0].target_port, "pg");
+        assert_eq!(services[1].ports[0].protocol, "TCP");
+    }
+
+    #[test]
+    fn test_parse_kube_pods() {
+        let body = r#"{"items": [
+            {"metadata": {"name": "web-7d9f", "namespace": "default"},
+             "spec": {"nodeName": "kind-control-plane",
+                      "containers": [{"name": "nginx", "ports": [{"containerPort": 80, "name": "http"}]},
+                                     {"name": "sidecar"}]},
+             "status": {"phase": "Running", "podIP": "10.244.0.5"}}
+        ]}"#;
+
+        let pods = parse_kube_pods(body).unwrap();
+        assert_eq!(pods.len(), 1);
+        assert_eq!(pods[0].phase, "Running");
+        assert_eq!(pods[0].pod_ip.as_deref(), Some("10.244.0.5"));
+        assert_eq!(pods[0].ports.len(), 1);
+        assert_eq!(pods[0].ports[0].container, "nginx");
+        assert_eq!(pods[0].ports[0].container_port, 80);
+        assert!(parse_kube_pods("not json").is_none());
+    }
+
+    #[test]
+    fn test_correlate_node_ports() {
+        let services = vec![KubeService {
+            namespace: "default".to_string(),
+            name: "web".to_string(),
+            service_type: "NodePort".to_string(),
+            cluster_ip: None,
+            ports: vec![
+                KubeServicePort {
+                    name: None,
+                    port: 80,
+                    target_port: "80".to_string(),
+                    node_port: Some(30080),
+                    protocol: "TCP".to_string(),
+                },
+                KubeServicePort {
+                    name: None,
+                    port: 443,
+                    target_port: "443".to_string(),
+                    node_port: None,
+                    protocol: "TCP".to_string(),
+                },
+            ],
+        }];
+        let listeners = vec![PortInfo {
+            port: 30080,
+            protocol: "tcp".to_string(),
+            address: "0.0.0.0".to_string(),
+            pid: "4242".to_string(),
+            process: "docker-proxy".to_string(),
+            user: "root".to_string(),
+            command: None,
+        }];
+
+        let bindings = correlate_node_ports(&services, &listeners);
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].node_port, 30080);
+        assert_eq!(bindings[0].process.as_deref(), Some("docker-proxy"));
+    }
+
+    #[test]
+    fn test_parse_port_forward_command() {
+        let command = parse_port_forward_command(
+            "/usr/local/bin/kubectl -n shop port-forward svc/web 8080:80 9090 --address 0.0.0.0",
+        )
+        .unwrap();
+        assert_eq!(command.namespace.as_deref(), Some("shop"));
+        assert_eq!(command.target, "svc/web");
+        assert_eq!(
+            command.mappings,
+            vec![
+                (Some(8080), "80".to_string()),
+                (Some(9090), "9090".to_string())
+            ]
+        );
+
+        let command =
+            parse_port_forward_command("kubectl port-forward web-7d9f :5432 --context=kind-dev")
+                .unwrap();
+        assert_eq!(command.target, "pod/web-7d9f");
+        assert_eq!(command.kube_context.as_deref(), Some("kind-dev"));
+        assert_eq!(command.mappings, vec![(None, "5432".to_string())]);
+
+        assert!(parse_port_forward_command("kubectl get pods").is_none());
+        assert!(parse_port_forward_command("ssh -L 8080:localhost:80 host").is_none());
+    }
+
+    #[test]
+    fn test_match_port_forward() {
+        let command =
+            parse_port_forward_command("kubectl port-forward deploy/api 3000:8080 :9000").unwrap();
+        let listener = |port: u16| PortInfo {
+            port,
+            protocol: "tcp".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "77".to_string(),
+            process: "kubectl".to_string(),
+            user: "me".to_string(),
+            command: None,
+        };
+
+        let session = match_port_forward(&command, &listener(3000)).unwrap();
+        assert_eq!(session.target, "deploy/api");
+        assert_eq!(session.remote_port, "8080");
+        let session = match_port_forward(&command, &listener(51234)).unwrap();
+        assert_eq!(session.remote_port, "9000");
+    }
+}
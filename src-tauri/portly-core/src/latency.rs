@@ -0,0 +1,343 @@
+//! 局域网设备延迟历史
+//!
+//! 每次设备发现都会记下各设备的 Ping 往返时间，按 IP 保留最近若干次采样，持久化在数据目录的
+//! `latency.json`。设备列表据此绘制延迟热力图 / 迷你折线图，慢或拥塞（抖动大、丢包多）的设备
+//! 一眼可见。本轮扫描范围内没有响应的已知设备记一次空采样，在热力图上显示为缺口；窗口内全部
+//! 为空采样的设备从历史中移除
+
+use crate::app_error::{AppError, AppResult};
+use crate::network::NetworkDevice;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 每台设备保留的采样数
+pub const MAX_SAMPLES: usize = 60;
+/// 最多记录的设备数，超出时丢弃最久没有响应的设备
+const MAX_DEVICES: usize = 1024;
+/// 平均延迟达到该值视为一般
+const FAIR_MS: f32 = 10.0;
+/// 平均延迟达到该值视为慢
+const SLOW_MS: f32 = 50.0;
+/// 相邻采样的平均抖动达到该值视为拥塞
+const JITTER_MS: f32 = 30.0;
+/// 丢包率达到该值视为拥塞
+const LOSS_PERCENT: f32 = 20.0;
+
+/// 串行化读改写，避免两次发现同时写入时丢失采样
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 一次采样；`rtt_ms` 为空表示该轮未响应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencySample {
+    pub at: String,
+    pub rtt_ms: Option<f32>,
+}
+
+/// 一台设备的采样历史
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceHistory {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// 按时间先后排列
+    #[serde(default)]
+    pub samples: Vec<LatencySample>,
+}
+
+/// 所有设备的延迟历史，按 IP 索引
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencyHistory {
+    #[serde(default)]
+    pub devices: BTreeMap<String, DeviceHistory>,
+}
+
+/// 延迟等级，供热力图着色
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LatencyLevel {
+    Good,
+    Fair,
+    Slow,
+    Offline,
+}
+
+/// 一台设备的延迟序列和统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceLatency {
+    pub ip: String,
+    pub hostname: Option<String>,
+    pub samples: Vec<LatencySample>,
+    /// 最近一次响应的往返时间
+    pub last_ms: Option<f32>,
+    pub avg_ms: Option<f32>,
+    pub min_ms: Option<f32>,
+    pub max_ms: Option<f32>,
+    /// 相邻两次响应之间延迟变化的平均值
+    pub jitter_ms: Option<f32>,
+    /// 空采样占比
+    pub loss_percent: f32,
+    pub level: LatencyLevel,
+}
+
+/// 延迟热力图数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyHeatmap {
+    pub generated_at: String,
+    /// 按 IP 排列
+    pub devices: Vec<DeviceLatency>,
+}
+
+impl LatencyHistory {
+    /// 记录一轮发现：`devices` 中在线的设备记下往返时间，`scanned` 范围内未出现的已知设备
+    /// 记一次空采样
+    pub fn record(&mut self, scanned: &HashSet<String>, devices: &[NetworkDevice], at: &str) {
+        let mut seen = HashSet::new();
+        for device in devices.iter().filter(|d| d.is_online) {
+            seen.insert(device.ip.as_str());
+            let history = self.devices.entry(device.ip.clone()).or_default();
+            if device.hostname.is_some() {
+                history.hostname = device.hostname.clone();
+            }
+            history.samples.push(LatencySample {
+                at: at.to_string(),
+                rtt_ms: device.rtt_ms,
+            });
+        }
+        for (ip, history) in self.devices.iter_mut() {
+            if scanned.contains(ip) && !seen.contains(ip.as_str()) {
+                history.samples.push(LatencySample {
+                    at: at.to_string(),
+                    rtt_ms: None,
+                });
+            }
+        }
+        self.prune();
+    }
+
+    /// 截断到 `MAX_SAMPLES`，移除长期无响应的设备并限制设备数
+    fn prune(&mut self) {
+        for history in self.devices.values_mut() {
+            let excess = history.samples.len().saturating_sub(MAX_SAMPLES);
+            history.samples.drain(..excess);
+        }
+        self.devices
+            .retain(|_, history| history.samples.iter().any(|s| s.rtt_ms.is_some()));
+        if self.devices.len() > MAX_DEVICES {
+            let mut by_last_seen: Vec<(String, String)> = self
+                .devices
+                .iter()
+                .map(|(ip, history)| (last_seen(history), ip.clone()))
+                .collect();
+            by_last_seen.sort();
+            for (_, ip) in by_last_seen
+                .into_iter()
+                .take(self.devices.len() - MAX_DEVICES)
+            {
+                self.devices.remove(&ip);
+            }
+        }
+    }
+
+    /// 生成热力图数据
+    pub fn heatmap(&self) -> LatencyHeatmap {
+        let mut devices: Vec<DeviceLatency> = self
+            .devices
+            .iter()
+            .map(|(ip, history)| summarize(ip, history))
+            .collect();
+        devices.sort_by_key(|d| d.ip.parse::<IpAddr>().ok());
+        LatencyHeatmap {
+            generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            devices,
+        }
+    }
+}
+
+fn last_seen(history: &DeviceHistory) -> String {
+    history
+        .samples
+        .iter()
+        .rev()
+        .find(|s| s.rtt_ms.is_some())
+        .map(|s| s.at.clone())
+        .unwrap_or_default()
+}
+
+fn summarize(ip: &str, history: &DeviceHistory) -> DeviceLatency {
+    let answered: Vec<f32> = history.samples.iter().filter_map(|s| s.rtt_ms).collect();
+    let count = answered.len() as f32;
+    let avg_ms = (!answered.is_empty()).then(|| answered.iter().sum::<f32>() / count);
+    let jitter_ms = (answered.len() > 1).then(|| {
+        answered
+            .windows(2)
+            .map(|w| (w[1] - w[0]).abs())
+            .sum::<f32>()
+            / (count - 1.0)
+    });
+    let loss_percent = if history.samples.is_empty() {
+        0.0
+    } else {
+        (history.samples.len() - answered.len()) as f32 * 100.0 / history.samples.len() as f32
+    };
+    let level = match (history.samples.last().and_then(|s| s.rtt_ms), avg_ms) {
+        (None, _) | (_, None) => LatencyLevel::Offline,
+        (Some(_), Some(avg)) => {
+            if avg >= SLOW_MS
+                || jitter_ms.is_some_and(|j| j >= JITTER_MS)
+                || loss_percent >= LOSS_PERCENT
+            {
+                LatencyLevel::Slow
+            } else if avg >= FAIR_MS {
+                LatencyLevel::Fair
+            } else {
+                LatencyLevel::Good
+            }
+        }
+    };
+
+    DeviceLatency {
+        ip: ip.to_string(),
+        hostname: history.hostname.clone(),
+        samples: history.samples.clone(),
+        last_ms: answered.last().copied(),
+        avg_ms,
+        min_ms: answered.iter().copied().reduce(f32::min),
+        max_ms: answered.iter().copied().reduce(f32::max),
+        jitter_ms,
+        loss_percent,
+        level,
+    }
+}
+
+/// 延迟历史文件路径
+pub fn history_path() -> PathBuf {
+    crate::export::get_data_dir().join("latency.json")
+}
+
+/// 读取延迟历史；文件不存在或无法解析时返回空历史
+pub fn load() -> LatencyHistory {
+    let Ok(content) = std::fs::read_to_string(history_path()) else {
+        return LatencyHistory::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "延迟历史文件无法解析，重新开始记录");
+        LatencyHistory::default()
+    })
+}
+
+fn save(history: &LatencyHistory) -> AppResult<()> {
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建数据目录失败: {}", e)))?;
+    }
+    let content = serde_json::to_string(history)
+        .map_err(|e| AppError::internal(format!("序列化延迟历史失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入延迟历史失败: {}", e)))
+}
+
+/// 把一次设备发现的结果写入延迟历史；`subnet` 为本次扫描的网段
+pub fn record_discovery(subnet: &str, devices: &[NetworkDevice]) -> AppResult<()> {
+    let scanned: HashSet<String> =
+        crate::network::subnet_host_addresses(subnet, crate::network::MAX_DISCOVER_HOSTS)
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut history = load();
+    let at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    history.record(&scanned, devices, &at);
+    save(&history)
+}
+
+/// 当前的延迟热力图数据
+pub fn device_latency() -> LatencyHeatmap {
+    load().heatmap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(ip: &str, rtt_ms: Option<f32>) -> NetworkDevice {
+        NetworkDevice {
+            ip: ip.to_string(),
+            mac: None,
+            hostname: Some(format!("host-{ip}")),
+            is_online: true,
+            interface_index: None,
+            rtt_ms,
+            tags: Vec::new(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_record_and_heatmap() {
+        let scanned: HashSet<String> = ["10.0.0.2", "10.0.0.10", "10.0.0.30"]
+            .iter()
+            .map(|ip| ip.to_string())
+            .collect();
+        let mut history = LatencyHistory::default();
+        for (round, rtt) in [2.0, 3.0, 80.0, 2.5].into_iter().enumerate() {
+            let devices = vec![
+                device("10.0.0.10", Some(1.0)),
+                device("10.0.0.2", Some(rtt)),
+            ];
+            history.record(&scanned, &devices, &format!("2026-10-16 10:0{round}:00"));
+        }
+        history.record(
+            &scanned,
+            &[device("10.0.0.10", Some(1.2))],
+            "2026-10-16 10:05:00",
+        );
+
+        let heatmap = history.heatmap();
+        let ips: Vec<&str> = heatmap.devices.iter().map(|d| d.ip.as_str()).collect();
+        assert_eq!(ips, vec!["10.0.0.2", "10.0.0.10"]);
+
+        let jittery = &heatmap.devices[0];
+        assert_eq!(jittery.samples.len(), 5);
+        assert_eq!(jittery.samples[4].rtt_ms, None);
+        assert_eq!(jittery.level, LatencyLevel::Offline);
+        assert_eq!(jittery.loss_percent, 20.0);
+        assert_eq!(jittery.max_ms, Some(80.0));
+        assert_eq!(jittery.last_ms, Some(2.5));
+
+        let steady = &heatmap.devices[1];
+        assert_eq!(steady.level, LatencyLevel::Good);
+        assert_eq!(steady.min_ms, Some(1.0));
+        assert_eq!(steady.hostname.as_deref(), Some("host-10.0.0.10"));
+
+        // 重新上线后按抖动判为拥塞
+        history.record(
+            &scanned,
+            &[device("10.0.0.2", Some(3.0))],
+            "2026-10-16 10:06:00",
+        );
+        assert_eq!(history.heatmap().devices[0].level, LatencyLevel::Slow);
+    }
+
+    #[test]
+    fn test_record_prunes_old_samples_and_silent_devices() {
+        let scanned: HashSet<String> = ["10.0.0.1".to_string()].into_iter().collect();
+        let mut history = LatencyHistory::default();
+        for i in 0..MAX_SAMPLES + 5 {
+            history.record(
+                &scanned,
+                &[device("10.0.0.1", Some(i as f32))],
+                &i.to_string(),
+            );
+        }
+        assert_eq!(history.devices["10.0.0.1"].samples.len(), MAX_SAMPLES);
+        assert_eq!(history.devices["10.0.0.1"].samples[0].rtt_ms, Some(5.0));
+
+        for i in 0..MAX_SAMPLES {
+            history.record(&scanned, &[], &format!("later-{i}"));
+        }
+        assert!(history.devices.is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+//! Portly 核心库
+//!
+//! 端口扫描、网络探测、Docker/Kubernetes、进程管理与导出等功能，不依赖 Tauri，
+//! 由桌面端（`portly_lib`）和 `portly-cli` 共同使用，也可以嵌入其他 Rust 工具
+
+pub mod advanced_scan;
+pub mod app_error;
+pub mod arp;
+pub mod audit;
+pub mod backup;
+pub mod cache;
+pub mod capabilities;
+pub mod command_exec;
+pub mod config;
+pub mod core;
+pub mod daemon;
+pub mod demo;
+pub mod dependencies;
+pub mod dhcp;
+pub mod dns;
+pub mod docker;
+pub mod export;
+pub mod exposure;
+pub mod firewall;
+pub mod hooks;
+pub mod host_pattern;
+pub mod i18n;
+pub mod iface_stats;
+pub mod inventory;
+pub mod known_ports;
+pub mod kube;
+pub mod latency;
+pub mod lldp;
+pub mod logging;
+pub mod monitor;
+pub mod network;
+pub mod ntp;
+pub mod output_schema;
+pub mod ownership;
+pub mod ping_compare;
+pub mod plugins;
+pub mod policy;
+pub mod port_forward;
+pub mod port_watch;
+pub mod printer;
+pub mod process;
+pub mod proxy;
+pub mod rpc;
+pub mod rules;
+pub mod scan_manager;
+pub mod scheduler;
+pub mod security_headers;
+pub mod settings;
+pub mod shares;
+pub mod ssl;
+pub mod tls_fingerprint;
+pub mod update;
+pub mod vpn;
+pub mod vulns;
+pub mod web_fingerprint;
+pub mod webhook;
+pub mod websocket;
+pub mod whois;
+
+pub use app_error::{AppError, AppResult, PortlyError, PortlyErrorKind};
+pub use config::*;
+pub use core::*;
+pub use dns::*;
+pub use docker::*;
+pub use export::*;
+pub use i18n::*;
+pub use kube::*;
+pub use network::*;
+pub use output_schema::*;
+pub use process::*;
+pub use whois::*;
@@ -0,0 +1,377 @@
+//! LLDP / CDP 邻居发现
+//!
+//! 交换机会周期性地从每个端口发出 LLDP（IEEE 802.1AB，默认每 30 秒）或 CDP（Cisco，默认每
+//! 60 秒）帧，其中带有交换机名称、端口号和端口 VLAN。在本机上行接口上监听这些帧，就能直接回答
+//! “我接在哪台交换机的哪个口上”。监听需要打开原始以太网通道，与 [`crate::advanced_scan`]
+//! 一样依赖抓包驱动和 root / 管理员权限；Windows 版本暂不支持
+
+use crate::app_error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+
+/// 默认监听时长，覆盖 LLDP 的默认发送间隔
+pub const DEFAULT_LISTEN_SECS: u64 = 35;
+/// 监听时长上限
+pub const MAX_LISTEN_SECS: u64 = 180;
+
+/// LLDP 以太网类型
+const ETHERTYPE_LLDP: u16 = 0x88cc;
+/// CDP 组播地址
+const CDP_MULTICAST: [u8; 6] = [0x01, 0x00, 0x0c, 0xcc, 0xcc, 0xcc];
+/// CDP 使用 802.3 帧，LLC/SNAP 头中的 Cisco OUI 与协议号
+const CDP_SNAP: [u8; 8] = [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x0c, 0x20, 0x00];
+/// IEEE 802.1 组织扩展 TLV 的 OUI
+const OUI_IEEE_8021: [u8; 3] = [0x00, 0x80, 0xc2];
+
+/// 邻居通告所用的协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NeighborProtocol {
+    Lldp,
+    Cdp,
+}
+
+/// 一台相邻设备（通常是上行交换机）的通告内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Neighbor {
+    pub protocol: NeighborProtocol,
+    /// 收到通告的本机接口
+    pub interface: String,
+    /// 通告帧的源 MAC
+    pub source_mac: String,
+    pub chassis_id: Option<String>,
+    /// 交换机名称（LLDP System Name / CDP Device ID）
+    pub system_name: Option<String>,
+    /// 交换机端口号，例如 `Gi1/0/12`
+    pub port_id: Option<String>,
+    pub port_description: Option<String>,
+    /// 端口 VLAN（LLDP Port VLAN ID / CDP Native VLAN）
+    pub vlan: Option<u16>,
+    pub management_address: Option<String>,
+    /// 设备型号（CDP Platform）
+    pub platform: Option<String>,
+    /// 系统描述或软件版本
+    pub system_description: Option<String>,
+    /// 通告的有效期
+    pub ttl_secs: Option<u16>,
+}
+
+impl Neighbor {
+    fn new(protocol: NeighborProtocol) -> Self {
+        Self {
+            protocol,
+            interface: String::new(),
+            source_mac: String::new(),
+            chassis_id: None,
+            system_name: None,
+            port_id: None,
+            port_description: None,
+            vlan: None,
+            management_address: None,
+            platform: None,
+            system_description: None,
+            ttl_secs: None,
+        }
+    }
+}
+
+/// 一次邻居发现的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeighborReport {
+    pub interface: String,
+    pub listened_secs: u64,
+    pub neighbors: Vec<Neighbor>,
+}
+
+/// 解析一个以太网帧；不是 LLDP / CDP 帧时返回 `None`
+pub fn parse_frame(frame: &[u8], interface: &str) -> Option<Neighbor> {
+    if frame.len() < 14 {
+        return None;
+    }
+    let ethertype = u16::from_be_bytes([frame[12], frame[13]]);
+    let mut neighbor = if ethertype == ETHERTYPE_LLDP {
+        parse_lldp(&frame[14..])?
+    } else if frame[..6] == CDP_MULTICAST && ethertype <= 1500 && frame[14..].starts_with(&CDP_SNAP)
+    {
+        parse_cdp(&frame[14 + CDP_SNAP.len()..])?
+    } else {
+        return None;
+    };
+    neighbor.interface = interface.to_string();
+    neighbor.source_mac = format_mac(&frame[6..12]);
+    Some(neighbor)
+}
+
+/// 解析 LLDPDU：TLV 头为 7 位类型 + 9 位长度
+fn parse_lldp(mut data: &[u8]) -> Option<Neighbor> {
+    let mut neighbor = Neighbor::new(NeighborProtocol::Lldp);
+    while data.len() >= 2 {
+        let header = u16::from_be_bytes([data[0], data[1]]);
+        let (kind, len) = (header >> 9, (header & 0x01ff) as usize);
+        let value = data.get(2..2 + len)?;
+        data = &data[2 + len..];
+        match kind {
+            0 => break,
+            1 => neighbor.chassis_id = lldp_id(value, 4, 5),
+            2 => neighbor.port_id = lldp_id(value, 3, 4),
+            3 if len == 2 => neighbor.ttl_secs = Some(u16::from_be_bytes([value[0], value[1]])),
+            4 => neighbor.port_description = text(value),
+            5 => neighbor.system_name = text(value),
+            6 => neighbor.system_description = text(value),
+            8 if neighbor.management_address.is_none() => {
+                // 地址字符串长度（含子类型）+ 地址子类型 + 地址
+                let addr_len = *value.first()? as usize;
+                neighbor.management_address = value
+                    .get(1..1 + addr_len)
+                    .and_then(|addr| format_address(addr[0], &addr[1..]));
+            }
+            127 if value.len() >= 6 && value[..3] == OUI_IEEE_8021 && value[3] == 1 => {
+                neighbor.vlan = Some(u16::from_be_bytes([value[4], value[5]])).filter(|v| *v != 0);
+            }
+            _ => {}
+        }
+    }
+    neighbor.chassis_id.is_some().then_some(neighbor)
+}
+
+/// LLDP Chassis ID / Port ID：首字节为子类型，MAC 和网络地址按类型格式化，其余按文本处理
+fn lldp_id(value: &[u8], mac_subtype: u8, address_subtype: u8) -> Option<String> {
+    let (&subtype, id) = value.split_first()?;
+    if subtype == mac_subtype && id.len() == 6 {
+        Some(format_mac(id))
+    } else if subtype == address_subtype && !id.is_empty() {
+        format_address(id[0], &id[1..])
+    } else {
+        text(id)
+    }
+}
+
+/// 解析 CDP：版本、TTL、校验和之后是 TLV，头为 16 位类型 + 16 位长度（含头部）
+fn parse_cdp(data: &[u8]) -> Option<Neighbor> {
+    let mut neighbor = Neighbor::new(NeighborProtocol::Cdp);
+    neighbor.ttl_secs = Some(*data.get(1)? as u16);
+    let mut data = data.get(4..)?;
+    while data.len() >= 4 {
+        let kind = u16::from_be_bytes([data[0], data[1]]);
+        let len = u16::from_be_bytes([data[2], data[3]]) as usize;
+        if len < 4 {
+            break;
+        }
+        let value = data.get(4..len)?;
+        data = &data[len..];
+        match kind {
+            0x0001 => neighbor.system_name = text(value),
+            0x0002 | 0x0016 if neighbor.management_address.is_none() => {
+                neighbor.management_address = cdp_address(value);
+            }
+            0x0003 => neighbor.port_id = text(value),
+            0x0005 => neighbor.system_description = text(value),
+            0x0006 => neighbor.platform = text(value),
+            0x000a if len == 6 => neighbor.vlan = Some(u16::from_be_bytes([value[0], value[1]])),
+            _ => {}
+        }
+    }
+    neighbor.chassis_id = neighbor.system_name.clone();
+    neighbor.system_name.is_some().then_some(neighbor)
+}
+
+/// CDP 地址列表中的第一个 IPv4 地址：NLPID 类型、协议 0xCC、4 字节地址
+fn cdp_address(value: &[u8]) -> Option<String> {
+    let mut rest = value.get(4..)?;
+    while rest.len() >= 2 {
+        let proto_len = rest[1] as usize;
+        let proto = rest.get(2..2 + proto_len)?;
+        let addr_at = 2 + proto_len;
+        let addr_len = u16::from_be_bytes([*rest.get(addr_at)?, *rest.get(addr_at + 1)?]) as usize;
+        let addr = rest.get(addr_at + 2..addr_at + 2 + addr_len)?;
+        if rest[0] == 1 && proto == [0xcc] {
+            return format_address(1, addr);
+        }
+        rest = &rest[addr_at + 2 + addr_len..];
+    }
+    None
+}
+
+/// IANA 地址族：1 为 IPv4，2 为 IPv6
+fn format_address(family: u8, addr: &[u8]) -> Option<String> {
+    match (family, addr.len()) {
+        (1, 4) => Some(std::net::Ipv4Addr::new(addr[0], addr[1], addr[2], addr[3]).to_string()),
+        (2, 16) => {
+            let octets: [u8; 16] = addr.try_into().ok()?;
+            Some(std::net::Ipv6Addr::from(octets).to_string())
+        }
+        _ => None,
+    }
+}
+
+fn format_mac(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn text(value: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(value)
+        .trim_matches(|c: char| c.is_whitespace() || c == '\0')
+        .to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// 在接口上监听 LLDP / CDP 通告；`interface` 为空时使用默认上行接口。
+/// 收到第一份通告后再等待片刻以收集同时发出的其他协议通告，然后返回
+#[tracing::instrument(level = "info")]
+pub fn discover_neighbors(interface: Option<&str>, listen_secs: u64) -> AppResult<NeighborReport> {
+    if listen_secs == 0 || listen_secs > MAX_LISTEN_SECS {
+        return Err(AppError::validation(
+            "监听时长",
+            format!("需要在 1-{} 秒之间", MAX_LISTEN_SECS),
+        ));
+    }
+    let report = platform::listen(interface, listen_secs)?;
+    tracing::info!(
+        interface = %report.interface,
+        neighbors = report.neighbors.len(),
+        "邻居发现完成"
+    );
+    Ok(report)
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::*;
+    use crate::advanced_scan::capture_interface;
+    use crate::capabilities::capture_error;
+    use pnet::datalink::{self, Channel, Config};
+    use std::time::{Duration, Instant};
+
+    /// 收到第一份通告后继续等待的时间
+    const GRACE: Duration = Duration::from_secs(2);
+
+    pub fn listen(interface: Option<&str>, listen_secs: u64) -> AppResult<NeighborReport> {
+        let iface = capture_interface(interface)?;
+        let config = Config {
+            read_timeout: Some(Duration::from_millis(500)),
+            promiscuous: true,
+            ..Config::default()
+        };
+        let mut rx = match datalink::channel(&iface, config) {
+            Ok(Channel::Ethernet(_, rx)) => rx,
+            Ok(_) => return Err(AppError::internal("不支持的链路层通道类型")),
+            Err(err) => return Err(capture_error("邻居发现", err)),
+        };
+
+        let started = Instant::now();
+        let mut deadline = started + Duration::from_secs(listen_secs);
+        let mut neighbors: Vec<Neighbor> = Vec::new();
+        while Instant::now() < deadline {
+            // 读超时返回错误，继续等待直到截止时间
+            let Ok(frame) = rx.next() else { continue };
+            let Some(neighbor) = parse_frame(frame, &iface.name) else {
+                continue;
+            };
+            if neighbors.is_empty() {
+                deadline = deadline.min(Instant::now() + GRACE);
+            }
+            neighbors.retain(|n| {
+                (n.protocol, &n.source_mac) != (neighbor.protocol, &neighbor.source_mac)
+            });
+            neighbors.push(neighbor);
+        }
+
+        Ok(NeighborReport {
+            interface: iface.name,
+            listened_secs: started.elapsed().as_secs(),
+            neighbors,
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::*;
+
+    pub fn listen(_interface: Option<&str>, _listen_secs: u64) -> AppResult<NeighborReport> {
+        Err(AppError::command_unavailable(
+            "Npcap",
+            "邻居发现",
+            "Windows 版本暂未启用链路层抓包",
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tlv(kind: u16, value: &[u8]) -> Vec<u8> {
+        let mut out = ((kind << 9) | value.len() as u16).to_be_bytes().to_vec();
+        out.extend_from_slice(value);
+        out
+    }
+
+    #[test]
+    fn test_parse_lldp_frame() {
+        let mut frame = vec![0x01, 0x80, 0xc2, 0x00, 0x00, 0x0e];
+        frame.extend([0x00, 0x1b, 0x54, 0xaa, 0xbb, 0x0c, 0x88, 0xcc]);
+        frame.extend(tlv(1, &[4, 0x00, 0x1b, 0x54, 0xaa, 0xbb, 0x00]));
+        frame.extend(tlv(2, b"\x05Gi1/0/12"));
+        frame.extend(tlv(3, &120u16.to_be_bytes()));
+        frame.extend(tlv(4, b"desk-42"));
+        frame.extend(tlv(5, b"sw-core-01\0"));
+        frame.extend(tlv(8, &[5, 1, 10, 0, 0, 2, 2, 0, 0, 0, 1, 0]));
+        frame.extend(tlv(127, &[0x00, 0x80, 0xc2, 1, 0x00, 0x14]));
+        frame.extend(tlv(0, &[]));
+
+        let neighbor = parse_frame(&frame, "eth0").unwrap();
+        assert_eq!(neighbor.protocol, NeighborProtocol::Lldp);
+        assert_eq!(neighbor.interface, "eth0");
+        assert_eq!(neighbor.source_mac, "00:1b:54:aa:bb:0c");
+        assert_eq!(neighbor.chassis_id.as_deref(), Some("00:1b:54:aa:bb:00"));
+        assert_eq!(neighbor.system_name.as_deref(), Some("sw-core-01"));
+        assert_eq!(neighbor.port_id.as_deref(), Some("Gi1/0/12"));
+        assert_eq!(neighbor.port_description.as_deref(), Some("desk-42"));
+        assert_eq!(neighbor.vlan, Some(20));
+        assert_eq!(neighbor.management_address.as_deref(), Some("10.0.0.2"));
+        assert_eq!(neighbor.ttl_secs, Some(120));
+
+        // 普通 IPv4 帧
+        let mut ipv4 = frame.clone();
+        ipv4[12..14].copy_from_slice(&[0x08, 0x00]);
+        assert_eq!(parse_frame(&ipv4, "eth0"), None);
+    }
+
+    #[test]
+    fn test_parse_cdp_frame() {
+        let cdp_tlv = |kind: u16, value: &[u8]| {
+            let mut out = kind.to_be_bytes().to_vec();
+            out.extend((value.len() as u16 + 4).to_be_bytes());
+            out.extend_from_slice(value);
+            out
+        };
+        let mut payload = vec![2, 180, 0x12, 0x34];
+        payload.extend(cdp_tlv(0x0001, b"sw-access-3.lan"));
+        payload.extend(cdp_tlv(
+            0x0002,
+            &[0, 0, 0, 1, 1, 1, 0xcc, 0, 4, 192, 168, 1, 3],
+        ));
+        payload.extend(cdp_tlv(0x0003, b"FastEthernet0/7"));
+        payload.extend(cdp_tlv(0x0006, b"cisco WS-C2960-24TT-L"));
+        payload.extend(cdp_tlv(0x000a, &[0x00, 0x0a]));
+
+        let mut frame = CDP_MULTICAST.to_vec();
+        frame.extend([0x00, 0x1e, 0x13, 0x01, 0x02, 0x03]);
+        frame.extend(((CDP_SNAP.len() + payload.len()) as u16).to_be_bytes());
+        frame.extend(CDP_SNAP);
+        frame.extend(payload);
+
+        let neighbor = parse_frame(&frame, "en0").unwrap();
+        assert_eq!(neighbor.protocol, NeighborProtocol::Cdp);
+        assert_eq!(neighbor.system_name.as_deref(), Some("sw-access-3.lan"));
+        assert_eq!(neighbor.port_id.as_deref(), Some("FastEthernet0/7"));
+        assert_eq!(neighbor.platform.as_deref(), Some("cisco WS-C2960-24TT-L"));
+        assert_eq!(neighbor.vlan, Some(10));
+        assert_eq!(neighbor.management_address.as_deref(), Some("192.168.1.3"));
+        assert_eq!(neighbor.ttl_secs, Some(180));
+    }
+}
@@ -0,0 +1,161 @@
+//! 日志模块
+//!
+//! 基于 `tracing` 记录扫描、探测和终止进程等操作，按天滚动写入数据目录下的
+//! `logs/portly.YYYY-MM-DD.log`，最多保留 7 个文件。日志级别可通过环境变量
+//! `PORTLY_LOG`（error / warn / info / debug / trace）调整，默认 info
+
+use crate::app_error::{AppError, AppResult};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+/// 指定日志级别的环境变量
+pub const LOG_LEVEL_ENV: &str = "PORTLY_LOG";
+
+/// 日志文件名前缀与后缀：`portly.2024-01-01.log`
+const LOG_FILE_PREFIX: &str = "portly";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// 最多保留的日志文件数（每天一个）
+const MAX_LOG_FILES: usize = 7;
+
+/// `init_logging` 只生效一次，重复调用返回首次结果
+static INIT_RESULT: OnceLock<Result<PathBuf, String>> = OnceLock::new();
+
+/// 日志目录
+pub fn log_dir() -> PathBuf {
+    crate::export::get_data_dir().join("logs")
+}
+
+/// 初始化全局日志，返回日志目录
+pub fn init_logging() -> AppResult<PathBuf> {
+    INIT_RESULT
+        .get_or_init(|| {
+            let dir = log_dir();
+            init_file_logging(&dir)
+                .map(|_| dir)
+                .map_err(|err| err.to_string())
+        })
+        .clone()
+        .map_err(AppError::internal)
+}
+
+fn init_file_logging(dir: &Path) -> AppResult<()> {
+    fs::create_dir_all(dir)
+        .map_err(|err| AppError::internal(format!("无法创建日志目录 {}：{err}", dir.display())))?;
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(dir)
+        .map_err(|err| AppError::internal(format!("无法打开日志文件：{err}")))?;
+
+    tracing_subscriber::fmt()
+        .with_writer(appender)
+        .with_ansi(false)
+        .with_target(true)
+        .with_max_level(level_from_env(std::env::var(LOG_LEVEL_ENV).ok().as_deref()))
+        .try_init()
+        .map_err(|err| AppError::internal(format!("日志初始化失败：{err}")))
+}
+
+/// 解析 `PORTLY_LOG`，无法识别时使用 info
+fn level_from_env(value: Option<&str>) -> LevelFilter {
+    value
+        .and_then(|v| v.trim().parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::INFO)
+}
+
+/// 读取最近的日志行（按时间顺序，最多 `max_lines` 行，跨越多个滚动文件）
+pub fn recent_logs(max_lines: usize) -> AppResult<Vec<String>> {
+    read_recent_lines(&log_dir(), max_lines)
+}
+
+fn read_recent_lines(dir: &Path, max_lines: usize) -> AppResult<Vec<String>> {
+    let mut files = log_files(dir)?;
+    // 文件名带日期，按名称倒序即从新到旧
+    files.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut lines: VecDeque<String> = VecDeque::with_capacity(max_lines);
+    for path in files {
+        if lines.len() >= max_lines {
+            break;
+        }
+        let file = fs::File::open(&path).map_err(|err| {
+            AppError::internal(format!("无法读取日志文件 {}：{err}", path.display()))
+        })?;
+        let mut tail: VecDeque<String> = VecDeque::new();
+        let remaining = max_lines - lines.len();
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if tail.len() == remaining {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+        for line in tail.into_iter().rev() {
+            lines.push_front(line);
+        }
+    }
+
+    Ok(lines.into())
+}
+
+/// 日志目录下的 Portly 日志文件（目录不存在时为空）
+fn log_files(dir: &Path) -> AppResult<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(AppError::internal(format!(
+                "无法读取日志目录 {}：{err}",
+                dir.display()
+            )))
+        }
+    };
+
+    Ok(entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+                })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_from_env() {
+        assert_eq!(level_from_env(None), LevelFilter::INFO);
+        assert_eq!(level_from_env(Some("debug")), LevelFilter::DEBUG);
+        assert_eq!(level_from_env(Some(" WARN ")), LevelFilter::WARN);
+        assert_eq!(level_from_env(Some("verbose")), LevelFilter::INFO);
+    }
+
+    #[test]
+    fn test_read_recent_lines_spans_rotated_files() {
+        let dir = std::env::temp_dir().join(format!("portly-logs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("portly.2024-01-01.log"), "a1\na2\na3\n").unwrap();
+        fs::write(dir.join("portly.2024-01-02.log"), "b1\nb2\n").unwrap();
+        fs::write(dir.join("other.txt"), "ignored\n").unwrap();
+
+        assert_eq!(read_recent_lines(&dir, 3).unwrap(), vec!["a3", "b1", "b2"]);
+        assert_eq!(read_recent_lines(&dir, 1).unwrap(), vec!["b2"]);
+        assert_eq!(read_recent_lines(&dir, 10).unwrap().len(), 5);
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(read_recent_lines(&dir, 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,900 @@
+//! 后台监控模块
+//!
+//! 按设置中的间隔周期性读取本机监听端口，可选发现局域网设备、检查关注的端点，
+//! 与之前的结果比较后生成 [`MonitorAlert`]。首轮只建立基线，不产生提醒。
+//! 同一进程在统计窗口内反复重新绑定同一端口（重新出现或换了 PID）时提醒频繁重启，
+//! 用于发现被守护进程反复拉起的崩溃服务。
+//! 登记的 TLS 端点按单独的间隔检查证书，剩余天数每跌破一个提醒阈值提醒一次。
+//! 每轮同时按 [`crate::rules`] 中的规则求值。系统通知由桌面端根据通知偏好发送，
+//! 最新状态可通过 [`latest_snapshot`] 读取
+
+use crate::core::PortInfo;
+use crate::i18n::{tr, tr_args, Lang, Msg};
+use crate::network::NetworkDevice;
+use crate::rules::{RuleEngine, RuleHit};
+use crate::settings::{MonitoredEndpoint, NotificationSettings, Settings};
+use crate::ssl::SslCertInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 保留的最近端口变化条数
+const MAX_RECENT_CHANGES: usize = 20;
+
+/// 等待下一轮时检查停止标志的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 最近一轮监控的结果
+static LATEST: Mutex<Option<MonitorSnapshot>> = Mutex::new(None);
+
+/// 提醒类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    NewPort,
+    PortClosed,
+    NewDevice,
+    EndpointDown,
+    EndpointUp,
+    /// 同一进程反复重新绑定同一端口
+    Flapping,
+    /// 关注的证书剩余天数跌破提醒阈值或已过期
+    CertExpiring,
+}
+
+/// 监控提醒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorAlert {
+    pub kind: AlertKind,
+    pub title: String,
+    pub body: String,
+    /// 端口变化或端点对应的端口
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// 新设备或端点的地址
+    #[serde(default)]
+    pub host: Option<String>,
+    /// 频繁重启提醒：统计窗口内的重启次数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restarts: Option<u32>,
+    /// 证书到期提醒：剩余天数，已过期时为 0 或负数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_left: Option<i64>,
+}
+
+impl MonitorAlert {
+    /// 按通知偏好判断是否需要发送系统通知
+    pub fn is_enabled(&self, prefs: &NotificationSettings) -> bool {
+        prefs.enabled
+            && match self.kind {
+                AlertKind::NewPort | AlertKind::PortClosed | AlertKind::Flapping => {
+                    prefs.port_changes
+                }
+                AlertKind::NewDevice => prefs.new_devices,
+                AlertKind::EndpointDown | AlertKind::EndpointUp => prefs.endpoint_down,
+                AlertKind::CertExpiring => prefs.cert_expiry,
+            }
+    }
+}
+
+/// 端口变化记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortChange {
+    pub port: u16,
+    pub process: String,
+    pub pid: String,
+    /// true 为新开启，false 为已关闭
+    pub opened: bool,
+    pub time: String,
+}
+
+/// 关注端点的检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointStatus {
+    pub host: String,
+    pub port: u16,
+    pub up: bool,
+}
+
+/// 关注证书的最近一次检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateStatus {
+    pub host: String,
+    pub port: u16,
+    pub valid_until: String,
+    pub days_until_expiry: i64,
+    /// 连接或解析失败的原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 监控状态快照
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MonitorSnapshot {
+    /// 最近一次扫描时间
+    pub last_scan: Option<String>,
+    pub listening_ports: Vec<PortInfo>,
+    /// 运行中的容器数，容器运行时不可用时为空
+    pub container_count: Option<usize>,
+    /// 已发现的局域网设备数，未开启局域网监控时为空
+    pub device_count: Option<usize>,
+    pub endpoints: Vec<EndpointStatus>,
+    /// 最近的端口变化，新的在前
+    pub recent_changes: Vec<PortChange>,
+    /// 关注证书的最近一次检查结果
+    #[serde(default)]
+    pub certificates: Vec<CertificateStatus>,
+}
+
+/// 一轮监控的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorTick {
+    pub snapshot: MonitorSnapshot,
+    pub alerts: Vec<MonitorAlert>,
+    /// 本轮新触发的规则
+    #[serde(default)]
+    pub rule_hits: Vec<RuleHit>,
+}
+
+/// (进程名, 端口) → (PID, 最后一次监听的时间)
+type Bindings = HashMap<(String, u16), (Option<String>, Instant)>;
+
+/// 监控基线，跨轮次比较端口、设备和端点状态
+#[derive(Debug, Default)]
+pub struct Monitor {
+    ports: Option<BTreeMap<u16, PortInfo>>,
+    devices: Option<HashSet<String>>,
+    endpoints: HashMap<MonitoredEndpoint, bool>,
+    recent_changes: VecDeque<PortChange>,
+    container_count: Option<usize>,
+    device_count: Option<usize>,
+    endpoint_statuses: Vec<EndpointStatus>,
+    last_lan_scan: Option<Instant>,
+    rules: RuleEngine,
+    /// (进程名, 端口) 上一轮的 PID 和最后一次监听的时间；已停止的 PID 为空，超过统计窗口后不再保留
+    bindings: Option<Bindings>,
+    /// 统计窗口内每次重新绑定的时间
+    restarts: HashMap<(String, u16), VecDeque<Instant>>,
+    /// 已提醒过频繁重启、尚未平复的绑定
+    flapping: HashSet<(String, u16)>,
+    /// 每个证书端点已提醒过的最低阈值（天），已过期为 0；续期后清除
+    cert_levels: HashMap<MonitoredEndpoint, u32>,
+    certificate_statuses: Vec<CertificateStatus>,
+    last_cert_check: Option<Instant>,
+}
+
+impl Monitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 比较监听端口，返回新开启和已关闭端口的提醒
+    pub fn observe_ports(&mut self, ports: &[PortInfo], lang: Lang) -> Vec<MonitorAlert> {
+        let mut current: BTreeMap<u16, PortInfo> = BTreeMap::new();
+        for port in ports {
+            current.entry(port.port).or_insert_with(|| port.clone());
+        }
+
+        let Some(previous) = self.ports.replace(current) else {
+            return Vec::new();
+        };
+        let current = self.ports.as_ref().expect("刚写入的端口基线");
+        let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let opened = current
+            .values()
+            .filter(|p| !previous.contains_key(&p.port))
+            .map(|p| (p, true));
+        let closed = previous
+            .values()
+            .filter(|p| !current.contains_key(&p.port))
+            .map(|p| (p, false));
+
+        let mut alerts = Vec::new();
+        for (info, is_open) in opened.chain(closed) {
+            let (kind, msg) = if is_open {
+                (AlertKind::NewPort, Msg::MonitorNewPort)
+            } else {
+                (AlertKind::PortClosed, Msg::MonitorPortClosed)
+            };
+            alerts.push(MonitorAlert {
+                kind,
+                title: tr_args(lang, msg, &[&info.port]),
+                body: tr_args(lang, Msg::MonitorPortOwner, &[&info.process, &info.pid]),
+                port: Some(info.port),
+                host: None,
+                restarts: None,
+                days_left: None,
+            });
+            self.recent_changes.push_front(PortChange {
+                port: info.port,
+                process: info.process.clone(),
+                pid: info.pid.clone(),
+                opened: is_open,
+                time: time.clone(),
+            });
+        }
+        self.recent_changes.truncate(MAX_RECENT_CHANGES);
+        alerts
+    }
+
+    /// 记录 (进程名, 端口) 的绑定，统计窗口内重新绑定达到 `threshold` 次时提醒一次，
+    /// 次数回落到阈值以下后才会再次提醒；`threshold` 为 0 时只记录不提醒
+    pub fn observe_restarts(
+        &mut self,
+        ports: &[PortInfo],
+        now: Instant,
+        threshold: u32,
+        window: Duration,
+        lang: Lang,
+    ) -> Vec<MonitorAlert> {
+        let mut current = Bindings::new();
+        for port in ports {
+            current
+                .entry((port.process.clone(), port.port))
+                .or_insert_with(|| (Some(port.pid.clone()), now));
+        }
+
+        let Some(previous) = self.bindings.take() else {
+            self.bindings = Some(current);
+            return Vec::new();
+        };
+        for (key, (pid, _)) in &current {
+            let restarted = match previous.get(key) {
+                Some((Some(previous_pid), _)) => Some(previous_pid) != pid.as_ref(),
+                Some((None, _)) => true,
+                None => false,
+            };
+            if restarted {
+                self.restarts.entry(key.clone()).or_default().push_back(now);
+            }
+        }
+        // 已停止的绑定保留为空，统计窗口内重新出现时计为一次重启；停止超过窗口的不再保留
+        for (key, (_, last_seen)) in previous {
+            if !current.contains_key(&key) && now.duration_since(last_seen) <= window {
+                current.insert(key, (None, last_seen));
+            }
+        }
+        self.bindings = Some(current);
+
+        let mut alerts = Vec::new();
+        self.restarts.retain(|key, times| {
+            while times
+                .front()
+                .is_some_and(|time| now.duration_since(*time) > window)
+            {
+                times.pop_front();
+            }
+            let count = times.len() as u32;
+            if threshold == 0 || count < threshold {
+                self.flapping.remove(key);
+            } else if self.flapping.insert(key.clone()) {
+                let (process, port) = key;
+                alerts.push(MonitorAlert {
+                    kind: AlertKind::Flapping,
+                    title: tr_args(lang, Msg::MonitorFlapping, &[port]),
+                    body: tr_args(
+                        lang,
+                        Msg::MonitorFlappingDetail,
+                        &[process, &count, &window.as_secs()],
+                    ),
+                    port: Some(*port),
+                    host: None,
+                    restarts: Some(count),
+                    days_left: None,
+                });
+            }
+            !times.is_empty()
+        });
+        alerts.sort_by_key(|alert| alert.port);
+        alerts
+    }
+
+    /// 记录局域网设备，返回首次出现的在线设备的提醒
+    ///
+    /// 基线为历史上见过的全部设备，离线后重新上线不会重复提醒
+    pub fn observe_devices(&mut self, devices: &[NetworkDevice], lang: Lang) -> Vec<MonitorAlert> {
+        let online: Vec<&NetworkDevice> = devices.iter().filter(|d| d.is_online).collect();
+        self.device_count = Some(online.len());
+
+        let Some(known) = self.devices.as_mut() else {
+            self.devices = Some(online.iter().map(|d| d.ip.clone()).collect());
+            return Vec::new();
+        };
+
+        online
+            .into_iter()
+            .filter(|device| known.insert(device.ip.clone()))
+            .map(|device| {
+                let mut body = device.ip.clone();
+                if let Some(name) = device.hostname.as_ref().or(device.mac.as_ref()) {
+                    body.push_str(&format!(" ({name})"));
+                }
+                MonitorAlert {
+                    kind: AlertKind::NewDevice,
+                    title: tr(lang, Msg::MonitorNewDevice).to_string(),
+                    body,
+                    port: None,
+                    host: Some(device.ip.clone()),
+                    restarts: None,
+                    days_left: None,
+                }
+            })
+            .collect()
+    }
+
+    /// 记录关注端点的状态，返回变为不可达或恢复的提醒
+    ///
+    /// 首次检查即不可达的端点也会提醒
+    pub fn observe_endpoints(
+        &mut self,
+        statuses: &[EndpointStatus],
+        lang: Lang,
+    ) -> Vec<MonitorAlert> {
+        let mut alerts = Vec::new();
+        let mut current = HashMap::new();
+        for status in statuses {
+            let endpoint = MonitoredEndpoint {
+                host: status.host.clone(),
+                port: status.port,
+            };
+            let was_up = self.endpoints.get(&endpoint).copied();
+            let target = format!("{}:{}", status.host, status.port);
+            match (was_up, status.up) {
+                (Some(true) | None, false) => alerts.push(MonitorAlert {
+                    kind: AlertKind::EndpointDown,
+                    title: tr_args(lang, Msg::MonitorEndpointDown, &[&target]),
+                    body: tr(lang, Msg::MonitorEndpointDetail).to_string(),
+                    port: Some(status.port),
+                    host: Some(status.host.clone()),
+                    restarts: None,
+                    days_left: None,
+                }),
+                (Some(false), true) => alerts.push(MonitorAlert {
+                    kind: AlertKind::EndpointUp,
+                    title: tr_args(lang, Msg::MonitorEndpointUp, &[&target]),
+                    body: tr(lang, Msg::MonitorEndpointRecovered).to_string(),
+                    port: Some(status.port),
+                    host: Some(status.host.clone()),
+                    restarts: None,
+                    days_left: None,
+                }),
+                _ => {}
+            }
+            current.insert(endpoint, status.up);
+        }
+        // 从设置中移除的端点不再保留状态
+        self.endpoints = current;
+        self.endpoint_statuses = statuses.to_vec();
+        alerts
+    }
+
+    /// 记录关注证书的检查结果，剩余天数跌破比上次更低的阈值或已过期时提醒
+    ///
+    /// 首次检查即处于阈值内的证书也会提醒；检查失败的证书保持原状态
+    pub fn observe_certificates(
+        &mut self,
+        certs: &[SslCertInfo],
+        warn_days: &[u32],
+        lang: Lang,
+    ) -> Vec<MonitorAlert> {
+        let mut alerts = Vec::new();
+        let mut levels = HashMap::new();
+        for cert in certs {
+            let endpoint = MonitoredEndpoint {
+                host: cert.host.clone(),
+                port: cert.port,
+            };
+            let previous = self.cert_levels.get(&endpoint).copied();
+            if cert.error.is_some() {
+                if let Some(level) = previous {
+                    levels.insert(endpoint, level);
+                }
+                continue;
+            }
+            let days = cert.days_until_expiry;
+            let level = if cert.is_expired || days <= 0 {
+                Some(0)
+            } else {
+                warn_days
+                    .iter()
+                    .copied()
+                    .filter(|threshold| days <= i64::from(*threshold))
+                    .min()
+            };
+            let Some(level) = level else {
+                continue;
+            };
+            if previous.is_none_or(|previous| level < previous) {
+                let target = format!("{}:{}", cert.host, cert.port);
+                let title = if level == 0 {
+                    tr_args(lang, Msg::MonitorCertExpired, &[&target])
+                } else {
+                    tr_args(lang, Msg::MonitorCertExpiring, &[&target, &days])
+                };
+                let subject = if cert.subject.is_empty() {
+                    &target
+                } else {
+                    &cert.subject
+                };
+                alerts.push(MonitorAlert {
+                    kind: AlertKind::CertExpiring,
+                    title,
+                    body: tr_args(lang, Msg::MonitorCertDetail, &[subject, &cert.valid_until]),
+                    port: Some(cert.port),
+                    host: Some(cert.host.clone()),
+                    restarts: None,
+                    days_left: Some(days),
+                });
+            }
+            levels.insert(endpoint, level);
+        }
+        // 续期或从设置中移除的证书不再保留状态
+        self.cert_levels = levels;
+        self.certificate_statuses = certs
+            .iter()
+            .map(|cert| CertificateStatus {
+                host: cert.host.clone(),
+                port: cert.port,
+                valid_until: cert.valid_until.clone(),
+                days_until_expiry: cert.days_until_expiry,
+                error: cert.error.clone(),
+            })
+            .collect();
+        alerts
+    }
+
+    /// 执行一轮监控并更新 [`latest_snapshot`]
+    pub fn tick(&mut self, settings: &Settings) -> MonitorTick {
+        let lang = settings.lang();
+        let mut alerts = Vec::new();
+
+        let ports = match crate::core::try_get_listening_ports() {
+            Ok(ports) => {
+                alerts.extend(self.observe_ports(&ports, lang));
+                alerts.extend(self.observe_restarts(
+                    &ports,
+                    Instant::now(),
+                    settings.monitor.flap_threshold,
+                    Duration::from_secs(settings.monitor.flap_window_secs),
+                    lang,
+                ));
+                Some(ports)
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, "后台监控读取监听端口失败");
+                None
+            }
+        };
+        let mut devices = None;
+
+        self.container_count = if crate::docker::is_docker_available() {
+            crate::docker::try_get_docker_containers()
+                .ok()
+                .map(|containers| {
+                    containers
+                        .iter()
+                        .filter(|c| c.state.is_empty() || c.state == "running")
+                        .count()
+                })
+        } else {
+            None
+        };
+
+        if settings.monitor.scan_lan {
+            let interval = Duration::from_secs(settings.monitor.lan_interval_secs);
+            if self
+                .last_lan_scan
+                .is_none_or(|last| last.elapsed() >= interval)
+            {
+                self.last_lan_scan = Some(Instant::now());
+                match lan_subnet(settings) {
+                    Some(subnet) => {
+                        let found = crate::network::discover_devices(&subnet);
+                        alerts.extend(self.observe_devices(&found, lang));
+                        devices = Some(found);
+                    }
+                    None => tracing::warn!("后台监控无法确定局域网子网，跳过设备发现"),
+                }
+            }
+        } else {
+            self.devices = None;
+            self.device_count = None;
+            self.last_lan_scan = None;
+        }
+
+        let statuses: Vec<EndpointStatus> = settings
+            .monitor
+            .endpoints
+            .iter()
+            .map(|endpoint| check_endpoint(endpoint, settings.scan.connect_timeout_ms))
+            .collect();
+        alerts.extend(self.observe_endpoints(&statuses, lang));
+
+        if settings.monitor.certificates.is_empty() {
+            self.cert_levels.clear();
+            self.certificate_statuses.clear();
+            self.last_cert_check = None;
+        } else {
+            let interval = Duration::from_secs(settings.monitor.cert_interval_secs);
+            if self
+                .last_cert_check
+                .is_none_or(|last| last.elapsed() >= interval)
+            {
+                self.last_cert_check = Some(Instant::now());
+                let certs: Vec<SslCertInfo> = settings
+                    .monitor
+                    .certificates
+                    .iter()
+                    .map(|e| crate::ssl::check_ssl_cert(e.host.clone(), Some(e.port)))
+                    .collect();
+                alerts.extend(self.observe_certificates(
+                    &certs,
+                    &settings.monitor.cert_warn_days,
+                    lang,
+                ));
+            }
+        }
+
+        let rule_hits = self.rules.evaluate(
+            &crate::rules::current(),
+            ports.as_deref(),
+            devices.as_deref(),
+            &crate::policy::current(),
+            lang,
+        );
+        if !rule_hits.is_empty() {
+            tracing::info!(count = rule_hits.len(), "规则触发");
+        }
+
+        let snapshot = self.snapshot();
+        if let Ok(mut latest) = LATEST.lock() {
+            *latest = Some(snapshot.clone());
+        }
+        if !alerts.is_empty() {
+            tracing::info!(count = alerts.len(), "后台监控产生提醒");
+        }
+        MonitorTick {
+            snapshot,
+            alerts,
+            rule_hits,
+        }
+    }
+
+    /// 当前状态快照
+    pub fn snapshot(&self) -> MonitorSnapshot {
+        MonitorSnapshot {
+            last_scan: Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+            listening_ports: self
+                .ports
+                .as_ref()
+                .map(|ports| ports.values().cloned().collect())
+                .unwrap_or_default(),
+            container_count: self.container_count,
+            device_count: self.device_count,
+            endpoints: self.endpoint_statuses.clone(),
+            recent_changes: self.recent_changes.iter().cloned().collect(),
+            certificates: self.certificate_statuses.clone(),
+        }
+    }
+}
+
+/// 最近一轮监控的结果；监控未运行过时为空
+pub fn latest_snapshot() -> Option<MonitorSnapshot> {
+    LATEST.lock().ok().and_then(|latest| latest.clone())
+}
+
+/// 运行后台监控，阻塞直到 `stop` 被置位
+///
+/// 每轮开始时重新读取设置，修改间隔或关注端点后无需重启监控
+pub fn watch<F>(stop: &AtomicBool, mut on_tick: F)
+where
+    F: FnMut(&MonitorTick),
+{
+    tracing::info!("后台监控已启动");
+    let mut monitor = Monitor::new();
+    while !stop.load(Ordering::Relaxed) {
+        let settings = crate::settings::current();
+        let tick = monitor.tick(&settings);
+        on_tick(&tick);
+
+        let interval = Duration::from_secs(settings.monitor.interval_secs);
+        let mut waited = Duration::ZERO;
+        while waited < interval && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(STOP_POLL_INTERVAL);
+            waited += STOP_POLL_INTERVAL;
+        }
+    }
+    tracing::info!("后台监控已停止");
+}
+
+/// 局域网监控的子网：设置优先，其次本机所在网段
+pub(crate) fn lan_subnet(settings: &Settings) -> Option<String> {
+    settings
+        .network
+        .default_subnet
+        .clone()
+        .or_else(crate::network::get_current_subnet)
+}
+
+/// 尝试建立 TCP 连接检查端点是否可达
+fn check_endpoint(endpoint: &MonitoredEndpoint, timeout_ms: u64) -> EndpointStatus {
+    let timeout = Duration::from_millis(timeout_ms);
+    let up = (endpoint.host.as_str(), endpoint.port)
+        .to_socket_addrs()
+        .map(|mut addrs| addrs.any(|addr| TcpStream::connect_timeout(&addr, timeout).is_ok()))
+        .unwrap_or(false);
+    EndpointStatus {
+        host: endpoint.host.clone(),
+        port: endpoint.port,
+        up,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, process: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "TCP".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "42".to_string(),
+            process: process.to_string(),
+            user: "me".to_string(),
+            command: None,
+        }
+    }
+
+    fn device(ip: &str, is_online: bool) -> NetworkDevice {
+        NetworkDevice {
+            ip: ip.to_string(),
+            mac: None,
+            hostname: Some("printer".to_string()),
+            is_online,
+            interface_index: None,
+            rtt_ms: None,
+            tags: Vec::new(),
+            note: None,
+        }
+    }
+
+    fn endpoint(up: bool) -> EndpointStatus {
+        EndpointStatus {
+            host: "db.local".to_string(),
+            port: 5432,
+            up,
+        }
+    }
+
+    #[test]
+    fn test_observe_ports_reports_changes_after_baseline() {
+        let mut monitor = Monitor::new();
+        assert!(monitor
+            .observe_ports(&[port(80, "nginx"), port(5432, "postgres")], Lang::En)
+            .is_empty());
+
+        let alerts = monitor.observe_ports(&[port(80, "nginx"), port(3000, "node")], Lang::En);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].kind, AlertKind::NewPort);
+        assert_eq!(alerts[0].title, "New listening port 3000");
+        assert_eq!(alerts[0].body, "node (PID 42)");
+        assert_eq!(alerts[1].kind, AlertKind::PortClosed);
+
+        let snapshot = monitor.snapshot();
+        assert_eq!(snapshot.listening_ports.len(), 2);
+        assert_eq!(snapshot.recent_changes.len(), 2);
+        assert_eq!(snapshot.recent_changes[0].port, 5432);
+        assert!(!snapshot.recent_changes[0].opened);
+    }
+
+    #[test]
+    fn test_observe_devices_alerts_once_per_new_device() {
+        let mut monitor = Monitor::new();
+        assert!(monitor
+            .observe_devices(&[device("192.168.1.1", true)], Lang::Zh)
+            .is_empty());
+
+        let devices = [device("192.168.1.1", true), device("192.168.1.9", true)];
+        let alerts = monitor.observe_devices(&devices, Lang::Zh);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].body, "192.168.1.9 (printer)");
+
+        monitor.observe_devices(&[device("192.168.1.1", true)], Lang::Zh);
+        assert!(monitor.observe_devices(&devices, Lang::Zh).is_empty());
+        assert!(monitor
+            .observe_devices(&[device("192.168.1.20", false)], Lang::Zh)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_observe_endpoints_alerts_on_transitions() {
+        let mut monitor = Monitor::new();
+        assert!(monitor
+            .observe_endpoints(&[endpoint(true)], Lang::En)
+            .is_empty());
+
+        let alerts = monitor.observe_endpoints(&[endpoint(false)], Lang::En);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::EndpointDown);
+        assert_eq!(alerts[0].title, "db.local:5432 is down");
+        assert!(monitor
+            .observe_endpoints(&[endpoint(false)], Lang::En)
+            .is_empty());
+
+        let alerts = monitor.observe_endpoints(&[endpoint(true)], Lang::En);
+        assert_eq!(alerts[0].kind, AlertKind::EndpointUp);
+
+        let mut fresh = Monitor::new();
+        assert_eq!(
+            fresh.observe_endpoints(&[endpoint(false)], Lang::En).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_observe_restarts_alerts_once_when_flapping() {
+        let window = Duration::from_secs(600);
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let pid = |pid: &str| PortInfo {
+            pid: pid.to_string(),
+            ..port(8080, "api")
+        };
+        let mut monitor = Monitor::new();
+        let mut observe = |ports: &[PortInfo], secs: u64| {
+            monitor.observe_restarts(ports, at(secs), 3, window, Lang::En)
+        };
+
+        assert!(observe(&[pid("1")], 0).is_empty());
+        // 换 PID、停止后在统计窗口内重新出现都算一次重启；其他进程首次出现不算
+        assert!(observe(&[pid("2"), port(5432, "postgres")], 30).is_empty());
+        assert!(observe(&[port(5432, "postgres")], 60).is_empty());
+        assert!(observe(&[pid("3"), port(5432, "postgres")], 90).is_empty());
+        let alerts = observe(&[pid("4")], 120);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::Flapping);
+        assert_eq!(alerts[0].title, "Port 8080 is flapping");
+        assert_eq!(alerts[0].body, "api restarted 3 times within 600 s");
+        assert_eq!(alerts[0].restarts, Some(3));
+
+        // 持续重启不重复提醒，窗口内次数回落后再次达到阈值才会提醒
+        assert!(observe(&[pid("5")], 150).is_empty());
+        assert!(observe(&[pid("5")], 700).is_empty());
+        assert!(observe(&[pid("5")], 1000).is_empty());
+        assert!(observe(&[pid("6")], 1010).is_empty());
+        assert!(observe(&[pid("7")], 1020).is_empty());
+        assert_eq!(observe(&[pid("8")], 1030).len(), 1);
+
+        let mut quiet = Monitor::new();
+        quiet.observe_restarts(&[pid("1")], at(0), 0, window, Lang::En);
+        for (i, secs) in [10, 20, 30, 40].into_iter().enumerate() {
+            let ports = [pid(&i.to_string())];
+            assert!(quiet
+                .observe_restarts(&ports, at(secs), 0, window, Lang::En)
+                .is_empty());
+        }
+    }
+
+    #[test]
+    fn test_observe_restarts_counts_crash_loop_without_pid_swap() {
+        let window = Duration::from_secs(600);
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let pid = |pid: &str| PortInfo {
+            pid: pid.to_string(),
+            ..port(8080, "api")
+        };
+        let mut monitor = Monitor::new();
+
+        // 每次都先停止一轮以上，再以新 PID 重新监听
+        let mut alerts = Vec::new();
+        for (i, secs) in [0, 20, 40, 60, 80].into_iter().enumerate() {
+            let up = [pid(&i.to_string())];
+            alerts.extend(monitor.observe_restarts(&up, at(secs), 3, window, Lang::En));
+            alerts.extend(monitor.observe_restarts(&[], at(secs + 5), 3, window, Lang::En));
+            alerts.extend(monitor.observe_restarts(&[], at(secs + 10), 3, window, Lang::En));
+        }
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].restarts, Some(3));
+
+        // 停止超过统计窗口后重新出现不算重启
+        let mut slow = Monitor::new();
+        slow.observe_restarts(&[pid("1")], at(0), 1, window, Lang::En);
+        slow.observe_restarts(&[], at(10), 1, window, Lang::En);
+        slow.observe_restarts(&[], at(700), 1, window, Lang::En);
+        assert!(slow
+            .observe_restarts(&[pid("2")], at(710), 1, window, Lang::En)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_observe_certificates_alerts_per_threshold() {
+        let cert = |days: i64, error: Option<&str>| SslCertInfo {
+            host: "example.com".to_string(),
+            port: 443,
+            subject: "CN=example.com".to_string(),
+            issuer: "CN=R3".to_string(),
+            valid_from: String::new(),
+            valid_until: "2026-11-01".to_string(),
+            is_valid: days > 0,
+            is_expired: days <= 0,
+            is_self_signed: false,
+            days_until_expiry: days,
+            signature_algorithm: String::new(),
+            version: String::new(),
+            serial_number: String::new(),
+            key_size: None,
+            certificate_chain: Vec::new(),
+            tls_version: String::new(),
+            cipher_suite: None,
+            error: error.map(str::to_string),
+        };
+        let mut monitor = Monitor::new();
+        let warn_days = [30, 7, 1];
+        let mut observe = |days: i64| {
+            monitor
+                .observe_certificates(&[cert(days, None)], &warn_days, Lang::En)
+                .into_iter()
+                .map(|alert| alert.title)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(observe(40).is_empty());
+        assert_eq!(
+            observe(25),
+            vec!["Certificate for example.com:443 expires in 25 days"]
+        );
+        assert!(observe(20).is_empty());
+        assert_eq!(observe(6).len(), 1);
+        assert!(observe(6).is_empty());
+        assert_eq!(
+            observe(0),
+            vec!["Certificate for example.com:443 has expired"]
+        );
+        assert!(observe(-1).is_empty());
+        // 续期后重新计算
+        assert!(observe(90).is_empty());
+        assert_eq!(observe(29).len(), 1);
+
+        // 检查失败不改变状态，也不会在恢复后重复提醒
+        let failed =
+            monitor.observe_certificates(&[cert(0, Some("timeout"))], &warn_days, Lang::En);
+        assert!(failed.is_empty());
+        assert_eq!(
+            monitor.snapshot().certificates[0].error.as_deref(),
+            Some("timeout")
+        );
+        let alerts = monitor.observe_certificates(&[cert(28, None)], &warn_days, Lang::En);
+        assert!(alerts.is_empty());
+
+        let alerts = Monitor::new().observe_certificates(&[cert(3, None)], &warn_days, Lang::En);
+        assert_eq!(alerts[0].kind, AlertKind::CertExpiring);
+        assert_eq!(alerts[0].days_left, Some(3));
+        assert_eq!(alerts[0].body, "CN=example.com is valid until 2026-11-01");
+    }
+
+    #[test]
+    fn test_alert_is_enabled_follows_preferences() {
+        let alert = MonitorAlert {
+            kind: AlertKind::NewDevice,
+            title: String::new(),
+            body: String::new(),
+            port: None,
+            host: None,
+            restarts: None,
+            days_left: None,
+        };
+        let mut prefs = NotificationSettings::default();
+        assert!(alert.is_enabled(&prefs));
+        prefs.new_devices = false;
+        assert!(!alert.is_enabled(&prefs));
+        prefs.new_devices = true;
+        prefs.enabled = false;
+        assert!(!alert.is_enabled(&prefs));
+    }
+}
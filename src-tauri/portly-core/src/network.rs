@@ -0,0 +1,3685 @@
+//! Portly 网络扫描模块 - 局域网设备发现和端口扫描
+//!
+//! 支持 macOS, Linux, Windows
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::{
+    run_command, run_command_streaming, CommandRunner, Platform, SystemRunner,
+};
+use crate::policy::{PolicyMatch, PortPolicy};
+use crate::security_headers::{self, SecurityHeaders};
+use crate::settings;
+use crate::vulns::{self, VersionHint};
+use crate::web_fingerprint;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream as TokioTcpStream;
+use tokio::time::timeout;
+
+/// 网络接口信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkInterface {
+    pub name: String,
+    /// 手动添加的子网为空
+    pub ip: String,
+    pub netmask: String,
+    /// 按系统报告的前缀长度计算的网段
+    pub subnet: String,
+    #[serde(default)]
+    pub prefix_len: u8,
+    #[serde(default)]
+    pub kind: InterfaceKind,
+}
+
+/// 网络接口类型，列表按此顺序排列
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InterfaceKind {
+    Ethernet,
+    Wifi,
+    Vpn,
+    /// 容器网桥、虚拟机网卡等
+    Virtual,
+    #[default]
+    Other,
+    /// 用户手动添加的子网
+    Manual,
+}
+
+/// 局域网设备信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkDevice {
+    pub ip: String,
+    pub mac: Option<String>,
+    pub hostname: Option<String>,
+    pub is_online: bool,
+    /// 所在网卡的接口索引（Windows 邻居表提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_index: Option<u32>,
+    /// 本次发现时 Ping 的往返时间（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<f32>,
+    /// 资产清单中的标签
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// 远程端口扫描结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemotePort {
+    pub port: u16,
+    pub is_open: bool,
+    pub service: Option<String>,
+    /// 开放端口命中的端口策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<PolicyMatch>,
+}
+
+/// 网络扫描结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkScanResult {
+    pub subnet: String,
+    pub devices: Vec<NetworkDevice>,
+    pub scan_time: String,
+}
+
+/// IPv4 子网计算结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubnetInfo {
+    /// 规范化后的网段，如 `192.168.1.0/24`
+    pub cidr: String,
+    /// 输入的地址，可以是网段内的任意主机
+    pub address: String,
+    pub prefix_len: u8,
+    pub netmask: String,
+    /// 反掩码（ACL、OSPF 中使用）
+    pub wildcard: String,
+    pub netmask_hex: String,
+    /// 按字节分组的二进制掩码
+    pub netmask_binary: String,
+    pub network: String,
+    pub broadcast: String,
+    /// 可用主机范围：/31 为点对点链路两端（RFC 3021），/32 为单个主机
+    pub first_host: String,
+    pub last_host: String,
+    pub host_count: u64,
+    pub total_addresses: u64,
+    /// RFC 1918 私有地址
+    pub is_private: bool,
+}
+
+/// 端口扫描结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortScanResult {
+    pub ip: String,
+    pub ports: Vec<RemotePort>,
+    pub scan_time: String,
+}
+
+pub(crate) const MAX_DISCOVER_HOSTS: usize = 1022;
+
+fn parse_ipv4_cidr(subnet: &str) -> Option<(u32, u8)> {
+    let mut parts = subnet.split('/');
+    let base = parts.next()?;
+    let prefix_len = parts.next()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let ip: Ipv4Addr = base.parse().ok()?;
+    let mask: u8 = prefix_len.parse().ok()?;
+    if mask > 32 {
+        return None;
+    }
+
+    Some((u32::from(ip), mask))
+}
+
+pub(crate) fn subnet_host_addresses(subnet: &str, max_hosts: usize) -> Option<Vec<String>> {
+    let (network_address, mask) = parse_ipv4_cidr(subnet)?;
+    if mask < 22 || mask > 24 {
+        return None;
+    }
+
+    let host_bits = 32 - mask as u32;
+    let total_addrs: u64 = if host_bits == 32 {
+        u64::from(u32::MAX) + 1
+    } else {
+        1u64 << host_bits
+    };
+    if total_addrs <= 2 {
+        return None;
+    }
+
+    let usable_hosts = total_addrs - 2;
+    if usable_hosts > max_hosts as u64 {
+        return None;
+    }
+
+    let network_mask = if mask == 0 { 0 } else { u32::MAX << (32 - mask as u32) };
+    let network_addr = network_address & network_mask;
+    let start = network_addr + 1;
+    let end = match network_addr.checked_add((total_addrs - 1) as u32) {
+        Some(v) => v - 1,
+        None => return None,
+    };
+
+    let mut hosts = Vec::with_capacity(usable_hosts as usize);
+    for ip in start..=end {
+        hosts.push(Ipv4Addr::from(ip).to_string());
+    }
+    Some(hosts)
+}
+
+/// 网络地址与前缀组成的 CIDR，如 `192.168.1.0/24`
+fn ipv4_cidr(ip: Ipv4Addr, prefix_len: u8) -> String {
+    let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+    format!("{}/{}", Ipv4Addr::from(u32::from(ip) & mask), prefix_len)
+}
+
+fn ipv4_netmask(prefix_len: u8) -> String {
+    Ipv4Addr::from(u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0)).to_string()
+}
+
+/// 规范化可用于设备发现的子网（/22–/24），主机位清零；格式不对时返回 None
+pub fn normalize_discover_subnet(subnet: &str) -> Option<String> {
+    let (address, prefix_len) = parse_ipv4_cidr(subnet.trim())?;
+    (22..=24)
+        .contains(&prefix_len)
+        .then(|| ipv4_cidr(Ipv4Addr::from(address), prefix_len))
+}
+
+/// 计算子网信息，支持 `10.0.0.1/8`、`10.0.0.1/255.0.0.0`、`10.0.0.1 255.0.0.0`，
+/// 只写地址时按 /32 计算
+pub fn subnet_info(input: &str) -> AppResult<SubnetInfo> {
+    let invalid = || AppError::validation("子网", "需要 CIDR 格式，例如 192.168.1.0/24");
+    let input = input.trim();
+    let (address, mask) = match input.split_once(['/', ' ']) {
+        Some((address, mask)) => (address, Some(mask.trim())),
+        None => (input, None),
+    };
+    let ip: Ipv4Addr = address.trim().parse().map_err(|_| invalid())?;
+    let prefix_len = match mask {
+        None => 32,
+        Some(mask) => match mask.parse::<u8>() {
+            Ok(prefix_len) if prefix_len <= 32 => prefix_len,
+            Ok(_) => return Err(invalid()),
+            // 点分掩码必须是连续的 1
+            Err(_) => {
+                let bits = u32::from(mask.parse::<Ipv4Addr>().map_err(|_| invalid())?);
+                if bits.leading_ones() + bits.trailing_zeros() != 32 {
+                    return Err(invalid());
+                }
+                bits.leading_ones() as u8
+            }
+        },
+    };
+
+    let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+    let network = u32::from(ip) & mask;
+    let broadcast = network | !mask;
+    let total_addresses = 1u64 << (32 - prefix_len as u32);
+    let (first_host, last_host, host_count) = match prefix_len {
+        32 => (network, network, 1),
+        31 => (network, broadcast, 2),
+        _ => (network + 1, broadcast - 1, total_addresses - 2),
+    };
+    let octets = mask.to_be_bytes();
+    Ok(SubnetInfo {
+        cidr: ipv4_cidr(ip, prefix_len),
+        address: ip.to_string(),
+        prefix_len,
+        netmask: ipv4_netmask(prefix_len),
+        wildcard: Ipv4Addr::from(!mask).to_string(),
+        netmask_hex: format!("0x{mask:08x}"),
+        netmask_binary: octets
+            .iter()
+            .map(|octet| format!("{octet:08b}"))
+            .collect::<Vec<_>>()
+            .join("."),
+        network: Ipv4Addr::from(network).to_string(),
+        broadcast: Ipv4Addr::from(broadcast).to_string(),
+        first_host: Ipv4Addr::from(first_host).to_string(),
+        last_host: Ipv4Addr::from(last_host).to_string(),
+        host_count,
+        total_addresses,
+        is_private: ip.is_private(),
+    })
+}
+
+/// 获取本机网络接口列表：系统报告的 IPv4 地址和前缀长度，之后是设置中手动添加的子网
+pub fn get_local_interfaces() -> Vec<NetworkInterface> {
+    if crate::demo::is_enabled() {
+        return crate::demo::interfaces();
+    }
+    let mut interfaces: Vec<NetworkInterface> = Vec::new();
+    let mut push = |name: String, ip: Option<Ipv4Addr>, prefix_len: u8, kind| {
+        let subnet = match ip {
+            Some(ip) => ipv4_cidr(ip, prefix_len),
+            None => name.clone(),
+        };
+        if interfaces.iter().any(|i| i.subnet == subnet) {
+            return;
+        }
+        interfaces.push(NetworkInterface {
+            name,
+            ip: ip.map(|ip| ip.to_string()).unwrap_or_default(),
+            netmask: ipv4_netmask(prefix_len),
+            subnet,
+            prefix_len,
+            kind,
+        });
+    };
+
+    let mut system = os_interfaces();
+    system.sort_by_key(|(_, _, _, kind)| *kind);
+    // 系统接口读取失败时退回只有地址的列表，按 /24 估计网段
+    if system.is_empty() {
+        if let Ok(all_ips) = local_ip_address::list_afinet_netifas() {
+            for (name, ip) in all_ips {
+                if let IpAddr::V4(ipv4) = ip {
+                    if !ipv4.is_loopback() {
+                        let kind = classify_interface_name(&name);
+                        system.push((name, ipv4, 24, kind));
+                    }
+                }
+            }
+        }
+    }
+    for (name, ip, prefix_len, kind) in system {
+        push(name, Some(ip), prefix_len, kind);
+    }
+
+    for subnet in settings::current().network.manual_subnets {
+        if let Some((_, prefix_len)) = parse_ipv4_cidr(&subnet) {
+            push(subnet, None, prefix_len, InterfaceKind::Manual);
+        }
+    }
+
+    interfaces
+}
+
+/// 系统报告的 IPv4 接口地址（名称、地址、前缀长度、类型），跳过回环和未启用的接口
+#[cfg(not(target_os = "windows"))]
+fn os_interfaces() -> Vec<(String, Ipv4Addr, u8, InterfaceKind)> {
+    #[cfg(target_os = "macos")]
+    let hardware_ports = run_command("networksetup", "网卡类型读取", |cmd| {
+        cmd.arg("-listallhardwareports");
+    })
+    .map(|out| parse_hardware_ports(&out.stdout))
+    .unwrap_or_default();
+
+    let mut result = Vec::new();
+    for iface in pnet::datalink::interfaces() {
+        if !iface.is_up() || iface.is_loopback() {
+            continue;
+        }
+        #[cfg(target_os = "macos")]
+        let kind = hardware_ports
+            .get(&iface.name)
+            .copied()
+            .unwrap_or_else(|| classify_interface_name(&iface.name));
+        #[cfg(target_os = "linux")]
+        let kind = {
+            let sys = std::path::Path::new("/sys/class/net").join(&iface.name);
+            if sys.join("wireless").exists() || sys.join("phy80211").exists() {
+                InterfaceKind::Wifi
+            } else {
+                classify_interface_name(&iface.name)
+            }
+        };
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        let kind = classify_interface_name(&iface.name);
+
+        for network in &iface.ips {
+            if let pnet::ipnetwork::IpNetwork::V4(v4) = network {
+                result.push((iface.name.clone(), v4.ip(), v4.prefix(), kind));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(target_os = "windows")]
+fn os_interfaces() -> Vec<(String, Ipv4Addr, u8, InterfaceKind)> {
+    ip_helper::adapters().unwrap_or_else(|code| {
+        tracing::warn!(code, "GetAdaptersAddresses 调用失败");
+        Vec::new()
+    })
+}
+
+/// 按接口名推断类型（Linux / macOS / BSD 的常见命名）
+pub(crate) fn classify_interface_name(name: &str) -> InterfaceKind {
+    const VPN: &[&str] = &[
+        "tun",
+        "tap",
+        "utun",
+        "wg",
+        "ppp",
+        "ipsec",
+        "tailscale",
+        "zt",
+    ];
+    const VIRTUAL: &[&str] = &[
+        "docker", "br-", "veth", "virbr", "vmnet", "vbox", "bridge", "cni", "flannel", "lxc",
+        "lxd", "podman", "awdl", "llw", "anpi", "vnic",
+    ];
+    const WIFI: &[&str] = &["wlan", "wlp", "wlx", "wifi"];
+    const ETHERNET: &[&str] = &["eth", "en", "em"];
+
+    let name = name.to_ascii_lowercase();
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|p| name.starts_with(p));
+    if starts(VPN) {
+        InterfaceKind::Vpn
+    } else if starts(VIRTUAL) {
+        InterfaceKind::Virtual
+    } else if starts(WIFI) {
+        InterfaceKind::Wifi
+    } else if starts(ETHERNET) {
+        InterfaceKind::Ethernet
+    } else {
+        InterfaceKind::Other
+    }
+}
+
+/// 解析 macOS `networksetup -listallhardwareports`，得到设备名到类型的映射
+#[cfg(any(target_os = "macos", test))]
+fn parse_hardware_ports(stdout: &str) -> HashMap<String, InterfaceKind> {
+    let mut ports = HashMap::new();
+    let mut hardware_port = None;
+    for line in stdout.lines() {
+        if let Some(port) = line.strip_prefix("Hardware Port: ") {
+            hardware_port = Some(port.trim().to_ascii_lowercase());
+        } else if let Some(device) = line.strip_prefix("Device: ") {
+            let Some(port) = hardware_port.take() else {
+                continue;
+            };
+            let kind = if port.contains("wi-fi") || port.contains("airport") {
+                InterfaceKind::Wifi
+            } else if port.contains("bridge") {
+                InterfaceKind::Virtual
+            } else if port.contains("ethernet") || port.contains("lan") {
+                InterfaceKind::Ethernet
+            } else {
+                continue;
+            };
+            ports.insert(device.trim().to_string(), kind);
+        }
+    }
+    ports
+}
+
+/// 按 Windows 适配器的 IfType 和描述推断类型；以太网类型的虚拟网卡、VPN 网卡靠描述区分
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn classify_windows_adapter(if_type: u32, description: &str) -> InterfaceKind {
+    const IF_TYPE_ETHERNET_CSMACD: u32 = 6;
+    const IF_TYPE_PPP: u32 = 23;
+    const IF_TYPE_PROP_VIRTUAL: u32 = 53;
+    const IF_TYPE_IEEE80211: u32 = 71;
+    const IF_TYPE_TUNNEL: u32 = 131;
+
+    const VPN: &[&str] = &[
+        "vpn",
+        "tap-",
+        "wireguard",
+        "tailscale",
+        "zerotier",
+        "wintun",
+    ];
+    const VIRTUAL: &[&str] = &[
+        "hyper-v",
+        "vmware",
+        "virtualbox",
+        "virtual",
+        "wsl",
+        "docker",
+    ];
+
+    let description = description.to_ascii_lowercase();
+    let mentions = |words: &[&str]| words.iter().any(|w| description.contains(w));
+    if mentions(VPN) {
+        return InterfaceKind::Vpn;
+    }
+    if mentions(VIRTUAL) {
+        return InterfaceKind::Virtual;
+    }
+    match if_type {
+        IF_TYPE_IEEE80211 => InterfaceKind::Wifi,
+        IF_TYPE_ETHERNET_CSMACD => InterfaceKind::Ethernet,
+        IF_TYPE_PPP | IF_TYPE_TUNNEL => InterfaceKind::Vpn,
+        IF_TYPE_PROP_VIRTUAL => InterfaceKind::Virtual,
+        _ => InterfaceKind::Other,
+    }
+}
+
+/// 获取当前子网（自动检测）：本机地址所在接口的网段。设备发现只支持 /22–/24，
+/// 前缀不在此范围时取本机所在的 /24
+pub fn get_current_subnet() -> Option<String> {
+    if crate::demo::is_enabled() {
+        return crate::demo::current_subnet();
+    }
+    let Ok(IpAddr::V4(local)) = local_ip_address::local_ip() else {
+        return None;
+    };
+    let prefix_len = os_interfaces()
+        .into_iter()
+        .find(|(_, ip, _, _)| *ip == local)
+        .map(|(_, _, prefix_len, _)| prefix_len)
+        .filter(|prefix_len| (22..=24).contains(prefix_len))
+        .unwrap_or(24);
+    Some(ipv4_cidr(local, prefix_len))
+}
+
+/// 路由表中的一条路由
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteEntry {
+    /// 目标网段（CIDR），默认路由为 `0.0.0.0/0` 或 `::/0`
+    pub destination: String,
+    /// 下一跳；直连网段（On-link、`link#N`）为空
+    pub gateway: Option<String>,
+    /// 出接口名；Windows 的 IPv6 路由只有接口索引
+    pub interface: String,
+    /// 跃点数，macOS 不报告
+    pub metric: Option<u32>,
+    pub ipv6: bool,
+    /// 出接口类型，用来标出走 VPN 的路由
+    #[serde(default)]
+    pub interface_kind: InterfaceKind,
+}
+
+impl RouteEntry {
+    fn new(
+        destination: String,
+        gateway: Option<String>,
+        interface: &str,
+        metric: Option<u32>,
+        ipv6: bool,
+    ) -> Self {
+        Self {
+            destination,
+            gateway,
+            interface: interface.to_string(),
+            metric,
+            ipv6,
+            interface_kind: classify_interface_name(interface),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.destination == "0.0.0.0/0" || self.destination == "::/0"
+    }
+
+    /// `ip` 落在目标网段内时返回前缀长度
+    fn matches(&self, ip: IpAddr) -> Option<u8> {
+        let (network, prefix_len) = self.destination.split_once('/')?;
+        let prefix_len: u8 = prefix_len.parse().ok()?;
+        let matched = match (network.parse::<IpAddr>().ok()?, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) if prefix_len <= 32 => {
+                let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) if prefix_len <= 128 => {
+                let mask = u128::MAX.checked_shl(128 - prefix_len as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        };
+        matched.then_some(prefix_len)
+    }
+}
+
+/// 读取系统路由表，出接口按本机接口列表补全名称和类型（Windows 的 IPv4 路由以接口地址标识出接口）
+pub fn get_routes() -> AppResult<Vec<RouteEntry>> {
+    if crate::demo::is_enabled() {
+        return Ok(crate::demo::routes());
+    }
+    let mut routes = get_routes_with(&SystemRunner, Platform::current())?;
+    let interfaces = get_local_interfaces();
+    for route in &mut routes {
+        if let Some(iface) = interfaces.iter().find(|i| {
+            i.kind != InterfaceKind::Manual
+                && (i.name == route.interface || i.ip == route.interface)
+        }) {
+            route.interface = iface.name.clone();
+            route.interface_kind = iface.kind;
+        }
+    }
+    Ok(routes)
+}
+
+/// 用指定的命令执行器读取路由表，按 `platform` 的输出格式解析；IPv4 路由排在前面
+pub fn get_routes_with(
+    runner: &dyn CommandRunner,
+    platform: Platform,
+) -> AppResult<Vec<RouteEntry>> {
+    let read = |program: &str, args: &[&str]| -> AppResult<String> {
+        let output = runner.run(program, "路由表读取", &mut |cmd| {
+            crate::command_exec::hide_console_window(cmd.args(args));
+        })?;
+        if output.status != 0 {
+            return Err(AppError::command_failed(
+                program,
+                "路由表读取",
+                output.status,
+                output.stderr,
+            ));
+        }
+        Ok(output.stdout)
+    };
+    let mut routes = match platform {
+        Platform::Linux => {
+            let mut routes = parse_ip_route(&read("ip", &["route", "show"])?, false);
+            // 没有 IPv6 的系统上 `ip -6` 可能失败，只保留 IPv4 路由
+            match read("ip", &["-6", "route", "show"]) {
+                Ok(stdout) => routes.extend(parse_ip_route(&stdout, true)),
+                Err(err) => tracing::debug!(error = %err, "读取 IPv6 路由失败"),
+            }
+            routes
+        }
+        Platform::MacOs => parse_netstat_routes(&read("netstat", &["-rn"])?),
+        Platform::Windows => parse_route_print(&read("route", &["print"])?),
+    };
+    routes.sort_by_key(|route| route.ipv6);
+    Ok(routes)
+}
+
+/// 到达 `ip` 实际使用的路由：最长前缀匹配，前缀相同时取跃点数最小、排在前面的一条
+pub fn route_for(routes: &[RouteEntry], ip: IpAddr) -> Option<&RouteEntry> {
+    routes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, route)| {
+            route
+                .matches(ip)
+                .map(|prefix_len| (prefix_len, index, route))
+        })
+        .min_by_key(|(prefix_len, index, route)| {
+            (
+                std::cmp::Reverse(*prefix_len),
+                route.metric.unwrap_or(0),
+                *index,
+            )
+        })
+        .map(|(_, _, route)| route)
+}
+
+/// 默认网关：跃点数最小的 IPv4 默认路由
+pub fn default_gateway(routes: &[RouteEntry]) -> Option<&RouteEntry> {
+    route_for(routes, IpAddr::V4(Ipv4Addr::UNSPECIFIED)).filter(|route| route.is_default())
+}
+
+/// 到达某个目标实际使用的路由
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteLookup {
+    pub target: String,
+    /// 解析出的地址
+    pub ip: String,
+    /// 没有匹配的路由（目标不可达）时为空
+    pub route: Option<RouteEntry>,
+}
+
+/// 解析目标并查出流量会走哪条路由、从哪个接口出去
+pub fn route_to(target: &str) -> AppResult<RouteLookup> {
+    let resolved = resolve_target(target)?;
+    let ip: IpAddr = resolved.ip.parse().map_err(|_| {
+        AppError::validation("目标地址", format!("无效的 IP 地址: {}", resolved.ip))
+    })?;
+    let routes = get_routes()?;
+    Ok(RouteLookup {
+        target: resolved.original,
+        ip: resolved.ip,
+        route: route_for(&routes, ip).cloned(),
+    })
+}
+
+/// 解析 Linux `ip route show` / `ip -6 route show`，跳过 blackhole、unreachable 等不转发的路由
+fn parse_ip_route(stdout: &str, ipv6: bool) -> Vec<RouteEntry> {
+    const NON_FORWARDING: &[&str] = &[
+        "blackhole",
+        "unreachable",
+        "prohibit",
+        "throw",
+        "local",
+        "broadcast",
+        "multicast",
+        "nat",
+    ];
+    let mut routes = Vec::new();
+    for line in stdout.lines() {
+        let mut parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.first() == Some(&"unicast") {
+            parts.remove(0);
+        }
+        let Some(&destination) = parts.first() else {
+            continue;
+        };
+        if NON_FORWARDING.contains(&destination) {
+            continue;
+        }
+        let value = |key: &str| {
+            let index = parts.iter().position(|part| *part == key)?;
+            parts.get(index + 1).copied()
+        };
+        let Some(interface) = value("dev") else {
+            continue;
+        };
+        let destination = match destination {
+            "default" if ipv6 => "::/0".to_string(),
+            "default" => "0.0.0.0/0".to_string(),
+            d if d.contains('/') => d.to_string(),
+            d if ipv6 => format!("{}/128", d),
+            d => format!("{}/32", d),
+        };
+        routes.push(RouteEntry::new(
+            destination,
+            value("via").map(String::from),
+            interface,
+            value("metric").and_then(|m| m.parse().ok()),
+            ipv6,
+        ));
+    }
+    routes
+}
+
+/// 解析 macOS `netstat -rn`：按 `Internet:` / `Internet6:` 分节，Netif 列的位置取自表头。
+/// 跳过 ARP / NDP 生成的主机条目（网关是 MAC 地址）和组播路由
+fn parse_netstat_routes(stdout: &str) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    let mut ipv6 = false;
+    let mut netif_column = None;
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.first().copied() {
+            Some("Internet:") | Some("Internet6:") => {
+                ipv6 = parts[0] == "Internet6:";
+                netif_column = None;
+                continue;
+            }
+            Some("Destination") => {
+                netif_column = parts.iter().position(|part| *part == "Netif");
+                continue;
+            }
+            _ => {}
+        }
+        let Some(column) = netif_column else {
+            continue;
+        };
+        let (Some(&destination), Some(&gateway), Some(&flags), Some(&interface)) =
+            (parts.first(), parts.get(1), parts.get(2), parts.get(column))
+        else {
+            continue;
+        };
+        if flags.contains('m') || crate::arp::normalize_mac(gateway).is_some() {
+            continue;
+        }
+        let Some(destination) = netstat_destination(destination, flags, ipv6) else {
+            continue;
+        };
+        // 没有 G 标志的是直连路由，网关列是 link#N 或本机地址
+        let gateway = flags.contains('G').then(|| gateway.to_string());
+        routes.push(RouteEntry::new(destination, gateway, interface, None, ipv6));
+    }
+    routes
+}
+
+/// 把 netstat 的目标列转为 CIDR：`default`、省略尾部零的 `10.8/24`、按类别推断前缀的 `192.168.1`、
+/// 带 H 标志的主机地址，以及带作用域的 `fe80::%en0/64`
+fn netstat_destination(destination: &str, flags: &str, ipv6: bool) -> Option<String> {
+    if destination == "default" {
+        return Some(if ipv6 { "::/0" } else { "0.0.0.0/0" }.to_string());
+    }
+    let (address, prefix_len) = match destination.split_once('/') {
+        Some((address, prefix_len)) => (address, Some(prefix_len.parse::<u8>().ok()?)),
+        None => (destination, None),
+    };
+    let address = address.split('%').next()?;
+    if ipv6 {
+        let ip: std::net::Ipv6Addr = address.parse().ok()?;
+        let prefix_len = prefix_len.unwrap_or(128);
+        return (prefix_len <= 128).then(|| format!("{}/{}", ip, prefix_len));
+    }
+    let octets: Vec<u8> = address
+        .split('.')
+        .map(|octet| octet.parse().ok())
+        .collect::<Option<_>>()?;
+    if octets.len() > 4 {
+        return None;
+    }
+    let prefix_len = match prefix_len {
+        Some(prefix_len) => prefix_len,
+        None if flags.contains('H') => 32,
+        None => octets.len() as u8 * 8,
+    };
+    if prefix_len > 32 {
+        return None;
+    }
+    let mut full = [0u8; 4];
+    full[..octets.len()].copy_from_slice(&octets);
+    Some(ipv4_cidr(Ipv4Addr::from(full), prefix_len))
+}
+
+/// 解析 Windows `route print` 的活动路由。IPv4 行为“目标 掩码 网关 接口地址 跃点数”，
+/// IPv6 行为“接口索引 跃点数 目标 网关”，目标过长时网关折到下一行；永久路由一节只有四列，不会匹配
+fn parse_route_print(stdout: &str) -> Vec<RouteEntry> {
+    let mut routes = Vec::new();
+    let mut pending_v6: Option<(String, u32, String)> = None;
+    for line in stdout.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if let Some((index, metric, destination)) = pending_v6.take() {
+            if let [gateway] = parts.as_slice() {
+                routes.push(windows_v6_route(&index, metric, destination, gateway));
+                continue;
+            }
+        }
+        match parts.as_slice() {
+            [destination, netmask, gateway, interface, metric] => {
+                let (Ok(destination), Ok(netmask), Ok(_), Ok(metric)) = (
+                    destination.parse::<Ipv4Addr>(),
+                    netmask.parse::<Ipv4Addr>(),
+                    interface.parse::<Ipv4Addr>(),
+                    metric.parse::<u32>(),
+                ) else {
+                    continue;
+                };
+                let prefix_len = u32::from(netmask).leading_ones() as u8;
+                routes.push(RouteEntry::new(
+                    ipv4_cidr(destination, prefix_len),
+                    gateway.parse::<Ipv4Addr>().ok().map(|ip| ip.to_string()),
+                    interface,
+                    Some(metric),
+                    false,
+                ));
+            }
+            [index, metric, destination, rest @ ..]
+                if destination.contains(':') && rest.len() <= 1 =>
+            {
+                let (Ok(_), Ok(metric)) = (index.parse::<u32>(), metric.parse::<u32>()) else {
+                    continue;
+                };
+                match rest {
+                    [gateway] => routes.push(windows_v6_route(
+                        index,
+                        metric,
+                        destination.to_string(),
+                        gateway,
+                    )),
+                    _ => pending_v6 = Some((index.to_string(), metric, destination.to_string())),
+                }
+            }
+            _ => {}
+        }
+    }
+    routes
+}
+
+fn windows_v6_route(index: &str, metric: u32, destination: String, gateway: &str) -> RouteEntry {
+    let gateway = gateway
+        .parse::<std::net::Ipv6Addr>()
+        .ok()
+        .map(|ip| ip.to_string());
+    RouteEntry::new(destination, gateway, index, Some(metric), true)
+}
+
+/// 扫描局域网设备
+pub fn discover_devices(subnet: &str) -> Vec<NetworkDevice> {
+    try_discover_devices_with(subnet, |_, _| Ok(()), |_| {}).unwrap_or_default()
+}
+
+/// 扫描局域网设备，每 Ping 完一台主机回调已探测主机数和总数，每解析完一台在线设备回调该设备；
+/// 进度回调返回错误（如任务被取消）时停止扫描并返回该错误
+#[tracing::instrument(level = "info", skip(on_progress, on_device))]
+pub fn try_discover_devices_with<P, D>(
+    subnet: &str,
+    mut on_progress: P,
+    mut on_device: D,
+) -> AppResult<Vec<NetworkDevice>>
+where
+    P: FnMut(usize, usize) -> AppResult<()>,
+    D: FnMut(&NetworkDevice),
+{
+    let targets = match subnet_host_addresses(subnet, MAX_DISCOVER_HOSTS) {
+        Some(hosts) if !hosts.is_empty() => hosts,
+        _ => {
+            tracing::warn!("子网无法展开为主机列表，跳过设备发现");
+            return Ok(Vec::new());
+        }
+    };
+
+    let target_set: HashSet<String> = targets.iter().cloned().collect();
+    if crate::demo::is_enabled() {
+        let mut devices = crate::demo::devices(&target_set);
+        crate::inventory::annotate_devices(&mut devices);
+        on_progress(targets.len(), targets.len())?;
+        devices.iter().for_each(&mut on_device);
+        return Ok(devices);
+    }
+    let mut gateway = None;
+    if let Some(first_host) = targets.first() {
+        let parts: Vec<&str> = first_host.split('.').collect();
+        if parts.len() == 4 {
+            gateway = Some(format!("{}.{}.{}.1", parts[0], parts[1], parts[2]));
+        }
+    }
+
+    // 先发送 ARP 请求刷新缓存
+    #[cfg(target_os = "macos")]
+    if let Some(gateway_ip) = gateway {
+        let _ = run_command("ping", "ARP 缓存刷新", |cmd| {
+            cmd.args(["-c", "1", "-W", "100"]);
+            cmd.arg(&gateway_ip);
+        })
+        .ok();
+    }
+
+    // 使用 ARP 表获取已知设备（过滤 incomplete）
+    let arp_devices = get_arp_table();
+
+    // 使用 ping 扫描发现新设备（只返回成功响应的），同时记下往返时间
+    let total = targets.len();
+    let ping_results: HashMap<String, f32> = ping_sweep(&targets, |done| on_progress(done, total))?
+        .into_iter()
+        .collect();
+
+    // 合并结果
+    let mut device_map: HashMap<String, NetworkDevice> = HashMap::new();
+
+    // 添加 ARP 表中有效的设备（有 MAC 地址的）
+    for device in arp_devices {
+        if target_set.contains(&device.ip) {
+            // 只添加有有效 MAC 地址的设备
+            if let Some(ref mac) = device.mac {
+                if !mac.contains("incomplete") && mac.len() >= 11 {
+                    device_map.insert(device.ip.clone(), device);
+                }
+            } else {
+                device_map.insert(
+                    device.ip.clone(),
+                    NetworkDevice {
+                        ip: device.ip.clone(),
+                        mac: None,
+                        hostname: None,
+                        is_online: true,
+                        interface_index: device.interface_index,
+                        rtt_ms: None,
+                        tags: Vec::new(),
+                        note: None,
+                    },
+                );
+            }
+        }
+    }
+
+    // 添加 ping 成功响应的设备
+    for ip in ping_results.keys() {
+        if !device_map.contains_key(ip) {
+            device_map.insert(
+                ip.clone(),
+                NetworkDevice {
+                    ip: ip.clone(),
+                    mac: None,
+                    hostname: None,
+                    is_online: true,
+                    interface_index: None,
+                    rtt_ms: None,
+                    tags: Vec::new(),
+                    note: None,
+                },
+            );
+        } else if let Some(d) = device_map.get_mut(ip) {
+            d.is_online = true;
+        }
+    }
+
+    // 标记未响应 ping 的设备为离线
+    for device in device_map.values_mut() {
+        match ping_results.get(&device.ip) {
+            Some(rtt) => device.rtt_ms = Some(*rtt),
+            None => device.is_online = false,
+        }
+    }
+
+    // 只保留在线设备
+    let mut devices: Vec<NetworkDevice> =
+        device_map.into_values().filter(|d| d.is_online).collect();
+
+    // 解析主机名，附上资产清单中的标签
+    for device in &mut devices {
+        if device.hostname.is_none() {
+            device.hostname = resolve_hostname(&device.ip);
+        }
+        crate::inventory::annotate_devices(std::slice::from_mut(device));
+        on_device(device);
+    }
+
+    devices.sort_by(|a, b| {
+        let a_num: u32 =
+            a.ip.split('.')
+                .next_back()
+                .unwrap_or("0")
+                .parse()
+                .unwrap_or(0);
+        let b_num: u32 =
+            b.ip.split('.')
+                .next_back()
+                .unwrap_or("0")
+                .parse()
+                .unwrap_or(0);
+        a_num.cmp(&b_num)
+    });
+
+    Ok(devices)
+}
+
+/// 重新探测单台设备：Ping 一次、读取 ARP 表中的 MAC 并重新解析主机名，不必扫描整个子网；
+/// 不在线时返回 `is_online` 为 false 的设备
+#[tracing::instrument(level = "info")]
+pub fn refresh_device(ip: &str) -> AppResult<NetworkDevice> {
+    let ip = ip.trim();
+    if ip.parse::<Ipv4Addr>().is_err() {
+        return Err(AppError::validation(
+            "目标地址",
+            format!("需要 IPv4 地址：{}", ip),
+        ));
+    }
+    let mut device = if crate::demo::is_enabled() {
+        let targets = HashSet::from([ip.to_string()]);
+        let mut devices = crate::demo::devices(&targets);
+        match devices.pop() {
+            Some(device) => device,
+            None => probed_device(ip, &[], None),
+        }
+    } else {
+        let rtt = ping_host(ip, settings::current().scan.ping_timeout_ms);
+        // Ping 之后再读 ARP 表，刚响应的设备已写入缓存
+        let mut device = probed_device(ip, &get_arp_table(), rtt);
+        if device.is_online {
+            device.hostname = lookup_hostname(ip);
+            crate::cache::shared()
+                .hostnames
+                .insert(ip.to_string(), device.hostname.clone());
+        }
+        device
+    };
+    crate::inventory::annotate_devices(std::slice::from_mut(&mut device));
+    tracing::info!(online = device.is_online, "设备刷新完成");
+    Ok(device)
+}
+
+/// 按单次 Ping 的结果和 ARP 表合成设备；无效的 ARP 条目（incomplete）不计入
+fn probed_device(ip: &str, arp: &[NetworkDevice], rtt: Option<f32>) -> NetworkDevice {
+    let entry = arp.iter().find(|device| device.ip == ip);
+    let mac = entry
+        .and_then(|device| device.mac.clone())
+        .filter(|mac| !mac.contains("incomplete") && mac.len() >= 11);
+    NetworkDevice {
+        ip: ip.to_string(),
+        mac,
+        hostname: None,
+        is_online: rtt.is_some(),
+        interface_index: entry.and_then(|device| device.interface_index),
+        rtt_ms: rtt,
+        tags: Vec::new(),
+        note: None,
+    }
+}
+
+/// 获取 ARP 表；Windows 上通过 IP Helper API 读取（不受系统语言影响），失败时退回 `arp -a`
+fn get_arp_table() -> Vec<NetworkDevice> {
+    #[cfg(windows)]
+    match ip_helper::ip_net_table() {
+        Ok(devices) => return devices,
+        Err(code) => tracing::warn!(code, "GetIpNetTable 调用失败，改用 arp -a"),
+    }
+    read_arp_table(&SystemRunner, Platform::current())
+}
+
+/// 通过 IP Helper API 读取 IPv4 邻居表，失败时返回 Win32 错误码
+#[cfg(windows)]
+pub(crate) fn windows_neighbor_table() -> Result<Vec<NetworkDevice>, u32> {
+    ip_helper::ip_net_table()
+}
+
+/// 通过 IP Helper API 读取网卡表：`GetIfTable` 返回的 MIB_IFTABLE 原始字节，以及适配器接口索引到
+/// 友好名称（如“以太网”“WLAN”）的映射。失败时返回 Win32 错误码
+#[cfg(windows)]
+pub(crate) fn windows_if_table() -> Result<(Vec<u8>, HashMap<u32, String>), u32> {
+    Ok((ip_helper::if_table()?, ip_helper::adapter_names()?))
+}
+
+/// 解析 `GetIpNetTable` 返回的 MIB_IPNETTABLE：4 字节条目数之后是 24 字节的 MIB_IPNETROW
+/// （接口索引、MAC 长度、8 字节 MAC、网络字节序的 IPv4 地址、条目类型）。跳过无效条目和
+/// 尚未解析出 MAC 的条目
+#[cfg(any(windows, test))]
+fn parse_ip_net_table(table: &[u8]) -> Vec<NetworkDevice> {
+    const ROW_SIZE: usize = 24;
+    const TYPE_INVALID: u32 = 2;
+
+    let field = |bytes: &[u8], offset: usize| {
+        u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    };
+    if table.len() < 4 {
+        return Vec::new();
+    }
+    table[4..]
+        .chunks_exact(ROW_SIZE)
+        .take(field(table, 0) as usize)
+        .filter_map(|row| {
+            let mac = &row[8..8 + (field(row, 4) as usize).min(8)];
+            if field(row, 20) == TYPE_INVALID || mac.iter().all(|b| *b == 0) {
+                return None;
+            }
+            let mac: Vec<String> = mac.iter().map(|b| format!("{:02x}", b)).collect();
+            Some(NetworkDevice {
+                ip: Ipv4Addr::new(row[16], row[17], row[18], row[19]).to_string(),
+                mac: Some(mac.join(":")),
+                hostname: None,
+                is_online: true,
+                interface_index: Some(field(row, 0)),
+                rtt_ms: None,
+                tags: Vec::new(),
+                note: None,
+            })
+        })
+        .collect()
+}
+
+/// Windows IP Helper API
+#[cfg(windows)]
+mod ip_helper {
+    use super::{classify_windows_adapter, parse_ip_net_table, InterfaceKind, NetworkDevice};
+    use std::collections::HashMap;
+    use std::ffi::c_void;
+    use std::net::Ipv4Addr;
+
+    const NO_ERROR: u32 = 0;
+    const ERROR_BUFFER_OVERFLOW: u32 = 111;
+    const ERROR_INSUFFICIENT_BUFFER: u32 = 122;
+    const ERROR_NO_DATA: u32 = 232;
+    const AF_UNSPEC: u32 = 0;
+    const AF_INET: u32 = 2;
+    /// GAA_FLAG_SKIP_ANYCAST | GAA_FLAG_SKIP_MULTICAST | GAA_FLAG_SKIP_DNS_SERVER
+    const GAA_FLAGS: u32 = 0x2 | 0x4 | 0x8;
+    const IF_TYPE_SOFTWARE_LOOPBACK: u32 = 24;
+    const IF_OPER_STATUS_UP: i32 = 1;
+
+    /// SOCKET_ADDRESS
+    #[repr(C)]
+    struct SocketAddress {
+        sockaddr: *const u8,
+        length: i32,
+    }
+
+    /// IP_ADAPTER_UNICAST_ADDRESS_LH，按 C 布局声明到需要的字段为止
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct UnicastAddress {
+        length: u32,
+        flags: u32,
+        next: *const UnicastAddress,
+        address: SocketAddress,
+        prefix_origin: i32,
+        suffix_origin: i32,
+        dad_state: i32,
+        valid_lifetime: u32,
+        preferred_lifetime: u32,
+        lease_lifetime: u32,
+        on_link_prefix_length: u8,
+    }
+
+    /// IP_ADAPTER_ADDRESSES_LH，按 C 布局声明到需要的字段为止
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct AdapterAddresses {
+        length: u32,
+        if_index: u32,
+        next: *const AdapterAddresses,
+        adapter_name: *const u8,
+        first_unicast: *const UnicastAddress,
+        first_anycast: *const c_void,
+        first_multicast: *const c_void,
+        first_dns_server: *const c_void,
+        dns_suffix: *const u16,
+        description: *const u16,
+        friendly_name: *const u16,
+        physical_address: [u8; 8],
+        physical_address_length: u32,
+        flags: u32,
+        mtu: u32,
+        if_type: u32,
+        oper_status: i32,
+    }
+
+    #[link(name = "iphlpapi")]
+    extern "system" {
+        fn GetIpNetTable(table: *mut u8, size: *mut u32, order: i32) -> u32;
+        fn GetIfTable(table: *mut u8, size: *mut u32, order: i32) -> u32;
+        fn IcmpCreateFile() -> *mut c_void;
+        fn IcmpCloseHandle(handle: *mut c_void) -> i32;
+        fn IcmpSendEcho(
+            handle: *mut c_void,
+            destination: u32,
+            request: *const c_void,
+            request_size: u16,
+            options: *const c_void,
+            reply: *mut c_void,
+            reply_size: u32,
+            timeout_ms: u32,
+        ) -> u32;
+        fn GetAdaptersAddresses(
+            family: u32,
+            flags: u32,
+            reserved: *mut c_void,
+            addresses: *mut AdapterAddresses,
+            size: *mut u32,
+        ) -> u32;
+    }
+
+    /// ICMP_ECHO_REPLY，`options` 为内联的 IP_OPTION_INFORMATION
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct IcmpEchoReply {
+        address: u32,
+        status: u32,
+        round_trip_time: u32,
+        data_size: u16,
+        reserved: u16,
+        data: *const c_void,
+        options: [u8; 4],
+        options_data: *const u8,
+    }
+
+    /// 读取以 NUL 结尾的 UTF-16 字符串
+    ///
+    /// # Safety
+    /// `text` 为空或指向以 NUL 结尾的 UTF-16 字符串
+    unsafe fn wide_string(text: *const u16) -> String {
+        if text.is_null() {
+            return String::new();
+        }
+        let mut len = 0;
+        while *text.add(len) != 0 {
+            len += 1;
+        }
+        String::from_utf16_lossy(std::slice::from_raw_parts(text, len))
+    }
+
+    /// 已启用适配器的 IPv4 地址（友好名称、地址、前缀长度、类型），失败时返回 Win32 错误码
+    pub(super) fn adapters() -> Result<Vec<(String, Ipv4Addr, u8, InterfaceKind)>, u32> {
+        // 官方建议先用 15 KB 缓冲区，不足时按返回的大小重试
+        let mut size = 15 * 1024u32;
+        for _ in 0..4 {
+            // 以 u64 分配，满足结构体的 8 字节对齐
+            let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+            size = (buffer.len() * 8) as u32;
+            let first = buffer.as_mut_ptr().cast::<AdapterAddresses>();
+            // SAFETY: buffer 至少有 size 字节可写，API 只在 size 范围内写入
+            let code = unsafe {
+                GetAdaptersAddresses(AF_INET, GAA_FLAGS, std::ptr::null_mut(), first, &mut size)
+            };
+            match code {
+                NO_ERROR => {}
+                ERROR_NO_DATA => return Ok(Vec::new()),
+                ERROR_BUFFER_OVERFLOW => continue,
+                code => return Err(code),
+            }
+
+            let mut result = Vec::new();
+            // SAFETY: 成功时 buffer 中是 API 填写的链表，所有指针都指向 buffer 内部或为空，
+            // buffer 在遍历期间一直存活
+            unsafe {
+                let mut adapter = first.cast_const();
+                while !adapter.is_null() {
+                    let a = &*adapter;
+                    adapter = a.next;
+                    if a.if_type == IF_TYPE_SOFTWARE_LOOPBACK || a.oper_status != IF_OPER_STATUS_UP
+                    {
+                        continue;
+                    }
+                    let name = wide_string(a.friendly_name);
+                    let kind = classify_windows_adapter(a.if_type, &wide_string(a.description));
+                    let mut unicast = a.first_unicast;
+                    while !unicast.is_null() {
+                        let u = &*unicast;
+                        unicast = u.next;
+                        let sockaddr = u.address.sockaddr;
+                        // SOCKADDR_IN: 2 字节地址族、2 字节端口、4 字节地址
+                        if sockaddr.is_null()
+                            || u16::from_ne_bytes([*sockaddr, *sockaddr.add(1)]) != AF_INET as u16
+                        {
+                            continue;
+                        }
+                        let ip = Ipv4Addr::new(
+                            *sockaddr.add(4),
+                            *sockaddr.add(5),
+                            *sockaddr.add(6),
+                            *sockaddr.add(7),
+                        );
+                        result.push((name.clone(), ip, u.on_link_prefix_length, kind));
+                    }
+                }
+            }
+            return Ok(result);
+        }
+        Err(ERROR_BUFFER_OVERFLOW)
+    }
+
+    /// 读取 IPv4 邻居表，失败时返回 Win32 错误码
+    pub(super) fn ip_net_table() -> Result<Vec<NetworkDevice>, u32> {
+        let mut size = 0u32;
+        // 邻居表可能在两次调用之间变大，缓冲区不足时按返回的大小重试
+        for _ in 0..4 {
+            // 以 u32 分配，满足 MIB_IPNETTABLE 的 4 字节对齐
+            let mut buffer = vec![0u32; (size as usize).div_ceil(4).max(1)];
+            size = (buffer.len() * 4) as u32;
+            // SAFETY: buffer 至少有 size 字节可写，API 只在 size 范围内写入
+            let code = unsafe { GetIpNetTable(buffer.as_mut_ptr().cast(), &mut size, 1) };
+            match code {
+                NO_ERROR => {
+                    let bytes: Vec<u8> =
+                        buffer.iter().flat_map(|word| word.to_ne_bytes()).collect();
+                    return Ok(parse_ip_net_table(&bytes));
+                }
+                ERROR_NO_DATA => return Ok(Vec::new()),
+                ERROR_INSUFFICIENT_BUFFER => continue,
+                code => return Err(code),
+            }
+        }
+        Err(ERROR_INSUFFICIENT_BUFFER)
+    }
+
+    /// 读取网卡表（MIB_IFTABLE 原始字节），失败时返回 Win32 错误码
+    pub(super) fn if_table() -> Result<Vec<u8>, u32> {
+        let mut size = 0u32;
+        for _ in 0..4 {
+            // 以 u32 分配，满足 MIB_IFTABLE 的 4 字节对齐
+            let mut buffer = vec![0u32; (size as usize).div_ceil(4).max(1)];
+            size = (buffer.len() * 4) as u32;
+            // SAFETY: buffer 至少有 size 字节可写，API 只在 size 范围内写入
+            let code = unsafe { GetIfTable(buffer.as_mut_ptr().cast(), &mut size, 1) };
+            match code {
+                NO_ERROR => return Ok(buffer.iter().flat_map(|word| word.to_ne_bytes()).collect()),
+                ERROR_NO_DATA => return Ok(Vec::new()),
+                ERROR_INSUFFICIENT_BUFFER => continue,
+                code => return Err(code),
+            }
+        }
+        Err(ERROR_INSUFFICIENT_BUFFER)
+    }
+
+    /// 发送一次 ICMP Echo，超时内收到应答时返回往返时间（毫秒）；打不开 ICMP 句柄时返回 Win32 错误码
+    pub(super) fn icmp_echo(ip: Ipv4Addr, timeout_ms: u32) -> Result<Option<f32>, u32> {
+        const ERROR_INVALID_HANDLE: u32 = 6;
+        const IP_SUCCESS: u32 = 0;
+        let request = *b"portly ping sweep";
+
+        // SAFETY: 无参数调用，失败时返回 INVALID_HANDLE_VALUE
+        let handle = unsafe { IcmpCreateFile() };
+        if handle.is_null() || handle as isize == -1 {
+            return Err(ERROR_INVALID_HANDLE);
+        }
+        // 应答缓冲区至少要放下一个 ICMP_ECHO_REPLY、回显的负载和 8 字节的 ICMP 错误信息
+        let size = std::mem::size_of::<IcmpEchoReply>() + request.len() + 8;
+        let mut buffer = vec![0u64; size.div_ceil(8)];
+        // SAFETY: handle 有效；request 和 buffer 在调用期间存活，长度与传入的大小一致
+        let replies = unsafe {
+            let replies = IcmpSendEcho(
+                handle,
+                u32::from_ne_bytes(ip.octets()),
+                request.as_ptr().cast(),
+                request.len() as u16,
+                std::ptr::null(),
+                buffer.as_mut_ptr().cast(),
+                (buffer.len() * 8) as u32,
+                timeout_ms,
+            );
+            IcmpCloseHandle(handle);
+            replies
+        };
+        if replies == 0 {
+            return Ok(None);
+        }
+        // SAFETY: 返回值非 0 时 buffer 开头是 API 填写的 ICMP_ECHO_REPLY，buffer 按 8 字节对齐
+        let reply = unsafe { &*buffer.as_ptr().cast::<IcmpEchoReply>() };
+        Ok((reply.status == IP_SUCCESS).then_some(reply.round_trip_time as f32))
+    }
+
+    /// 全部适配器（含未启用的）接口索引到友好名称的映射，失败时返回 Win32 错误码
+    pub(super) fn adapter_names() -> Result<HashMap<u32, String>, u32> {
+        let mut size = 15 * 1024u32;
+        for _ in 0..4 {
+            let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+            size = (buffer.len() * 8) as u32;
+            let first = buffer.as_mut_ptr().cast::<AdapterAddresses>();
+            // SAFETY: buffer 至少有 size 字节可写，API 只在 size 范围内写入
+            let code = unsafe {
+                GetAdaptersAddresses(AF_UNSPEC, GAA_FLAGS, std::ptr::null_mut(), first, &mut size)
+            };
+            match code {
+                NO_ERROR => {}
+                ERROR_NO_DATA => return Ok(HashMap::new()),
+                ERROR_BUFFER_OVERFLOW => continue,
+                code => return Err(code),
+            }
+
+            let mut names = HashMap::new();
+            // SAFETY: 同 adapters，成功时 buffer 中是 API 填写的链表，遍历期间 buffer 一直存活
+            unsafe {
+                let mut adapter = first.cast_const();
+                while !adapter.is_null() {
+                    let a = &*adapter;
+                    adapter = a.next;
+                    names.insert(a.if_index, wide_string(a.friendly_name));
+                }
+            }
+            return Ok(names);
+        }
+        Err(ERROR_BUFFER_OVERFLOW)
+    }
+}
+
+/// 用指定的命令执行器读取 `arp -a`，按 `platform` 的输出格式解析
+fn read_arp_table(runner: &dyn CommandRunner, platform: Platform) -> Vec<NetworkDevice> {
+    let mut devices = Vec::new();
+
+    let output = runner.run("arp", "ARP 表读取", &mut |cmd| {
+        cmd.args(["-a"]);
+    });
+
+    if let Ok(out) = output {
+        let stdout = out.stdout;
+
+        for line in stdout.lines() {
+            if let Some(device) = parse_arp_line(line, platform) {
+                devices.push(device);
+            }
+        }
+    }
+
+    devices
+}
+
+/// 解析 ARP 行
+fn parse_arp_line(line: &str, platform: Platform) -> Option<NetworkDevice> {
+    // macOS/Linux 格式: hostname (192.168.1.1) at aa:bb:cc:dd:ee:ff
+    // Windows 格式: 192.168.1.1    aa-bb-cc-dd-ee-ff    dynamic
+
+    let parts: Vec<&str> = line.split_whitespace().collect();
+
+    if platform == Platform::Windows {
+        if parts.len() >= 2 {
+            let ip = parts[0];
+            if ip.contains('.') && !ip.starts_with("Interface") {
+                let mac = if parts.len() > 1 {
+                    Some(parts[1].replace('-', ":"))
+                } else {
+                    None
+                };
+                return Some(NetworkDevice {
+                    ip: ip.to_string(),
+                    mac,
+                    hostname: None,
+                    is_online: true,
+                    interface_index: None,
+                    rtt_ms: None,
+                    tags: Vec::new(),
+                    note: None,
+                });
+            }
+        }
+    } else {
+        // 查找 (IP) 格式
+        for (i, part) in parts.iter().enumerate() {
+            if part.starts_with('(') && part.ends_with(')') {
+                let ip = part.trim_matches(|c| c == '(' || c == ')');
+                if ip.contains('.') {
+                    let mac = parts.get(i + 2).map(|s| s.to_string());
+                    let hostname = if i > 0 && !parts[0].starts_with('?') {
+                        Some(parts[0].to_string())
+                    } else {
+                        None
+                    };
+                    return Some(NetworkDevice {
+                        ip: ip.to_string(),
+                        mac,
+                        hostname,
+                        is_online: true,
+                        interface_index: None,
+                        rtt_ms: None,
+                        tags: Vec::new(),
+                        note: None,
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Ping 扫描，每完成一台主机回调已完成的主机数
+fn ping_sweep<F>(targets: &[String], on_progress: F) -> AppResult<Vec<(String, f32)>>
+where
+    F: FnMut(usize) -> AppResult<()>,
+{
+    let scan = settings::current().scan;
+    let timeout_ms = scan.ping_timeout_ms;
+    ping_sweep_with(
+        targets,
+        scan.ping_concurrency,
+        &|ip| ping_host(ip, timeout_ms),
+        on_progress,
+    )
+}
+
+/// 用固定数量的工作线程从队列中取主机逐个 `ping`，同时进行的 Ping 不超过 `workers` 个，
+/// 慢主机不会拖住其他主机；在线主机及其往返时间按 `targets` 的顺序返回。进度回调返回错误时
+/// 不再派发新主机，等进行中的 Ping 结束后返回该错误
+fn ping_sweep_with<F>(
+    targets: &[String],
+    workers: usize,
+    ping: &(dyn Fn(&str) -> Option<f32> + Sync),
+    mut on_progress: F,
+) -> AppResult<Vec<(String, f32)>>
+where
+    F: FnMut(usize) -> AppResult<()>,
+{
+    let next = AtomicUsize::new(0);
+    let stopped = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, targets.len().max(1)) {
+            let tx = tx.clone();
+            let (next, stopped) = (&next, &stopped);
+            scope.spawn(move || {
+                while !stopped.load(Ordering::Relaxed) {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(ip) = targets.get(index) else {
+                        break;
+                    };
+                    if tx.send((index, ping(ip))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        let mut online = vec![None; targets.len()];
+        for (done, (index, rtt)) in rx.iter().enumerate() {
+            online[index] = rtt;
+            if let Err(err) = on_progress(done + 1) {
+                stopped.store(true, Ordering::Relaxed);
+                return Err(err);
+            }
+        }
+        Ok(targets
+            .iter()
+            .zip(online)
+            .filter_map(|(ip, rtt)| Some((ip.clone(), rtt?)))
+            .collect())
+    })
+}
+
+/// Ping 单个主机，在线时返回往返时间（毫秒）
+///
+/// IPv4 目标优先用原生 ICMP Echo（[`icmp_echo`]），不必为每台主机启动一个 `ping` 进程；系统不允许
+/// 打开 ICMP 套接字时（Linux 的 `ping_group_range` 不含当前用户且没有 root 权限）退回 `ping` 命令，
+/// 之后不再尝试
+fn ping_host(ip: &str, timeout_ms: u64) -> Option<f32> {
+    static NATIVE_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+    if let Ok(addr) = ip.parse::<Ipv4Addr>() {
+        if !NATIVE_UNAVAILABLE.load(Ordering::Relaxed) {
+            match icmp_echo(addr, Duration::from_millis(timeout_ms)) {
+                Ok(rtt) => return rtt,
+                Err(err) => {
+                    if !NATIVE_UNAVAILABLE.swap(true, Ordering::Relaxed) {
+                        tracing::info!(error = %err, "无法打开 ICMP 套接字，Ping 扫描改用 ping 命令");
+                    }
+                }
+            }
+        }
+    }
+    ping_host_command(ip, timeout_ms)
+}
+
+/// 用系统 `ping` 命令 Ping 单个主机；输出中读不到延迟时用命令耗时近似
+fn ping_host_command(ip: &str, timeout_ms: u64) -> Option<f32> {
+    let started = Instant::now();
+    let output = run_command("ping", "主机 Ping 检测", |cmd| {
+        #[cfg(target_os = "windows")]
+        cmd.args(["-n", "1", "-w", &timeout_ms.to_string()]);
+
+        #[cfg(target_os = "macos")]
+        cmd.args(["-c", "1", "-W", &timeout_ms.to_string()]);
+
+        // Linux 的 -W 以秒为单位
+        #[cfg(target_os = "linux")]
+        cmd.args(["-c", "1", "-W", &timeout_ms.div_ceil(1000).to_string()]);
+
+        cmd.arg(ip);
+    });
+
+    match output {
+        Ok(out) if out.status == 0 => Some(
+            out.stdout
+                .lines()
+                .find_map(|line| parse_ping_reply(ip, 0, line)?.time_ms)
+                .unwrap_or_else(|| started.elapsed().as_secs_f32() * 1000.0),
+        ),
+        _ => None,
+    }
+}
+
+/// 发送一次 ICMP Echo，超时内收到应答时返回往返时间（毫秒）
+///
+/// 先用无需特权的 ICMP 数据报套接字（macOS 默认可用，Linux 受 `ping_group_range` 限制），不行再用
+/// 原始套接字（需要 root）。只有套接字打不开时返回错误，目标不可达和超时都返回 `Ok(None)`
+#[cfg(unix)]
+fn icmp_echo(ip: Ipv4Addr, timeout: Duration) -> std::io::Result<Option<f32>> {
+    use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+    use std::io::Read;
+    use std::sync::atomic::AtomicU16;
+
+    static SEQUENCE: AtomicU16 = AtomicU16::new(0);
+
+    let icmp = Some(Protocol::ICMPV4);
+    let (socket, raw) = match Socket::new(Domain::IPV4, Type::DGRAM, icmp) {
+        Ok(socket) => (socket, false),
+        Err(_) => (Socket::new(Domain::IPV4, Type::RAW, icmp)?, true),
+    };
+    // Linux 的数据报套接字会把标识符改成套接字自己的编号，只能按序号匹配
+    let check_id = raw || !cfg!(target_os = "linux");
+    let id = std::process::id() as u16;
+    let seq = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+    let started = Instant::now();
+    // connect 之后内核只交付来自目标地址的报文
+    let target = SockAddr::from(SocketAddr::new(IpAddr::V4(ip), 0));
+    if socket.connect(&target).is_err() || socket.send(&icmp_echo_request(id, seq)).is_err() {
+        return Ok(None);
+    }
+
+    let mut buffer = [0u8; 1024];
+    loop {
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() || socket.set_read_timeout(Some(remaining)).is_err() {
+            return Ok(None);
+        }
+        let Ok(len) = (&socket).read(&mut buffer) else {
+            return Ok(None);
+        };
+        match parse_icmp_echo_reply(&buffer[..len]) {
+            Some((reply_id, reply_seq)) if reply_seq == seq && (!check_id || reply_id == id) => {
+                return Ok(Some(started.elapsed().as_secs_f32() * 1000.0));
+            }
+            _ => continue,
+        }
+    }
+}
+
+/// 通过 IP Helper 的 `IcmpSendEcho` 发送一次 ICMP Echo，无需管理员权限
+#[cfg(windows)]
+fn icmp_echo(ip: Ipv4Addr, timeout: Duration) -> std::io::Result<Option<f32>> {
+    ip_helper::icmp_echo(ip, timeout.as_millis() as u32)
+        .map_err(|code| std::io::Error::from_raw_os_error(code as i32))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn icmp_echo(_ip: Ipv4Addr, _timeout: Duration) -> std::io::Result<Option<f32>> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// 构造 ICMP Echo 请求（RFC 792）：类型 8、校验和、标识符、序号和固定的负载
+#[cfg(any(unix, test))]
+fn icmp_echo_request(id: u16, seq: u16) -> Vec<u8> {
+    let mut packet = vec![8, 0, 0, 0];
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&seq.to_be_bytes());
+    packet.extend_from_slice(b"portly ping sweep");
+    let checksum = icmp_checksum(&packet);
+    packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    packet
+}
+
+/// 解析 ICMP Echo 应答，返回标识符和序号；原始套接字和 macOS 的数据报套接字收到的报文带 IP 头
+#[cfg(any(unix, test))]
+fn parse_icmp_echo_reply(packet: &[u8]) -> Option<(u16, u16)> {
+    let icmp = match packet.first()? >> 4 {
+        4 => packet.get(usize::from(packet[0] & 0x0f) * 4..)?,
+        _ => packet,
+    };
+    if icmp.len() < 8 || icmp[0] != 0 || icmp[1] != 0 {
+        return None;
+    }
+    Some((
+        u16::from_be_bytes([icmp[4], icmp[5]]),
+        u16::from_be_bytes([icmp[6], icmp[7]]),
+    ))
+}
+
+/// 互联网校验和：按 16 位求反码和再取反
+#[cfg(any(unix, test))]
+fn icmp_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+/// 解析主机名，结果（包括查不到）按 TTL 缓存
+fn resolve_hostname(ip: &str) -> Option<String> {
+    crate::cache::shared()
+        .hostnames
+        .get_or_insert_with(ip.to_string(), || lookup_hostname(ip))
+}
+
+fn lookup_hostname(ip: &str) -> Option<String> {
+    if crate::demo::is_enabled() {
+        return crate::demo::hostname(ip);
+    }
+    let output = run_command(
+        if cfg!(windows) { "nslookup" } else { "host" },
+        "主机名解析",
+        |cmd| {
+            cmd.arg(ip);
+        },
+    );
+
+    if let Ok(out) = output {
+        let stdout = out.stdout;
+        // 解析输出中的主机名
+        for line in stdout.lines() {
+            if line.contains("name =") || line.contains("domain name pointer") {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                if let Some(name) = parts.last() {
+                    return Some(name.trim_end_matches('.').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// 解析目标结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolveResult {
+    pub original: String,
+    /// 后续扫描使用的地址，默认是系统解析器排在最前的地址
+    pub ip: String,
+    pub is_domain: bool,
+    pub hostname: Option<String>,
+    /// DNS 返回的全部 A / AAAA 地址，保持系统解析器的排序
+    #[serde(default)]
+    pub addresses: Vec<ResolvedAddress>,
+}
+
+/// 地址族
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl AddressFamily {
+    pub fn of(ip: &IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(_) => AddressFamily::Ipv4,
+            IpAddr::V6(_) => AddressFamily::Ipv6,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AddressFamily::Ipv4 => "ipv4",
+            AddressFamily::Ipv6 => "ipv6",
+        }
+    }
+}
+
+/// 目标解析出的一个地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedAddress {
+    pub ip: String,
+    pub family: AddressFamily,
+    /// TCP 握手往返时间，用于在多个地址间挑选；未探测或无应答时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<f32>,
+}
+
+impl ResolveResult {
+    fn new(original: &str, ips: &[IpAddr], is_domain: bool, hostname: Option<String>) -> Self {
+        let mut addresses: Vec<ResolvedAddress> = Vec::new();
+        for ip in ips {
+            let ip_text = ip.to_string();
+            if !addresses.iter().any(|a| a.ip == ip_text) {
+                addresses.push(ResolvedAddress {
+                    ip: ip_text,
+                    family: AddressFamily::of(ip),
+                    rtt_ms: None,
+                });
+            }
+        }
+        Self {
+            original: original.to_string(),
+            ip: addresses.first().map(|a| a.ip.clone()).unwrap_or_default(),
+            is_domain,
+            hostname,
+            addresses,
+        }
+    }
+
+    /// 选择后续扫描使用的地址：`ipv4` / `ipv6` 取该地址族的第一个地址，或直接给出解析结果中的某个 IP
+    pub fn select_address(mut self, choice: &str) -> AppResult<Self> {
+        let choice = choice.trim();
+        let wanted = choice.parse::<IpAddr>().ok();
+        let picked = self.addresses.iter().find(|address| match wanted {
+            Some(ip) => address.ip.parse::<IpAddr>().ok() == Some(ip),
+            None => address.family.name().eq_ignore_ascii_case(choice),
+        });
+        match picked {
+            Some(address) => {
+                self.ip = address.ip.clone();
+                Ok(self)
+            }
+            None => Err(AppError::validation(
+                "目标地址",
+                format!("{} 不在 {} 的解析结果中", choice, self.original),
+            )),
+        }
+    }
+}
+
+/// 对每个地址的 `port` 发起一次 TCP 连接测量往返时间，多个地址并行探测。连接成功或被拒绝（RST）
+/// 都说明收到了对端应答；超时、不可达时为空
+pub fn probe_address_rtt(result: &mut ResolveResult, port: u16, timeout_ms: u64) {
+    if crate::demo::is_enabled() {
+        return;
+    }
+    let timeout = Duration::from_millis(timeout_ms);
+    std::thread::scope(|scope| {
+        for address in result.addresses.iter_mut() {
+            scope.spawn(move || {
+                let Ok(ip) = address.ip.parse::<IpAddr>() else {
+                    return;
+                };
+                let started = Instant::now();
+                let target = SocketAddr::new(ip, port);
+                let answered = match TcpStream::connect_timeout(&target, timeout) {
+                    Ok(_) => true,
+                    Err(err) => err.kind() == std::io::ErrorKind::ConnectionRefused,
+                };
+                if answered {
+                    address.rtt_ms = Some(started.elapsed().as_secs_f32() * 1000.0);
+                }
+            });
+        }
+    });
+}
+
+/// 解析域名或验证 IP 地址
+/// 支持输入：IP 地址 (如 192.168.1.1) 或域名 (如 example.com)
+pub fn resolve_target(target: &str) -> AppResult<ResolveResult> {
+    let target = target.trim();
+
+    // 首先检查是否已经是有效的 IP 地址
+    if let Ok(ip) = target.parse::<IpAddr>() {
+        return Ok(ResolveResult::new(
+            target,
+            &[ip],
+            false,
+            resolve_hostname(&ip.to_string()),
+        ));
+    }
+
+    // 对看起来像 IPv4 的字符串提前校验，避免被系统 DNS 重定向成“可解析”假阳性结果
+    let looks_like_ipv4_like = target.chars().all(|c| c.is_ascii_digit() || c == '.');
+    if looks_like_ipv4_like {
+        let has_four_segments = target.split('.').count() == 4;
+        if has_four_segments {
+            return Err(AppError::validation(
+                "目标地址",
+                format!("无效的 IP 地址: {}", target),
+            ));
+        }
+    }
+
+    if crate::demo::is_enabled() {
+        return match crate::demo::resolve(target).and_then(|ip| ip.parse::<IpAddr>().ok()) {
+            Some(ip) => Ok(ResolveResult::new(
+                target,
+                &[ip],
+                true,
+                Some(target.to_string()),
+            )),
+            None => Err(AppError::host_unreachable(target, "DNS 未返回任何地址")),
+        };
+    }
+
+    // 尝试解析域名
+    use std::net::ToSocketAddrs;
+
+    // 添加端口以便解析
+    let addr_str = format!("{}:80", target);
+
+    match addr_str.to_socket_addrs() {
+        Ok(addrs) => {
+            let ips: Vec<IpAddr> = addrs.map(|addr| addr.ip()).collect();
+            if ips.is_empty() {
+                Err(AppError::host_unreachable(target, "DNS 未返回任何地址"))
+            } else {
+                Ok(ResolveResult::new(
+                    target,
+                    &ips,
+                    true,
+                    Some(target.to_string()),
+                ))
+            }
+        }
+        Err(e) => Err(AppError::host_unreachable(
+            target,
+            format!("DNS 解析失败 ({})", e),
+        )),
+    }
+}
+
+/// 扫描远程主机端口（同步版本，用于快速扫描）
+pub fn scan_ports_sync(ip: &str, ports: &[u16], timeout_ms: u64) -> Vec<RemotePort> {
+    if crate::demo::is_enabled() {
+        return crate::demo::scan_ports(ip, ports);
+    }
+    let policies = crate::policy::current();
+    let timeout_duration = Duration::from_millis(timeout_ms);
+    let mut results = Vec::new();
+
+    for &port in ports {
+        let addr = format!("{}:{}", ip, port);
+        let is_open = if let Ok(socket_addr) = addr.parse::<SocketAddr>() {
+            TcpStream::connect_timeout(&socket_addr, timeout_duration).is_ok()
+        } else {
+            false
+        };
+
+        results.push(remote_port(port, is_open, &policies));
+    }
+
+    results
+}
+
+/// 扫描远程主机端口；主机或网络不可达时立即返回错误，而不是报告全部端口关闭
+pub fn try_scan_ports_sync(ip: &str, ports: &[u16], timeout_ms: u64) -> AppResult<Vec<RemotePort>> {
+    try_scan_ports_with(ip, ports, timeout_ms, |_, _| Ok(()))
+}
+
+/// 扫描远程主机端口，每个端口扫描完成后回调结果和已完成数量；
+/// 回调返回错误（如任务被取消）时停止扫描并返回该错误
+#[tracing::instrument(level = "info", skip(ports, on_port), fields(ports = ports.len()))]
+pub fn try_scan_ports_with<F>(
+    ip: &str,
+    ports: &[u16],
+    timeout_ms: u64,
+    mut on_port: F,
+) -> AppResult<Vec<RemotePort>>
+where
+    F: FnMut(&RemotePort, usize) -> AppResult<()>,
+{
+    if crate::demo::is_enabled() {
+        let results = crate::demo::scan_ports(ip, ports);
+        for (i, result) in results.iter().enumerate() {
+            on_port(result, i + 1)?;
+        }
+        return Ok(results);
+    }
+
+    let policies = crate::policy::current();
+    let timeout_duration = Duration::from_millis(timeout_ms);
+    let mut results = Vec::new();
+
+    for &port in ports {
+        let addr = format!("{}:{}", ip, port);
+        let is_open = match addr.parse::<SocketAddr>() {
+            Ok(socket_addr) => match TcpStream::connect_timeout(&socket_addr, timeout_duration) {
+                Ok(_) => true,
+                Err(err) if is_unreachable_error(&err) => {
+                    tracing::warn!(port, error = %err, "目标主机不可达，停止扫描");
+                    return Err(AppError::host_unreachable(ip, err.to_string()));
+                }
+                Err(_) => false,
+            },
+            Err(_) => false,
+        };
+
+        let result = remote_port(port, is_open, &policies);
+        on_port(&result, results.len() + 1)?;
+        results.push(result);
+    }
+
+    let open = results.iter().filter(|r| r.is_open).count();
+    tracing::info!(open, "远程端口扫描完成");
+    Ok(results)
+}
+
+/// 连接失败是否说明目标主机/网络整体不可达（而非单个端口关闭）
+fn is_unreachable_error(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::HostUnreachable | std::io::ErrorKind::NetworkUnreachable
+    )
+}
+
+/// 异步扫描端口（更快）
+pub async fn scan_ports_async(ip: &str, ports: &[u16], timeout_ms: u64) -> Vec<RemotePort> {
+    if crate::demo::is_enabled() {
+        return crate::demo::scan_ports(ip, ports);
+    }
+    let policies = Arc::new(crate::policy::current());
+    let timeout_duration = Duration::from_millis(timeout_ms);
+    let mut handles = Vec::new();
+
+    for &port in ports {
+        let ip_clone = ip.to_string();
+        let policies = Arc::clone(&policies);
+        let handle = tokio::spawn(async move {
+            let addr = format!("{}:{}", ip_clone, port);
+            let is_open = if let Ok(socket_addr) = addr.parse::<SocketAddr>() {
+                timeout(timeout_duration, TokioTcpStream::connect(socket_addr))
+                    .await
+                    .map(|r| r.is_ok())
+                    .unwrap_or(false)
+            } else {
+                false
+            };
+
+            remote_port(port, is_open, &policies)
+        });
+        handles.push(handle);
+    }
+
+    let mut results = Vec::new();
+    for handle in handles {
+        if let Ok(result) = handle.await {
+            results.push(result);
+        }
+    }
+
+    results.sort_by_key(|r| r.port);
+    results
+}
+
+/// 组装单个端口的扫描结果，开放端口按配置中的端口策略检查
+pub(crate) fn remote_port(port: u16, is_open: bool, policies: &[PortPolicy]) -> RemotePort {
+    let service = if is_open {
+        get_service_name(port)
+    } else {
+        None
+    };
+    let policy = if is_open {
+        crate::policy::check_remote(policies, port, service.as_deref())
+    } else {
+        None
+    };
+    RemotePort {
+        port,
+        is_open,
+        service,
+        policy,
+    }
+}
+
+/// 获取端口对应的服务名称（来自常用端口库，含用户自定义端口）
+pub(crate) fn get_service_name(port: u16) -> Option<String> {
+    crate::known_ports::service_name(port)
+}
+
+/// 获取常用端口列表
+pub fn get_common_ports() -> Vec<u16> {
+    crate::known_ports::common_ports()
+}
+
+/// 生成端口范围
+pub fn port_range(start: u16, end: u16) -> Vec<u16> {
+    (start..=end).collect()
+}
+
+/// 解析端口描述
+/// 支持：`top100` / `common`（常用端口）、`all`（1-65535）、
+/// 以及逗号分隔的端口和范围，如 `22,80,8000-8100`
+pub fn parse_port_spec(spec: &str) -> Result<Vec<u16>, String> {
+    let spec = spec.trim().to_lowercase();
+    match spec.as_str() {
+        "top100" | "common" => return Ok(get_common_ports().into_iter().take(100).collect()),
+        "all" => return Ok(port_range(1, u16::MAX)),
+        _ => {}
+    }
+
+    let parse = |value: &str| -> Result<u16, String> {
+        match value.trim().parse::<u16>() {
+            Ok(port) if port > 0 => Ok(port),
+            _ => Err(format!("无效的端口: {}", value.trim())),
+        }
+    };
+
+    let mut ports = std::collections::BTreeSet::new();
+    for item in spec.split(',').filter(|item| !item.trim().is_empty()) {
+        match item.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse(start)?, parse(end)?);
+                if start > end {
+                    return Err(format!("无效的端口范围: {}", item.trim()));
+                }
+                ports.extend(start..=end);
+            }
+            None => {
+                ports.insert(parse(item)?);
+            }
+        }
+    }
+
+    if ports.is_empty() {
+        return Err("端口列表不能为空".to_string());
+    }
+    Ok(ports.into_iter().collect())
+}
+
+/// 快速扫描（只扫描常用端口，超时取自设置）
+pub fn quick_scan(ip: &str) -> Vec<RemotePort> {
+    let ports = get_common_ports();
+    scan_ports_sync(ip, &ports, settings::current().scan.connect_timeout_ms)
+}
+
+/// 快速扫描，主机不可达时返回错误
+pub fn try_quick_scan(ip: &str) -> AppResult<Vec<RemotePort>> {
+    let ports = get_common_ports();
+    try_scan_ports_sync(ip, &ports, settings::current().scan.connect_timeout_ms)
+}
+
+/// 完整扫描（扫描指定范围）
+pub fn full_scan(ip: &str, start: u16, end: u16, timeout_ms: u64) -> Vec<RemotePort> {
+    let ports = port_range(start, end);
+    scan_ports_sync(ip, &ports, timeout_ms)
+}
+
+/// 完整扫描，主机不可达时返回错误
+pub fn try_full_scan(
+    ip: &str,
+    start: u16,
+    end: u16,
+    timeout_ms: u64,
+) -> AppResult<Vec<RemotePort>> {
+    let ports = port_range(start, end);
+    try_scan_ports_sync(ip, &ports, timeout_ms)
+}
+
+// ===== Ping 和 Traceroute 功能 =====
+
+/// Ping 结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingResult {
+    pub ip: String,
+    pub is_reachable: bool,
+    pub packets_sent: u32,
+    pub packets_received: u32,
+    pub packet_loss: f32,
+    pub min_ms: Option<f32>,
+    pub avg_ms: Option<f32>,
+    pub max_ms: Option<f32>,
+    pub raw_output: String,
+}
+
+/// 单次 Ping 结果（用于流式显示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingOneResult {
+    pub ip: String,
+    pub seq: u32,
+    pub success: bool,
+    pub time_ms: Option<f32>,
+    pub ttl: Option<u32>,
+    pub line: String,
+}
+
+/// Traceroute 跳数信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceHop {
+    pub hop: u32,
+    pub ip: Option<String>,
+    pub hostname: Option<String>,
+    pub time_ms: Option<f32>,
+}
+
+/// Traceroute 结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracerouteResult {
+    pub target: String,
+    pub hops: Vec<TraceHop>,
+    pub raw_output: String,
+}
+
+/// 执行 Ping 测试
+pub fn ping_test(ip: &str, count: u32) -> PingResult {
+    match ping_test_with(ip, count, |_| Ok(())) {
+        Ok(result) => result,
+        // 回调不会失败，这里只为穷尽匹配
+        Err(err) => PingResult {
+            ip: ip.to_string(),
+            is_reachable: false,
+            packets_sent: count,
+            packets_received: 0,
+            packet_loss: 100.0,
+            min_ms: None,
+            avg_ms: None,
+            max_ms: None,
+            raw_output: err.to_string(),
+        },
+    }
+}
+
+/// 执行 Ping 测试，每收到一个回复就回调一次；
+/// 回调返回错误（如任务被取消）时终止 ping 并返回该错误
+#[tracing::instrument(level = "info", skip(on_reply))]
+pub fn ping_test_with<F>(ip: &str, count: u32, mut on_reply: F) -> AppResult<PingResult>
+where
+    F: FnMut(&PingOneResult) -> AppResult<()>,
+{
+    if crate::demo::is_enabled() {
+        let (replies, result) = crate::demo::ping(ip, count);
+        replies.iter().try_for_each(&mut on_reply)?;
+        return Ok(result);
+    }
+
+    let ping_count = count.to_string();
+    let mut seq = 0;
+    let mut stopped = false;
+    let output = run_command_streaming(
+        "ping",
+        "Ping 测试",
+        |cmd| {
+            #[cfg(target_os = "windows")]
+            cmd.args(["-n", ping_count.as_str()]);
+
+            #[cfg(target_os = "macos")]
+            cmd.args(["-c", ping_count.as_str()]);
+
+            #[cfg(target_os = "linux")]
+            cmd.args(["-c", ping_count.as_str()]);
+
+            cmd.arg(ip);
+        },
+        |line| {
+            let Some(reply) = parse_ping_reply(ip, seq + 1, line) else {
+                return Ok(());
+            };
+            seq += 1;
+            on_reply(&reply).inspect_err(|_| stopped = true)
+        },
+    );
+
+    let mut result = PingResult {
+        ip: ip.to_string(),
+        is_reachable: false,
+        packets_sent: count,
+        packets_received: 0,
+        packet_loss: 100.0,
+        min_ms: None,
+        avg_ms: None,
+        max_ms: None,
+        raw_output: String::new(),
+    };
+
+    match output {
+        Ok(out) => {
+        result.raw_output = out.stdout;
+        result.is_reachable = out.status == 0;
+
+        // 解析输出 - 先克隆避免借用冲突
+        let output_clone = result.raw_output.clone();
+        parse_ping_output(&output_clone, &mut result);
+        }
+        Err(err) if stopped => return Err(err),
+        Err(err) => {
+            result.raw_output = format!("ping 命令执行失败：{err}");
+        }
+    }
+
+    Ok(result)
+}
+
+/// 执行单次 Ping（用于流式显示）
+pub fn ping_one(ip: &str, seq: u32) -> PingOneResult {
+    if crate::demo::is_enabled() {
+        return crate::demo::ping_one(ip, seq);
+    }
+    let output = run_command("ping", "单次 Ping 测试", |cmd| {
+        #[cfg(target_os = "windows")]
+        cmd.args(["-n", "1", "-w", "2000"]);
+
+        #[cfg(target_os = "macos")]
+        cmd.args(["-c", "1", "-W", "2000"]); // macOS -W 是毫秒
+
+        #[cfg(target_os = "linux")]
+        cmd.args(["-c", "1", "-W", "2"]); // Linux -W 是秒
+
+        cmd.arg(ip);
+    });
+
+    let mut result = PingOneResult {
+        ip: ip.to_string(),
+        seq,
+        success: false,
+        time_ms: None,
+        ttl: None,
+        line: String::new(),
+    };
+
+    match output {
+        Ok(out) => {
+        let stdout = out.stdout;
+
+        // 解析输出行 - 只有真正收到响应才算成功
+        if let Some(reply) = stdout.lines().find_map(|line| parse_ping_reply(ip, seq, line)) {
+            result = reply;
+        }
+
+        // 没有收到响应
+        if result.line.is_empty() {
+            result.line = "Request timeout".to_string();
+            result.success = false;
+        }
+        }
+        Err(err) => {
+            result.line = format!("ping 命令执行失败：{err}");
+        }
+    }
+
+    result
+}
+
+/// 解析 Ping 响应行（`bytes from` / `Reply from`），其他行返回 None
+fn parse_ping_reply(ip: &str, seq: u32, line: &str) -> Option<PingOneResult> {
+    if !line.contains("bytes from") && !line.contains("Reply from") {
+        return None;
+    }
+
+    let mut result = PingOneResult {
+        ip: ip.to_string(),
+        seq,
+        success: true,
+        time_ms: None,
+        ttl: None,
+        line: line.to_string(),
+    };
+
+    // 提取 time
+    if let Some(pos) = line.find("time=") {
+        let after = &line[pos + 5..];
+        let time_str: String = after
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        result.time_ms = time_str.parse().ok();
+    }
+    // Windows 亚毫秒响应显示为 "time<1ms"，按上限计
+    else if let Some(pos) = line.find("time<") {
+        let after = &line[pos + 5..];
+        let time_str: String = after
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        result.time_ms = time_str.parse().ok();
+    }
+    // macOS 可能用 "time " 格式
+    else if let Some(pos) = line.find("time ") {
+        let after = &line[pos + 5..];
+        let time_str: String = after
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        result.time_ms = time_str.parse().ok();
+    }
+
+    // 提取 ttl
+    if let Some(pos) = line.to_lowercase().find("ttl=") {
+        let after = &line[pos + 4..];
+        let ttl_str: String = after.chars().take_while(|c| c.is_ascii_digit()).collect();
+        result.ttl = ttl_str.parse().ok();
+    }
+
+    Some(result)
+}
+
+/// 解析 Ping 输出
+fn parse_ping_output(output: &str, result: &mut PingResult) {
+    for line in output.lines() {
+        let line_lower = line.to_lowercase();
+
+        // 解析丢包率
+        if line_lower.contains("packet loss") || line_lower.contains("packets") {
+            // macOS/Linux: "4 packets transmitted, 4 received, 0% packet loss"
+            // Windows: "Packets: Sent = 4, Received = 4, Lost = 0 (0% loss)"
+            if let Some(loss) = extract_packet_loss(line) {
+                result.packet_loss = loss;
+                result.packets_received =
+                    ((100.0 - loss) / 100.0 * result.packets_sent as f32) as u32;
+            }
+        }
+
+        // 解析延迟统计
+        if line_lower.contains("min/avg/max") || line_lower.contains("minimum") {
+            // macOS/Linux: "round-trip min/avg/max/stddev = 1.234/2.345/3.456/0.5 ms"
+            if let Some((min, avg, max)) = extract_latency_stats(line) {
+                result.min_ms = Some(min);
+                result.avg_ms = Some(avg);
+                result.max_ms = Some(max);
+            }
+        }
+    }
+}
+
+/// 提取丢包率
+fn extract_packet_loss(line: &str) -> Option<f32> {
+    // 查找百分比数字
+    let re_patterns = ["%", "% packet loss", "% loss"];
+    for pattern in re_patterns {
+        if let Some(pos) = line.find(pattern) {
+            let before = &line[..pos];
+            let num_str: String = before
+                .chars()
+                .rev()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect::<String>()
+                .chars()
+                .rev()
+                .collect();
+            if let Ok(loss) = num_str.parse::<f32>() {
+                return Some(loss);
+            }
+        }
+    }
+    None
+}
+
+/// 提取延迟统计
+fn extract_latency_stats(line: &str) -> Option<(f32, f32, f32)> {
+    // 查找 "= X/Y/Z" 格式
+    if let Some(pos) = line.find('=') {
+        let stats_part = &line[pos + 1..];
+        let parts: Vec<&str> = stats_part.split('/').collect();
+        if parts.len() >= 3 {
+            let min = parts[0].trim().parse::<f32>().ok()?;
+            let avg = parts[1].trim().parse::<f32>().ok()?;
+            let max = parts[2].split_whitespace().next()?.parse::<f32>().ok()?;
+            return Some((min, avg, max));
+        }
+    }
+    None
+}
+
+/// Traceroute 最大跳数，也是流式进度的总数
+pub const TRACEROUTE_MAX_HOPS: u32 = 30;
+
+/// 执行 Traceroute
+pub fn traceroute(ip: &str) -> TracerouteResult {
+    match traceroute_with(ip, |_| Ok(())) {
+        Ok(result) => result,
+        // 回调不会失败，这里只为穷尽匹配
+        Err(err) => TracerouteResult {
+            target: ip.to_string(),
+            hops: Vec::new(),
+            raw_output: err.to_string(),
+        },
+    }
+}
+
+/// 执行 Traceroute，每解析出一跳就回调一次；
+/// 回调返回错误（如任务被取消）时终止 traceroute 并返回该错误
+#[tracing::instrument(level = "info", skip(on_hop))]
+pub fn traceroute_with<F>(ip: &str, mut on_hop: F) -> AppResult<TracerouteResult>
+where
+    F: FnMut(&TraceHop) -> AppResult<()>,
+{
+    if crate::demo::is_enabled() {
+        let result = crate::demo::traceroute(ip);
+        result.hops.iter().try_for_each(&mut on_hop)?;
+        return Ok(result);
+    }
+
+    let max_hops = TRACEROUTE_MAX_HOPS.to_string();
+    let mut hops = Vec::new();
+    let mut stopped = false;
+    let output = run_command_streaming(
+        if cfg!(windows) { "tracert" } else { "traceroute" },
+        "Traceroute",
+        |cmd| {
+            #[cfg(target_os = "windows")]
+            cmd.args(["-d", "-w", "1000", "-h", max_hops.as_str()]);
+
+            #[cfg(not(target_os = "windows"))]
+            cmd.args(["-n", "-w", "2", "-q", "1", "-m", max_hops.as_str()]);
+
+            cmd.arg(ip);
+        },
+        |line| {
+            let Some(hop) = parse_traceroute_line(line) else {
+                return Ok(());
+            };
+            let result = on_hop(&hop).inspect_err(|_| stopped = true);
+            hops.push(hop);
+            result
+        },
+    );
+
+    let mut result = TracerouteResult {
+        target: ip.to_string(),
+        hops,
+        raw_output: String::new(),
+    };
+
+    match output {
+        Ok(out) => result.raw_output = out.stdout,
+        Err(err) if stopped => return Err(err),
+        Err(err) => {
+            result.raw_output = format!("traceroute 命令执行失败：{err}");
+        }
+    }
+
+    Ok(result)
+}
+
+/// 解析 Traceroute 行
+fn parse_traceroute_line(line: &str) -> Option<TraceHop> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.is_empty() {
+        return None;
+    }
+
+    // 第一部分应该是跳数
+    let hop_num = parts[0].parse::<u32>().ok()?;
+
+    // 查找 IP 地址和延迟
+    let mut ip = None;
+    let mut time_ms = None;
+
+    for part in &parts[1..] {
+        // 仅把有效 IPv4 识别为 hop IP，避免把 "1.234" 误判为 IP
+        if part.parse::<Ipv4Addr>().is_ok() {
+            ip = Some(part.to_string());
+        }
+        // 检查是否是延迟时间 (e.g., "1.234" 后跟 "ms")
+        else if let Ok(ms) = part.parse::<f32>() {
+            time_ms = Some(ms);
+        }
+    }
+
+    // 超时的情况
+    if line.contains('*') && ip.is_none() {
+        return Some(TraceHop {
+            hop: hop_num,
+            ip: None,
+            hostname: None,
+            time_ms: None,
+        });
+    }
+
+    if ip.is_some() || time_ms.is_some() {
+        Some(TraceHop {
+            hop: hop_num,
+            ip,
+            hostname: None,
+            time_ms,
+        })
+    } else {
+        None
+    }
+}
+
+// ===== 服务类型探测 =====
+
+/// 服务类型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    pub port: u16,
+    pub service: String,
+    pub service_type: String, // "api", "web", "database", "other"
+    pub server: Option<String>,
+    pub content_type: Option<String>,
+    /// `server` 中的版本命中的风险提示（见 [`crate::vulns`]）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub version_hints: Vec<VersionHint>,
+    /// HTTP 服务的安全响应头检查（见 [`crate::security_headers`]）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_headers: Option<SecurityHeaders>,
+}
+
+/// 探测服务类型：先交给探测插件，其次探测 HTTP 和握手横幅，最后按端口推断；结果按 TTL 缓存
+pub fn detect_service_type(ip: &str, port: u16) -> ServiceInfo {
+    crate::cache::shared()
+        .services
+        .get_or_insert_with((ip.to_string(), port), || probe_service_type(ip, port))
+}
+
+#[tracing::instrument(level = "debug")]
+fn probe_service_type(ip: &str, port: u16) -> ServiceInfo {
+    let mut info = probe_service_info(ip, port);
+    if settings::current().scan.version_hints {
+        if let Some(server) = &info.server {
+            info.version_hints = vulns::hints_for_banner(server);
+        }
+    }
+    info
+}
+
+fn probe_service_info(ip: &str, port: u16) -> ServiceInfo {
+    if crate::demo::is_enabled() {
+        return crate::demo::service(ip, port);
+    }
+    if let Some(info) = crate::plugins::probe(ip, port) {
+        return info;
+    }
+
+    let base_service = get_service_name(port).unwrap_or_else(|| "Unknown".to_string());
+
+    // IPP 打印机返回型号和状态；9100 只在用户主动查询时探测，见 [`crate::printer`]
+    if port == crate::printer::IPP_PORT {
+        if let Ok(printer) = crate::printer::query_printer(ip, port) {
+            return printer.service_info();
+        }
+    }
+
+    // 对于 HTTP 端口，尝试探测
+    if is_http_port(port) {
+        if let Some(info) = probe_http_service(ip, port) {
+            return info;
+        }
+    }
+
+    // 根据端口推断类型
+    let service_type = infer_service_type(port);
+
+    ServiceInfo {
+        port,
+        service: base_service,
+        service_type,
+        server: probe_banner(ip, port),
+        content_type: None,
+        version_hints: Vec::new(),
+        security_headers: None,
+    }
+}
+
+/// 读取握手横幅中的软件版本：SSH / FTP / SMTP / POP3 / IMAP 连接后直接发送横幅，
+/// Redis 需要发送 `INFO server`（开启认证时没有结果）
+fn probe_banner(ip: &str, port: u16) -> Option<String> {
+    use std::io::{Read, Write};
+
+    let greeting = matches!(port, 21 | 22 | 25 | 110 | 143 | 587 | 2222);
+    if !greeting && port != 6379 {
+        return None;
+    }
+    let addr: SocketAddr = format!("{}:{}", ip, port).parse().ok()?;
+    let mut stream = TcpStream::connect_timeout(&addr, Duration::from_secs(2)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(2))).ok()?;
+    if !greeting {
+        stream
+            .set_write_timeout(Some(Duration::from_secs(2)))
+            .ok()?;
+        stream.write_all(b"INFO server\r\n").ok()?;
+    }
+    let mut buffer = vec![0u8; 4096];
+    let n = stream.read(&mut buffer).ok()?;
+    parse_banner(port, &String::from_utf8_lossy(&buffer[..n]))
+}
+
+/// 从横幅中取出软件描述，去掉协议状态码
+fn parse_banner(port: u16, response: &str) -> Option<String> {
+    if port == 6379 {
+        return response
+            .lines()
+            .find_map(|line| line.strip_prefix("redis_version:"))
+            .map(|version| format!("Redis/{}", version.trim()));
+    }
+    let line = response.lines().next()?.trim();
+    let text = ["220-", "220 ", "+OK ", "* OK "]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .unwrap_or(line)
+        .trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// 判断是否是 HTTP 端口
+fn is_http_port(port: u16) -> bool {
+    matches!(
+        port,
+        80 | 443
+            | 3000
+            | 3001
+            | 4000
+            | 4200
+            | 5000
+            | 5173
+            | 8000
+            | 8080
+            | 8081
+            | 8443
+            | 8888
+            | 9000
+            | 9090
+            | 19000
+    )
+}
+
+/// 响应读取上限，足够覆盖首页中的标题、meta 和脚本路径
+const HTTP_PROBE_MAX_LEN: usize = 64 * 1024;
+
+/// 发送 GET 请求，读到对端关闭连接、超时或达到长度上限
+fn http_get(ip: &str, port: u16, path: &str) -> Option<String> {
+    use std::io::{Read, Write};
+
+    let addr = format!("{}:{}", ip, port);
+    let mut stream =
+        TcpStream::connect_timeout(&addr.parse().ok()?, Duration::from_secs(2)).ok()?;
+
+    stream.set_read_timeout(Some(Duration::from_secs(2))).ok()?;
+    stream
+        .set_write_timeout(Some(Duration::from_secs(2)))
+        .ok()?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: Portly/1.0\r\nAccept: */*\r\nConnection: close\r\n\r\n",
+        path, ip
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = Vec::new();
+    let mut buffer = vec![0u8; 8192];
+    while response.len() < HTTP_PROBE_MAX_LEN {
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => response.extend_from_slice(&buffer[..n]),
+            Err(_) if !response.is_empty() => break,
+            Err(_) => return None,
+        }
+    }
+    if response.is_empty() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&response).into_owned())
+}
+
+/// 探测 HTTP 服务
+fn probe_http_service(ip: &str, port: u16) -> Option<ServiceInfo> {
+    let response = http_get(ip, port, "/")?;
+
+    // 解析响应头
+    let mut server = None;
+    let mut content_type = None;
+    let mut service_type = "web".to_string();
+
+    for line in response.lines() {
+        let line_lower = line.to_lowercase();
+
+        if line_lower.starts_with("server:") {
+            server = Some(line[7..].trim().to_string());
+        }
+        if line_lower.starts_with("content-type:") {
+            let ct = line[13..].trim().to_string();
+            content_type = Some(ct.clone());
+
+            // 根据 Content-Type 判断类型
+            if ct.contains("application/json") || ct.contains("api") {
+                service_type = "api".to_string();
+            } else if ct.contains("text/html") {
+                service_type = "web".to_string();
+            } else if ct.contains("application/xml") || ct.contains("text/xml") {
+                service_type = "api".to_string();
+            }
+        }
+
+        // 检查特殊标识
+        if line_lower.contains("x-powered-by:") {
+            let powered = line.to_lowercase();
+            if powered.contains("express")
+                || powered.contains("flask")
+                || powered.contains("django")
+                || powered.contains("fastapi")
+            {
+                service_type = "api".to_string();
+            }
+        }
+    }
+
+    // 检查响应体中的特征
+    let body = response.to_lowercase();
+    if body.contains("<!doctype html") || body.contains("<html") {
+        if body.contains("react")
+            || body.contains("vue")
+            || body.contains("angular")
+            || body.contains("next")
+            || body.contains("vite")
+        {
+            service_type = "web".to_string();
+        }
+    } else if body.starts_with("{") || body.contains("\"data\":") || body.contains("\"error\":") {
+        service_type = "api".to_string();
+    }
+
+    let headers = security_headers::parse_headers(&response);
+    // 首页跳转到站内页面时（如 Grafana 的 /login）再请求一次跳转目标来识别应用
+    let app = web_fingerprint::identify(&headers, &response).or_else(|| {
+        let (_, location) = headers.iter().find(|(name, _)| name == "location")?;
+        if !location.starts_with('/') || location.starts_with("//") {
+            return None;
+        }
+        let page = http_get(ip, port, location)?;
+        web_fingerprint::identify(&security_headers::parse_headers(&page), &page)
+    });
+
+    let service = match (&app, service_type.as_str()) {
+        (Some(app), _) => app.label(),
+        (None, "api") => format!("API ({})", port),
+        (None, "web") => format!("Web ({})", port),
+        _ => get_service_name(port).unwrap_or_else(|| format!("HTTP ({})", port)),
+    };
+
+    Some(ServiceInfo {
+        port,
+        service,
+        service_type,
+        server,
+        content_type,
+        version_hints: Vec::new(),
+        security_headers: Some(security_headers::audit_headers(&headers)),
+    })
+}
+
+/// 根据端口推断服务类型
+pub(crate) fn infer_service_type(port: u16) -> String {
+    match port {
+        // 数据库
+        1433 | 1521 | 3306 | 5432 | 5984 | 6379 | 9042 | 9200 | 27017 | 28015 => "database",
+        // 消息队列
+        1883 | 5672 | 6650 | 9092 | 61616 => "queue",
+        // 缓存
+        11211 => "cache",
+        // Web 服务
+        80 | 443 | 3000 | 4200 | 5173 | 8080 | 8443 => "web",
+        // API 服务
+        4000 | 5000 | 8000 | 9000 => "api",
+        // 打印机
+        515 | 631 | 9100 => "printer",
+        // 其他
+        _ => "other",
+    }
+    .to_string()
+}
+
+/// 批量探测服务类型
+pub fn detect_services(ip: &str, ports: &[u16]) -> Vec<ServiceInfo> {
+    let probe_timeout = Duration::from_millis(settings::current().scan.service_probe_timeout_ms);
+    ports
+        .iter()
+        .filter_map(|&port| {
+            // 先检查端口是否开放
+            let addr = format!("{}:{}", ip, port);
+            if TcpStream::connect_timeout(&addr.parse().ok()?, probe_timeout).is_ok() {
+                Some(detect_service_type(ip, port))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+// ===== 单元测试 =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_exec::FixtureRunner;
+
+    #[test]
+    fn test_parse_port_spec() {
+        assert_eq!(parse_port_spec("1-3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(
+            parse_port_spec("443, 22,80-81,22").unwrap(),
+            vec![22, 80, 81, 443]
+        );
+        assert_eq!(parse_port_spec("TOP100").unwrap(), get_common_ports());
+        assert_eq!(parse_port_spec("all").unwrap().len(), 65535);
+        assert!(parse_port_spec("0").is_err());
+        assert!(parse_port_spec("100-10").is_err());
+        assert!(parse_port_spec("http").is_err());
+        assert!(parse_port_spec(" , ").is_err());
+    }
+
+    #[test]
+    fn test_probed_device_merges_ping_and_arp() {
+        let arp_entry = |ip: &str, mac: &str| NetworkDevice {
+            ip: ip.to_string(),
+            mac: Some(mac.to_string()),
+            hostname: None,
+            is_online: true,
+            interface_index: Some(7),
+            rtt_ms: None,
+            tags: Vec::new(),
+            note: None,
+        };
+        let arp = vec![
+            arp_entry("192.168.1.20", "aa:bb:cc:dd:ee:20"),
+            arp_entry("192.168.1.21", "(incomplete)"),
+        ];
+
+        let online = probed_device("192.168.1.20", &arp, Some(3.5));
+        assert!(online.is_online);
+        assert_eq!(online.mac.as_deref(), Some("aa:bb:cc:dd:ee:20"));
+        assert_eq!(online.interface_index, Some(7));
+        assert_eq!(online.rtt_ms, Some(3.5));
+
+        let offline = probed_device("192.168.1.21", &arp, None);
+        assert!(!offline.is_online);
+        assert_eq!(offline.mac, None);
+        assert!(refresh_device("nas.lan").is_err());
+    }
+
+    #[test]
+    fn test_get_local_interfaces_returns_valid_interfaces() {
+        let interfaces = get_local_interfaces();
+        // 检查返回非空或有效的结构
+        for iface in &interfaces {
+            assert!(!iface.name.is_empty());
+            assert!(!iface.subnet.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_get_current_subnet_format() {
+        let subnet = get_current_subnet();
+        if let Some(sn) = subnet {
+            // 验证子网格式 (xxx.xxx.xxx.0/22~24)
+            let (base, prefix_len) = sn.split_once('/').unwrap();
+            assert!(["22", "23", "24"].contains(&prefix_len));
+            let parts: Vec<&str> = base.split('.').filter(|p| !p.is_empty()).collect();
+            // 某些系统可能返回不同格式，检查至少有3部分且每部分都是数字
+            assert!(parts.len() >= 3);
+            for part in &parts[..3] {
+                assert!(part.chars().all(|c| c.is_ascii_digit()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_discover_subnet() {
+        let normalize = normalize_discover_subnet;
+        assert_eq!(
+            normalize("192.168.1.7/24").as_deref(),
+            Some("192.168.1.0/24")
+        );
+        assert_eq!(normalize(" 10.0.5.9/22 ").as_deref(), Some("10.0.4.0/22"));
+        assert_eq!(normalize("172.16.0.0/23").as_deref(), Some("172.16.0.0/23"));
+        assert_eq!(normalize("10.0.0.0/8"), None);
+        assert_eq!(normalize("10.0.0.0/25"), None);
+        assert_eq!(normalize("10.0.0.0"), None);
+        assert_eq!(ipv4_netmask(22), "255.255.252.0");
+        assert_eq!(ipv4_netmask(0), "0.0.0.0");
+    }
+
+    #[test]
+    fn test_subnet_info() {
+        let info = subnet_info("192.168.1.77/22").unwrap();
+        assert_eq!(info.cidr, "192.168.0.0/22");
+        assert_eq!(info.address, "192.168.1.77");
+        assert_eq!(info.netmask, "255.255.252.0");
+        assert_eq!(info.wildcard, "0.0.3.255");
+        assert_eq!(info.netmask_hex, "0xfffffc00");
+        assert_eq!(info.netmask_binary, "11111111.11111111.11111100.00000000");
+        assert_eq!(info.broadcast, "192.168.3.255");
+        assert_eq!(info.first_host, "192.168.0.1");
+        assert_eq!(info.last_host, "192.168.3.254");
+        assert_eq!(info.host_count, 1022);
+        assert_eq!(info.total_addresses, 1024);
+        assert!(info.is_private);
+
+        assert_eq!(subnet_info("10.1.2.3/255.0.0.0").unwrap().prefix_len, 8);
+        assert_eq!(
+            subnet_info(" 10.1.2.3 255.255.255.0 ").unwrap().cidr,
+            "10.1.2.0/24"
+        );
+        let p2p = subnet_info("203.0.113.9/31").unwrap();
+        assert_eq!(
+            (p2p.first_host.as_str(), p2p.host_count),
+            ("203.0.113.8", 2)
+        );
+        assert!(!p2p.is_private);
+        let host = subnet_info("8.8.8.8").unwrap();
+        assert_eq!((host.prefix_len, host.host_count), (32, 1));
+        let all = subnet_info("0.0.0.0/0").unwrap();
+        assert_eq!(all.total_addresses, 1 << 32);
+
+        assert!(subnet_info("10.0.0.0/33").is_err());
+        assert!(subnet_info("10.0.0.0/255.0.255.0").is_err());
+        assert!(subnet_info("example.com/24").is_err());
+    }
+
+    #[test]
+    fn test_classify_interface_name() {
+        assert_eq!(classify_interface_name("eth0"), InterfaceKind::Ethernet);
+        assert_eq!(classify_interface_name("enp3s0"), InterfaceKind::Ethernet);
+        assert_eq!(classify_interface_name("wlp2s0"), InterfaceKind::Wifi);
+        assert_eq!(classify_interface_name("utun4"), InterfaceKind::Vpn);
+        assert_eq!(classify_interface_name("wg0"), InterfaceKind::Vpn);
+        assert_eq!(classify_interface_name("tailscale0"), InterfaceKind::Vpn);
+        assert_eq!(classify_interface_name("docker0"), InterfaceKind::Virtual);
+        assert_eq!(classify_interface_name("br-1a2b3c"), InterfaceKind::Virtual);
+        assert_eq!(classify_interface_name("veth12ab"), InterfaceKind::Virtual);
+        assert_eq!(classify_interface_name("ifb0"), InterfaceKind::Other);
+    }
+
+    #[test]
+    fn test_parse_hardware_ports() {
+        let stdout =
+            "Hardware Port: Ethernet\nDevice: en0\nEthernet Address: 3c:22:fb:00:00:01\n\n\
+                      Hardware Port: Wi-Fi\nDevice: en1\nEthernet Address: 3c:22:fb:00:00:02\n\n\
+                      Hardware Port: Thunderbolt Bridge\nDevice: bridge0\nEthernet Address: N/A\n\n\
+                      Hardware Port: iPhone USB\nDevice: en5\nEthernet Address: N/A\n";
+        let ports = parse_hardware_ports(stdout);
+        assert_eq!(ports.get("en0"), Some(&InterfaceKind::Ethernet));
+        assert_eq!(ports.get("en1"), Some(&InterfaceKind::Wifi));
+        assert_eq!(ports.get("bridge0"), Some(&InterfaceKind::Virtual));
+        assert_eq!(ports.get("en5"), None);
+    }
+
+    #[test]
+    fn test_classify_windows_adapter() {
+        let classify = classify_windows_adapter;
+        assert_eq!(classify(71, "Intel(R) Wi-Fi 6 AX201"), InterfaceKind::Wifi);
+        assert_eq!(classify(6, "Realtek PCIe GbE"), InterfaceKind::Ethernet);
+        assert_eq!(
+            classify(6, "Hyper-V Virtual Ethernet"),
+            InterfaceKind::Virtual
+        );
+        assert_eq!(classify(6, "TAP-Windows Adapter V9"), InterfaceKind::Vpn);
+        assert_eq!(classify(53, "WireGuard Tunnel"), InterfaceKind::Vpn);
+        assert_eq!(classify(23, "WAN Miniport (PPTP)"), InterfaceKind::Vpn);
+        assert_eq!(classify(53, "Npcap Loopback"), InterfaceKind::Virtual);
+        assert_eq!(classify(144, "IEEE 1394 Adapter"), InterfaceKind::Other);
+    }
+
+    #[test]
+    fn test_quick_scan_returns_expected_results() {
+        // 测试本地主机快速扫描
+        let results = quick_scan("127.0.0.1");
+        // 应该包含一些常见端口
+        assert!(results.len() > 0 || results.is_empty());
+    }
+
+    #[test]
+    fn test_ping_test_returns_valid_result() {
+        let result = ping_test("127.0.0.1", 1);
+        assert_eq!(result.ip, "127.0.0.1");
+        assert_eq!(result.packets_sent, 1);
+        // localhost 应该可达
+        assert!(result.is_reachable || result.packets_received == 0);
+    }
+
+    #[test]
+    fn test_resolve_target_with_ip() {
+        let result = resolve_target("127.0.0.1").unwrap();
+        assert_eq!(result.ip, "127.0.0.1");
+        assert!(!result.is_domain);
+    }
+
+    #[test]
+    fn test_resolve_target_with_domain() {
+        // 测试域名解析
+        let result = resolve_target("localhost");
+        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert!(result.is_domain);
+        assert!(!result.ip.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_result_keeps_all_addresses_and_selects_one() {
+        let ips: Vec<IpAddr> = ["2001:db8::1", "192.0.2.1", "2001:db8::1", "192.0.2.2"]
+            .iter()
+            .map(|ip| ip.parse().unwrap())
+            .collect();
+        let result = ResolveResult::new("example.com", &ips, true, None);
+        assert_eq!(result.ip, "2001:db8::1");
+        let families: Vec<&str> = result.addresses.iter().map(|a| a.family.name()).collect();
+        assert_eq!(families, vec!["ipv6", "ipv4", "ipv4"]);
+
+        let pick = |choice: &str| result.clone().select_address(choice).map(|r| r.ip);
+        assert_eq!(pick("ipv4").unwrap(), "192.0.2.1");
+        assert_eq!(pick("192.0.2.2").unwrap(), "192.0.2.2");
+        assert_eq!(pick("2001:DB8:0::1").unwrap(), "2001:db8::1");
+        assert!(pick("192.0.2.3").is_err());
+    }
+
+    #[test]
+    fn test_probe_address_rtt_counts_refused_connections_as_answers() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut result = resolve_target("127.0.0.1").unwrap();
+        probe_address_rtt(&mut result, port, 500);
+        assert!(result.addresses[0].rtt_ms.is_some());
+
+        drop(listener);
+        let mut result = resolve_target("127.0.0.1").unwrap();
+        probe_address_rtt(&mut result, port, 500);
+        assert!(result.addresses[0].rtt_ms.is_some());
+    }
+
+    #[test]
+    fn test_extract_packet_loss() {
+        // 测试丢包率解析
+        assert_eq!(extract_packet_loss("0% loss"), Some(0.0));
+        assert_eq!(extract_packet_loss("50% packet loss"), Some(50.0));
+        assert_eq!(extract_packet_loss("100%"), Some(100.0));
+        assert_eq!(extract_packet_loss("invalid"), None);
+    }
+
+    #[test]
+    fn test_get_service_name_for_known_ports() {
+        // 由于 get_service_name 是私有函数，我们通过扫描结果间接测试
+        let results = quick_scan("127.0.0.1");
+        // 检查返回结果包含正确的端口信息
+        for port_result in &results {
+            assert!(port_result.port > 0);
+            // 如果端口开放，应该有服务名或者端口是已知端口
+            if port_result.is_open {
+                let is_known_port = get_common_ports().contains(&port_result.port);
+                if is_known_port {
+                    assert!(port_result.service.is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_common_ports() {
+        let ports = get_common_ports();
+        assert!(!ports.is_empty());
+        // 验证常见端口存在
+        assert!(ports.contains(&80));
+        assert!(ports.contains(&443));
+        assert!(ports.contains(&22));
+    }
+
+    #[test]
+    fn test_port_range() {
+        let ports = port_range(80, 85);
+        assert_eq!(ports.len(), 6);
+        assert_eq!(ports, vec![80, 81, 82, 83, 84, 85]);
+    }
+
+    #[test]
+    fn test_full_scan() {
+        let results = full_scan("127.0.0.1", 3000, 3010, 100);
+        assert_eq!(results.len(), 11);
+        // 验证端口顺序
+        for (i, port_result) in results.iter().enumerate() {
+            assert_eq!(port_result.port, 3000 + i as u16);
+        }
+    }
+
+    #[test]
+    fn test_resolve_target_with_invalid_ip() {
+        // 测试无效 IP 格式：原始 IP 字符串看起来像 IPv4，但格式非法
+        let result = resolve_target("999.999.999.999");
+        assert!(matches!(result, Err(AppError::Validation { .. })));
+    }
+
+    #[test]
+    fn test_resolve_target_with_invalid_domain() {
+        // 测试无效域名格式（双点导致域名标签为空）
+        let result = resolve_target("bad..domain");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_supports_22() {
+        let hosts = subnet_host_addresses("192.168.0.0/22", 1022).unwrap();
+        assert_eq!(hosts.len(), 1022);
+        assert_eq!(hosts.first().unwrap(), "192.168.0.1");
+        assert_eq!(hosts.last().unwrap(), "192.168.3.254");
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_supports_23() {
+        let hosts = subnet_host_addresses("192.168.1.0/23", 1022).unwrap();
+        assert_eq!(hosts.len(), 510);
+        assert_eq!(hosts.first().unwrap(), "192.168.0.1");
+        assert_eq!(hosts.last().unwrap(), "192.168.1.254");
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_supports_24() {
+        let hosts = subnet_host_addresses("10.0.0.0/24", 254).unwrap();
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(hosts.first().unwrap(), "10.0.0.1");
+        assert_eq!(hosts.last().unwrap(), "10.0.0.254");
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_respects_max_hosts_for_22() {
+        assert!(subnet_host_addresses("192.168.0.0/22", 1021).is_none());
+        assert!(subnet_host_addresses("192.168.0.0/22", 1022).is_some());
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_respects_max_hosts_for_24() {
+        assert!(subnet_host_addresses("10.0.0.0/24", 253).is_none());
+        assert!(subnet_host_addresses("10.0.0.0/24", 254).is_some());
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_rejects_oversize() {
+        assert!(subnet_host_addresses("192.168.0.0/21", 1022).is_none());
+    }
+
+    #[test]
+    fn test_subnet_host_addresses_invalid() {
+        assert!(subnet_host_addresses("bad-subnet", 1022).is_none());
+    }
+
+    #[test]
+    fn test_discover_devices_invalid_subnet_returns_empty() {
+        let devices = discover_devices("bad-subnet");
+        assert!(devices.is_empty());
+    }
+
+    #[test]
+    fn test_parse_arp_line_malformed_returns_none() {
+        assert!(parse_arp_line("this is not an arp record", Platform::Linux).is_none());
+    }
+
+    #[test]
+    fn test_parse_arp_line_posix_with_incomplete_record() {
+        let device = parse_arp_line(
+            "?_gateway (192.168.1.1) at aa:bb:cc:dd:ee:ff on en0",
+            Platform::MacOs,
+        );
+        assert!(device.is_some());
+        let device = device.unwrap();
+        assert_eq!(device.ip, "192.168.1.1");
+        assert_eq!(device.mac, Some("aa:bb:cc:dd:ee:ff".to_string()));
+        assert_eq!(device.hostname, None);
+        assert!(device.is_online);
+    }
+
+    #[test]
+    fn test_parse_arp_line_windows_style() {
+        let device = parse_arp_line(
+            "192.168.1.1    00-11-22-33-44-55    dynamic",
+            Platform::Windows,
+        );
+        assert!(device.is_some());
+        let device = device.unwrap();
+        assert_eq!(device.ip, "192.168.1.1");
+        assert_eq!(device.mac, Some("00:11:22:33:44:55".to_string()));
+        assert_eq!(device.hostname, None);
+    }
+
+    #[test]
+    fn test_parse_ip_net_table() {
+        let row = |index: u32, mac: &[u8], ip: [u8; 4], kind: u32| {
+            let mut row = Vec::new();
+            row.extend(index.to_ne_bytes());
+            row.extend((mac.len() as u32).to_ne_bytes());
+            let mut phys = [0u8; 8];
+            phys[..mac.len()].copy_from_slice(mac);
+            row.extend(phys);
+            row.extend(ip);
+            row.extend(kind.to_ne_bytes());
+            row
+        };
+        let mut table = 4u32.to_ne_bytes().to_vec();
+        let router = [0xaa, 0xbb, 0xcc, 13, 14, 15];
+        table.extend(row(12, &router, [192, 168, 1, 1], 3));
+        table.extend(row(12, &[0; 6], [192, 168, 1, 9], 3));
+        table.extend(row(12, &[1, 2, 3, 4, 5, 6], [192, 168, 1, 7], 2));
+        table.extend(row(7, &[0, 0x15, 0x5d, 1, 2, 3], [10, 0, 0, 2], 4));
+        // 条目数之外的字节不解析
+        table.extend(row(1, &[1, 1, 1, 1, 1, 1], [10, 0, 0, 3], 3));
+
+        let devices = parse_ip_net_table(&table);
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].ip, "192.168.1.1");
+        assert_eq!(devices[0].mac.as_deref(), Some("aa:bb:cc:0d:0e:0f"));
+        assert_eq!(devices[0].interface_index, Some(12));
+        assert_eq!(devices[1].ip, "10.0.0.2");
+        assert_eq!(devices[1].mac.as_deref(), Some("00:15:5d:01:02:03"));
+        assert_eq!(devices[1].interface_index, Some(7));
+        assert!(parse_ip_net_table(&[1, 0]).is_empty());
+    }
+
+    #[test]
+    fn test_read_arp_table_fixtures() {
+        let arp = |platform, stdout: &str| {
+            let runner = FixtureRunner::new().with_stdout("arp -a", stdout);
+            read_arp_table(&runner, platform)
+        };
+
+        let devices = arp(Platform::MacOs, include_str!("../fixtures/macos/arp.txt"));
+        assert_eq!(devices.len(), 4);
+        assert_eq!(devices[1].hostname.as_deref(), Some("nas.lan"));
+        assert_eq!(devices[1].mac.as_deref(), Some("0:11:32:ab:cd:ef"));
+        assert_eq!(devices[2].mac.as_deref(), Some("(incomplete)"));
+
+        let devices = arp(Platform::Linux, include_str!("../fixtures/linux/arp.txt"));
+        let ips: Vec<&str> = devices.iter().map(|d| d.ip.as_str()).collect();
+        assert_eq!(ips, vec!["192.168.1.1", "192.168.1.57", "192.168.1.60"]);
+        assert_eq!(devices[0].hostname.as_deref(), Some("_gateway"));
+
+        let windows = include_str!("../fixtures/windows/arp.txt").replace('\n', "\r\n");
+        let devices = arp(Platform::Windows, &windows);
+        assert_eq!(devices.len(), 4);
+        assert_eq!(devices[0].ip, "192.168.1.1");
+        assert_eq!(devices[0].mac.as_deref(), Some("a4:91:b1:2c:7e:10"));
+
+        assert!(read_arp_table(&FixtureRunner::new(), Platform::Linux).is_empty());
+    }
+
+    #[test]
+    fn test_get_routes_fixtures() {
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                "ip route show",
+                include_str!("../fixtures/linux/ip_route.txt"),
+            )
+            .with_stdout(
+                "ip -6 route show",
+                include_str!("../fixtures/linux/ip_route6.txt"),
+            );
+        let routes = get_routes_with(&runner, Platform::Linux).unwrap();
+        assert_eq!(routes.len(), 11);
+        assert_eq!(routes[0].gateway.as_deref(), Some("192.168.1.1"));
+        assert_eq!(routes[0].metric, Some(100));
+        assert_eq!(routes[3].destination, "10.20.0.0/16");
+        assert_eq!(routes[3].interface_kind, InterfaceKind::Vpn);
+        assert_eq!(routes[6].destination, "192.168.1.1/32");
+        assert!(routes[10].ipv6 && routes[10].is_default());
+
+        let runner = FixtureRunner::new().with_stdout(
+            "netstat -rn",
+            include_str!("../fixtures/macos/netstat_rn.txt"),
+        );
+        let routes = get_routes_with(&runner, Platform::MacOs).unwrap();
+        let destinations: Vec<&str> = routes.iter().map(|r| r.destination.as_str()).collect();
+        assert_eq!(
+            &destinations[..7],
+            [
+                "0.0.0.0/0",
+                "0.0.0.0/0",
+                "10.8.0.0/24",
+                "10.20.0.0/16",
+                "127.0.0.0/8",
+                "127.0.0.1/32",
+                "169.254.0.0/16"
+            ]
+        );
+        assert_eq!(routes[1].gateway, None);
+        assert_eq!(routes[1].interface, "utun4");
+        // ARP 生成的主机条目和组播路由被跳过
+        assert_eq!(routes.iter().filter(|r| !r.ipv6).count(), 11);
+        assert!(routes
+            .iter()
+            .any(|r| r.destination == "fe80::/64" && r.interface == "en0"));
+        assert!(!routes.iter().any(|r| r.destination == "ff00::/8"));
+
+        let windows = include_str!("../fixtures/windows/route_print.txt").replace('\n', "\r\n");
+        let runner = FixtureRunner::new().with_stdout("route print", &windows);
+        let routes = get_routes_with(&runner, Platform::Windows).unwrap();
+        assert_eq!(routes.len(), 13);
+        assert_eq!(routes[0].destination, "0.0.0.0/0");
+        assert_eq!(routes[0].interface, "192.168.1.40");
+        assert_eq!(routes[0].metric, Some(25));
+        assert_eq!(routes[1].gateway, None);
+        assert_eq!(routes[9].destination, "::/0");
+        assert_eq!(routes[9].interface, "7");
+
+        let failing = FixtureRunner::new().with_output("netstat -rn", 1, "", "boom");
+        assert!(get_routes_with(&failing, Platform::MacOs).is_err());
+    }
+
+    #[test]
+    fn test_route_for_longest_prefix_and_metric() {
+        let runner = FixtureRunner::new()
+            .with_stdout(
+                "ip route show",
+                include_str!("../fixtures/linux/ip_route.txt"),
+            )
+            .with_stdout(
+                "ip -6 route show",
+                include_str!("../fixtures/linux/ip_route6.txt"),
+            );
+        let routes = get_routes_with(&runner, Platform::Linux).unwrap();
+        let route = |ip: &str| route_for(&routes, ip.parse().unwrap()).unwrap();
+
+        // 10.20.0.0/16 走 VPN，其余公网地址走跃点数较小的 eth0 默认路由
+        assert_eq!(route("10.20.3.4").interface, "wg0");
+        assert_eq!(route("10.20.3.4").gateway.as_deref(), Some("10.8.0.1"));
+        assert_eq!(route("1.1.1.1").interface, "eth0");
+        assert_eq!(route("192.168.1.1").destination, "192.168.1.1/32");
+        assert_eq!(route("192.168.1.77").gateway, None);
+        assert_eq!(route("2001:db8:1::5").destination, "2001:db8:1::/64");
+        assert_eq!(
+            default_gateway(&routes).unwrap().gateway.as_deref(),
+            Some("192.168.1.1")
+        );
+        assert!(route_for(&routes[..0], "1.1.1.1".parse().unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_parse_traceroute_line_timeout() {
+        let hop = parse_traceroute_line("2 * * *");
+        assert!(hop.is_some());
+        let hop = hop.unwrap();
+        assert_eq!(hop.hop, 2);
+        assert!(hop.ip.is_none());
+        assert!(hop.time_ms.is_none());
+    }
+
+    #[test]
+    fn test_parse_traceroute_line_with_times() {
+        let hop = parse_traceroute_line("3 10.0.0.1 1.111 ms 2.222 ms 3.333 ms");
+        assert!(hop.is_some());
+        let hop = hop.unwrap();
+        assert_eq!(hop.hop, 3);
+        assert_eq!(hop.ip, Some("10.0.0.1".to_string()));
+        assert!(hop.time_ms.is_some());
+    }
+
+    #[test]
+    fn test_parse_traceroute_line_invalid() {
+        assert!(parse_traceroute_line("no-hop-number info").is_none());
+    }
+
+    #[test]
+    fn test_ping_one() {
+        let result = ping_one("127.0.0.1", 1);
+        assert_eq!(result.ip, "127.0.0.1");
+        assert_eq!(result.seq, 1);
+        // localhost 通常应该可达
+        // 注意：某些系统配置可能导致 localhost ping 失败
+        assert!(result.success || !result.success);
+    }
+
+    #[test]
+    fn test_extract_latency_stats() {
+        // 测试延迟统计解析
+        let result = extract_latency_stats("round-trip min/avg/max/stddev = 1.0/2.0/3.0/0.5 ms");
+        assert!(result.is_some());
+        let (min, avg, max) = result.unwrap();
+        assert_eq!(min, 1.0);
+        assert_eq!(avg, 2.0);
+        assert_eq!(max, 3.0);
+    }
+
+    #[test]
+    fn test_extract_latency_stats_invalid() {
+        // 测试无效格式
+        let result = extract_latency_stats("invalid format");
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_extract_packet_loss_windows_style() {
+        assert_eq!(
+            extract_packet_loss("Packets: Sent = 4, Received = 4, Lost = 0 (0% loss)"),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_parse_ping_output_with_irregular_lines() {
+        let mut result = PingResult {
+            ip: "127.0.0.1".to_string(),
+            is_reachable: false,
+            packets_sent: 4,
+            packets_received: 0,
+            packet_loss: 100.0,
+            min_ms: None,
+            avg_ms: None,
+            max_ms: None,
+            raw_output: String::new(),
+        };
+
+        parse_ping_output(
+            "random log\nnot a valid line\n",
+            &mut result,
+        );
+
+        assert_eq!(result.packets_received, 0);
+        assert_eq!(result.packet_loss, 100.0);
+        assert!(result.min_ms.is_none());
+        assert!(result.avg_ms.is_none());
+        assert!(result.max_ms.is_none());
+    }
+
+    #[test]
+    fn test_parse_ping_reply_time() {
+        let linux = "64 bytes from 10.0.0.1: icmp_seq=1 ttl=64 time=0.412 ms";
+        let reply = parse_ping_reply("10.0.0.1", 1, linux).unwrap();
+        assert_eq!(reply.time_ms, Some(0.412));
+        assert_eq!(reply.ttl, Some(64));
+
+        let windows = "Reply from 10.0.0.1: bytes=32 time<1ms TTL=128";
+        let reply = parse_ping_reply("10.0.0.1", 1, windows).unwrap();
+        assert_eq!(reply.time_ms, Some(1.0));
+        assert!(parse_ping_reply("10.0.0.1", 1, "Request timed out.").is_none());
+    }
+
+    #[test]
+    fn test_ping_host_invalid_target_returns_false() {
+        // 不同平台/策略下 ping 对空目标行为不一致，这里只验证函数稳定返回布尔值
+        let result = ping_host("", 500);
+        assert!(result.is_none_or(|rtt| rtt >= 0.0));
+    }
+
+    #[test]
+    fn test_icmp_echo_request_and_reply() {
+        let request = icmp_echo_request(0x1234, 7);
+        assert_eq!(request[0], 8);
+        // 带正确校验和的报文再求一次校验和为 0
+        assert_eq!(icmp_checksum(&request), 0);
+        // 请求不是应答
+        assert_eq!(parse_icmp_echo_reply(&request), None);
+
+        let mut reply = request.clone();
+        reply[0] = 0;
+        assert_eq!(parse_icmp_echo_reply(&reply), Some((0x1234, 7)));
+        // 带 20 字节 IPv4 头的应答
+        let mut with_header = vec![0x45, 0, 0, 45, 0, 0, 0, 0, 64, 1, 0, 0];
+        with_header.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+        with_header.extend_from_slice(&reply);
+        assert_eq!(parse_icmp_echo_reply(&with_header), Some((0x1234, 7)));
+        assert_eq!(parse_icmp_echo_reply(&with_header[..24]), None);
+    }
+
+    #[test]
+    fn test_ping_sweep_with_empty_targets_returns_empty() {
+        let online_ips = ping_sweep(&[], |_| Ok(())).unwrap();
+        assert!(online_ips.is_empty());
+    }
+
+    #[test]
+    fn test_ping_sweep_with_bounds_parallelism_and_keeps_order() {
+        let targets: Vec<String> = (1..=40).map(|i| format!("10.0.0.{i}")).collect();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let ping = |ip: &str| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(2));
+            running.fetch_sub(1, Ordering::SeqCst);
+            ip.ends_with('0').then_some(1.5)
+        };
+        let mut progress = Vec::new();
+        let online = ping_sweep_with(&targets, 4, &ping, |done| {
+            progress.push(done);
+            Ok(())
+        })
+        .unwrap();
+        let online: Vec<&str> = online.iter().map(|(ip, _)| ip.as_str()).collect();
+        assert_eq!(
+            online,
+            vec!["10.0.0.10", "10.0.0.20", "10.0.0.30", "10.0.0.40"]
+        );
+        assert_eq!(progress, (1..=40).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 4);
+
+        // 回调出错后不再派发新的主机
+        let pinged = AtomicUsize::new(0);
+        let result = ping_sweep_with(
+            &targets,
+            2,
+            &|_| {
+                pinged.fetch_add(1, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(2));
+                None
+            },
+            |done| match done {
+                3 => Err(AppError::cancelled("局域网扫描")),
+                _ => Ok(()),
+            },
+        );
+        assert!(result.is_err());
+        assert!(pinged.load(Ordering::SeqCst) < targets.len());
+    }
+
+    #[test]
+    fn test_ping_test_invalid_target_keeps_failure_state() {
+        let result = ping_test("", 1);
+        assert_eq!(result.ip, "");
+        assert_eq!(result.packets_sent, 1);
+        // 在不同网络策略下可达性与输出差异较大，这里只验证结构范围
+        assert!((0.0..=100.0).contains(&result.packet_loss));
+    }
+
+    #[test]
+    fn test_ping_one_invalid_target_returns_failure_result() {
+        let result = ping_one("", 1);
+        assert_eq!(result.ip, "");
+        assert_eq!(result.seq, 1);
+        assert!(!result.line.is_empty());
+    }
+
+    #[test]
+    fn test_traceroute_invalid_target_returns_empty_result() {
+        let result = traceroute("");
+        assert_eq!(result.target, "");
+        // 不同系统/网络策略对非法目标输出差异较大，仅验证结构可访问
+        assert!(result.hops.len() <= 64);
+    }
+
+    #[test]
+    fn test_network_device_creation() {
+        let device = NetworkDevice {
+            ip: "192.168.1.1".to_string(),
+            mac: Some("00:11:22:33:44:55".to_string()),
+            hostname: Some("test-host".to_string()),
+            is_online: true,
+            interface_index: None,
+            rtt_ms: None,
+            tags: Vec::new(),
+            note: None,
+        };
+        assert_eq!(device.ip, "192.168.1.1");
+        assert_eq!(device.mac, Some("00:11:22:33:44:55".to_string()));
+        assert_eq!(device.hostname, Some("test-host".to_string()));
+        assert!(device.is_online);
+    }
+
+    #[test]
+    fn test_remote_port_creation() {
+        let port = RemotePort {
+            port: 80,
+            is_open: true,
+            service: Some("HTTP".to_string()),
+            policy: None,
+        };
+        assert_eq!(port.port, 80);
+        assert!(port.is_open);
+        assert_eq!(port.service, Some("HTTP".to_string()));
+    }
+
+    #[test]
+    fn test_is_http_port() {
+        // 测试 HTTP 端口判断
+        assert!(is_http_port(80));
+        assert!(is_http_port(443));
+        assert!(is_http_port(8080));
+        assert!(is_http_port(3000));
+        assert!(!is_http_port(22));
+        assert!(!is_http_port(3306));
+    }
+
+    #[test]
+    fn test_infer_service_type() {
+        assert_eq!(infer_service_type(3306), "database");
+        assert_eq!(infer_service_type(5432), "database");
+        assert_eq!(infer_service_type(6379), "database");
+        assert_eq!(infer_service_type(5672), "queue");
+        assert_eq!(infer_service_type(9092), "queue");
+        assert_eq!(infer_service_type(11211), "cache");
+        assert_eq!(infer_service_type(80), "web");
+        assert_eq!(infer_service_type(443), "web");
+        assert_eq!(infer_service_type(4000), "api");
+        assert_eq!(infer_service_type(8000), "api");
+        assert_eq!(infer_service_type(9999), "other");
+    }
+
+    #[test]
+    fn test_parse_banner() {
+        assert_eq!(
+            parse_banner(22, "SSH-2.0-OpenSSH_9.3p1 Debian-1\r\n").as_deref(),
+            Some("SSH-2.0-OpenSSH_9.3p1 Debian-1")
+        );
+        assert_eq!(
+            parse_banner(21, "220 (vsFTPd 2.3.4)\r\n").as_deref(),
+            Some("(vsFTPd 2.3.4)")
+        );
+        assert_eq!(
+            parse_banner(6379, "$120\r\n# Server\r\nredis_version:5.0.7\r\n").as_deref(),
+            Some("Redis/5.0.7")
+        );
+        assert_eq!(
+            parse_banner(6379, "-NOAUTH Authentication required.\r\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ping_result_creation() {
+        let result = PingResult {
+            ip: "127.0.0.1".to_string(),
+            is_reachable: true,
+            packets_sent: 4,
+            packets_received: 4,
+            packet_loss: 0.0,
+            min_ms: Some(1.0),
+            avg_ms: Some(2.0),
+            max_ms: Some(3.0),
+            raw_output: String::new(),
+        };
+        assert_eq!(result.ip, "127.0.0.1");
+        assert!(result.is_reachable);
+        assert_eq!(result.packet_loss, 0.0);
+    }
+}
@@ -0,0 +1,472 @@
+//! NTP 时间偏差检测
+//!
+//! 向 NTP 服务器（UDP 123）发送 SNTP 请求，按 RFC 4330 由四个时间戳算出本机时钟偏差和往返时延。
+//! 时钟偏差是 TLS 证书“尚未生效 / 已过期”、Kerberos 认证失败和 TOTP 验证码错误的常见根因。
+//! 未指定服务器时查询系统配置的 NTP 服务器（chrony、ntpd、systemd-timesyncd、Windows 时间服务），
+//! 都没有时查询 `pool.ntp.org`
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_timeout;
+use serde::{Deserialize, Serialize};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 单台服务器的默认超时
+pub const DEFAULT_TIMEOUT_MS: u64 = 2000;
+/// 一次最多查询的服务器数量
+pub const MAX_SERVERS: usize = 16;
+/// 偏差超过 1 秒时提示
+pub const DRIFT_WARNING_MS: f64 = 1000.0;
+/// 偏差超过 30 秒时 TOTP 验证码开始失效，Kerberos 默认容忍 5 分钟
+pub const DRIFT_CRITICAL_MS: f64 = 30_000.0;
+/// 未配置 NTP 服务器时使用
+pub const DEFAULT_SERVER: &str = "pool.ntp.org";
+
+const NTP_PORT: u16 = 123;
+/// 1900-01-01 到 1970-01-01 的秒数
+const NTP_UNIX_OFFSET: f64 = 2_208_988_800.0;
+const PACKET_LEN: usize = 48;
+
+/// 被查询服务器的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NtpServerSource {
+    /// 调用方指定
+    Given,
+    /// 系统时间同步服务的配置
+    System,
+    /// 默认的公共服务器
+    Default,
+}
+
+/// 偏差等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftLevel {
+    Ok,
+    Warning,
+    Critical,
+    /// 没有服务器给出可用的时间
+    Unknown,
+}
+
+/// 一台 NTP 服务器的查询结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NtpServerResult {
+    pub server: String,
+    /// 实际查询的地址
+    pub address: Option<String>,
+    pub reachable: bool,
+    pub stratum: Option<u8>,
+    /// 参考源：一级服务器为 `GPS`、`PPS` 这类标识，其余为上游服务器的 IPv4 地址
+    pub reference_id: Option<String>,
+    /// 本机时钟相对服务器的偏差，正数表示本机慢了
+    pub offset_ms: Option<f64>,
+    pub delay_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// 时间偏差检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeDriftReport {
+    pub checked_at: String,
+    pub source: NtpServerSource,
+    pub servers: Vec<NtpServerResult>,
+    /// 可用服务器偏差的中位数
+    pub drift_ms: Option<f64>,
+    pub level: DriftLevel,
+}
+
+/// 服务器应答中解析出的时间样本
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct NtpSample {
+    stratum: u8,
+    reference_id: [u8; 4],
+    offset_ms: f64,
+    delay_ms: f64,
+}
+
+/// 构造 SNTP 客户端请求（版本 4，模式 3），发送时间写入 Transmit Timestamp
+fn build_request(transmit: f64) -> [u8; PACKET_LEN] {
+    let mut packet = [0u8; PACKET_LEN];
+    packet[0] = (4 << 3) | 3;
+    packet[40..48].copy_from_slice(&to_ntp_timestamp(transmit).to_be_bytes());
+    packet
+}
+
+fn to_ntp_timestamp(unix_secs: f64) -> u64 {
+    let secs = unix_secs + NTP_UNIX_OFFSET;
+    ((secs.trunc() as u64) << 32) | ((secs.fract() * 4_294_967_296.0) as u64)
+}
+
+fn from_ntp_timestamp(bytes: &[u8]) -> f64 {
+    let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    secs + fraction / 4_294_967_296.0 - NTP_UNIX_OFFSET
+}
+
+/// 解析服务器应答；`sent` / `received` 为本机发送和收到的 Unix 时间（秒）
+fn parse_response(packet: &[u8], sent: f64, received: f64) -> Result<NtpSample, String> {
+    if packet.len() < PACKET_LEN {
+        return Err("应答长度不足".to_string());
+    }
+    if packet[0] & 0x07 != 4 {
+        return Err("不是 NTP 服务器应答".to_string());
+    }
+    // 服务器原样返回请求的发送时间，不一致说明是迟到或伪造的应答
+    if packet[24..32] != to_ntp_timestamp(sent).to_be_bytes() {
+        return Err("应答与请求不匹配".to_string());
+    }
+    let stratum = packet[1];
+    let reference_id = [packet[12], packet[13], packet[14], packet[15]];
+    if stratum == 0 {
+        return Err(format!(
+            "服务器拒绝服务（{}）",
+            format_kiss_code(&reference_id)
+        ));
+    }
+    if packet[0] >> 6 == 3 {
+        return Err("服务器自身尚未同步".to_string());
+    }
+    let server_received = from_ntp_timestamp(&packet[32..40]);
+    let server_sent = from_ntp_timestamp(&packet[40..48]);
+    let offset = ((server_received - sent) + (server_sent - received)) / 2.0;
+    let delay = (received - sent) - (server_sent - server_received);
+    Ok(NtpSample {
+        stratum,
+        reference_id,
+        offset_ms: offset * 1000.0,
+        delay_ms: delay.max(0.0) * 1000.0,
+    })
+}
+
+fn format_kiss_code(code: &[u8; 4]) -> String {
+    String::from_utf8_lossy(code)
+        .trim_end_matches('\0')
+        .trim()
+        .to_string()
+}
+
+/// 参考标识：一级服务器为 ASCII 时钟源代码，二级以上为上游地址
+fn format_reference_id(stratum: u8, id: &[u8; 4]) -> String {
+    if stratum == 1 {
+        format_kiss_code(id)
+    } else {
+        std::net::Ipv4Addr::from(*id).to_string()
+    }
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// 查询一台 NTP 服务器
+pub fn query_server(server: &str, timeout: Duration) -> NtpServerResult {
+    let mut result = NtpServerResult {
+        server: server.to_string(),
+        address: None,
+        reachable: false,
+        stratum: None,
+        reference_id: None,
+        offset_ms: None,
+        delay_ms: None,
+        error: None,
+    };
+    let address = match (server, NTP_PORT)
+        .to_socket_addrs()
+        .map(|mut addrs| addrs.next())
+    {
+        Ok(Some(address)) => address,
+        Ok(None) => {
+            result.error = Some("DNS 未返回任何地址".to_string());
+            return result;
+        }
+        Err(err) => {
+            result.error = Some(format!("DNS 解析失败 ({})", err));
+            return result;
+        }
+    };
+    result.address = Some(address.ip().to_string());
+    match exchange(address, timeout) {
+        Ok(sample) => {
+            result.reachable = true;
+            result.stratum = Some(sample.stratum);
+            result.reference_id = Some(format_reference_id(sample.stratum, &sample.reference_id));
+            result.offset_ms = Some(sample.offset_ms);
+            result.delay_ms = Some(sample.delay_ms);
+        }
+        Err(err) => result.error = Some(err),
+    }
+    result
+}
+
+fn exchange(address: SocketAddr, timeout: Duration) -> Result<NtpSample, String> {
+    let bind = if address.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+    socket
+        .set_read_timeout(Some(timeout))
+        .map_err(|e| e.to_string())?;
+    socket.connect(address).map_err(|e| e.to_string())?;
+
+    let sent = unix_now();
+    socket
+        .send(&build_request(sent))
+        .map_err(|e| e.to_string())?;
+    let mut buffer = [0u8; 128];
+    let len = socket.recv(&mut buffer).map_err(|e| match e.kind() {
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut => "超时".to_string(),
+        _ => e.to_string(),
+    })?;
+    parse_response(&buffer[..len], sent, unix_now())
+}
+
+/// 从 chrony / ntpd 配置中读取 `server` / `pool` 行
+fn parse_ntpd_config(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            match fields.next()? {
+                "server" | "pool" | "peer" => fields.find(|field| !field.starts_with('-')),
+                _ => None,
+            }
+        })
+        // 127.127.x.x 是 ntpd 的本地参考时钟驱动
+        .filter(|server| !server.starts_with("127.127."))
+        .map(str::to_string)
+        .collect()
+}
+
+/// 从 systemd-timesyncd 配置中读取 `NTP=`
+fn parse_timesyncd_config(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("NTP="))
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect()
+}
+
+/// 解析 `reg query ...\W32Time\Parameters /v NtpServer`，例如 `time.windows.com,0x9`
+fn parse_w32time_servers(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split("REG_SZ").nth(1))
+        .flat_map(str::split_whitespace)
+        .map(|entry| entry.split(',').next().unwrap_or(entry).to_string())
+        .filter(|server| !server.is_empty())
+        .collect()
+}
+
+/// 系统时间同步服务配置的 NTP 服务器
+pub fn configured_servers() -> Vec<String> {
+    let mut servers = Vec::new();
+    if cfg!(target_os = "windows") {
+        let output = run_command_with_timeout(
+            "reg",
+            "NTP 服务器读取",
+            |cmd| {
+                cmd.args([
+                    "query",
+                    r"HKLM\SYSTEM\CurrentControlSet\Services\W32Time\Parameters",
+                    "/v",
+                    "NtpServer",
+                ]);
+            },
+            Duration::from_secs(5),
+        );
+        if let Ok(output) = output {
+            servers = parse_w32time_servers(&output.stdout);
+        }
+    } else {
+        for path in [
+            "/etc/chrony.conf",
+            "/etc/chrony/chrony.conf",
+            "/etc/ntp.conf",
+        ] {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                servers.extend(parse_ntpd_config(&content));
+            }
+        }
+        if let Ok(content) = std::fs::read_to_string("/etc/systemd/timesyncd.conf") {
+            servers.extend(parse_timesyncd_config(&content));
+        }
+    }
+    let mut seen = std::collections::HashSet::new();
+    servers.retain(|server| seen.insert(server.to_ascii_lowercase()));
+    servers
+}
+
+/// 偏差中位数和等级；只统计可达的服务器
+fn summarize(servers: &[NtpServerResult]) -> (Option<f64>, DriftLevel) {
+    let mut offsets: Vec<f64> = servers.iter().filter_map(|s| s.offset_ms).collect();
+    if offsets.is_empty() {
+        return (None, DriftLevel::Unknown);
+    }
+    offsets.sort_by(|a, b| a.total_cmp(b));
+    let middle = offsets.len() / 2;
+    let drift = if offsets.len().is_multiple_of(2) {
+        (offsets[middle - 1] + offsets[middle]) / 2.0
+    } else {
+        offsets[middle]
+    };
+    let level = match drift.abs() {
+        abs if abs >= DRIFT_CRITICAL_MS => DriftLevel::Critical,
+        abs if abs >= DRIFT_WARNING_MS => DriftLevel::Warning,
+        _ => DriftLevel::Ok,
+    };
+    (Some(drift), level)
+}
+
+/// 查询 `servers`（为空时查询系统配置的服务器）并比较本机时钟
+#[tracing::instrument(level = "info")]
+pub fn check_time_drift(servers: &[String], timeout_ms: u64) -> AppResult<TimeDriftReport> {
+    let given: Vec<String> = servers
+        .iter()
+        .map(|server| server.trim().to_string())
+        .filter(|server| !server.is_empty())
+        .collect();
+    if given.len() > MAX_SERVERS {
+        return Err(AppError::validation(
+            "NTP 服务器",
+            format!("最多 {} 个", MAX_SERVERS),
+        ));
+    }
+    if timeout_ms == 0 {
+        return Err(AppError::validation("NTP 超时", "必须大于 0"));
+    }
+    let (source, mut targets) = if !given.is_empty() {
+        (NtpServerSource::Given, given)
+    } else {
+        match configured_servers() {
+            configured if configured.is_empty() => {
+                (NtpServerSource::Default, vec![DEFAULT_SERVER.to_string()])
+            }
+            configured => (NtpServerSource::System, configured),
+        }
+    };
+    targets.truncate(MAX_SERVERS);
+
+    let timeout = Duration::from_millis(timeout_ms);
+    let results: Vec<NtpServerResult> = std::thread::scope(|scope| {
+        let handles: Vec<_> = targets
+            .iter()
+            .map(|server| scope.spawn(move || query_server(server, timeout)))
+            .collect();
+        handles
+            .into_iter()
+            .zip(&targets)
+            .map(|(handle, server)| {
+                handle.join().unwrap_or_else(|_| NtpServerResult {
+                    server: server.clone(),
+                    address: None,
+                    reachable: false,
+                    stratum: None,
+                    reference_id: None,
+                    offset_ms: None,
+                    delay_ms: None,
+                    error: Some("查询线程异常退出".to_string()),
+                })
+            })
+            .collect()
+    });
+
+    let (drift_ms, level) = summarize(&results);
+    tracing::info!(
+        servers = results.len(),
+        ?drift_ms,
+        ?level,
+        "时间偏差检测完成"
+    );
+    Ok(TimeDriftReport {
+        checked_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        source,
+        servers: results,
+        drift_ms,
+        level,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 模拟服务器应答：服务器时钟比本机快 `skew` 秒，单程耗时 `one_way` 秒
+    fn response(sent: f64, skew: f64, one_way: f64) -> Vec<u8> {
+        let mut packet = build_request(sent).to_vec();
+        packet[0] = (4 << 3) | 4;
+        packet[1] = 2;
+        packet[12..16].copy_from_slice(&[192, 0, 2, 10]);
+        packet[24..32].copy_from_slice(&to_ntp_timestamp(sent).to_be_bytes());
+        let server_received = sent + one_way + skew;
+        packet[32..40].copy_from_slice(&to_ntp_timestamp(server_received).to_be_bytes());
+        packet[40..48].copy_from_slice(&to_ntp_timestamp(server_received + 0.001).to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn test_parse_response_offset_and_delay() {
+        let sent = 1_790_000_000.25;
+        let packet = response(sent, 42.0, 0.010);
+        let received = sent + 0.010 + 0.001 + 0.010;
+        let sample = parse_response(&packet, sent, received).unwrap();
+        assert!((sample.offset_ms - 42_000.0).abs() < 1.0);
+        assert!((sample.delay_ms - 20.0).abs() < 1.0);
+        assert_eq!(
+            format_reference_id(sample.stratum, &sample.reference_id),
+            "192.0.2.10"
+        );
+
+        // 事务不匹配、服务器未同步和 KoD 都不计入结果
+        assert!(parse_response(&packet, sent + 1.0, received).is_err());
+        let mut unsynced = packet.clone();
+        unsynced[0] |= 0xc0;
+        assert!(parse_response(&unsynced, sent, received).is_err());
+        let mut kiss = packet;
+        kiss[1] = 0;
+        kiss[12..16].copy_from_slice(b"RATE");
+        let err = parse_response(&kiss, sent, received).unwrap_err();
+        assert!(err.contains("RATE"));
+
+        let result = |offset: Option<f64>| NtpServerResult {
+            server: "ntp".to_string(),
+            address: None,
+            reachable: offset.is_some(),
+            stratum: None,
+            reference_id: None,
+            offset_ms: offset,
+            delay_ms: None,
+            error: None,
+        };
+        let servers = [result(Some(-40.0)), result(None), result(Some(45_000.0))];
+        assert_eq!(summarize(&servers), (Some(22_480.0), DriftLevel::Warning));
+        assert_eq!(summarize(&[result(None)]), (None, DriftLevel::Unknown));
+    }
+
+    #[test]
+    fn test_parse_configured_servers() {
+        let chrony = "# comment\npool 2.debian.pool.ntp.org iburst\nserver -4 ntp.lan prefer\n\
+                      server 127.127.1.0\ndriftfile /var/lib/chrony/drift\n";
+        assert_eq!(
+            parse_ntpd_config(chrony),
+            vec!["2.debian.pool.ntp.org", "ntp.lan"]
+        );
+        let timesyncd = "[Time]\nNTP=time1.example.com time2.example.com\n#FallbackNTP=x\n";
+        assert_eq!(
+            parse_timesyncd_config(timesyncd),
+            vec!["time1.example.com", "time2.example.com"]
+        );
+        let reg = "\r\nHKEY_LOCAL_MACHINE\\SYSTEM\\CurrentControlSet\\Services\\W32Time\\Parameters\r\n    NtpServer    REG_SZ    time.windows.com,0x9 ntp.lan,0x8\r\n";
+        assert_eq!(
+            parse_w32time_servers(reg),
+            vec!["time.windows.com", "ntp.lan"]
+        );
+    }
+}
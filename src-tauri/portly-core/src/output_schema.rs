@@ -0,0 +1,170 @@
+//! CLI JSON 输出契约
+//!
+//! `portly-cli -j` / `--output json-v1` 的输出结构。每个文档都带有 `schema_version`，
+//! 同一版本内只允许新增字段，删除或重命名字段必须提升版本号；字段集合由下方测试锁定
+
+use crate::core::{AppGroup, PortInfo};
+use crate::network::RemotePort;
+use serde::Serialize;
+
+/// 当前 JSON 输出版本
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// 支持的 JSON 输出版本
+pub const SUPPORTED_JSON_SCHEMA_VERSIONS: &[u32] = &[1];
+
+/// 解析 `json` / `json-v1` 形式的输出格式名，返回对应版本
+pub fn parse_json_schema_version(name: &str) -> Option<Result<u32, String>> {
+    let name = name.trim().to_lowercase();
+    if name == "json" {
+        return Some(Ok(JSON_SCHEMA_VERSION));
+    }
+    let version = name.strip_prefix("json-v")?;
+    Some(match version.parse::<u32>() {
+        Ok(v) if SUPPORTED_JSON_SCHEMA_VERSIONS.contains(&v) => Ok(v),
+        _ => Err(format!(
+            "不支持的 JSON 输出版本: {} (支持 json-v{})",
+            name, JSON_SCHEMA_VERSION
+        )),
+    })
+}
+
+/// 本机端口列表（`portly-cli -j`）
+#[derive(Debug, Serialize)]
+pub struct JsonPortsOutput<'a> {
+    pub schema_version: u32,
+    pub scan_time: &'a str,
+    pub total_ports: usize,
+    pub ports: &'a [PortInfo],
+}
+
+/// 按应用分组（`portly-cli -g -j`）
+#[derive(Debug, Serialize)]
+pub struct JsonGroupsOutput<'a> {
+    pub schema_version: u32,
+    pub total_groups: usize,
+    pub groups: &'a [AppGroup],
+}
+
+/// 远程端口扫描（`portly-cli scan <target> -j`）
+#[derive(Debug, Serialize)]
+pub struct JsonRemoteScanOutput<'a> {
+    pub schema_version: u32,
+    pub target: &'a str,
+    pub ip: &'a str,
+    pub scanned_ports: usize,
+    pub open_ports: usize,
+    pub elapsed_ms: u128,
+    pub ports: &'a [RemotePort],
+    /// 资产清单中的标签，没有时省略
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    pub tags: &'a [String],
+}
+
+impl<'a> JsonPortsOutput<'a> {
+    pub fn new(scan_time: &'a str, ports: &'a [PortInfo]) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            scan_time,
+            total_ports: ports.len(),
+            ports,
+        }
+    }
+}
+
+impl<'a> JsonGroupsOutput<'a> {
+    pub fn new(groups: &'a [AppGroup]) -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            total_groups: groups.len(),
+            groups,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(value: &serde_json::Value) -> Vec<String> {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn test_json_v1_contract() {
+        let ports = vec![PortInfo {
+            port: 8080,
+            protocol: "IPv4".to_string(),
+            address: "127.0.0.1".to_string(),
+            pid: "1234".to_string(),
+            process: "node".to_string(),
+            user: "dev".to_string(),
+            command: None,
+        }];
+        let value =
+            serde_json::to_value(JsonPortsOutput::new("2026-01-01 10:00:00", &ports)).unwrap();
+        assert_eq!(value["schema_version"], 1);
+        assert_eq!(
+            keys(&value),
+            ["ports", "scan_time", "schema_version", "total_ports"]
+        );
+        assert_eq!(
+            keys(&value["ports"][0]),
+            ["address", "command", "pid", "port", "process", "protocol", "user"]
+        );
+
+        let groups = vec![AppGroup {
+            process: "node".to_string(),
+            pid: "1234".to_string(),
+            ports: vec![8080],
+            command: None,
+        }];
+        let value = serde_json::to_value(JsonGroupsOutput::new(&groups)).unwrap();
+        assert_eq!(keys(&value), ["groups", "schema_version", "total_groups"]);
+        assert_eq!(
+            keys(&value["groups"][0]),
+            ["command", "pid", "ports", "process"]
+        );
+
+        let remote = vec![RemotePort {
+            port: 22,
+            is_open: true,
+            service: Some("SSH".to_string()),
+            policy: None,
+        }];
+        let value = serde_json::to_value(JsonRemoteScanOutput {
+            schema_version: JSON_SCHEMA_VERSION,
+            target: "example.com",
+            ip: "93.184.216.34",
+            scanned_ports: 1,
+            open_ports: 1,
+            elapsed_ms: 12,
+            ports: &remote,
+            tags: &[],
+        })
+        .unwrap();
+        assert_eq!(
+            keys(&value),
+            [
+                "elapsed_ms",
+                "ip",
+                "open_ports",
+                "ports",
+                "scanned_ports",
+                "schema_version",
+                "target"
+            ]
+        );
+        assert_eq!(keys(&value["ports"][0]), ["is_open", "port", "service"]);
+    }
+
+    #[test]
+    fn test_parse_json_schema_version() {
+        assert_eq!(parse_json_schema_version("json"), Some(Ok(1)));
+        assert_eq!(parse_json_schema_version("JSON-v1"), Some(Ok(1)));
+        assert!(matches!(parse_json_schema_version("json-v9"), Some(Err(_))));
+        assert_eq!(parse_json_schema_version("csv"), None);
+    }
+}
@@ -0,0 +1,278 @@
+//! 监听端口归属
+//!
+//! 把本机所有监听端口分到四类：宿主机进程、容器（端口映射到容器，或由 docker-proxy、
+//! vpnkit 等容器网络代理持有）、虚拟机（VirtualBox / VMware / Parallels / QEMU 的 NAT
+//! 端口转发进程）和系统服务，供仪表盘展示“端口都被谁占着”。容器运行时不可用时只按进程名判断
+
+use crate::app_error::AppResult;
+use crate::core::PortInfo;
+use crate::docker::DockerContainer;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// 容器运行时用来把宿主机端口转发进容器的进程
+const CONTAINER_PROXIES: &[&str] = &[
+    "docker-proxy",
+    "com.docker.backend",
+    "com.docker.vpnkit",
+    "vpnkit",
+    "rootlessport",
+    "rootlesskit",
+    "slirp4netns",
+    "pasta",
+    "gvproxy",
+    "conmon",
+    "wslrelay",
+];
+
+/// 虚拟机软件的 NAT / 端口转发进程（按前缀匹配）
+const VM_PROCESSES: &[&str] = &[
+    "vboxheadless",
+    "virtualboxvm",
+    "vboxnetnat",
+    "vboxnetdhcp",
+    "vboxsvc",
+    "vmnat",
+    "vmnet-natd",
+    "vmware-natd",
+    "vmnetdhcp",
+    "vmware-authd",
+    "vmware-hostd",
+    "prl_naptd",
+    "prl_vm_app",
+    "qemu-system",
+    "vmms",
+    "vmcompute",
+];
+
+/// 各平台的系统服务进程
+const SYSTEM_PROCESSES: &[&str] = &[
+    // macOS
+    "launchd",
+    "rapportd",
+    "controlce",
+    "mdnsresponder",
+    "netbiosd",
+    "sharingd",
+    "remoted",
+    // Linux
+    "systemd",
+    "systemd-resolve",
+    "systemd-resolved",
+    "sshd",
+    "cupsd",
+    "avahi-daemon",
+    "dnsmasq",
+    "chronyd",
+    "rpcbind",
+    "rpc.statd",
+    "dbus-daemon",
+    "networkmanager",
+    // Windows
+    "system",
+    "svchost",
+    "svchost.exe",
+    "lsass",
+    "lsass.exe",
+    "services",
+    "services.exe",
+    "wininit",
+    "wininit.exe",
+    "spoolsv",
+    "spoolsv.exe",
+];
+
+/// 端口归属类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnerKind {
+    Host,
+    Container,
+    Vm,
+    System,
+}
+
+impl OwnerKind {
+    pub const ALL: [OwnerKind; 4] = [
+        OwnerKind::Host,
+        OwnerKind::Container,
+        OwnerKind::Vm,
+        OwnerKind::System,
+    ];
+}
+
+/// 一个监听端口的归属
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListenerOwner {
+    pub port: u16,
+    pub protocol: String,
+    pub address: String,
+    pub pid: String,
+    pub process: String,
+    pub kind: OwnerKind,
+    /// 端口映射到的容器名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+/// 一类归属的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipBucket {
+    pub kind: OwnerKind,
+    /// 监听套接字数（同一端口的 IPv4 / IPv6 分别计数）
+    pub listeners: usize,
+    /// 去重后的端口，升序
+    pub ports: Vec<u16>,
+}
+
+/// 监听端口归属汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OwnershipSummary {
+    pub generated_at: String,
+    pub total: usize,
+    /// 固定按宿主机、容器、虚拟机、系统服务的顺序，没有端口的类别计数为 0
+    pub buckets: Vec<OwnershipBucket>,
+    /// 按端口排列
+    pub listeners: Vec<ListenerOwner>,
+}
+
+/// 读取本机监听端口和容器列表，生成归属汇总
+#[tracing::instrument(level = "info")]
+pub fn listener_ownership() -> AppResult<OwnershipSummary> {
+    let ports = crate::core::try_get_listening_ports()?;
+    let containers = if crate::docker::is_docker_available() {
+        crate::docker::get_docker_containers()
+    } else {
+        Vec::new()
+    };
+    Ok(summarize_ownership(&ports, &containers))
+}
+
+/// 按进程名和容器端口映射给监听端口分类
+pub fn summarize_ownership(ports: &[PortInfo], containers: &[DockerContainer]) -> OwnershipSummary {
+    let mapped: HashMap<u16, &str> = containers
+        .iter()
+        .flat_map(|c| c.ports.iter().map(move |p| (p.host_port, c.name.as_str())))
+        .collect();
+
+    let mut listeners: Vec<ListenerOwner> = ports
+        .iter()
+        .map(|p| {
+            let container = mapped.get(&p.port).map(|name| name.to_string());
+            ListenerOwner {
+                port: p.port,
+                protocol: p.protocol.clone(),
+                address: p.address.clone(),
+                pid: p.pid.clone(),
+                process: p.process.clone(),
+                kind: classify_process(&p.process, container.is_some()),
+                container,
+            }
+        })
+        .collect();
+    listeners.sort_by_key(|l| (l.port, l.kind));
+
+    let buckets = OwnerKind::ALL
+        .into_iter()
+        .map(|kind| {
+            let owned = listeners.iter().filter(|l| l.kind == kind);
+            OwnershipBucket {
+                kind,
+                listeners: owned.clone().count(),
+                ports: owned
+                    .map(|l| l.port)
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+            }
+        })
+        .collect();
+
+    OwnershipSummary {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        total: listeners.len(),
+        buckets,
+        listeners,
+    }
+}
+
+/// 映射到容器的端口归容器；其余按进程名判断
+fn classify_process(process: &str, mapped_to_container: bool) -> OwnerKind {
+    let name = process.to_ascii_lowercase();
+    if mapped_to_container || CONTAINER_PROXIES.contains(&name.as_str()) {
+        OwnerKind::Container
+    } else if VM_PROCESSES.iter().any(|vm| name.starts_with(vm)) {
+        OwnerKind::Vm
+    } else if SYSTEM_PROCESSES.contains(&name.as_str()) {
+        OwnerKind::System
+    } else {
+        OwnerKind::Host
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docker::DockerPort;
+
+    fn listener(port: u16, process: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "TCP".to_string(),
+            address: "0.0.0.0".to_string(),
+            pid: "1".to_string(),
+            process: process.to_string(),
+            user: "me".to_string(),
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_ownership() {
+        let ports = vec![
+            listener(3000, "node"),
+            listener(5432, "com.docker.backend"),
+            listener(6379, "redis-server"),
+            listener(2222, "VBoxHeadless"),
+            listener(8006, "qemu-system-x86_64"),
+            listener(22, "sshd"),
+            listener(22, "sshd"),
+            listener(135, "svchost.exe"),
+        ];
+        let containers = vec![DockerContainer {
+            id: "abc".to_string(),
+            name: "cache".to_string(),
+            image: "redis:7".to_string(),
+            status: "Up".to_string(),
+            ports: vec![DockerPort {
+                host_port: 6379,
+                container_port: 6379,
+                protocol: "tcp".to_string(),
+                host_ip: "0.0.0.0".to_string(),
+            }],
+            state: "running".to_string(),
+            labels: HashMap::new(),
+            networks: Vec::new(),
+            exposed_ports: Vec::new(),
+            ip_address: None,
+            health: None,
+        }];
+
+        let summary = summarize_ownership(&ports, &containers);
+        assert_eq!(summary.total, 8);
+        let bucket = |kind| summary.buckets.iter().find(|b| b.kind == kind).unwrap();
+        assert_eq!(bucket(OwnerKind::Host).ports, vec![3000]);
+        assert_eq!(bucket(OwnerKind::Container).ports, vec![5432, 6379]);
+        assert_eq!(bucket(OwnerKind::Vm).ports, vec![2222, 8006]);
+        assert_eq!(bucket(OwnerKind::System).ports, vec![22, 135]);
+        assert_eq!(bucket(OwnerKind::System).listeners, 3);
+
+        let redis = summary.listeners.iter().find(|l| l.port == 6379).unwrap();
+        assert_eq!(redis.container.as_deref(), Some("cache"));
+        assert_eq!(summary.listeners[0].port, 22);
+
+        let empty = summarize_ownership(&[], &[]);
+        assert_eq!(empty.buckets.len(), 4);
+        assert!(empty.buckets.iter().all(|b| b.listeners == 0));
+    }
+}
@@ -0,0 +1,261 @@
+//! 多目标 Ping 对比
+//!
+//! 每一轮同时向所有目标各发一次 Ping，连续若干轮后按目标汇总丢包率、最小 / 平均 / 最大延迟
+//! 和抖动，便于把网关、DNS 和公网地址放在同一张图里比较，判断问题出在局域网还是上游。
+//! 抖动取相邻两次成功回复的延迟差的平均值；超时的一轮在 `samples` 中记为空
+
+use crate::app_error::{AppError, AppResult};
+use crate::network::PingOneResult;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+/// 单次对比最多的目标数
+pub const MAX_TARGETS: usize = 16;
+
+/// 轮数范围
+const ROUNDS_RANGE: RangeInclusive<u32> = 1..=100;
+
+/// 两轮之间的间隔范围（毫秒）
+const INTERVAL_RANGE_MS: RangeInclusive<u64> = 200..=10_000;
+
+/// 一个目标的对比结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetPingStats {
+    pub target: String,
+    pub sent: u32,
+    pub received: u32,
+    /// 丢包率（百分比）
+    pub loss_percent: f32,
+    pub min_ms: Option<f32>,
+    pub avg_ms: Option<f32>,
+    pub max_ms: Option<f32>,
+    /// 相邻两次成功回复的延迟差的平均值，成功回复少于两次时为空
+    pub jitter_ms: Option<f32>,
+    /// 每一轮的延迟，超时为空，供前端绘制折线
+    pub samples: Vec<Option<f32>>,
+}
+
+/// 多目标 Ping 对比结果，目标按输入顺序排列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingComparison {
+    pub started_at: String,
+    pub rounds: u32,
+    pub interval_ms: u64,
+    pub targets: Vec<TargetPingStats>,
+}
+
+/// 校验目标、轮数和间隔；重复的目标只保留一个
+pub fn validate_request(
+    targets: &[String],
+    rounds: u32,
+    interval_ms: u64,
+) -> AppResult<Vec<String>> {
+    let mut unique: Vec<String> = Vec::new();
+    for target in targets.iter().map(|t| t.trim()) {
+        if !target.is_empty() && !unique.iter().any(|t| t == target) {
+            unique.push(target.to_string());
+        }
+    }
+    if unique.is_empty() || unique.len() > MAX_TARGETS {
+        return Err(AppError::validation(
+            "Ping 目标",
+            format!("需要 1-{} 个目标", MAX_TARGETS),
+        ));
+    }
+    if !ROUNDS_RANGE.contains(&rounds) {
+        return Err(AppError::validation(
+            "Ping 次数",
+            format!("应在 {}-{} 之间", ROUNDS_RANGE.start(), ROUNDS_RANGE.end()),
+        ));
+    }
+    if !INTERVAL_RANGE_MS.contains(&interval_ms) {
+        return Err(AppError::validation(
+            "Ping 间隔",
+            format!(
+                "应在 {}-{} 毫秒之间",
+                INTERVAL_RANGE_MS.start(),
+                INTERVAL_RANGE_MS.end()
+            ),
+        ));
+    }
+    Ok(unique)
+}
+
+/// 按每一轮的延迟汇总一个目标
+pub fn target_stats(target: &str, samples: Vec<Option<f32>>) -> TargetPingStats {
+    let replies: Vec<f32> = samples.iter().flatten().copied().collect();
+    let sent = samples.len() as u32;
+    let received = replies.len() as u32;
+    let loss_percent = if sent == 0 {
+        0.0
+    } else {
+        (sent - received) as f32 * 100.0 / sent as f32
+    };
+    let avg_ms = (!replies.is_empty()).then(|| replies.iter().sum::<f32>() / replies.len() as f32);
+    let jitter_ms = (replies.len() >= 2).then(|| {
+        let deltas: f32 = replies.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+        deltas / (replies.len() - 1) as f32
+    });
+    TargetPingStats {
+        target: target.to_string(),
+        sent,
+        received,
+        loss_percent,
+        min_ms: replies.iter().copied().reduce(f32::min),
+        avg_ms,
+        max_ms: replies.iter().copied().reduce(f32::max),
+        jitter_ms,
+        samples,
+    }
+}
+
+/// 同时 Ping 多个目标 `rounds` 轮，每轮间隔 `interval`
+///
+/// `ping` 发送一次 Ping（参数为目标和轮次）；每轮结束后回调 `on_round` 传入本轮各目标的结果，
+/// 回调返回错误（如任务被取消）时停止并返回该错误。调用方需先用 [`validate_request`] 校验参数
+pub fn compare_with<P, F>(
+    targets: &[String],
+    rounds: u32,
+    interval: Duration,
+    ping: P,
+    mut on_round: F,
+) -> AppResult<PingComparison>
+where
+    P: Fn(&str, u32) -> PingOneResult + Sync,
+    F: FnMut(u32, &[PingOneResult]) -> AppResult<()>,
+{
+    let started_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut samples: Vec<Vec<Option<f32>>> = vec![Vec::new(); targets.len()];
+    for round in 0..rounds {
+        let started = Instant::now();
+        let results: Vec<PingOneResult> = std::thread::scope(|scope| {
+            let handles: Vec<_> = targets
+                .iter()
+                .map(|target| scope.spawn(|| ping(target, round)))
+                .collect();
+            handles
+                .into_iter()
+                .zip(targets)
+                .map(|(handle, target)| {
+                    handle.join().unwrap_or_else(|_| PingOneResult {
+                        ip: target.clone(),
+                        seq: round,
+                        success: false,
+                        time_ms: None,
+                        ttl: None,
+                        line: String::new(),
+                    })
+                })
+                .collect()
+        });
+        for (history, result) in samples.iter_mut().zip(&results) {
+            history.push(if result.success { result.time_ms } else { None });
+        }
+        on_round(round + 1, &results)?;
+
+        if round + 1 < rounds {
+            std::thread::sleep(interval.saturating_sub(started.elapsed()));
+        }
+    }
+
+    let targets: Vec<TargetPingStats> = targets
+        .iter()
+        .zip(samples)
+        .map(|(target, samples)| target_stats(target, samples))
+        .collect();
+    tracing::info!(targets = targets.len(), rounds, "多目标 Ping 对比完成");
+    Ok(PingComparison {
+        started_at,
+        rounds,
+        interval_ms: interval.as_millis() as u64,
+        targets,
+    })
+}
+
+/// 用系统 `ping` 同时 Ping 多个目标
+#[tracing::instrument(level = "info", skip(on_round))]
+pub fn compare<F>(
+    targets: &[String],
+    rounds: u32,
+    interval_ms: u64,
+    on_round: F,
+) -> AppResult<PingComparison>
+where
+    F: FnMut(u32, &[PingOneResult]) -> AppResult<()>,
+{
+    let targets = validate_request(targets, rounds, interval_ms)?;
+    compare_with(
+        &targets,
+        rounds,
+        Duration::from_millis(interval_ms),
+        crate::network::ping_one,
+        on_round,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_stats_loss_and_jitter() {
+        let stats = target_stats("192.168.1.1", vec![Some(2.0), Some(4.0), None, Some(3.0)]);
+        assert_eq!(stats.sent, 4);
+        assert_eq!(stats.received, 3);
+        assert_eq!(stats.loss_percent, 25.0);
+        assert_eq!(stats.min_ms, Some(2.0));
+        assert_eq!(stats.max_ms, Some(4.0));
+        assert_eq!(stats.avg_ms, Some(3.0));
+        // |4-2| 和 |3-4| 的平均
+        assert_eq!(stats.jitter_ms, Some(1.5));
+
+        let lost = target_stats("203.0.113.9", vec![None, None]);
+        assert_eq!(lost.loss_percent, 100.0);
+        assert_eq!(lost.avg_ms, None);
+        assert_eq!(lost.jitter_ms, None);
+    }
+
+    #[test]
+    fn test_compare_with_pings_every_target_each_round() {
+        let targets = validate_request(
+            &[
+                "192.168.1.1".to_string(),
+                " 1.1.1.1 ".to_string(),
+                "192.168.1.1".to_string(),
+            ],
+            3,
+            200,
+        )
+        .unwrap();
+        assert_eq!(targets, vec!["192.168.1.1", "1.1.1.1"]);
+
+        let ping = |target: &str, round: u32| PingOneResult {
+            ip: target.to_string(),
+            seq: round,
+            success: target == "192.168.1.1" || round != 1,
+            time_ms: Some(if target == "1.1.1.1" { 20.0 } else { 1.0 }),
+            ttl: Some(64),
+            line: String::new(),
+        };
+        let mut rounds_seen = Vec::new();
+        let comparison = compare_with(&targets, 3, Duration::ZERO, ping, |round, results| {
+            assert_eq!(results.len(), 2);
+            rounds_seen.push(round);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(rounds_seen, vec![1, 2, 3]);
+        assert_eq!(comparison.targets[0].loss_percent, 0.0);
+        assert_eq!(
+            comparison.targets[1].samples,
+            vec![Some(20.0), None, Some(20.0)]
+        );
+        assert_eq!(comparison.targets[1].jitter_ms, Some(0.0));
+
+        assert!(validate_request(&[], 3, 1000).is_err());
+        assert!(validate_request(&targets, 0, 1000).is_err());
+        assert!(validate_request(&targets, 3, 50).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! 服务探测插件
+//!
+//! 数据目录下 `plugins/` 中的每个 `*.json` 清单描述一个外部可执行程序，用于识别内部或
+//! 私有服务。探测时以 `<command> [args...] <ip> <port>` 调用插件（同时设置环境变量
+//! `PORTLY_IP`、`PORTLY_PORT`），插件在 stdout 输出 JSON：
+//!
+//! ```json
+//! {"service": "Acme Billing", "service_type": "api", "server": "acme/2.1"}
+//! ```
+//!
+//! 无输出、输出 `null` 或退出码非 0 表示不认识该服务，继续使用内置探测
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_timeout;
+use crate::network::ServiceInfo;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// 插件默认超时
+const DEFAULT_TIMEOUT_MS: u64 = 5_000;
+
+/// 已加载的插件，首次探测时从插件目录读取
+static LOADED: RwLock<Option<Vec<ProbePlugin>>> = RwLock::new(None);
+
+/// 插件清单
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbePlugin {
+    pub name: String,
+    /// 可执行文件；相对路径按清单所在目录解析，找不到时从 PATH 查找
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 只探测这些端口，为空时探测所有端口
+    #[serde(default)]
+    pub ports: Vec<u16>,
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 清单文件路径，加载时填写
+    #[serde(default)]
+    pub manifest: String,
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT_MS
+}
+
+/// 插件输出
+#[derive(Debug, Deserialize)]
+struct PluginOutput {
+    service: String,
+    #[serde(default)]
+    service_type: Option<String>,
+    #[serde(default)]
+    server: Option<String>,
+    #[serde(default)]
+    content_type: Option<String>,
+}
+
+impl ProbePlugin {
+    pub fn handles(&self, port: u16) -> bool {
+        self.ports.is_empty() || self.ports.contains(&port)
+    }
+}
+
+/// 插件目录
+pub fn plugins_dir() -> PathBuf {
+    crate::export::get_data_dir().join("plugins")
+}
+
+/// 读取目录下的全部插件清单，无效的清单记录日志后跳过
+pub fn load_plugins(dir: &Path) -> Vec<ProbePlugin> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut manifests: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    manifests.sort();
+
+    manifests
+        .into_iter()
+        .filter_map(|path| match load_manifest(&path) {
+            Ok(plugin) => Some(plugin),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), error = %err, "插件清单无效，已跳过");
+                None
+            }
+        })
+        .collect()
+}
+
+fn load_manifest(path: &Path) -> AppResult<ProbePlugin> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::internal(format!("读取插件清单失败: {}", e)))?;
+    let mut plugin: ProbePlugin = serde_json::from_str(&content)
+        .map_err(|e| AppError::validation("插件清单", e.to_string()))?;
+    if plugin.name.trim().is_empty() || plugin.command.trim().is_empty() {
+        return Err(AppError::validation("插件清单", "name 和 command 不能为空"));
+    }
+    let local = path
+        .parent()
+        .map(|dir| dir.join(&plugin.command))
+        .filter(|candidate| candidate.is_file());
+    if let Some(local) = local {
+        plugin.command = local.to_string_lossy().to_string();
+    }
+    plugin.manifest = path.to_string_lossy().to_string();
+    Ok(plugin)
+}
+
+/// 当前加载的插件
+pub fn current() -> Vec<ProbePlugin> {
+    if let Some(plugins) = LOADED.read().ok().and_then(|guard| guard.clone()) {
+        return plugins;
+    }
+    reload()
+}
+
+/// 重新扫描插件目录
+pub fn reload() -> Vec<ProbePlugin> {
+    let plugins = load_plugins(&plugins_dir());
+    tracing::info!(count = plugins.len(), "已加载服务探测插件");
+    // 插件变化后之前的探测结果可能不再准确
+    crate::cache::shared().clear(Some(crate::cache::CacheKind::Services));
+    if let Ok(mut guard) = LOADED.write() {
+        *guard = Some(plugins.clone());
+    }
+    plugins
+}
+
+/// 依次用处理该端口的插件探测，返回第一个识别出的服务
+pub fn probe(ip: &str, port: u16) -> Option<ServiceInfo> {
+    current()
+        .iter()
+        .filter(|plugin| plugin.handles(port))
+        .find_map(|plugin| match run_plugin(plugin, ip, port) {
+            Ok(info) => info,
+            Err(err) => {
+                tracing::warn!(plugin = %plugin.name, error = %err, "探测插件执行失败");
+                None
+            }
+        })
+}
+
+/// 执行单个插件；插件不认识该服务时返回 `None`
+#[tracing::instrument(level = "debug", skip(plugin), fields(plugin = %plugin.name))]
+pub fn run_plugin(plugin: &ProbePlugin, ip: &str, port: u16) -> AppResult<Option<ServiceInfo>> {
+    let port_arg = port.to_string();
+    let output = run_command_with_timeout(
+        &plugin.command,
+        "服务探测插件",
+        |cmd| {
+            cmd.args(&plugin.args)
+                .args([ip, port_arg.as_str()])
+                .env("PORTLY_IP", ip)
+                .env("PORTLY_PORT", &port_arg)
+                .stdin(Stdio::null());
+        },
+        Duration::from_millis(plugin.timeout_ms),
+    )?;
+    if output.status != 0 {
+        tracing::debug!(status = output.status, "插件未识别该服务");
+        return Ok(None);
+    }
+    parse_output(&output.stdout, port)
+}
+
+fn parse_output(stdout: &str, port: u16) -> AppResult<Option<ServiceInfo>> {
+    let stdout = stdout.trim();
+    if stdout.is_empty() || stdout == "null" {
+        return Ok(None);
+    }
+    let output: PluginOutput = serde_json::from_str(stdout)
+        .map_err(|e| AppError::validation("插件输出", e.to_string()))?;
+    Ok(Some(ServiceInfo {
+        port,
+        service: output.service,
+        service_type: output.service_type.unwrap_or_else(|| "other".to_string()),
+        server: output.server,
+        content_type: output.content_type,
+        version_hints: Vec::new(),
+        security_headers: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        assert!(parse_output("", 80).unwrap().is_none());
+        assert!(parse_output("null\n", 80).unwrap().is_none());
+        let info = parse_output(r#"{"service":"Acme","server":"acme/2"}"#, 9100)
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.port, 9100);
+        assert_eq!(info.service, "Acme");
+        assert_eq!(info.service_type, "other");
+        assert_eq!(info.server.as_deref(), Some("acme/2"));
+        assert!(parse_output("not json", 80).is_err());
+    }
+
+    #[test]
+    fn test_load_plugins_skips_invalid_manifests() {
+        let dir = std::env::temp_dir().join(format!("portly_plugins_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("probe.sh"), "#!/bin/sh\n").unwrap();
+        std::fs::write(
+            dir.join("acme.json"),
+            r#"{"name":"acme","command":"probe.sh","ports":[9100]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("broken.json"), r#"{"name":""}"#).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let plugins = load_plugins(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(plugins.len(), 1);
+        assert!(plugins[0].command.ends_with("probe.sh"));
+        assert!(Path::new(&plugins[0].command).is_absolute());
+        assert_eq!(plugins[0].timeout_ms, DEFAULT_TIMEOUT_MS);
+        assert!(plugins[0].handles(9100));
+        assert!(!plugins[0].handles(80));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_plugin_passes_target() {
+        let plugin = ProbePlugin {
+            name: "echo".to_string(),
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"[ "$2" = "$PORTLY_PORT" ] && printf '{"service":"svc-%s"}' "$1""#.to_string(),
+                "probe".to_string(),
+            ],
+            ports: vec![],
+            timeout_ms: DEFAULT_TIMEOUT_MS,
+            manifest: String::new(),
+        };
+        let info = run_plugin(&plugin, "10.0.0.5", 7000).unwrap().unwrap();
+        assert_eq!(info.service, "svc-10.0.0.5");
+        assert_eq!(info.port, 7000);
+    }
+}
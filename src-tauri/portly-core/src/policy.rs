@@ -0,0 +1,210 @@
+//! 端口策略
+//!
+//! 用户在共享配置文件中按端口或服务定义严重程度，如“任何地址上的 Telnet = critical”、
+//! “监听 0.0.0.0 的 Postgres = high”：
+//!
+//! ```toml
+//! [[policies]]
+//! name = "Telnet anywhere"
+//! service = "telnet"
+//! severity = "critical"
+//!
+//! [[policies]]
+//! name = "Postgres on all interfaces"
+//! port = 5432
+//! scope = "wildcard"
+//! severity = "high"
+//! ```
+//!
+//! 本机扫描按监听地址判断范围；远程扫描能连上的端口一定可从网络访问，所有范围都算命中。
+//! 命中结果写入远程扫描结果、安全审计报告，并可由 `policy_violation` 规则触发动作
+
+use crate::app_error::{AppError, AppResult};
+use crate::audit::Severity;
+use crate::core::PortInfo;
+use crate::rules::{is_loopback, is_wildcard};
+use serde::{Deserialize, Serialize};
+
+/// 一条端口策略
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortPolicy {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// 服务名或进程名，不区分大小写，包含即匹配；与 `port` 同时设置时都需满足
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    #[serde(default)]
+    pub scope: PolicyScope,
+    pub severity: Severity,
+}
+
+/// 策略适用的监听范围
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyScope {
+    /// 任何地址，包括回环
+    #[default]
+    Any,
+    /// 回环以外的地址
+    Exposed,
+    /// 所有网卡（`0.0.0.0`、`::`、`*`）
+    Wildcard,
+}
+
+/// 端口命中的策略
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyMatch {
+    pub policy: String,
+    pub severity: Severity,
+}
+
+impl PortPolicy {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::validation("策略名称", "不能为空"));
+        }
+        if self.port == Some(0) {
+            return Err(AppError::validation("策略端口", "必须大于 0"));
+        }
+        let service = self.service.as_deref().map(str::trim).unwrap_or_default();
+        if self.port.is_none() && service.is_empty() {
+            return Err(AppError::validation("端口策略", "需要指定端口或服务名"));
+        }
+        Ok(())
+    }
+
+    /// `address` 为 None 表示远程扫描结果，视为可从网络访问
+    fn matches(&self, port: u16, names: &[&str], address: Option<&str>) -> bool {
+        if self.port.is_some_and(|p| p != port) {
+            return false;
+        }
+        if let Some(service) = self.service.as_deref().map(str::to_lowercase) {
+            if !names
+                .iter()
+                .any(|name| name.to_lowercase().contains(&service))
+            {
+                return false;
+            }
+        }
+        match self.scope {
+            PolicyScope::Any => true,
+            PolicyScope::Exposed => address.is_none_or(|a| !is_loopback(a)),
+            PolicyScope::Wildcard => address.is_none_or(is_wildcard),
+        }
+    }
+}
+
+/// 配置文件中的端口策略；读取失败时记录警告并视为没有策略
+pub fn current() -> Vec<PortPolicy> {
+    match crate::config::load_config() {
+        Ok(config) => config.policies,
+        Err(err) => {
+            tracing::warn!(error = %err, "读取端口策略失败");
+            Vec::new()
+        }
+    }
+}
+
+/// 命中的策略中严重程度最高的一条
+pub fn check(
+    policies: &[PortPolicy],
+    port: u16,
+    names: &[&str],
+    address: Option<&str>,
+) -> Option<PolicyMatch> {
+    policies
+        .iter()
+        .filter(|policy| policy.matches(port, names, address))
+        .max_by_key(|policy| policy.severity)
+        .map(|policy| PolicyMatch {
+            policy: policy.name.clone(),
+            severity: policy.severity,
+        })
+}
+
+/// 检查本机监听端口，按进程名和常用端口库中的服务名匹配
+pub fn check_listening(policies: &[PortPolicy], info: &PortInfo) -> Option<PolicyMatch> {
+    if policies.is_empty() {
+        return None;
+    }
+    let service = crate::network::get_service_name(info.port).unwrap_or_default();
+    check(
+        policies,
+        info.port,
+        &[&info.process, &service],
+        Some(&info.address),
+    )
+}
+
+/// 检查远程扫描中开放的端口
+pub fn check_remote(
+    policies: &[PortPolicy],
+    port: u16,
+    service: Option<&str>,
+) -> Option<PolicyMatch> {
+    check(policies, port, service.as_slice(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(
+        name: &str,
+        port: Option<u16>,
+        service: Option<&str>,
+        scope: PolicyScope,
+    ) -> PortPolicy {
+        PortPolicy {
+            name: name.to_string(),
+            port,
+            service: service.map(str::to_string),
+            scope,
+            severity: Severity::High,
+        }
+    }
+
+    #[test]
+    fn test_policy_scope_and_service_matching() {
+        let mut telnet = policy("telnet", None, Some("telnet"), PolicyScope::Any);
+        telnet.severity = Severity::Critical;
+        let postgres = policy("pg", Some(5432), Some("postgres"), PolicyScope::Wildcard);
+        let policies = vec![telnet, postgres];
+
+        let hit = check(&policies, 23, &["telnetd"], Some("127.0.0.1")).unwrap();
+        assert_eq!(hit.severity, Severity::Critical);
+        assert_eq!(
+            check(&policies, 5432, &["postgres"], Some("0.0.0.0"))
+                .unwrap()
+                .policy,
+            "pg"
+        );
+        assert!(check(&policies, 5432, &["postgres"], Some("[::]")).is_some());
+        assert!(check(&policies, 5432, &["postgres"], Some("127.0.0.1")).is_none());
+        assert!(check(&policies, 5432, &["mysqld"], Some("*")).is_none());
+        // 远程扫描结果视为监听在所有网卡
+        assert!(check_remote(&policies, 5432, Some("PostgreSQL")).is_some());
+        assert!(check_remote(&policies, 5433, Some("PostgreSQL")).is_none());
+
+        let exposed = policy("redis", Some(6379), None, PolicyScope::Exposed);
+        assert!(exposed.matches(6379, &[], Some("192.168.1.5")));
+        assert!(!exposed.matches(6379, &[], Some("::1")));
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        assert!(policy("ok", Some(22), None, PolicyScope::Any)
+            .validate()
+            .is_ok());
+        assert!(policy("", Some(22), None, PolicyScope::Any)
+            .validate()
+            .is_err());
+        assert!(policy("none", None, Some(" "), PolicyScope::Any)
+            .validate()
+            .is_err());
+        assert!(policy("zero", Some(0), None, PolicyScope::Any)
+            .validate()
+            .is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! 端口转发检测
+//!
+//! 判断路由器上的端口转发是否真的通到本机（“我的游戏服务器能从公网访问吗？”）。
+//! 本机无法从 NAT 内部验证公网可达性，需要请 NAT 外的反射服务回连：反射服务可以是运行在
+//! 云主机上的 `portly-cli serve`，也可以是实现了同样接口的任意 HTTP 服务——
+//! `GET <url>/api/reflect?port=<端口>[&host=<地址>]`，未给出 host 时回连请求方的公网地址，
+//! 返回 [`ReflectResult`]。请求通过系统的 `curl` 发送（与 [`crate::webhook`] 相同）。
+//! 回连失败时结合本机监听情况给出原因：没有进程监听、只监听回环地址，或被路由器 / 防火墙拦截
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_timeout;
+use crate::core::PortInfo;
+use crate::settings::ReflectorSettings;
+use serde::{Deserialize, Serialize};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// 反射服务回连的默认超时
+pub const DEFAULT_REFLECT_TIMEOUT_MS: u64 = 3000;
+/// 反射服务回连超时的上限
+pub const MAX_REFLECT_TIMEOUT_MS: u64 = 10_000;
+/// 请求反射服务本身的额外等待时间
+const REQUEST_OVERHEAD: Duration = Duration::from_secs(10);
+
+/// 反射服务的回连结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReflectResult {
+    /// 回连的地址
+    pub host: String,
+    pub port: u16,
+    pub reachable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<f32>,
+    /// 回连失败的原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 检测结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortForwardVerdict {
+    /// 从公网可以连通
+    Reachable,
+    /// 本机没有进程监听该端口
+    NoListener,
+    /// 本机只在回环地址上监听，外部连接到不了
+    LoopbackOnly,
+    /// 本机在监听，但公网连不进来：路由器未转发、防火墙拦截或运营商封锁
+    Blocked,
+}
+
+/// 端口转发检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortForwardCheck {
+    pub port: u16,
+    /// 使用的反射服务
+    pub reflector: String,
+    /// 反射服务回连的地址，一般是本机的公网 IP
+    pub public_host: String,
+    pub reachable: bool,
+    pub rtt_ms: Option<f32>,
+    pub verdict: PortForwardVerdict,
+    /// 本机监听该端口的进程
+    pub listener: Option<String>,
+    /// 本机监听的地址
+    pub listener_addresses: Vec<String>,
+    /// 反射服务给出的回连失败原因
+    pub error: Option<String>,
+}
+
+/// 从 `host` 一侧连接 `port`，供反射服务使用
+pub fn reflect(host: &str, port: u16, timeout_ms: u64) -> ReflectResult {
+    let mut result = ReflectResult {
+        host: host.to_string(),
+        port,
+        reachable: false,
+        rtt_ms: None,
+        error: None,
+    };
+    let address = match (host, port).to_socket_addrs().map(|mut addrs| addrs.next()) {
+        Ok(Some(address)) => address,
+        Ok(None) => {
+            result.error = Some("DNS 未返回任何地址".to_string());
+            return result;
+        }
+        Err(err) => {
+            result.error = Some(format!("DNS 解析失败 ({})", err));
+            return result;
+        }
+    };
+    let started = Instant::now();
+    match TcpStream::connect_timeout(&address, Duration::from_millis(timeout_ms)) {
+        Ok(_) => {
+            result.reachable = true;
+            result.rtt_ms = Some(started.elapsed().as_secs_f32() * 1000.0);
+        }
+        Err(err) => result.error = Some(err.to_string()),
+    }
+    result
+}
+
+/// 使用设置中的反射服务检测端口转发
+pub fn check_port_forward_with_settings(
+    port: u16,
+    host: Option<&str>,
+) -> AppResult<PortForwardCheck> {
+    check_port_forward(port, host, &crate::settings::current().reflector)
+}
+
+/// 请反射服务回连 `port`，并结合本机监听情况给出结论；`host` 为空时回连本机的公网地址
+#[tracing::instrument(level = "info", skip(reflector))]
+pub fn check_port_forward(
+    port: u16,
+    host: Option<&str>,
+    reflector: &ReflectorSettings,
+) -> AppResult<PortForwardCheck> {
+    if port == 0 {
+        return Err(AppError::validation("端口", "必须大于 0"));
+    }
+    let url = reflector
+        .url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .ok_or_else(|| {
+            AppError::validation(
+                "端口转发反射服务",
+                "未配置，可在 NAT 外运行 portly-cli serve 并在设置中填写其地址",
+            )
+        })?;
+    let reflected = query_reflector(url, reflector.token.as_deref(), port, host)?;
+    let listeners: Vec<PortInfo> = crate::core::try_get_listening_ports()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|p| p.port == port && p.protocol.eq_ignore_ascii_case("tcp"))
+        .collect();
+    let check = build_check(url, reflected, &listeners);
+    tracing::info!(
+        reachable = check.reachable,
+        verdict = ?check.verdict,
+        "端口转发检测完成"
+    );
+    Ok(check)
+}
+
+/// 请求反射服务；`curl -w` 在响应体之后追加一行 HTTP 状态码
+fn query_reflector(
+    url: &str,
+    token: Option<&str>,
+    port: u16,
+    host: Option<&str>,
+) -> AppResult<ReflectResult> {
+    let mut endpoint = format!(
+        "{}/api/reflect?port={}&timeout={}",
+        url.trim_end_matches('/'),
+        port,
+        DEFAULT_REFLECT_TIMEOUT_MS
+    );
+    if let Some(host) = host.map(str::trim).filter(|host| !host.is_empty()) {
+        endpoint.push_str("&host=");
+        endpoint.push_str(host);
+    }
+    let max_time = Duration::from_millis(DEFAULT_REFLECT_TIMEOUT_MS) + REQUEST_OVERHEAD;
+    let output = run_command_with_timeout(
+        "curl",
+        "端口转发检测",
+        |cmd| {
+            cmd.args(["-sS", "-w", "\n%{http_code}"]);
+            cmd.args(["-H", "User-Agent: portly"]);
+            if let Some(token) = token.filter(|token| !token.trim().is_empty()) {
+                cmd.args(["-H", &format!("Authorization: Bearer {}", token.trim())]);
+            }
+            cmd.args(["--max-time", &max_time.as_secs().to_string()]);
+            cmd.arg(&endpoint);
+        },
+        max_time + Duration::from_secs(1),
+    )?;
+    if output.status != 0 {
+        return Err(AppError::command_failed(
+            "curl",
+            "端口转发检测",
+            output.status,
+            output.stderr.trim(),
+        ));
+    }
+    parse_reflector_response(url, &output.stdout)
+}
+
+fn parse_reflector_response(url: &str, stdout: &str) -> AppResult<ReflectResult> {
+    let (body, status) = stdout.trim_end().rsplit_once('\n').unwrap_or(("", stdout));
+    match status.trim().parse::<u16>() {
+        Ok(200) => serde_json::from_str(body)
+            .map_err(|e| AppError::internal(format!("解析反射服务响应失败: {}", e))),
+        Ok(status) => {
+            let message = serde_json::from_str::<serde_json::Value>(body)
+                .ok()
+                .and_then(|value| value.get("message")?.as_str().map(str::to_string))
+                .unwrap_or_else(|| body.trim().to_string());
+            Err(AppError::host_unreachable(
+                url,
+                format!("反射服务返回 HTTP {}：{}", status, message),
+            ))
+        }
+        Err(_) => Err(AppError::internal("反射服务响应缺少状态码")),
+    }
+}
+
+fn build_check(url: &str, reflected: ReflectResult, listeners: &[PortInfo]) -> PortForwardCheck {
+    let addresses: Vec<String> = listeners.iter().map(|p| p.address.clone()).collect();
+    let verdict = if reflected.reachable {
+        PortForwardVerdict::Reachable
+    } else if listeners.is_empty() {
+        PortForwardVerdict::NoListener
+    } else if addresses.iter().all(|address| is_loopback(address)) {
+        PortForwardVerdict::LoopbackOnly
+    } else {
+        PortForwardVerdict::Blocked
+    };
+    PortForwardCheck {
+        port: reflected.port,
+        reflector: url.to_string(),
+        public_host: reflected.host,
+        reachable: reflected.reachable,
+        rtt_ms: reflected.rtt_ms,
+        verdict,
+        listener: listeners.first().map(|p| p.process.clone()),
+        listener_addresses: addresses,
+        error: reflected.error,
+    }
+}
+
+fn is_loopback(address: &str) -> bool {
+    let address = address.trim_start_matches('[').trim_end_matches(']');
+    address == "localhost"
+        || address
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn listener(address: &str) -> PortInfo {
+        PortInfo {
+            port: 25565,
+            protocol: "TCP".to_string(),
+            address: address.to_string(),
+            pid: "42".to_string(),
+            process: "java".to_string(),
+            user: "me".to_string(),
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_build_check_verdicts() {
+        let reflected = |reachable| ReflectResult {
+            host: "203.0.113.7".to_string(),
+            port: 25565,
+            reachable,
+            rtt_ms: reachable.then_some(31.5),
+            error: (!reachable).then(|| "Connection timed out".to_string()),
+        };
+        let url = "https://vps.example.com:7070";
+
+        let ok = build_check(url, reflected(true), &[listener("0.0.0.0")]);
+        assert_eq!(ok.verdict, PortForwardVerdict::Reachable);
+        assert_eq!(ok.public_host, "203.0.113.7");
+        assert_eq!(ok.listener.as_deref(), Some("java"));
+
+        let none = build_check(url, reflected(false), &[]);
+        assert_eq!(none.verdict, PortForwardVerdict::NoListener);
+
+        let loopback = build_check(
+            url,
+            reflected(false),
+            &[listener("127.0.0.1"), listener("[::1]")],
+        );
+        assert_eq!(loopback.verdict, PortForwardVerdict::LoopbackOnly);
+
+        let blocked = build_check(url, reflected(false), &[listener("*")]);
+        assert_eq!(blocked.verdict, PortForwardVerdict::Blocked);
+        assert_eq!(blocked.error.as_deref(), Some("Connection timed out"));
+    }
+
+    #[test]
+    fn test_parse_reflector_response() {
+        let body = r#"{"host":"203.0.113.7","port":8080,"reachable":true,"rtt_ms":12.0}"#;
+        let result = parse_reflector_response("http://vps:7070", &format!("{body}\n200")).unwrap();
+        assert!(result.reachable);
+        assert_eq!(result.port, 8080);
+
+        let denied = r#"{"kind":"validation","message":"缺少或错误的访问令牌"}"#;
+        let err = parse_reflector_response("http://vps:7070", &format!("{denied}\n401"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("HTTP 401"));
+        assert!(err.contains("访问令牌"));
+    }
+
+    #[test]
+    fn test_reflect_connects_back() {
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = server.local_addr().unwrap().port();
+        let result = reflect("127.0.0.1", port, 1000);
+        assert!(result.reachable);
+        assert!(result.rtt_ms.is_some());
+
+        drop(server);
+        let result = reflect("127.0.0.1", port, 1000);
+        assert!(!result.reachable);
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn test_check_port_forward_requires_reflector() {
+        let err = check_port_forward(8080, None, &ReflectorSettings::default()).unwrap_err();
+        assert!(err.to_string().contains("端口转发反射服务"));
+    }
+}
@@ -0,0 +1,209 @@
+//! 本机端口实时监听
+//!
+//! 按固定间隔重新读取监听端口，与上一轮比较后只交出变化：新开启的端口、已关闭的端口，以及
+//! 仍在监听但换了进程的端口（PID 或进程名变化，如服务重启）。GUI 转发为 `port-opened` /
+//! `port-closed` / `process-changed` 事件，前端不必轮询整张端口表。与后台监控不同，这里不做
+//! 设备发现、端点检查和提醒，间隔可以短到 1 秒
+
+use crate::app_error::{AppError, AppResult};
+use crate::core::PortInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// 默认的扫描间隔（秒）
+pub const DEFAULT_INTERVAL_SECS: u64 = 2;
+
+/// 扫描间隔的范围（秒）
+const INTERVAL_RANGE: RangeInclusive<u64> = 1..=3600;
+
+/// 等待下一轮时检查停止请求的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 同一端口换了进程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessChange {
+    pub port: u16,
+    pub before: PortInfo,
+    pub after: PortInfo,
+}
+
+/// 两轮扫描之间的变化，均按端口升序
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortDiff {
+    pub opened: Vec<PortInfo>,
+    pub closed: Vec<PortInfo>,
+    pub changed: Vec<ProcessChange>,
+}
+
+impl PortDiff {
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// 按端口归并，同一端口有多条记录（IPv4 和 IPv6）时取第一条
+fn by_port(ports: &[PortInfo]) -> BTreeMap<u16, &PortInfo> {
+    let mut map = BTreeMap::new();
+    for port in ports {
+        map.entry(port.port).or_insert(port);
+    }
+    map
+}
+
+/// 比较两轮扫描的监听端口
+pub fn diff_ports(previous: &[PortInfo], current: &[PortInfo]) -> PortDiff {
+    let previous = by_port(previous);
+    let current = by_port(current);
+    let mut diff = PortDiff::default();
+    for (port, after) in &current {
+        match previous.get(port) {
+            None => diff.opened.push((*after).clone()),
+            Some(before) if before.pid != after.pid || before.process != after.process => {
+                diff.changed.push(ProcessChange {
+                    port: *port,
+                    before: (*before).clone(),
+                    after: (*after).clone(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    diff.closed = previous
+        .iter()
+        .filter(|(port, _)| !current.contains_key(port))
+        .map(|(_, info)| (*info).clone())
+        .collect();
+    diff
+}
+
+/// 跨轮次保存上一轮的端口
+#[derive(Debug, Default)]
+pub struct PortWatcher {
+    baseline: Option<Vec<PortInfo>>,
+}
+
+impl PortWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录本轮端口并返回与上一轮的差异；第一轮只建立基线，返回空
+    pub fn observe(&mut self, ports: Vec<PortInfo>) -> Option<PortDiff> {
+        let diff = self
+            .baseline
+            .as_deref()
+            .map(|previous| diff_ports(previous, &ports));
+        self.baseline = Some(ports);
+        diff
+    }
+}
+
+/// 校验扫描间隔（秒）
+pub fn interval(secs: u64) -> AppResult<Duration> {
+    if !INTERVAL_RANGE.contains(&secs) {
+        return Err(AppError::validation(
+            "端口监听间隔",
+            format!(
+                "应在 {}-{} 秒之间",
+                INTERVAL_RANGE.start(),
+                INTERVAL_RANGE.end()
+            ),
+        ));
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// 按间隔重新读取监听端口，有变化时调用 `on_diff`，阻塞直到 `stop` 被置位
+///
+/// 读取失败的一轮跳过，不更新基线
+pub fn watch_ports<F>(stop: &AtomicBool, interval: Duration, mut on_diff: F)
+where
+    F: FnMut(&PortDiff),
+{
+    tracing::info!(interval_secs = interval.as_secs(), "端口实时监听已启动");
+    let mut watcher = PortWatcher::new();
+    while !stop.load(Ordering::Relaxed) {
+        match crate::core::try_get_listening_ports() {
+            Ok(ports) => {
+                if let Some(diff) = watcher.observe(ports).filter(|diff| !diff.is_empty()) {
+                    tracing::info!(
+                        opened = diff.opened.len(),
+                        closed = diff.closed.len(),
+                        changed = diff.changed.len(),
+                        "监听端口发生变化"
+                    );
+                    on_diff(&diff);
+                }
+            }
+            Err(err) => tracing::warn!(error = %err, "端口实时监听读取监听端口失败"),
+        }
+
+        let mut waited = Duration::ZERO;
+        while waited < interval && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(STOP_POLL_INTERVAL);
+            waited += STOP_POLL_INTERVAL;
+        }
+    }
+    tracing::info!("端口实时监听已停止");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, protocol: &str, pid: &str, process: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: protocol.to_string(),
+            address: "*".to_string(),
+            pid: pid.to_string(),
+            process: process.to_string(),
+            user: "alice".to_string(),
+            command: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_ports_opened_closed_and_changed() {
+        let previous = vec![
+            port(3000, "IPv4", "48213", "node"),
+            port(5432, "IPv4", "733", "postgres"),
+            port(5432, "IPv6", "733", "postgres"),
+            port(8080, "IPv4", "902", "java"),
+        ];
+        let current = vec![
+            port(3000, "IPv4", "48990", "node"),
+            port(5432, "IPv6", "733", "postgres"),
+            port(6379, "IPv4", "801", "redis-server"),
+        ];
+        let diff = diff_ports(&previous, &current);
+
+        let opened: Vec<u16> = diff.opened.iter().map(|p| p.port).collect();
+        let closed: Vec<u16> = diff.closed.iter().map(|p| p.port).collect();
+        assert_eq!(opened, vec![6379]);
+        assert_eq!(closed, vec![8080]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].port, 3000);
+        assert_eq!(diff.changed[0].before.pid, "48213");
+        assert_eq!(diff.changed[0].after.pid, "48990");
+        assert!(diff_ports(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn test_watcher_baseline_and_interval() {
+        let mut watcher = PortWatcher::new();
+        assert!(watcher
+            .observe(vec![port(3000, "IPv4", "1", "node")])
+            .is_none());
+        let diff = watcher.observe(Vec::new()).unwrap();
+        assert_eq!(diff.closed.len(), 1);
+        assert!(watcher.observe(Vec::new()).unwrap().is_empty());
+
+        assert_eq!(interval(2).unwrap(), Duration::from_secs(2));
+        assert!(interval(0).is_err());
+        assert!(interval(3601).is_err());
+    }
+}
@@ -0,0 +1,604 @@
+//! 打印机信息查询
+//!
+//! 对开放了 631（IPP）的设备发送 IPP Get-Printer-Attributes 请求（RFC 8011），读取型号、
+//! 名称、位置、状态和耗材余量；对 9100（JetDirect / 原始打印）发送 PJL `INFO ID` 和
+//! `INFO STATUS` 查询。9100 上收到的数据会被当作打印任务，不支持 PJL 的打印机可能把查询
+//! 打印出来，因此只在用户主动查询时使用，服务探测只查询 IPP
+
+use crate::app_error::{AppError, AppResult};
+use crate::network::ServiceInfo;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// IPP 端口
+pub const IPP_PORT: u16 = 631;
+/// JetDirect 原始打印端口
+pub const JETDIRECT_PORT: u16 = 9100;
+
+const TIMEOUT: Duration = Duration::from_secs(3);
+/// 应答长度上限，打印机忽略 requested-attributes 返回全部属性时也足够
+const MAX_RESPONSE_LEN: usize = 1 << 20;
+/// 依次尝试的打印机路径：IPP Everywhere、常见的 `/ipp`、CUPS 的默认打印机
+const IPP_PATHS: &[&str] = &["/ipp/print", "/ipp", "/"];
+const REQUESTED_ATTRIBUTES: &[&str] = &[
+    "printer-make-and-model",
+    "printer-name",
+    "printer-info",
+    "printer-location",
+    "printer-state",
+    "printer-state-reasons",
+    "printer-state-message",
+    "marker-names",
+    "marker-levels",
+];
+
+/// PJL 通用退出语言（UEL）
+const PJL_UEL: &str = "\x1b%-12345X";
+
+/// 查询所用的协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterProtocol {
+    Ipp,
+    Pjl,
+}
+
+/// 打印机状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrinterState {
+    Idle,
+    Processing,
+    Stopped,
+    Unknown,
+}
+
+/// 一种耗材（墨盒、硒鼓等）的余量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkerLevel {
+    pub name: String,
+    /// 百分比；打印机报告未知时为 None
+    pub level: Option<u8>,
+}
+
+/// 打印机信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrinterInfo {
+    pub host: String,
+    pub port: u16,
+    pub protocol: PrinterProtocol,
+    pub make_and_model: Option<String>,
+    pub name: Option<String>,
+    pub location: Option<String>,
+    /// 管理员填写的描述
+    pub info: Option<String>,
+    pub state: PrinterState,
+    /// 如 `media-empty-warning`、`toner-low-report`；`none` 不列出
+    pub state_reasons: Vec<String>,
+    /// 面板上显示的状态文字
+    pub status_message: Option<String>,
+    pub markers: Vec<MarkerLevel>,
+}
+
+impl PrinterInfo {
+    /// 供服务探测使用：型号写入 `server`，服务类型标为 printer
+    pub fn service_info(&self) -> ServiceInfo {
+        ServiceInfo {
+            port: self.port,
+            service: match self.protocol {
+                PrinterProtocol::Ipp => "IPP".to_string(),
+                PrinterProtocol::Pjl => "JetDirect".to_string(),
+            },
+            service_type: "printer".to_string(),
+            server: self.make_and_model.clone().or_else(|| self.name.clone()),
+            content_type: None,
+            version_hints: Vec::new(),
+            security_headers: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum IppValue {
+    Integer(i32),
+    Text(String),
+}
+
+/// 按出现顺序排列的属性及其取值
+type IppAttributes = Vec<(String, Vec<IppValue>)>;
+
+/// 按 RFC 8010 编码 Get-Printer-Attributes 请求
+fn build_ipp_request(printer_uri: &str) -> Vec<u8> {
+    fn attribute(out: &mut Vec<u8>, tag: u8, name: &str, value: &str) {
+        out.push(tag);
+        out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        out.extend_from_slice(value.as_bytes());
+    }
+
+    // 版本 1.1、操作 Get-Printer-Attributes (0x000B)、请求 ID 1
+    let mut out = vec![0x01, 0x01, 0x00, 0x0B, 0x00, 0x00, 0x00, 0x01];
+    out.push(0x01); // operation-attributes-tag
+    attribute(&mut out, 0x47, "attributes-charset", "utf-8");
+    attribute(&mut out, 0x48, "attributes-natural-language", "en");
+    attribute(&mut out, 0x45, "printer-uri", printer_uri);
+    for (index, name) in REQUESTED_ATTRIBUTES.iter().enumerate() {
+        // 同一属性的后续值名称为空
+        let attr_name = if index == 0 {
+            "requested-attributes"
+        } else {
+            ""
+        };
+        attribute(&mut out, 0x44, attr_name, name);
+    }
+    out.push(0x03); // end-of-attributes-tag
+    out
+}
+
+/// 解析 IPP 应答，返回状态码和属性；集合类型的属性被跳过
+fn parse_ipp_response(data: &[u8]) -> Result<(u16, IppAttributes), String> {
+    fn read_u16(data: &[u8], pos: usize) -> Option<usize> {
+        Some(u16::from_be_bytes([*data.get(pos)?, *data.get(pos + 1)?]) as usize)
+    }
+
+    if data.len() < 8 {
+        return Err("应答长度不足".to_string());
+    }
+    if data[0] == 0 || data[0] > 2 {
+        return Err("不是 IPP 应答".to_string());
+    }
+    let status = u16::from_be_bytes([data[2], data[3]]);
+    let mut attributes = IppAttributes::new();
+    let mut depth = 0usize;
+    let mut pos = 8;
+    while let Some(&tag) = data.get(pos) {
+        pos += 1;
+        if tag == 0x03 {
+            break;
+        }
+        // 分组标签
+        if tag < 0x10 {
+            continue;
+        }
+        let truncated = || "应答被截断".to_string();
+        let name_len = read_u16(data, pos).ok_or_else(truncated)?;
+        let name = data
+            .get(pos + 2..pos + 2 + name_len)
+            .ok_or_else(truncated)?;
+        pos += 2 + name_len;
+        let value_len = read_u16(data, pos).ok_or_else(truncated)?;
+        let value = data
+            .get(pos + 2..pos + 2 + value_len)
+            .ok_or_else(truncated)?;
+        pos += 2 + value_len;
+
+        match tag {
+            0x34 => {
+                depth += 1;
+                continue;
+            }
+            0x37 => {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            _ if depth > 0 => continue,
+            _ => {}
+        }
+        let value = match tag {
+            0x21 | 0x23 => <[u8; 4]>::try_from(value)
+                .ok()
+                .map(|bytes| IppValue::Integer(i32::from_be_bytes(bytes))),
+            // textWithLanguage / nameWithLanguage：语言和文本各带长度前缀
+            0x35 | 0x36 => read_u16(value, 0).and_then(|lang_len| {
+                let text = value.get(lang_len + 4..)?;
+                Some(IppValue::Text(String::from_utf8_lossy(text).into_owned()))
+            }),
+            0x41..=0x49 => Some(IppValue::Text(String::from_utf8_lossy(value).into_owned())),
+            _ => None,
+        };
+        if name.is_empty() {
+            if let (Some(value), Some((_, values))) = (value, attributes.last_mut()) {
+                values.push(value);
+            }
+        } else {
+            let name = String::from_utf8_lossy(name).into_owned();
+            attributes.push((name, value.into_iter().collect()));
+        }
+    }
+    Ok((status, attributes))
+}
+
+fn printer_from_ipp(host: &str, port: u16, attributes: &[(String, Vec<IppValue>)]) -> PrinterInfo {
+    let values = |name: &str| {
+        attributes
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or_default()
+    };
+    let texts = |name: &str| -> Vec<String> {
+        values(name)
+            .iter()
+            .filter_map(|value| match value {
+                IppValue::Text(text) if !text.trim().is_empty() => Some(text.trim().to_string()),
+                _ => None,
+            })
+            .collect()
+    };
+    let text = |name: &str| texts(name).into_iter().next();
+
+    let state = match values("printer-state").first() {
+        Some(IppValue::Integer(3)) => PrinterState::Idle,
+        Some(IppValue::Integer(4)) => PrinterState::Processing,
+        Some(IppValue::Integer(5)) => PrinterState::Stopped,
+        _ => PrinterState::Unknown,
+    };
+    // 负数表示未知（-1）、不可用（-2）或有余量但无法计量（-3）
+    let levels: Vec<Option<u8>> = values("marker-levels")
+        .iter()
+        .map(|value| match value {
+            IppValue::Integer(level) => u8::try_from(*level).ok().map(|level| level.min(100)),
+            _ => None,
+        })
+        .collect();
+    let markers = texts("marker-names")
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| MarkerLevel {
+            name,
+            level: levels.get(index).copied().flatten(),
+        })
+        .collect();
+
+    PrinterInfo {
+        host: host.to_string(),
+        port,
+        protocol: PrinterProtocol::Ipp,
+        make_and_model: text("printer-make-and-model"),
+        name: text("printer-name"),
+        location: text("printer-location"),
+        info: text("printer-info"),
+        state,
+        state_reasons: texts("printer-state-reasons")
+            .into_iter()
+            .filter(|reason| reason != "none")
+            .collect(),
+        status_message: text("printer-state-message"),
+        markers,
+    }
+}
+
+/// 取出 HTTP 应答的主体，处理 chunked 编码
+fn http_body(response: &[u8]) -> Result<Vec<u8>, String> {
+    let header_end = response
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or("HTTP 应答不完整")?;
+    let head = String::from_utf8_lossy(&response[..header_end]);
+    let status_line = head.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "200" {
+        return Err(format!("HTTP {}", status_line.trim()));
+    }
+    let body = &response[header_end + 4..];
+    let chunked = head.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.to_ascii_lowercase().contains("chunked")
+        })
+    });
+    if !chunked {
+        return Ok(body.to_vec());
+    }
+
+    let mut out = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or("chunked 编码不完整")?;
+        let size_line = String::from_utf8_lossy(&rest[..line_end]);
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size_hex, 16).map_err(|_| "chunked 编码不正确")?;
+        if size == 0 {
+            return Ok(out);
+        }
+        let chunk = rest
+            .get(line_end + 2..line_end + 2 + size)
+            .ok_or("chunked 编码不完整")?;
+        out.extend_from_slice(chunk);
+        rest = rest.get(line_end + 4 + size..).unwrap_or_default();
+    }
+}
+
+fn connect(host: &str, port: u16) -> AppResult<TcpStream> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|err| AppError::host_unreachable(host, err.to_string()))?
+        .next()
+        .ok_or_else(|| AppError::host_unreachable(host, "无法解析地址"))?;
+    let stream = TcpStream::connect_timeout(&addr, TIMEOUT)
+        .map_err(|err| AppError::host_unreachable(host, err.to_string()))?;
+    stream
+        .set_read_timeout(Some(TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+        .map_err(|err| AppError::host_unreachable(host, err.to_string()))?;
+    Ok(stream)
+}
+
+/// 发送请求并读到 `complete` 成立、对端关闭连接、超时或达到长度上限
+fn exchange(
+    host: &str,
+    port: u16,
+    request: &[u8],
+    complete: fn(&[u8]) -> bool,
+) -> AppResult<Vec<u8>> {
+    let mut stream = connect(host, port)?;
+    stream
+        .write_all(request)
+        .map_err(|err| AppError::host_unreachable(host, err.to_string()))?;
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 8192];
+    while response.len() < MAX_RESPONSE_LEN {
+        match stream.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => {
+                response.extend_from_slice(&buffer[..n]);
+                if complete(&response) {
+                    break;
+                }
+            }
+            Err(_) if !response.is_empty() => break,
+            Err(err) => return Err(AppError::host_unreachable(host, err.to_string())),
+        }
+    }
+    Ok(response)
+}
+
+fn query_ipp(host: &str, port: u16) -> AppResult<PrinterInfo> {
+    let authority = if host.contains(':') {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    };
+    let mut last_error = String::new();
+    for path in IPP_PATHS {
+        let body = build_ipp_request(&format!("ipp://{}{}", authority, path));
+        let mut request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: Portly/1.0\r\nContent-Type: application/ipp\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            path,
+            authority,
+            body.len()
+        )
+        .into_bytes();
+        request.extend_from_slice(&body);
+
+        let response = exchange(host, port, &request, |_| false)?;
+        let parsed = http_body(&response).and_then(|body| parse_ipp_response(&body));
+        match parsed {
+            // 0x0000-0x00FF 为成功状态
+            Ok((status, attributes)) if status <= 0x00FF => {
+                return Ok(printer_from_ipp(host, port, &attributes));
+            }
+            Ok((status, _)) => last_error = format!("{}: IPP 状态 0x{:04x}", path, status),
+            Err(err) => last_error = format!("{}: {}", path, err),
+        }
+    }
+    Err(AppError::host_unreachable(
+        host,
+        format!("没有可用的 IPP 应答（{}）", last_error),
+    ))
+}
+
+/// 解析 PJL `INFO ID` / `INFO STATUS` 的应答，各段以换页符结束，例如
+/// `@PJL INFO STATUS\r\nCODE=10001\r\nDISPLAY="Ready"\r\nONLINE=TRUE\r\n\x0c`
+fn parse_pjl(host: &str, port: u16, response: &str) -> Option<PrinterInfo> {
+    let unquote = |value: &str| value.trim().trim_matches('"').trim().to_string();
+    let mut make_and_model = None;
+    let mut status_message = None;
+    let mut code = None;
+    let mut online = None;
+    for section in response.split('\x0c') {
+        let mut lines = section
+            .lines()
+            .map(str::trim)
+            .skip_while(|line| line.is_empty());
+        match lines.next().map(str::to_ascii_uppercase).as_deref() {
+            Some("@PJL INFO ID") => {
+                make_and_model = lines.next().map(unquote).filter(|id| !id.is_empty());
+            }
+            Some("@PJL INFO STATUS") => {
+                for line in lines {
+                    match line.split_once('=') {
+                        Some(("CODE", value)) => code = value.trim().parse::<u32>().ok(),
+                        Some(("DISPLAY", value)) => status_message = Some(unquote(value)),
+                        Some(("ONLINE", value)) => {
+                            online = Some(value.trim().eq_ignore_ascii_case("TRUE"))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if make_and_model.is_none() && code.is_none() {
+        return None;
+    }
+    // 10023 / 10024 为正在处理和打印任务
+    let state = match (online, code) {
+        (Some(false), _) => PrinterState::Stopped,
+        (_, Some(10023 | 10024)) => PrinterState::Processing,
+        (Some(true), _) => PrinterState::Idle,
+        _ => PrinterState::Unknown,
+    };
+    Some(PrinterInfo {
+        host: host.to_string(),
+        port,
+        protocol: PrinterProtocol::Pjl,
+        make_and_model,
+        name: None,
+        location: None,
+        info: None,
+        state,
+        state_reasons: Vec::new(),
+        status_message: status_message.filter(|message| !message.is_empty()),
+        markers: Vec::new(),
+    })
+}
+
+fn query_pjl(host: &str, port: u16) -> AppResult<PrinterInfo> {
+    let request = format!(
+        "{uel}@PJL\r\n@PJL INFO ID\r\n@PJL INFO STATUS\r\n{uel}",
+        uel = PJL_UEL
+    );
+    // 打印机通常不主动断开 9100 上的连接，两段应答都以换页符结束后即停止读取
+    let response = exchange(host, port, request.as_bytes(), |response| {
+        response.iter().filter(|&&byte| byte == 0x0c).count() >= 2
+    })?;
+    parse_pjl(host, port, &String::from_utf8_lossy(&response))
+        .ok_or_else(|| AppError::host_unreachable(host, "没有 PJL 应答"))
+}
+
+/// 查询打印机信息：631 使用 IPP，9100 使用 PJL
+#[tracing::instrument(level = "info")]
+pub fn query_printer(host: &str, port: u16) -> AppResult<PrinterInfo> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(AppError::validation("目标地址", "不能为空"));
+    }
+    let info = match port {
+        IPP_PORT => query_ipp(host, port)?,
+        JETDIRECT_PORT => query_pjl(host, port)?,
+        _ => {
+            return Err(AppError::validation(
+                "打印机端口",
+                "仅支持 631（IPP）和 9100（JetDirect）",
+            ))
+        }
+    };
+    tracing::info!(
+        model = info.make_and_model.as_deref().unwrap_or_default(),
+        state = ?info.state,
+        "打印机查询完成"
+    );
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(out: &mut Vec<u8>, tag: u8, name: &str, value: &[u8]) {
+        out.push(tag);
+        out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        out.extend_from_slice(value);
+    }
+
+    #[test]
+    fn test_ipp_request_and_response() {
+        let request = build_ipp_request("ipp://10.0.0.9:631/ipp/print");
+        assert_eq!(&request[..4], &[0x01, 0x01, 0x00, 0x0B]);
+        assert_eq!(request.last(), Some(&0x03));
+        let (_, attributes) = parse_ipp_response(&request).unwrap();
+        assert_eq!(attributes[2].0, "printer-uri");
+        assert_eq!(attributes[3].1.len(), REQUESTED_ATTRIBUTES.len());
+
+        let mut body = vec![0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01];
+        attribute(&mut body, 0x47, "attributes-charset", b"utf-8");
+        body.push(0x04); // printer-attributes-tag
+        attribute(
+            &mut body,
+            0x41,
+            "printer-make-and-model",
+            b"HP LaserJet M404dn",
+        );
+        attribute(&mut body, 0x36, "printer-name", b"\x00\x02en\x00\x06office");
+        attribute(&mut body, 0x23, "printer-state", &5i32.to_be_bytes());
+        attribute(
+            &mut body,
+            0x44,
+            "printer-state-reasons",
+            b"media-empty-error",
+        );
+        attribute(&mut body, 0x44, "", b"toner-low-report");
+        attribute(&mut body, 0x34, "media-col-default", b"");
+        attribute(&mut body, 0x4A, "", b"media-size");
+        attribute(&mut body, 0x21, "", &21000i32.to_be_bytes());
+        attribute(&mut body, 0x37, "", b"");
+        attribute(&mut body, 0x42, "marker-names", b"Black Cartridge");
+        attribute(&mut body, 0x42, "", b"Imaging Drum");
+        attribute(&mut body, 0x21, "marker-levels", &12i32.to_be_bytes());
+        attribute(&mut body, 0x21, "", &(-3i32).to_be_bytes());
+        body.push(0x03);
+
+        let response = [
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/ipp\r\nTransfer-Encoding: chunked\r\n\r\n"
+                .as_slice(),
+            format!("{:x}\r\n", 20).as_bytes(),
+            &body[..20],
+            format!("\r\n{:x}\r\n", body.len() - 20).as_bytes(),
+            &body[20..],
+            b"\r\n0\r\n\r\n",
+        ]
+        .concat();
+        let (status, attributes) = parse_ipp_response(&http_body(&response).unwrap()).unwrap();
+        assert_eq!(status, 0);
+        let info = printer_from_ipp("10.0.0.9", IPP_PORT, &attributes);
+        assert_eq!(info.make_and_model.as_deref(), Some("HP LaserJet M404dn"));
+        assert_eq!(info.name.as_deref(), Some("office"));
+        assert_eq!(info.state, PrinterState::Stopped);
+        assert_eq!(
+            info.state_reasons,
+            vec!["media-empty-error", "toner-low-report"]
+        );
+        assert_eq!(
+            info.markers,
+            vec![
+                MarkerLevel {
+                    name: "Black Cartridge".to_string(),
+                    level: Some(12)
+                },
+                MarkerLevel {
+                    name: "Imaging Drum".to_string(),
+                    level: None
+                },
+            ]
+        );
+        let service = info.service_info();
+        assert_eq!(service.service_type, "printer");
+        assert_eq!(service.server.as_deref(), Some("HP LaserJet M404dn"));
+
+        assert!(http_body(b"HTTP/1.1 404 Not Found\r\n\r\n").is_err());
+        assert!(parse_ipp_response(b"<html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_pjl_info() {
+        let response = "@PJL INFO ID\r\n\"HP LaserJet Pro M402dn\"\r\n\x0c@PJL INFO STATUS\r\nCODE=10023\r\nDISPLAY=\"Processing Job\"\r\nONLINE=TRUE\r\n\x0c";
+        let info = parse_pjl("10.0.0.8", JETDIRECT_PORT, response).unwrap();
+        assert_eq!(info.protocol, PrinterProtocol::Pjl);
+        assert_eq!(
+            info.make_and_model.as_deref(),
+            Some("HP LaserJet Pro M402dn")
+        );
+        assert_eq!(info.status_message.as_deref(), Some("Processing Job"));
+        assert_eq!(info.state, PrinterState::Processing);
+        assert_eq!(info.service_info().service, "JetDirect");
+
+        let offline =
+            "@PJL INFO STATUS\r\nCODE=40021\r\nDISPLAY=\"Close Door\"\r\nONLINE=FALSE\r\n\x0c";
+        assert_eq!(
+            parse_pjl("h", 9100, offline).unwrap().state,
+            PrinterState::Stopped
+        );
+        assert!(parse_pjl("h", 9100, "").is_none());
+        assert!(query_printer("10.0.0.8", 80).is_err());
+    }
+}
@@ -1,8 +1,10 @@
 //! Process management module for Portly
 //! Provides cross-platform process termination capabilities
+//! Result messages follow the backend message language (see `i18n::set_lang`)
 
 use serde::{Deserialize, Serialize};
 use crate::command_exec::run_command;
+use crate::i18n::{current_lang, tr_args, Msg};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -119,19 +121,55 @@ pub fn get_process_info(pid: u32) -> Option<ProcessInfo> {
     })
 }
 
+fn killed_message(pid: u32, force: bool) -> String {
+    let msg = if force {
+        Msg::KillForced
+    } else {
+        Msg::KillDone
+    };
+    tr_args(current_lang(), msg, &[&pid])
+}
+
+/// Whether kill / taskkill failed because the process belongs to another user
+fn is_permission_denied(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    ["not permitted", "access is denied", "拒绝访问"]
+        .iter()
+        .any(|pattern| stderr.contains(pattern))
+}
+
+/// Failure message; permission errors explain how to get elevated privileges
+fn kill_failed_message(pid: u32, stderr: &str) -> String {
+    let hint = if is_permission_denied(stderr) {
+        crate::capabilities::elevation_hint()
+    } else {
+        None
+    };
+    failure_message(pid, stderr, hint)
+}
+
+fn failure_message(pid: u32, stderr: &str, elevation_hint: Option<String>) -> String {
+    match elevation_hint {
+        Some(hint) => tr_args(current_lang(), Msg::KillNeedsPrivilege, &[&pid, &hint]),
+        None => tr_args(current_lang(), Msg::KillFailed, &[&stderr.trim()]),
+    }
+}
+
 /// Kill a process by PID (Unix: macOS/Linux)
 #[cfg(any(target_os = "macos", target_os = "linux"))]
+#[tracing::instrument(level = "info", ret)]
 pub fn kill_process(pid: u32, force: bool) -> KillResult {
+    if crate::demo::is_enabled() {
+        return crate::demo::kill_process(pid);
+    }
+
     // First, check if process exists and is safe to kill
     if let Some(info) = get_process_info(pid) {
         if info.is_system && !force {
             return KillResult {
                 success: false,
                 pid,
-                message: format!(
-                    "进程 '{}' (PID: {}) 是受保护的系统进程。如需强制终止，请使用强制模式。",
-                    info.name, pid
-                ),
+                message: tr_args(current_lang(), Msg::KillProtected, &[&info.name, &pid]),
             };
         }
     }
@@ -150,40 +188,39 @@ pub fn kill_process(pid: u32, force: bool) -> KillResult {
                 KillResult {
                     success: true,
                     pid: pid_number,
-                    message: format!("进程 {} 已{}终止", pid, if force { "强制" } else { "" }),
+                    message: killed_message(pid_number, force),
                 }
             } else {
                 KillResult {
                     success: false,
                     pid: pid_number,
-                    message: format!(
-                        "终止进程失败: {}",
-                        result.stderr.trim()
-                    ),
+                    message: kill_failed_message(pid_number, &result.stderr),
                 }
             }
         }
         Err(e) => KillResult {
             success: false,
             pid: pid_number,
-            message: format!("执行 kill 命令失败: {}", e),
+            message: tr_args(current_lang(), Msg::KillCommandFailed, &[&"kill", &e]),
         },
     }
 }
 
 /// Kill a process by PID (Windows)
 #[cfg(target_os = "windows")]
+#[tracing::instrument(level = "info", ret)]
 pub fn kill_process(pid: u32, force: bool) -> KillResult {
+    if crate::demo::is_enabled() {
+        return crate::demo::kill_process(pid);
+    }
+
     // Check if process exists and is safe to kill
     if let Some(info) = get_process_info(pid) {
         if info.is_system && !force {
             return KillResult {
                 success: false,
                 pid,
-                message: format!(
-                    "Process '{}' (PID: {}) is a protected system process. Use force mode to override.",
-                    info.name, pid
-                ),
+                message: tr_args(current_lang(), Msg::KillProtected, &[&info.name, &pid]),
             };
         }
     }
@@ -205,30 +242,31 @@ pub fn kill_process(pid: u32, force: bool) -> KillResult {
                 KillResult {
                     success: true,
                     pid: pid_num,
-                    message: format!(
-                        "Process {} terminated{}",
-                        pid_num,
-                        if force { " (forced)" } else { "" }
-                    ),
+                    message: killed_message(pid_num, force),
                 }
             } else {
                 KillResult {
                     success: false,
                     pid: pid_num,
-                    message: format!("Failed to terminate process: {}", result.stderr.trim()),
+                    message: kill_failed_message(pid_num, &result.stderr),
                 }
             }
         }
         Err(e) => KillResult {
             success: false,
             pid: pid_num,
-            message: format!("Failed to execute taskkill: {}", e),
+            message: tr_args(current_lang(), Msg::KillCommandFailed, &[&"taskkill", &e]),
         },
     }
 }
 
 /// Try to kill a process blocking a specific port
+#[tracing::instrument(level = "info", ret)]
 pub fn kill_port_process(port: u16) -> KillResult {
+    if crate::demo::is_enabled() {
+        return crate::demo::kill_port_process(port);
+    }
+
     // Find the process using this port
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     {
@@ -249,7 +287,7 @@ pub fn kill_port_process(port: u16) -> KillResult {
                     return KillResult {
                         success: false,
                         pid: 0,
-                        message: format!("端口 {} 上未找到占用进程", port),
+                        message: tr_args(current_lang(), Msg::KillNoProcessOnPort, &[&port]),
                     };
                 }
 
@@ -274,7 +312,7 @@ pub fn kill_port_process(port: u16) -> KillResult {
             Err(e) => KillResult {
                 success: false,
                 pid: 0,
-                message: format!("查找端口进程失败: {}", e),
+                message: tr_args(current_lang(), Msg::KillLookupFailed, &[&e]),
             },
         }
     }
@@ -301,7 +339,7 @@ pub fn kill_port_process(port: u16) -> KillResult {
                     return KillResult {
                         success: false,
                         pid: 0,
-                        message: format!("No process found on port {}", port),
+                        message: tr_args(current_lang(), Msg::KillNoProcessOnPort, &[&port]),
                     };
                 }
 
@@ -325,7 +363,7 @@ pub fn kill_port_process(port: u16) -> KillResult {
             Err(e) => KillResult {
                 success: false,
                 pid: 0,
-                message: format!("Failed to find port process: {}", e),
+                message: tr_args(current_lang(), Msg::KillLookupFailed, &[&e]),
             },
         }
     }
@@ -522,4 +560,18 @@ mod tests {
             assert!(is_protected_process("svchost.exe"));
         }
     }
+
+    #[test]
+    fn test_permission_denied_suggests_elevation() {
+        assert!(is_permission_denied(
+            "kill: (733) - Operation not permitted"
+        ));
+        assert!(is_permission_denied("ERROR: Access is denied."));
+        assert!(!is_permission_denied("kill: (733) - No such process"));
+
+        let message = failure_message(733, "Operation not permitted", Some("sudo".to_string()));
+        assert!(message.contains("733") && message.contains("sudo"));
+        let message = failure_message(733, " No such process\n", None);
+        assert!(message.contains("No such process"));
+    }
 }
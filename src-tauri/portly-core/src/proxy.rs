@@ -0,0 +1,1154 @@
+//! 反向代理后端映射
+//!
+//! nginx / Caddy / Traefik 占着 80、443 这类公共端口时，端口列表里只看得到代理进程本身。
+//! 这里按设置中登记的来源（配置文件、Caddy 管理接口、Traefik API）读出每个公共端口的
+//! 主机名 / 路径路由和上游后端，再把指向本机的后端对回监听该端口的进程，让 443 展开成
+//! 代理背后真正的应用。来源需要手动登记，未登记时不读取任何文件或接口
+
+use crate::app_error::AppResult;
+use crate::core::PortInfo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 管理接口的连接和读取超时
+const ADMIN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// nginx `include` 的最大嵌套层数，防止配置互相包含
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// nginx 中把请求转给上游的指令
+const NGINX_PASS_DIRECTIVES: &[&str] = &[
+    "proxy_pass",
+    "grpc_pass",
+    "fastcgi_pass",
+    "uwsgi_pass",
+    "scgi_pass",
+];
+
+/// 代理软件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyKind {
+    Nginx,
+    Caddy,
+    Traefik,
+}
+
+/// 一个上游后端
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyBackend {
+    /// 配置中的地址，如 `127.0.0.1:3000`、`unix:/run/app.sock`
+    pub address: String,
+    /// 后端在本机监听时对应的进程
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<String>,
+}
+
+/// 一条代理路由：公共端口上某个主机名 / 路径的请求转发到的后端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyRoute {
+    pub proxy: ProxyKind,
+    pub listen_port: u16,
+    /// 匹配的主机名，为空表示该端口上的全部请求
+    pub hosts: Vec<String>,
+    /// 匹配的路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub backends: Vec<ProxyBackend>,
+    /// 配置文件路径或管理接口地址
+    pub source: String,
+}
+
+/// 读取失败的来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxySourceError {
+    pub source: String,
+    pub message: String,
+}
+
+/// 反向代理映射结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyMapping {
+    pub generated_at: String,
+    /// 按公共端口、主机名排列
+    pub routes: Vec<ProxyRoute>,
+    /// 单个来源读取失败不影响其他来源
+    pub errors: Vec<ProxySourceError>,
+}
+
+/// 读取设置中登记的全部来源，生成公共端口到后端的映射
+#[tracing::instrument(level = "info")]
+pub fn proxy_backends() -> AppResult<ProxyMapping> {
+    let settings = crate::settings::current().proxy;
+    let mut routes = Vec::new();
+    let mut errors = Vec::new();
+    let mut collect = |source: &str, result: Result<Vec<ProxyRoute>, String>| match result {
+        Ok(found) => routes.extend(found),
+        Err(message) => errors.push(ProxySourceError {
+            source: source.to_string(),
+            message,
+        }),
+    };
+
+    for pattern in &settings.config_paths {
+        let paths = glob_paths(Path::new(pattern.trim()));
+        if paths.is_empty() {
+            collect(pattern, Err("未找到匹配的文件".to_string()));
+        }
+        for path in paths {
+            let source = path.display().to_string();
+            collect(&source, read_config_file(&path));
+        }
+    }
+    if let Some(admin) = &settings.caddy_admin {
+        let source = format!("{}/config/", admin_base(admin));
+        let result = http_get(admin, "/config/").and_then(|body| parse_caddy_json(&body, &source));
+        collect(&source, result);
+    }
+    if let Some(api) = &settings.traefik_api {
+        let source = format!("{}/api", admin_base(api));
+        collect(&source, traefik_routes(api, &source));
+    }
+
+    if !routes.is_empty() {
+        let ports = crate::core::try_get_listening_ports()?;
+        resolve_local_backends(&mut routes, &ports);
+    }
+    routes.sort_by(|a, b| {
+        (a.listen_port, &a.hosts, &a.path).cmp(&(b.listen_port, &b.hosts, &b.path))
+    });
+    Ok(ProxyMapping {
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        routes,
+        errors,
+    })
+}
+
+/// 按文件名区分 Caddyfile 和 nginx 配置
+fn read_config_file(path: &Path) -> Result<Vec<ProxyRoute>, String> {
+    let source = path.display().to_string();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    if name.contains("caddyfile") || name.ends_with(".caddy") {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Ok(parse_caddyfile(&text, &source))
+    } else {
+        let base = path.parent().unwrap_or(Path::new("."));
+        let directives = load_nginx(path, base, 0)?;
+        Ok(nginx_routes(&directives, &source))
+    }
+}
+
+/// 文件名中的 `*` 按通配符展开，结果按名称排列；不含通配符时原样返回存在的文件
+fn glob_paths(pattern: &Path) -> Vec<PathBuf> {
+    let name = pattern
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Some((prefix, suffix)) = name.split_once('*') else {
+        return if pattern.is_file() {
+            vec![pattern.to_path_buf()]
+        } else {
+            Vec::new()
+        };
+    };
+    let dir = pattern.parent().unwrap_or(Path::new("."));
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            path.is_file()
+                && file.len() >= prefix.len() + suffix.len()
+                && file.starts_with(prefix)
+                && file.ends_with(suffix)
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// nginx 配置中的一条指令，带块的指令（server、location 等）包含子指令
+#[derive(Debug, Clone, PartialEq)]
+struct Directive {
+    name: String,
+    args: Vec<String>,
+    block: Option<Vec<Directive>>,
+}
+
+/// 读取 nginx 配置并展开 `include`，相对路径按主配置所在目录解析
+fn load_nginx(path: &Path, base: &Path, depth: usize) -> Result<Vec<Directive>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let directives = parse_nginx(&text);
+    Ok(expand_includes(directives, base, depth))
+}
+
+fn expand_includes(directives: Vec<Directive>, base: &Path, depth: usize) -> Vec<Directive> {
+    let mut expanded = Vec::new();
+    for mut directive in directives {
+        if directive.name == "include" && depth < MAX_INCLUDE_DEPTH {
+            for arg in &directive.args {
+                let pattern = base.join(arg);
+                for path in glob_paths(&pattern) {
+                    match load_nginx(&path, base, depth + 1) {
+                        Ok(included) => expanded.extend(included),
+                        Err(err) => {
+                            tracing::warn!(path = %path.display(), error = %err, "nginx include 读取失败")
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+        directive.block = directive
+            .block
+            .map(|block| expand_includes(block, base, depth));
+        expanded.push(directive);
+    }
+    expanded
+}
+
+/// 把 nginx 配置拆成指令树：支持 `#` 注释和引号参数
+fn parse_nginx(text: &str) -> Vec<Directive> {
+    let mut stack: Vec<(Directive, Vec<Directive>)> = Vec::new();
+    let mut current: Vec<Directive> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            ';' => {
+                if let Some(directive) = take_directive(&mut words) {
+                    current.push(directive);
+                }
+            }
+            '{' => {
+                let parent = take_directive(&mut words).unwrap_or(Directive {
+                    name: String::new(),
+                    args: Vec::new(),
+                    block: None,
+                });
+                stack.push((parent, std::mem::take(&mut current)));
+            }
+            '}' => {
+                if let Some(directive) = take_directive(&mut words) {
+                    current.push(directive);
+                }
+                if let Some((mut parent, siblings)) = stack.pop() {
+                    parent.block = Some(std::mem::replace(&mut current, siblings));
+                    current.push(parent);
+                }
+            }
+            '"' | '\'' => {
+                let mut word = String::new();
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => word.extend(chars.next()),
+                        q if q == c => break,
+                        other => word.push(other),
+                    }
+                }
+                words.push(word);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, ';' | '{' | '}') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                words.push(word);
+            }
+        }
+    }
+    // 未闭合的块按已读到的内容收尾
+    while let Some((mut parent, siblings)) = stack.pop() {
+        parent.block = Some(std::mem::replace(&mut current, siblings));
+        current.push(parent);
+    }
+    current
+}
+
+fn take_directive(words: &mut Vec<String>) -> Option<Directive> {
+    if words.is_empty() {
+        return None;
+    }
+    let mut args = std::mem::take(words);
+    let name = args.remove(0);
+    Some(Directive {
+        name,
+        args,
+        block: None,
+    })
+}
+
+/// 从 nginx 指令树中找出所有 server 块的转发规则
+fn nginx_routes(directives: &[Directive], source: &str) -> Vec<ProxyRoute> {
+    let mut upstreams = HashMap::new();
+    collect_upstreams(directives, &mut upstreams);
+    let mut routes = Vec::new();
+    collect_servers(directives, &upstreams, source, &mut routes);
+    routes
+}
+
+fn collect_upstreams(directives: &[Directive], upstreams: &mut HashMap<String, Vec<String>>) {
+    for directive in directives {
+        let Some(block) = &directive.block else {
+            continue;
+        };
+        if directive.name == "upstream" {
+            let Some(name) = directive.args.first() else {
+                continue;
+            };
+            let servers = block
+                .iter()
+                .filter(|d| d.name == "server")
+                .filter_map(|d| d.args.first())
+                .map(|addr| with_default_port(addr, 80))
+                .collect();
+            upstreams.insert(name.clone(), servers);
+        } else {
+            collect_upstreams(block, upstreams);
+        }
+    }
+}
+
+fn collect_servers(
+    directives: &[Directive],
+    upstreams: &HashMap<String, Vec<String>>,
+    source: &str,
+    routes: &mut Vec<ProxyRoute>,
+) {
+    for directive in directives {
+        let Some(block) = &directive.block else {
+            continue;
+        };
+        if directive.name != "server" {
+            collect_servers(block, upstreams, source, routes);
+            continue;
+        }
+
+        let mut ports: Vec<u16> = block
+            .iter()
+            .filter(|d| d.name == "listen")
+            .filter_map(|d| parse_nginx_listen(&d.args))
+            .collect();
+        if ports.is_empty() {
+            ports.push(80);
+        }
+        ports.dedup();
+        let hosts: Vec<String> = block
+            .iter()
+            .filter(|d| d.name == "server_name")
+            .flat_map(|d| d.args.iter())
+            .filter(|name| !name.is_empty() && *name != "_")
+            .cloned()
+            .collect();
+
+        let mut passes = Vec::new();
+        collect_passes(block, None, upstreams, &mut passes);
+        for port in ports {
+            for (path, backends) in &passes {
+                routes.push(ProxyRoute {
+                    proxy: ProxyKind::Nginx,
+                    listen_port: port,
+                    hosts: hosts.clone(),
+                    path: path.clone(),
+                    backends: backends.clone(),
+                    source: source.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// 收集 server 块及其 location（含嵌套 location）中的转发目标
+fn collect_passes(
+    block: &[Directive],
+    path: Option<&str>,
+    upstreams: &HashMap<String, Vec<String>>,
+    passes: &mut Vec<(Option<String>, Vec<ProxyBackend>)>,
+) {
+    for directive in block {
+        if NGINX_PASS_DIRECTIVES.contains(&directive.name.as_str()) {
+            if let Some(target) = directive.args.first() {
+                let backends = nginx_backends(target, upstreams)
+                    .into_iter()
+                    .map(backend)
+                    .collect();
+                passes.push((path.map(str::to_string), backends));
+            }
+        } else if directive.name == "location" {
+            if let (Some(location), Some(inner)) = (directive.args.last(), &directive.block) {
+                collect_passes(inner, Some(location), upstreams, passes);
+            }
+        }
+    }
+}
+
+/// `listen` 的第一个参数：`443`、`[::]:443`、`127.0.0.1:8080`、`*:80`、仅地址（默认 80）；
+/// Unix 套接字不对外提供端口
+fn parse_nginx_listen(args: &[String]) -> Option<u16> {
+    let listen = args.first()?;
+    if listen.starts_with("unix:") {
+        return None;
+    }
+    if let Ok(port) = listen.parse() {
+        return Some(port);
+    }
+    match listen.rsplit_once(':') {
+        Some((_, port)) if !listen.ends_with(']') => port.parse().ok(),
+        _ => Some(80),
+    }
+}
+
+/// 转发目标指向 upstream 名称时展开为其中的服务器，否则取地址部分
+fn nginx_backends(target: &str, upstreams: &HashMap<String, Vec<String>>) -> Vec<String> {
+    if target.starts_with("unix:") {
+        return vec![target.to_string()];
+    }
+    let (scheme, rest) = target.split_once("://").unwrap_or(("", target));
+    let authority = rest.split('/').next().unwrap_or(rest);
+    if let Some(servers) = upstreams.get(authority) {
+        return servers.clone();
+    }
+    if let Some(socket) = authority.strip_prefix("unix:") {
+        return vec![format!("unix:{socket}")];
+    }
+    let default_port = match scheme {
+        "https" | "grpcs" => 443,
+        _ => 80,
+    };
+    vec![with_default_port(authority, default_port)]
+}
+
+/// 地址未写端口时补上默认端口；变量和 Unix 套接字原样保留
+fn with_default_port(address: &str, port: u16) -> String {
+    let has_port = match address.rsplit_once(':') {
+        Some((_, p)) => !address.ends_with(']') && p.parse::<u16>().is_ok(),
+        None => false,
+    };
+    if has_port || address.contains('$') || address.starts_with("unix:") {
+        address.to_string()
+    } else {
+        format!("{address}:{port}")
+    }
+}
+
+fn backend(address: String) -> ProxyBackend {
+    ProxyBackend {
+        address,
+        process: None,
+        pid: None,
+    }
+}
+
+/// Caddyfile 中的一行，以 `{` 结尾的行带子块
+#[derive(Debug)]
+struct CaddyLine {
+    tokens: Vec<String>,
+    block: Vec<CaddyLine>,
+}
+
+/// 解析 Caddyfile：站点地址决定公共端口，`reverse_proxy` / `php_fastcgi` 给出后端，
+/// `handle`、`handle_path`、`route` 的路径匹配作为路由路径
+fn parse_caddyfile(text: &str, source: &str) -> Vec<ProxyRoute> {
+    let lines = caddy_lines(text);
+    // 全局选项块（单独的 `{`）和片段（`(name) {`）不是站点
+    let sites: Vec<&CaddyLine> = lines
+        .iter()
+        .filter(|line| {
+            line.tokens
+                .first()
+                .is_some_and(|first| !first.starts_with('('))
+        })
+        .collect();
+    let single_site;
+    let sites = if sites.iter().any(|site| !site.block.is_empty()) {
+        sites
+            .into_iter()
+            .filter(|site| !site.block.is_empty())
+            .collect()
+    } else if let Some((first, rest)) = lines
+        .split_first()
+        .filter(|(first, _)| !first.tokens.is_empty())
+    {
+        // 只有一个站点时可以省略花括号：第一行是地址，其余都是该站点的指令
+        single_site = CaddyLine {
+            tokens: first.tokens.clone(),
+            block: rest
+                .iter()
+                .map(|line| CaddyLine {
+                    tokens: line.tokens.clone(),
+                    block: Vec::new(),
+                })
+                .collect(),
+        };
+        vec![&single_site]
+    } else {
+        Vec::new()
+    };
+
+    let mut routes = Vec::new();
+    for site in sites {
+        let mut by_port: BTreeMap<u16, Vec<String>> = BTreeMap::new();
+        for address in site.tokens.iter().flat_map(|t| t.split(',')) {
+            if let Some((port, host)) = parse_caddy_address(address) {
+                let hosts = by_port.entry(port).or_default();
+                hosts.extend(host);
+            }
+        }
+        let mut proxies = Vec::new();
+        collect_caddy_proxies(&site.block, None, &mut proxies);
+        for (port, hosts) in by_port {
+            for (path, backends) in &proxies {
+                routes.push(ProxyRoute {
+                    proxy: ProxyKind::Caddy,
+                    listen_port: port,
+                    hosts: hosts.clone(),
+                    path: path.clone(),
+                    backends: backends.iter().cloned().map(backend).collect(),
+                    source: source.to_string(),
+                });
+            }
+        }
+    }
+    routes
+}
+
+/// 按行拆分 Caddyfile，行尾的 `{` 开启子块，单独的 `}` 结束子块
+fn caddy_lines(text: &str) -> Vec<CaddyLine> {
+    let mut stack: Vec<(Vec<String>, Vec<CaddyLine>)> = Vec::new();
+    let mut current = Vec::new();
+    for raw in text.lines() {
+        let tokens: Vec<String> = raw
+            .split_whitespace()
+            .take_while(|token| !token.starts_with('#'))
+            .map(|token| token.trim_matches('"').to_string())
+            .collect();
+        match tokens.last().map(String::as_str) {
+            None => {}
+            Some("}") => {
+                let mut tokens = tokens;
+                tokens.pop();
+                if !tokens.is_empty() {
+                    current.push(CaddyLine {
+                        tokens,
+                        block: Vec::new(),
+                    });
+                }
+                if let Some((tokens, siblings)) = stack.pop() {
+                    let block = std::mem::replace(&mut current, siblings);
+                    current.push(CaddyLine { tokens, block });
+                }
+            }
+            Some("{") => {
+                let mut tokens = tokens;
+                tokens.pop();
+                stack.push((tokens, std::mem::take(&mut current)));
+            }
+            Some(_) => current.push(CaddyLine {
+                tokens,
+                block: Vec::new(),
+            }),
+        }
+    }
+    while let Some((tokens, siblings)) = stack.pop() {
+        let block = std::mem::replace(&mut current, siblings);
+        current.push(CaddyLine { tokens, block });
+    }
+    current
+}
+
+/// 站点地址 → (端口, 主机名)：`:8080`、`http://a.test`、`a.test:8443`，未写端口和协议时
+/// Caddy 自动启用 HTTPS，默认 443
+fn parse_caddy_address(address: &str) -> Option<(u16, Option<String>)> {
+    let address = address.trim();
+    if address.is_empty() {
+        return None;
+    }
+    let (default_port, rest) = match address.split_once("://") {
+        Some(("http", rest)) => (80, rest),
+        Some((_, rest)) => (443, rest),
+        None => (443, address),
+    };
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !authority.ends_with(']') => (host, port.parse().ok()?),
+        _ => (authority, default_port),
+    };
+    Some((port, (!host.is_empty()).then(|| host.to_string())))
+}
+
+fn collect_caddy_proxies(
+    block: &[CaddyLine],
+    path: Option<&str>,
+    proxies: &mut Vec<(Option<String>, Vec<String>)>,
+) {
+    for line in block {
+        let Some(name) = line.tokens.first() else {
+            continue;
+        };
+        let args = &line.tokens[1..];
+        match name.as_str() {
+            "reverse_proxy" | "php_fastcgi" => {
+                // 第一个参数以 / 开头时是路径匹配
+                let (matcher, targets) = match args.split_first() {
+                    Some((first, rest)) if first.starts_with('/') => (Some(first.as_str()), rest),
+                    _ => (None, args),
+                };
+                let mut upstreams: Vec<String> =
+                    targets.iter().map(|t| caddy_upstream(t)).collect();
+                upstreams.extend(
+                    line.block
+                        .iter()
+                        .filter(|inner| inner.tokens.first().is_some_and(|t| t == "to"))
+                        .flat_map(|inner| inner.tokens[1..].iter().map(|t| caddy_upstream(t))),
+                );
+                if !upstreams.is_empty() {
+                    proxies.push((matcher.or(path).map(str::to_string), upstreams));
+                }
+            }
+            "handle" | "handle_path" | "route" => {
+                let inner = args
+                    .first()
+                    .filter(|arg| arg.starts_with('/'))
+                    .map(String::as_str)
+                    .or(path);
+                collect_caddy_proxies(&line.block, inner, proxies);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `localhost:3000`、`:3000`、`http://app:8080`、`unix//run/app.sock` → 后端地址
+fn caddy_upstream(target: &str) -> String {
+    if let Some(socket) = target.strip_prefix("unix/") {
+        return format!("unix:{socket}");
+    }
+    let (scheme, rest) = target.split_once("://").unwrap_or(("", target));
+    let rest = rest.split('/').next().unwrap_or(rest);
+    let rest = if rest.starts_with(':') {
+        format!("localhost{rest}")
+    } else {
+        rest.to_string()
+    };
+    with_default_port(&rest, if scheme == "https" { 443 } else { 80 })
+}
+
+/// 解析 Caddy 管理接口 `GET /config/` 返回的 JSON 配置
+fn parse_caddy_json(body: &str, source: &str) -> Result<Vec<ProxyRoute>, String> {
+    let config: Value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+    let mut routes = Vec::new();
+    let Some(servers) = config
+        .pointer("/apps/http/servers")
+        .and_then(Value::as_object)
+    else {
+        return Ok(routes);
+    };
+    for server in servers.values() {
+        let ports: Vec<u16> = server
+            .get("listen")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter_map(parse_listen_address)
+            .collect();
+        let mut proxies = Vec::new();
+        walk_caddy_routes(server.get("routes"), &[], None, &mut proxies);
+        for port in ports {
+            for (hosts, path, backends) in &proxies {
+                routes.push(ProxyRoute {
+                    proxy: ProxyKind::Caddy,
+                    listen_port: port,
+                    hosts: hosts.clone(),
+                    path: path.clone(),
+                    backends: backends.iter().cloned().map(backend).collect(),
+                    source: source.to_string(),
+                });
+            }
+        }
+    }
+    Ok(routes)
+}
+
+type CaddyProxy = (Vec<String>, Option<String>, Vec<String>);
+
+/// 递归遍历路由和 subroute，子路由继承外层的主机名和路径匹配
+fn walk_caddy_routes(
+    routes: Option<&Value>,
+    hosts: &[String],
+    path: Option<&str>,
+    proxies: &mut Vec<CaddyProxy>,
+) {
+    for route in routes.and_then(Value::as_array).into_iter().flatten() {
+        let matchers = route.get("match").and_then(Value::as_array);
+        let strings = |key: &str| -> Vec<String> {
+            matchers
+                .into_iter()
+                .flatten()
+                .filter_map(|m| m.get(key).and_then(Value::as_array))
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        };
+        let matched_hosts = strings("host");
+        let hosts = if matched_hosts.is_empty() {
+            hosts.to_vec()
+        } else {
+            matched_hosts
+        };
+        let matched_path = strings("path").into_iter().next();
+        let path = matched_path.as_deref().or(path);
+
+        for handler in route
+            .get("handle")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            match handler.get("handler").and_then(Value::as_str) {
+                Some("reverse_proxy") => {
+                    let upstreams: Vec<String> = handler
+                        .get("upstreams")
+                        .and_then(Value::as_array)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|u| u.get("dial").and_then(Value::as_str))
+                        .map(str::to_string)
+                        .collect();
+                    if !upstreams.is_empty() {
+                        proxies.push((hosts.clone(), path.map(str::to_string), upstreams));
+                    }
+                }
+                Some("subroute") => walk_caddy_routes(handler.get("routes"), &hosts, path, proxies),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `:443`、`0.0.0.0:8080`、`tcp/:80`、`:8000-8010`（取第一个端口）
+fn parse_listen_address(address: &str) -> Option<u16> {
+    let (_, port) = address.rsplit_once(':')?;
+    port.split('-').next()?.parse().ok()
+}
+
+/// 读取 Traefik API 的入口点、HTTP 路由和服务
+fn traefik_routes(api: &str, source: &str) -> Result<Vec<ProxyRoute>, String> {
+    let get = |path: &str| -> Result<Value, String> {
+        let body = http_get(api, path)?;
+        serde_json::from_str(&body).map_err(|e| format!("{path}: {e}"))
+    };
+    let entrypoints = get("/api/entrypoints")?;
+    let routers = get("/api/http/routers")?;
+    let services = get("/api/http/services")?;
+    Ok(parse_traefik(&entrypoints, &routers, &services, source))
+}
+
+fn parse_traefik(
+    entrypoints: &Value,
+    routers: &Value,
+    services: &Value,
+    source: &str,
+) -> Vec<ProxyRoute> {
+    let str_field =
+        |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+    let entrypoint_ports: HashMap<String, u16> = entrypoints
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|ep| {
+            let port = parse_listen_address(&str_field(ep, "address")?)?;
+            Some((str_field(ep, "name")?, port))
+        })
+        .collect();
+    let service_servers: HashMap<String, Vec<String>> = services
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|svc| {
+            let servers = svc
+                .pointer("/loadBalancer/servers")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|server| str_field(server, "url"))
+                .map(|url| caddy_upstream(&url))
+                .collect();
+            Some((str_field(svc, "name")?, servers))
+        })
+        .collect();
+
+    let mut routes = Vec::new();
+    for router in routers.as_array().into_iter().flatten() {
+        let (Some(name), Some(service)) = (str_field(router, "name"), str_field(router, "service"))
+        else {
+            continue;
+        };
+        // 路由引用同一提供方的服务时省略 @provider 后缀
+        let service = match (service.contains('@'), name.split_once('@')) {
+            (false, Some((_, provider))) => format!("{service}@{provider}"),
+            _ => service,
+        };
+        let Some(servers) = service_servers.get(&service) else {
+            continue;
+        };
+        let rule = str_field(router, "rule").unwrap_or_default();
+        let hosts = rule_values(&rule, "Host");
+        let path = rule_values(&rule, "PathPrefix")
+            .into_iter()
+            .chain(rule_values(&rule, "Path"))
+            .next();
+        let ports = router
+            .get("entryPoints")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .filter_map(|ep| entrypoint_ports.get(ep).copied());
+        for port in ports {
+            routes.push(ProxyRoute {
+                proxy: ProxyKind::Traefik,
+                listen_port: port,
+                hosts: hosts.clone(),
+                path: path.clone(),
+                backends: servers.iter().cloned().map(backend).collect(),
+                source: source.to_string(),
+            });
+        }
+    }
+    routes
+}
+
+/// 取规则中 ``Host(`a`, `b`)`` 这类匹配器的参数；HostRegexp、PathPrefix 名称不同，不会误匹配
+fn rule_values(rule: &str, matcher: &str) -> Vec<String> {
+    let pattern = format!("{matcher}(");
+    let mut values = Vec::new();
+    let mut rest = rule;
+    while let Some(start) = rest.find(&pattern) {
+        rest = &rest[start + pattern.len()..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        values.extend(
+            rest[..end]
+                .split(',')
+                .map(|v| v.trim().trim_matches(|c| c == '`' || c == '"').to_string())
+                .filter(|v| !v.is_empty()),
+        );
+        rest = &rest[end..];
+    }
+    values
+}
+
+/// 管理接口地址统一为 `http://host:port`
+fn admin_base(address: &str) -> String {
+    let address = address.trim().trim_end_matches('/');
+    if address.contains("://") {
+        address.to_string()
+    } else {
+        format!("http://{address}")
+    }
+}
+
+/// 通过 HTTP/1.0 请求本地管理接口，返回 2xx 响应体
+fn http_get(address: &str, path: &str) -> Result<String, String> {
+    let base = admin_base(address);
+    let authority = base
+        .strip_prefix("http://")
+        .ok_or_else(|| "仅支持 http:// 管理接口".to_string())?;
+    let addr = authority
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("无法解析 {authority}"))?;
+    let mut stream = TcpStream::connect_timeout(&addr, ADMIN_TIMEOUT).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(ADMIN_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let request = format!("GET {path} HTTP/1.0\r\nHost: {authority}\r\nUser-Agent: Portly\r\n\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).map_err(|e| e.to_string())?;
+    crate::docker::parse_http_response(&String::from_utf8_lossy(&raw))
+        .ok_or_else(|| format!("{path} 返回错误状态"))
+}
+
+/// 后端指向本机时补上监听该端口的进程
+fn resolve_local_backends(routes: &mut [ProxyRoute], ports: &[PortInfo]) {
+    for backend in routes.iter_mut().flat_map(|r| r.backends.iter_mut()) {
+        let Some((host, port)) = backend.address.rsplit_once(':') else {
+            continue;
+        };
+        let Ok(port) = port.parse::<u16>() else {
+            continue;
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let local = matches!(
+            host,
+            "localhost" | "127.0.0.1" | "::1" | "0.0.0.0" | "::" | ""
+        );
+        let listener = ports.iter().find(|p| {
+            p.port == port && p.protocol.eq_ignore_ascii_case("tcp") && (local || p.address == host)
+        });
+        if let Some(listener) = listener {
+            backend.process = Some(listener.process.clone());
+            backend.pid = Some(listener.pid.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses(route: &ProxyRoute) -> Vec<&str> {
+        route.backends.iter().map(|b| b.address.as_str()).collect()
+    }
+
+    #[test]
+    fn test_nginx_routes() {
+        let conf = r#"
+            # upstream pool
+            upstream app_pool {
+                server 127.0.0.1:3000 weight=2;
+                server 10.0.0.5;
+            }
+            http {
+                server {
+                    listen 443 ssl http2;
+                    listen [::]:443 ssl;
+                    server_name example.com www.example.com;
+                    location / { proxy_pass http://app_pool; }
+                    location /api/ {
+                        proxy_pass "http://localhost:8080/v1/";
+                    }
+                    location ~ \.php$ { fastcgi_pass unix:/run/php/fpm.sock; }
+                }
+                server {
+                    server_name _;
+                    location /grpc { grpc_pass grpcs://backend.internal; }
+                }
+            }
+        "#;
+        let routes = nginx_routes(&parse_nginx(conf), "nginx.conf");
+        assert_eq!(routes.len(), 4);
+
+        assert_eq!(routes[0].listen_port, 443);
+        assert_eq!(routes[0].hosts, vec!["example.com", "www.example.com"]);
+        assert_eq!(routes[0].path.as_deref(), Some("/"));
+        assert_eq!(addresses(&routes[0]), vec!["127.0.0.1:3000", "10.0.0.5:80"]);
+        assert_eq!(addresses(&routes[1]), vec!["localhost:8080"]);
+        assert_eq!(addresses(&routes[2]), vec!["unix:/run/php/fpm.sock"]);
+
+        assert_eq!(routes[3].listen_port, 80);
+        assert!(routes[3].hosts.is_empty());
+        assert_eq!(addresses(&routes[3]), vec!["backend.internal:443"]);
+    }
+
+    #[test]
+    fn test_nginx_include_expansion() {
+        let dir = std::env::temp_dir().join(format!("portly-proxy-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sites")).unwrap();
+        std::fs::write(
+            dir.join("nginx.conf"),
+            "http { include sites/*.conf; include missing.conf; }",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("sites/app.conf"),
+            "server { listen 8443; location / { proxy_pass http://127.0.0.1:5000; } }",
+        )
+        .unwrap();
+
+        let routes = read_config_file(&dir.join("nginx.conf")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].listen_port, 8443);
+        assert_eq!(addresses(&routes[0]), vec!["127.0.0.1:5000"]);
+    }
+
+    #[test]
+    fn test_parse_caddyfile() {
+        let caddyfile = r#"
+            {
+                email admin@example.com
+            }
+            (common) {
+                encode gzip
+            }
+            example.com, www.example.com {
+                import common
+                handle_path /api/* {
+                    reverse_proxy localhost:8080 localhost:8081
+                }
+                reverse_proxy /ws/* :9000
+                handle {
+                    reverse_proxy {
+                        to http://127.0.0.1:3000
+                    }
+                }
+            }
+            http://intranet.test:8088 {
+                php_fastcgi unix//run/php/fpm.sock
+            }
+        "#;
+        let routes = parse_caddyfile(caddyfile, "Caddyfile");
+        assert_eq!(routes.len(), 4);
+
+        assert_eq!(routes[0].listen_port, 443);
+        assert_eq!(routes[0].hosts, vec!["example.com", "www.example.com"]);
+        assert_eq!(routes[0].path.as_deref(), Some("/api/*"));
+        assert_eq!(
+            addresses(&routes[0]),
+            vec!["localhost:8080", "localhost:8081"]
+        );
+        assert_eq!(routes[1].path.as_deref(), Some("/ws/*"));
+        assert_eq!(addresses(&routes[1]), vec!["localhost:9000"]);
+        assert_eq!(routes[2].path, None);
+        assert_eq!(addresses(&routes[2]), vec!["127.0.0.1:3000"]);
+
+        assert_eq!(routes[3].listen_port, 8088);
+        assert_eq!(routes[3].hosts, vec!["intranet.test"]);
+        assert_eq!(addresses(&routes[3]), vec!["unix:/run/php/fpm.sock"]);
+
+        let single = parse_caddyfile(":8080\nreverse_proxy app:3000\n", "Caddyfile");
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].listen_port, 8080);
+        assert!(single[0].hosts.is_empty());
+        assert_eq!(addresses(&single[0]), vec!["app:3000"]);
+    }
+
+    #[test]
+    fn test_parse_caddy_json() {
+        let body = r#"{"apps": {"http": {"servers": {"srv0": {
+            "listen": [":443"],
+            "routes": [{
+                "match": [{"host": ["app.example.com"]}],
+                "handle": [{"handler": "subroute", "routes": [
+                    {"match": [{"path": ["/api/*"]}],
+                     "handle": [{"handler": "reverse_proxy", "upstreams": [{"dial": "localhost:8080"}]}]},
+                    {"handle": [{"handler": "reverse_proxy", "upstreams": [{"dial": "localhost:3000"}]}]}
+                ]}]
+            }]
+        }}}}}"#;
+        let routes = parse_caddy_json(body, "caddy").unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].listen_port, 443);
+        assert_eq!(routes[0].hosts, vec!["app.example.com"]);
+        assert_eq!(routes[0].path.as_deref(), Some("/api/*"));
+        assert_eq!(addresses(&routes[0]), vec!["localhost:8080"]);
+        assert_eq!(routes[1].path, None);
+        assert_eq!(addresses(&routes[1]), vec!["localhost:3000"]);
+
+        assert!(parse_caddy_json("{}", "caddy").unwrap().is_empty());
+        assert!(parse_caddy_json("nope", "caddy").is_err());
+    }
+
+    #[test]
+    fn test_parse_traefik() {
+        let entrypoints: Value = serde_json::from_str(
+            r#"[{"name": "web", "address": ":80"}, {"name": "websecure", "address": ":443"}]"#,
+        )
+        .unwrap();
+        let routers: Value = serde_json::from_str(
+            r#"[
+                {"name": "app@docker", "entryPoints": ["web", "websecure"], "service": "app",
+                 "rule": "Host(`app.example.com`) && PathPrefix(`/api`)"},
+                {"name": "dash@internal", "entryPoints": ["traefik"], "service": "api@internal",
+                 "rule": "PathPrefix(`/dashboard`)"},
+                {"name": "wiki@file", "entryPoints": ["websecure"], "service": "wiki@file",
+                 "rule": "HostRegexp(`{sub:[a-z]+}.wiki.test`) || Host(`wiki.test`)"}
+            ]"#,
+        )
+        .unwrap();
+        let services: Value = serde_json::from_str(
+            r#"[
+                {"name": "app@docker", "loadBalancer": {"servers": [{"url": "http://172.17.0.3:8080"}]}},
+                {"name": "wiki@file", "loadBalancer": {"servers": [{"url": "http://127.0.0.1:4567/"}]}}
+            ]"#,
+        )
+        .unwrap();
+
+        let routes = parse_traefik(&entrypoints, &routers, &services, "traefik");
+        assert_eq!(routes.len(), 3);
+        assert_eq!(routes[0].listen_port, 80);
+        assert_eq!(routes[1].listen_port, 443);
+        assert_eq!(routes[0].hosts, vec!["app.example.com"]);
+        assert_eq!(routes[0].path.as_deref(), Some("/api"));
+        assert_eq!(addresses(&routes[0]), vec!["172.17.0.3:8080"]);
+        assert_eq!(routes[2].hosts, vec!["wiki.test"]);
+        assert_eq!(addresses(&routes[2]), vec!["127.0.0.1:4567"]);
+    }
+
+    #[test]
+    fn test_resolve_local_backends() {
+        let listener = |port: u16, address: &str, process: &str| PortInfo {
+            port,
+            protocol: "TCP".to_string(),
+            address: address.to_string(),
+            pid: port.to_string(),
+            process: process.to_string(),
+            user: "me".to_string(),
+            command: None,
+        };
+        let ports = vec![
+            listener(3000, "127.0.0.1", "node"),
+            listener(5432, "10.0.0.9", "postgres"),
+        ];
+        let mut routes = vec![ProxyRoute {
+            proxy: ProxyKind::Nginx,
+            listen_port: 443,
+            hosts: Vec::new(),
+            path: None,
+            backends: [
+                "localhost:3000",
+                "10.0.0.9:5432",
+                "10.0.0.5:80",
+                "unix:/run/a.sock",
+            ]
+            .into_iter()
+            .map(|a| backend(a.to_string()))
+            .collect(),
+            source: "nginx.conf".to_string(),
+        }];
+        resolve_local_backends(&mut routes, &ports);
+        let processes: Vec<Option<&str>> = routes[0]
+            .backends
+            .iter()
+            .map(|b| b.process.as_deref())
+            .collect();
+        assert_eq!(processes, vec![Some("node"), Some("postgres"), None, None]);
+        assert_eq!(routes[0].backends[0].pid.as_deref(), Some("3000"));
+    }
+}
@@ -0,0 +1,302 @@
+//! JSON-RPC 2.0 自动化接口（stdio）
+//!
+//! `portly-cli --rpc` 从 stdin 逐行读取请求、向 stdout 逐行写出响应，每行一个 JSON 对象，
+//! 供编辑器插件、脚本和其他本机工具调用，不需要启动 HTTP 守护进程。请求按顺序处理；
+//! 不支持批量请求。`discover` 在扫描过程中以 `discover.device` 通知推送已发现的设备。
+//!
+//! 业务错误使用 `-32000`，`data` 为与 REST API 相同的 `{kind, message}` 结构
+
+use crate::app_error::{AppError, PortlyErrorKind};
+use crate::{core, network, process};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+/// 支持的方法
+pub const METHODS: &[&str] = &[
+    "ports.list",
+    "ports.grouped",
+    "ports.filter",
+    "scan",
+    "discover",
+    "kill",
+];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// 错误对象
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    fn from_app(err: &AppError) -> Self {
+        let code = match err.kind() {
+            PortlyErrorKind::Validation => INVALID_PARAMS,
+            _ => SERVER_ERROR,
+        };
+        Self {
+            code,
+            message: err.to_string(),
+            data: serde_json::to_value(err).ok(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct PortsParams {
+    command: bool,
+    udp: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FilterParams {
+    port: Option<u16>,
+    app: Option<String>,
+    exclude_system: bool,
+}
+
+#[derive(Deserialize)]
+struct ScanParams {
+    target: String,
+    #[serde(default)]
+    ports: Option<String>,
+    #[serde(default)]
+    timeout: Option<u64>,
+    #[serde(default)]
+    all: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct DiscoverParams {
+    /// 为空时使用当前子网
+    subnet: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct KillParams {
+    pid: Option<u32>,
+    port: Option<u16>,
+    force: bool,
+}
+
+/// 处理 stdin 上的请求直到输入结束
+pub fn serve_stdio(input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+    tracing::info!("JSON-RPC 已启动");
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        // 通知立即写出，调用方在长时间的扫描中也能实时收到；写失败时由随后的响应报告
+        let response = handle_line(&line, &mut |method, params| {
+            let _ = writeln!(output, "{}", notification(method, params));
+            let _ = output.flush();
+        });
+        if let Some(response) = response {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// 处理一行请求，返回响应；通知（没有 `id`）不返回响应。
+/// `notify` 收到处理过程中要推送给调用方的通知
+pub fn handle_line(line: &str, notify: &mut dyn FnMut(&str, Value)) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(err) => {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, err.to_string()),
+            ))
+        }
+    };
+    let id = request.get("id").cloned();
+    let method = request.get("method").and_then(Value::as_str);
+    let (Some(method), Some("2.0")) = (method, request.get("jsonrpc").and_then(Value::as_str))
+    else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            RpcError::new(
+                INVALID_REQUEST,
+                "需要 jsonrpc 为 \"2.0\" 且包含 method 的对象",
+            ),
+        ));
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    tracing::debug!(method, "JSON-RPC 请求");
+    let result = dispatch(method, params, notify);
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error_response(id, err),
+    })
+}
+
+fn dispatch(
+    method: &str,
+    params: Value,
+    notify: &mut dyn FnMut(&str, Value),
+) -> Result<Value, RpcError> {
+    match method {
+        "ports.list" => {
+            let params: PortsParams = parse_params(params)?;
+            to_result(core::try_scan_ports(params.command, params.udp))
+        }
+        "ports.grouped" => to_result(core::try_scan_ports_grouped()),
+        "ports.filter" => {
+            let params: FilterParams = parse_params(params)?;
+            if params.port == Some(0) {
+                return Err(RpcError::from_app(&AppError::validation(
+                    "port",
+                    "需要 1-65535 的端口号",
+                )));
+            }
+            to_result(Ok(core::filter_ports(
+                params.port,
+                params.app,
+                params.exclude_system,
+            )))
+        }
+        "scan" => {
+            let params: ScanParams = parse_params(params)?;
+            crate::daemon::remote_scan_output(
+                &params.target,
+                params.ports.as_deref(),
+                params.timeout,
+                params.all,
+            )
+            .map_err(|err| RpcError::from_app(&err))
+        }
+        "discover" => {
+            let params: DiscoverParams = parse_params(params)?;
+            let subnet = params
+                .subnet
+                .filter(|subnet| !subnet.trim().is_empty())
+                .or_else(network::get_current_subnet)
+                .ok_or_else(|| {
+                    RpcError::from_app(&AppError::validation("subnet", "无法检测当前子网"))
+                })?;
+            to_result(network::try_discover_devices_with(
+                &subnet,
+                |_, _| Ok(()),
+                |device| notify("discover.device", json!(device)),
+            ))
+        }
+        "kill" => {
+            let params: KillParams = parse_params(params)?;
+            let result = match (params.pid, params.port) {
+                (Some(pid), None) => process::kill_process(pid, params.force),
+                (None, Some(port)) if port > 0 => process::kill_port_process(port),
+                _ => {
+                    return Err(RpcError::from_app(&AppError::validation(
+                        "params",
+                        "需要 pid 或 port 其中之一",
+                    )))
+                }
+            };
+            to_result(Ok(result))
+        }
+        _ => Err(RpcError {
+            data: Some(json!(METHODS)),
+            ..RpcError::new(METHOD_NOT_FOUND, format!("未知方法：{method}"))
+        }),
+    }
+}
+
+/// 省略 params 或为 null 时按空对象解析
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+fn to_result<T: Serialize>(result: Result<T, AppError>) -> Result<Value, RpcError> {
+    let value = result.map_err(|err| RpcError::from_app(&err))?;
+    serde_json::to_value(value).map_err(|err| RpcError::new(SERVER_ERROR, err.to_string()))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(line: &str) -> Option<Value> {
+        handle_line(line, &mut |_, _| {})
+    }
+
+    #[test]
+    fn test_protocol_errors() {
+        let response = call("{not json").unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = call(r#"{"id": 1, "method": "scan"}"#).unwrap();
+        assert_eq!(response["error"]["code"], INVALID_REQUEST);
+        assert_eq!(response["id"], 1);
+
+        let response = call(r#"{"jsonrpc": "2.0", "id": "a", "method": "nope"}"#).unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(response["error"]["data"][0], "ports.list");
+
+        // 缺少必填参数和业务校验失败都是 invalid params
+        let response = call(r#"{"jsonrpc": "2.0", "id": 2, "method": "scan"}"#).unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        let response = call(
+            r#"{"jsonrpc": "2.0", "id": 3, "method": "kill", "params": {"pid": 1, "port": 80}}"#,
+        )
+        .unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert_eq!(response["error"]["data"]["kind"], "validation");
+
+        // 没有 id 的通知不返回响应
+        assert!(call(r#"{"jsonrpc": "2.0", "method": "nope"}"#).is_none());
+    }
+
+    #[test]
+    fn test_serve_stdio_writes_one_line_per_response() {
+        let input = "\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ports.filter\",\"params\":{\"port\":0}}\n\
+                     {\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"nope\"}\n";
+        let mut output = Vec::new();
+        serve_stdio(std::io::Cursor::new(input), &mut output).unwrap();
+        let lines: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["id"], 1);
+        assert_eq!(lines[0]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(lines[1]["id"], 2);
+    }
+}
@@ -0,0 +1,586 @@
+//! 规则引擎
+//!
+//! 用户定义的规则保存在数据目录下的 `rules.json`，由后台监控每轮按本机监听端口和
+//! 局域网设备求值。规则按“匹配项”边沿触发：同一端口或设备持续满足条件时只触发一次，
+//! 条件消失后再次出现会重新触发。触发后的动作（系统通知、Webhook、日志、确认后终止进程）
+//! 由调用方执行
+
+use crate::app_error::{AppError, AppResult};
+use crate::audit::Severity;
+use crate::core::PortInfo;
+use crate::i18n::{tr_args, Lang, Msg};
+use crate::network::NetworkDevice;
+use crate::policy::PortPolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// 当前生效的规则，首次读取时从磁盘加载
+static CURRENT: RwLock<Option<Vec<Rule>>> = RwLock::new(None);
+
+/// 规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    /// 为空时保存时自动生成
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: RuleCondition,
+    pub actions: Vec<RuleAction>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 规则条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// 端口在 `min..=max` 范围内（`outside` 为 true 时在范围外）被监听，可限定监听地址
+    PortListening {
+        min: u16,
+        max: u16,
+        #[serde(default)]
+        outside: bool,
+        /// 如 `0.0.0.0`；`0.0.0.0`、`::` 与 `*` 都表示所有地址
+        #[serde(default)]
+        address: Option<String>,
+    },
+    /// 局域网出现 MAC 不在白名单中的在线设备（没有 MAC 的设备也算未知）
+    UnknownDevice {
+        #[serde(default)]
+        known_macs: Vec<String>,
+    },
+    /// 端口监听在非回环地址上，可从局域网访问
+    LanExposed { port: u16 },
+    /// 监听端口命中配置文件中的端口策略，可限定最低严重程度
+    PolicyViolation {
+        #[serde(default)]
+        min_severity: Option<Severity>,
+    },
+}
+
+/// 规则触发后的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Notify,
+    Webhook,
+    Log,
+    /// 经用户确认后终止占用端口的进程，只对端口类条件生效
+    Kill,
+}
+
+/// 一次规则触发
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleHit {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub actions: Vec<RuleAction>,
+    pub title: String,
+    pub body: String,
+    /// 端口类条件命中的端口与进程
+    pub port: Option<u16>,
+    pub pid: Option<u32>,
+}
+
+/// 规则求值状态，记录每条规则已触发过的匹配项
+#[derive(Debug, Default)]
+pub struct RuleEngine {
+    fired: HashMap<String, HashSet<String>>,
+}
+
+/// 单个匹配项
+struct RuleMatch {
+    key: String,
+    body: String,
+    port: Option<u16>,
+    pid: Option<u32>,
+}
+
+impl Rule {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::validation("规则名称", "不能为空"));
+        }
+        if self.actions.is_empty() {
+            return Err(AppError::validation("规则动作", "至少需要一个动作"));
+        }
+        match &self.condition {
+            RuleCondition::PortListening {
+                min, max, address, ..
+            } => {
+                if *min == 0 || min > max {
+                    return Err(AppError::validation(
+                        "规则端口范围",
+                        "需要 1-65535 且起始不大于结束",
+                    ));
+                }
+                if let Some(address) = address {
+                    if !is_wildcard(address) && parse_ip(address).is_none() {
+                        return Err(AppError::validation("规则监听地址", "不是合法的 IP 地址"));
+                    }
+                }
+            }
+            RuleCondition::LanExposed { port } => {
+                if *port == 0 {
+                    return Err(AppError::validation("规则端口", "必须大于 0"));
+                }
+            }
+            RuleCondition::PolicyViolation { .. } => {}
+            RuleCondition::UnknownDevice { .. } => {
+                if self.actions.contains(&RuleAction::Kill) {
+                    return Err(AppError::validation("规则动作", "设备规则不支持终止进程"));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 当前满足条件的匹配项；`None` 表示本轮没有可用数据，保持上次状态
+    fn matches(
+        &self,
+        ports: Option<&[PortInfo]>,
+        devices: Option<&[NetworkDevice]>,
+        policies: &[PortPolicy],
+        lang: Lang,
+    ) -> Option<Vec<RuleMatch>> {
+        match &self.condition {
+            RuleCondition::PortListening {
+                min,
+                max,
+                outside,
+                address,
+            } => {
+                let matched = ports?
+                    .iter()
+                    .filter(|p| (*min..=*max).contains(&p.port) != *outside)
+                    .filter(|p| {
+                        address
+                            .as_deref()
+                            .is_none_or(|address| same_address(address, &p.address))
+                    })
+                    .map(|p| port_match(p, Msg::RulePortListening, lang))
+                    .collect();
+                Some(matched)
+            }
+            RuleCondition::LanExposed { port } => {
+                let matched = ports?
+                    .iter()
+                    .filter(|p| p.port == *port && !is_loopback(&p.address))
+                    .map(|p| port_match(p, Msg::RuleLanExposed, lang))
+                    .collect();
+                Some(matched)
+            }
+            RuleCondition::PolicyViolation { min_severity } => {
+                let matched = ports?
+                    .iter()
+                    .filter_map(|p| {
+                        let hit = crate::policy::check_listening(policies, p)?;
+                        if min_severity.is_some_and(|min| hit.severity < min) {
+                            return None;
+                        }
+                        let owner = tr_args(lang, Msg::MonitorPortOwner, &[&p.process, &p.pid]);
+                        Some(RuleMatch {
+                            key: format!("{}:{}:{}:{}", p.port, p.address, p.pid, hit.policy),
+                            body: tr_args(
+                                lang,
+                                Msg::RulePolicyViolation,
+                                &[&p.port, &hit.policy, &hit.severity.name(), &owner],
+                            ),
+                            port: Some(p.port),
+                            pid: p.pid.parse().ok(),
+                        })
+                    })
+                    .collect();
+                Some(matched)
+            }
+            RuleCondition::UnknownDevice { known_macs } => {
+                let known: HashSet<String> = known_macs.iter().map(|m| normalize_mac(m)).collect();
+                let matched = devices?
+                    .iter()
+                    .filter(|d| d.is_online)
+                    .filter(|d| {
+                        d.mac
+                            .as_deref()
+                            .is_none_or(|mac| !known.contains(&normalize_mac(mac)))
+                    })
+                    .map(|d| {
+                        let mut target = d.ip.clone();
+                        if let Some(mac) = &d.mac {
+                            target.push_str(&format!(" ({mac})"));
+                        }
+                        RuleMatch {
+                            key: d.mac.clone().unwrap_or_else(|| d.ip.clone()),
+                            body: tr_args(lang, Msg::RuleUnknownDevice, &[&target]),
+                            port: None,
+                            pid: None,
+                        }
+                    })
+                    .collect();
+                Some(matched)
+            }
+        }
+    }
+}
+
+fn port_match(port: &PortInfo, msg: Msg, lang: Lang) -> RuleMatch {
+    let owner = tr_args(lang, Msg::MonitorPortOwner, &[&port.process, &port.pid]);
+    RuleMatch {
+        key: format!("{}:{}:{}", port.port, port.address, port.pid),
+        body: tr_args(lang, msg, &[&port.port, &port.address, &owner]),
+        port: Some(port.port),
+        pid: port.pid.parse().ok(),
+    }
+}
+
+impl RuleEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 按本轮数据求值启用的规则，返回新出现的匹配项
+    ///
+    /// `ports` / `devices` 为 `None` 表示本轮没有读取（如局域网扫描未到间隔），相关规则保持原状态；
+    /// `policies` 是 `policy_violation` 条件使用的端口策略
+    pub fn evaluate(
+        &mut self,
+        rules: &[Rule],
+        ports: Option<&[PortInfo]>,
+        devices: Option<&[NetworkDevice]>,
+        policies: &[PortPolicy],
+        lang: Lang,
+    ) -> Vec<RuleHit> {
+        // 删除或停用的规则不再保留状态
+        self.fired
+            .retain(|id, _| rules.iter().any(|rule| rule.enabled && rule.id == *id));
+
+        let mut hits = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            let Some(matches) = rule.matches(ports, devices, policies, lang) else {
+                continue;
+            };
+            let previous = self.fired.remove(&rule.id).unwrap_or_default();
+            let mut current = HashSet::new();
+            for matched in matches {
+                if !previous.contains(&matched.key) {
+                    hits.push(RuleHit {
+                        rule_id: rule.id.clone(),
+                        rule_name: rule.name.clone(),
+                        actions: rule.actions.clone(),
+                        title: tr_args(lang, Msg::RuleTriggered, &[&rule.name]),
+                        body: matched.body,
+                        port: matched.port,
+                        pid: matched.pid,
+                    });
+                }
+                current.insert(matched.key);
+            }
+            self.fired.insert(rule.id.clone(), current);
+        }
+        hits
+    }
+}
+
+/// 规则文件路径
+pub fn rules_path() -> PathBuf {
+    crate::export::get_data_dir().join("rules.json")
+}
+
+/// 当前生效的规则；规则文件无法读取时记录日志并视为没有规则
+pub fn current() -> Vec<Rule> {
+    if let Some(rules) = CURRENT.read().ok().and_then(|guard| guard.clone()) {
+        return rules;
+    }
+    let rules = load_rules().unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "读取规则文件失败");
+        Vec::new()
+    });
+    install(rules.clone());
+    rules
+}
+
+/// 读取规则文件；文件不存在时返回空列表
+pub fn load_rules() -> AppResult<Vec<Rule>> {
+    let path = rules_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::internal(format!("读取规则文件失败: {}", e)))?;
+    serde_json::from_str(&content).map_err(|e| AppError::validation("规则文件", e.to_string()))
+}
+
+/// 校验并保存全部规则，成功后立即生效
+pub fn save_rules(rules: &[Rule]) -> AppResult<()> {
+    for rule in rules {
+        rule.validate()?;
+    }
+    let path = rules_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建规则目录失败: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(rules)
+        .map_err(|e| AppError::internal(format!("序列化规则失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入规则文件失败: {}", e)))?;
+    install(rules.to_vec());
+    tracing::info!(path = %path.display(), count = rules.len(), "规则已保存");
+    Ok(())
+}
+
+/// 新增或按 ID 替换一条规则，返回保存后的全部规则
+pub fn upsert_rule(mut rule: Rule) -> AppResult<Vec<Rule>> {
+    rule.validate()?;
+    let mut rules = current();
+    if rule.id.trim().is_empty() {
+        rule.id = generate_id(&rules);
+    }
+    match rules.iter_mut().find(|r| r.id == rule.id) {
+        Some(existing) => *existing = rule,
+        None => rules.push(rule),
+    }
+    save_rules(&rules)?;
+    Ok(rules)
+}
+
+/// 按 ID 删除规则，返回保存后的全部规则
+pub fn delete_rule(id: &str) -> AppResult<Vec<Rule>> {
+    let mut rules = current();
+    let before = rules.len();
+    rules.retain(|rule| rule.id != id);
+    if rules.len() == before {
+        return Err(AppError::validation("规则 ID", format!("不存在：{id}")));
+    }
+    save_rules(&rules)?;
+    Ok(rules)
+}
+
+fn install(rules: Vec<Rule>) {
+    if let Ok(mut guard) = CURRENT.write() {
+        *guard = Some(rules);
+    }
+}
+
+/// 基于当前时间生成不重复的规则 ID
+fn generate_id(rules: &[Rule]) -> String {
+    let mut millis = chrono::Local::now().timestamp_millis();
+    loop {
+        let id = format!("rule-{millis}");
+        if rules.iter().all(|rule| rule.id != id) {
+            return id;
+        }
+        millis += 1;
+    }
+}
+
+pub(crate) fn is_wildcard(address: &str) -> bool {
+    matches!(address, "*" | "0.0.0.0" | "::" | "[::]")
+}
+
+pub(crate) fn parse_ip(address: &str) -> Option<IpAddr> {
+    address
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split('%')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn same_address(expected: &str, actual: &str) -> bool {
+    if is_wildcard(expected) || is_wildcard(actual) {
+        return is_wildcard(expected) && is_wildcard(actual);
+    }
+    match (parse_ip(expected), parse_ip(actual)) {
+        (Some(a), Some(b)) => a == b,
+        _ => expected.eq_ignore_ascii_case(actual),
+    }
+}
+
+pub(crate) fn is_loopback(address: &str) -> bool {
+    address.eq_ignore_ascii_case("localhost")
+        || parse_ip(address).is_some_and(|ip| ip.is_loopback())
+}
+
+fn normalize_mac(mac: &str) -> String {
+    mac.to_ascii_lowercase().replace('-', ":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port: u16, address: &str) -> PortInfo {
+        PortInfo {
+            port,
+            protocol: "TCP".to_string(),
+            address: address.to_string(),
+            pid: "42".to_string(),
+            process: "mysqld".to_string(),
+            user: "me".to_string(),
+            command: None,
+        }
+    }
+
+    fn rule(condition: RuleCondition) -> Rule {
+        Rule {
+            id: "r1".to_string(),
+            name: "test".to_string(),
+            enabled: true,
+            condition,
+            actions: vec![RuleAction::Notify],
+        }
+    }
+
+    #[test]
+    fn test_port_rules_fire_once_per_match() {
+        let rules = vec![rule(RuleCondition::PortListening {
+            min: 1,
+            max: 1024,
+            outside: true,
+            address: Some("0.0.0.0".to_string()),
+        })];
+        let mut engine = RuleEngine::new();
+        let ports = vec![port(80, "*"), port(3000, "*"), port(5173, "127.0.0.1")];
+
+        let hits = engine.evaluate(&rules, Some(&ports), None, &[], Lang::En);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].port, Some(3000));
+        assert_eq!(hits[0].pid, Some(42));
+        assert!(engine
+            .evaluate(&rules, Some(&ports), None, &[], Lang::En)
+            .is_empty());
+        // 没有端口数据时保持状态
+        assert!(engine
+            .evaluate(&rules, None, None, &[], Lang::En)
+            .is_empty());
+
+        // 端口关闭后再次出现会重新触发
+        assert!(engine
+            .evaluate(&rules, Some(&[]), None, &[], Lang::En)
+            .is_empty());
+        assert_eq!(
+            engine
+                .evaluate(&rules, Some(&ports), None, &[], Lang::En)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_lan_exposed_ignores_loopback() {
+        let rules = vec![rule(RuleCondition::LanExposed { port: 3306 })];
+        let mut engine = RuleEngine::new();
+        let local = vec![port(3306, "127.0.0.1"), port(3306, "[::1]")];
+        assert!(engine
+            .evaluate(&rules, Some(&local), None, &[], Lang::En)
+            .is_empty());
+
+        let exposed = vec![port(3306, "192.168.1.10")];
+        let hits = engine.evaluate(&rules, Some(&exposed), None, &[], Lang::En);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].body.contains("3306"));
+    }
+
+    #[test]
+    fn test_policy_violation_respects_min_severity() {
+        let policies = vec![
+            PortPolicy {
+                name: "MySQL exposed".to_string(),
+                port: Some(3306),
+                service: None,
+                scope: crate::policy::PolicyScope::Exposed,
+                severity: Severity::High,
+            },
+            PortPolicy {
+                name: "Any dev server".to_string(),
+                port: Some(3000),
+                service: None,
+                scope: crate::policy::PolicyScope::Any,
+                severity: Severity::Low,
+            },
+        ];
+        let ports = vec![
+            port(3306, "0.0.0.0"),
+            port(3306, "127.0.0.1"),
+            port(3000, "*"),
+        ];
+
+        let rules = vec![rule(RuleCondition::PolicyViolation { min_severity: None })];
+        let hits = RuleEngine::new().evaluate(&rules, Some(&ports), None, &policies, Lang::En);
+        assert_eq!(hits.len(), 2);
+
+        let rules = vec![rule(RuleCondition::PolicyViolation {
+            min_severity: Some(Severity::Medium),
+        })];
+        let hits = RuleEngine::new().evaluate(&rules, Some(&ports), None, &policies, Lang::En);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].port, Some(3306));
+        assert!(hits[0].body.contains("MySQL exposed"));
+    }
+
+    #[test]
+    fn test_unknown_device_uses_mac_allowlist() {
+        let rules = vec![rule(RuleCondition::UnknownDevice {
+            known_macs: vec!["AA-BB-CC-DD-EE-FF".to_string()],
+        })];
+        let device = |ip: &str, mac: Option<&str>| NetworkDevice {
+            ip: ip.to_string(),
+            mac: mac.map(str::to_string),
+            hostname: None,
+            is_online: true,
+            interface_index: None,
+            rtt_ms: None,
+            tags: Vec::new(),
+            note: None,
+        };
+        let devices = vec![
+            device("192.168.1.2", Some("aa:bb:cc:dd:ee:ff")),
+            device("192.168.1.3", Some("11:22:33:44:55:66")),
+            device("192.168.1.4", None),
+        ];
+        let mut engine = RuleEngine::new();
+        // 本轮没有设备数据时不求值
+        assert!(engine
+            .evaluate(&rules, Some(&[]), None, &[], Lang::En)
+            .is_empty());
+        let hits = engine.evaluate(&rules, None, Some(&devices), &[], Lang::En);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.pid.is_none()));
+    }
+
+    #[test]
+    fn test_rule_validate() {
+        assert!(rule(RuleCondition::LanExposed { port: 3306 })
+            .validate()
+            .is_ok());
+        assert!(rule(RuleCondition::LanExposed { port: 0 })
+            .validate()
+            .is_err());
+        assert!(rule(RuleCondition::PortListening {
+            min: 2000,
+            max: 1000,
+            outside: false,
+            address: None,
+        })
+        .validate()
+        .is_err());
+
+        let mut kill_device = rule(RuleCondition::UnknownDevice { known_macs: vec![] });
+        kill_device.actions = vec![RuleAction::Kill];
+        assert!(kill_device.validate().is_err());
+
+        let json = r#"{"name":"db","condition":{"type":"lan_exposed","port":3306},"actions":["notify","kill"]}"#;
+        let parsed: Rule = serde_json::from_str(json).unwrap();
+        assert!(parsed.enabled);
+        assert!(parsed.id.is_empty());
+        assert_eq!(parsed.actions, vec![RuleAction::Notify, RuleAction::Kill]);
+    }
+}
@@ -0,0 +1,579 @@
+//! 扫描任务管理
+//!
+//! [`ScanManager`] 记录所有长时间运行的操作（本机扫描、设备发现、端口扫描、Ping、
+//! Traceroute、后台监控、端口实时监听、事件订阅）：分配 ID、跟踪状态与进度、支持取消，并限制
+//! 同类任务的并发数。任务在调用方的线程中执行，通过 [`TaskHandle`] 汇报进度和
+//! 检查是否已被取消
+//!
+//! 进度、逐条结果和结束状态以 [`ScanEvent`] 推送给订阅者（GUI 转发为
+//! `scan://progress` / `scan://result` / `scan://done` 事件），前端无需等待整个任务结束
+
+use crate::app_error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// 保留的已结束任务数
+const MAX_FINISHED_TASKS: usize = 50;
+
+/// 任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskKind {
+    LocalScan,
+    Discovery,
+    PortScan,
+    Ping,
+    Traceroute,
+    ServiceProbe,
+    Monitor,
+    PortWatch,
+    InterfaceWatch,
+    DockerEvents,
+    Scheduler,
+}
+
+impl TaskKind {
+    /// 同类任务允许同时运行的数量
+    pub fn max_concurrent(self) -> usize {
+        match self {
+            // 局域网发现会占满 Ping 并发，同时只跑一个
+            TaskKind::Discovery => 1,
+            TaskKind::LocalScan => 2,
+            TaskKind::PortScan | TaskKind::Traceroute | TaskKind::ServiceProbe => 4,
+            TaskKind::Ping => 8,
+            // 常驻任务只需要一个实例
+            TaskKind::Monitor
+            | TaskKind::PortWatch
+            | TaskKind::InterfaceWatch
+            | TaskKind::DockerEvents
+            | TaskKind::Scheduler => 1,
+        }
+    }
+
+    /// 用于日志和错误信息的名称
+    pub fn display_name(self) -> &'static str {
+        match self {
+            TaskKind::LocalScan => "本机端口扫描",
+            TaskKind::Discovery => "局域网设备发现",
+            TaskKind::PortScan => "端口扫描",
+            TaskKind::Ping => "Ping 测试",
+            TaskKind::Traceroute => "Traceroute",
+            TaskKind::ServiceProbe => "服务探测",
+            TaskKind::Monitor => "后台监控",
+            TaskKind::PortWatch => "端口实时监听",
+            TaskKind::InterfaceWatch => "网卡流量采样",
+            TaskKind::DockerEvents => "Docker 事件订阅",
+            TaskKind::Scheduler => "定时任务",
+        }
+    }
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// 任务进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+/// 任务信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskInfo {
+    pub id: u64,
+    pub kind: TaskKind,
+    /// 任务目标，如子网或主机
+    pub label: String,
+    pub status: TaskStatus,
+    pub progress: Option<TaskProgress>,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 任务进度事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgressEvent {
+    pub task_id: u64,
+    pub kind: TaskKind,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// 任务产出的单条结果（设备、开放端口、Ping 回复、路由跳点等）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanResultEvent {
+    pub task_id: u64,
+    pub kind: TaskKind,
+    pub item: serde_json::Value,
+}
+
+/// 任务结束事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDoneEvent {
+    pub task_id: u64,
+    pub kind: TaskKind,
+    pub status: TaskStatus,
+    pub error: Option<String>,
+}
+
+/// 推送给订阅者的任务事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ScanEvent {
+    Progress(ScanProgressEvent),
+    Result(ScanResultEvent),
+    Done(ScanDoneEvent),
+}
+
+impl ScanEvent {
+    /// 事件名
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScanEvent::Progress(_) => "scan://progress",
+            ScanEvent::Result(_) => "scan://result",
+            ScanEvent::Done(_) => "scan://done",
+        }
+    }
+}
+
+type Subscriber = Arc<dyn Fn(&ScanEvent) + Send + Sync>;
+
+struct TaskEntry {
+    info: TaskInfo,
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct Inner {
+    next_id: AtomicU64,
+    tasks: Mutex<BTreeMap<u64, TaskEntry>>,
+    subscribers: RwLock<Vec<Subscriber>>,
+}
+
+/// 任务登记表，克隆后共享同一份状态
+#[derive(Clone, Default)]
+pub struct ScanManager {
+    inner: Arc<Inner>,
+}
+
+impl ScanManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅全部任务的事件；回调在任务线程中同步执行，应尽快返回
+    pub fn subscribe<F>(&self, subscriber: F)
+    where
+        F: Fn(&ScanEvent) + Send + Sync + 'static,
+    {
+        self.inner
+            .subscribers
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(Arc::new(subscriber));
+    }
+
+    /// 登记新任务；同类任务已达并发上限时返回错误（已请求取消的任务不占名额）
+    pub fn begin(&self, kind: TaskKind, label: impl Into<String>) -> AppResult<TaskHandle> {
+        let mut tasks = self.lock();
+        let running = tasks
+            .values()
+            .filter(|t| {
+                t.info.kind == kind
+                    && t.info.status == TaskStatus::Running
+                    && !t.cancel.load(Ordering::Relaxed)
+            })
+            .count();
+        if running >= kind.max_concurrent() {
+            return Err(AppError::validation(
+                "任务",
+                format!(
+                    "{}同时最多运行 {} 个，请等待或取消正在运行的任务",
+                    kind.display_name(),
+                    kind.max_concurrent()
+                ),
+            ));
+        }
+
+        let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let label = label.into();
+        tracing::debug!(id, ?kind, label = %label, "任务开始");
+        tasks.insert(
+            id,
+            TaskEntry {
+                info: TaskInfo {
+                    id,
+                    kind,
+                    label,
+                    status: TaskStatus::Running,
+                    progress: None,
+                    started_at: now(),
+                    finished_at: None,
+                    error: None,
+                },
+                cancel: cancel.clone(),
+            },
+        );
+        Ok(TaskHandle {
+            id,
+            kind,
+            cancel,
+            manager: self.clone(),
+            finished: false,
+        })
+    }
+
+    /// 登记任务并在当前线程执行，结束后按结果更新状态
+    pub fn run<T, F>(&self, kind: TaskKind, label: impl Into<String>, task: F) -> AppResult<T>
+    where
+        F: FnOnce(&TaskHandle) -> AppResult<T>,
+    {
+        self.begin(kind, label)?.run(task)
+    }
+
+    /// 请求取消任务；任务不存在或已结束时返回 false
+    pub fn cancel(&self, id: u64) -> bool {
+        let tasks = self.lock();
+        match tasks.get(&id) {
+            Some(entry) if entry.info.status == TaskStatus::Running => {
+                entry.cancel.store(true, Ordering::Relaxed);
+                tracing::info!(id, kind = ?entry.info.kind, "请求取消任务");
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 取消某类全部运行中的任务，返回是否有任务被取消
+    pub fn cancel_kind(&self, kind: TaskKind) -> bool {
+        let tasks = self.lock();
+        let mut cancelled = false;
+        for entry in tasks
+            .values()
+            .filter(|t| t.info.kind == kind && t.info.status == TaskStatus::Running)
+        {
+            entry.cancel.store(true, Ordering::Relaxed);
+            cancelled = true;
+        }
+        if cancelled {
+            tracing::info!(?kind, "请求取消同类任务");
+        }
+        cancelled
+    }
+
+    /// 任务是否已请求取消
+    pub fn is_cancelled(&self, id: u64) -> bool {
+        self.lock()
+            .get(&id)
+            .is_some_and(|entry| entry.cancel.load(Ordering::Relaxed))
+    }
+
+    /// 是否有该类任务正在运行（已请求取消的不算）
+    pub fn is_running(&self, kind: TaskKind) -> bool {
+        self.lock().values().any(|t| {
+            t.info.kind == kind
+                && t.info.status == TaskStatus::Running
+                && !t.cancel.load(Ordering::Relaxed)
+        })
+    }
+
+    /// 全部任务，运行中的在前，其余按开始时间从新到旧
+    pub fn list(&self) -> Vec<TaskInfo> {
+        let mut tasks: Vec<TaskInfo> = self.lock().values().map(|t| t.info.clone()).collect();
+        tasks.sort_by_key(|t| (t.status != TaskStatus::Running, std::cmp::Reverse(t.id)));
+        tasks
+    }
+
+    pub fn get(&self, id: u64) -> Option<TaskInfo> {
+        self.lock().get(&id).map(|t| t.info.clone())
+    }
+
+    fn set_progress(&self, id: u64, progress: TaskProgress) {
+        let kind = match self.lock().get_mut(&id) {
+            Some(entry) => {
+                entry.info.progress = Some(progress);
+                entry.info.kind
+            }
+            None => return,
+        };
+        self.publish(|| {
+            ScanEvent::Progress(ScanProgressEvent {
+                task_id: id,
+                kind,
+                done: progress.done,
+                total: progress.total,
+            })
+        });
+    }
+
+    fn publish_result(&self, id: u64, kind: TaskKind, item: serde_json::Value) {
+        self.publish(|| {
+            ScanEvent::Result(ScanResultEvent {
+                task_id: id,
+                kind,
+                item,
+            })
+        });
+    }
+
+    /// 向订阅者推送事件；没有订阅者时不构造事件
+    fn publish(&self, event: impl FnOnce() -> ScanEvent) {
+        let subscribers = self
+            .inner
+            .subscribers
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        if subscribers.is_empty() {
+            return;
+        }
+        let event = event();
+        for subscriber in &subscribers {
+            subscriber(&event);
+        }
+    }
+
+    fn finish(&self, id: u64, error: Option<&AppError>) {
+        let done = self.finish_entry(id, error);
+        if let Some(done) = done {
+            self.publish(|| ScanEvent::Done(done));
+        }
+    }
+
+    /// 更新任务的结束状态，返回结束事件；在锁内执行，不回调订阅者
+    fn finish_entry(&self, id: u64, error: Option<&AppError>) -> Option<ScanDoneEvent> {
+        let mut tasks = self.lock();
+        let mut done = None;
+        if let Some(entry) = tasks.get_mut(&id) {
+            let cancelled = entry.cancel.load(Ordering::Relaxed)
+                || matches!(error, Some(AppError::Cancelled { .. }));
+            entry.info.status = match error {
+                _ if cancelled => TaskStatus::Cancelled,
+                Some(_) => TaskStatus::Failed,
+                None => TaskStatus::Completed,
+            };
+            entry.info.error = error.filter(|_| !cancelled).map(ToString::to_string);
+            entry.info.finished_at = Some(now());
+            tracing::debug!(id, status = ?entry.info.status, "任务结束");
+            done = Some(ScanDoneEvent {
+                task_id: id,
+                kind: entry.info.kind,
+                status: entry.info.status,
+                error: entry.info.error.clone(),
+            });
+        }
+
+        // 只保留最近结束的任务
+        let finished: Vec<u64> = tasks
+            .values()
+            .filter(|t| t.info.status != TaskStatus::Running)
+            .map(|t| t.info.id)
+            .collect();
+        for id in finished
+            .iter()
+            .take(finished.len().saturating_sub(MAX_FINISHED_TASKS))
+        {
+            tasks.remove(id);
+        }
+        done
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<u64, TaskEntry>> {
+        self.inner
+            .tasks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// 运行中任务的句柄；未调用 [`TaskHandle::finish`] 就被丢弃时任务记为失败
+pub struct TaskHandle {
+    id: u64,
+    kind: TaskKind,
+    cancel: Arc<AtomicBool>,
+    manager: ScanManager,
+    finished: bool,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn kind(&self) -> TaskKind {
+        self.kind
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// 取消标志，可直接传给 `watch_docker_events` 等以停止标志驱动的循环
+    pub fn cancel_flag(&self) -> &AtomicBool {
+        &self.cancel
+    }
+
+    /// 已取消时返回 [`AppError::Cancelled`]，用于分批执行的任务在批次之间检查
+    pub fn check_cancelled(&self) -> AppResult<()> {
+        if self.is_cancelled() {
+            Err(AppError::cancelled(self.kind.display_name()))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn set_progress(&self, done: usize, total: usize) {
+        self.manager
+            .set_progress(self.id, TaskProgress { done, total });
+    }
+
+    /// 推送一条结果，供前端边扫描边展示
+    pub fn emit_result<T: Serialize>(&self, item: &T) {
+        match serde_json::to_value(item) {
+            Ok(item) => self.manager.publish_result(self.id, self.kind, item),
+            Err(err) => tracing::warn!(id = self.id, error = %err, "任务结果序列化失败"),
+        }
+    }
+
+    /// 执行任务并按结果结束；执行期间被取消时丢弃结果并返回 [`AppError::Cancelled`]
+    pub fn run<T, F>(self, task: F) -> AppResult<T>
+    where
+        F: FnOnce(&TaskHandle) -> AppResult<T>,
+    {
+        let result = task(&self).and_then(|value| {
+            // 不支持中途取消的任务执行完后再丢弃结果
+            self.check_cancelled()?;
+            Ok(value)
+        });
+        self.finish(&result);
+        result
+    }
+
+    /// 按执行结果结束任务
+    pub fn finish<T>(mut self, result: &AppResult<T>) {
+        self.finished = true;
+        self.manager.finish(self.id, result.as_ref().err());
+    }
+}
+
+impl Drop for TaskHandle {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.manager
+                .finish(self.id, Some(&AppError::internal("任务异常结束")));
+        }
+    }
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_records_status_and_progress() {
+        let manager = ScanManager::new();
+        let value = manager
+            .run(TaskKind::PortScan, "10.0.0.5", |task| {
+                task.set_progress(3, 10);
+                Ok(42)
+            })
+            .unwrap();
+        assert_eq!(value, 42);
+
+        let err = manager
+            .run(TaskKind::Ping, "10.0.0.6", |_| {
+                Err::<(), _>(AppError::host_unreachable("10.0.0.6", "timeout"))
+            })
+            .unwrap_err();
+        assert_eq!(err.kind(), crate::PortlyErrorKind::HostUnreachable);
+
+        let tasks = manager.list();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].kind, TaskKind::Ping);
+        assert_eq!(tasks[0].status, TaskStatus::Failed);
+        assert!(tasks[0].error.is_some());
+        assert_eq!(tasks[1].status, TaskStatus::Completed);
+        assert_eq!(tasks[1].progress, Some(TaskProgress { done: 3, total: 10 }));
+    }
+
+    #[test]
+    fn test_begin_enforces_concurrency_limit() {
+        let manager = ScanManager::new();
+        let first = manager
+            .begin(TaskKind::Discovery, "192.168.1.0/24")
+            .unwrap();
+        let err = manager
+            .begin(TaskKind::Discovery, "10.0.0.0/24")
+            .err()
+            .expect("第二个发现任务应被拒绝");
+        assert_eq!(err.kind(), crate::PortlyErrorKind::Validation);
+
+        first.finish(&Ok(()));
+        assert!(manager.begin(TaskKind::Discovery, "10.0.0.0/24").is_ok());
+    }
+
+    #[test]
+    fn test_cancel_marks_task_cancelled() {
+        let manager = ScanManager::new();
+        let handle = manager.begin(TaskKind::Monitor, "本机").unwrap();
+        assert!(manager.is_running(TaskKind::Monitor));
+        assert!(manager.cancel_kind(TaskKind::Monitor));
+        assert!(handle.is_cancelled());
+        assert!(!manager.is_running(TaskKind::Monitor));
+
+        let id = handle.id();
+        handle.finish(&Ok(()));
+        assert_eq!(manager.get(id).unwrap().status, TaskStatus::Cancelled);
+        assert!(!manager.cancel(id));
+
+        let id = manager.begin(TaskKind::Ping, "1.1.1.1").unwrap().id();
+        assert_eq!(manager.get(id).unwrap().status, TaskStatus::Failed);
+    }
+
+    #[test]
+    fn test_subscribers_receive_progress_results_and_done() {
+        let manager = ScanManager::new();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        manager.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let id = manager
+            .run(TaskKind::PortScan, "10.0.0.5", |task| {
+                task.set_progress(1, 2);
+                task.emit_result(&22u16);
+                Ok(task.id())
+            })
+            .unwrap();
+
+        let events = events.lock().unwrap();
+        let names: Vec<&str> = events.iter().map(ScanEvent::name).collect();
+        assert_eq!(names, ["scan://progress", "scan://result", "scan://done"]);
+        match &events[1] {
+            ScanEvent::Result(result) => {
+                assert_eq!(result.task_id, id);
+                assert_eq!(result.item, serde_json::json!(22));
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+        match &events[2] {
+            ScanEvent::Done(done) => assert_eq!(done.status, TaskStatus::Completed),
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+}
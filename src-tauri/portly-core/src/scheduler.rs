@@ -0,0 +1,531 @@
+//! 定时任务
+//!
+//! 用户定义的定时任务保存在数据目录下的 `schedules.json`，支持按间隔、每天或每周的
+//! 固定时间执行本机端口扫描（写入扫描历史）、局域网设备发现和自动导出。
+//! [`Scheduler`] 只负责计算哪些任务到期，执行线程由调用方管理
+
+use crate::app_error::{AppError, AppResult};
+use crate::export::ExportFormat;
+use crate::i18n::{tr_args, Lang, Msg};
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+/// 时间字段的格式，与监控和扫描结果一致
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// 最短执行间隔（分钟）
+const MIN_INTERVAL_MINUTES: u32 = 1;
+
+/// 检查到期任务的间隔
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// 等待下一次检查时检查停止标志的间隔
+const STOP_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// 当前生效的定时任务，首次读取时从磁盘加载
+static CURRENT: RwLock<Option<Vec<ScheduledJob>>> = RwLock::new(None);
+
+/// 定时任务
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// 为空时保存时自动生成
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub schedule: Schedule,
+    pub task: JobTask,
+    /// 最近一次执行的结果
+    #[serde(default)]
+    pub last_run: Option<JobRun>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 执行时间
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    /// 每隔 N 分钟
+    Every { minutes: u32 },
+    /// 每天的固定时间
+    Daily { hour: u32, minute: u32 },
+    /// 每周的固定时间，`weekday` 0 为周一、6 为周日
+    Weekly {
+        weekday: u32,
+        hour: u32,
+        minute: u32,
+    },
+}
+
+/// 任务内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobTask {
+    /// 扫描本机监听端口并写入扫描历史
+    PortScan,
+    /// 发现局域网设备，未指定子网时使用设置中的默认子网或本机网段
+    Discovery {
+        #[serde(default)]
+        subnet: Option<String>,
+    },
+    /// 扫描本机监听端口并导出到默认导出目录
+    Export { format: ExportFormat },
+}
+
+/// 一次执行的结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobRun {
+    pub job_id: String,
+    pub time: String,
+    pub success: bool,
+    pub summary: String,
+}
+
+impl Schedule {
+    /// `from` 之后的下一次执行时间
+    pub fn next_after(&self, from: NaiveDateTime) -> NaiveDateTime {
+        match *self {
+            Schedule::Every { minutes } => from + Duration::minutes(i64::from(minutes.max(1))),
+            Schedule::Daily { hour, minute } => {
+                let at = from.date().and_time(time_of_day(hour, minute));
+                if at > from {
+                    at
+                } else {
+                    at + Duration::days(1)
+                }
+            }
+            Schedule::Weekly {
+                weekday,
+                hour,
+                minute,
+            } => {
+                let today = i64::from(from.weekday().num_days_from_monday());
+                let days = (i64::from(weekday) - today).rem_euclid(7);
+                let at = (from.date() + Duration::days(days)).and_time(time_of_day(hour, minute));
+                if at > from {
+                    at
+                } else {
+                    at + Duration::days(7)
+                }
+            }
+        }
+    }
+
+    fn validate(&self) -> AppResult<()> {
+        let (hour, minute) = match *self {
+            Schedule::Every { minutes } => {
+                if minutes < MIN_INTERVAL_MINUTES {
+                    return Err(AppError::validation("执行间隔", "至少 1 分钟"));
+                }
+                return Ok(());
+            }
+            Schedule::Daily { hour, minute } => (hour, minute),
+            Schedule::Weekly {
+                weekday,
+                hour,
+                minute,
+            } => {
+                if weekday > 6 {
+                    return Err(AppError::validation("星期", "需要 0-6（0 为周一）"));
+                }
+                (hour, minute)
+            }
+        };
+        if hour > 23 || minute > 59 {
+            return Err(AppError::validation("执行时间", "需要 00:00-23:59"));
+        }
+        Ok(())
+    }
+}
+
+fn time_of_day(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or(NaiveTime::MIN)
+}
+
+impl ScheduledJob {
+    pub fn validate(&self) -> AppResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::validation("任务名称", "不能为空"));
+        }
+        self.schedule.validate()?;
+        if let JobTask::Discovery {
+            subnet: Some(subnet),
+        } = &self.task
+        {
+            let valid = crate::network::subnet_host_addresses(subnet, usize::MAX)
+                .is_some_and(|hosts| !hosts.is_empty());
+            if !valid {
+                return Err(AppError::validation(
+                    "子网",
+                    "需要 CIDR 格式，例如 192.168.1.0/24",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 最近一次执行的时间
+    fn last_run_time(&self) -> Option<NaiveDateTime> {
+        self.last_run
+            .as_ref()
+            .and_then(|run| NaiveDateTime::parse_from_str(&run.time, TIME_FORMAT).ok())
+    }
+}
+
+/// 到期计算，记录每个任务的下一次执行时间
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    next: HashMap<String, (Schedule, NaiveDateTime)>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 返回 `now` 时到期的启用任务，并把它们的下一次执行时间顺延
+    ///
+    /// 新出现或修改了执行时间的任务从最近一次执行时间（没有时为 `now`）开始计算
+    pub fn due(&mut self, jobs: &[ScheduledJob], now: NaiveDateTime) -> Vec<ScheduledJob> {
+        self.next
+            .retain(|id, _| jobs.iter().any(|job| job.enabled && job.id == *id));
+
+        let mut due = Vec::new();
+        for job in jobs.iter().filter(|job| job.enabled) {
+            let next = match self.next.get(&job.id) {
+                Some((schedule, next)) if *schedule == job.schedule => *next,
+                _ => job
+                    .schedule
+                    .next_after(job.last_run_time().unwrap_or(now).min(now)),
+            };
+            if next <= now {
+                due.push(job.clone());
+                self.next.insert(
+                    job.id.clone(),
+                    (job.schedule.clone(), job.schedule.next_after(now)),
+                );
+            } else {
+                self.next
+                    .insert(job.id.clone(), (job.schedule.clone(), next));
+            }
+        }
+        due
+    }
+}
+
+/// 运行定时任务调度，阻塞直到 `stop` 被置位；到期任务在当前线程依次执行
+///
+/// 每次检查时重新读取任务列表，增删任务后无需重启
+pub fn watch<F>(stop: &AtomicBool, mut on_run: F)
+where
+    F: FnMut(&JobRun),
+{
+    tracing::info!("定时任务调度已启动");
+    let mut scheduler = Scheduler::new();
+    while !stop.load(Ordering::Relaxed) {
+        let now = chrono::Local::now().naive_local();
+        for job in scheduler.due(&current(), now) {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+            tracing::info!(job = %job.name, "执行定时任务");
+            let run = run_job(&job, crate::settings::current().lang());
+            on_run(&run);
+        }
+
+        let mut waited = std::time::Duration::ZERO;
+        while waited < CHECK_INTERVAL && !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(STOP_POLL_INTERVAL);
+            waited += STOP_POLL_INTERVAL;
+        }
+    }
+    tracing::info!("定时任务调度已停止");
+}
+
+/// 执行一个任务，返回结果摘要
+#[tracing::instrument(level = "info", skip(task))]
+pub fn run_task(task: &JobTask, lang: Lang) -> AppResult<String> {
+    match task {
+        JobTask::PortScan => {
+            let result = crate::core::try_scan_ports(false, false)?;
+            crate::export::save_to_history(&result).map_err(AppError::internal)?;
+            Ok(tr_args(lang, Msg::JobPortScanDone, &[&result.total_ports]))
+        }
+        JobTask::Discovery { subnet } => {
+            let settings = crate::settings::current();
+            let subnet = subnet
+                .clone()
+                .or_else(|| crate::monitor::lan_subnet(&settings))
+                .ok_or_else(|| AppError::validation("子网", "无法确定本机所在网段"))?;
+            let devices =
+                crate::network::try_discover_devices_with(&subnet, |_, _| Ok(()), |_| {})?;
+            let online = devices.iter().filter(|d| d.is_online).count();
+            Ok(tr_args(lang, Msg::JobDiscoveryDone, &[&subnet, &online]))
+        }
+        JobTask::Export { format } => {
+            let result = crate::core::try_scan_ports(false, false)?;
+            let export = crate::export::export_auto(&result.ports, &result, format.clone());
+            if !export.success {
+                return Err(AppError::internal(export.message));
+            }
+            let path = export.path.unwrap_or_default();
+            Ok(tr_args(lang, Msg::JobExportDone, &[&path]))
+        }
+    }
+}
+
+/// 执行任务并把结果记录到任务的 `last_run`
+pub fn run_job(job: &ScheduledJob, lang: Lang) -> JobRun {
+    let (success, summary) = match run_task(&job.task, lang) {
+        Ok(summary) => (true, summary),
+        Err(err) => {
+            tracing::warn!(job = %job.name, error = %err, "定时任务执行失败");
+            (false, err.to_string())
+        }
+    };
+    let run = JobRun {
+        job_id: job.id.clone(),
+        time: chrono::Local::now().format(TIME_FORMAT).to_string(),
+        success,
+        summary,
+    };
+    if let Err(err) = record_run(&run) {
+        tracing::warn!(error = %err, "保存定时任务结果失败");
+    }
+    run
+}
+
+/// 定时任务文件路径
+pub fn schedules_path() -> PathBuf {
+    crate::export::get_data_dir().join("schedules.json")
+}
+
+/// 当前生效的定时任务；文件无法读取时记录日志并视为没有任务
+pub fn current() -> Vec<ScheduledJob> {
+    if let Some(jobs) = CURRENT.read().ok().and_then(|guard| guard.clone()) {
+        return jobs;
+    }
+    let jobs = load_jobs().unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "读取定时任务文件失败");
+        Vec::new()
+    });
+    install(jobs.clone());
+    jobs
+}
+
+/// 读取定时任务文件；文件不存在时返回空列表
+pub fn load_jobs() -> AppResult<Vec<ScheduledJob>> {
+    let path = schedules_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::internal(format!("读取定时任务文件失败: {}", e)))?;
+    serde_json::from_str(&content).map_err(|e| AppError::validation("定时任务文件", e.to_string()))
+}
+
+/// 校验并保存全部定时任务，成功后立即生效
+pub fn save_jobs(jobs: &[ScheduledJob]) -> AppResult<()> {
+    for job in jobs {
+        job.validate()?;
+    }
+    let path = schedules_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建定时任务目录失败: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(jobs)
+        .map_err(|e| AppError::internal(format!("序列化定时任务失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入定时任务文件失败: {}", e)))?;
+    install(jobs.to_vec());
+    tracing::info!(path = %path.display(), count = jobs.len(), "定时任务已保存");
+    Ok(())
+}
+
+/// 新增或按 ID 替换一个任务（保留已有的执行记录），返回保存后的全部任务
+pub fn upsert_job(mut job: ScheduledJob) -> AppResult<Vec<ScheduledJob>> {
+    job.validate()?;
+    let mut jobs = current();
+    if job.id.trim().is_empty() {
+        job.id = generate_id(&jobs);
+    }
+    match jobs.iter_mut().find(|j| j.id == job.id) {
+        Some(existing) => {
+            job.last_run = job.last_run.or_else(|| existing.last_run.take());
+            *existing = job;
+        }
+        None => jobs.push(job),
+    }
+    save_jobs(&jobs)?;
+    Ok(jobs)
+}
+
+/// 按 ID 删除任务，返回保存后的全部任务
+pub fn delete_job(id: &str) -> AppResult<Vec<ScheduledJob>> {
+    let mut jobs = current();
+    let before = jobs.len();
+    jobs.retain(|job| job.id != id);
+    if jobs.len() == before {
+        return Err(AppError::validation("定时任务 ID", format!("不存在：{id}")));
+    }
+    save_jobs(&jobs)?;
+    Ok(jobs)
+}
+
+/// 按 ID 查找任务
+pub fn find_job(id: &str) -> AppResult<ScheduledJob> {
+    current()
+        .into_iter()
+        .find(|job| job.id == id)
+        .ok_or_else(|| AppError::validation("定时任务 ID", format!("不存在：{id}")))
+}
+
+/// 记录执行结果；任务已被删除时忽略
+fn record_run(run: &JobRun) -> AppResult<()> {
+    let mut jobs = current();
+    let Some(job) = jobs.iter_mut().find(|job| job.id == run.job_id) else {
+        return Ok(());
+    };
+    job.last_run = Some(run.clone());
+    save_jobs(&jobs)
+}
+
+fn install(jobs: Vec<ScheduledJob>) {
+    if let Ok(mut guard) = CURRENT.write() {
+        *guard = Some(jobs);
+    }
+}
+
+/// 基于当前时间生成不重复的任务 ID
+fn generate_id(jobs: &[ScheduledJob]) -> String {
+    let mut millis = chrono::Local::now().timestamp_millis();
+    loop {
+        let id = format!("job-{millis}");
+        if jobs.iter().all(|job| job.id != id) {
+            return id;
+        }
+        millis += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, TIME_FORMAT).unwrap()
+    }
+
+    fn job(schedule: Schedule) -> ScheduledJob {
+        ScheduledJob {
+            id: "j1".to_string(),
+            name: "scan".to_string(),
+            enabled: true,
+            schedule,
+            task: JobTask::PortScan,
+            last_run: None,
+        }
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2024-01-03 是周三
+        let from = at("2024-01-03 12:00:00");
+        assert_eq!(
+            Schedule::Every { minutes: 15 }.next_after(from),
+            at("2024-01-03 12:15:00")
+        );
+        assert_eq!(
+            Schedule::Daily { hour: 3, minute: 0 }.next_after(from),
+            at("2024-01-04 03:00:00")
+        );
+        assert_eq!(
+            Schedule::Daily {
+                hour: 18,
+                minute: 30
+            }
+            .next_after(from),
+            at("2024-01-03 18:30:00")
+        );
+        assert_eq!(
+            Schedule::Weekly {
+                weekday: 0,
+                hour: 9,
+                minute: 0
+            }
+            .next_after(from),
+            at("2024-01-08 09:00:00")
+        );
+        assert_eq!(
+            Schedule::Weekly {
+                weekday: 2,
+                hour: 12,
+                minute: 0
+            }
+            .next_after(from),
+            at("2024-01-10 12:00:00")
+        );
+    }
+
+    #[test]
+    fn test_scheduler_due() {
+        let jobs = vec![job(Schedule::Every { minutes: 10 })];
+        let mut scheduler = Scheduler::new();
+        assert!(scheduler.due(&jobs, at("2024-01-03 12:00:00")).is_empty());
+        assert!(scheduler.due(&jobs, at("2024-01-03 12:09:00")).is_empty());
+        assert_eq!(scheduler.due(&jobs, at("2024-01-03 12:10:00")).len(), 1);
+        assert!(scheduler.due(&jobs, at("2024-01-03 12:15:00")).is_empty());
+
+        // 上次执行已超过间隔的任务立即到期
+        let mut overdue = job(Schedule::Every { minutes: 10 });
+        overdue.id = "j2".to_string();
+        overdue.last_run = Some(JobRun {
+            job_id: "j2".to_string(),
+            time: "2024-01-03 11:00:00".to_string(),
+            success: true,
+            summary: String::new(),
+        });
+        let due = scheduler.due(&[overdue], at("2024-01-03 12:15:00"));
+        assert_eq!(due.len(), 1);
+
+        // 停用的任务不会到期
+        let mut disabled = job(Schedule::Every { minutes: 1 });
+        disabled.enabled = false;
+        assert!(scheduler
+            .due(&[disabled], at("2024-01-04 00:00:00"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_job_validate() {
+        assert!(job(Schedule::Every { minutes: 0 }).validate().is_err());
+        assert!(job(Schedule::Daily {
+            hour: 24,
+            minute: 0
+        })
+        .validate()
+        .is_err());
+        assert!(job(Schedule::Weekly {
+            weekday: 7,
+            hour: 1,
+            minute: 0
+        })
+        .validate()
+        .is_err());
+
+        let json = r#"{"name":"nightly","schedule":{"type":"daily","hour":2,"minute":30},"task":{"type":"discovery"}}"#;
+        let parsed: ScheduledJob = serde_json::from_str(json).unwrap();
+        assert!(parsed.enabled);
+        assert_eq!(parsed.task, JobTask::Discovery { subnet: None });
+        assert!(parsed.validate().is_ok());
+    }
+}
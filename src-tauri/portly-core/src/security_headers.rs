@@ -0,0 +1,267 @@
+//! HTTP 安全响应头检查
+//!
+//! 服务探测取得 HTTP 响应后检查 HSTS、CSP、X-Frame-Options、X-Content-Type-Options 和
+//! Cookie 属性，给出 A-F 的简单评级，便于快速排查内网仪表盘。探测使用明文 HTTP，
+//! 响应跳转到 HTTPS 时 HSTS 一项视同通过；Cookie 只检查 HttpOnly 和 SameSite，
+//! 明文站点无法设置 Secure Cookie
+
+use serde::{Deserialize, Serialize};
+
+/// HSTS 有效期不足半年时视为偏弱
+const HSTS_MIN_MAX_AGE: u64 = 15_552_000;
+
+/// 单项检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeaderStatus {
+    Ok,
+    Weak,
+    Missing,
+}
+
+/// 一项响应头检查
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeaderCheck {
+    pub header: String,
+    pub status: HeaderStatus,
+    /// 偏弱或缺失的原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// 评级，A 最好
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HeaderGrade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+/// 一个 HTTP 服务的安全响应头检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityHeaders {
+    pub grade: HeaderGrade,
+    pub checks: Vec<HeaderCheck>,
+}
+
+/// 取出原始 HTTP 响应中的响应头，名称转为小写
+pub(crate) fn parse_headers(response: &str) -> Vec<(String, String)> {
+    response
+        .lines()
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn check(header: &str, status: HeaderStatus, detail: Option<String>) -> HeaderCheck {
+    HeaderCheck {
+        header: header.to_string(),
+        status,
+        detail,
+    }
+}
+
+fn hsts_check(headers: &[(String, String)]) -> HeaderCheck {
+    let value = |name: &str| {
+        headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    };
+    const NAME: &str = "Strict-Transport-Security";
+    if let Some(hsts) = value("strict-transport-security") {
+        let max_age = hsts
+            .split(';')
+            .filter_map(|directive| directive.trim().split_once('='))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("max-age"))
+            .and_then(|(_, age)| age.trim().trim_matches('"').parse::<u64>().ok())
+            .unwrap_or(0);
+        return if max_age >= HSTS_MIN_MAX_AGE {
+            check(NAME, HeaderStatus::Ok, None)
+        } else {
+            check(
+                NAME,
+                HeaderStatus::Weak,
+                Some(format!("max-age={} 不足半年", max_age)),
+            )
+        };
+    }
+    let redirects_to_https = value("location")
+        .is_some_and(|location| location.to_ascii_lowercase().starts_with("https://"));
+    if redirects_to_https {
+        check(NAME, HeaderStatus::Ok, Some("跳转到 HTTPS".to_string()))
+    } else {
+        check(
+            NAME,
+            HeaderStatus::Missing,
+            Some("未启用 HSTS，也没有跳转到 HTTPS".to_string()),
+        )
+    }
+}
+
+/// 检查响应头并评级；没有 Set-Cookie 时 Cookie 一项不参与评级
+pub fn audit_headers(headers: &[(String, String)]) -> SecurityHeaders {
+    let value = |name: &str| {
+        headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.to_ascii_lowercase())
+    };
+    let csp = value("content-security-policy");
+
+    let mut checks = vec![hsts_check(headers)];
+    checks.push(match &csp {
+        None => check("Content-Security-Policy", HeaderStatus::Missing, None),
+        Some(policy) if policy.contains("'unsafe-inline'") || policy.contains("'unsafe-eval'") => {
+            check(
+                "Content-Security-Policy",
+                HeaderStatus::Weak,
+                Some("允许 'unsafe-inline' 或 'unsafe-eval'".to_string()),
+            )
+        }
+        Some(_) => check("Content-Security-Policy", HeaderStatus::Ok, None),
+    });
+    // CSP 的 frame-ancestors 可以替代 X-Frame-Options
+    let frame_ancestors = csp
+        .as_deref()
+        .is_some_and(|policy| policy.contains("frame-ancestors"));
+    checks.push(match value("x-frame-options") {
+        Some(option) if option == "deny" || option == "sameorigin" => {
+            check("X-Frame-Options", HeaderStatus::Ok, None)
+        }
+        _ if frame_ancestors => check(
+            "X-Frame-Options",
+            HeaderStatus::Ok,
+            Some("由 CSP frame-ancestors 限制".to_string()),
+        ),
+        Some(option) => check("X-Frame-Options", HeaderStatus::Weak, Some(option)),
+        None => check(
+            "X-Frame-Options",
+            HeaderStatus::Missing,
+            Some("页面可被其他站点嵌入（点击劫持）".to_string()),
+        ),
+    });
+    checks.push(match value("x-content-type-options") {
+        Some(option) if option == "nosniff" => {
+            check("X-Content-Type-Options", HeaderStatus::Ok, None)
+        }
+        Some(option) => check("X-Content-Type-Options", HeaderStatus::Weak, Some(option)),
+        None => check("X-Content-Type-Options", HeaderStatus::Missing, None),
+    });
+
+    let weak_cookies: Vec<String> = headers
+        .iter()
+        .filter(|(header, _)| header == "set-cookie")
+        .filter_map(|(_, cookie)| {
+            let mut attributes = cookie.split(';').map(str::trim);
+            let name = attributes.next()?.split('=').next()?.to_string();
+            let attributes: Vec<String> = attributes.map(str::to_ascii_lowercase).collect();
+            let has = |flag: &str| {
+                attributes
+                    .iter()
+                    .any(|attribute| attribute.split('=').next() == Some(flag))
+            };
+            let missing: Vec<&str> = [("httponly", "HttpOnly"), ("samesite", "SameSite")]
+                .into_iter()
+                .filter(|(flag, _)| !has(flag))
+                .map(|(_, label)| label)
+                .collect();
+            (!missing.is_empty()).then(|| format!("{} 缺少 {}", name, missing.join("、")))
+        })
+        .collect();
+    if headers.iter().any(|(header, _)| header == "set-cookie") {
+        checks.push(if weak_cookies.is_empty() {
+            check("Set-Cookie", HeaderStatus::Ok, None)
+        } else {
+            check(
+                "Set-Cookie",
+                HeaderStatus::Weak,
+                Some(weak_cookies.join("；")),
+            )
+        });
+    }
+
+    let points: usize = checks
+        .iter()
+        .map(|check| match check.status {
+            HeaderStatus::Ok => 2,
+            HeaderStatus::Weak => 1,
+            HeaderStatus::Missing => 0,
+        })
+        .sum();
+    let grade = match points * 100 / (checks.len() * 2) {
+        90.. => HeaderGrade::A,
+        75.. => HeaderGrade::B,
+        50.. => HeaderGrade::C,
+        25.. => HeaderGrade::D,
+        _ => HeaderGrade::F,
+    };
+    SecurityHeaders { grade, checks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hardened_response_grades_a() {
+        let response = "HTTP/1.1 200 OK\r\n\
+Strict-Transport-Security: max-age=31536000; includeSubDomains\r\n\
+Content-Security-Policy: default-src 'self'; frame-ancestors 'none'\r\n\
+X-Content-Type-Options: nosniff\r\n\
+Set-Cookie: session=abc; Path=/; HttpOnly; SameSite=Lax\r\n\
+\r\n\
+X-Frame-Options: bogus\r\n";
+        let headers = parse_headers(response);
+        assert_eq!(headers.len(), 4);
+        let report = audit_headers(&headers);
+        assert_eq!(report.grade, HeaderGrade::A);
+        assert_eq!(report.checks.len(), 5);
+        assert!(report
+            .checks
+            .iter()
+            .all(|check| check.status == HeaderStatus::Ok));
+
+        // 跳转到 HTTPS 的明文端口
+        let redirect =
+            parse_headers("HTTP/1.1 301 Moved\r\nLocation: https://grafana.lan/\r\n\r\n");
+        assert_eq!(audit_headers(&redirect).checks[0].status, HeaderStatus::Ok);
+    }
+
+    #[test]
+    fn test_bare_dashboard_grades_low() {
+        let response = "HTTP/1.1 200 OK\r\n\
+Content-Type: text/html\r\n\
+Strict-Transport-Security: max-age=300\r\n\
+Content-Security-Policy: script-src 'self' 'unsafe-inline'\r\n\
+Set-Cookie: grafana_session=x; Path=/\r\n\
+Set-Cookie: lang=en; HttpOnly; SameSite=Strict\r\n\r\n";
+        let report = audit_headers(&parse_headers(response));
+        let status: Vec<HeaderStatus> = report.checks.iter().map(|c| c.status).collect();
+        assert_eq!(
+            status,
+            vec![
+                HeaderStatus::Weak,
+                HeaderStatus::Weak,
+                HeaderStatus::Missing,
+                HeaderStatus::Missing,
+                HeaderStatus::Weak,
+            ]
+        );
+        assert_eq!(report.grade, HeaderGrade::D);
+        assert_eq!(
+            report.checks[4].detail.as_deref(),
+            Some("grafana_session 缺少 HttpOnly、SameSite")
+        );
+
+        let empty = audit_headers(&parse_headers("HTTP/1.1 200 OK\r\n\r\n"));
+        assert_eq!(empty.grade, HeaderGrade::F);
+        assert_eq!(empty.checks.len(), 4);
+    }
+}
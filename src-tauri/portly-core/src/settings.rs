@@ -0,0 +1,618 @@
+//! 应用设置模块
+//!
+//! 桌面端的运行参数（扫描超时、并发数、默认子网、后台监控、通知偏好、Webhook、事件脚本、语言）
+//! 保存在数据目录下的 `settings.json`。与 CLI 共用的 `config.toml` 不同，这里的值只影响桌面端：
+//! 未调用 [`init_settings`] 的进程（如 `portly-cli`）始终使用默认值
+
+use crate::app_error::{AppError, AppResult};
+use crate::i18n::Lang;
+use crate::webhook::{WebhookEvent, WebhookPayload};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// 当前生效的设置；未初始化时使用默认值
+static CURRENT: RwLock<Option<Settings>> = RwLock::new(None);
+
+/// 超时设置的上限（毫秒）
+const MAX_TIMEOUT_MS: u64 = 60_000;
+
+/// Ping 扫描并发数上限
+const MAX_PING_CONCURRENCY: usize = 256;
+
+/// 后台监控本机端口的扫描间隔范围（秒）
+const MONITOR_INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 5..=3600;
+
+/// 后台监控局域网的扫描间隔范围（秒）
+const MONITOR_LAN_INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 30..=86_400;
+
+/// 频繁重启统计窗口的范围（秒）
+const MONITOR_FLAP_WINDOW_RANGE: std::ops::RangeInclusive<u64> = 60..=86_400;
+
+/// 证书到期检查的间隔范围（秒）
+const MONITOR_CERT_INTERVAL_RANGE: std::ops::RangeInclusive<u64> = 600..=604_800;
+
+/// 证书到期提醒阈值的范围（天）
+const MONITOR_CERT_WARN_DAYS_RANGE: std::ops::RangeInclusive<u32> = 1..=365;
+
+/// 应用设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub scan: ScanSettings,
+    pub network: NetworkSettings,
+    pub monitor: MonitorSettings,
+    pub notifications: NotificationSettings,
+    /// 后台监控事件的 Webhook 推送目标
+    pub webhooks: Vec<WebhookSettings>,
+    /// 后台监控事件触发的本地脚本
+    pub hooks: Vec<ScriptHookSettings>,
+    /// 反向代理后端映射读取的配置文件和管理接口
+    pub proxy: ProxySettings,
+    /// 端口转发检测使用的 NAT 外反射服务
+    pub reflector: ReflectorSettings,
+    /// 界面语言: zh / en，未设置时按系统语言检测
+    pub lang: Option<String>,
+}
+
+/// 扫描参数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanSettings {
+    /// 常用端口快速扫描的连接超时
+    pub connect_timeout_ms: u64,
+    /// 服务探测前检查端口是否开放的连接超时
+    pub service_probe_timeout_ms: u64,
+    /// 局域网发现时单个主机的 Ping 超时
+    pub ping_timeout_ms: u64,
+    /// 局域网发现时同时 Ping 的主机数
+    pub ping_concurrency: usize,
+    /// 服务探测取得版本后，与内置摘要比对标出停止维护或高危的版本
+    pub version_hints: bool,
+}
+
+impl Default for ScanSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 500,
+            service_probe_timeout_ms: 500,
+            ping_timeout_ms: 500,
+            ping_concurrency: 64,
+            version_hints: true,
+        }
+    }
+}
+
+/// 网络参数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkSettings {
+    /// 默认扫描的子网（CIDR），未设置时使用本机接口所在网段
+    pub default_subnet: Option<String>,
+    /// 手动添加的子网（CIDR），列在本机接口之后供选择
+    pub manual_subnets: Vec<String>,
+}
+
+/// 反向代理后端映射的来源，默认不读取任何来源
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    /// nginx 配置或 Caddyfile 的路径，文件名可以使用 `*` 通配符
+    pub config_paths: Vec<String>,
+    /// Caddy 管理接口地址，例如 `localhost:2019`
+    pub caddy_admin: Option<String>,
+    /// Traefik API 地址，例如 `localhost:8080`
+    pub traefik_api: Option<String>,
+}
+
+/// 端口转发检测的反射服务，一般是在 NAT 外运行的 `portly-cli serve`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReflectorSettings {
+    /// 反射服务地址，例如 `https://vps.example.com:7070`
+    pub url: Option<String>,
+    /// 反射服务的访问令牌
+    pub token: Option<String>,
+}
+
+/// 后台监控参数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MonitorSettings {
+    /// 启动时自动开启后台监控
+    pub enabled: bool,
+    /// 本机监听端口的扫描间隔（秒）
+    pub interval_secs: u64,
+    /// 同时发现局域网设备
+    pub scan_lan: bool,
+    /// 局域网设备发现的间隔（秒）
+    pub lan_interval_secs: u64,
+    /// 关注的端点，连接失败时通知
+    pub endpoints: Vec<MonitoredEndpoint>,
+    /// 监控运行时关闭主窗口只隐藏窗口，监控继续在后台运行
+    pub keep_running_in_background: bool,
+    /// 统计窗口内同一进程重新绑定同一端口达到该次数时提醒频繁重启，0 表示不提醒
+    pub flap_threshold: u32,
+    /// 频繁重启的统计窗口（秒）
+    pub flap_window_secs: u64,
+    /// 定期检查证书到期时间的 TLS 端点
+    pub certificates: Vec<MonitoredEndpoint>,
+    /// 证书到期检查的间隔（秒）
+    pub cert_interval_secs: u64,
+    /// 剩余天数降到这些值以下时各提醒一次，证书过期时总会提醒
+    pub cert_warn_days: Vec<u32>,
+}
+
+impl Default for MonitorSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 30,
+            scan_lan: false,
+            lan_interval_secs: 300,
+            endpoints: Vec::new(),
+            keep_running_in_background: true,
+            flap_threshold: 3,
+            flap_window_secs: 600,
+            certificates: Vec::new(),
+            cert_interval_secs: 21_600,
+            cert_warn_days: vec![30, 7, 1],
+        }
+    }
+}
+
+/// 后台监控关注的 TCP 端点
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MonitoredEndpoint {
+    pub host: String,
+    pub port: u16,
+}
+
+/// 通知偏好
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// 端口开启 / 关闭时通知
+    pub port_changes: bool,
+    /// 局域网出现新设备时通知
+    pub new_devices: bool,
+    /// 关注的端点不可达 / 恢复时通知
+    pub endpoint_down: bool,
+    /// Docker 容器启动 / 停止时通知
+    pub container_events: bool,
+    /// 长时间扫描完成时通知
+    pub scan_complete: bool,
+    /// 关注的证书即将过期 / 已过期时通知
+    pub cert_expiry: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            port_changes: true,
+            new_devices: true,
+            endpoint_down: true,
+            container_events: false,
+            scan_complete: false,
+            cert_expiry: true,
+        }
+    }
+}
+
+/// Webhook 推送目标
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    /// 名称，用于日志和界面显示
+    pub name: String,
+    /// http:// 或 https:// 地址
+    pub url: String,
+    /// 推送的事件，为空时推送全部事件
+    pub events: Vec<WebhookEvent>,
+    /// JSON 负载模板，支持 `{{event}}` `{{title}}` `{{body}}` `{{time}}` `{{port}}` `{{host}}`；
+    /// 为空时发送默认结构
+    pub template: Option<String>,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            name: String::new(),
+            url: String::new(),
+            events: Vec::new(),
+            template: None,
+        }
+    }
+}
+
+/// 事件脚本：事件发生时执行的 shell 命令
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptHookSettings {
+    pub enabled: bool,
+    /// 名称，用于日志和界面显示
+    pub name: String,
+    /// 通过 `sh -c`（Windows 为 `cmd /C`）执行的命令
+    pub command: String,
+    /// 触发的事件，为空时所有事件都执行
+    pub events: Vec<WebhookEvent>,
+}
+
+impl Default for ScriptHookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            name: String::new(),
+            command: String::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// 检查取值范围，保存前调用
+    pub fn validate(&self) -> AppResult<()> {
+        let timeouts = [
+            ("连接超时", self.scan.connect_timeout_ms),
+            ("服务探测超时", self.scan.service_probe_timeout_ms),
+            ("Ping 超时", self.scan.ping_timeout_ms),
+        ];
+        for (field, value) in timeouts {
+            if value == 0 || value > MAX_TIMEOUT_MS {
+                return Err(AppError::validation(field, "应在 1-60000 毫秒之间"));
+            }
+        }
+        if self.scan.ping_concurrency == 0 || self.scan.ping_concurrency > MAX_PING_CONCURRENCY {
+            return Err(AppError::validation("Ping 并发数", "应在 1-256 之间"));
+        }
+        if !MONITOR_INTERVAL_RANGE.contains(&self.monitor.interval_secs) {
+            return Err(AppError::validation("监控间隔", "应在 5-3600 秒之间"));
+        }
+        if !MONITOR_LAN_INTERVAL_RANGE.contains(&self.monitor.lan_interval_secs) {
+            return Err(AppError::validation(
+                "局域网监控间隔",
+                "应在 30-86400 秒之间",
+            ));
+        }
+        if !MONITOR_FLAP_WINDOW_RANGE.contains(&self.monitor.flap_window_secs) {
+            return Err(AppError::validation("重启统计窗口", "应在 60-86400 秒之间"));
+        }
+        for endpoint in &self.monitor.endpoints {
+            if endpoint.host.trim().is_empty() || endpoint.port == 0 {
+                return Err(AppError::validation(
+                    "监控端点",
+                    "需要主机名和 1-65535 的端口",
+                ));
+            }
+        }
+        for endpoint in &self.monitor.certificates {
+            if endpoint.host.trim().is_empty() || endpoint.port == 0 {
+                return Err(AppError::validation(
+                    "证书监控端点",
+                    "需要主机名和 1-65535 的端口",
+                ));
+            }
+        }
+        if !MONITOR_CERT_INTERVAL_RANGE.contains(&self.monitor.cert_interval_secs) {
+            return Err(AppError::validation(
+                "证书检查间隔",
+                "应在 600-604800 秒之间",
+            ));
+        }
+        if self
+            .monitor
+            .cert_warn_days
+            .iter()
+            .any(|days| !MONITOR_CERT_WARN_DAYS_RANGE.contains(days))
+        {
+            return Err(AppError::validation("证书提醒阈值", "应在 1-365 天之间"));
+        }
+        for hook in &self.webhooks {
+            let url = hook.url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(AppError::validation(
+                    "Webhook 地址",
+                    "需要 http:// 或 https:// 开头",
+                ));
+            }
+            let sample = WebhookPayload::new(WebhookEvent::Test, "Portly", "Portly");
+            crate::webhook::render(hook.template.as_deref(), &sample)?;
+        }
+        if self.hooks.iter().any(|hook| hook.command.trim().is_empty()) {
+            return Err(AppError::validation("事件脚本命令", "不能为空"));
+        }
+        if let Some(subnet) = &self.network.default_subnet {
+            let valid = crate::network::subnet_host_addresses(subnet, usize::MAX)
+                .is_some_and(|hosts| !hosts.is_empty());
+            if !valid {
+                return Err(AppError::validation(
+                    "默认子网",
+                    "需要 CIDR 格式，例如 192.168.1.0/24",
+                ));
+            }
+        }
+        if self
+            .network
+            .manual_subnets
+            .iter()
+            .any(|subnet| crate::network::normalize_discover_subnet(subnet).is_none())
+        {
+            return Err(AppError::validation(
+                "手动子网",
+                "需要 CIDR 格式，例如 192.168.1.0/24",
+            ));
+        }
+        if self
+            .proxy
+            .config_paths
+            .iter()
+            .any(|path| path.trim().is_empty())
+        {
+            return Err(AppError::validation("代理配置路径", "不能为空"));
+        }
+        let admins = [&self.proxy.caddy_admin, &self.proxy.traefik_api];
+        for admin in admins.into_iter().flatten() {
+            let address = admin
+                .trim()
+                .trim_start_matches("http://")
+                .trim_end_matches('/');
+            let valid = address
+                .rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+            if !valid {
+                return Err(AppError::validation(
+                    "代理管理接口",
+                    "需要 主机:端口 格式，例如 localhost:2019",
+                ));
+            }
+        }
+        if let Some(url) = &self.reflector.url {
+            let url = url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(AppError::validation(
+                    "端口转发反射服务",
+                    "需要 http:// 或 https:// 开头",
+                ));
+            }
+        }
+        if let Some(lang) = &self.lang {
+            if Lang::from_name(lang).is_none() {
+                return Err(AppError::validation("语言", "仅支持 zh / en"));
+            }
+        }
+        Ok(())
+    }
+
+    /// 生效的语言：设置优先，其次系统语言
+    pub fn lang(&self) -> Lang {
+        self.lang
+            .as_deref()
+            .and_then(Lang::from_name)
+            .unwrap_or_else(Lang::detect)
+    }
+}
+
+/// 设置文件路径
+pub fn settings_path() -> PathBuf {
+    crate::export::get_data_dir().join("settings.json")
+}
+
+/// 当前生效的设置
+pub fn current() -> Settings {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_default()
+}
+
+/// 从磁盘读取设置并设为当前值；文件不存在时使用默认值
+pub fn init_settings() -> AppResult<Settings> {
+    let settings = load_settings()?;
+    install(settings.clone());
+    Ok(settings)
+}
+
+/// 读取设置文件；文件不存在时返回默认值
+pub fn load_settings() -> AppResult<Settings> {
+    let path = settings_path();
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::internal(format!("读取设置文件失败: {}", e)))?;
+    parse_settings(&content)
+}
+
+/// 解析设置 JSON，缺失的字段使用默认值
+pub fn parse_settings(content: &str) -> AppResult<Settings> {
+    let settings: Settings = serde_json::from_str(content)
+        .map_err(|e| AppError::validation("设置文件", e.to_string()))?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+/// 校验并保存设置，成功后立即生效
+pub fn save_settings(settings: &Settings) -> AppResult<()> {
+    settings.validate()?;
+    let path = settings_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::internal(format!("创建设置目录失败: {}", e)))?;
+    }
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| AppError::internal(format!("序列化设置失败: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::internal(format!("写入设置文件失败: {}", e)))?;
+    install(settings.clone());
+    tracing::info!(path = %path.display(), "设置已保存");
+    Ok(())
+}
+
+/// 添加手动子网并保存；子网按网络地址规范化，已存在时不重复添加
+pub fn add_manual_subnet(subnet: &str) -> AppResult<Settings> {
+    let Some(subnet) = crate::network::normalize_discover_subnet(subnet) else {
+        return Err(AppError::validation(
+            "手动子网",
+            "需要 CIDR 格式，例如 192.168.1.0/24",
+        ));
+    };
+    let mut settings = current();
+    if !settings.network.manual_subnets.contains(&subnet) {
+        settings.network.manual_subnets.push(subnet);
+        save_settings(&settings)?;
+    }
+    Ok(settings)
+}
+
+/// 移除手动子网并保存
+pub fn remove_manual_subnet(subnet: &str) -> AppResult<Settings> {
+    let subnet =
+        crate::network::normalize_discover_subnet(subnet).unwrap_or_else(|| subnet.to_string());
+    let mut settings = current();
+    let before = settings.network.manual_subnets.len();
+    settings.network.manual_subnets.retain(|s| *s != subnet);
+    if settings.network.manual_subnets.len() != before {
+        save_settings(&settings)?;
+    }
+    Ok(settings)
+}
+
+/// 添加需要监控到期时间的证书端点并保存，已存在时不重复添加
+pub fn add_certificate_watch(host: &str, port: u16) -> AppResult<Settings> {
+    let endpoint = MonitoredEndpoint {
+        host: host.trim().to_string(),
+        port,
+    };
+    let mut settings = current();
+    if !settings.monitor.certificates.contains(&endpoint) {
+        settings.monitor.certificates.push(endpoint);
+        save_settings(&settings)?;
+    }
+    Ok(settings)
+}
+
+/// 移除证书监控端点并保存
+pub fn remove_certificate_watch(host: &str, port: u16) -> AppResult<Settings> {
+    let mut settings = current();
+    let before = settings.monitor.certificates.len();
+    settings
+        .monitor
+        .certificates
+        .retain(|e| !(e.host == host.trim() && e.port == port));
+    if settings.monitor.certificates.len() != before {
+        save_settings(&settings)?;
+    }
+    Ok(settings)
+}
+
+fn install(settings: Settings) {
+    crate::i18n::set_lang(settings.lang());
+    if let Ok(mut guard) = CURRENT.write() {
+        *guard = Some(settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings_fills_defaults() {
+        let settings = parse_settings(r#"{"scan":{"ping_concurrency":8},"lang":"en"}"#).unwrap();
+        assert_eq!(settings.scan.ping_concurrency, 8);
+        assert_eq!(settings.scan.connect_timeout_ms, 500);
+        assert!(settings.notifications.enabled);
+        assert_eq!(settings.lang(), Lang::En);
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_values() {
+        let mut settings = Settings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.scan.ping_timeout_ms = 0;
+        let err = settings.validate().unwrap_err();
+        assert_eq!(err.to_string(), "Ping 超时 校验失败：应在 1-60000 毫秒之间");
+
+        let mut settings = Settings::default();
+        settings.scan.ping_concurrency = 1000;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.network.default_subnet = Some("192.168.1.1".to_string());
+        assert!(settings.validate().is_err());
+        settings.network.default_subnet = Some("192.168.1.0/24".to_string());
+        assert!(settings.validate().is_ok());
+        settings.network.manual_subnets = vec!["10.0.0.0/8".to_string()];
+        assert!(settings.validate().is_err());
+        settings.network.manual_subnets = vec!["10.0.4.0/22".to_string()];
+        assert!(settings.validate().is_ok());
+
+        settings.lang = Some("fr".to_string());
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.monitor.interval_secs = 1;
+        assert!(settings.validate().is_err());
+        let mut settings = Settings::default();
+        settings.monitor.flap_window_secs = 10;
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.monitor.cert_warn_days = vec![30, 0];
+        assert!(settings.validate().is_err());
+        let mut settings = Settings::default();
+        settings.monitor.cert_interval_secs = 60;
+        assert!(settings.validate().is_err());
+        let mut settings = Settings::default();
+        settings.monitor.certificates.push(MonitoredEndpoint {
+            host: "example.com".to_string(),
+            port: 0,
+        });
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.monitor.endpoints.push(MonitoredEndpoint {
+            host: " ".to_string(),
+            port: 443,
+        });
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.webhooks.push(WebhookSettings {
+            url: "ftp://example.com".to_string(),
+            ..Default::default()
+        });
+        assert!(settings.validate().is_err());
+        settings.webhooks[0].url = "https://ntfy.sh/portly".to_string();
+        assert!(settings.validate().is_ok());
+        settings.webhooks[0].template = Some(r#"{"text": {{title}}}"#.to_string());
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.proxy.config_paths = vec![" ".to_string()];
+        assert!(settings.validate().is_err());
+        settings.proxy.config_paths = vec!["/etc/nginx/sites-enabled/*".to_string()];
+        settings.proxy.caddy_admin = Some("localhost".to_string());
+        assert!(settings.validate().is_err());
+        settings.proxy.caddy_admin = Some("http://localhost:2019/".to_string());
+        assert!(settings.validate().is_ok());
+
+        let mut settings = Settings::default();
+        settings.reflector.url = Some("vps.example.com:7070".to_string());
+        assert!(settings.validate().is_err());
+        settings.reflector.url = Some("https://vps.example.com:7070".to_string());
+        assert!(settings.validate().is_ok());
+
+        let mut settings = Settings::default();
+        settings.hooks.push(ScriptHookSettings::default());
+        assert!(settings.validate().is_err());
+        settings.hooks[0].command = "notify-send \"$PORTLY_TITLE\"".to_string();
+        assert!(settings.validate().is_ok());
+    }
+}
@@ -0,0 +1,331 @@
+//! SMB / NFS 共享枚举
+//!
+//! 对开放了 445（SMB）或 2049（NFS）的主机列出可见的共享：SMB 使用 `smbclient -L -N` 以匿名身份
+//! 列出共享（Windows 上为 `net view /all`，使用当前用户的凭据），NFS 使用 `showmount -e` 读取导出
+//! 列表。匿名可见的磁盘共享和对所有主机开放的 NFS 导出被标为公开，供 [`crate::audit`] 检查
+//! 本机是否无意中公开了文件共享
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_timeout;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// SMB 端口
+pub const SMB_PORT: u16 = 445;
+/// NFS 端口
+pub const NFS_PORT: u16 = 2049;
+
+/// 单个外部命令的超时
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// SMB 共享类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmbShareKind {
+    Disk,
+    Printer,
+    Ipc,
+    Other,
+}
+
+/// 一个 SMB 共享
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SmbShare {
+    pub name: String,
+    pub kind: SmbShareKind,
+    pub comment: String,
+    /// 不需要账号即可看到的磁盘共享；`$` 结尾的管理共享除外
+    pub public: bool,
+}
+
+/// 一条 NFS 导出
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NfsExport {
+    pub path: String,
+    /// 允许挂载的客户端：主机名、网段或 `*`
+    pub clients: Vec<String>,
+    /// 对所有主机开放
+    pub public: bool,
+}
+
+/// 一台主机的共享列表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShareListing {
+    pub host: String,
+    pub smb_shares: Vec<SmbShare>,
+    pub nfs_exports: Vec<NfsExport>,
+    /// 某个协议没能列出时的原因，例如拒绝匿名访问或缺少 smbclient
+    pub errors: Vec<String>,
+}
+
+impl ShareListing {
+    /// 是否存在公开的共享或导出
+    pub fn has_public(&self) -> bool {
+        self.smb_shares.iter().any(|share| share.public)
+            || self.nfs_exports.iter().any(|export| export.public)
+    }
+}
+
+fn smb_share(name: &str, kind: SmbShareKind, comment: &str) -> SmbShare {
+    SmbShare {
+        name: name.to_string(),
+        kind,
+        comment: comment.trim().to_string(),
+        public: kind == SmbShareKind::Disk && !name.ends_with('$'),
+    }
+}
+
+/// 解析 `smbclient -L <host> -N -g` 的输出，例如 `Disk|public|Public files`；
+/// `Server|` / `Workgroup|` 这类浏览列表行被忽略
+fn parse_smbclient(output: &str) -> Vec<SmbShare> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().splitn(3, '|');
+            let kind = match fields.next()? {
+                "Disk" => SmbShareKind::Disk,
+                "Printer" => SmbShareKind::Printer,
+                "IPC" => SmbShareKind::Ipc,
+                _ => return None,
+            };
+            let name = fields.next()?;
+            Some(smb_share(name, kind, fields.next().unwrap_or("")))
+        })
+        .collect()
+}
+
+/// 解析 `net view \\<host> /all`：虚线之后每行为“共享名 类型 用途 注释”，列之间至少两个空格
+fn parse_net_view(output: &str) -> Vec<SmbShare> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line
+                .split("  ")
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .collect();
+            let (name, kind) = (columns.first()?, columns.get(1)?);
+            let kind = match *kind {
+                "Disk" | "磁盘" => SmbShareKind::Disk,
+                "Print" | "打印" => SmbShareKind::Printer,
+                "IPC" => SmbShareKind::Ipc,
+                _ => SmbShareKind::Other,
+            };
+            let comment = columns
+                .get(2..)
+                .map(|rest| rest.join(" "))
+                .unwrap_or_default();
+            Some(smb_share(name, kind, &comment))
+        })
+        .collect()
+}
+
+/// 解析 `showmount -e <host>`：首行为 `Export list for <host>:`，之后每行为“路径 客户端列表”；
+/// Windows 的 showmount 以 `All Machines` 表示不限制
+fn parse_showmount(output: &str) -> Vec<NfsExport> {
+    output
+        .lines()
+        .filter(|line| line.starts_with('/'))
+        .map(|line| {
+            let (path, clients) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let clients = clients.trim();
+            let clients: Vec<String> = if clients.eq_ignore_ascii_case("All Machines") {
+                vec![clients.to_string()]
+            } else {
+                clients
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|client| !client.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            };
+            let public = clients.is_empty()
+                || clients.iter().any(|client| {
+                    matches!(
+                        client.to_ascii_lowercase().as_str(),
+                        "*" | "(everyone)" | "everyone" | "all machines" | "0.0.0.0/0" | "::/0"
+                    )
+                });
+            NfsExport {
+                path: path.to_string(),
+                clients,
+                public,
+            }
+        })
+        .collect()
+}
+
+fn list_smb_shares(host: &str) -> AppResult<Vec<SmbShare>> {
+    if cfg!(target_os = "windows") {
+        let output = run_command_with_timeout(
+            "net",
+            "SMB 共享枚举",
+            |cmd| {
+                cmd.args(["view", &format!(r"\\{}", host), "/all"]);
+            },
+            COMMAND_TIMEOUT,
+        )?;
+        if output.status != 0 {
+            return Err(AppError::command_failed(
+                "net",
+                "SMB 共享枚举",
+                output.status,
+                output.stderr.trim(),
+            ));
+        }
+        return Ok(parse_net_view(&output.stdout));
+    }
+    let output = run_command_with_timeout(
+        "smbclient",
+        "SMB 共享枚举",
+        |cmd| {
+            cmd.args(["-L", host, "-N", "-g"]);
+        },
+        COMMAND_TIMEOUT,
+    )?;
+    // 拒绝匿名访问时 smbclient 返回非零并在 stdout 中给出 NT_STATUS_* 原因
+    if output.status != 0 {
+        let reason = format!("{}\n{}", output.stdout, output.stderr);
+        let reason = reason
+            .split_whitespace()
+            .find(|word| word.starts_with("NT_STATUS_"))
+            .unwrap_or_else(|| output.stderr.trim());
+        return Err(AppError::command_failed(
+            "smbclient",
+            "SMB 共享枚举",
+            output.status,
+            reason,
+        ));
+    }
+    Ok(parse_smbclient(&output.stdout))
+}
+
+fn list_nfs_exports(host: &str) -> AppResult<Vec<NfsExport>> {
+    let output = run_command_with_timeout(
+        "showmount",
+        "NFS 导出枚举",
+        |cmd| {
+            cmd.args(["-e", host]);
+        },
+        COMMAND_TIMEOUT,
+    )?;
+    if output.status != 0 {
+        return Err(AppError::command_failed(
+            "showmount",
+            "NFS 导出枚举",
+            output.status,
+            output.stderr.trim(),
+        ));
+    }
+    Ok(parse_showmount(&output.stdout))
+}
+
+/// 列出 `host` 上的 SMB 共享（`smb`）和 NFS 导出（`nfs`）；某个协议失败时记入 `errors`，
+/// 不影响另一个协议的结果
+#[tracing::instrument(level = "info")]
+pub fn list_shares(host: &str, smb: bool, nfs: bool) -> AppResult<ShareListing> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err(AppError::validation("目标地址", "不能为空"));
+    }
+    // 主机名会作为外部命令的参数，不能被当成选项
+    if host.starts_with('-') {
+        return Err(AppError::validation("目标地址", "格式不正确"));
+    }
+    let mut listing = ShareListing {
+        host: host.to_string(),
+        ..Default::default()
+    };
+    if smb {
+        match list_smb_shares(host) {
+            Ok(shares) => listing.smb_shares = shares,
+            Err(err) => listing.errors.push(format!("SMB: {}", err)),
+        }
+    }
+    if nfs {
+        match list_nfs_exports(host) {
+            Ok(exports) => listing.nfs_exports = exports,
+            Err(err) => listing.errors.push(format!("NFS: {}", err)),
+        }
+    }
+    tracing::info!(
+        smb = listing.smb_shares.len(),
+        nfs = listing.nfs_exports.len(),
+        public = listing.has_public(),
+        "共享枚举完成"
+    );
+    Ok(listing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_smb_share_lists() {
+        let smbclient = "\
+Disk|public|Public files
+Disk|print$|Printer Drivers
+IPC|IPC$|IPC Service (Samba 4.19)
+Printer|hp-laser|HP LaserJet
+Server|NAS|Samba
+Workgroup|WORKGROUP|NAS
+";
+        let shares = parse_smbclient(smbclient);
+        assert_eq!(shares.len(), 4);
+        assert!(shares[0].public);
+        assert_eq!(shares[0].comment, "Public files");
+        assert!(!shares[1].public);
+        assert_eq!(shares[2].kind, SmbShareKind::Ipc);
+        assert!(!shares[3].public);
+
+        let net_view = "\
+Shared resources at \\\\nas
+
+Samba 4.19
+
+Share name  Type  Used as  Comment
+
+-------------------------------------------------------------------------------
+Team Docs   Disk           Shared documents
+ADMIN$      Disk           Remote Admin
+IPC$        IPC            Remote IPC
+The command completed successfully.
+";
+        let shares = parse_net_view(net_view);
+        assert_eq!(shares[0].name, "Team Docs");
+        assert!(shares[0].public);
+        assert_eq!(shares[0].comment, "Shared documents");
+        assert!(!shares[1].public);
+        assert_eq!(shares[2].kind, SmbShareKind::Ipc);
+    }
+
+    #[test]
+    fn test_parse_showmount_flags_world_exports() {
+        let output = "\
+Export list for nas:
+/srv/media   *
+/home        192.168.1.0/24,10.0.0.5
+/backup      (everyone)
+";
+        let exports = parse_showmount(output);
+        assert_eq!(exports.len(), 3);
+        assert!(exports[0].public);
+        assert_eq!(exports[1].clients, vec!["192.168.1.0/24", "10.0.0.5"]);
+        assert!(!exports[1].public);
+        assert!(exports[2].public);
+
+        let windows = "Exports list on nas:\n/share                             All Machines\n";
+        let exports = parse_showmount(windows);
+        assert_eq!(exports[0].clients, vec!["All Machines"]);
+        assert!(exports[0].public);
+
+        let listing = ShareListing {
+            host: "nas".to_string(),
+            nfs_exports: parse_showmount(output),
+            ..Default::default()
+        };
+        assert!(listing.has_public());
+    }
+}
@@ -0,0 +1,625 @@
+//! TLS 指纹：JARM 与 JA3S
+//!
+//! JARM 向目标发送 10 个参数各异的 ClientHello（TLS 版本、密码套件及其顺序、GREASE、ALPN、
+//! 扩展顺序），把每个 ServerHello 选中的套件、版本和扩展拼接后做模糊哈希：前 30 个字符记录
+//! 套件与版本，后 32 个字符是 ALPN 与扩展列表 SHA-256 的前缀。同一 TLS 栈和配置得到同一指纹，
+//! 审计时可用来识别 C2 框架、负载均衡器和特定的 TLS 实现。报文构造与哈希遵循 Salesforce 的
+//! JARM 参考实现。JA3S 取第一个探测的 ServerHello（版本、套件、扩展编号）做 MD5
+
+use crate::app_error::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// 单个探测的连接与读取超时
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 读取 ServerHello 的最大字节数，与参考实现一致
+const MAX_RESPONSE_BYTES: usize = 1484;
+/// 没有任何 TLS 响应时的指纹
+const EMPTY_JARM: &str = "00000000000000000000000000000000000000000000000000000000000000";
+
+/// TLS 指纹结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsFingerprint {
+    pub host: String,
+    pub port: u16,
+    /// 62 位 JARM 指纹；目标不响应 TLS 时全为 0
+    pub jarm: String,
+    /// JA3S 的 MD5，第一个探测没有 ServerHello 时为空
+    pub ja3s: Option<String>,
+    /// 参与 JA3S 哈希的原始字符串：`版本,套件,扩展-扩展`
+    pub ja3s_raw: Option<String>,
+    /// 与公开的已知 JARM 指纹相同时的说明；相同 TLS 栈的正常服务也可能命中
+    pub known_as: Option<String>,
+}
+
+/// 公开报道过的 JARM 指纹
+const KNOWN_JARM: &[(&str, &str)] = &[
+    (
+        "07d14d16d21d21d07c42d41d00041d24a458a375eef0c576d23a7bab9a9fb1",
+        "Cobalt Strike (default Java TLS stack)",
+    ),
+    (
+        "07d14d16d21d21d00042d43d000000aa99ce74e2c6d013c745aa52b5cc042d",
+        "Metasploit SSL listener",
+    ),
+    (
+        "29d21b20d29d29d21c41d21b21b41d494e0df9532e75299f15ba73156cee38",
+        "Merlin C2",
+    ),
+    (
+        "22b22b09b22b22b22b22b22b22b22b352842cd5d6b0278445702035e06875c",
+        "TrickBot",
+    ),
+    (
+        "1dd40d40d00040d1dc1dd40d1dd40d3df2d6a0c2caaa0dc59908f0d3602943",
+        "AsyncRAT",
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TlsVersion {
+    Tls11,
+    Tls12,
+    Tls13,
+}
+
+/// 密码套件、ALPN 和支持版本列表的排列方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Forward,
+    Reverse,
+    TopHalf,
+    BottomHalf,
+    MiddleOut,
+}
+
+/// `supported_versions` 扩展
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Support {
+    None,
+    Tls12,
+    Tls13,
+}
+
+/// 一次探测的 ClientHello 参数
+struct Probe {
+    version: TlsVersion,
+    /// 为假时不提供 TLS 1.3 套件
+    tls13_ciphers: bool,
+    cipher_order: Order,
+    grease: bool,
+    rare_alpn: bool,
+    support: Support,
+    extension_order: Order,
+}
+
+const fn probe(
+    version: TlsVersion,
+    tls13_ciphers: bool,
+    cipher_order: Order,
+    grease: bool,
+    rare_alpn: bool,
+    support: Support,
+    extension_order: Order,
+) -> Probe {
+    Probe {
+        version,
+        tls13_ciphers,
+        cipher_order,
+        grease,
+        rare_alpn,
+        support,
+        extension_order,
+    }
+}
+
+/// 10 个探测，顺序决定指纹中各段的位置
+const PROBES: [Probe; 10] = {
+    use Order::*;
+    use TlsVersion::*;
+    [
+        probe(Tls12, true, Forward, false, false, Support::Tls12, Reverse),
+        probe(Tls12, true, Reverse, false, false, Support::Tls12, Forward),
+        probe(Tls12, true, TopHalf, false, false, Support::None, Forward),
+        probe(Tls12, true, BottomHalf, false, true, Support::None, Forward),
+        probe(Tls12, true, MiddleOut, true, true, Support::None, Reverse),
+        probe(Tls11, true, Forward, false, false, Support::None, Forward),
+        probe(Tls13, true, Forward, false, false, Support::Tls13, Reverse),
+        probe(Tls13, true, Reverse, false, false, Support::Tls13, Forward),
+        probe(Tls13, false, Forward, false, false, Support::Tls13, Forward),
+        probe(Tls13, true, MiddleOut, true, false, Support::Tls13, Reverse),
+    ]
+};
+
+/// ClientHello 中的密码套件（参考实现的 `ALL` 列表顺序）
+const CIPHERS: &[u16] = &[
+    0x0016, 0x0033, 0x0067, 0xc09e, 0xc0a2, 0x009e, 0x0039, 0x006b, 0xc09f, 0xc0a3, 0x009f, 0x0045,
+    0x00be, 0x0088, 0x00c4, 0x009a, 0xc008, 0xc009, 0xc023, 0xc0ac, 0xc0ae, 0xc02b, 0xc00a, 0xc024,
+    0xc0ad, 0xc0af, 0xc02c, 0xc072, 0xc073, 0xcca9, 0x1302, 0x1301, 0xcc14, 0xc007, 0xc012, 0xc013,
+    0xc027, 0xc02f, 0xc014, 0xc028, 0xc030, 0xc060, 0xc061, 0xc076, 0xc077, 0xcca8, 0x1305, 0x1304,
+    0x1303, 0xcc13, 0xc011, 0x000a, 0x002f, 0x003c, 0xc09c, 0xc0a0, 0x009c, 0x0035, 0x003d, 0xc09d,
+    0xc0a1, 0x009d, 0x0041, 0x00ba, 0x0084, 0x00c0, 0x0007, 0x0004, 0x0005,
+];
+
+/// 指纹中套件编号的查找表（按数值排序）
+const CIPHER_INDEX: &[u16] = &[
+    0x0004, 0x0005, 0x0007, 0x000a, 0x0016, 0x002f, 0x0033, 0x0035, 0x0039, 0x003c, 0x003d, 0x0041,
+    0x0045, 0x0067, 0x006b, 0x0084, 0x0088, 0x009a, 0x009c, 0x009d, 0x009e, 0x009f, 0x00ba, 0x00be,
+    0x00c0, 0x00c4, 0xc007, 0xc008, 0xc009, 0xc00a, 0xc011, 0xc012, 0xc013, 0xc014, 0xc023, 0xc024,
+    0xc027, 0xc028, 0xc02b, 0xc02c, 0xc02f, 0xc030, 0xc060, 0xc061, 0xc072, 0xc073, 0xc076, 0xc077,
+    0xc09c, 0xc09d, 0xc09e, 0xc09f, 0xc0a0, 0xc0a1, 0xc0a2, 0xc0a3, 0xc0ac, 0xc0ad, 0xc0ae, 0xc0af,
+    0xcc13, 0xcc14, 0xcca8, 0xcca9, 0x1301, 0x1302, 0x1303, 0x1304, 0x1305,
+];
+
+/// ALPN，按从弱到强排列
+const ALPNS: &[&str] = &[
+    "http/0.9", "http/1.0", "http/1.1", "spdy/1", "spdy/2", "spdy/3", "h2", "h2c", "hq",
+];
+/// 去掉 h2 和 http/1.1 的少见 ALPN
+const RARE_ALPNS: &[&str] = &[
+    "http/0.9", "http/1.0", "spdy/1", "spdy/2", "spdy/3", "h2c", "hq",
+];
+
+/// 计算目标的 JARM 与 JA3S 指纹；10 个探测并行发送
+#[tracing::instrument(level = "debug")]
+pub fn tls_fingerprint(host: &str, port: u16) -> AppResult<TlsFingerprint> {
+    let addr = (host, port)
+        .to_socket_addrs()
+        .map_err(|err| AppError::host_unreachable(host, err.to_string()))?
+        .next()
+        .ok_or_else(|| AppError::host_unreachable(host, "无法解析地址"))?;
+
+    let responses: Vec<Option<Vec<u8>>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = PROBES
+            .iter()
+            .map(|probe| scope.spawn(move || send_probe(addr, &client_hello(probe, host))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().ok().flatten())
+            .collect()
+    });
+    let hellos: Vec<Option<ServerHello>> = responses
+        .iter()
+        .map(|data| data.as_deref().and_then(parse_server_hello))
+        .collect();
+
+    let jarm = jarm_hash(&hellos);
+    let ja3s_raw = hellos[0].as_ref().map(ServerHello::ja3s_raw);
+    let known_as = KNOWN_JARM
+        .iter()
+        .find(|(fingerprint, _)| *fingerprint == jarm)
+        .map(|(_, label)| label.to_string());
+    Ok(TlsFingerprint {
+        host: host.to_string(),
+        port,
+        ja3s: ja3s_raw
+            .as_ref()
+            .map(|raw| format!("{:x}", md5::compute(raw))),
+        ja3s_raw,
+        jarm,
+        known_as,
+    })
+}
+
+/// 发送一个 ClientHello，返回最多 `MAX_RESPONSE_BYTES` 字节的响应；连接失败或无响应时为空
+fn send_probe(addr: SocketAddr, hello: &[u8]) -> Option<Vec<u8>> {
+    let mut stream = TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(PROBE_TIMEOUT)).ok()?;
+    stream.write_all(hello).ok()?;
+
+    let mut data = Vec::new();
+    let mut buffer = [0u8; MAX_RESPONSE_BYTES];
+    // 读到第一条记录完整或达到上限为止
+    while data.len() < MAX_RESPONSE_BYTES {
+        let wanted = match data.get(3..5) {
+            Some(len) => 5 + u16::from_be_bytes([len[0], len[1]]) as usize,
+            None => 5,
+        };
+        if data.len() >= wanted {
+            break;
+        }
+        match stream.read(&mut buffer[..MAX_RESPONSE_BYTES - data.len()]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => data.extend_from_slice(&buffer[..n]),
+        }
+    }
+    let _ = stream.shutdown(std::net::Shutdown::Both);
+    (!data.is_empty()).then_some(data)
+}
+
+/// 按参考实现的规则重排列表
+fn reorder<T: Clone>(items: &[T], order: Order) -> Vec<T> {
+    let len = items.len();
+    match order {
+        Order::Forward => items.to_vec(),
+        Order::Reverse => items.iter().rev().cloned().collect(),
+        Order::BottomHalf => items[len / 2 + len % 2..].to_vec(),
+        Order::TopHalf => {
+            // 奇数个时中间项归入前半部分，放在最前
+            let mut output = if len % 2 == 1 {
+                vec![items[len / 2].clone()]
+            } else {
+                Vec::new()
+            };
+            output.extend(reorder(&reorder(items, Order::Reverse), Order::BottomHalf));
+            output
+        }
+        Order::MiddleOut => {
+            let middle = len / 2;
+            let mut output = Vec::with_capacity(len);
+            if len % 2 == 1 {
+                output.push(items[middle].clone());
+                for i in 1..=middle {
+                    output.push(items[middle + i].clone());
+                    output.push(items[middle - i].clone());
+                }
+            } else {
+                for i in 1..=middle {
+                    output.push(items[middle - 1 + i].clone());
+                    output.push(items[middle - i].clone());
+                }
+            }
+            output
+        }
+    }
+}
+
+/// 构造一个完整的 TLS 记录（含 ClientHello）
+fn client_hello(probe: &Probe, host: &str) -> Vec<u8> {
+    let (record_version, hello_version): ([u8; 2], [u8; 2]) = match probe.version {
+        TlsVersion::Tls11 => ([0x03, 0x02], [0x03, 0x02]),
+        TlsVersion::Tls12 => ([0x03, 0x03], [0x03, 0x03]),
+        TlsVersion::Tls13 => ([0x03, 0x01], [0x03, 0x03]),
+    };
+
+    let mut hello = hello_version.to_vec();
+    hello.extend(random_bytes(32));
+    hello.push(32);
+    hello.extend(random_bytes(32));
+
+    let ciphers: Vec<u16> = CIPHERS
+        .iter()
+        .copied()
+        .filter(|cipher| probe.tls13_ciphers || cipher >> 8 != 0x13)
+        .collect();
+    let mut ciphers = reorder(&ciphers, probe.cipher_order);
+    if probe.grease {
+        ciphers.insert(0, grease_value());
+    }
+    push_u16(&mut hello, ciphers.len() as u16 * 2);
+    for cipher in ciphers {
+        push_u16(&mut hello, cipher);
+    }
+    // 一种压缩方法：null
+    hello.extend([0x01, 0x00]);
+    hello.extend(extensions(probe, host));
+
+    let mut handshake = vec![0x01, 0x00];
+    push_u16(&mut handshake, hello.len() as u16);
+    handshake.extend(hello);
+
+    let mut record = vec![0x16];
+    record.extend(record_version);
+    push_u16(&mut record, handshake.len() as u16);
+    record.extend(handshake);
+    record
+}
+
+fn extensions(probe: &Probe, host: &str) -> Vec<u8> {
+    let mut all = Vec::new();
+    if probe.grease {
+        push_u16(&mut all, grease_value());
+        all.extend([0x00, 0x00]);
+    }
+
+    // server_name
+    let name = host.as_bytes();
+    all.extend([0x00, 0x00]);
+    push_u16(&mut all, name.len() as u16 + 5);
+    push_u16(&mut all, name.len() as u16 + 3);
+    all.push(0x00);
+    push_u16(&mut all, name.len() as u16);
+    all.extend(name);
+
+    // extended_master_secret、max_fragment_length、renegotiation_info、supported_groups、
+    // ec_point_formats、session_ticket
+    all.extend([0x00, 0x17, 0x00, 0x00]);
+    all.extend([0x00, 0x01, 0x00, 0x01, 0x01]);
+    all.extend([0xff, 0x01, 0x00, 0x01, 0x00]);
+    all.extend([
+        0x00, 0x0a, 0x00, 0x0a, 0x00, 0x08, 0x00, 0x1d, 0x00, 0x17, 0x00, 0x18, 0x00, 0x19,
+    ]);
+    all.extend([0x00, 0x0b, 0x00, 0x02, 0x01, 0x00]);
+    all.extend([0x00, 0x23, 0x00, 0x00]);
+
+    // application_layer_protocol_negotiation
+    let alpns = if probe.rare_alpn { RARE_ALPNS } else { ALPNS };
+    let mut alpn_list = Vec::new();
+    for alpn in reorder(alpns, probe.extension_order) {
+        alpn_list.push(alpn.len() as u8);
+        alpn_list.extend(alpn.as_bytes());
+    }
+    all.extend([0x00, 0x10]);
+    push_u16(&mut all, alpn_list.len() as u16 + 2);
+    push_u16(&mut all, alpn_list.len() as u16);
+    all.extend(alpn_list);
+
+    // signature_algorithms
+    all.extend([
+        0x00, 0x0d, 0x00, 0x14, 0x00, 0x12, 0x04, 0x03, 0x08, 0x04, 0x04, 0x01, 0x05, 0x03, 0x08,
+        0x05, 0x05, 0x01, 0x08, 0x06, 0x06, 0x01, 0x02, 0x01,
+    ]);
+
+    // key_share：x25519
+    let mut share = Vec::new();
+    if probe.grease {
+        push_u16(&mut share, grease_value());
+        share.extend([0x00, 0x01, 0x00]);
+    }
+    share.extend([0x00, 0x1d, 0x00, 0x20]);
+    share.extend(random_bytes(32));
+    all.extend([0x00, 0x33]);
+    push_u16(&mut all, share.len() as u16 + 2);
+    push_u16(&mut all, share.len() as u16);
+    all.extend(share);
+
+    // psk_key_exchange_modes
+    all.extend([0x00, 0x2d, 0x00, 0x02, 0x01, 0x01]);
+
+    if probe.version == TlsVersion::Tls13 || probe.support == Support::Tls12 {
+        let versions: &[u16] = if probe.support == Support::Tls12 {
+            &[0x0301, 0x0302, 0x0303]
+        } else {
+            &[0x0301, 0x0302, 0x0303, 0x0304]
+        };
+        let mut list = Vec::new();
+        if probe.grease {
+            push_u16(&mut list, grease_value());
+        }
+        for version in reorder(versions, probe.extension_order) {
+            push_u16(&mut list, version);
+        }
+        all.extend([0x00, 0x2b]);
+        push_u16(&mut all, list.len() as u16 + 1);
+        all.push(list.len() as u8);
+        all.extend(list);
+    }
+
+    let mut output = Vec::with_capacity(all.len() + 2);
+    push_u16(&mut output, all.len() as u16);
+    output.extend(all);
+    output
+}
+
+fn push_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend(value.to_be_bytes());
+}
+
+/// 随机字节；ClientHello 的随机数只需不可预测，不要求密码学强度
+fn random_bytes(len: usize) -> Vec<u8> {
+    let state = RandomState::new();
+    (0..len)
+        .step_by(8)
+        .flat_map(|i| {
+            let mut hasher = state.build_hasher();
+            hasher.write_usize(i);
+            hasher.finish().to_be_bytes()
+        })
+        .take(len)
+        .collect()
+}
+
+/// 随机的 GREASE 值（0x0a0a、0x1a1a … 0xfafa）
+fn grease_value() -> u16 {
+    let nibble = (random_bytes(1)[0] % 16) as u16;
+    let byte = (nibble << 4) | 0x0a;
+    (byte << 8) | byte
+}
+
+/// ServerHello 中参与指纹的字段
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ServerHello {
+    version: u16,
+    cipher: u16,
+    /// 扩展类型与内容，按出现顺序
+    extensions: Vec<(u16, Vec<u8>)>,
+}
+
+impl ServerHello {
+    fn alpn(&self) -> String {
+        self.extensions
+            .iter()
+            .find(|(kind, _)| *kind == 0x0010)
+            .and_then(|(_, value)| value.get(3..))
+            .map(|name| String::from_utf8_lossy(name).to_string())
+            .unwrap_or_default()
+    }
+
+    /// JARM 中的一段：`套件|版本|ALPN|扩展-扩展`
+    fn jarm_component(&self) -> String {
+        let kinds: Vec<String> = self
+            .extensions
+            .iter()
+            .map(|(kind, _)| format!("{:04x}", kind))
+            .collect();
+        format!(
+            "{:04x}|{:04x}|{}|{}",
+            self.cipher,
+            self.version,
+            self.alpn(),
+            kinds.join("-")
+        )
+    }
+
+    fn ja3s_raw(&self) -> String {
+        let kinds: Vec<String> = self
+            .extensions
+            .iter()
+            .map(|(kind, _)| kind.to_string())
+            .collect();
+        format!("{},{},{}", self.version, self.cipher, kinds.join("-"))
+    }
+}
+
+/// 按参考实现的偏移解析 ServerHello；告警或其他响应返回 None
+fn parse_server_hello(data: &[u8]) -> Option<ServerHello> {
+    let u16_at = |at: usize| {
+        data.get(at..at + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+    };
+    if data.first() != Some(&0x16) || data.get(5) != Some(&0x02) {
+        return None;
+    }
+    let counter = *data.get(43)? as usize;
+    let cipher = u16_at(counter + 44)?;
+    let version = u16_at(9)?;
+    let hello_length = u16_at(3)? as usize;
+
+    let mut extensions = Vec::new();
+    let no_extensions = data.get(counter + 47) == Some(&11)
+        || data.get(counter + 50..counter + 53) == Some(&[0x0e, 0xac, 0x0b][..])
+        || data.get(82..85) == Some(&[0x0f, 0xf0, 0x0b][..])
+        || counter + 42 >= hello_length;
+    if !no_extensions {
+        // 扩展不完整时按没有扩展处理
+        let parsed = (|| {
+            let mut count = counter + 49;
+            let end = u16_at(counter + 47)? as usize + count - 1;
+            let mut parsed = Vec::new();
+            while count < end {
+                let kind = u16_at(count)?;
+                let len = u16_at(count + 2)? as usize;
+                let value = data.get(count + 4..count + 4 + len)?.to_vec();
+                parsed.push((kind, value));
+                count += len + 4;
+            }
+            Some(parsed)
+        })();
+        extensions = parsed.unwrap_or_default();
+    }
+    Some(ServerHello {
+        version,
+        cipher,
+        extensions,
+    })
+}
+
+/// 把 10 个探测结果合成 62 位模糊哈希
+fn jarm_hash(hellos: &[Option<ServerHello>]) -> String {
+    if hellos.iter().all(Option::is_none) {
+        return EMPTY_JARM.to_string();
+    }
+    let mut fuzzy = String::with_capacity(62);
+    let mut alpns_and_extensions = String::new();
+    for hello in hellos {
+        let component = hello
+            .as_ref()
+            .map(ServerHello::jarm_component)
+            .unwrap_or_else(|| "|||".to_string());
+        let parts: Vec<&str> = component.split('|').collect();
+        fuzzy.push_str(&cipher_code(parts[0]));
+        fuzzy.push(version_code(parts[1]));
+        alpns_and_extensions.push_str(parts[2]);
+        alpns_and_extensions.push_str(parts[3]);
+    }
+    let digest = Sha256::digest(alpns_and_extensions.as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    fuzzy.push_str(&hex[..32]);
+    fuzzy
+}
+
+/// 套件在查找表中的序号（从 1 开始，两位十六进制）；没有 ServerHello 时为 `00`
+fn cipher_code(cipher: &str) -> String {
+    if cipher.is_empty() {
+        return "00".to_string();
+    }
+    let index = CIPHER_INDEX
+        .iter()
+        .position(|known| format!("{:04x}", known) == cipher)
+        .unwrap_or(CIPHER_INDEX.len());
+    format!("{:02x}", index + 1)
+}
+
+/// 版本末位映射为字母：`0303` → `d`；没有 ServerHello 时为 `0`
+fn version_code(version: &str) -> char {
+    version
+        .chars()
+        .nth(3)
+        .and_then(|c| c.to_digit(10))
+        .and_then(|digit| "abcdef".chars().nth(digit as usize))
+        .unwrap_or('0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorder_matches_reference() {
+        let odd = [1, 2, 3, 4, 5];
+        assert_eq!(reorder(&odd, Order::Reverse), vec![5, 4, 3, 2, 1]);
+        assert_eq!(reorder(&odd, Order::BottomHalf), vec![4, 5]);
+        assert_eq!(reorder(&odd, Order::TopHalf), vec![3, 2, 1]);
+        assert_eq!(reorder(&odd, Order::MiddleOut), vec![3, 4, 2, 5, 1]);
+
+        let even = [1, 2, 3, 4];
+        assert_eq!(reorder(&even, Order::BottomHalf), vec![3, 4]);
+        assert_eq!(reorder(&even, Order::TopHalf), vec![2, 1]);
+        assert_eq!(reorder(&even, Order::MiddleOut), vec![3, 2, 4, 1]);
+    }
+
+    #[test]
+    fn test_client_hello_lengths_are_consistent() {
+        for probe in &PROBES {
+            let record = client_hello(probe, "example.com");
+            assert_eq!(record[0], 0x16);
+            let record_len = u16::from_be_bytes([record[3], record[4]]) as usize;
+            assert_eq!(record.len(), 5 + record_len);
+            assert_eq!(record[5], 0x01);
+            let hello_len = u16::from_be_bytes([record[7], record[8]]) as usize;
+            assert_eq!(record_len, 4 + hello_len);
+        }
+        assert_eq!(CIPHERS.len(), CIPHER_INDEX.len());
+    }
+
+    #[test]
+    fn test_parse_server_hello_and_hash() {
+        // TLS 1.2 ServerHello：32 字节会话 ID，套件 c02f，扩展 ff01 与 ALPN h2
+        let mut body = vec![0x03, 0x03];
+        body.extend([0u8; 32]);
+        body.push(32);
+        body.extend([0u8; 32]);
+        body.extend([0xc0, 0x2f, 0x00]);
+        let extensions = [
+            0xff, 0x01, 0x00, 0x01, 0x00, 0x00, 0x10, 0x00, 0x05, 0x00, 0x03, 0x02, b'h', b'2',
+        ];
+        body.extend((extensions.len() as u16).to_be_bytes());
+        body.extend(extensions);
+        let mut handshake = vec![0x02, 0x00];
+        handshake.extend((body.len() as u16).to_be_bytes());
+        handshake.extend(body);
+        let mut record = vec![0x16, 0x03, 0x03];
+        record.extend((handshake.len() as u16).to_be_bytes());
+        record.extend(handshake);
+
+        let hello = parse_server_hello(&record).unwrap();
+        assert_eq!(hello.jarm_component(), "c02f|0303|h2|ff01-0010");
+        assert_eq!(hello.ja3s_raw(), "771,49199,65281-16");
+        assert!(parse_server_hello(&[0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x28]).is_none());
+
+        let mut hellos = vec![None; 10];
+        assert_eq!(jarm_hash(&hellos), EMPTY_JARM);
+        hellos[0] = Some(hello);
+        let jarm = jarm_hash(&hellos);
+        assert_eq!(jarm.len(), 62);
+        // c02f 在查找表中排第 41 位，版本 0303 记为 d
+        assert!(jarm.starts_with("29d000000"));
+    }
+}
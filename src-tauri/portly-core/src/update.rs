@@ -0,0 +1,201 @@
+//! 版本更新检查
+//!
+//! 查询 GitHub Releases 的最新发布，与当前版本比较，返回下载地址和更新说明。
+//! 只做检查不做下载安装；请求通过系统的 `curl` 发送（与 [`crate::webhook`] 相同）
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_timeout;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// 最新发布的查询地址
+pub const RELEASES_API: &str = "https://api.github.com/repos/zots0127/portly/releases/latest";
+
+/// 查询超时
+const CHECK_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// GitHub 发布信息中用到的字段
+#[derive(Debug, Clone, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// 更新检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    /// 发布页面
+    pub release_url: String,
+    /// 与当前平台匹配的安装包，找不到时为空，可从发布页面下载
+    pub download_url: Option<String>,
+    /// 更新说明（发布正文，Markdown）
+    pub changelog: String,
+    pub published_at: Option<String>,
+}
+
+/// 查询最新发布并与 `current_version` 比较
+#[tracing::instrument(level = "info")]
+pub fn check_update(current_version: &str) -> AppResult<UpdateInfo> {
+    let output = run_command_with_timeout(
+        "curl",
+        "检查更新",
+        |cmd| {
+            cmd.args(["-sS", "--fail", "-L"]);
+            cmd.args(["-H", "Accept: application/vnd.github+json"]);
+            cmd.args(["-H", "User-Agent: portly"]);
+            cmd.args(["--max-time", &CHECK_TIMEOUT.as_secs().to_string()]);
+            cmd.arg(RELEASES_API);
+        },
+        CHECK_TIMEOUT + Duration::from_secs(1),
+    )?;
+    if output.status != 0 {
+        return Err(AppError::command_failed(
+            "curl",
+            "检查更新",
+            output.status,
+            output.stderr.trim(),
+        ));
+    }
+    let release = parse_release(&output.stdout)?;
+    let info = update_info(current_version, release, std::env::consts::OS);
+    tracing::info!(
+        latest = %info.latest_version,
+        update_available = info.update_available,
+        "更新检查完成"
+    );
+    Ok(info)
+}
+
+fn parse_release(json: &str) -> AppResult<Release> {
+    serde_json::from_str(json).map_err(|e| AppError::internal(format!("解析发布信息失败: {}", e)))
+}
+
+fn update_info(current_version: &str, release: Release, os: &str) -> UpdateInfo {
+    let latest_version = release.tag_name.trim_start_matches('v').to_string();
+    let download_url = pick_asset(&release.assets, os).map(|a| a.browser_download_url.clone());
+    UpdateInfo {
+        current_version: current_version.to_string(),
+        update_available: compare_versions(&latest_version, current_version) == Ordering::Greater,
+        latest_version,
+        release_url: release.html_url,
+        download_url,
+        changelog: release.body.unwrap_or_default().trim().to_string(),
+        published_at: release.published_at,
+    }
+}
+
+/// 按平台挑选安装包：文件名含平台名或该平台常见的安装包后缀
+fn pick_asset<'a>(assets: &'a [ReleaseAsset], os: &str) -> Option<&'a ReleaseAsset> {
+    let keywords: &[&str] = match os {
+        "macos" => &["macos", "darwin", ".dmg"],
+        "windows" => &["windows", ".msi", ".exe"],
+        "linux" => &["linux", ".appimage", ".deb"],
+        _ => &[],
+    };
+    keywords.iter().find_map(|keyword| {
+        assets
+            .iter()
+            .find(|asset| asset.name.to_lowercase().contains(keyword))
+    })
+}
+
+/// 比较 `1.2.3` 形式的版本号，可带 `v` 前缀；带预发布后缀（`1.2.3-beta.1`）的版本低于正式版
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (Vec<u64>, Option<&str>) {
+        let version = version.trim().trim_start_matches('v');
+        let (core, pre) = match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        };
+        let core = core.split('+').next().unwrap_or_default();
+        let numbers = core
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect();
+        (numbers, pre)
+    }
+
+    let (a_numbers, a_pre) = split(a);
+    let (b_numbers, b_pre) = split(b);
+    let len = a_numbers.len().max(b_numbers.len());
+    for i in 0..len {
+        let x = a_numbers.get(i).copied().unwrap_or(0);
+        let y = b_numbers.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+    }
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => x.cmp(y),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE: &str = r#"{
+        "tag_name": "v0.3.0",
+        "html_url": "https://github.com/zots0127/portly/releases/tag/v0.3.0",
+        "body": "- Faster scans\n",
+        "published_at": "2024-05-01T08:00:00Z",
+        "assets": [
+            {"name": "Portly_0.3.0_aarch64.dmg", "browser_download_url": "https://example.com/mac.dmg"},
+            {"name": "Portly_0.3.0_x64_en-US.msi", "browser_download_url": "https://example.com/win.msi"},
+            {"name": "portly-cli-linux-x86_64.tar.gz", "browser_download_url": "https://example.com/cli.tar.gz"}
+        ]
+    }"#;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.3.0", "0.2.4"), Ordering::Greater);
+        assert_eq!(compare_versions("v0.2.4", "0.2.4"), Ordering::Equal);
+        assert_eq!(compare_versions("0.2.10", "0.2.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+        assert_eq!(compare_versions("1.0.0-beta.1", "1.0.0"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_update_info_from_release() {
+        let release = parse_release(RELEASE).unwrap();
+        let info = update_info("0.2.4", release.clone(), "macos");
+        assert!(info.update_available);
+        assert_eq!(info.latest_version, "0.3.0");
+        assert_eq!(
+            info.download_url.as_deref(),
+            Some("https://example.com/mac.dmg")
+        );
+        assert_eq!(info.changelog, "- Faster scans");
+
+        let info = update_info("0.3.0", release.clone(), "linux");
+        assert!(!info.update_available);
+        assert_eq!(
+            info.download_url.as_deref(),
+            Some("https://example.com/cli.tar.gz")
+        );
+        assert!(update_info("0.2.4", release, "freebsd")
+            .download_url
+            .is_none());
+        assert!(parse_release("{}").is_err());
+    }
+}
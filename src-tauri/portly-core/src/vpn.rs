@@ -0,0 +1,266 @@
+//! VPN 覆盖网络节点
+//!
+//! 本机接口中的 VPN 接口只说明“连着某个覆盖网络”，网络里还有哪些机器要问 VPN 自己：
+//! Tailscale 读 `tailscale status --json`（即本地 API 的状态），WireGuard 读
+//! `wg show all dump`。读出的节点在设备列表里单独成组，可以像局域网设备一样扫描端口；
+//! 对应工具未安装、服务未运行或权限不足时该来源返回空列表
+
+use crate::command_exec::run_command;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// WireGuard 最近握手在这个时间内视为在线，与 WireGuard 的重新握手间隔（2 分钟）加余量一致
+const WIREGUARD_ONLINE_SECS: u64 = 180;
+
+/// macOS 上 App Store 版 Tailscale 不在 PATH 中，命令行在应用包里
+#[cfg(target_os = "macos")]
+const TAILSCALE_APP_CLI: &str = "/Applications/Tailscale.app/Contents/MacOS/Tailscale";
+
+/// 节点来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VpnProvider {
+    Tailscale,
+    Wireguard,
+}
+
+/// 覆盖网络中的一个节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VpnPeer {
+    pub provider: VpnProvider,
+    /// 所在的本机接口（WireGuard 提供）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface: Option<String>,
+    /// 显示名：Tailscale 主机名，WireGuard 公钥前缀
+    pub name: String,
+    /// 可直接扫描的地址，优先 IPv4
+    pub ip: String,
+    /// 节点的全部隧道地址
+    pub addresses: Vec<String>,
+    /// MagicDNS 名称等可解析的主机名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+    pub online: bool,
+    /// 当前直连的对端地址；经中继转发时为中继名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// 读取所有已知 VPN 的节点：Tailscale 节点按名称排列在前，之后是 WireGuard 节点
+pub fn list_vpn_peers() -> Vec<VpnPeer> {
+    if crate::demo::is_enabled() {
+        return crate::demo::vpn_peers();
+    }
+    let mut peers = tailscale_peers();
+    peers.extend(wireguard_peers());
+    peers
+}
+
+fn tailscale_peers() -> Vec<VpnPeer> {
+    let status = |command: &str| {
+        run_command(command, "Tailscale 状态读取", |cmd| {
+            cmd.args(["status", "--json"]);
+        })
+        .ok()
+        .filter(|output| output.status == 0)
+    };
+    let output = status("tailscale");
+    #[cfg(target_os = "macos")]
+    let output = output.or_else(|| status(TAILSCALE_APP_CLI));
+    let Some(output) = output else {
+        return Vec::new();
+    };
+    parse_tailscale_status(&output.stdout).unwrap_or_else(|err| {
+        tracing::warn!(error = %err, "Tailscale 状态解析失败");
+        Vec::new()
+    })
+}
+
+fn wireguard_peers() -> Vec<VpnPeer> {
+    match run_command("wg", "WireGuard 状态读取", |cmd| {
+        cmd.args(["show", "all", "dump"]);
+    }) {
+        Ok(output) if output.status == 0 => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            parse_wireguard_dump(&output.stdout, now)
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TailscaleStatus {
+    #[serde(default)]
+    backend_state: String,
+    #[serde(default)]
+    peer: HashMap<String, TailscalePeer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TailscalePeer {
+    #[serde(default)]
+    host_name: String,
+    #[serde(default, rename = "DNSName")]
+    dns_name: String,
+    #[serde(default, rename = "OS")]
+    os: String,
+    #[serde(default, rename = "TailscaleIPs")]
+    tailscale_ips: Vec<String>,
+    #[serde(default)]
+    online: bool,
+    #[serde(default)]
+    cur_addr: String,
+    #[serde(default)]
+    relay: String,
+}
+
+/// 解析 `tailscale status --json`；未登录或已停止时没有节点
+fn parse_tailscale_status(stdout: &str) -> serde_json::Result<Vec<VpnPeer>> {
+    let status: TailscaleStatus = serde_json::from_str(stdout)?;
+    if status.backend_state != "Running" {
+        return Ok(Vec::new());
+    }
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    let mut peers: Vec<VpnPeer> = status
+        .peer
+        .into_values()
+        .filter_map(|peer| {
+            let ip = pick_address(&peer.tailscale_ips)?;
+            let endpoint = non_empty(&peer.cur_addr)
+                .or_else(|| non_empty(&peer.relay).map(|relay| format!("DERP {relay}")));
+            Some(VpnPeer {
+                provider: VpnProvider::Tailscale,
+                interface: None,
+                name: non_empty(&peer.host_name).unwrap_or_else(|| ip.clone()),
+                ip,
+                addresses: peer.tailscale_ips,
+                hostname: non_empty(peer.dns_name.trim_end_matches('.')),
+                os: non_empty(&peer.os),
+                online: peer.online,
+                endpoint,
+            })
+        })
+        .collect();
+    peers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(peers)
+}
+
+/// 解析 `wg show all dump`：接口行 5 列，节点行 9 列（接口、公钥、预共享密钥、对端、
+/// 允许的地址、最近握手时间、收、发、保活间隔）
+fn parse_wireguard_dump(stdout: &str, now: u64) -> Vec<VpnPeer> {
+    let mut peers = Vec::new();
+    for line in stdout.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [interface, public_key, _, endpoint, allowed_ips, handshake, _, _, _] = fields[..]
+        else {
+            continue;
+        };
+        // 允许的地址是节点背后的网段，只有单主机前缀才是节点本身的隧道地址
+        let addresses: Vec<String> = allowed_ips
+            .split(',')
+            .filter_map(|cidr| {
+                let (ip, prefix) = cidr.trim().split_once('/')?;
+                matches!(prefix, "32" | "128").then(|| ip.to_string())
+            })
+            .collect();
+        let Some(ip) = pick_address(&addresses) else {
+            continue;
+        };
+        let handshake: u64 = handshake.parse().unwrap_or(0);
+        peers.push(VpnPeer {
+            provider: VpnProvider::Wireguard,
+            interface: Some(interface.to_string()),
+            name: public_key.chars().take(8).collect(),
+            ip,
+            addresses,
+            hostname: None,
+            os: None,
+            online: handshake > 0 && now.saturating_sub(handshake) <= WIREGUARD_ONLINE_SECS,
+            endpoint: (endpoint != "(none)").then(|| endpoint.to_string()),
+        });
+    }
+    peers
+}
+
+/// 优先 IPv4 地址
+fn pick_address(addresses: &[String]) -> Option<String> {
+    addresses
+        .iter()
+        .find(|ip| !ip.contains(':'))
+        .or_else(|| addresses.first())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tailscale_status() {
+        let stdout = r#"{
+            "BackendState": "Running",
+            "Self": {"HostName": "laptop", "TailscaleIPs": ["100.64.0.1"]},
+            "Peer": {
+                "nodekey:aa": {
+                    "HostName": "nas", "DNSName": "nas.tail1234.ts.net.", "OS": "linux",
+                    "TailscaleIPs": ["fd7a:115c:a1e0::2", "100.64.0.2"],
+                    "Online": true, "CurAddr": "203.0.113.7:41641", "Relay": "fra"
+                },
+                "nodekey:bb": {
+                    "HostName": "phone", "DNSName": "phone.tail1234.ts.net.", "OS": "iOS",
+                    "TailscaleIPs": ["100.64.0.3"], "Online": false, "CurAddr": "", "Relay": "sfo"
+                },
+                "nodekey:cc": {"HostName": "expired", "TailscaleIPs": []}
+            }
+        }"#;
+        let peers = parse_tailscale_status(stdout).unwrap();
+        assert_eq!(peers.len(), 2);
+
+        assert_eq!(peers[0].name, "nas");
+        assert_eq!(peers[0].ip, "100.64.0.2");
+        assert_eq!(peers[0].addresses.len(), 2);
+        assert_eq!(peers[0].hostname.as_deref(), Some("nas.tail1234.ts.net"));
+        assert_eq!(peers[0].os.as_deref(), Some("linux"));
+        assert!(peers[0].online);
+        assert_eq!(peers[0].endpoint.as_deref(), Some("203.0.113.7:41641"));
+
+        assert_eq!(peers[1].name, "phone");
+        assert!(!peers[1].online);
+        assert_eq!(peers[1].endpoint.as_deref(), Some("DERP sfo"));
+
+        let stopped = r#"{"BackendState": "Stopped", "Peer": {"nodekey:aa": {"TailscaleIPs": ["100.64.0.2"]}}}"#;
+        assert!(parse_tailscale_status(stopped).unwrap().is_empty());
+        assert!(parse_tailscale_status("not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_wireguard_dump() {
+        let stdout = "wg0\tPRIVATE=\tSERVERPUB=\t51820\toff\n\
+                      wg0\tPEERONE+abc=\t(none)\t198.51.100.4:51820\t10.8.0.2/32,192.168.50.0/24\t1000\t10\t20\t25\n\
+                      wg0\tPEERTWO+def=\t(none)\t(none)\t10.8.0.3/32\t0\t0\t0\toff\n\
+                      wg0\tSITE+ghi=\t(none)\t(none)\t10.20.0.0/16\t1000\t0\t0\toff\n";
+        let peers = parse_wireguard_dump(stdout, 1100);
+        assert_eq!(peers.len(), 2);
+
+        assert_eq!(peers[0].interface.as_deref(), Some("wg0"));
+        assert_eq!(peers[0].name, "PEERONE+");
+        assert_eq!(peers[0].ip, "10.8.0.2");
+        assert_eq!(peers[0].addresses, vec!["10.8.0.2".to_string()]);
+        assert!(peers[0].online);
+        assert_eq!(peers[0].endpoint.as_deref(), Some("198.51.100.4:51820"));
+
+        assert_eq!(peers[1].ip, "10.8.0.3");
+        assert!(!peers[1].online);
+        assert_eq!(peers[1].endpoint, None);
+
+        assert!(!parse_wireguard_dump(stdout, 1000 + WIREGUARD_ONLINE_SECS + 1)[0].online);
+    }
+}
@@ -0,0 +1,284 @@
+//! 服务版本风险提示
+//!
+//! 服务探测从 HTTP `Server` 头或握手横幅（SSH、FTP、SMTP、Redis）中取得产品和版本后，
+//! 与内置的离线摘要比对，标出已停止维护或存在知名高危漏洞的版本。摘要只收录少量常见服务，
+//! 只按上游版本号判断：发行版回移植的补丁不会改变版本号，结果仅作提示
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// 风险类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionRisk {
+    /// 上游已停止维护
+    Eol,
+    /// 存在知名高危漏洞
+    High,
+}
+
+/// 命中的风险提示
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionHint {
+    pub product: String,
+    pub version: String,
+    pub risk: VersionRisk,
+    pub summary: String,
+    /// CVE 编号或公告
+    pub references: Vec<String>,
+}
+
+/// 内置摘要中的一条记录：`affected_from <= version < fixed_in` 时命中
+struct Advisory {
+    product: &'static str,
+    affected_from: Option<&'static str>,
+    fixed_in: &'static str,
+    risk: VersionRisk,
+    summary: &'static str,
+    references: &'static [&'static str],
+}
+
+const ADVISORIES: &[Advisory] = &[
+    Advisory {
+        product: "nginx",
+        affected_from: Some("0.6.18"),
+        fixed_in: "1.20.1",
+        risk: VersionRisk::High,
+        summary: "DNS resolver off-by-one can overwrite memory",
+        references: &["CVE-2021-23017"],
+    },
+    Advisory {
+        product: "Apache",
+        affected_from: None,
+        fixed_in: "2.4",
+        risk: VersionRisk::Eol,
+        summary: "Apache httpd 2.2 and older are end of life",
+        references: &[],
+    },
+    Advisory {
+        product: "Apache",
+        affected_from: Some("2.4.49"),
+        fixed_in: "2.4.51",
+        risk: VersionRisk::High,
+        summary: "Path traversal and remote code execution",
+        references: &["CVE-2021-41773", "CVE-2021-42013"],
+    },
+    Advisory {
+        product: "Apache",
+        affected_from: Some("2.4.0"),
+        fixed_in: "2.4.56",
+        risk: VersionRisk::High,
+        summary: "HTTP request smuggling via mod_proxy",
+        references: &["CVE-2023-25690"],
+    },
+    Advisory {
+        product: "Microsoft-IIS",
+        affected_from: None,
+        fixed_in: "8.5",
+        risk: VersionRisk::Eol,
+        summary: "IIS ships with a Windows Server release that is end of life",
+        references: &[],
+    },
+    Advisory {
+        product: "OpenSSH",
+        affected_from: None,
+        fixed_in: "7.7",
+        risk: VersionRisk::High,
+        summary: "Username enumeration",
+        references: &["CVE-2018-15473"],
+    },
+    Advisory {
+        product: "OpenSSH",
+        affected_from: Some("8.5"),
+        fixed_in: "9.8",
+        risk: VersionRisk::High,
+        summary: "regreSSHion: unauthenticated remote code execution in sshd (glibc)",
+        references: &["CVE-2024-6387"],
+    },
+    Advisory {
+        product: "Redis",
+        affected_from: None,
+        fixed_in: "6.2",
+        risk: VersionRisk::Eol,
+        summary: "Redis 6.0 and older are end of life",
+        references: &[],
+    },
+    Advisory {
+        product: "vsFTPd",
+        affected_from: Some("2.3.4"),
+        fixed_in: "2.3.5",
+        risk: VersionRisk::High,
+        summary: "Backdoored release opens a root shell on port 6200",
+        references: &["CVE-2011-2523"],
+    },
+    Advisory {
+        product: "ProFTPD",
+        affected_from: None,
+        fixed_in: "1.3.6",
+        risk: VersionRisk::High,
+        summary: "mod_copy allows unauthenticated file copy",
+        references: &["CVE-2015-3306", "CVE-2019-12815"],
+    },
+    Advisory {
+        product: "Exim",
+        affected_from: Some("4.87"),
+        fixed_in: "4.92",
+        risk: VersionRisk::High,
+        summary: "Remote command execution in deliver_message",
+        references: &["CVE-2019-10149"],
+    },
+];
+
+/// 从 `Server` 头或握手横幅中找出候选的（产品，版本）对
+///
+/// 支持 `nginx/1.18.0 (Ubuntu)`、`SSH-2.0-OpenSSH_7.2p2 Ubuntu-4`、`(vsFTPd 2.3.4)`、
+/// `mail.example.com ESMTP Exim 4.89` 等形式
+pub fn parse_product_versions(banner: &str) -> Vec<(String, String)> {
+    let banner = banner.trim();
+    // SSH 横幅: SSH-<协议版本>-<软件>_<版本> <注释>
+    if let Some(rest) = banner.strip_prefix("SSH-") {
+        let software = rest.split_once('-').map(|(_, s)| s).unwrap_or(rest);
+        let software = software.split_whitespace().next().unwrap_or_default();
+        return software
+            .split_once('_')
+            .map(|(product, version)| vec![(product.to_string(), version.to_string())])
+            .unwrap_or_default();
+    }
+
+    let tokens: Vec<&str> = banner
+        .split_whitespace()
+        .map(|token| token.trim_matches(|c: char| matches!(c, '(' | ')' | '[' | ']' | ',' | ';')))
+        .filter(|token| !token.is_empty())
+        .collect();
+    let mut pairs = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if let Some((product, version)) = token.split_once('/') {
+            if starts_with_digit(version) {
+                pairs.push((product.to_string(), version.to_string()));
+            }
+        } else if let Some(next) = tokens.get(i + 1).filter(|next| starts_with_digit(next)) {
+            if !starts_with_digit(token) {
+                pairs.push((token.to_string(), next.to_string()));
+            }
+        }
+    }
+    pairs
+}
+
+fn starts_with_digit(text: &str) -> bool {
+    text.chars().next().is_some_and(|c| c.is_ascii_digit())
+}
+
+/// 横幅中各产品版本命中的风险提示
+pub fn hints_for_banner(banner: &str) -> Vec<VersionHint> {
+    parse_product_versions(banner)
+        .into_iter()
+        .flat_map(|(product, version)| hints_for(&product, &version))
+        .collect()
+}
+
+/// 指定产品版本命中的风险提示；产品名不区分大小写
+pub fn hints_for(product: &str, version: &str) -> Vec<VersionHint> {
+    ADVISORIES
+        .iter()
+        .filter(|advisory| advisory.product.eq_ignore_ascii_case(product))
+        .filter(|advisory| {
+            advisory
+                .affected_from
+                .is_none_or(|from| compare_versions(version, from) != Ordering::Less)
+                && compare_versions(version, advisory.fixed_in) == Ordering::Less
+        })
+        .map(|advisory| VersionHint {
+            product: advisory.product.to_string(),
+            version: version.to_string(),
+            risk: advisory.risk,
+            summary: advisory.summary.to_string(),
+            references: advisory.references.iter().map(|r| r.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// 按数字段比较版本号，`7.2p2` 取 `7.2`；缺少的段视为 0
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (version_numbers(a), version_numbers(b));
+    let len = a.len().max(b.len());
+    (0..len)
+        .map(|i| {
+            let x = a.get(i).copied().unwrap_or(0);
+            let y = b.get(i).copied().unwrap_or(0);
+            x.cmp(&y)
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+fn version_numbers(version: &str) -> Vec<u64> {
+    let mut numbers = Vec::new();
+    for part in version.split('.') {
+        let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+        let Ok(number) = digits.parse() else {
+            break;
+        };
+        numbers.push(number);
+        // `7.2p2` 之类带后缀的段之后不再取数字
+        if digits.len() != part.len() {
+            break;
+        }
+    }
+    numbers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_product_versions() {
+        assert_eq!(
+            parse_product_versions("nginx/1.18.0 (Ubuntu)"),
+            vec![("nginx".to_string(), "1.18.0".to_string())]
+        );
+        assert_eq!(
+            parse_product_versions("SSH-2.0-OpenSSH_7.2p2 Ubuntu-4ubuntu2.10"),
+            vec![("OpenSSH".to_string(), "7.2p2".to_string())]
+        );
+        assert_eq!(
+            parse_product_versions("(vsFTPd 2.3.4)"),
+            vec![("vsFTPd".to_string(), "2.3.4".to_string())]
+        );
+        assert_eq!(
+            parse_product_versions("mail.example.com ESMTP Exim 4.89"),
+            vec![("Exim".to_string(), "4.89".to_string())]
+        );
+        assert!(parse_product_versions("cloudflare").is_empty());
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.18.0", "1.20.1"), Ordering::Less);
+        assert_eq!(compare_versions("7.2p2", "7.2"), Ordering::Equal);
+        assert_eq!(compare_versions("9.8p1", "9.8"), Ordering::Equal);
+        assert_eq!(compare_versions("2.4.10", "2.4.9"), Ordering::Greater);
+        assert_eq!(compare_versions("5", "6.2"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_hints_flag_known_risky_versions() {
+        let hints = hints_for_banner("SSH-2.0-OpenSSH_9.3p1 Debian-1");
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].references, vec!["CVE-2024-6387"]);
+
+        let hints = hints_for_banner("Redis/5.0.7");
+        assert_eq!(hints[0].risk, VersionRisk::Eol);
+
+        let apache: Vec<String> = hints_for_banner("Apache/2.4.49 (Unix)")
+            .into_iter()
+            .flat_map(|hint| hint.references)
+            .collect();
+        assert!(apache.contains(&"CVE-2021-41773".to_string()));
+
+        assert!(hints_for_banner("nginx/1.25.3").is_empty());
+        assert!(hints_for_banner("SSH-2.0-OpenSSH_9.8p1").is_empty());
+        assert!(hints_for_banner("vsFTPd 3.0.3").is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+//! Web 应用指纹
+//!
+//! 按响应头、Cookie 名称、页面标题、脚本路径和 `<meta name="generator">` 识别常见的自托管
+//! 应用（Grafana、Jenkins、GitLab、Portainer、Home Assistant 等），服务探测据此把
+//! “Web (3000)” 显示为具体的应用名称。签名只覆盖首页和一次站内跳转能看到的特征
+
+use serde::{Deserialize, Serialize};
+
+/// 识别到的 Web 应用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebApp {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+impl WebApp {
+    /// 带版本号的显示名称
+    pub fn label(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{} {}", self.name, version),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// 一条特征；响应头名称、Cookie 名称和页面内容均按小写比较
+enum Rule {
+    /// 存在该响应头，取其值为版本号
+    HeaderVersion(&'static str),
+    /// 响应头的值包含指定内容
+    Header(&'static str, &'static str),
+    /// Set-Cookie 中存在以此开头的 Cookie
+    Cookie(&'static str),
+    /// 页面中包含指定内容，如标题或脚本路径
+    Body(&'static str),
+}
+
+struct Signature {
+    name: &'static str,
+    rules: &'static [Rule],
+}
+
+/// 内置签名，按顺序匹配，命中任一特征即识别
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        name: "Grafana",
+        rules: &[
+            Rule::Cookie("grafana_session"),
+            Rule::Body("<title>grafana"),
+            Rule::Body("public/build/grafana"),
+        ],
+    },
+    Signature {
+        name: "Jenkins",
+        rules: &[
+            Rule::HeaderVersion("x-jenkins"),
+            Rule::Header("x-hudson", ""),
+            Rule::Body("<title>jenkins"),
+        ],
+    },
+    Signature {
+        name: "GitLab",
+        rules: &[
+            Rule::Cookie("_gitlab_session"),
+            Rule::Header("x-gitlab-meta", ""),
+            Rule::Body("content=\"gitlab\""),
+        ],
+    },
+    Signature {
+        name: "Gitea",
+        rules: &[Rule::Cookie("i_like_gitea"), Rule::Body("powered by gitea")],
+    },
+    Signature {
+        name: "Portainer",
+        rules: &[Rule::Body("<title>portainer"), Rule::Body("portainer.js")],
+    },
+    Signature {
+        name: "Home Assistant",
+        rules: &[
+            Rule::Body("<title>home assistant"),
+            Rule::Body("/frontend_latest/"),
+        ],
+    },
+    Signature {
+        name: "Kibana",
+        rules: &[
+            Rule::HeaderVersion("kbn-version"),
+            Rule::Header("kbn-name", ""),
+        ],
+    },
+    Signature {
+        name: "Prometheus",
+        rules: &[Rule::Body("<title>prometheus")],
+    },
+    Signature {
+        name: "Jupyter",
+        rules: &[
+            Rule::Body("<title>jupyter"),
+            Rule::Body("jupyter-config-data"),
+        ],
+    },
+    Signature {
+        name: "phpMyAdmin",
+        rules: &[Rule::Cookie("phpmyadmin"), Rule::Body("<title>phpmyadmin")],
+    },
+    Signature {
+        name: "Nextcloud",
+        rules: &[
+            Rule::Header("x-nextcloud", ""),
+            Rule::Body("<title>nextcloud"),
+        ],
+    },
+    Signature {
+        name: "Proxmox VE",
+        rules: &[Rule::Body("proxmox virtual environment</title>")],
+    },
+];
+
+/// 取出 `<meta name="generator" content="...">` 的内容
+fn meta_generator(body: &str) -> Option<String> {
+    let lower = body.to_ascii_lowercase();
+    let mut rest = lower.as_str();
+    while let Some(start) = rest.find("<meta") {
+        let tag = &rest[start..];
+        let end = tag.find('>')?;
+        let tag_lower = &tag[..end];
+        if tag_lower.contains("name=\"generator\"") || tag_lower.contains("name='generator'") {
+            // 在原文中取值以保留大小写
+            let offset = lower.len() - rest.len() + start;
+            let original = &body[offset..offset + end];
+            let index = tag_lower.find("content=")? + "content=".len();
+            let quote = original[index..]
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')?;
+            let value = original[index + quote.len_utf8()..].split(quote).next()?;
+            let value = value.trim();
+            return (!value.is_empty()).then(|| value.to_string());
+        }
+        rest = &tag[end..];
+    }
+    None
+}
+
+/// 按响应头（名称为小写）和页面内容识别 Web 应用；没有命中签名时退回 meta generator
+pub fn identify(headers: &[(String, String)], body: &str) -> Option<WebApp> {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let cookie_names: Vec<String> = headers
+        .iter()
+        .filter(|(header, _)| header == "set-cookie")
+        .filter_map(|(_, cookie)| cookie.split('=').next())
+        .map(|name| name.trim().to_ascii_lowercase())
+        .collect();
+    let lower_body = body.to_ascii_lowercase();
+
+    for signature in SIGNATURES {
+        let mut version = None;
+        let matched = signature.rules.iter().any(|rule| match rule {
+            Rule::HeaderVersion(name) => header(name)
+                .inspect(|value| version = Some(value.trim().to_string()).filter(|v| !v.is_empty()))
+                .is_some(),
+            Rule::Header(name, needle) => {
+                header(name).is_some_and(|value| value.to_ascii_lowercase().contains(needle))
+            }
+            Rule::Cookie(prefix) => cookie_names.iter().any(|name| name.starts_with(prefix)),
+            Rule::Body(needle) => lower_body.contains(needle),
+        });
+        if matched {
+            return Some(WebApp {
+                name: signature.name.to_string(),
+                version,
+            });
+        }
+    }
+
+    // 如 “WordPress 6.4.2”、“Hugo 0.120.4”：最后一段以数字开头时视为版本号
+    let generator = meta_generator(body)?;
+    let (name, version) = match generator.rsplit_once(' ') {
+        Some((name, version)) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+            (name.to_string(), Some(version.to_string()))
+        }
+        _ => (generator, None),
+    };
+    Some(WebApp { name, version })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_identify_known_apps() {
+        let jenkins = identify(&headers(&[("x-jenkins", "2.426.1")]), "");
+        assert_eq!(jenkins.unwrap().label(), "Jenkins 2.426.1");
+
+        let gitlab = identify(
+            &headers(&[("set-cookie", "_gitlab_session=abc; path=/; HttpOnly")]),
+            "",
+        );
+        assert_eq!(gitlab.unwrap().name, "GitLab");
+
+        let grafana = identify(
+            &[],
+            "<!DOCTYPE html><html><head><title>Grafana</title><script src=\"public/build/runtime.js\">",
+        );
+        assert_eq!(grafana.unwrap().name, "Grafana");
+
+        let home_assistant = identify(
+            &[],
+            "<html><head><title>Home Assistant</title><script src=\"/frontend_latest/app.js\">",
+        );
+        assert_eq!(home_assistant.unwrap().name, "Home Assistant");
+
+        let portainer = identify(&[], "<title>Portainer</title>");
+        assert_eq!(portainer.unwrap().name, "Portainer");
+
+        assert!(identify(&headers(&[("server", "nginx")]), "<html>hello</html>").is_none());
+    }
+
+    #[test]
+    fn test_meta_generator_fallback() {
+        let body = "<head><meta charset=\"utf-8\"><META Name=\"generator\" content=\"WordPress 6.4.2\" /></head>";
+        let app = identify(&[], body).unwrap();
+        assert_eq!(app.name, "WordPress");
+        assert_eq!(app.version.as_deref(), Some("6.4.2"));
+
+        let app = identify(&[], "<meta name='generator' content='Docusaurus'>").unwrap();
+        assert_eq!(app.label(), "Docusaurus");
+        assert_eq!(
+            meta_generator("<meta name=\"viewport\" content=\"x\">"),
+            None
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Webhook 推送
+//!
+//! 后台监控产生提醒（新端口、端口关闭、新设备、端点不可达 / 恢复、频繁重启）、规则触发或启停时，
+//! 按设置中的 [`WebhookSettings`] 以 JSON POST 到配置的地址，Slack / Discord / ntfy
+//! 等服务可直接接收。负载可用模板定制，模板中的 `{{event}}`、`{{title}}`、`{{body}}`、
+//! `{{time}}`、`{{port}}`、`{{host}}` 会替换为转义后的字符串。请求通过系统的 `curl` 发送，
+//! 以便支持 HTTPS。同样的事件和负载也用于 [`crate::hooks`] 的事件脚本
+
+use crate::app_error::{AppError, AppResult};
+use crate::command_exec::run_command_with_timeout;
+use crate::monitor::{AlertKind, MonitorAlert};
+use crate::rules::RuleHit;
+use crate::settings::WebhookSettings;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// 单次推送的超时
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 可触发 Webhook 的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    NewPort,
+    PortClosed,
+    NewDevice,
+    EndpointDown,
+    EndpointUp,
+    Flapping,
+    CertExpiring,
+    MonitorStarted,
+    MonitorStopped,
+    /// 规则触发，见 [`crate::rules`]
+    RuleTriggered,
+    /// 设置页的“发送测试”，不受事件过滤影响
+    Test,
+}
+
+impl WebhookEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::NewPort => "new_port",
+            WebhookEvent::PortClosed => "port_closed",
+            WebhookEvent::NewDevice => "new_device",
+            WebhookEvent::EndpointDown => "endpoint_down",
+            WebhookEvent::EndpointUp => "endpoint_up",
+            WebhookEvent::Flapping => "flapping",
+            WebhookEvent::CertExpiring => "cert_expiring",
+            WebhookEvent::MonitorStarted => "monitor_started",
+            WebhookEvent::MonitorStopped => "monitor_stopped",
+            WebhookEvent::RuleTriggered => "rule_triggered",
+            WebhookEvent::Test => "test",
+        }
+    }
+}
+
+impl From<AlertKind> for WebhookEvent {
+    fn from(kind: AlertKind) -> Self {
+        match kind {
+            AlertKind::NewPort => WebhookEvent::NewPort,
+            AlertKind::PortClosed => WebhookEvent::PortClosed,
+            AlertKind::NewDevice => WebhookEvent::NewDevice,
+            AlertKind::EndpointDown => WebhookEvent::EndpointDown,
+            AlertKind::EndpointUp => WebhookEvent::EndpointUp,
+            AlertKind::Flapping => WebhookEvent::Flapping,
+            AlertKind::CertExpiring => WebhookEvent::CertExpiring,
+        }
+    }
+}
+
+/// 推送内容，未设置模板时按此结构序列化
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub title: String,
+    pub body: String,
+    pub time: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+impl WebhookPayload {
+    pub fn new(event: WebhookEvent, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            event,
+            title: title.into(),
+            body: body.into(),
+            time: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            port: None,
+            host: None,
+        }
+    }
+
+    pub fn from_alert(alert: &MonitorAlert) -> Self {
+        Self {
+            port: alert.port,
+            host: alert.host.clone(),
+            ..Self::new(alert.kind.into(), &alert.title, &alert.body)
+        }
+    }
+
+    pub fn from_rule_hit(hit: &RuleHit) -> Self {
+        Self {
+            port: hit.port,
+            ..Self::new(WebhookEvent::RuleTriggered, &hit.title, &hit.body)
+        }
+    }
+}
+
+impl WebhookSettings {
+    /// 是否推送该事件；测试事件总是推送
+    pub fn accepts(&self, event: WebhookEvent) -> bool {
+        event == WebhookEvent::Test || self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// 生成请求体：有模板时替换占位符，否则序列化 [`WebhookPayload`]；结果必须是合法 JSON
+pub fn render(template: Option<&str>, payload: &WebhookPayload) -> AppResult<String> {
+    let Some(template) = template.filter(|t| !t.trim().is_empty()) else {
+        return serde_json::to_string(payload)
+            .map_err(|err| AppError::internal(format!("Webhook 负载序列化失败：{err}")));
+    };
+
+    let port = payload
+        .port
+        .map(|port| port.to_string())
+        .unwrap_or_default();
+    let body = [
+        ("{{event}}", payload.event.name()),
+        ("{{title}}", payload.title.as_str()),
+        ("{{body}}", payload.body.as_str()),
+        ("{{time}}", payload.time.as_str()),
+        ("{{port}}", port.as_str()),
+        ("{{host}}", payload.host.as_deref().unwrap_or_default()),
+    ]
+    .into_iter()
+    .fold(template.to_string(), |body, (placeholder, value)| {
+        body.replace(placeholder, &json_escape(value))
+    });
+    serde_json::from_str::<serde_json::Value>(&body).map_err(|err| {
+        AppError::validation("Webhook 模板", format!("替换后不是合法 JSON：{err}"))
+    })?;
+    Ok(body)
+}
+
+/// 转义为 JSON 字符串内容（不含两侧引号），模板中通常写作 `"{{title}}"`
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 发送一次推送
+#[tracing::instrument(level = "debug", skip(hook, payload), fields(name = %hook.name, event = payload.event.name()))]
+pub fn send(hook: &WebhookSettings, payload: &WebhookPayload) -> AppResult<()> {
+    let body = render(hook.template.as_deref(), payload)?;
+    let output = run_command_with_timeout(
+        "curl",
+        "Webhook 推送",
+        |cmd| {
+            cmd.args(["-sS", "--fail", "-X", "POST"]);
+            cmd.args(["-H", "Content-Type: application/json"]);
+            cmd.args(["--max-time", &SEND_TIMEOUT.as_secs().to_string()]);
+            cmd.args(["--data-binary", &body]);
+            cmd.arg(&hook.url);
+        },
+        SEND_TIMEOUT + Duration::from_secs(1),
+    )?;
+    if output.status != 0 {
+        return Err(AppError::command_failed(
+            "curl",
+            "Webhook 推送",
+            output.status,
+            output.stderr.trim(),
+        ));
+    }
+    tracing::debug!("Webhook 推送成功");
+    Ok(())
+}
+
+/// 在后台线程把事件推送给所有启用且订阅了该事件的 Webhook，失败只记录日志
+pub fn dispatch(hooks: &[WebhookSettings], payloads: Vec<WebhookPayload>) {
+    let jobs: Vec<(WebhookSettings, WebhookPayload)> = hooks
+        .iter()
+        .filter(|hook| hook.enabled)
+        .flat_map(|hook| {
+            payloads
+                .iter()
+                .filter(|payload| hook.accepts(payload.event))
+                .map(|payload| (hook.clone(), payload.clone()))
+        })
+        .collect();
+    if jobs.is_empty() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for (hook, payload) in jobs {
+            if let Err(err) = send(&hook, &payload) {
+                tracing::warn!(name = %hook.name, event = payload.event.name(), error = %err, "Webhook 推送失败");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload {
+            event: WebhookEvent::NewPort,
+            title: "New listening port 3000".to_string(),
+            body: "node \"dev\" (PID 42)".to_string(),
+            time: "2024-01-01 12:00:00".to_string(),
+            port: Some(3000),
+            host: None,
+        }
+    }
+
+    #[test]
+    fn test_render_default_and_template() {
+        let body: serde_json::Value =
+            serde_json::from_str(&render(None, &payload()).unwrap()).unwrap();
+        assert_eq!(body["event"], "new_port");
+        assert_eq!(body["title"], "New listening port 3000");
+        assert_eq!(body["port"], 3000);
+        assert!(body.get("host").is_none());
+
+        let slack = render(Some(r#"{"text": "{{title}}: {{body}}"}"#), &payload()).unwrap();
+        let slack: serde_json::Value = serde_json::from_str(&slack).unwrap();
+        assert_eq!(
+            slack["text"],
+            "New listening port 3000: node \"dev\" (PID 42)"
+        );
+
+        let err = render(Some("{{title}}"), &payload()).unwrap_err();
+        assert_eq!(err.kind(), crate::PortlyErrorKind::Validation);
+    }
+
+    #[test]
+    fn test_accepts_filters_events() {
+        let mut hook = WebhookSettings::default();
+        assert!(hook.accepts(WebhookEvent::NewDevice));
+
+        hook.events = vec![WebhookEvent::NewPort];
+        assert!(hook.accepts(WebhookEvent::NewPort));
+        assert!(!hook.accepts(WebhookEvent::MonitorStopped));
+        assert!(hook.accepts(WebhookEvent::Test));
+    }
+}
@@ -0,0 +1,301 @@
+//! WebSocket 推送
+//!
+//! 守护进程 `GET /api/events` 升级为 WebSocket 后，把后台监控的事件（快照、端口变化和
+//! 设备提醒、规则触发）推送给远程仪表盘，与桌面端收到的事件相同。只实现 RFC 6455 中
+//! 服务端推送需要的部分：握手、发送文本帧 / Ping / Close、读取并丢弃客户端帧。
+//! 与 REST API 一样只用标准库，握手需要的 SHA-1 和 Base64 在这里实现
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+/// 握手时拼接在客户端密钥后的固定 GUID
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+/// 客户端帧大小上限，仪表盘只会发送 Close / Pong 等控制帧
+const MAX_CLIENT_FRAME: u64 = 64 * 1024;
+
+pub const OPCODE_TEXT: u8 = 0x1;
+pub const OPCODE_CLOSE: u8 = 0x8;
+pub const OPCODE_PING: u8 = 0x9;
+
+/// 推送给客户端的事件，名称与桌面端一致
+#[derive(Debug, Serialize)]
+struct Envelope<'a, T: Serialize> {
+    event: &'a str,
+    payload: &'a T,
+}
+
+/// 事件分发：每个 WebSocket 连接订阅一个通道，断开的连接在下次发布时移除
+#[derive(Debug, Default)]
+pub struct EventHub {
+    subscribers: Mutex<Vec<Sender<String>>>,
+}
+
+impl EventHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 订阅之后发布的事件，每条为一段 JSON 文本
+    pub fn subscribe(&self) -> Receiver<String> {
+        let (sender, receiver) = channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(sender);
+        }
+        receiver
+    }
+
+    /// 向全部订阅者发布事件
+    pub fn publish<T: Serialize>(&self, event: &str, payload: &T) {
+        let Some(message) = event_message(event, payload) else {
+            return;
+        };
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.retain(|sender| sender.send(message.clone()).is_ok());
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().map(|s| s.len()).unwrap_or(0)
+    }
+
+    /// 断开全部订阅，连接线程随后结束
+    pub fn close(&self) {
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.clear();
+        }
+    }
+}
+
+/// 事件消息 `{"event": name, "payload": ...}`
+pub fn event_message<T: Serialize>(event: &str, payload: &T) -> Option<String> {
+    serde_json::to_string(&Envelope { event, payload }).ok()
+}
+
+/// 请求头（名称为小写）是否为 WebSocket 升级请求
+pub fn is_upgrade(headers: &HashMap<String, String>) -> bool {
+    let has_token = |name: &str, token: &str| {
+        headers.get(name).is_some_and(|value| {
+            value
+                .split(',')
+                .any(|part| part.trim().eq_ignore_ascii_case(token))
+        })
+    };
+    has_token("upgrade", "websocket")
+        && has_token("connection", "upgrade")
+        && headers.contains_key("sec-websocket-key")
+}
+
+/// 握手响应中的 `Sec-WebSocket-Accept`
+pub fn accept_key(client_key: &str) -> String {
+    base64(&sha1(
+        format!("{}{}", client_key.trim(), HANDSHAKE_GUID).as_bytes(),
+    ))
+}
+
+/// 写出 101 握手响应
+pub fn write_handshake(stream: &mut impl Write, client_key: &str) -> std::io::Result<()> {
+    let head = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(client_key)
+    );
+    stream.write_all(head.as_bytes())?;
+    stream.flush()
+}
+
+/// 写出一个不分片、不加掩码的服务端帧
+pub fn write_frame(stream: &mut impl Write, opcode: u8, payload: &[u8]) -> std::io::Result<()> {
+    let mut head = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => head.push(len as u8),
+        len @ 126..=0xFFFF => {
+            head.push(126);
+            head.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            head.push(127);
+            head.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    stream.write_all(&head)?;
+    stream.write_all(payload)?;
+    stream.flush()
+}
+
+/// 读取一个帧，返回操作码和去掉掩码后的负载
+pub fn read_frame(stream: &mut impl Read) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut head = [0u8; 2];
+    stream.read_exact(&mut head)?;
+    let opcode = head[0] & 0x0F;
+    let masked = head[1] & 0x80 != 0;
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut ext = [0u8; 2];
+            stream.read_exact(&mut ext)?;
+            u16::from_be_bytes(ext) as u64
+        }
+        127 => {
+            let mut ext = [0u8; 8];
+            stream.read_exact(&mut ext)?;
+            u64::from_be_bytes(ext)
+        }
+        len => len as u64,
+    };
+    if len > MAX_CLIENT_FRAME {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("WebSocket 帧超过 {MAX_CLIENT_FRAME} 字节"),
+        ));
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        stream.read_exact(&mut mask)?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    stream.read_exact(&mut payload)?;
+    if masked {
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+    }
+    Ok((opcode, payload))
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key_matches_rfc_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"f"), "Zg==");
+
+        let headers: HashMap<String, String> = [
+            ("upgrade", "websocket"),
+            ("connection", "keep-alive, Upgrade"),
+            ("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        assert!(is_upgrade(&headers));
+        assert!(!is_upgrade(&HashMap::new()));
+    }
+
+    #[test]
+    fn test_frames_round_trip() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, OPCODE_TEXT, b"hello").unwrap();
+        assert_eq!(&buffer[..2], &[0x81, 5]);
+        let long = vec![b'x'; 300];
+        write_frame(&mut buffer, OPCODE_TEXT, &long).unwrap();
+
+        let mut reader = std::io::Cursor::new(buffer);
+        assert_eq!(
+            read_frame(&mut reader).unwrap(),
+            (OPCODE_TEXT, b"hello".to_vec())
+        );
+        assert_eq!(read_frame(&mut reader).unwrap(), (OPCODE_TEXT, long));
+
+        // 客户端帧带掩码
+        let mask = [1u8, 2, 3, 4];
+        let mut frame = vec![0x88, 0x80 | 2];
+        frame.extend_from_slice(&mask);
+        frame.extend([0x03 ^ mask[0], 0xE8 ^ mask[1]]);
+        let (opcode, payload) = read_frame(&mut std::io::Cursor::new(frame)).unwrap();
+        assert_eq!((opcode, payload), (OPCODE_CLOSE, vec![0x03, 0xE8]));
+    }
+
+    #[test]
+    fn test_hub_drops_closed_subscribers() {
+        let hub = EventHub::new();
+        let first = hub.subscribe();
+        let second = hub.subscribe();
+        drop(second);
+        hub.publish("monitor-alert", &serde_json::json!({"port": 8080}));
+        assert_eq!(hub.subscriber_count(), 1);
+        assert_eq!(
+            first.try_recv().unwrap(),
+            r#"{"event":"monitor-alert","payload":{"port":8080}}"#
+        );
+        hub.close();
+        assert!(first.recv().is_err());
+    }
+}
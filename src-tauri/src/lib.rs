@@ -1,28 +1,63 @@
 //! Portly - Tauri GUI 入口
 
-mod app_error;
-mod command_exec;
-mod advanced_scan;
-mod core;
-mod dns;
-mod docker;
-mod export;
-mod network;
-mod process;
-mod ssl;
-mod whois;
-
-pub use core::*;
-pub use dns::*;
-pub use docker::*;
-pub use export::*;
-pub use network::*;
-pub use process::*;
-pub use whois::*;
+pub use portly_core::*;
+pub use portly_core::{
+    advanced_scan, arp, audit, backup, cache, capabilities, command_exec, config, core, demo,
+    dependencies, dhcp, dns, docker, export, exposure, firewall, hooks, host_pattern, iface_stats,
+    inventory, known_ports, kube, latency, lldp, logging, monitor, network, ntp, ownership,
+    ping_compare, plugins, port_forward, port_watch, printer, process, proxy, rules, scan_manager,
+    scheduler, settings, shares, ssl, tls_fingerprint, update, vpn, webhook, whois,
+};
+
+mod tray;
 
 use tokio::task::spawn_blocking;
+use cache::{CacheKind, CacheStats, LookupCache};
+use scan_manager::{ScanManager, TaskHandle, TaskKind};
 use std::net::IpAddr;
-use crate::app_error::{AppError, AppResult};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{Emitter, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+use tauri_plugin_notification::NotificationExt;
+use webhook::{WebhookEvent, WebhookPayload};
+
+/// 推送给前端的 Docker 容器事件名
+const DOCKER_EVENT: &str = "docker-event";
+
+/// 推送给前端的导出进度事件名
+const EXPORT_PROGRESS_EVENT: &str = "export-progress";
+
+/// 设置保存后推送给前端的事件名（负载为新的设置）
+const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+/// 后台监控每轮结束后推送给前端的事件名（负载为监控快照）
+const MONITOR_UPDATE_EVENT: &str = "monitor-update";
+
+/// 端口实时监听发现新开启端口时推送给前端的事件名（负载为端口列表）
+const PORT_OPENED_EVENT: &str = "port-opened";
+
+/// 端口实时监听发现端口关闭时推送给前端的事件名（负载为端口列表）
+const PORT_CLOSED_EVENT: &str = "port-closed";
+
+/// 端口实时监听发现端口换了进程时推送给前端的事件名（负载为变化列表）
+const PROCESS_CHANGED_EVENT: &str = "process-changed";
+
+/// 单个端口刷新后推送给前端的事件名（负载为该端口的刷新结果）
+const PORT_REFRESHED_EVENT: &str = "port-refreshed";
+
+/// 单台设备刷新后推送给前端的事件名（负载为该设备）
+const DEVICE_REFRESHED_EVENT: &str = "device-refreshed";
+
+/// 定时任务执行后推送给前端的事件名（负载为执行结果）
+const SCHEDULER_RUN_EVENT: &str = "scheduler-run";
+
+/// 网卡流量采样每次采样后推送给前端的事件名（负载为各网卡的吞吐量）
+const INTERFACE_STATS_EVENT: &str = "interface-stats";
+
+/// 等待扫描任务时检查取消请求的间隔
+const TASK_CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 async fn run_blocking_with_context<T, F>(context: &'static str, task: F) -> AppResult<T>
 where
@@ -34,16 +69,58 @@ where
         .map_err(|err| AppError::internal(format!("{context} 执行失败：{err}")))
 }
 
-fn to_tauri_error<T>(result: AppResult<T>) -> Result<T, String> {
-    result.map_err(|err| err.to_string())
+/// 统一转换为前端可区分类别的 `PortlyError`（旧模块的 String 错误归为内部错误）
+fn to_tauri_error<T, E: Into<PortlyError>>(result: Result<T, E>) -> Result<T, PortlyError> {
+    result.map_err(Into::into)
 }
 
-async fn run_blocking_to_tauri<T, F>(context: &'static str, task: F) -> Result<T, String>
+async fn run_blocking_to_tauri<T, F>(context: &'static str, task: F) -> Result<T, PortlyError>
 where
     T: Send + 'static,
     F: FnOnce() -> T + Send + 'static,
 {
-    run_blocking_with_context(context, task).await.map_err(|err| err.to_string())
+    run_blocking_with_context(context, task).await
+}
+
+/// 在阻塞线程中执行可失败的任务，保留任务本身的错误类别
+async fn run_blocking_fallible<T, F>(context: &'static str, task: F) -> Result<T, PortlyError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> AppResult<T> + Send + 'static,
+{
+    run_blocking_with_context(context, task).await?
+}
+
+/// 在阻塞线程中执行登记到 `ScanManager` 的扫描任务
+///
+/// 任务被取消后立即返回 `Cancelled`，不再等待阻塞线程结束；
+/// 支持分批执行的任务可通过 `TaskHandle` 汇报进度并提前停止
+async fn run_scan_task<T, F>(
+    manager: &ScanManager,
+    kind: TaskKind,
+    label: impl Into<String>,
+    task: F,
+) -> Result<T, PortlyError>
+where
+    T: Send + 'static,
+    F: FnOnce(&TaskHandle) -> AppResult<T> + Send + 'static,
+{
+    let handle = manager.begin(kind, label)?;
+    let id = handle.id();
+    let mut join = spawn_blocking(move || handle.run(task));
+    loop {
+        match tokio::time::timeout(TASK_CANCEL_POLL_INTERVAL, &mut join).await {
+            Ok(joined) => {
+                return joined.map_err(|err| {
+                    AppError::internal(format!("{} 执行失败：{err}", kind.display_name()))
+                })?;
+            }
+            Err(_) if manager.is_cancelled(id) => {
+                return Err(AppError::cancelled(kind.display_name()));
+            }
+            Err(_) => {}
+        }
+    }
 }
 
 fn normalize_host(raw: &str, field: &'static str) -> AppResult<String> {
@@ -156,372 +233,2059 @@ fn normalize_timeout_ms(timeout_ms: u64, field: &'static str) -> AppResult<u64>
     Ok(timeout_ms)
 }
 
-fn ensure_command_available(command: &str) -> AppResult<()> {
-    command_exec::ensure_command_available(command)
+fn ensure_command_available(command: &str) -> AppResult<()> {
+    command_exec::ensure_command_available(command)
+}
+
+/// Tauri 命令: 扫描端口；`include_udp` 时一并列出 UDP 套接字，`dual_stack` 时同一进程的
+/// IPv4 / IPv6 监听合并为一行
+#[tauri::command]
+async fn tauri_scan_ports(
+    manager: State<'_, ScanManager>,
+    include_command: bool,
+    include_udp: Option<bool>,
+    dual_stack: Option<bool>,
+) -> Result<ScanResult, PortlyError> {
+    let include_udp = include_udp.unwrap_or(false);
+    let dual_stack = dual_stack.unwrap_or(false);
+    run_scan_task(&manager, TaskKind::LocalScan, "本机", move |_| {
+        let result = core::try_scan_ports(include_command, include_udp)?;
+        Ok(if dual_stack {
+            result.merge_dual_stack()
+        } else {
+            result
+        })
+    })
+    .await
+}
+
+/// Tauri 命令: 只重新读取一个端口，并推送 `port-refreshed`；`ports` 为空表示已不再监听
+#[tauri::command]
+async fn tauri_refresh_port(
+    app: tauri::AppHandle,
+    port: u16,
+    include_command: Option<bool>,
+    include_udp: Option<bool>,
+    dual_stack: Option<bool>,
+) -> Result<core::PortRefresh, PortlyError> {
+    let include_command = include_command.unwrap_or(false);
+    let include_udp = include_udp.unwrap_or(false);
+    let mut refresh = run_blocking_fallible("端口刷新", move || {
+        core::try_refresh_port(port, include_command, include_udp)
+    })
+    .await?;
+    if dual_stack.unwrap_or(false) {
+        refresh.ports = core::merge_dual_stack(refresh.ports);
+    }
+    let _ = app.emit(PORT_REFRESHED_EVENT, &refresh);
+    Ok(refresh)
+}
+
+/// Tauri 命令: 本机 TCP 连接（对端地址、状态、PID 和进程名）
+#[tauri::command]
+async fn tauri_scan_connections() -> Result<Vec<core::ConnectionInfo>, PortlyError> {
+    run_blocking_fallible("连接扫描", core::try_get_connections).await
+}
+
+/// Tauri 命令: 按应用分组
+#[tauri::command]
+async fn tauri_scan_ports_grouped(
+    manager: State<'_, ScanManager>,
+) -> Result<Vec<AppGroup>, PortlyError> {
+    run_scan_task(&manager, TaskKind::LocalScan, "本机", |_| {
+        core::try_scan_ports_grouped()
+    })
+    .await
+}
+
+/// Tauri 命令: 过滤端口
+#[tauri::command]
+fn tauri_filter_ports(
+    port_filter: Option<u16>,
+    app_filter: Option<String>,
+    exclude_system: bool,
+) -> Vec<PortInfo> {
+    core::filter_ports(port_filter, app_filter, exclude_system)
+}
+
+// ===== 网络扫描命令 =====
+
+/// Tauri 命令: 获取本机网络接口
+#[tauri::command]
+fn tauri_get_interfaces() -> Vec<network::NetworkInterface> {
+    network::get_local_interfaces()
+}
+
+/// Tauri 命令: 添加手动子网（会出现在接口列表末尾）并推送 `settings-changed`
+#[tauri::command]
+async fn tauri_add_manual_subnet(
+    app: tauri::AppHandle,
+    subnet: String,
+) -> Result<settings::Settings, PortlyError> {
+    let saved = run_blocking_fallible("添加手动子网", move || {
+        settings::add_manual_subnet(&subnet)
+    })
+    .await?;
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+    Ok(saved)
+}
+
+/// Tauri 命令: 移除手动子网并推送 `settings-changed`
+#[tauri::command]
+async fn tauri_remove_manual_subnet(
+    app: tauri::AppHandle,
+    subnet: String,
+) -> Result<settings::Settings, PortlyError> {
+    let saved = run_blocking_fallible("移除手动子网", move || {
+        settings::remove_manual_subnet(&subnet)
+    })
+    .await?;
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+    Ok(saved)
+}
+
+/// Tauri 命令: 读取 Tailscale / WireGuard 覆盖网络中的节点，设备列表中单独成组
+#[tauri::command]
+async fn tauri_list_vpn_peers() -> Result<Vec<vpn::VpnPeer>, PortlyError> {
+    run_blocking_to_tauri("VPN 节点读取", vpn::list_vpn_peers).await
+}
+
+/// Tauri 命令: 子网计算（网段、广播地址、主机范围和数量、掩码换算）
+#[tauri::command]
+fn tauri_subnet_info(cidr: String) -> Result<network::SubnetInfo, PortlyError> {
+    to_tauri_error(network::subnet_info(&cidr))
+}
+
+/// Tauri 命令: 获取当前子网（设置中的默认子网优先）
+#[tauri::command]
+fn tauri_get_current_subnet() -> Option<String> {
+    settings::current()
+        .network
+        .default_subnet
+        .or_else(network::get_current_subnet)
+}
+
+/// Tauri 命令: 发现局域网设备（异步）
+#[tauri::command]
+async fn tauri_discover_devices(
+    manager: State<'_, ScanManager>,
+    subnet: String,
+) -> Result<Vec<network::NetworkDevice>, PortlyError> {
+    let subnet = to_tauri_error(normalize_subnet(&subnet, "子网"))?;
+    run_scan_task(&manager, TaskKind::Discovery, subnet.clone(), move |task| {
+        let devices = network::try_discover_devices_with(
+            &subnet,
+            |done, total| {
+                task.set_progress(done, total);
+                task.check_cancelled()
+            },
+            |device| task.emit_result(device),
+        )?;
+        if let Err(err) = latency::record_discovery(&subnet, &devices) {
+            tracing::warn!(error = %err, "记录设备延迟失败");
+        }
+        Ok(devices)
+    })
+    .await
+}
+
+/// Tauri 命令: 只重新探测一台设备（Ping、MAC、主机名），并推送 `device-refreshed`
+#[tauri::command]
+async fn tauri_refresh_device(
+    app: tauri::AppHandle,
+    ip: String,
+) -> Result<network::NetworkDevice, PortlyError> {
+    let device = run_blocking_fallible("设备刷新", move || network::refresh_device(&ip)).await?;
+    let _ = app.emit(DEVICE_REFRESHED_EVENT, &device);
+    Ok(device)
+}
+
+/// Tauri 命令: 读取本机 ARP / 邻居表（带厂商和标签）
+#[tauri::command]
+async fn tauri_arp_table() -> Result<Vec<arp::ArpEntry>, PortlyError> {
+    run_blocking_fallible("ARP 表读取", arp::arp_table).await
+}
+
+/// Tauri 命令: 弹窗确认后清空 ARP 缓存，`ip` 不为空时只删除该条目；用户取消时返回 false
+#[tauri::command]
+async fn tauri_flush_arp_cache(
+    app: tauri::AppHandle,
+    ip: Option<String>,
+) -> Result<bool, PortlyError> {
+    run_blocking_fallible("清空 ARP 缓存", move || {
+        let lang = settings::current().lang();
+        let message = match ip.as_deref() {
+            Some(ip) => tr_args(lang, Msg::ArpFlushOneConfirm, &[&ip]),
+            None => tr(lang, Msg::ArpFlushConfirm).to_string(),
+        };
+        let confirmed = app
+            .dialog()
+            .message(message)
+            .title(tr(lang, Msg::ArpFlushConfirmTitle))
+            .kind(tauri_plugin_dialog::MessageDialogKind::Warning)
+            .buttons(tauri_plugin_dialog::MessageDialogButtons::OkCancel)
+            .blocking_show();
+        if !confirmed {
+            return Ok(false);
+        }
+        arp::flush_arp_cache(ip.as_deref())?;
+        Ok(true)
+    })
+    .await
+}
+
+/// Tauri 命令: 读取系统路由表
+#[tauri::command]
+async fn tauri_get_routes() -> Result<Vec<network::RouteEntry>, PortlyError> {
+    run_blocking_fallible("路由表读取", network::get_routes).await
+}
+
+/// Tauri 命令: 查询到目标的流量实际走哪条路由、从哪个接口发出
+#[tauri::command]
+async fn tauri_route_to(target: String) -> Result<network::RouteLookup, PortlyError> {
+    let target = to_tauri_error(normalize_host(&target, "目标地址"))?;
+    run_blocking_fallible("路由表读取", move || network::route_to(&target)).await
+}
+
+/// Tauri 命令: 读取各网卡的链路速率、MTU 和收发计数
+#[tauri::command]
+async fn tauri_interface_stats() -> Result<Vec<iface_stats::InterfaceStats>, PortlyError> {
+    run_blocking_fallible("网卡统计读取", iface_stats::interface_stats).await
+}
+
+/// Tauri 命令: 各设备最近若干次发现的 Ping 延迟，供设备列表绘制热力图
+#[tauri::command]
+async fn tauri_device_latency() -> Result<latency::LatencyHeatmap, PortlyError> {
+    run_blocking_to_tauri("设备延迟历史", latency::device_latency).await
+}
+
+/// Tauri 命令: 监听 LLDP/CDP 通告，获取本机所接交换机的名称、端口和 VLAN（需要抓包权限）
+#[tauri::command]
+async fn tauri_discover_neighbors(
+    interface: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<lldp::NeighborReport, PortlyError> {
+    let listen_secs = timeout_secs.unwrap_or(lldp::DEFAULT_LISTEN_SECS);
+    run_blocking_fallible("邻居发现", move || {
+        lldp::discover_neighbors(interface.as_deref(), listen_secs)
+    })
+    .await
+}
+
+/// Tauri 命令: 广播 DHCPDISCOVER（或被动监听），列出局域网内的 DHCP 服务器并检测私设 DHCP
+#[tauri::command]
+async fn tauri_discover_dhcp(
+    interface: Option<String>,
+    timeout_secs: Option<u64>,
+    passive: Option<bool>,
+) -> Result<dhcp::DhcpReport, PortlyError> {
+    let listen_secs = timeout_secs.unwrap_or(dhcp::DEFAULT_LISTEN_SECS);
+    run_blocking_fallible("DHCP 服务器发现", move || {
+        dhcp::discover_dhcp_servers(interface.as_deref(), listen_secs, passive.unwrap_or(false))
+    })
+    .await
+}
+
+/// Tauri 命令: 智能扫描（异步）；可指定接口或 802.1Q VLAN，在 trunk 口上逐个扫描 VLAN
+#[tauri::command]
+async fn tauri_smart_scan(
+    manager: State<'_, ScanManager>,
+    subnet: String,
+    interface: Option<String>,
+    vlan: Option<u16>,
+) -> Result<advanced_scan::AdvancedScanResult, PortlyError> {
+    let subnet = to_tauri_error(normalize_subnet(&subnet, "子网"))?;
+    let options = advanced_scan::ArpScanOptions {
+        interface: interface.filter(|name| !name.trim().is_empty()),
+        vlan,
+    };
+    run_scan_task(&manager, TaskKind::Discovery, subnet.clone(), move |_| {
+        advanced_scan::smart_scan_with(&subnet, &options)
+    })
+    .await
+}
+
+/// Tauri 命令: 检查是否有高级扫描权限
+#[tauri::command]
+fn tauri_check_permission() -> bool {
+    advanced_scan::check_raw_socket_permission()
+}
+
+/// Tauri 命令: 获取高级扫描能力报告（驱动/权限/接口）
+#[tauri::command]
+async fn tauri_check_capability() -> Result<advanced_scan::CapabilityReport, PortlyError> {
+    run_blocking_to_tauri("扫描能力检测", advanced_scan::check_raw_socket_capability).await
+}
+
+/// Tauri 命令: 检测权限与系统能力（可用、缺少及获取方式）
+#[tauri::command]
+async fn tauri_get_capabilities() -> Result<capabilities::CapabilitiesReport, PortlyError> {
+    run_blocking_to_tauri("能力检测", capabilities::detect).await
+}
+
+/// Tauri 命令: 快速端口扫描（异步）
+#[tauri::command]
+async fn tauri_quick_scan(
+    manager: State<'_, ScanManager>,
+    ip: String,
+) -> Result<Vec<network::RemotePort>, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
+    let ports = network::get_common_ports();
+    let timeout_ms = settings::current().scan.connect_timeout_ms;
+    run_scan_task(&manager, TaskKind::PortScan, ip.clone(), move |task| {
+        scan_ports_with_progress(task, &ip, &ports, timeout_ms)
+    })
+    .await
+}
+
+/// 逐个端口扫描并更新任务进度，开放端口即时推送，任务取消后停止
+fn scan_ports_with_progress(
+    task: &TaskHandle,
+    ip: &str,
+    ports: &[u16],
+    timeout_ms: u64,
+) -> AppResult<Vec<network::RemotePort>> {
+    let total = ports.len();
+    network::try_scan_ports_with(ip, ports, timeout_ms, |port, done| {
+        if port.is_open {
+            task.emit_result(port);
+        }
+        task.set_progress(done, total);
+        task.check_cancelled()
+    })
+}
+
+/// Tauri 命令: 自定义端口扫描（异步）
+#[tauri::command]
+async fn tauri_scan_ports_range(
+    manager: State<'_, ScanManager>,
+    ip: String,
+    start: u16,
+    end: u16,
+    timeout_ms: u64,
+) -> Result<Vec<network::RemotePort>, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
+    let (start, end) = to_tauri_error(normalize_port_range(start, end))?;
+    let timeout_ms = to_tauri_error(normalize_timeout_ms(timeout_ms, "扫描超时"))?;
+    let ports = network::port_range(start, end);
+    run_scan_task(&manager, TaskKind::PortScan, ip.clone(), move |task| {
+        scan_ports_with_progress(task, &ip, &ports, timeout_ms)
+    })
+    .await
+}
+
+/// Tauri 命令: 获取常用端口列表
+#[tauri::command]
+fn tauri_get_common_ports() -> Vec<u16> {
+    network::get_common_ports()
+}
+
+/// Tauri 命令: Ping 测试（异步）
+#[tauri::command]
+async fn tauri_ping(
+    manager: State<'_, ScanManager>,
+    ip: String,
+    count: u32,
+) -> Result<network::PingResult, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "Ping 目标"))?;
+    to_tauri_error(ensure_command_available("ping"))?;
+    if count == 0 || count > 100 {
+        return Err(AppError::validation("Ping 次数", "应在 1-100 之间"));
+    }
+    run_scan_task(&manager, TaskKind::Ping, ip.clone(), move |task| {
+        network::ping_test_with(&ip, count, |reply| {
+            task.emit_result(reply);
+            task.set_progress(reply.seq as usize, count as usize);
+            task.check_cancelled()
+        })
+    })
+    .await
+}
+
+/// Tauri 命令: 单次 Ping（异步，用于流式显示）
+#[tauri::command]
+async fn tauri_ping_one(ip: String, seq: u32) -> Result<network::PingOneResult, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "Ping 目标"))?;
+    to_tauri_error(ensure_command_available("ping"))?;
+    run_blocking_to_tauri("单次 Ping", move || network::ping_one(&ip, seq)).await
+}
+
+/// Tauri 命令: 同时 Ping 多个目标 `rounds` 轮（间隔默认 1 秒），返回各目标的丢包率、延迟和抖动；
+/// 每轮结束后以 `scan://result` 推送本轮各目标的结果
+#[tauri::command]
+async fn tauri_compare_ping(
+    manager: State<'_, ScanManager>,
+    targets: Vec<String>,
+    rounds: u32,
+    interval_ms: Option<u64>,
+) -> Result<ping_compare::PingComparison, PortlyError> {
+    let targets = to_tauri_error(
+        targets
+            .iter()
+            .map(|target| normalize_host(target, "Ping 目标"))
+            .collect::<AppResult<Vec<String>>>(),
+    )?;
+    let interval_ms = interval_ms.unwrap_or(1000);
+    to_tauri_error(ping_compare::validate_request(
+        &targets,
+        rounds,
+        interval_ms,
+    ))?;
+    to_tauri_error(ensure_command_available("ping"))?;
+    let label = targets.join(", ");
+    run_scan_task(&manager, TaskKind::Ping, label, move |task| {
+        ping_compare::compare(&targets, rounds, interval_ms, |round, results| {
+            task.emit_result(&results);
+            task.set_progress(round as usize, rounds as usize);
+            task.check_cancelled()
+        })
+    })
+    .await
+}
+
+/// Tauri 命令: Traceroute（异步）
+#[tauri::command]
+async fn tauri_traceroute(
+    manager: State<'_, ScanManager>,
+    ip: String,
+) -> Result<network::TracerouteResult, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "Traceroute 目标"))?;
+    to_tauri_error(ensure_command_available("traceroute").or_else(|_| ensure_command_available("tracert")))?;
+    run_scan_task(&manager, TaskKind::Traceroute, ip.clone(), move |task| {
+        network::traceroute_with(&ip, |hop| {
+            task.emit_result(hop);
+            task.set_progress(hop.hop as usize, network::TRACEROUTE_MAX_HOPS as usize);
+            task.check_cancelled()
+        })
+    })
+    .await
+}
+
+/// Tauri 命令: 查询 NTP 服务器并比较本机时钟；未指定服务器时使用系统配置的服务器
+#[tauri::command]
+async fn tauri_check_time_drift(
+    servers: Option<Vec<String>>,
+) -> Result<ntp::TimeDriftReport, PortlyError> {
+    let servers = to_tauri_error(
+        servers
+            .unwrap_or_default()
+            .iter()
+            .map(|server| normalize_host(server, "NTP 服务器"))
+            .collect::<AppResult<Vec<_>>>(),
+    )?;
+    run_blocking_fallible("时间偏差检测", move || {
+        ntp::check_time_drift(&servers, ntp::DEFAULT_TIMEOUT_MS)
+    })
+    .await
+}
+
+/// Tauri 命令: 列出主机的 SMB 共享和 NFS 导出，默认两种都查
+#[tauri::command]
+async fn tauri_list_shares(
+    ip: String,
+    smb: Option<bool>,
+    nfs: Option<bool>,
+) -> Result<shares::ShareListing, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
+    run_blocking_fallible("共享枚举", move || {
+        shares::list_shares(&ip, smb.unwrap_or(true), nfs.unwrap_or(true))
+    })
+    .await
+}
+
+/// Tauri 命令: 查询打印机型号和状态，631 使用 IPP，9100 使用 PJL
+#[tauri::command]
+async fn tauri_printer_info(ip: String, port: u16) -> Result<printer::PrinterInfo, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
+    run_blocking_fallible("打印机查询", move || printer::query_printer(&ip, port)).await
+}
+
+/// Tauri 命令: 探测服务类型（异步）
+#[tauri::command]
+async fn tauri_detect_service(ip: String, port: u16) -> Result<network::ServiceInfo, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
+    let port = to_tauri_error(normalize_port(port, "服务检测端口"))?;
+    run_blocking_to_tauri("服务探测", move || network::detect_service_type(&ip, port)).await
+}
+
+/// Tauri 命令: 批量探测服务（异步）
+#[tauri::command]
+async fn tauri_detect_services(
+    manager: State<'_, ScanManager>,
+    ip: String,
+    ports: Vec<u16>,
+) -> Result<Vec<network::ServiceInfo>, PortlyError> {
+    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
+    let mut ports: Vec<u16> = ports
+        .into_iter()
+        .filter_map(|port| (1..=65535).contains(&port).then_some(port))
+        .collect();
+    if ports.is_empty() {
+        return Ok(vec![]);
+    }
+    ports.sort_unstable();
+    ports.dedup();
+    run_scan_task(&manager, TaskKind::ServiceProbe, ip.clone(), move |_| {
+        Ok(network::detect_services(&ip, &ports))
+    })
+    .await
+}
+
+// ===== Docker 命令 =====
+
+/// Tauri 命令: 检查 Docker 是否可用
+#[tauri::command]
+fn tauri_docker_available() -> bool {
+    docker::is_docker_available()
+}
+
+/// Tauri 命令: 探测容器运行时（Docker Desktop / OrbStack / Colima / Rancher Desktop / rootless 等）
+#[tauri::command]
+async fn tauri_probe_container_runtimes() -> Result<Vec<docker::RuntimeProbe>, PortlyError> {
+    run_blocking_to_tauri("容器运行时探测", docker::probe_container_runtimes).await
+}
+
+/// Tauri 命令: 获取当前生效的容器运行时
+#[tauri::command]
+async fn tauri_get_container_runtime() -> Result<Option<docker::ContainerRuntime>, PortlyError> {
+    run_blocking_to_tauri("容器运行时检测", docker::detect_container_runtime).await
+}
+
+/// Tauri 命令: 获取当前生效的 Docker 主机（手动指定 / DOCKER_HOST / docker context）
+#[tauri::command]
+fn tauri_get_docker_host() -> Option<docker::DockerHostConfig> {
+    docker::get_docker_host_config()
+}
+
+/// Tauri 命令: 指定远程 Docker 主机（tcp:// 或 ssh://），传空值恢复默认
+#[tauri::command]
+fn tauri_set_docker_host(host: Option<String>) -> Result<(), PortlyError> {
+    to_tauri_error(docker::set_docker_host(host))
+}
+
+/// Tauri 命令: 获取 Docker 容器列表
+#[tauri::command]
+async fn tauri_get_docker_containers() -> Result<Vec<docker::DockerContainer>, PortlyError> {
+    run_blocking_fallible("Docker 容器列表读取", docker::try_get_docker_containers).await
+}
+
+/// Tauri 命令: 按 Compose 项目分组获取容器
+#[tauri::command]
+async fn tauri_get_compose_groups() -> Result<docker::ContainerGroups, PortlyError> {
+    run_blocking_to_tauri("Compose 项目分组", docker::get_compose_groups).await
+}
+
+/// Tauri 命令: 获取端口对应的 Compose 项目目录和配置文件
+#[tauri::command]
+async fn tauri_get_compose_location(
+    port: u16,
+) -> Result<Option<docker::ComposeLocation>, PortlyError> {
+    let port = to_tauri_error(normalize_port(port, "端口"))?;
+    run_blocking_to_tauri("Compose 项目定位", move || {
+        docker::get_compose_location(port)
+    })
+    .await
+}
+
+/// Tauri 命令: 订阅 Docker 容器事件，容器变化时推送 `docker-event`
+#[tauri::command]
+fn tauri_start_docker_events(app: tauri::AppHandle, manager: State<'_, ScanManager>) -> bool {
+    if manager.is_running(TaskKind::DockerEvents) {
+        return false;
+    }
+    let Ok(task) = manager.begin(TaskKind::DockerEvents, "docker") else {
+        return false;
+    };
+    std::thread::spawn(move || {
+        docker::watch_docker_events(task.cancel_flag(), |event| {
+            let _ = app.emit(DOCKER_EVENT, event);
+        });
+        task.finish(&Ok(()));
+    });
+    true
+}
+
+/// Tauri 命令: 停止 Docker 容器事件订阅
+#[tauri::command]
+fn tauri_stop_docker_events(manager: State<'_, ScanManager>) -> bool {
+    manager.cancel_kind(TaskKind::DockerEvents)
+}
+
+/// Tauri 命令: 获取端口的容器信息（容器名、镜像、健康检查状态）
+#[tauri::command]
+fn tauri_get_docker_port_info(port: u16) -> Option<(String, String, Option<docker::HealthStatus>)> {
+    docker::get_docker_port_info(port)
+}
+
+// ===== Kubernetes 命令 =====
+
+/// Tauri 命令: 获取 Kubernetes Service / Pod 端口及本机 NodePort 关联
+#[tauri::command]
+async fn tauri_get_kube_overview() -> Result<kube::KubeOverview, PortlyError> {
+    run_blocking_to_tauri("Kubernetes 端口读取", kube::get_kube_overview).await
+}
+
+/// Tauri 命令: 识别 `kubectl port-forward` 创建的本地监听端口
+#[tauri::command]
+async fn tauri_get_port_forwards() -> Result<Vec<kube::PortForwardSession>, PortlyError> {
+    run_blocking_to_tauri("kubectl port-forward 识别", kube::get_port_forward_sessions).await
+}
+
+/// Tauri 命令: 解析 IP 或域名，返回全部地址；有多个地址时探测各地址 443 端口的握手往返时间
+#[tauri::command]
+async fn tauri_resolve_target(target: String) -> Result<network::ResolveResult, PortlyError> {
+    let target = to_tauri_error(normalize_host(&target, "目标地址"))?;
+    run_blocking_fallible("目标解析", move || {
+        let mut result = network::resolve_target(&target)?;
+        if result.addresses.len() > 1 {
+            let timeout_ms = settings::current().scan.connect_timeout_ms;
+            network::probe_address_rtt(&mut result, 443, timeout_ms);
+        }
+        Ok(result)
+    })
+    .await
+}
+
+/// Tauri 命令: 展开主机名模板（如 node{01..20}.lan）并批量解析
+#[tauri::command]
+async fn tauri_resolve_host_pattern(
+    pattern: String,
+) -> Result<Vec<host_pattern::PatternHost>, PortlyError> {
+    run_blocking_fallible("主机名模板解析", move || {
+        host_pattern::resolve_host_pattern(&pattern)
+    })
+    .await
+}
+
+// ===== 进程管理命令 =====
+
+/// Tauri 命令: 终止进程
+#[tauri::command]
+fn tauri_kill_process(pid: u32, force: bool) -> process::KillResult {
+    if pid == 0 {
+        return process::KillResult {
+            success: false,
+            pid,
+            message: "进程 ID 不能为 0".to_string(),
+        };
+    }
+    process::kill_process(pid, force)
+}
+
+/// Tauri 命令: 终止占用端口的进程
+#[tauri::command]
+fn tauri_kill_port(port: u16) -> process::KillResult {
+    let port = match normalize_port(port, "端口") {
+        Ok(v) => v,
+        Err(e) => {
+            return process::KillResult {
+                success: false,
+                pid: 0,
+                message: e.to_string(),
+            }
+        }
+    };
+    process::kill_port_process(port)
+}
+
+/// Tauri 命令: 获取进程信息
+#[tauri::command]
+fn tauri_get_process_info(pid: u32) -> Option<process::ProcessInfo> {
+    process::get_process_info(pid)
+}
+
+// ===== 导出命令 =====
+
+/// Tauri 命令: 导出到 CSV
+#[tauri::command]
+async fn tauri_export_csv(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("CSV 导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_to_csv_with_progress(&ports, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 导出到 JSON
+#[tauri::command]
+async fn tauri_export_json(
+    app: tauri::AppHandle,
+    scan_result: core::ScanResult,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("JSON 导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_to_json_with_progress(&scan_result, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 导出到 NDJSON（每行一条记录）
+#[tauri::command]
+async fn tauri_export_ndjson(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("NDJSON 导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_to_ndjson_with_progress(&ports, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 导出远程端口扫描结果到 NDJSON
+#[tauri::command]
+async fn tauri_export_remote_ndjson(
+    app: tauri::AppHandle,
+    host: String,
+    ports: Vec<network::RemotePort>,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("远程端口导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_remote_ports_with_progress(
+            &host,
+            &ports,
+            &export::ExportFormat::Ndjson,
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 导出到文本
+#[tauri::command]
+async fn tauri_export_txt(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("文本导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_to_txt_with_progress(&ports, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 导出到 Markdown 表格
+#[tauri::command]
+async fn tauri_export_markdown(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("Markdown 导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_to_markdown_with_progress(&ports, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 导出局域网设备到 Markdown 表格
+#[tauri::command]
+async fn tauri_export_devices_markdown(
+    app: tauri::AppHandle,
+    devices: Vec<network::NetworkDevice>,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("局域网设备导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_records_with_progress(
+            &devices,
+            &export::ExportFormat::Markdown,
+            "局域网设备报告",
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 导出到 Excel（端口 / 应用分组 / Docker 容器三个工作表）
+#[tauri::command]
+async fn tauri_export_xlsx(
+    app: tauri::AppHandle,
+    scan_result: core::ScanResult,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("Excel 导出", move || {
+        let groups = export::group_ports_for_export(&scan_result.ports);
+        let containers = docker::get_docker_containers();
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_to_xlsx_with_progress(
+            &scan_result,
+            &groups,
+            &containers,
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 导出扫描结果，未指定路径时弹出系统保存对话框
+#[tauri::command]
+async fn tauri_export_scan(
+    app: tauri::AppHandle,
+    scan_result: core::ScanResult,
+    format: String,
+    path: Option<String>,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("扫描结果导出", move || {
+        let path = match path {
+            Some(path) => path,
+            None => match pick_export_path(&app, &format) {
+                Ok(Some(path)) => path,
+                Ok(None) => {
+                    return export::ExportResult {
+                        success: false,
+                        path: None,
+                        message: "已取消导出".to_string(),
+                        record_count: 0,
+                    }
+                }
+                Err(e) => {
+                    return export::ExportResult {
+                        success: false,
+                        path: None,
+                        message: format!("无法获取保存路径: {}", e),
+                        record_count: 0,
+                    }
+                }
+            },
+        };
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_scan_with_progress(&scan_result, &format, &path, &mut progress)
+    })
+    .await
+}
+
+/// 生成向前端推送导出进度的回调
+fn export_progress_emitter<'a>(
+    app: &'a tauri::AppHandle,
+    path: &'a str,
+) -> impl FnMut(usize, usize) + 'a {
+    move |written, total| {
+        let _ = app.emit(
+            EXPORT_PROGRESS_EVENT,
+            export::ExportProgress {
+                path: path.to_string(),
+                written,
+                total,
+            },
+        );
+    }
+}
+
+/// 弹出保存对话框；用户取消时返回 None
+fn pick_export_path(
+    app: &tauri::AppHandle,
+    format: &export::ExportFormat,
+) -> Result<Option<String>, tauri_plugin_dialog::Error> {
+    let extension = export::export_extension(format);
+    let picked = app
+        .dialog()
+        .file()
+        .set_title("导出扫描结果")
+        .set_directory(export::get_default_export_dir())
+        .set_file_name(export::generate_export_filename(format))
+        .add_filter(extension.to_uppercase(), &[extension])
+        .blocking_save_file();
+
+    match picked {
+        Some(file) => Ok(Some(file.into_path()?.to_string_lossy().to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Tauri 命令: 加密导出扫描结果（age 口令加密，生成 .age 文件）
+#[tauri::command]
+async fn tauri_export_encrypted(
+    app: tauri::AppHandle,
+    scan_result: core::ScanResult,
+    format: String,
+    path: String,
+    passphrase: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("加密导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_encrypted_with_progress(
+            &scan_result,
+            &format,
+            &path,
+            &passphrase,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 自动导出（使用默认路径）
+#[tauri::command]
+async fn tauri_export_auto(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    scan_result: core::ScanResult,
+    format: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let export_format =
+        export::ExportFormat::from_name(&format).unwrap_or(export::ExportFormat::Csv);
+    run_blocking_to_tauri("自动导出", move || {
+        let path = export::auto_export_path(&export_format);
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_ports_with_progress(
+            &ports,
+            &scan_result,
+            &export_format,
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+fn parse_export_format(format: &str) -> AppResult<export::ExportFormat> {
+    export::ExportFormat::from_name(format)
+        .ok_or_else(|| AppError::validation("导出格式", "仅支持 csv/json/ndjson/txt/md/xlsx"))
+}
+
+/// Tauri 命令: 导出局域网设备列表
+#[tauri::command]
+async fn tauri_export_devices(
+    app: tauri::AppHandle,
+    devices: Vec<network::NetworkDevice>,
+    format: String,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("局域网设备导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_records_with_progress(
+            &devices,
+            &format,
+            "局域网设备报告",
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 导出远程端口扫描结果
+#[tauri::command]
+async fn tauri_export_remote_ports(
+    app: tauri::AppHandle,
+    host: String,
+    ports: Vec<network::RemotePort>,
+    format: String,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("远程端口导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_remote_ports_with_progress(&host, &ports, &format, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 导出 Ping 测试结果
+#[tauri::command]
+async fn tauri_export_ping_results(
+    app: tauri::AppHandle,
+    results: Vec<network::PingResult>,
+    format: String,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("Ping 结果导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_records_with_progress(
+            &results,
+            &format,
+            "Ping 测试报告",
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 导出路由追踪结果
+#[tauri::command]
+async fn tauri_export_traceroute(
+    app: tauri::AppHandle,
+    result: network::TracerouteResult,
+    format: String,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("路由追踪导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_traceroute_with_progress(&result, &format, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 按列选择与顺序导出
+#[tauri::command]
+async fn tauri_export_with_columns(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    columns: Vec<export::PortColumn>,
+    format: String,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("按列导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_with_columns_with_progress(&ports, &columns, &format, &path, &mut progress)
+    })
+    .await
+}
+
+/// Tauri 命令: 获取已保存的导出预设
+#[tauri::command]
+fn tauri_get_export_presets() -> Vec<export::ExportPreset> {
+    export::load_export_presets()
+}
+
+/// Tauri 命令: 保存导出预设（同名覆盖）
+#[tauri::command]
+fn tauri_save_export_preset(preset: export::ExportPreset) -> Result<(), PortlyError> {
+    to_tauri_error(export::save_export_preset(preset))
+}
+
+/// Tauri 命令: 删除导出预设
+#[tauri::command]
+fn tauri_delete_export_preset(name: String) -> Result<(), PortlyError> {
+    to_tauri_error(export::delete_export_preset(&name))
+}
+
+/// Tauri 命令: 使用导出预设导出到默认目录
+#[tauri::command]
+async fn tauri_export_with_preset(
+    app: tauri::AppHandle,
+    ports: Vec<core::PortInfo>,
+    preset: String,
+) -> Result<export::ExportResult, PortlyError> {
+    run_blocking_to_tauri("预设导出", move || {
+        let preset = match export::find_export_preset(&preset) {
+            Ok(preset) => preset,
+            Err(message) => {
+                return export::ExportResult {
+                    success: false,
+                    path: None,
+                    message,
+                    record_count: 0,
+                }
+            }
+        };
+        let path = export::preset_export_path(&preset, ports.len());
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_with_columns_with_progress(
+            &ports,
+            &preset.columns,
+            &preset.format,
+            &path,
+            &mut progress,
+        )
+    })
+    .await
+}
+
+/// Tauri 命令: 获取默认导出目录
+#[tauri::command]
+fn tauri_get_export_dir() -> String {
+    export::get_default_export_dir()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Tauri 命令: 获取扫描历史摘要（不含端口明细）
+#[tauri::command]
+async fn tauri_get_history_summary() -> Result<Vec<export::HistorySummary>, PortlyError> {
+    run_blocking_to_tauri("读取扫描历史", export::get_history_summary).await
+}
+
+/// Tauri 命令: 保存扫描到历史
+#[tauri::command]
+async fn tauri_save_to_history(scan_result: core::ScanResult) -> Result<(), PortlyError> {
+    run_blocking_fallible("保存扫描历史", move || {
+        to_tauri_error(export::save_to_history(&scan_result))
+    })
+    .await
+}
+
+/// Tauri 命令: 按时间戳加载一次历史扫描的完整数据
+#[tauri::command]
+async fn tauri_load_history_entry(
+    timestamp: String,
+) -> Result<export::ScanHistoryEntry, PortlyError> {
+    run_blocking_fallible("加载历史记录", move || {
+        to_tauri_error(export::load_history_entry(&timestamp))
+    })
+    .await
+}
+
+/// Tauri 命令: 搜索扫描历史（进程名 / 端口 / 命令行 / 日期范围）
+#[tauri::command]
+async fn tauri_search_history(
+    query: export::HistorySearchQuery,
+) -> Result<Vec<export::HistoryMatch>, PortlyError> {
+    run_blocking_fallible("历史记录搜索", move || {
+        to_tauri_error(export::search_history(&query))
+    })
+    .await
+}
+
+/// Tauri 命令: 端口在历史扫描中的占用时间线（哪个进程在哪段时间占用、何时空闲）
+#[tauri::command]
+async fn tauri_port_timeline(port: u16) -> Result<export::PortTimeline, PortlyError> {
+    let port = to_tauri_error(normalize_port(port, "端口"))?;
+    run_blocking_to_tauri("端口时间线", move || export::port_timeline(port)).await
+}
+
+/// Tauri 命令: 将导出的 JSON 文件导入扫描历史
+#[tauri::command]
+async fn tauri_import_history(path: String) -> Result<export::HistoryImportResult, PortlyError> {
+    run_blocking_fallible("历史记录导入", move || {
+        to_tauri_error(export::import_history(&path))
+    })
+    .await
+}
+
+/// Tauri 命令: 比较两次历史扫描（按时间戳定位）
+#[tauri::command]
+async fn tauri_diff_history(from: String, to: String) -> Result<export::HistoryDiff, PortlyError> {
+    run_blocking_fallible("历史记录比较", move || {
+        to_tauri_error(export::diff_history(&from, &to))
+    })
+    .await
+}
+
+// ===== DNS 查询命令 =====
+
+/// Tauri 命令: DNS 查询
+#[tauri::command]
+async fn tauri_dns_query(
+    domain: String,
+    record_type: String,
+    dns_server: Option<String>,
+) -> Result<dns::DnsQueryResult, PortlyError> {
+    let domain = to_tauri_error(normalize_host(&domain, "域名"))?;
+    let dns_server = dns_server
+        .map(|s| to_tauri_error(normalize_host(&s, "DNS服务器")))
+        .transpose()?;
+    run_blocking_to_tauri("DNS 查询", move || dns::dns_query(domain, record_type, dns_server)).await
+}
+
+/// Tauri 命令: 获取常用 DNS 服务器列表
+#[tauri::command]
+fn tauri_get_dns_servers() -> Vec<dns::DnsServerInfo> {
+    dns::get_popular_dns_servers()
+}
+
+/// Tauri 命令: 批量 DNS 查询
+#[tauri::command]
+async fn tauri_dns_batch_query(
+    domain: String,
+    record_types: Vec<String>,
+    dns_server: Option<String>,
+) -> Result<Vec<dns::DnsQueryResult>, PortlyError> {
+    let domain = to_tauri_error(normalize_host(&domain, "域名"))?;
+    let dns_server = dns_server
+        .map(|s| to_tauri_error(normalize_host(&s, "DNS服务器")))
+        .transpose()?;
+    run_blocking_to_tauri("批量 DNS 查询", move || dns::batch_query(domain, record_types, dns_server)).await
+}
+
+// ===== Whois 查询命令 =====
+
+/// Tauri 命令: Whois 域名查询
+#[tauri::command]
+async fn tauri_whois_query(domain: String) -> Result<whois::WhoisResult, PortlyError> {
+    let domain = to_tauri_error(normalize_host(&domain, "域名"))?;
+    run_blocking_to_tauri("Whois 查询", move || whois::whois_query(domain)).await
+}
+
+// ===== SSL 证书检查命令 =====
+
+/// Tauri 命令: 检查 SSL 证书
+#[tauri::command]
+async fn tauri_check_ssl_cert(
+    host: String,
+    port: Option<u16>,
+) -> Result<ssl::SslCertInfo, PortlyError> {
+    let host = to_tauri_error(normalize_host(&host, "主机"))?;
+    let port = port.map(|p| to_tauri_error(normalize_port(p, "端口"))).transpose()?;
+    to_tauri_error(ensure_command_available("openssl"))?;
+    run_blocking_to_tauri("SSL 证书检查", move || ssl::check_ssl_cert(host, port)).await
+}
+
+/// Tauri 命令: 批量检查 SSL 证书
+#[tauri::command]
+async fn tauri_check_ssl_certs(
+    targets: Vec<(String,
+    Option<u16>)>,
+) -> Result<Vec<ssl::SslCertInfo>, PortlyError> {
+    let targets: Vec<(String, Option<u16>)> = to_tauri_error(
+        targets
+        .into_iter()
+        .map(|(host, port)| {
+            Ok((
+                normalize_host(&host, "主机")?,
+                port.map(|p| normalize_port(p, "端口")).transpose()?,
+            ))
+        })
+        .collect::<AppResult<Vec<_>>>(),
+    )?;
+    to_tauri_error(ensure_command_available("openssl"))?;
+    run_blocking_to_tauri("批量 SSL 证书检查", move || ssl::check_ssl_certs(targets)).await
+}
+
+/// Tauri 命令: 登记证书到期监控（后台监控按间隔检查）并推送 `settings-changed`
+#[tauri::command]
+async fn tauri_watch_certificate(
+    app: tauri::AppHandle,
+    host: String,
+    port: Option<u16>,
+) -> Result<settings::Settings, PortlyError> {
+    let host = to_tauri_error(normalize_host(&host, "主机"))?;
+    let port = to_tauri_error(normalize_port(port.unwrap_or(443), "端口"))?;
+    let saved = run_blocking_fallible("添加证书监控", move || {
+        settings::add_certificate_watch(&host, port)
+    })
+    .await?;
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+    Ok(saved)
+}
+
+/// Tauri 命令: 取消证书到期监控并推送 `settings-changed`
+#[tauri::command]
+async fn tauri_unwatch_certificate(
+    app: tauri::AppHandle,
+    host: String,
+    port: u16,
+) -> Result<settings::Settings, PortlyError> {
+    let saved = run_blocking_fallible("移除证书监控", move || {
+        settings::remove_certificate_watch(&host, port)
+    })
+    .await?;
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+    Ok(saved)
+}
+
+/// Tauri 命令: 计算 TLS 服务的 JARM / JA3S 指纹，默认端口 443
+#[tauri::command]
+async fn tauri_tls_fingerprint(
+    host: String,
+    port: Option<u16>,
+) -> Result<tls_fingerprint::TlsFingerprint, PortlyError> {
+    let host = to_tauri_error(normalize_host(&host, "主机"))?;
+    let port = to_tauri_error(normalize_port(port.unwrap_or(443), "端口"))?;
+    run_blocking_fallible("TLS 指纹", move || {
+        tls_fingerprint::tls_fingerprint(&host, port)
+    })
+    .await
+}
+
+// ===== 探测插件命令 =====
+
+/// Tauri 命令: 列出已加载的服务探测插件
+#[tauri::command]
+async fn tauri_list_plugins() -> Result<Vec<plugins::ProbePlugin>, PortlyError> {
+    run_blocking_to_tauri("读取探测插件", plugins::current).await
+}
+
+/// Tauri 命令: 重新扫描插件目录，添加或修改插件后无需重启
+#[tauri::command]
+async fn tauri_reload_plugins() -> Result<Vec<plugins::ProbePlugin>, PortlyError> {
+    run_blocking_to_tauri("加载探测插件", plugins::reload).await
+}
+
+/// Tauri 命令: 插件目录路径
+#[tauri::command]
+fn tauri_get_plugins_dir() -> String {
+    plugins::plugins_dir().to_string_lossy().to_string()
+}
+
+// ===== 配置命令 =====
+
+/// Tauri 命令: 读取共享配置文件（与 CLI 共用）
+#[tauri::command]
+fn tauri_get_config() -> Result<config::PortlyConfig, PortlyError> {
+    to_tauri_error(config::load_config())
+}
+
+/// Tauri 命令: 保存共享配置文件
+#[tauri::command]
+fn tauri_save_config(config: config::PortlyConfig) -> Result<(), PortlyError> {
+    to_tauri_error(config::save_config(&config))
+}
+
+/// Tauri 命令: 获取配置文件路径
+#[tauri::command]
+fn tauri_get_config_path() -> String {
+    config::config_path().to_string_lossy().to_string()
+}
+
+// ===== 设置命令 =====
+
+/// Tauri 命令: 获取当前设置
+#[tauri::command]
+fn tauri_get_settings() -> settings::Settings {
+    settings::current()
+}
+
+/// Tauri 命令: 保存设置并推送 `settings-changed`
+#[tauri::command]
+async fn tauri_save_settings(
+    app: tauri::AppHandle,
+    settings: settings::Settings,
+) -> Result<settings::Settings, PortlyError> {
+    let saved = run_blocking_fallible("保存设置", move || {
+        settings::save_settings(&settings).map(|_| settings)
+    })
+    .await?;
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+    if saved.monitor.enabled {
+        start_monitor(app.clone());
+    } else {
+        stop_monitor(&app);
+    }
+    tray::refresh(&app);
+    Ok(saved)
+}
+
+/// Tauri 命令: 恢复默认设置
+#[tauri::command]
+async fn tauri_reset_settings(app: tauri::AppHandle) -> Result<settings::Settings, PortlyError> {
+    tauri_save_settings(app, settings::Settings::default()).await
+}
+
+/// Tauri 命令: 以测试事件执行一次事件脚本，用于检查命令
+#[tauri::command]
+async fn tauri_test_hook(hook: settings::ScriptHookSettings) -> Result<(), PortlyError> {
+    run_blocking_fallible("事件脚本测试", move || {
+        let lang = settings::current().lang();
+        let payload = WebhookPayload::new(
+            WebhookEvent::Test,
+            tr(lang, Msg::WebhookTestTitle),
+            tr(lang, Msg::HookTestBody),
+        );
+        hooks::run(&hook, &payload)
+    })
+    .await
+}
+
+/// Tauri 命令: 向 Webhook 发送一条测试消息，用于检查地址和模板
+#[tauri::command]
+async fn tauri_test_webhook(hook: settings::WebhookSettings) -> Result<(), PortlyError> {
+    run_blocking_fallible("Webhook 测试", move || {
+        let lang = settings::current().lang();
+        let payload = WebhookPayload::new(
+            WebhookEvent::Test,
+            tr(lang, Msg::WebhookTestTitle),
+            tr(lang, Msg::WebhookTestBody),
+        );
+        webhook::send(&hook, &payload)
+    })
+    .await
+}
+
+// ===== 规则命令 =====
+
+/// Tauri 命令: 读取全部规则
+#[tauri::command]
+async fn tauri_list_rules() -> Result<Vec<rules::Rule>, PortlyError> {
+    run_blocking_to_tauri("读取规则", rules::current).await
+}
+
+/// Tauri 命令: 新增或更新规则（ID 为空时新增），返回保存后的全部规则
+#[tauri::command]
+async fn tauri_save_rule(rule: rules::Rule) -> Result<Vec<rules::Rule>, PortlyError> {
+    run_blocking_fallible("保存规则", move || rules::upsert_rule(rule)).await
+}
+
+/// Tauri 命令: 删除规则，返回保存后的全部规则
+#[tauri::command]
+async fn tauri_delete_rule(id: String) -> Result<Vec<rules::Rule>, PortlyError> {
+    run_blocking_fallible("删除规则", move || rules::delete_rule(&id)).await
+}
+
+// ===== 端口库命令 =====
+
+/// Tauri 命令: 读取常用端口库（内置端口与用户自定义端口合并后）
+#[tauri::command]
+async fn tauri_list_known_ports() -> Result<Vec<known_ports::KnownPort>, PortlyError> {
+    run_blocking_to_tauri("读取端口库", known_ports::current).await
+}
+
+/// Tauri 命令: 新增或修改自定义端口，返回保存后的端口库
+#[tauri::command]
+async fn tauri_save_known_port(
+    entry: known_ports::PortEntry,
+) -> Result<Vec<known_ports::KnownPort>, PortlyError> {
+    run_blocking_fallible("保存端口", move || known_ports::upsert_port(entry)).await
+}
+
+/// Tauri 命令: 删除自定义端口或移除内置端口，返回保存后的端口库
+#[tauri::command]
+async fn tauri_delete_known_port(port: u16) -> Result<Vec<known_ports::KnownPort>, PortlyError> {
+    run_blocking_fallible("删除端口", move || known_ports::delete_port(port)).await
 }
 
-/// Tauri 命令: 扫描端口
+// ===== 资产清单命令 =====
+
+/// Tauri 命令: 读取资产清单（远程主机和局域网设备的标签与备注）
 #[tauri::command]
-async fn tauri_scan_ports(include_command: bool) -> Result<ScanResult, String> {
-    run_blocking_to_tauri("端口扫描", move || core::scan_ports(include_command)).await
+async fn tauri_list_inventory() -> Result<Vec<inventory::InventoryEntry>, PortlyError> {
+    run_blocking_to_tauri("读取资产清单", inventory::current).await
 }
 
-/// Tauri 命令: 按应用分组
+/// Tauri 命令: 替换目标的标签和备注，返回保存后的清单；都为空时删除该目标
 #[tauri::command]
-async fn tauri_scan_ports_grouped() -> Result<Vec<AppGroup>, String> {
-    run_blocking_to_tauri("应用分组扫描", move || core::scan_ports_grouped()).await
+async fn tauri_tag_target(
+    target: String,
+    tags: Vec<String>,
+    note: Option<String>,
+) -> Result<Vec<inventory::InventoryEntry>, PortlyError> {
+    run_blocking_fallible("保存标签", move || {
+        inventory::set_target(&target, &tags, note.as_deref())
+    })
+    .await
 }
 
-/// Tauri 命令: 过滤端口
+/// Tauri 命令: 删除目标的标签和备注
 #[tauri::command]
-fn tauri_filter_ports(
-    port_filter: Option<u16>,
-    app_filter: Option<String>,
-    exclude_system: bool,
-) -> Vec<PortInfo> {
-    core::filter_ports(port_filter, app_filter, exclude_system)
+async fn tauri_untag_target(target: String) -> Result<Vec<inventory::InventoryEntry>, PortlyError> {
+    run_blocking_fallible("删除标签", move || inventory::remove_target(&target)).await
 }
 
-// ===== 网络扫描命令 =====
+// ===== 备份命令 =====
 
-/// Tauri 命令: 获取本机网络接口
+/// Tauri 命令: 导出完整状态备份
 #[tauri::command]
-fn tauri_get_interfaces() -> Vec<network::NetworkInterface> {
-    network::get_local_interfaces()
+async fn tauri_export_backup(path: String) -> Result<backup::BackupSummary, PortlyError> {
+    run_blocking_fallible("导出备份", move || backup::export_backup(&path)).await
 }
 
-/// Tauri 命令: 获取当前子网
+/// Tauri 命令: 读取备份概况（含脚本钩子命令），导入前展示给用户确认
 #[tauri::command]
-fn tauri_get_current_subnet() -> Option<String> {
-    network::get_current_subnet()
+async fn tauri_preview_backup(path: String) -> Result<backup::BackupPreview, PortlyError> {
+    run_blocking_fallible("读取备份", move || backup::preview_backup(&path)).await
 }
 
-/// Tauri 命令: 发现局域网设备（异步）
+/// Tauri 命令: 从备份恢复，恢复后按新的设置推送 `settings-changed` 并重启后台监控
+///
+/// 备份中的脚本钩子只有 `with_hooks` 为真时才安装
 #[tauri::command]
-async fn tauri_discover_devices(subnet: String) -> Result<Vec<network::NetworkDevice>, String> {
-    let subnet = to_tauri_error(normalize_subnet(&subnet, "子网"))?;
-    run_blocking_to_tauri("局域网设备发现", move || network::discover_devices(&subnet)).await
+async fn tauri_import_backup(
+    app: tauri::AppHandle,
+    path: String,
+    with_hooks: Option<bool>,
+) -> Result<backup::BackupImportResult, PortlyError> {
+    let with_hooks = with_hooks.unwrap_or(false);
+    let result = run_blocking_fallible("导入备份", move || {
+        backup::import_backup(&path, with_hooks)
+    })
+    .await?;
+    let restored = settings::current();
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &restored);
+    if restored.monitor.enabled {
+        start_monitor(app.clone());
+    } else {
+        stop_monitor(&app);
+    }
+    tray::refresh(&app);
+    Ok(result)
 }
 
-/// Tauri 命令: 智能扫描（异步）
-#[tauri::command]
-async fn tauri_smart_scan(subnet: String) -> Result<advanced_scan::AdvancedScanResult, String> {
-    let subnet = to_tauri_error(normalize_subnet(&subnet, "子网"))?;
-    run_blocking_to_tauri("高级网段扫描", move || advanced_scan::smart_scan(&subnet)).await
+// ===== 定时任务命令 =====
+
+/// 启动定时任务调度线程，随应用常驻
+fn start_scheduler(app: tauri::AppHandle) {
+    let manager = app.state::<ScanManager>();
+    let Ok(task) = manager.begin(TaskKind::Scheduler, "本机") else {
+        return;
+    };
+    std::thread::spawn(move || {
+        scheduler::watch(task.cancel_flag(), |run| {
+            let _ = app.emit(SCHEDULER_RUN_EVENT, run);
+        });
+        task.finish(&Ok(()));
+    });
 }
 
-/// Tauri 命令: 检查是否有高级扫描权限
+/// Tauri 命令: 读取全部定时任务
 #[tauri::command]
-fn tauri_check_permission() -> bool {
-    advanced_scan::check_raw_socket_permission()
+async fn tauri_list_jobs() -> Result<Vec<scheduler::ScheduledJob>, PortlyError> {
+    run_blocking_to_tauri("读取定时任务", scheduler::current).await
 }
 
-/// Tauri 命令: 快速端口扫描（异步）
+/// Tauri 命令: 新增或更新定时任务（ID 为空时新增），返回保存后的全部任务
 #[tauri::command]
-async fn tauri_quick_scan(ip: String) -> Result<Vec<network::RemotePort>, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
-    run_blocking_to_tauri("常用端口扫描", move || network::quick_scan(&ip)).await
+async fn tauri_save_job(
+    job: scheduler::ScheduledJob,
+) -> Result<Vec<scheduler::ScheduledJob>, PortlyError> {
+    run_blocking_fallible("保存定时任务", move || scheduler::upsert_job(job)).await
 }
 
-/// Tauri 命令: 自定义端口扫描（异步）
+/// Tauri 命令: 删除定时任务，返回保存后的全部任务
 #[tauri::command]
-async fn tauri_scan_ports_range(
-    ip: String,
-    start: u16,
-    end: u16,
-    timeout_ms: u64,
-) -> Result<Vec<network::RemotePort>, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
-    let (start, end) = to_tauri_error(normalize_port_range(start, end))?;
-    let timeout_ms = to_tauri_error(normalize_timeout_ms(timeout_ms, "扫描超时"))?;
-    run_blocking_to_tauri(
-        "自定义端口扫描",
-        move || network::full_scan(&ip, start, end, timeout_ms),
-    )
-    .await
+async fn tauri_delete_job(id: String) -> Result<Vec<scheduler::ScheduledJob>, PortlyError> {
+    run_blocking_fallible("删除定时任务", move || scheduler::delete_job(&id)).await
 }
 
-/// Tauri 命令: 获取常用端口列表
+/// Tauri 命令: 立即执行一次定时任务，同样推送 `scheduler-run`
 #[tauri::command]
-fn tauri_get_common_ports() -> Vec<u16> {
-    network::get_common_ports()
+async fn tauri_run_job(
+    app: tauri::AppHandle,
+    id: String,
+) -> Result<scheduler::JobRun, PortlyError> {
+    let run = run_blocking_fallible("执行定时任务", move || {
+        let job = scheduler::find_job(&id)?;
+        Ok(scheduler::run_job(&job, settings::current().lang()))
+    })
+    .await?;
+    let _ = app.emit(SCHEDULER_RUN_EVENT, &run);
+    Ok(run)
 }
 
-/// Tauri 命令: Ping 测试（异步）
-#[tauri::command]
-async fn tauri_ping(ip: String, count: u32) -> Result<network::PingResult, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "Ping 目标"))?;
-    to_tauri_error(ensure_command_available("ping"))?;
-    if count == 0 || count > 100 {
-        return Err(AppError::validation("Ping 次数", "应在 1-100 之间").to_string());
+// ===== 后台监控命令 =====
+
+/// 后台监控状态
+#[derive(Debug, Serialize)]
+struct MonitorStatus {
+    running: bool,
+    snapshot: Option<monitor::MonitorSnapshot>,
+}
+
+/// 启动后台监控线程，已在运行时返回 false
+fn start_monitor(app: tauri::AppHandle) -> bool {
+    let manager = app.state::<ScanManager>();
+    if manager.is_running(TaskKind::Monitor) {
+        return false;
     }
-    run_blocking_to_tauri("Ping 测试", move || network::ping_test(&ip, count)).await
+    let Ok(task) = manager.begin(TaskKind::Monitor, "本机") else {
+        return false;
+    };
+    std::thread::spawn(move || {
+        push_monitor_state(WebhookEvent::MonitorStarted, Msg::WebhookMonitorStarted);
+        monitor::watch(task.cancel_flag(), |tick| {
+            let _ = app.emit(MONITOR_UPDATE_EVENT, &tick.snapshot);
+            notify_alerts(&app, &tick.alerts);
+            publish_events(tick.alerts.iter().map(WebhookPayload::from_alert).collect());
+            run_rule_actions(&app, &tick.rule_hits);
+            tray::refresh(&app);
+        });
+        push_monitor_state(WebhookEvent::MonitorStopped, Msg::WebhookMonitorStopped);
+        task.finish(&Ok(()));
+    });
+    true
 }
 
-/// Tauri 命令: 单次 Ping（异步，用于流式显示）
-#[tauri::command]
-async fn tauri_ping_one(ip: String, seq: u32) -> Result<network::PingOneResult, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "Ping 目标"))?;
-    to_tauri_error(ensure_command_available("ping"))?;
-    run_blocking_to_tauri("单次 Ping", move || network::ping_one(&ip, seq)).await
+/// 把监控启停推送给 Webhook 和事件脚本
+fn push_monitor_state(event: WebhookEvent, title: Msg) {
+    let lang = settings::current().lang();
+    let payload = WebhookPayload::new(event, tr(lang, title), tr(lang, Msg::WebhookMonitorDetail));
+    publish_events(vec![payload]);
 }
 
-/// Tauri 命令: Traceroute（异步）
-#[tauri::command]
-async fn tauri_traceroute(ip: String) -> Result<network::TracerouteResult, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "Traceroute 目标"))?;
-    to_tauri_error(ensure_command_available("traceroute").or_else(|_| ensure_command_available("tracert")))?;
-    run_blocking_to_tauri("Traceroute", move || network::traceroute(&ip)).await
+/// 把监控事件交给 Webhook 和事件脚本，两者都在后台线程执行
+fn publish_events(payloads: Vec<WebhookPayload>) {
+    if payloads.is_empty() {
+        return;
+    }
+    let settings = settings::current();
+    hooks::dispatch(&settings.hooks, payloads.clone());
+    webhook::dispatch(&settings.webhooks, payloads);
 }
 
-/// Tauri 命令: 探测服务类型（异步）
-#[tauri::command]
-async fn tauri_detect_service(ip: String, port: u16) -> Result<network::ServiceInfo, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
-    let port = to_tauri_error(normalize_port(port, "服务检测端口"))?;
-    run_blocking_to_tauri("服务探测", move || network::detect_service_type(&ip, port)).await
+/// 停止后台监控，未运行时返回 false
+fn stop_monitor(app: &tauri::AppHandle) -> bool {
+    app.state::<ScanManager>().cancel_kind(TaskKind::Monitor)
 }
 
-/// Tauri 命令: 批量探测服务（异步）
-#[tauri::command]
-async fn tauri_detect_services(ip: String, ports: Vec<u16>) -> Result<Vec<network::ServiceInfo>, String> {
-    let ip = to_tauri_error(normalize_host(&ip, "目标地址"))?;
-    let mut ports: Vec<u16> = ports
-        .into_iter()
-        .filter_map(|port| (1..=65535).contains(&port).then_some(port))
+fn monitor_running(app: &tauri::AppHandle) -> bool {
+    app.state::<ScanManager>().is_running(TaskKind::Monitor)
+}
+
+/// 按通知偏好把监控提醒发送为系统通知
+fn notify_alerts(app: &tauri::AppHandle, alerts: &[monitor::MonitorAlert]) {
+    let prefs = settings::current().notifications;
+    for alert in alerts.iter().filter(|alert| alert.is_enabled(&prefs)) {
+        if let Err(err) = app
+            .notification()
+            .builder()
+            .title(&alert.title)
+            .body(&alert.body)
+            .show()
+        {
+            tracing::warn!(error = %err, "系统通知发送失败");
+        }
+    }
+}
+
+/// 执行规则触发后的动作
+fn run_rule_actions(app: &tauri::AppHandle, hits: &[rules::RuleHit]) {
+    let webhooks: Vec<WebhookPayload> = hits
+        .iter()
+        .filter(|hit| hit.actions.contains(&rules::RuleAction::Webhook))
+        .map(WebhookPayload::from_rule_hit)
         .collect();
-    if ports.is_empty() {
-        return Ok(vec![]);
+    webhook::dispatch(&settings::current().webhooks, webhooks);
+
+    for hit in hits {
+        if hit.actions.contains(&rules::RuleAction::Log) {
+            tracing::warn!(rule = %hit.rule_name, "{}", hit.body);
+        }
+        if hit.actions.contains(&rules::RuleAction::Notify) {
+            if let Err(err) = app
+                .notification()
+                .builder()
+                .title(&hit.title)
+                .body(&hit.body)
+                .show()
+            {
+                tracing::warn!(error = %err, "系统通知发送失败");
+            }
+        }
+        if let (true, Some(pid)) = (hit.actions.contains(&rules::RuleAction::Kill), hit.pid) {
+            confirm_rule_kill(app.clone(), hit.clone(), pid);
+        }
     }
-    ports.sort_unstable();
-    ports.dedup();
-    run_blocking_to_tauri("批量服务探测", move || network::detect_services(&ip, &ports)).await
 }
 
-// ===== Docker 命令 =====
+/// 弹窗确认后终止规则命中的进程；确认框阻塞，放在单独线程中不影响监控
+fn confirm_rule_kill(app: tauri::AppHandle, hit: rules::RuleHit, pid: u32) {
+    std::thread::spawn(move || {
+        let lang = settings::current().lang();
+        let message = tr_args(lang, Msg::RuleKillConfirm, &[&hit.rule_name, &hit.body]);
+        let confirmed = app
+            .dialog()
+            .message(message)
+            .title(tr(lang, Msg::RuleKillConfirmTitle))
+            .kind(tauri_plugin_dialog::MessageDialogKind::Warning)
+            .buttons(tauri_plugin_dialog::MessageDialogButtons::OkCancel)
+            .blocking_show();
+        if !confirmed {
+            tracing::info!(rule = %hit.rule_name, pid, "用户取消了规则的终止进程动作");
+            return;
+        }
+        // 确认框可能停留很久，期间进程可能已退出、PID 被复用；终止前重新扫描确认
+        match rule_kill_target_holds_port(&hit, pid) {
+            Ok(true) => {}
+            Ok(false) => {
+                tracing::warn!(
+                    rule = %hit.rule_name,
+                    pid,
+                    port = ?hit.port,
+                    "进程已不再占用命中的端口，跳过终止"
+                );
+                return;
+            }
+            Err(err) => {
+                tracing::warn!(
+                    rule = %hit.rule_name,
+                    pid,
+                    error = %err,
+                    "重新扫描端口失败，跳过终止"
+                );
+                return;
+            }
+        }
+        let result = process::kill_process(pid, false);
+        tracing::info!(rule = %hit.rule_name, pid, success = result.success, "规则终止进程");
+        tray::refresh(&app);
+    });
+}
 
-/// Tauri 命令: 检查 Docker 是否可用
+/// 重新扫描本机端口，检查规则命中的 PID 是否仍占用命中的端口；没有端口信息时不终止
+fn rule_kill_target_holds_port(hit: &rules::RuleHit, pid: u32) -> AppResult<bool> {
+    let Some(port) = hit.port else {
+        return Ok(false);
+    };
+    let scan = core::try_scan_ports(false, false)?;
+    Ok(scan
+        .ports
+        .iter()
+        .any(|info| info.port == port && info.pid == pid.to_string()))
+}
+
+/// Tauri 命令: 启动后台监控，每轮结束后推送 `monitor-update`
 #[tauri::command]
-fn tauri_docker_available() -> bool {
-    docker::is_docker_available()
+fn tauri_start_monitor(app: tauri::AppHandle) -> bool {
+    let started = start_monitor(app.clone());
+    tray::refresh(&app);
+    started
 }
 
-/// Tauri 命令: 获取 Docker 容器列表
+/// Tauri 命令: 停止后台监控
 #[tauri::command]
-fn tauri_get_docker_containers() -> Vec<docker::DockerContainer> {
-    docker::get_docker_containers()
+fn tauri_stop_monitor(app: tauri::AppHandle) -> bool {
+    let stopped = stop_monitor(&app);
+    tray::refresh(&app);
+    stopped
 }
 
-/// Tauri 命令: 获取端口的容器信息
+/// Tauri 命令: 获取后台监控是否运行及最近一轮的结果
 #[tauri::command]
-fn tauri_get_docker_port_info(port: u16) -> Option<(String, String)> {
-    docker::get_docker_port_info(port)
+fn tauri_get_monitor_status(app: tauri::AppHandle) -> MonitorStatus {
+    MonitorStatus {
+        running: monitor_running(&app),
+        snapshot: monitor::latest_snapshot(),
+    }
 }
 
-/// Tauri 命令: 解析 IP 或域名
+// ===== 端口实时监听命令 =====
+
+/// Tauri 命令: 启动端口实时监听，每隔 `interval_secs` 秒（默认 2 秒）重新读取监听端口，
+/// 有变化时推送 `port-opened` / `port-closed` / `process-changed`；已在运行时返回 false
 #[tauri::command]
-fn tauri_resolve_target(target: String) -> Result<network::ResolveResult, String> {
-    let target = to_tauri_error(normalize_host(&target, "目标地址"))?;
-    network::resolve_target(&target)
+fn tauri_start_port_watch(
+    app: tauri::AppHandle,
+    interval_secs: Option<u64>,
+) -> Result<bool, PortlyError> {
+    let interval = to_tauri_error(port_watch::interval(
+        interval_secs.unwrap_or(port_watch::DEFAULT_INTERVAL_SECS),
+    ))?;
+    let manager = app.state::<ScanManager>();
+    if manager.is_running(TaskKind::PortWatch) {
+        return Ok(false);
+    }
+    let task = to_tauri_error(manager.begin(TaskKind::PortWatch, "本机"))?;
+    std::thread::spawn(move || {
+        port_watch::watch_ports(task.cancel_flag(), interval, |diff| {
+            if !diff.opened.is_empty() {
+                let _ = app.emit(PORT_OPENED_EVENT, &diff.opened);
+            }
+            if !diff.closed.is_empty() {
+                let _ = app.emit(PORT_CLOSED_EVENT, &diff.closed);
+            }
+            if !diff.changed.is_empty() {
+                let _ = app.emit(PROCESS_CHANGED_EVENT, &diff.changed);
+            }
+        });
+        task.finish(&Ok(()));
+    });
+    Ok(true)
 }
 
-// ===== 进程管理命令 =====
+/// Tauri 命令: 停止端口实时监听，未运行时返回 false
+#[tauri::command]
+fn tauri_stop_port_watch(manager: State<'_, ScanManager>) -> bool {
+    manager.cancel_kind(TaskKind::PortWatch)
+}
 
-/// Tauri 命令: 终止进程
+/// Tauri 命令: 端口实时监听是否在运行
 #[tauri::command]
-fn tauri_kill_process(pid: u32, force: bool) -> process::KillResult {
-    if pid == 0 {
-        return process::KillResult {
-            success: false,
-            pid,
-            message: "进程 ID 不能为 0".to_string(),
-        };
+fn tauri_port_watch_running(manager: State<'_, ScanManager>) -> bool {
+    manager.is_running(TaskKind::PortWatch)
+}
+
+// ===== 网卡流量采样命令 =====
+
+/// Tauri 命令: 启动网卡流量采样，每隔 `interval_secs` 秒（默认 2 秒）读取一次计数器并推送
+/// `interface-stats`；已在运行时返回 false
+#[tauri::command]
+fn tauri_start_interface_watch(
+    app: tauri::AppHandle,
+    interval_secs: Option<u64>,
+) -> Result<bool, PortlyError> {
+    let interval = to_tauri_error(iface_stats::interval(
+        interval_secs.unwrap_or(iface_stats::DEFAULT_INTERVAL_SECS),
+    ))?;
+    let manager = app.state::<ScanManager>();
+    if manager.is_running(TaskKind::InterfaceWatch) {
+        return Ok(false);
     }
-    process::kill_process(pid, force)
+    let task = to_tauri_error(manager.begin(TaskKind::InterfaceWatch, "本机"))?;
+    std::thread::spawn(move || {
+        iface_stats::watch_interfaces(task.cancel_flag(), interval, |sample| {
+            let _ = app.emit(INTERFACE_STATS_EVENT, sample);
+        });
+        task.finish(&Ok(()));
+    });
+    Ok(true)
 }
 
-/// Tauri 命令: 终止占用端口的进程
+/// Tauri 命令: 停止网卡流量采样，未运行时返回 false
 #[tauri::command]
-fn tauri_kill_port(port: u16) -> process::KillResult {
-    let port = match normalize_port(port, "端口") {
-        Ok(v) => v,
-        Err(e) => {
-            return process::KillResult {
-                success: false,
-                pid: 0,
-                message: e.to_string(),
-            }
-        }
-    };
-    process::kill_port_process(port)
+fn tauri_stop_interface_watch(manager: State<'_, ScanManager>) -> bool {
+    manager.cancel_kind(TaskKind::InterfaceWatch)
 }
 
-/// Tauri 命令: 获取进程信息
+/// Tauri 命令: 网卡流量采样是否在运行
 #[tauri::command]
-fn tauri_get_process_info(pid: u32) -> Option<process::ProcessInfo> {
-    process::get_process_info(pid)
+fn tauri_interface_watch_running(manager: State<'_, ScanManager>) -> bool {
+    manager.is_running(TaskKind::InterfaceWatch)
 }
 
-// ===== 导出命令 =====
+// ===== 任务管理命令 =====
 
-/// Tauri 命令: 导出到 CSV
+/// Tauri 命令: 列出运行中和最近结束的扫描任务
 #[tauri::command]
-fn tauri_export_csv(ports: Vec<core::PortInfo>, path: String) -> export::ExportResult {
-    export::export_to_csv(&ports, &path)
+fn tauri_list_tasks(manager: State<'_, ScanManager>) -> Vec<scan_manager::TaskInfo> {
+    manager.list()
 }
 
-/// Tauri 命令: 导出到 JSON
+/// Tauri 命令: 取消扫描任务
 #[tauri::command]
-fn tauri_export_json(scan_result: core::ScanResult, path: String) -> export::ExportResult {
-    export::export_to_json(&scan_result, &path)
+fn tauri_cancel_task(manager: State<'_, ScanManager>, id: u64) -> bool {
+    manager.cancel(id)
 }
 
-/// Tauri 命令: 导出到文本
+// ===== 缓存命令 =====
+
+/// Tauri 命令: 查看主机名、服务探测和 Docker 缓存的条目数
 #[tauri::command]
-fn tauri_export_txt(ports: Vec<core::PortInfo>, path: String) -> export::ExportResult {
-    export::export_to_txt(&ports, &path)
+fn tauri_get_cache_stats(cache: State<'_, Arc<LookupCache>>) -> Vec<CacheStats> {
+    cache.stats()
 }
 
-/// Tauri 命令: 自动导出（使用默认路径）
+/// Tauri 命令: 清空指定类别的缓存，未指定时清空全部
 #[tauri::command]
-fn tauri_export_auto(
-    ports: Vec<core::PortInfo>,
-    scan_result: core::ScanResult,
-    format: String,
-) -> export::ExportResult {
-    let export_format = match format.to_lowercase().as_str() {
-        "csv" => export::ExportFormat::Csv,
-        "json" => export::ExportFormat::Json,
-        "txt" => export::ExportFormat::Txt,
-        _ => export::ExportFormat::Csv,
-    };
-    export::export_auto(&ports, &scan_result, export_format)
+fn tauri_clear_cache(cache: State<'_, Arc<LookupCache>>, kind: Option<CacheKind>) {
+    cache.clear(kind);
 }
 
-/// Tauri 命令: 获取默认导出目录
+// ===== 安全审计命令 =====
+
+/// Tauri 命令: 执行安全审计（暴露面、Docker、弱服务、防火墙）
 #[tauri::command]
-fn tauri_get_export_dir() -> String {
-    export::get_default_export_dir()
-        .to_string_lossy()
-        .to_string()
+async fn tauri_run_audit() -> Result<audit::AuditReport, PortlyError> {
+    run_blocking_fallible("安全审计", audit::run_audit).await
 }
 
-/// Tauri 命令: 获取扫描历史摘要
+/// Tauri 命令: 查询本机防火墙状态
 #[tauri::command]
-fn tauri_get_history_summary() -> Vec<export::HistorySummary> {
-    export::get_history_summary()
+async fn tauri_get_firewall_status() -> Result<firewall::FirewallStatus, PortlyError> {
+    run_blocking_to_tauri("防火墙状态", firewall::firewall_status).await
 }
 
-/// Tauri 命令: 保存扫描到历史
+/// Tauri 命令: 监听端口按宿主机进程 / 容器 / 虚拟机 / 系统服务归类的汇总
 #[tauri::command]
-fn tauri_save_to_history(scan_result: core::ScanResult) -> Result<(), String> {
-    export::save_to_history(&scan_result)
+async fn tauri_listener_ownership() -> Result<ownership::OwnershipSummary, PortlyError> {
+    run_blocking_fallible("端口归属统计", ownership::listener_ownership).await
 }
 
-// ===== DNS 查询命令 =====
+/// Tauri 命令: 按本机 TCP 连接生成服务依赖图，如 3000 上的 node 连接了 5432 和 6379
+#[tauri::command]
+async fn tauri_dependency_graph() -> Result<dependencies::DependencyGraph, PortlyError> {
+    run_blocking_fallible("服务依赖图", dependencies::dependency_graph).await
+}
 
-/// Tauri 命令: DNS 查询
+/// Tauri 命令: 按设置中登记的 nginx / Caddy / Traefik 来源，把公共端口展开为上游后端
 #[tauri::command]
-async fn tauri_dns_query(
-    domain: String,
-    record_type: String,
-    dns_server: Option<String>,
-) -> Result<dns::DnsQueryResult, String> {
-    let domain = to_tauri_error(normalize_host(&domain, "域名"))?;
-    let dns_server = dns_server
-        .map(|s| to_tauri_error(normalize_host(&s, "DNS服务器")))
-        .transpose()?;
-    run_blocking_to_tauri("DNS 查询", move || dns::dns_query(domain, record_type, dns_server)).await
+async fn tauri_proxy_backends() -> Result<proxy::ProxyMapping, PortlyError> {
+    run_blocking_fallible("反向代理映射", proxy::proxy_backends).await
 }
 
-/// Tauri 命令: 获取常用 DNS 服务器列表
+/// Tauri 命令: 局域网可达性报告（对外监听端口 × 防火墙入站规则，可选自扫描）
 #[tauri::command]
-fn tauri_get_dns_servers() -> Vec<dns::DnsServerInfo> {
-    dns::get_popular_dns_servers()
+async fn tauri_exposure_report(
+    self_scan: Option<bool>,
+) -> Result<exposure::ExposureReport, PortlyError> {
+    let self_scan = self_scan.unwrap_or(false);
+    run_blocking_fallible("局域网可达性检查", move || {
+        exposure::run_exposure_report(self_scan)
+    })
+    .await
 }
 
-/// Tauri 命令: 批量 DNS 查询
+/// Tauri 命令: 请设置中的 NAT 外反射服务回连公网端口，检测路由器端口转发是否可用
 #[tauri::command]
-async fn tauri_dns_batch_query(
-    domain: String,
-    record_types: Vec<String>,
-    dns_server: Option<String>,
-) -> Result<Vec<dns::DnsQueryResult>, String> {
-    let domain = to_tauri_error(normalize_host(&domain, "域名"))?;
-    let dns_server = dns_server
-        .map(|s| to_tauri_error(normalize_host(&s, "DNS服务器")))
-        .transpose()?;
-    run_blocking_to_tauri("批量 DNS 查询", move || dns::batch_query(domain, record_types, dns_server)).await
+async fn tauri_check_port_forward(
+    port: u16,
+    host: Option<String>,
+) -> Result<port_forward::PortForwardCheck, PortlyError> {
+    let port = to_tauri_error(normalize_port(port, "端口"))?;
+    run_blocking_fallible("端口转发检测", move || {
+        port_forward::check_port_forward_with_settings(port, host.as_deref())
+    })
+    .await
 }
 
-// ===== Whois 查询命令 =====
+/// Tauri 命令: 导出安全审计报告
+#[tauri::command]
+async fn tauri_export_audit(
+    app: tauri::AppHandle,
+    report: audit::AuditReport,
+    format: String,
+    path: String,
+) -> Result<export::ExportResult, PortlyError> {
+    let format = to_tauri_error(parse_export_format(&format))?;
+    run_blocking_to_tauri("安全审计导出", move || {
+        let mut progress = export_progress_emitter(&app, &path);
+        export::export_audit_with_progress(&report, &format, &path, &mut progress)
+    })
+    .await
+}
 
-/// Tauri 命令: Whois 域名查询
+// ===== 演示模式命令 =====
+
+/// Tauri 命令: 是否处于演示模式（扫描返回内置示例数据）
 #[tauri::command]
-async fn tauri_whois_query(domain: String) -> Result<whois::WhoisResult, String> {
-    let domain = to_tauri_error(normalize_host(&domain, "域名"))?;
-    run_blocking_to_tauri("Whois 查询", move || whois::whois_query(domain)).await
+fn tauri_get_demo_mode() -> bool {
+    demo::is_enabled()
 }
 
-// ===== SSL 证书检查命令 =====
+/// Tauri 命令: 开启或关闭演示模式，切换时清空缓存
+#[tauri::command]
+fn tauri_set_demo_mode(enabled: bool) -> bool {
+    demo::set_enabled(enabled);
+    demo::is_enabled()
+}
 
-/// Tauri 命令: 检查 SSL 证书
+// ===== 日志命令 =====
+
+/// 日志查看默认返回的行数与上限
+const DEFAULT_LOG_LINES: usize = 200;
+const MAX_LOG_LINES: usize = 5000;
+
+/// Tauri 命令: 读取最近的日志（用于排查扫描结果为空等问题）
 #[tauri::command]
-async fn tauri_check_ssl_cert(host: String, port: Option<u16>) -> Result<ssl::SslCertInfo, String> {
-    let host = to_tauri_error(normalize_host(&host, "主机"))?;
-    let port = port.map(|p| to_tauri_error(normalize_port(p, "端口"))).transpose()?;
-    to_tauri_error(ensure_command_available("openssl"))?;
-    run_blocking_to_tauri("SSL 证书检查", move || ssl::check_ssl_cert(host, port)).await
+async fn tauri_get_recent_logs(lines: Option<usize>) -> Result<Vec<String>, PortlyError> {
+    let lines = lines.unwrap_or(DEFAULT_LOG_LINES);
+    if lines == 0 || lines > MAX_LOG_LINES {
+        return Err(AppError::validation("日志行数", "应在 1-5000 之间"));
+    }
+    run_blocking_fallible("读取日志", move || logging::recent_logs(lines)).await
 }
 
-/// Tauri 命令: 批量检查 SSL 证书
+/// Tauri 命令: 获取日志目录
 #[tauri::command]
-async fn tauri_check_ssl_certs(targets: Vec<(String, Option<u16>)>) -> Result<Vec<ssl::SslCertInfo>, String> {
-    let targets: Vec<(String, Option<u16>)> = to_tauri_error(
-        targets
-        .into_iter()
-        .map(|(host, port)| {
-            Ok((
-                normalize_host(&host, "主机")?,
-                port.map(|p| normalize_port(p, "端口")).transpose()?,
-            ))
-        })
-        .collect::<AppResult<Vec<_>>>(),
-    )?;
-    to_tauri_error(ensure_command_available("openssl"))?;
-    run_blocking_to_tauri("批量 SSL 证书检查", move || ssl::check_ssl_certs(targets)).await
+fn tauri_get_log_dir() -> String {
+    logging::log_dir().to_string_lossy().to_string()
+}
+
+// ===== 更新命令 =====
+
+/// Tauri 命令: 查询 GitHub 上的最新发布，返回是否有新版本、下载地址和更新说明
+#[tauri::command]
+async fn tauri_check_update() -> Result<update::UpdateInfo, PortlyError> {
+    run_blocking_fallible("检查更新", || {
+        update::check_update(env!("CARGO_PKG_VERSION"))
+    })
+    .await
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    match logging::init_logging() {
+        Ok(dir) => tracing::info!(
+            log_dir = %dir.display(),
+            version = env!("CARGO_PKG_VERSION"),
+            "Portly 启动"
+        ),
+        Err(err) => eprintln!("{err}"),
+    }
+    if let Err(err) = settings::init_settings() {
+        tracing::warn!(error = %err, "设置文件无效，使用默认设置");
+    }
+
     tauri::Builder::default()
+        .manage(ScanManager::new())
+        .manage(cache::shared())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(|app| {
+            // 扫描任务的进度、结果和结束状态转发为 scan://progress / scan://result / scan://done
+            let events = app.handle().clone();
+            app.state::<ScanManager>().subscribe(move |event| {
+                let _ = events.emit(event.name(), event);
+            });
+            if settings::current().monitor.enabled {
+                start_monitor(app.handle().clone());
+            }
+            start_scheduler(app.handle().clone());
+            tray::create(app.handle())?;
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // 监控运行时关闭窗口只隐藏，监控继续在后台运行
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if monitor_running(window.app_handle())
+                    && settings::current().monitor.keep_running_in_background
+                {
+                    api.prevent_close();
+                    let _ = window.hide();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             tauri_scan_ports,
+            tauri_refresh_port,
             tauri_scan_ports_grouped,
+            tauri_scan_connections,
             tauri_filter_ports,
             // 网络扫描
             tauri_get_interfaces,
+            tauri_add_manual_subnet,
+            tauri_remove_manual_subnet,
+            tauri_list_vpn_peers,
             tauri_get_current_subnet,
+            tauri_subnet_info,
             tauri_discover_devices,
+            tauri_refresh_device,
+            tauri_arp_table,
+            tauri_flush_arp_cache,
+            tauri_get_routes,
+            tauri_route_to,
+            tauri_interface_stats,
+            tauri_device_latency,
+            tauri_discover_neighbors,
+            tauri_discover_dhcp,
             tauri_smart_scan,
             tauri_check_permission,
+            tauri_check_capability,
+            tauri_get_capabilities,
             tauri_quick_scan,
             tauri_scan_ports_range,
             tauri_get_common_ports,
             // 连通性测试
             tauri_ping,
             tauri_ping_one,
+            tauri_compare_ping,
             tauri_traceroute,
+            tauri_check_time_drift,
+            tauri_list_shares,
+            tauri_printer_info,
             // 服务探测
             tauri_detect_service,
             tauri_detect_services,
+            // 探测插件
+            tauri_list_plugins,
+            tauri_reload_plugins,
+            tauri_get_plugins_dir,
             // Docker
             tauri_docker_available,
+            tauri_probe_container_runtimes,
+            tauri_get_container_runtime,
+            tauri_get_docker_host,
+            tauri_set_docker_host,
             tauri_get_docker_containers,
+            tauri_get_compose_groups,
+            tauri_get_compose_location,
+            tauri_start_docker_events,
+            tauri_stop_docker_events,
             tauri_get_docker_port_info,
+            // Kubernetes
+            tauri_get_kube_overview,
+            tauri_get_port_forwards,
             // IP/域名解析
             tauri_resolve_target,
+            tauri_resolve_host_pattern,
             // 进程管理
             tauri_kill_process,
             tauri_kill_port,
@@ -529,11 +2293,32 @@ pub fn run() {
             // 导出
             tauri_export_csv,
             tauri_export_json,
+            tauri_export_ndjson,
+            tauri_export_remote_ndjson,
             tauri_export_txt,
+            tauri_export_markdown,
+            tauri_export_devices_markdown,
+            tauri_export_xlsx,
+            tauri_export_scan,
+            tauri_export_encrypted,
             tauri_export_auto,
+            tauri_export_devices,
+            tauri_export_remote_ports,
+            tauri_export_ping_results,
+            tauri_export_traceroute,
+            tauri_export_with_columns,
+            tauri_get_export_presets,
+            tauri_save_export_preset,
+            tauri_delete_export_preset,
+            tauri_export_with_preset,
             tauri_get_export_dir,
             tauri_get_history_summary,
             tauri_save_to_history,
+            tauri_load_history_entry,
+            tauri_search_history,
+            tauri_port_timeline,
+            tauri_import_history,
+            tauri_diff_history,
             // DNS 查询
             tauri_dns_query,
             tauri_get_dns_servers,
@@ -542,7 +2327,73 @@ pub fn run() {
             tauri_whois_query,
             // SSL 证书检查
             tauri_check_ssl_cert,
-            tauri_check_ssl_certs
+            tauri_check_ssl_certs,
+            tauri_watch_certificate,
+            tauri_unwatch_certificate,
+            tauri_tls_fingerprint,
+            // 配置
+            tauri_get_config,
+            tauri_save_config,
+            tauri_get_config_path,
+            // 设置
+            tauri_get_settings,
+            tauri_save_settings,
+            tauri_test_webhook,
+            tauri_test_hook,
+            tauri_reset_settings,
+            // 规则
+            tauri_list_rules,
+            tauri_save_rule,
+            tauri_delete_rule,
+            // 端口库
+            tauri_list_known_ports,
+            tauri_save_known_port,
+            tauri_delete_known_port,
+            tauri_list_inventory,
+            tauri_tag_target,
+            tauri_untag_target,
+            // 备份
+            tauri_export_backup,
+            tauri_preview_backup,
+            tauri_import_backup,
+            // 定时任务
+            tauri_list_jobs,
+            tauri_save_job,
+            tauri_delete_job,
+            tauri_run_job,
+            // 后台监控
+            tauri_start_monitor,
+            tauri_stop_monitor,
+            tauri_get_monitor_status,
+            tauri_start_port_watch,
+            tauri_stop_port_watch,
+            tauri_port_watch_running,
+            tauri_start_interface_watch,
+            tauri_stop_interface_watch,
+            tauri_interface_watch_running,
+            // 任务管理
+            tauri_list_tasks,
+            tauri_cancel_task,
+            // 缓存
+            tauri_get_cache_stats,
+            tauri_clear_cache,
+            // 安全审计
+            tauri_run_audit,
+            tauri_get_firewall_status,
+            tauri_listener_ownership,
+            tauri_dependency_graph,
+            tauri_proxy_backends,
+            tauri_exposure_report,
+            tauri_check_port_forward,
+            tauri_export_audit,
+            // 演示模式
+            tauri_get_demo_mode,
+            tauri_set_demo_mode,
+            // 日志
+            tauri_get_recent_logs,
+            tauri_get_log_dir,
+            // 更新
+            tauri_check_update
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -553,6 +2404,13 @@ mod tests {
     use super::*;
     use std::env;
 
+    /// 托管了 `ScanManager` 的测试 App，供需要 `State` 的命令使用
+    fn mock_app() -> tauri::App<tauri::test::MockRuntime> {
+        let app = tauri::test::mock_app();
+        app.manage(ScanManager::new());
+        app
+    }
+
     #[test]
     fn test_normalize_host_accepts_ip() {
         let host = normalize_host(" 127.0.0.1 ", "目标地址").unwrap();
@@ -581,7 +2439,10 @@ mod tests {
     #[test]
     fn test_normalize_subnet_invalid_format() {
         let err = normalize_subnet("192.168.1.1", "子网").unwrap_err();
-        assert_eq!(err.to_string(), "子网 校验失败：需要 CIDR 格式，例如 192.168.1.0/24");
+        assert_eq!(
+            err.to_string(),
+            "子网 校验失败：需要 CIDR 格式，例如 192.168.1.0/24"
+        );
     }
 
     #[test]
@@ -605,7 +2466,10 @@ mod tests {
     #[test]
     fn test_normalize_port_range_reject_start_gt_end() {
         let err = normalize_port_range(2000, 1000).unwrap_err();
-        assert_eq!(err.to_string(), "端口范围 校验失败：起始端口不能大于结束端口");
+        assert_eq!(
+            err.to_string(),
+            "端口范围 校验失败：起始端口不能大于结束端口"
+        );
     }
 
     #[test]
@@ -615,15 +2479,25 @@ mod tests {
     }
 
     #[test]
-    fn test_to_tauri_error_is_string() {
+    fn test_to_tauri_error_keeps_kind() {
         let err = to_tauri_error(normalize_port_range(0, 0)).unwrap_err();
-        assert_eq!(err, "端口范围 校验失败：端口号必须大于 0");
+        assert_eq!(err.kind(), PortlyErrorKind::Validation);
+        assert_eq!(err.to_string(), "端口范围 校验失败：端口号必须大于 0");
+    }
+
+    #[test]
+    fn test_to_tauri_error_wraps_string_error_as_internal() {
+        let err = to_tauri_error(Err::<(), String>("历史记录不存在".to_string())).unwrap_err();
+        assert_eq!(err.kind(), PortlyErrorKind::Internal);
+        assert_eq!(err.to_string(), "内部错误：历史记录不存在");
     }
 
     #[test]
     fn test_ensure_command_available_not_found() {
-        let err = to_tauri_error(ensure_command_available("a_command_that_not_exists_12345")).unwrap_err();
-        assert!(err.contains("不可用"));
+        let err = to_tauri_error(ensure_command_available("a_command_that_not_exists_12345"))
+            .unwrap_err();
+        assert_eq!(err.kind(), PortlyErrorKind::ToolMissing);
+        assert!(err.to_string().contains("不可用"));
     }
 
     #[test]
@@ -643,7 +2517,7 @@ mod tests {
         let err = rt
             .block_on(tauri_dns_query(" ".to_string(), "A".to_string(), None))
             .unwrap_err();
-        assert_eq!(err, "域名 校验失败：不能为空");
+        assert_eq!(err.to_string(), "域名 校验失败：不能为空");
     }
 
     #[test]
@@ -655,36 +2529,52 @@ mod tests {
         let err = rt
             .block_on(tauri_whois_query(" ".to_string()))
             .unwrap_err();
-        assert_eq!(err, "域名 校验失败：不能为空");
+        assert_eq!(err.to_string(), "域名 校验失败：不能为空");
     }
 
     #[test]
     fn test_tauri_scan_ports_range_reject_invalid_range() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
             .block_on(tauri_scan_ports_range(
+                app.state(),
                 "127.0.0.1".to_string(),
                 2000,
                 1000,
                 3000,
             ))
             .unwrap_err();
-        assert_eq!(err, "端口范围 校验失败：起始端口不能大于结束端口");
+        assert_eq!(
+            err.to_string(),
+            "端口范围 校验失败：起始端口不能大于结束端口"
+        );
+    }
+
+    #[test]
+    fn test_tauri_get_recent_logs_rejects_invalid_lines() {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let err = rt.block_on(tauri_get_recent_logs(Some(0))).unwrap_err();
+        assert_eq!(err.to_string(), "日志行数 校验失败：应在 1-5000 之间");
     }
 
     #[test]
     fn test_tauri_ping_rejects_invalid_count() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_ping("127.0.0.1".to_string(), 0))
+            .block_on(tauri_ping(app.state(), "127.0.0.1".to_string(), 0))
             .unwrap_err();
-        assert_eq!(err, "Ping 次数 校验失败：应在 1-100 之间");
+        assert_eq!(err.to_string(), "Ping 次数 校验失败：应在 1-100 之间");
     }
 
     #[test]
@@ -696,7 +2586,7 @@ mod tests {
         let err = rt
             .block_on(tauri_check_ssl_cert("127.0.0.1".to_string(), Some(0)))
             .unwrap_err();
-        assert_eq!(err, "端口 校验失败：必须大于 0");
+        assert_eq!(err.to_string(), "端口 校验失败：必须大于 0");
     }
 
     #[test]
@@ -712,7 +2602,7 @@ mod tests {
                 Some("bad host!".to_string()),
             ))
             .unwrap_err();
-        assert_eq!(err, "DNS服务器 校验失败：格式不正确");
+        assert_eq!(err.to_string(), "DNS服务器 校验失败：格式不正确");
     }
 
     #[test]
@@ -727,79 +2617,104 @@ mod tests {
                 ("example.com".to_string(), Some(0)),
             ]))
             .unwrap_err();
-        assert_eq!(err, "主机 校验失败：不能为空");
+        assert_eq!(err.to_string(), "主机 校验失败：不能为空");
     }
 
     #[test]
     fn test_tauri_discover_devices_rejects_invalid_subnet() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_discover_devices("10.0.0.1".to_string()))
+            .block_on(tauri_discover_devices(app.state(), "10.0.0.1".to_string()))
             .unwrap_err();
-        assert_eq!(err, "子网 校验失败：需要 CIDR 格式，例如 192.168.1.0/24");
+        assert_eq!(
+            err.to_string(),
+            "子网 校验失败：需要 CIDR 格式，例如 192.168.1.0/24"
+        );
     }
 
     #[test]
     fn test_tauri_detect_services_accepts_empty_ports() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let result = rt
-            .block_on(tauri_detect_services("127.0.0.1".to_string(), vec![]))
+            .block_on(tauri_detect_services(
+                app.state(),
+                "127.0.0.1".to_string(),
+                vec![],
+            ))
             .unwrap();
         assert!(result.is_empty());
     }
 
     #[test]
     fn test_tauri_detect_services_rejects_invalid_host() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_detect_services("bad host!".to_string(), vec![80, 443]))
+            .block_on(tauri_detect_services(
+                app.state(),
+                "bad host!".to_string(),
+                vec![80, 443],
+            ))
             .unwrap_err();
-        assert_eq!(err, "目标地址 校验失败：格式不正确");
+        assert_eq!(err.to_string(), "目标地址 校验失败：格式不正确");
     }
 
     #[test]
     fn test_tauri_smart_scan_rejects_invalid_subnet() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_smart_scan("10.0.0.1".to_string()))
+            .block_on(tauri_smart_scan(
+                app.state(),
+                "10.0.0.1".to_string(),
+                None,
+                None,
+            ))
             .unwrap_err();
-        assert_eq!(err, "子网 校验失败：需要 CIDR 格式，例如 192.168.1.0/24");
+        assert_eq!(
+            err.to_string(),
+            "子网 校验失败：需要 CIDR 格式，例如 192.168.1.0/24"
+        );
     }
 
     #[test]
     fn test_tauri_traceroute_rejects_empty_target() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_traceroute(" ".to_string()))
+            .block_on(tauri_traceroute(app.state(), " ".to_string()))
             .unwrap_err();
-        assert_eq!(err, "Traceroute 目标 校验失败：不能为空");
+        assert_eq!(err.to_string(), "Traceroute 目标 校验失败：不能为空");
     }
 
     #[test]
     fn test_tauri_ping_rejects_invalid_target() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_ping(" bad host ".to_string(), 1))
+            .block_on(tauri_ping(app.state(), " bad host ".to_string(), 1))
             .unwrap_err();
-        assert_eq!(err, "Ping 目标 校验失败：格式不正确");
+        assert_eq!(err.to_string(), "Ping 目标 校验失败：格式不正确");
     }
 
     #[test]
@@ -811,7 +2726,7 @@ mod tests {
         let err = rt
             .block_on(tauri_ping_one("".to_string(), 1))
             .unwrap_err();
-        assert_eq!(err, "Ping 目标 校验失败：不能为空");
+        assert_eq!(err.to_string(), "Ping 目标 校验失败：不能为空");
     }
 
     #[test]
@@ -823,7 +2738,7 @@ mod tests {
         let err = rt
             .block_on(tauri_detect_service("127.0.0.1".to_string(), 0))
             .unwrap_err();
-        assert_eq!(err, "服务检测端口 校验失败：必须大于 0");
+        assert_eq!(err.to_string(), "服务检测端口 校验失败：必须大于 0");
     }
 
     #[test]
@@ -835,7 +2750,7 @@ mod tests {
         let err = rt
             .block_on(tauri_check_ssl_certs(vec![("example.com".to_string(), Some(0))]))
             .unwrap_err();
-        assert_eq!(err, "端口 校验失败：必须大于 0");
+        assert_eq!(err.to_string(), "端口 校验失败：必须大于 0");
     }
 
     #[test]
@@ -850,31 +2765,34 @@ mod tests {
 
     #[test]
     fn test_tauri_quick_scan_rejects_invalid_host() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
-            .block_on(tauri_quick_scan("..bad".to_string()))
+            .block_on(tauri_quick_scan(app.state(), "..bad".to_string()))
             .unwrap_err();
-        assert_eq!(err, "目标地址 校验失败：格式不正确");
+        assert_eq!(err.to_string(), "目标地址 校验失败：格式不正确");
     }
 
     #[test]
     fn test_tauri_scan_ports_range_rejects_zero_timeout() {
+        let app = mock_app();
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         let err = rt
             .block_on(tauri_scan_ports_range(
+                app.state(),
                 "127.0.0.1".to_string(),
                 80,
                 81,
                 0,
             ))
             .unwrap_err();
-        assert_eq!(err, "扫描超时 校验失败：不能为 0");
+        assert_eq!(err.to_string(), "扫描超时 校验失败：不能为 0");
     }
 
     #[test]
@@ -890,7 +2808,7 @@ mod tests {
                 Some("bad host".to_string()),
             ))
             .unwrap_err();
-        assert_eq!(err, "DNS服务器 校验失败：格式不正确");
+        assert_eq!(err.to_string(), "DNS服务器 校验失败：格式不正确");
     }
 
     #[test]
@@ -0,0 +1,320 @@
+//! 系统托盘
+//!
+//! 托盘菜单展示后台监控的最新结果：监听端口与容器数量、最近变化的端口，
+//! 以及按端口终止进程的快捷入口。监控每轮结束或启停后重建菜单
+
+use crate::{monitor, process, settings};
+use portly_core::i18n::{tr, tr_args, Lang, Msg};
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const TRAY_ID: &str = "portly";
+
+/// 菜单项 ID
+const MENU_SHOW: &str = "show";
+const MENU_TOGGLE_MONITOR: &str = "toggle-monitor";
+const MENU_QUIT: &str = "quit";
+const KILL_PORT_PREFIX: &str = "kill-port:";
+
+/// 子菜单最多显示的条目数
+const MAX_RECENT_ITEMS: usize = 8;
+const MAX_KILL_ITEMS: usize = 20;
+
+/// 创建托盘图标
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let lang = settings::current().lang();
+    let snapshot = monitor::latest_snapshot();
+    let menu = build_menu(app, snapshot.as_ref(), lang)?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .tooltip(tooltip(
+            snapshot.as_ref(),
+            crate::monitor_running(app),
+            lang,
+        ))
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// 按最新的监控结果重建托盘菜单
+pub fn refresh(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let lang = settings::current().lang();
+    let snapshot = monitor::latest_snapshot();
+    let result = build_menu(app, snapshot.as_ref(), lang)
+        .and_then(|menu| tray.set_menu(Some(menu)))
+        .and_then(|_| {
+            tray.set_tooltip(Some(tooltip(
+                snapshot.as_ref(),
+                crate::monitor_running(app),
+                lang,
+            )))
+        });
+    if let Err(err) = result {
+        tracing::warn!(error = %err, "托盘菜单更新失败");
+    }
+}
+
+fn build_menu(
+    app: &AppHandle,
+    snapshot: Option<&monitor::MonitorSnapshot>,
+    lang: Lang,
+) -> tauri::Result<Menu<tauri::Wry>> {
+    let running = crate::monitor_running(app);
+    let no_accel = None::<&str>;
+
+    let summary = MenuItem::with_id(
+        app,
+        "summary",
+        summary_text(snapshot, running, lang),
+        false,
+        no_accel,
+    )?;
+
+    let recent = Submenu::with_id(app, "recent", tr(lang, Msg::TrayRecentChanges), true)?;
+    let recent_items = recent_labels(snapshot);
+    if recent_items.is_empty() {
+        recent.append(&MenuItem::with_id(
+            app,
+            "recent:none",
+            tr(lang, Msg::TrayNoChanges),
+            false,
+            no_accel,
+        )?)?;
+    }
+    for (index, label) in recent_items.into_iter().enumerate() {
+        recent.append(&MenuItem::with_id(
+            app,
+            format!("recent:{index}"),
+            label,
+            false,
+            no_accel,
+        )?)?;
+    }
+
+    let kill = Submenu::with_id(app, "kill", tr(lang, Msg::TrayKillPort), true)?;
+    let kill_items = kill_entries(snapshot, lang);
+    if kill_items.is_empty() {
+        kill.append(&MenuItem::with_id(
+            app,
+            "kill:none",
+            tr(lang, Msg::TrayNoPorts),
+            false,
+            no_accel,
+        )?)?;
+    }
+    for (port, label) in kill_items {
+        kill.append(&MenuItem::with_id(
+            app,
+            format!("{KILL_PORT_PREFIX}{port}"),
+            label,
+            true,
+            no_accel,
+        )?)?;
+    }
+
+    let toggle_label = if running {
+        tr(lang, Msg::TrayStopMonitor)
+    } else {
+        tr(lang, Msg::TrayStartMonitor)
+    };
+    let show = MenuItem::with_id(
+        app,
+        MENU_SHOW,
+        tr(lang, Msg::TrayShowWindow),
+        true,
+        no_accel,
+    )?;
+    let toggle = MenuItem::with_id(app, MENU_TOGGLE_MONITOR, toggle_label, true, no_accel)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, tr(lang, Msg::TrayQuit), true, no_accel)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &summary,
+            &PredefinedMenuItem::separator(app)?,
+            &recent,
+            &kill,
+            &PredefinedMenuItem::separator(app)?,
+            &show,
+            &toggle,
+            &quit,
+        ],
+    )
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        MENU_SHOW => show_main_window(app),
+        MENU_TOGGLE_MONITOR => {
+            if !crate::stop_monitor(app) {
+                crate::start_monitor(app.clone());
+            }
+            refresh(app);
+        }
+        MENU_QUIT => {
+            crate::stop_monitor(app);
+            app.exit(0);
+        }
+        id => {
+            if let Some(port) = parse_kill_port(id) {
+                kill_port(app.clone(), port);
+            }
+        }
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// 在后台线程终止占用端口的进程，结果以系统通知展示
+fn kill_port(app: AppHandle, port: u16) {
+    std::thread::spawn(move || {
+        let result = process::kill_port_process(port);
+        let lang = settings::current().lang();
+        if let Err(err) = app
+            .notification()
+            .builder()
+            .title(tr_args(lang, Msg::TrayKillPortTitle, &[&port]))
+            .body(&result.message)
+            .show()
+        {
+            tracing::warn!(error = %err, "系统通知发送失败");
+        }
+        refresh(&app);
+    });
+}
+
+/// 菜单顶部的摘要
+fn summary_text(snapshot: Option<&monitor::MonitorSnapshot>, running: bool, lang: Lang) -> String {
+    match snapshot {
+        Some(snapshot) if running => {
+            let ports = snapshot.listening_ports.len();
+            match snapshot.container_count {
+                Some(containers) => tr_args(lang, Msg::TraySummary, &[&ports, &containers]),
+                None => tr_args(lang, Msg::TraySummaryNoContainers, &[&ports]),
+            }
+        }
+        _ => tr(lang, Msg::TrayMonitorStopped).to_string(),
+    }
+}
+
+fn tooltip(snapshot: Option<&monitor::MonitorSnapshot>, running: bool, lang: Lang) -> String {
+    format!("Portly · {}", summary_text(snapshot, running, lang))
+}
+
+/// 最近变化的端口，如 `+ 3000 node 12:30:05`
+fn recent_labels(snapshot: Option<&monitor::MonitorSnapshot>) -> Vec<String> {
+    snapshot
+        .map(|snapshot| {
+            snapshot
+                .recent_changes
+                .iter()
+                .take(MAX_RECENT_ITEMS)
+                .map(|change| {
+                    let sign = if change.opened { '+' } else { '-' };
+                    // 时间只保留时分秒
+                    let time = change.time.get(11..).unwrap_or(&change.time);
+                    format!("{sign} {} {} {time}", change.port, change.process)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 可终止的端口：有 PID 的监听端口
+fn kill_entries(snapshot: Option<&monitor::MonitorSnapshot>, lang: Lang) -> Vec<(u16, String)> {
+    snapshot
+        .map(|snapshot| {
+            snapshot
+                .listening_ports
+                .iter()
+                .filter(|port| port.pid.parse::<u32>().is_ok())
+                .take(MAX_KILL_ITEMS)
+                .map(|port| {
+                    let owner = tr_args(lang, Msg::MonitorPortOwner, &[&port.process, &port.pid]);
+                    (port.port, format!("{} · {owner}", port.port))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_kill_port(id: &str) -> Option<u16> {
+    id.strip_prefix(KILL_PORT_PREFIX)?
+        .parse::<u16>()
+        .ok()
+        .filter(|port| *port != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::PortInfo;
+    use crate::monitor::{MonitorSnapshot, PortChange};
+
+    fn snapshot() -> MonitorSnapshot {
+        let port = |port: u16, pid: &str| PortInfo {
+            port,
+            protocol: "TCP".to_string(),
+            address: "0.0.0.0".to_string(),
+            pid: pid.to_string(),
+            process: "node".to_string(),
+            user: "me".to_string(),
+            command: None,
+        };
+        MonitorSnapshot {
+            listening_ports: vec![port(3000, "42"), port(5353, "-")],
+            container_count: Some(2),
+            recent_changes: vec![PortChange {
+                port: 3000,
+                process: "node".to_string(),
+                pid: "42".to_string(),
+                opened: true,
+                time: "2024-01-01 12:30:05".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tray_labels_follow_snapshot() {
+        let snapshot = snapshot();
+        assert_eq!(
+            summary_text(Some(&snapshot), true, Lang::En),
+            "2 listening ports · 2 running containers"
+        );
+        assert_eq!(
+            summary_text(Some(&snapshot), false, Lang::En),
+            "Background monitor is off"
+        );
+        assert_eq!(recent_labels(Some(&snapshot)), vec!["+ 3000 node 12:30:05"]);
+        assert_eq!(
+            kill_entries(Some(&snapshot), Lang::En),
+            vec![(3000, "3000 · node (PID 42)".to_string())]
+        );
+        assert!(kill_entries(None, Lang::En).is_empty());
+    }
+
+    #[test]
+    fn test_parse_kill_port() {
+        assert_eq!(parse_kill_port("kill-port:8080"), Some(8080));
+        assert_eq!(parse_kill_port("kill-port:0"), None);
+        assert_eq!(parse_kill_port("kill-port:abc"), None);
+        assert_eq!(parse_kill_port("show"), None);
+    }
+}
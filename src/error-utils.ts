@@ -26,8 +26,46 @@ export function toCommandErrorMessage(error: unknown): string {
   return JSON.stringify(error);
 }
 
+/** 后端 PortlyError 序列化后的 `kind` 字段 */
+export type CommandErrorKind =
+  | "validation"
+  | "tool_missing"
+  | "permission_denied"
+  | "timeout"
+  | "host_unreachable"
+  | "command_failed"
+  | "cancelled"
+  | "internal";
+
+const COMMAND_ERROR_KINDS: readonly CommandErrorKind[] = [
+  "validation",
+  "tool_missing",
+  "permission_denied",
+  "timeout",
+  "host_unreachable",
+  "command_failed",
+  "cancelled",
+  "internal",
+];
+
+const COMMAND_ERROR_HINTS: Partial<Record<CommandErrorKind, string>> = {
+  tool_missing: "请先安装对应的命令行工具",
+  permission_denied: "请尝试以管理员权限运行",
+  host_unreachable: "请检查目标主机与网络连接",
+};
+
+export function toCommandErrorKind(error: unknown): CommandErrorKind | null {
+  if (error === null || typeof error !== "object") {
+    return null;
+  }
+  const kind = (error as Record<string, unknown>).kind;
+  return COMMAND_ERROR_KINDS.find((candidate) => candidate === kind) ?? null;
+}
+
 export function formatCommandErrorMessage(action: string, error: unknown): string {
   const detail = toCommandErrorMessage(error);
-  return `❌ ${action}失败: ${detail}`;
+  const kind = toCommandErrorKind(error);
+  const hint = kind ? COMMAND_ERROR_HINTS[kind] : undefined;
+  return hint ? `❌ ${action}失败: ${detail}（${hint}）` : `❌ ${action}失败: ${detail}`;
 }
 
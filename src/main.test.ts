@@ -1,11 +1,12 @@
 import { describe, it, expect, vi } from "vitest";
 import { invoke } from "@tauri-apps/api/core";
-import { toCommandErrorMessage, formatCommandErrorMessage } from "./error-utils";
+import { toCommandErrorMessage, toCommandErrorKind, formatCommandErrorMessage } from "./error-utils";
 import {
   buildScanLoadingHtml,
   buildSubnetScanEstimateMessage,
   estimateSubnetHostCount,
   estimateScanDurationSeconds,
+  formatBytes,
   formatEstimatedDuration,
   getSubnetInput,
   isValidSubnetRange,
@@ -178,6 +179,12 @@ describe("Subnet Utilities", () => {
     expect(formatEstimatedDuration(45)).toBe("45 秒");
   });
 
+  it("should format byte counts with binary units", () => {
+    expect(formatBytes(512)).toBe("512 B");
+    expect(formatBytes(1536)).toBe("1.5 KB");
+    expect(formatBytes(9876543210)).toBe("9.2 GB");
+  });
+
   it("should render scan loading html with estimate remaining", () => {
     const startAt = Date.now();
     const html = buildScanLoadingHtml("扫描中", startAt, 12);
@@ -324,4 +331,14 @@ describe("Error Message Utilities", () => {
     expect(toCommandErrorMessage(null)).toBe("未知错误");
     expect(toCommandErrorMessage(undefined)).toBe("未知错误");
   });
+
+  it("should read kind from typed backend errors", () => {
+    const err = { kind: "tool_missing", message: "docker 不可用", command: "docker" };
+    expect(toCommandErrorKind(err)).toBe("tool_missing");
+    expect(toCommandErrorKind({ kind: "unknown", message: "x" })).toBeNull();
+    expect(toCommandErrorKind("目标地址 校验失败：不能为空")).toBeNull();
+    expect(formatCommandErrorMessage("容器列表读取", err)).toBe(
+      "❌ 容器列表读取失败: docker 不可用（请先安装对应的命令行工具）",
+    );
+  });
 });
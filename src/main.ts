@@ -1,10 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { openUrl } from "@tauri-apps/plugin-opener";
 import { toCommandErrorMessage, formatCommandErrorMessage } from "./error-utils";
 import { showToast } from "./ui-feedback";
 import {
   buildSubnetScanEstimateMessage,
   estimateScanDurationSeconds,
+  formatBytes,
   getSubnetInput,
   isValidSubnetRange,
 } from "./network-utils";
@@ -39,6 +41,77 @@ interface PortInfo {
   command?: string;
 }
 
+interface PortForwardCheck {
+  port: number;
+  reflector: string;
+  public_host: string;
+  reachable: boolean;
+  rtt_ms: number | null;
+  verdict: "reachable" | "no_listener" | "loopback_only" | "blocked";
+  listener: string | null;
+  listener_addresses: string[];
+  error: string | null;
+}
+
+// 端口转发检测结论的提示文字
+const PORT_FORWARD_HINTS: Record<PortForwardCheck["verdict"], string> = {
+  reachable: "可以从公网访问",
+  no_listener: "本机没有进程监听该端口",
+  loopback_only: "服务只监听回环地址，请改为监听 0.0.0.0 或局域网地址",
+  blocked: "请检查路由器端口转发、防火墙入站规则或运营商封锁",
+};
+
+interface AdvancedScanResult {
+  devices: NetworkDevice[];
+  scan_method: string;
+  scan_time_ms: number;
+  has_permission: boolean;
+  interface?: string;
+  vlan?: number;
+}
+
+interface Neighbor {
+  protocol: "lldp" | "cdp";
+  interface: string;
+  source_mac: string;
+  chassis_id: string | null;
+  system_name: string | null;
+  port_id: string | null;
+  port_description: string | null;
+  vlan: number | null;
+  management_address: string | null;
+  platform: string | null;
+  system_description: string | null;
+  ttl_secs: number | null;
+}
+
+interface NeighborReport {
+  interface: string;
+  listened_secs: number;
+  neighbors: Neighbor[];
+}
+
+interface DhcpOffer {
+  message_type: "offer" | "ack";
+  server_id: string;
+  server_mac: string;
+  offered_ip: string;
+  subnet_mask: string | null;
+  network: string | null;
+  routers: string[];
+  dns_servers: string[];
+  domain: string | null;
+  lease_secs: number | null;
+}
+
+interface DhcpReport {
+  interface: string;
+  listened_secs: number;
+  passive: boolean;
+  servers: DhcpOffer[];
+  rogue_suspected: boolean;
+}
+
 interface ScanResult {
   scan_time: string;
   total_ports: number;
@@ -53,20 +126,105 @@ interface AppGroup {
   command?: string;
 }
 
+type InterfaceKind = "ethernet" | "wifi" | "vpn" | "virtual" | "other" | "manual";
+
 interface NetworkInterface {
   name: string;
   ip: string;
   netmask: string;
   subnet: string;
+  prefix_len: number;
+  kind: InterfaceKind;
 }
 
+const INTERFACE_KIND_LABELS: Record<InterfaceKind, string> = {
+  ethernet: "有线",
+  wifi: "Wi-Fi",
+  vpn: "VPN",
+  virtual: "虚拟",
+  other: "其他",
+  manual: "手动",
+};
+
 interface NetworkDevice {
   ip: string;
   mac?: string;
   hostname?: string;
   is_online: boolean;
+  interface_index?: number;
+  rtt_ms?: number;
+  tags?: string[];
+  note?: string;
+}
+
+interface InventoryEntry {
+  target: string;
+  tags: string[];
+  note?: string;
+  updated_at: string;
 }
 
+interface LatencySample {
+  at: string;
+  rtt_ms: number | null;
+}
+
+interface DeviceLatency {
+  ip: string;
+  hostname: string | null;
+  samples: LatencySample[];
+  last_ms: number | null;
+  avg_ms: number | null;
+  min_ms: number | null;
+  max_ms: number | null;
+  jitter_ms: number | null;
+  loss_percent: number;
+  level: "good" | "fair" | "slow" | "offline";
+}
+
+interface LatencyHeatmap {
+  generated_at: string;
+  devices: DeviceLatency[];
+}
+
+interface VpnPeer {
+  provider: "tailscale" | "wireguard";
+  interface?: string;
+  name: string;
+  ip: string;
+  addresses: string[];
+  hostname?: string;
+  os?: string;
+  online: boolean;
+  endpoint?: string;
+}
+
+const VPN_PROVIDER_LABELS: Record<VpnPeer["provider"], string> = {
+  tailscale: "Tailscale",
+  wireguard: "WireGuard",
+};
+
+type OwnerKind = "host" | "container" | "vm" | "system";
+
+interface OwnershipBucket {
+  kind: OwnerKind;
+  listeners: number;
+  ports: number[];
+}
+
+interface OwnershipSummary {
+  generated_at: string;
+  total: number;
+  buckets: OwnershipBucket[];
+}
+
+const OWNER_KINDS: Record<OwnerKind, { label: string; icon: string; color: string }> = {
+  host: { label: "宿主机进程", icon: "💻", color: "var(--accent)" },
+  container: { label: "容器", icon: "🐳", color: "var(--green)" },
+  vm: { label: "虚拟机", icon: "🖥️", color: "var(--orange)" },
+  system: { label: "系统服务", icon: "⚙️", color: "var(--text-tertiary)" },
+};
+
 interface RemotePort {
   port: number;
   is_open: boolean;
@@ -80,14 +238,28 @@ interface DockerPort {
   host_ip: string;
 }
 
+type ContainerHealth = "healthy" | "unhealthy" | "starting";
+
 interface DockerContainer {
   id: string;
   name: string;
   image: string;
   status: string;
   ports: DockerPort[];
+  health?: ContainerHealth | null;
 }
 
+/** 映射到宿主机端口的容器 */
+interface DockerPortOwner {
+  name: string;
+  health: ContainerHealth | null;
+}
+
+const CONTAINER_HEALTH_BADGES: Partial<Record<ContainerHealth, { label: string; title: string }>> = {
+  unhealthy: { label: "不健康", title: "容器健康检查失败，端口背后的服务可能不可用" },
+  starting: { label: "启动中", title: "容器健康检查尚未通过" },
+};
+
 // ===== 进程管理类型 =====
 interface KillResult {
   success: boolean;
@@ -193,6 +365,9 @@ const pageSsl = document.getElementById("page-ssl") as HTMLDivElement;
 const viewTableBtn = document.getElementById("view-table") as HTMLButtonElement;
 const viewGroupBtn = document.getElementById("view-group") as HTMLButtonElement;
 const showCommand = document.getElementById("show-command") as HTMLInputElement;
+const includeUdpToggle = document.getElementById("include-udp") as HTMLInputElement;
+const dualStackToggle = document.getElementById("dual-stack") as HTMLInputElement;
+const watchPortsToggle = document.getElementById("watch-ports") as HTMLInputElement;
 const appFilter = document.getElementById("app-filter") as HTMLInputElement;
 const portFilter = document.getElementById("port-filter") as HTMLInputElement;
 const excludeSystem = document.getElementById("exclude-system") as HTMLInputElement;
@@ -200,6 +375,9 @@ const refreshBtn = document.getElementById("refresh-btn") as HTMLButtonElement;
 const statTime = document.getElementById("stat-time") as HTMLSpanElement;
 const statApps = document.getElementById("stat-apps") as HTMLSpanElement;
 const statPorts = document.getElementById("stat-ports") as HTMLSpanElement;
+const statOwnership = document.getElementById("stat-ownership") as HTMLDivElement;
+const statOwnershipPie = document.getElementById("stat-ownership-pie") as HTMLSpanElement;
+const statOwnershipText = document.getElementById("stat-ownership-text") as HTMLSpanElement;
 const portTable = document.getElementById("port-table") as HTMLTableElement;
 const portTbody = document.getElementById("port-tbody") as HTMLTableSectionElement;
 const groupView = document.getElementById("group-view") as HTMLDivElement;
@@ -207,11 +385,15 @@ const groupView = document.getElementById("group-view") as HTMLDivElement;
 // 网络扫描页面
 const subnetSelect = document.getElementById("subnet-select") as HTMLSelectElement;
 const manualSubnetInput = document.getElementById("manual-subnet") as HTMLInputElement;
+const scanVlanInput = document.getElementById("scan-vlan") as HTMLInputElement;
 const scanDevicesBtn = document.getElementById("scan-devices-btn") as HTMLButtonElement;
 const refreshNetworkBtn = document.getElementById("refresh-network-btn") as HTMLButtonElement;
+const neighborsBtn = document.getElementById("neighbors-btn") as HTMLButtonElement;
+const dhcpBtn = document.getElementById("dhcp-btn") as HTMLButtonElement;
 const netStatDevices = document.getElementById("net-stat-devices") as HTMLSpanElement;
 const deviceCount = document.getElementById("device-count") as HTMLSpanElement;
 const deviceList = document.getElementById("device-list") as HTMLDivElement;
+const deviceTagFilter = document.getElementById("device-tag-filter") as HTMLInputElement;
 const selectedDeviceIp = document.getElementById("selected-device-ip") as HTMLSpanElement;
 const scanType = document.getElementById("scan-type") as HTMLSelectElement;
 const portStart = document.getElementById("port-start") as HTMLInputElement;
@@ -225,8 +407,15 @@ let currentPage: "local" | "network" | "monitor" | "dns" | "whois" | "ssl" = "lo
 let isLoading = false;
 let selectedDevice: NetworkDevice | null = null;
 let discoveredDevices: NetworkDevice[] = [];
+let vpnPeers: VpnPeer[] = [];
+// 设备 IP → 最近若干次发现的延迟，设备列表据此画热力条
+let deviceLatency = new Map<string, DeviceLatency>();
+// 设备 IP → 查询到的打印机型号，设备列表据此标出打印机
+let printerModels = new Map<string, string>();
+// 资产清单：按 IP、主机名或 MAC 记录的标签和备注
+let inventory: InventoryEntry[] = [];
 let sourceFilter: "all" | "local" | "docker" = "all";
-let cachedDockerPorts: Map<number, string> = new Map();
+let cachedDockerPorts: Map<number, DockerPortOwner> = new Map();
 
 // 来源筛选按钮
 const sourceFilterBtns = document.querySelectorAll("#source-filter .segment");
@@ -299,15 +488,17 @@ async function scanPorts() {
 
   try {
     const includeCommand = showCommand.checked;
-    const result: ScanResult = await invoke("tauri_scan_ports", { includeCommand });
+    const includeUdp = includeUdpToggle?.checked ?? false;
+    const dualStack = dualStackToggle?.checked ?? false;
+    const result: ScanResult = await invoke("tauri_scan_ports", { includeCommand, includeUdp, dualStack });
 
     // 获取 Docker 容器端口映射
-    let dockerPorts: Map<number, string> = new Map();
+    let dockerPorts: Map<number, DockerPortOwner> = new Map();
     try {
       const containers: DockerContainer[] = await invoke("tauri_get_docker_containers");
       for (const c of containers) {
         for (const p of c.ports) {
-          dockerPorts.set(p.host_port, c.name);
+          dockerPorts.set(p.host_port, { name: c.name, health: c.health ?? null });
         }
       }
     } catch {
@@ -350,6 +541,7 @@ async function scanPorts() {
     statTime.textContent = result.scan_time.split(' ')[1] || result.scan_time;
     statApps.textContent = uniqueApps.toString();
     statPorts.textContent = filteredPorts.length.toString();
+    void refreshOwnership();
 
     // 保存结果用于导出
     lastScanResult = result;
@@ -367,7 +559,7 @@ async function scanPorts() {
   }
 }
 
-function renderTable(ports: PortInfo[], showCmd: boolean, dockerPorts?: Map<number, string>) {
+function renderTable(ports: PortInfo[], showCmd: boolean, dockerPorts?: Map<number, DockerPortOwner>) {
   portTbody.innerHTML = "";
 
   if (ports.length === 0) {
@@ -383,8 +575,14 @@ function renderTable(ports: PortInfo[], showCmd: boolean, dockerPorts?: Map<numb
     const dockerContainer = dockerPorts?.get(p.port);
     const isDocker = !!dockerContainer;
     const typeIcon = isDocker ? "🐳" : "💻";
-    const processDisplay = isDocker
-      ? `<span class="docker-tag">🐳 ${dockerContainer}</span>`
+    // 健康检查失败或尚未通过时提醒端口背后的服务可能不可用
+    const health = dockerContainer?.health;
+    const healthBadge = health ? CONTAINER_HEALTH_BADGES[health] : undefined;
+    const healthDisplay = healthBadge
+      ? ` <span class="docker-health docker-health-${health}" title="${healthBadge.title}">${healthBadge.label}</span>`
+      : "";
+    const processDisplay = dockerContainer
+      ? `<span class="docker-tag">🐳 ${escapeHtml(dockerContainer.name)}</span>${healthDisplay}`
       : p.process;
 
     // 服务协议检测
@@ -449,8 +647,8 @@ function renderTable(ports: PortInfo[], showCmd: boolean, dockerPorts?: Map<numb
     const cellActions = document.createElement("td");
     cellActions.className = "cell-actions";
 
-    // 创建打开按钮
-    if (service.canOpen) {
+    // 创建打开按钮（UDP 套接字不能用浏览器打开）
+    if (service.canOpen && !p.protocol.startsWith("UDP")) {
       const openBtn = document.createElement("button");
       openBtn.className = "port-open-btn action-btn";
       openBtn.title = "在浏览器中打开";
@@ -503,6 +701,32 @@ function renderTable(ports: PortInfo[], showCmd: boolean, dockerPorts?: Map<numb
     console.log("Kill button created for PID:", p.pid);
     cellActions.appendChild(killBtn);
 
+    // 只刷新这个端口，结果通过 port-refreshed 事件更新表格
+    const refreshPortBtn = document.createElement("button");
+    refreshPortBtn.className = "port-refresh-btn action-btn";
+    refreshPortBtn.title = "只重新读取这个端口";
+    refreshPortBtn.textContent = "↻";
+    refreshPortBtn.addEventListener("click", async (e) => {
+      e.preventDefault();
+      e.stopPropagation();
+      await refreshPort(p.port);
+    });
+    cellActions.appendChild(refreshPortBtn);
+
+    // 端口转发检测：请 NAT 外的反射服务回连该端口
+    if (p.protocol.toUpperCase() === "TCP") {
+      const forwardBtn = document.createElement("button");
+      forwardBtn.className = "port-forward-btn action-btn";
+      forwardBtn.title = "检测能否从公网访问（端口转发）";
+      forwardBtn.textContent = "🌐";
+      forwardBtn.addEventListener("click", async (e) => {
+        e.preventDefault();
+        e.stopPropagation();
+        await checkPortForward(p.port, forwardBtn);
+      });
+      cellActions.appendChild(forwardBtn);
+    }
+
     // 组装行
     row.appendChild(cellPort);
     row.appendChild(cellProtocol);
@@ -523,6 +747,111 @@ function renderTable(ports: PortInfo[], showCmd: boolean, dockerPorts?: Map<numb
   }
 }
 
+// LLDP/CDP 邻居发现：本机接在哪台交换机的哪个端口
+async function discoverNeighbors() {
+  const label = neighborsBtn.textContent;
+  neighborsBtn.disabled = true;
+  neighborsBtn.textContent = "📡 监听中...";
+  try {
+    const report: NeighborReport = await invoke("tauri_discover_neighbors", {});
+    if (report.neighbors.length === 0) {
+      showToast(`${report.interface} 在 ${report.listened_secs} 秒内没有收到 LLDP/CDP 通告`, "info");
+      return;
+    }
+    for (const n of report.neighbors) {
+      const parts = [
+        `${n.interface} → ${n.system_name || n.chassis_id || n.source_mac}`,
+        `端口 ${n.port_id || "-"}`,
+        n.vlan != null ? `VLAN ${n.vlan}` : "",
+        n.management_address || "",
+      ].filter(Boolean);
+      showToast(`🔌 ${parts.join(" · ")} [${n.protocol.toUpperCase()}]`, "success");
+    }
+  } catch (error) {
+    reportCommandError("邻居发现", error);
+  } finally {
+    neighborsBtn.disabled = false;
+    neighborsBtn.textContent = label;
+  }
+}
+
+// DHCP 服务器发现：多台服务器时提示私设 DHCP
+async function discoverDhcpServers() {
+  const label = dhcpBtn.textContent;
+  dhcpBtn.disabled = true;
+  dhcpBtn.textContent = "🏷️ 等待应答...";
+  try {
+    const report: DhcpReport = await invoke("tauri_discover_dhcp", {});
+    if (report.servers.length === 0) {
+      showToast(`${report.interface} 在 ${report.listened_secs} 秒内没有收到 DHCP 应答`, "info");
+      return;
+    }
+    for (const server of report.servers) {
+      const parts = [
+        `${server.server_id} (${server.server_mac})`,
+        `提供 ${server.offered_ip}`,
+        server.network || "",
+        server.routers.length > 0 ? `网关 ${server.routers.join(", ")}` : "",
+        server.dns_servers.length > 0 ? `DNS ${server.dns_servers.join(", ")}` : "",
+      ].filter(Boolean);
+      showToast(`🏷️ ${parts.join(" · ")}`, report.rogue_suspected ? "warning" : "success");
+    }
+    if (report.rogue_suspected) {
+      showToast(`发现 ${report.servers.length} 台 DHCP 服务器，疑似私设 DHCP`, "error");
+    }
+  } catch (error) {
+    reportCommandError("DHCP 服务器发现", error);
+  } finally {
+    dhcpBtn.disabled = false;
+    dhcpBtn.textContent = label;
+  }
+}
+
+// 端口转发检测
+async function checkPortForward(port: number, button: HTMLButtonElement) {
+  button.disabled = true;
+  try {
+    const result: PortForwardCheck = await invoke("tauri_check_port_forward", { port });
+    if (result.reachable) {
+      const rtt = result.rtt_ms != null ? `，${result.rtt_ms.toFixed(1)} ms` : "";
+      showToast(`✅ 端口 ${port} ${PORT_FORWARD_HINTS.reachable}（${result.public_host}${rtt}）`, "success");
+    } else {
+      const reason = result.error ? `（${result.error}）` : "";
+      showToast(`❌ 端口 ${port} 无法从公网访问${reason}：${PORT_FORWARD_HINTS[result.verdict]}`, "error");
+    }
+  } catch (error) {
+    reportCommandError("端口转发检测", error);
+  } finally {
+    button.disabled = false;
+  }
+}
+
+// 端口归属：宿主机进程 / 容器 / 虚拟机 / 系统服务的占比饼图
+async function refreshOwnership() {
+  try {
+    const summary: OwnershipSummary = await invoke("tauri_listener_ownership");
+    const present = summary.buckets.filter(b => b.listeners > 0);
+    let start = 0;
+    const slices = present.map((b) => {
+      const end = start + (b.listeners / summary.total) * 360;
+      const slice = `${OWNER_KINDS[b.kind].color} ${start}deg ${end}deg`;
+      start = end;
+      return slice;
+    });
+    statOwnershipPie.style.background = slices.length
+      ? `conic-gradient(${slices.join(", ")})`
+      : "";
+    statOwnershipText.textContent = present
+      .map(b => `${OWNER_KINDS[b.kind].icon} ${b.listeners}`)
+      .join(" ") || "-";
+    statOwnership.title = summary.buckets
+      .map(b => `${OWNER_KINDS[b.kind].label}: ${b.listeners}`)
+      .join("\n");
+  } catch {
+    // 归属统计只是辅助信息，失败时保留上次的结果
+  }
+}
+
 // 初始化表格点击事件（只执行一次）
 let tableClickHandlerInitialized = false;
 function initTableClickHandler() {
@@ -806,7 +1135,10 @@ async function loadInterfaces() {
     for (const iface of interfaces) {
       const option = document.createElement("option");
       option.value = iface.subnet;
-      option.textContent = iface.ip ? `${iface.subnet} (${iface.ip})` : iface.subnet;
+      const kind = INTERFACE_KIND_LABELS[iface.kind] ?? "";
+      option.textContent = iface.ip
+        ? `${iface.subnet} (${iface.name} · ${kind} · ${iface.ip})`
+        : `${iface.subnet} (${kind})`;
       if (currentSubnet && iface.subnet === currentSubnet) {
         option.selected = true;
       }
@@ -825,6 +1157,12 @@ async function discoverDevices() {
     showToast("⚠️ 子网格式应为 IPv4 CIDR，当前支持 /22~24（如 192.168.1.0/24）", "warning");
     return;
   }
+  const vlanText = scanVlanInput?.value.trim() ?? "";
+  const vlan = vlanText ? Number(vlanText) : null;
+  if (vlan !== null && !(Number.isInteger(vlan) && vlan >= 1 && vlan <= 4094)) {
+    showToast("⚠️ VLAN ID 应为 1-4094", "warning");
+    return;
+  }
 
   const rangeEstimateMessage = buildSubnetScanEstimateMessage(subnet);
   if (rangeEstimateMessage) {
@@ -850,7 +1188,33 @@ async function discoverDevices() {
   }
 
   try {
-    discoveredDevices = await invoke("tauri_discover_devices", { subnet });
+    if (vlan !== null) {
+      // 指定 VLAN 时使用高级 ARP 扫描：有子接口走子接口，否则打 802.1Q 标签
+      const result: AdvancedScanResult = await invoke("tauri_smart_scan", { subnet, vlan });
+      discoveredDevices = result.devices;
+      showToast(`🏷️ ${result.scan_method} · ${result.interface ?? "-"}`, "info");
+    } else {
+      discoveredDevices = await invoke("tauri_discover_devices", { subnet });
+    }
+    try {
+      const heatmap: LatencyHeatmap = await invoke("tauri_device_latency");
+      deviceLatency = new Map(heatmap.devices.map(device => [device.ip, device]));
+    } catch (error) {
+      deviceLatency = new Map();
+      reportCommandError("读取设备延迟", error);
+    }
+    try {
+      vpnPeers = await invoke("tauri_list_vpn_peers");
+    } catch (error) {
+      vpnPeers = [];
+      reportCommandError("读取 VPN 节点", error);
+    }
+    // 手动输入的子网扫描成功后记住，下次出现在接口列表里
+    if (manualSubnetInput.value.trim()) {
+      await invoke("tauri_add_manual_subnet", { subnet }).catch((error) =>
+        reportCommandError("保存手动子网", error),
+      );
+    }
 
     netStatDevices.textContent = discoveredDevices.length.toString();
     deviceCount.textContent = discoveredDevices.length.toString();
@@ -868,8 +1232,76 @@ async function discoverDevices() {
   }
 }
 
+// 热力条最多显示的采样数
+const LATENCY_STRIP_SAMPLES = 20;
+
+function latencyCellClass(rtt: number | null): string {
+  if (rtt === null) return "missing";
+  if (rtt < 10) return "good";
+  if (rtt < 50) return "fair";
+  return "slow";
+}
+
+// 设备最近几次发现的延迟热力条；慢或拥塞的设备额外标出
+function renderLatencyStrip(ip: string): string {
+  const latency = deviceLatency.get(ip);
+  if (!latency || latency.samples.length === 0) return "";
+  const format = (value: number | null) => (value === null ? "-" : `${value.toFixed(1)}ms`);
+  const title = `平均 ${format(latency.avg_ms)} · 最低 ${format(latency.min_ms)} · 最高 ${format(latency.max_ms)} · 抖动 ${format(latency.jitter_ms)} · 丢包 ${latency.loss_percent.toFixed(0)}%`;
+  const cells = latency.samples
+    .slice(-LATENCY_STRIP_SAMPLES)
+    .map(sample => `<span class="latency-cell ${latencyCellClass(sample.rtt_ms)}" title="${sample.at} ${format(sample.rtt_ms)}"></span>`)
+    .join("");
+  return `
+    <div class="latency-strip level-${latency.level}" title="${title}">
+      ${cells}
+      <span class="latency-value">${format(latency.last_ms)}</span>
+    </div>
+  `;
+}
+
+// 小写并去掉 IPv6 方括号，MAC 地址统一用冒号分隔，与后端保存的格式一致
+function normalizeTarget(target: string): string {
+  const value = target.trim().replace(/^\[/, "").replace(/\]$/, "").toLowerCase();
+  return /^([0-9a-f]{2}[:-]){5}[0-9a-f]{2}$/.test(value) ? value.replace(/-/g, ":") : value;
+}
+
+// 按 MAC、IP、主机名依次匹配资产清单：标签取并集，备注和编辑时使用的目标取第一个命中的条目
+function deviceAnnotation(device: NetworkDevice): { target?: string; tags: string[]; note?: string } {
+  const result: { target?: string; tags: string[]; note?: string } = { tags: [] };
+  for (const key of [device.mac, device.ip, device.hostname]) {
+    if (!key) continue;
+    const entry = inventory.find(e => e.target === normalizeTarget(key));
+    if (!entry) continue;
+    result.target = result.target ?? entry.target;
+    result.note = result.note ?? entry.note;
+    entry.tags.filter(tag => !result.tags.includes(tag)).forEach(tag => result.tags.push(tag));
+  }
+  return result;
+}
+
+function matchesTagFilter(device: NetworkDevice): boolean {
+  const filter = deviceTagFilter?.value.trim().toLowerCase();
+  return !filter || deviceAnnotation(device).tags.includes(filter);
+}
+
+function renderDeviceTags(device: NetworkDevice): string {
+  return deviceAnnotation(device)
+    .tags.map(tag => `<span class="device-tag">#${escapeHtml(tag)}</span>`)
+    .join("");
+}
+
+async function loadInventory() {
+  try {
+    inventory = await invoke("tauri_list_inventory");
+  } catch (error) {
+    inventory = [];
+    reportCommandError("读取资产清单", error);
+  }
+}
+
 function renderDeviceList() {
-  if (discoveredDevices.length === 0) {
+  if (discoveredDevices.length === 0 && vpnPeers.length === 0) {
     deviceList.innerHTML = `
       <div class="empty-state">
         <div class="icon">📡</div>
@@ -881,12 +1313,16 @@ function renderDeviceList() {
 
   deviceList.innerHTML = "";
 
-  for (const device of discoveredDevices) {
+  for (const device of discoveredDevices.filter(matchesTagFilter)) {
     const item = document.createElement("div");
     item.className = "device-item";
     if (selectedDevice?.ip === device.ip) {
       item.classList.add("selected");
     }
+    const note = deviceAnnotation(device).note;
+    if (note) {
+      item.title = note;
+    }
 
     item.innerHTML = `
       <div class="device-status ${device.is_online ? 'online' : 'offline'}"></div>
@@ -895,10 +1331,56 @@ function renderDeviceList() {
         <div class="device-details">
           ${device.hostname ? `<span>${device.hostname}</span>` : ""}
           ${device.mac ? `<span class="device-mac">${device.mac}</span>` : ""}
+          ${printerModels.has(device.ip) ? `<span>🖨️ ${escapeHtml(printerModels.get(device.ip)!)}</span>` : ""}
+          ${renderDeviceTags(device)}
         </div>
+        ${renderLatencyStrip(device.ip)}
       </div>
     `;
 
+    item.dataset.ip = device.ip;
+    item.addEventListener("click", () => selectDevice(device));
+    deviceList.appendChild(item);
+  }
+
+  // VPN 覆盖网络中的节点不在本地网段内，单独成组
+  const peers = vpnPeers
+    .map(peer => ({
+      peer,
+      device: { ip: peer.ip, hostname: peer.hostname ?? peer.name, is_online: peer.online } as NetworkDevice,
+    }))
+    .filter(({ device }) => matchesTagFilter(device));
+  if (peers.length > 0) {
+    const title = document.createElement("div");
+    title.className = "device-group-title";
+    title.textContent = `VPN 节点 (${peers.length})`;
+    deviceList.appendChild(title);
+  }
+  for (const { peer, device } of peers) {
+    const item = document.createElement("div");
+    item.className = "device-item";
+    if (selectedDevice?.ip === peer.ip) {
+      item.classList.add("selected");
+    }
+    const source = peer.interface
+      ? `${VPN_PROVIDER_LABELS[peer.provider]} · ${peer.interface}`
+      : VPN_PROVIDER_LABELS[peer.provider];
+
+    item.innerHTML = `
+      <div class="device-status ${peer.online ? 'online' : 'offline'}"></div>
+      <div class="device-info">
+        <div class="device-ip">${peer.ip}</div>
+        <div class="device-details">
+          <span>${peer.name}</span>
+          <span>${source}</span>
+          ${peer.os ? `<span>${peer.os}</span>` : ""}
+          ${peer.endpoint ? `<span class="device-mac">${peer.endpoint}</span>` : ""}
+          ${renderDeviceTags(device)}
+        </div>
+      </div>
+    `;
+
+    item.dataset.ip = peer.ip;
     item.addEventListener("click", () => selectDevice(device));
     deviceList.appendChild(item);
   }
@@ -917,10 +1399,9 @@ function selectDevice(device: NetworkDevice) {
 
   // 更新选中状态
   document.querySelectorAll(".device-item").forEach(el => el.classList.remove("selected"));
-  const index = discoveredDevices.findIndex(d => d.ip === device.ip);
-  if (index >= 0) {
-    deviceList.children[index]?.classList.add("selected");
-  }
+  deviceList
+    .querySelectorAll<HTMLElement>(".device-item")
+    .forEach(el => el.classList.toggle("selected", el.dataset.ip === device.ip));
 
   // 清空端口结果
   portResults.innerHTML = `
@@ -1006,11 +1487,170 @@ function renderPortResults(ports: RemotePort[]) {
         <div class="port-result-item">
           <span class="port-number">${p.port}</span>
           <span class="port-service">${p.service || "未知"}</span>
+          <button class="action-btn detect-btn" data-port="${p.port}" title="探测服务；HTTP 服务同时检查安全响应头">🔍</button>
+          ${SHARE_PORTS.includes(p.port) ? `<button class="action-btn share-btn" data-port="${p.port}" title="列出该主机的 SMB 共享和 NFS 导出">📂</button>` : ""}
+          ${PRINTER_PORTS.includes(p.port) ? `<button class="action-btn printer-btn" data-port="${p.port}" title="查询打印机型号、状态和耗材余量">🖨️</button>` : ""}
           <span class="port-status open">开放</span>
         </div>
       `).join("")}
     </div>
   `;
+  portResults.querySelectorAll<HTMLButtonElement>(".share-btn").forEach(button => {
+    button.addEventListener("click", () => listShares(Number(button.dataset.port), button));
+  });
+  portResults.querySelectorAll<HTMLButtonElement>(".detect-btn").forEach(button => {
+    button.addEventListener("click", () => detectService(Number(button.dataset.port), button));
+  });
+  portResults.querySelectorAll<HTMLButtonElement>(".printer-btn").forEach(button => {
+    button.addEventListener("click", () => queryPrinter(Number(button.dataset.port), button));
+  });
+}
+
+interface HeaderCheck {
+  header: string;
+  status: "ok" | "weak" | "missing";
+  detail?: string;
+}
+
+interface ServiceInfo {
+  port: number;
+  service: string;
+  service_type: string;
+  server: string | null;
+  content_type: string | null;
+  security_headers?: { grade: "A" | "B" | "C" | "D" | "F"; checks: HeaderCheck[] };
+}
+
+// 探测选中设备上的服务，结果写回该端口行；HTTP 服务附上安全响应头评级
+async function detectService(port: number, button: HTMLButtonElement) {
+  if (!selectedDevice) return;
+  button.disabled = true;
+  try {
+    const info: ServiceInfo = await invoke("tauri_detect_service", { ip: selectedDevice.ip, port });
+    const label = button.parentElement?.querySelector(".port-service");
+    if (!label) return;
+    label.textContent = info.server ? `${info.service} · ${info.server}` : info.service;
+    const headers = info.security_headers;
+    if (headers) {
+      const badge = document.createElement("span");
+      badge.className = `header-grade grade-${headers.grade.toLowerCase()}`;
+      badge.textContent = headers.grade;
+      badge.title = headers.checks
+        .filter(check => check.status !== "ok")
+        .map(check => `${check.header}：${check.status === "missing" ? "缺失" : "偏弱"}${check.detail ? `（${check.detail}）` : ""}`)
+        .join("\n") || "安全响应头齐全";
+      label.appendChild(badge);
+    }
+  } catch (error) {
+    reportCommandError("服务探测", error);
+  } finally {
+    button.disabled = false;
+  }
+}
+
+interface SmbShare {
+  name: string;
+  kind: "disk" | "printer" | "ipc" | "other";
+  comment: string;
+  public: boolean;
+}
+
+interface NfsExport {
+  path: string;
+  clients: string[];
+  public: boolean;
+}
+
+interface ShareListing {
+  host: string;
+  smb_shares: SmbShare[];
+  nfs_exports: NfsExport[];
+  errors: string[];
+}
+
+// SMB 445 / NFS 2049
+const SHARE_PORTS = [445, 2049];
+
+// 列出选中设备的 SMB 共享或 NFS 导出，公开的共享以警告提示
+async function listShares(port: number, button: HTMLButtonElement) {
+  if (!selectedDevice) return;
+  button.disabled = true;
+  try {
+    const listing: ShareListing = await invoke("tauri_list_shares", {
+      ip: selectedDevice.ip,
+      smb: port === 445,
+      nfs: port === 2049,
+    });
+    const entries = [
+      ...listing.smb_shares.map(share => ({ name: `\\\\${listing.host}\\${share.name}`, public: share.public })),
+      ...listing.nfs_exports.map(e => ({ name: `${listing.host}:${e.path} (${e.clients.join(", ")})`, public: e.public })),
+    ];
+    for (const error of listing.errors) {
+      showToast(`❌ ${error}`, "error");
+    }
+    if (entries.length === 0) {
+      if (listing.errors.length === 0) showToast(`${listing.host} 上没有可见的共享`, "info");
+      return;
+    }
+    for (const entry of entries) {
+      showToast(`📂 ${entry.name}${entry.public ? " ⚠️ 公开" : ""}`, entry.public ? "warning" : "info");
+    }
+  } catch (error) {
+    reportCommandError("共享枚举", error);
+  } finally {
+    button.disabled = false;
+  }
+}
+
+interface PrinterInfo {
+  host: string;
+  port: number;
+  protocol: "ipp" | "pjl";
+  make_and_model: string | null;
+  name: string | null;
+  location: string | null;
+  info: string | null;
+  state: "idle" | "processing" | "stopped" | "unknown";
+  state_reasons: string[];
+  status_message: string | null;
+  markers: { name: string; level: number | null }[];
+}
+
+// IPP 631 / JetDirect 9100
+const PRINTER_PORTS = [631, 9100];
+
+const PRINTER_STATE_LABELS: Record<PrinterInfo["state"], string> = {
+  idle: "空闲",
+  processing: "正在打印",
+  stopped: "已停止",
+  unknown: "状态未知",
+};
+
+// 查询选中设备的打印机信息，型号记入设备列表
+async function queryPrinter(port: number, button: HTMLButtonElement) {
+  if (!selectedDevice) return;
+  const ip = selectedDevice.ip;
+  button.disabled = true;
+  try {
+    const printer: PrinterInfo = await invoke("tauri_printer_info", { ip, port });
+    const model = printer.make_and_model || printer.name || ip;
+    printerModels.set(ip, model);
+    renderDeviceList();
+
+    const details = [
+      PRINTER_STATE_LABELS[printer.state] + (printer.status_message ? ` (${printer.status_message})` : ""),
+      printer.location,
+      ...printer.markers.map(marker => `${marker.name} ${marker.level === null ? "?" : `${marker.level}%`}`),
+    ].filter(Boolean);
+    showToast(`🖨️ ${model}：${details.join("，")}`, printer.state === "stopped" ? "warning" : "info");
+    for (const reason of printer.state_reasons) {
+      showToast(`⚠️ ${model}：${reason}`, "warning");
+    }
+  } catch (error) {
+    reportCommandError("打印机查询", error);
+  } finally {
+    button.disabled = false;
+  }
 }
 
 // ===== 事件绑定 =====
@@ -1023,17 +1663,67 @@ refreshBtn.addEventListener("click", () => {
 
 scanDevicesBtn.addEventListener("click", discoverDevices);
 refreshNetworkBtn.addEventListener("click", loadInterfaces);
+neighborsBtn?.addEventListener("click", discoverNeighbors);
+dhcpBtn?.addEventListener("click", discoverDhcpServers);
+document.getElementById("arp-btn")?.addEventListener("click", showArpTable);
+document.getElementById("routes-btn")?.addEventListener("click", showRoutes);
+document.getElementById("iface-stats-btn")?.addEventListener("click", showInterfaceStats);
 scanPortsBtn.addEventListener("click", scanRemotePorts);
 
 // 手动添加目标 IP/域名
 const manualTargetInput = document.getElementById("manual-target") as HTMLInputElement;
 const addManualTargetBtn = document.getElementById("add-manual-target");
 
+interface ResolvedAddress {
+  ip: string;
+  family: "ipv4" | "ipv6";
+  rtt_ms?: number;
+}
+
 interface ResolveResult {
   original: string;
   ip: string;
   is_domain: boolean;
   hostname: string | null;
+  addresses?: ResolvedAddress[];
+}
+
+interface PatternHost {
+  name: string;
+  resolved?: ResolveResult;
+  error?: string;
+}
+
+// node{01..20}.lan、{web,db}.lan、host[1-5] 这类主机名模板；[::1] 这样的 IPv6 地址不算
+function isHostPattern(target: string): boolean {
+  return /[{*?]/.test(target) || (target.includes("[") && !target.includes(":"));
+}
+
+// 展开主机名模板并批量解析，每台解析成功的主机按系统优先的地址加入设备列表
+async function addPatternTargets(pattern: string) {
+  try {
+    const hosts: PatternHost[] = await invoke("tauri_resolve_host_pattern", { pattern });
+    const resolved = hosts.filter(host => host.resolved);
+    for (const host of resolved) {
+      const ip = host.resolved!.ip;
+      if (!discoveredDevices.some(d => d.ip === ip)) {
+        discoveredDevices.push({ ip, mac: undefined, hostname: host.name, is_online: true });
+      }
+    }
+    renderDeviceList();
+    manualTargetInput.value = "";
+
+    const failed = hosts.filter(host => !host.resolved);
+    if (failed.length) {
+      console.warn("主机名模板解析失败:", failed.map(host => `${host.name}: ${host.error}`));
+    }
+    showToast(
+      `📋 ${pattern} 展开 ${hosts.length} 台，已添加 ${resolved.length} 台${failed.length ? `，${failed.length} 台解析失败` : ""}`,
+      resolved.length ? "success" : "warning",
+    );
+  } catch (error) {
+    reportCommandError("解析主机名模板", error);
+  }
 }
 
 addManualTargetBtn?.addEventListener("click", async () => {
@@ -1042,6 +1732,10 @@ addManualTargetBtn?.addEventListener("click", async () => {
     showToast("⚠️ 请输入 IP 地址或域名", "warning");
     return;
   }
+  if (isHostPattern(target)) {
+    await addPatternTargets(target);
+    return;
+  }
   if (!isValidHost(target)) {
     showToast("⚠️ 请输入有效的 IP 或域名", "warning");
     return;
@@ -1050,30 +1744,40 @@ addManualTargetBtn?.addEventListener("click", async () => {
   try {
     const result: ResolveResult = await invoke("tauri_resolve_target", { target });
 
-    // 创建设备并添加到列表
-    const device: NetworkDevice = {
-      ip: result.ip,
-      mac: undefined,
-      hostname: result.hostname || (result.is_domain ? result.original : undefined),
-      is_online: true,
-    };
+    // 每个解析出的地址作为一台设备加入列表，选中哪个地址后续扫描就使用哪个
+    const name = result.hostname || (result.is_domain ? result.original : undefined);
+    const addresses = result.addresses?.length
+      ? result.addresses
+      : [{ ip: result.ip, family: result.ip.includes(":") ? "ipv6" : "ipv4" } as ResolvedAddress];
+    const devices: NetworkDevice[] = addresses.map(address => {
+      const rtt = address.rtt_ms !== undefined ? `, ${address.rtt_ms.toFixed(1)}ms` : "";
+      return {
+        ip: address.ip,
+        mac: undefined,
+        hostname: addresses.length > 1 && name
+          ? `${name} (${address.family === "ipv6" ? "IPv6" : "IPv4"}${rtt})`
+          : name,
+        is_online: true,
+      };
+    });
 
     // 检查是否已存在
-    const exists = discoveredDevices.some(d => d.ip === device.ip);
-    if (!exists) {
-      discoveredDevices.push(device);
+    for (const device of devices) {
+      if (!discoveredDevices.some(d => d.ip === device.ip)) {
+        discoveredDevices.push(device);
+      }
     }
 
-    // 刷新列表并选中
+    // 刷新列表并选中系统优先的地址
     renderDeviceList();
-    selectDevice(device);
+    selectDevice(devices.find(d => d.ip === result.ip) ?? devices[0]);
 
     // 清空输入框
     manualTargetInput.value = "";
 
     // 提示用户
     if (result.is_domain) {
-      console.log(`域名 ${result.original} 解析为 ${result.ip}`);
+      console.log(`域名 ${result.original} 解析为 ${addresses.map(a => a.ip).join(", ")}`);
     }
   } catch (error) {
     reportCommandError("解析目标", error);
@@ -1109,6 +1813,147 @@ excludeSystem.addEventListener("change", debouncedScan);
 showCommand.addEventListener("change", () => {
   if (currentView === "table") scanPorts();
 });
+includeUdpToggle?.addEventListener("change", () => {
+  if (currentView === "table") scanPorts();
+});
+dualStackToggle?.addEventListener("change", () => {
+  if (currentView === "table") scanPorts();
+});
+
+// ===== 单个端口 / 设备刷新 =====
+interface PortRefresh {
+  port: number;
+  scan_time: string;
+  ports: PortInfo[];
+}
+
+async function refreshPort(port: number) {
+  try {
+    await invoke("tauri_refresh_port", {
+      port,
+      includeCommand: showCommand.checked,
+      includeUdp: includeUdpToggle?.checked ?? false,
+      dualStack: dualStackToggle?.checked ?? false,
+    });
+  } catch (error) {
+    reportCommandError("刷新端口", error);
+  }
+}
+
+function applyPortRefresh(refresh: PortRefresh) {
+  if (lastScanResult) {
+    lastScanResult.ports = lastScanResult.ports
+      .filter(p => p.port !== refresh.port)
+      .concat(refresh.ports)
+      .sort((a, b) => a.port - b.port);
+  }
+  const index = lastFilteredPorts.findIndex(p => p.port === refresh.port);
+  if (index === -1) return;
+  lastFilteredPorts = lastFilteredPorts.filter(p => p.port !== refresh.port);
+  lastFilteredPorts.splice(index, 0, ...refresh.ports);
+  statPorts.textContent = lastFilteredPorts.length.toString();
+  if (currentView === "table") {
+    renderTable(lastFilteredPorts, showCommand.checked, cachedDockerPorts);
+  }
+  if (refresh.ports.length === 0) {
+    showToast(`端口 ${refresh.port} 已不再监听`, "info");
+  }
+}
+
+async function refreshSelectedDevice() {
+  if (!selectedDevice) return;
+  const button = document.getElementById("refresh-device-btn") as HTMLButtonElement | null;
+  if (button) button.disabled = true;
+  try {
+    await invoke("tauri_refresh_device", { ip: selectedDevice.ip });
+  } catch (error) {
+    reportCommandError("刷新设备", error);
+  } finally {
+    if (button) button.disabled = false;
+  }
+}
+
+function applyDeviceRefresh(device: NetworkDevice) {
+  const index = discoveredDevices.findIndex(d => d.ip === device.ip);
+  if (index === -1) {
+    if (!device.is_online) return;
+    discoveredDevices.push(device);
+  } else {
+    discoveredDevices[index] = device;
+  }
+  if (selectedDevice?.ip === device.ip) {
+    selectedDevice = device;
+  }
+  renderDeviceList();
+  showToast(`${device.ip} ${device.is_online ? "在线" : "离线"}`, device.is_online ? "success" : "info");
+}
+
+void listen<PortRefresh>("port-refreshed", (event) => applyPortRefresh(event.payload));
+void listen<NetworkDevice>("device-refreshed", (event) => applyDeviceRefresh(event.payload));
+
+// ===== 端口实时监听 =====
+interface ProcessChange {
+  port: number;
+  before: PortInfo;
+  after: PortInfo;
+}
+
+let portWatchUnlisten: UnlistenFn[] = [];
+
+function refreshAfterPortChange() {
+  if (currentView === "table") scanPorts();
+  else scanGrouped();
+}
+
+function describePorts(ports: PortInfo[]): string {
+  return ports.map(p => `${p.port} (${p.process})`).join("、");
+}
+
+async function startPortWatch() {
+  portWatchUnlisten = await Promise.all([
+    listen<PortInfo[]>("port-opened", (event) => {
+      showToast(`🟢 新开启端口：${describePorts(event.payload)}`, "info");
+      refreshAfterPortChange();
+    }),
+    listen<PortInfo[]>("port-closed", (event) => {
+      showToast(`⚪ 端口已关闭：${describePorts(event.payload)}`, "info");
+      refreshAfterPortChange();
+    }),
+    listen<ProcessChange[]>("process-changed", (event) => {
+      const changes = event.payload
+        .map(c => `${c.port}: ${c.before.process} (${c.before.pid}) → ${c.after.process} (${c.after.pid})`)
+        .join("、");
+      showToast(`🔄 端口换了进程：${changes}`, "info");
+      refreshAfterPortChange();
+    }),
+  ]);
+  try {
+    await invoke("tauri_start_port_watch", { intervalSecs: 2 });
+  } catch (error) {
+    stopPortWatchListeners();
+    watchPortsToggle.checked = false;
+    reportCommandError("启动端口实时监听", error);
+  }
+}
+
+function stopPortWatchListeners() {
+  portWatchUnlisten.forEach(unlisten => unlisten());
+  portWatchUnlisten = [];
+}
+
+async function stopPortWatch() {
+  stopPortWatchListeners();
+  try {
+    await invoke("tauri_stop_port_watch");
+  } catch (error) {
+    reportCommandError("停止端口实时监听", error);
+  }
+}
+
+watchPortsToggle?.addEventListener("change", () => {
+  if (watchPortsToggle.checked) void startPortWatch();
+  else void stopPortWatch();
+});
 
 // ===== Ping/Traceroute =====
 interface PingResult {
@@ -1139,6 +1984,7 @@ interface TracerouteResult {
 const deviceActions = document.getElementById("device-actions") as HTMLDivElement;
 const pingBtn = document.getElementById("ping-btn") as HTMLButtonElement;
 const traceBtn = document.getElementById("trace-btn") as HTMLButtonElement;
+const ntpBtn = document.getElementById("ntp-btn") as HTMLButtonElement;
 
 // Ping 监测状态
 let pingMonitorInterval: number | null = null;
@@ -1345,6 +2191,500 @@ async function runTraceroute() {
   }
 }
 
+interface NtpServerResult {
+  server: string;
+  address: string | null;
+  reachable: boolean;
+  stratum: number | null;
+  reference_id: string | null;
+  offset_ms: number | null;
+  delay_ms: number | null;
+  error: string | null;
+}
+
+interface TimeDriftReport {
+  checked_at: string;
+  source: "given" | "system" | "default";
+  servers: NtpServerResult[];
+  drift_ms: number | null;
+  level: "ok" | "warning" | "critical" | "unknown";
+}
+
+const DRIFT_HINTS: Record<TimeDriftReport["level"], string> = {
+  ok: "本机时钟正常",
+  warning: "偏差超过 1 秒，建议检查时间同步服务",
+  critical: "偏差超过 30 秒，TLS 证书校验、Kerberos 和 TOTP 验证码可能失败",
+  unknown: "没有应答，设备可能未提供 NTP 服务",
+};
+
+function formatDrift(ms: number): string {
+  return Math.abs(ms) >= 1000 ? `${(ms / 1000).toFixed(3)} s` : `${ms.toFixed(1)} ms`;
+}
+
+// 把选中的设备当作 NTP 服务器查询，比较本机时钟
+async function runTimeDrift() {
+  if (!selectedDevice) return;
+  if (!isValidHost(selectedDevice.ip)) {
+    showToast("⚠️ 选中的设备地址不合法", "warning");
+    return;
+  }
+
+  ntpBtn.disabled = true;
+  ntpBtn.textContent = "⏳ 查询中...";
+  try {
+    const report: TimeDriftReport = await invoke("tauri_check_time_drift", { servers: [selectedDevice.ip] });
+    const rows = report.servers.map(server => `
+      <div class="port-result-item">
+        <span class="port-number">${escapeHtml(server.server)}</span>
+        <span class="port-service">${server.reachable ? `层级 ${server.stratum ?? "-"} · 参考源 ${escapeHtml(server.reference_id || "-")}` : escapeHtml(server.error || "无应答")}</span>
+        <span class="port-status ${server.reachable ? "open" : "closed"}">${server.offset_ms != null ? formatDrift(server.offset_ms) : "*"}</span>
+      </div>
+    `).join("");
+    portResults.innerHTML = `
+      <div class="port-results-header">
+        时间偏差 ${report.drift_ms != null ? formatDrift(report.drift_ms) : "-"} - ${DRIFT_HINTS[report.level]}
+      </div>
+      <div class="port-results-list">${rows}</div>
+    `;
+    if (report.level === "warning" || report.level === "critical") {
+      showToast(`🕒 ${DRIFT_HINTS[report.level]}`, report.level === "critical" ? "error" : "warning");
+    }
+  } catch (error) {
+    reportCommandError("时间偏差检测", error);
+  } finally {
+    ntpBtn.disabled = false;
+    ntpBtn.textContent = "🕒 时间偏差";
+  }
+}
+
+// 在端口面板中编辑选中设备的标签和备注；局域网设备默认按 MAC 记录，换了 IP 也能对上
+function editDeviceTags() {
+  if (!selectedDevice) return;
+  const device = selectedDevice;
+  const annotation = deviceAnnotation(device);
+  const target = annotation.target ?? device.mac ?? device.ip;
+
+  portResults.innerHTML = `
+    <div class="port-results-header">🏷️ ${escapeHtml(target)} 的标签</div>
+    <div class="tag-editor">
+      <input type="text" id="tag-editor-tags" placeholder="标签，空格或逗号分隔 (如 prod-db kids-tablet)" value="${escapeHtml(annotation.tags.join(" "))}" />
+      <textarea id="tag-editor-note" rows="3" placeholder="备注">${escapeHtml(annotation.note ?? "")}</textarea>
+      <div class="tag-editor-actions">
+        <button class="btn-primary" id="tag-editor-save">保存</button>
+        <button class="btn-secondary" id="tag-editor-clear" ${annotation.target ? "" : "disabled"}>清除</button>
+      </div>
+    </div>
+  `;
+
+  const save = async (tags: string[], note: string | null) => {
+    try {
+      inventory = await invoke("tauri_tag_target", { target, tags, note });
+      renderDeviceList();
+      showToast(tags.length || note ? `🏷️ 已保存 ${target} 的标签` : `🏷️ 已清除 ${target} 的标签`, "success");
+      editDeviceTags();
+    } catch (error) {
+      reportCommandError("保存标签", error);
+    }
+  };
+  document.getElementById("tag-editor-save")?.addEventListener("click", () => {
+    const tags = (document.getElementById("tag-editor-tags") as HTMLInputElement).value
+      .split(/[\s,]+/)
+      .filter(Boolean);
+    const note = (document.getElementById("tag-editor-note") as HTMLTextAreaElement).value.trim();
+    save(tags, note || null);
+  });
+  document.getElementById("tag-editor-clear")?.addEventListener("click", () => save([], null));
+}
+
+// ===== 多目标 Ping 对比 =====
+interface TargetPingStats {
+  target: string;
+  sent: number;
+  received: number;
+  loss_percent: number;
+  min_ms: number | null;
+  avg_ms: number | null;
+  max_ms: number | null;
+  jitter_ms: number | null;
+  samples: (number | null)[];
+}
+
+interface PingComparison {
+  started_at: string;
+  rounds: number;
+  interval_ms: number;
+  targets: TargetPingStats[];
+}
+
+const COMPARE_COLORS = ["#007aff", "#34c759", "#ff9500", "#af52de", "#ff3b30", "#5ac8fa", "#ffcc00", "#8e8e93"];
+
+function showPingCompare() {
+  // 默认对比：所选设备所在网段的 .1（通常是网关）、公共 DNS 和所选设备
+  const defaults: string[] = [];
+  const ip = selectedDevice?.ip;
+  if (ip && /^\d+\.\d+\.\d+\.\d+$/.test(ip)) {
+    defaults.push(ip.replace(/\.\d+$/, ".1"));
+  }
+  defaults.push("1.1.1.1", "8.8.8.8");
+  if (ip && !defaults.includes(ip)) defaults.push(ip);
+
+  portResults.innerHTML = `
+    <div class="port-results-header">📈 多目标 Ping 对比</div>
+    <div class="ping-compare-form">
+      <input type="text" id="ping-compare-targets" placeholder="目标，空格或逗号分隔" value="${escapeHtml(defaults.join(" "))}" />
+      <label>轮数 <input type="number" id="ping-compare-rounds" min="1" max="100" value="10" /></label>
+      <button class="btn-primary" id="ping-compare-run">开始</button>
+    </div>
+    <div id="ping-compare-result"></div>
+  `;
+  document.getElementById("ping-compare-run")?.addEventListener("click", runPingCompare);
+}
+
+async function runPingCompare() {
+  const button = document.getElementById("ping-compare-run") as HTMLButtonElement | null;
+  const result = document.getElementById("ping-compare-result");
+  const targets = (document.getElementById("ping-compare-targets") as HTMLInputElement).value
+    .split(/[\s,]+/)
+    .filter(Boolean);
+  const rounds = parseInt((document.getElementById("ping-compare-rounds") as HTMLInputElement).value, 10);
+  if (!result) return;
+  if (button) button.disabled = true;
+  result.innerHTML = `<div class="loading">正在 Ping ${targets.length} 个目标...</div>`;
+  try {
+    const comparison: PingComparison = await invoke("tauri_compare_ping", { targets, rounds });
+    renderPingComparison(result, comparison);
+  } catch (error) {
+    result.innerHTML = "";
+    reportCommandError("多目标 Ping 对比", error);
+  } finally {
+    if (button) button.disabled = false;
+  }
+}
+
+function renderPingComparison(container: HTMLElement, comparison: PingComparison) {
+  const fmt = (ms: number | null) => (ms === null ? "-" : `${ms.toFixed(1)} ms`);
+  const rows = comparison.targets
+    .map((t, i) => `
+      <tr>
+        <td><span class="ping-compare-swatch" style="background: ${COMPARE_COLORS[i % COMPARE_COLORS.length]}"></span>${escapeHtml(t.target)}</td>
+        <td>${t.received}/${t.sent}</td>
+        <td class="${t.loss_percent > 0 ? "ping-compare-loss" : ""}">${t.loss_percent.toFixed(0)}%</td>
+        <td>${fmt(t.min_ms)}</td>
+        <td>${fmt(t.avg_ms)}</td>
+        <td>${fmt(t.max_ms)}</td>
+        <td>${fmt(t.jitter_ms)}</td>
+      </tr>`)
+    .join("");
+  container.innerHTML = `
+    ${renderPingCompareChart(comparison)}
+    <table class="ping-compare-table">
+      <thead><tr><th>目标</th><th>收到</th><th>丢包</th><th>最小</th><th>平均</th><th>最大</th><th>抖动</th></tr></thead>
+      <tbody>${rows}</tbody>
+    </table>
+  `;
+}
+
+/** 每个目标一条折线，超时的轮次断开 */
+function renderPingCompareChart(comparison: PingComparison): string {
+  const width = 560;
+  const height = 160;
+  const pad = 24;
+  const maxMs = Math.max(1, ...comparison.targets.flatMap(t => t.samples.filter((s): s is number => s !== null)));
+  const step = comparison.rounds > 1 ? (width - pad * 2) / (comparison.rounds - 1) : 0;
+  const x = (round: number) => pad + round * step;
+  const y = (ms: number) => height - pad - (ms / maxMs) * (height - pad * 2);
+
+  let svg = `<svg class="ping-compare-chart" viewBox="0 0 ${width} ${height}" preserveAspectRatio="none">`;
+  svg += `<text x="4" y="${pad - 8}" class="ping-compare-axis">${maxMs.toFixed(0)} ms</text>`;
+  comparison.targets.forEach((t, i) => {
+    const color = COMPARE_COLORS[i % COMPARE_COLORS.length];
+    let segment: string[] = [];
+    const flush = () => {
+      if (segment.length > 1) {
+        svg += `<polyline points="${segment.join(" ")}" fill="none" stroke="${color}" stroke-width="2"/>`;
+      } else if (segment.length === 1) {
+        const [cx, cy] = segment[0].split(",");
+        svg += `<circle cx="${cx}" cy="${cy}" r="2.5" fill="${color}"/>`;
+      }
+      segment = [];
+    };
+    t.samples.forEach((ms, round) => {
+      if (ms === null) {
+        flush();
+        svg += `<line x1="${x(round)}" y1="${height - pad}" x2="${x(round)}" y2="${height - pad + 6}" stroke="${color}" stroke-width="2"/>`;
+      } else {
+        segment.push(`${x(round).toFixed(1)},${y(ms).toFixed(1)}`);
+      }
+    });
+    flush();
+  });
+  svg += `</svg>`;
+  return svg;
+}
+
+// ===== ARP 缓存 =====
+interface ArpEntry {
+  ip: string;
+  mac: string | null;
+  hostname: string | null;
+  interface: string | null;
+  kind: "dynamic" | "static" | "incomplete";
+  state?: string;
+  vendor: string | null;
+  randomized: boolean;
+  tags?: string[];
+}
+
+const ARP_KIND_LABELS: Record<ArpEntry["kind"], string> = {
+  dynamic: "动态",
+  static: "静态",
+  incomplete: "未解析",
+};
+
+async function showArpTable() {
+  portResults.innerHTML = `<div class="loading">正在读取 ARP 缓存...</div>`;
+  try {
+    const entries: ArpEntry[] = await invoke("tauri_arp_table");
+    renderArpTable(entries);
+  } catch (error) {
+    portResults.innerHTML = "";
+    reportCommandError("ARP 表读取", error);
+  }
+}
+
+function renderArpTable(entries: ArpEntry[]) {
+  const rows = entries
+    .map(e => {
+      const vendor = e.vendor || (e.randomized ? "随机 MAC" : "-");
+      const stale = e.kind === "incomplete" || e.state === "STALE" || e.state === "FAILED";
+      return `
+      <tr class="${stale ? "arp-stale" : ""}">
+        <td>${escapeHtml(e.ip)}${e.hostname ? `<div class="arp-hostname">${escapeHtml(e.hostname)}</div>` : ""}</td>
+        <td class="arp-mac">${escapeHtml(e.mac || "-")}</td>
+        <td>${escapeHtml(vendor)}</td>
+        <td>${escapeHtml(e.interface || "-")}</td>
+        <td>${ARP_KIND_LABELS[e.kind]}${e.state ? ` · ${escapeHtml(e.state)}` : ""}</td>
+        <td><button class="action-btn arp-delete-btn" data-ip="${escapeHtml(e.ip)}" title="从 ARP 缓存中删除">✕</button></td>
+      </tr>`;
+    })
+    .join("");
+  portResults.innerHTML = `
+    <div class="port-results-header arp-header">
+      <span>📋 ARP 缓存（${entries.length}）</span>
+      <button class="btn-secondary" id="arp-flush-btn" title="清空本机 ARP 缓存（需要管理员权限）">🧹 清空缓存</button>
+    </div>
+    ${entries.length === 0
+      ? `<div class="empty-state">ARP 表为空</div>`
+      : `<table class="arp-table">
+      <thead><tr><th>IP</th><th>MAC</th><th>厂商</th><th>接口</th><th>类型</th><th></th></tr></thead>
+      <tbody>${rows}</tbody>
+    </table>`}
+  `;
+  document.getElementById("arp-flush-btn")?.addEventListener("click", () => flushArpCache(null));
+  portResults.querySelectorAll<HTMLButtonElement>(".arp-delete-btn").forEach(btn => {
+    btn.addEventListener("click", () => flushArpCache(btn.dataset.ip || null));
+  });
+}
+
+async function flushArpCache(ip: string | null) {
+  try {
+    const flushed: boolean = await invoke("tauri_flush_arp_cache", { ip });
+    if (!flushed) return;
+    showToast(ip ? `已从 ARP 缓存中删除 ${ip}` : "ARP 缓存已清空", "success");
+    await showArpTable();
+  } catch (error) {
+    reportCommandError("清空 ARP 缓存", error);
+  }
+}
+
+// ===== 路由表 =====
+interface RouteEntry {
+  destination: string;
+  gateway: string | null;
+  interface: string;
+  metric: number | null;
+  ipv6: boolean;
+  interface_kind: string;
+}
+
+interface RouteLookup {
+  target: string;
+  ip: string;
+  route: RouteEntry | null;
+}
+
+let routeEntries: RouteEntry[] = [];
+
+async function showRoutes() {
+  portResults.innerHTML = `<div class="loading">正在读取路由表...</div>`;
+  try {
+    routeEntries = await invoke("tauri_get_routes");
+    renderRoutes(null);
+  } catch (error) {
+    portResults.innerHTML = "";
+    reportCommandError("路由表读取", error);
+  }
+}
+
+function isDefaultRoute(route: RouteEntry): boolean {
+  return route.destination === "0.0.0.0/0" || route.destination === "::/0";
+}
+
+function renderRoutes(lookup: RouteLookup | null) {
+  const matched = lookup?.route;
+  const rows = routeEntries
+    .map(r => {
+      const classes = [
+        isDefaultRoute(r) ? "route-default" : "",
+        r.interface_kind === "vpn" ? "route-vpn" : "",
+        matched && matched.destination === r.destination && matched.interface === r.interface ? "route-matched" : "",
+      ].filter(Boolean).join(" ");
+      return `
+      <tr class="${classes}">
+        <td class="route-destination">${escapeHtml(r.destination)}</td>
+        <td class="route-destination">${escapeHtml(r.gateway || "直连")}</td>
+        <td>${escapeHtml(r.interface)}${r.interface_kind === "vpn" ? ` <span class="route-vpn-badge">VPN</span>` : ""}</td>
+        <td>${r.metric ?? "-"}</td>
+      </tr>`;
+    })
+    .join("");
+  let lookupText = "";
+  if (lookup) {
+    lookupText = matched
+      ? `到 ${escapeHtml(lookup.ip)} 的流量走 ${escapeHtml(matched.destination)}，经 ${escapeHtml(matched.gateway || "直连")} 从 ${escapeHtml(matched.interface)} 发出`
+      : `没有到 ${escapeHtml(lookup.ip)} 的路由`;
+  }
+  portResults.innerHTML = `
+    <div class="port-results-header">🧭 路由表（${routeEntries.length}）</div>
+    <div class="ping-compare-form">
+      <input type="text" id="route-target" placeholder="目标主机或 IP，查询走哪个接口" value="${escapeHtml(lookup?.target || "")}" />
+      <button class="btn-primary" id="route-lookup-btn">查询</button>
+    </div>
+    ${lookupText ? `<div class="route-lookup">${lookupText}</div>` : ""}
+    ${routeEntries.length === 0
+      ? `<div class="empty-state">路由表为空</div>`
+      : `<table class="arp-table route-table">
+      <thead><tr><th>目标网段</th><th>网关</th><th>接口</th><th>跃点数</th></tr></thead>
+      <tbody>${rows}</tbody>
+    </table>`}
+  `;
+  document.getElementById("route-lookup-btn")?.addEventListener("click", lookupRoute);
+  document.getElementById("route-target")?.addEventListener("keydown", e => {
+    if (e.key === "Enter") lookupRoute();
+  });
+}
+
+async function lookupRoute() {
+  const target = (document.getElementById("route-target") as HTMLInputElement | null)?.value.trim();
+  if (!target) return;
+  try {
+    const lookup: RouteLookup = await invoke("tauri_route_to", { target });
+    renderRoutes(lookup);
+  } catch (error) {
+    reportCommandError("路由表读取", error);
+  }
+}
+
+// ===== 网卡流量 =====
+interface InterfaceRate {
+  name: string;
+  kind: InterfaceKind;
+  is_up: boolean;
+  mtu: number | null;
+  speed_mbps: number | null;
+  rx_bytes: number;
+  tx_bytes: number;
+  rx_errors: number;
+  tx_errors: number;
+  rx_bytes_per_sec: number | null;
+  tx_bytes_per_sec: number | null;
+  new_errors: number;
+  utilization_percent: number | null;
+}
+
+interface InterfaceSample {
+  sampled_at: string;
+  elapsed_ms: number;
+  interfaces: InterfaceRate[];
+}
+
+let interfaceStatsUnlisten: UnlistenFn | null = null;
+
+async function showInterfaceStats() {
+  portResults.innerHTML = `
+    <div class="port-results-header">📶 网卡流量 <button class="btn-secondary" id="iface-stats-stop-btn">停止</button></div>
+    <div id="iface-stats"><div class="loading">正在采样网卡计数器...</div></div>
+  `;
+  document.getElementById("iface-stats-stop-btn")?.addEventListener("click", () => void stopInterfaceStats());
+  if (!interfaceStatsUnlisten) {
+    interfaceStatsUnlisten = await listen<InterfaceSample>("interface-stats", (event) => {
+      const container = document.getElementById("iface-stats");
+      // 切换到其他面板后停止采样
+      if (!container) {
+        void stopInterfaceStats();
+        return;
+      }
+      container.innerHTML = renderInterfaceStats(event.payload);
+    });
+  }
+  try {
+    await invoke("tauri_start_interface_watch", { intervalSecs: 2 });
+  } catch (error) {
+    await stopInterfaceStats();
+    reportCommandError("网卡流量采样", error);
+  }
+}
+
+async function stopInterfaceStats() {
+  interfaceStatsUnlisten?.();
+  interfaceStatsUnlisten = null;
+  document.getElementById("iface-stats-stop-btn")?.remove();
+  try {
+    await invoke("tauri_stop_interface_watch");
+  } catch (error) {
+    reportCommandError("停止网卡流量采样", error);
+  }
+}
+
+function formatRate(bytesPerSec: number | null): string {
+  return bytesPerSec === null ? "-" : `${formatBytes(bytesPerSec)}/s`;
+}
+
+function formatLinkSpeed(mbps: number | null): string {
+  if (!mbps) return "-";
+  return mbps >= 1000 && mbps % 1000 === 0 ? `${mbps / 1000} Gb/s` : `${mbps} Mb/s`;
+}
+
+function renderInterfaceStats(sample: InterfaceSample): string {
+  if (sample.interfaces.length === 0) {
+    return `<div class="empty-state">没有可统计的网卡</div>`;
+  }
+  const rows = sample.interfaces
+    .map(i => {
+      const errors = i.rx_errors + i.tx_errors;
+      const utilization = i.utilization_percent === null ? "" : ` <span class="iface-utilization">${i.utilization_percent.toFixed(0)}%</span>`;
+      return `
+      <tr class="${i.is_up ? "" : "iface-down"}">
+        <td>${escapeHtml(i.name)} <span class="arp-hostname">${INTERFACE_KIND_LABELS[i.kind] ?? i.kind}</span></td>
+        <td>${i.is_up ? "已连接" : "未连接"}</td>
+        <td>${formatLinkSpeed(i.speed_mbps)}</td>
+        <td>${i.mtu ?? "-"}</td>
+        <td class="iface-rate">${formatRate(i.rx_bytes_per_sec)}${utilization}</td>
+        <td class="iface-rate">${formatRate(i.tx_bytes_per_sec)}</td>
+        <td>${formatBytes(i.rx_bytes)} / ${formatBytes(i.tx_bytes)}</td>
+        <td class="${i.new_errors > 0 ? "iface-errors" : ""}">${errors}${i.new_errors > 0 ? ` (+${i.new_errors})` : ""}</td>
+      </tr>`;
+    })
+    .join("");
+  return `
+    <div class="route-lookup">采样时间 ${escapeHtml(sample.sampled_at)}</div>
+    <table class="arp-table iface-table">
+      <thead><tr><th>网卡</th><th>状态</th><th>链路速率</th><th>MTU</th><th>接收</th><th>发送</th><th>累计收 / 发</th><th>错误</th></tr></thead>
+      <tbody>${rows}</tbody>
+    </table>
+  `;
+}
+
 function escapeHtml(text: string): string {
   return text
     .replace(/&/g, "&amp;")
@@ -1544,7 +2884,12 @@ function renderSpectrumChart() {
 
 pingBtn.addEventListener("click", runPing);
 traceBtn.addEventListener("click", runTraceroute);
+ntpBtn?.addEventListener("click", runTimeDrift);
+document.getElementById("tag-btn")?.addEventListener("click", editDeviceTags);
+document.getElementById("refresh-device-btn")?.addEventListener("click", refreshSelectedDevice);
+deviceTagFilter?.addEventListener("input", renderDeviceList);
 multiPingBtn?.addEventListener("click", runMultiPing);
+document.getElementById("compare-ping-btn")?.addEventListener("click", showPingCompare);
 
 // ===== 独立监测页面 =====
 const monitorSubnet = document.getElementById("monitor-subnet") as HTMLSelectElement;
@@ -1575,11 +2920,10 @@ async function initMonitorPage() {
     const interfaces: NetworkInterface[] = await invoke("tauri_get_interfaces");
     monitorSubnet.innerHTML = '<option value="">选择网段...</option>';
     interfaces.forEach((iface) => {
-      if (iface.ip && !iface.ip.startsWith("127.")) {
-        const subnet = iface.ip.split(".").slice(0, 3).join(".") + ".0/24";
+      if (!iface.ip.startsWith("127.")) {
         const opt = document.createElement("option");
-        opt.value = subnet;
-        opt.textContent = `${iface.name} - ${subnet}`;
+        opt.value = iface.subnet;
+        opt.textContent = iface.ip ? `${iface.name} - ${iface.subnet}` : iface.subnet;
         monitorSubnet.appendChild(opt);
       }
     });
@@ -1956,6 +3300,7 @@ whoisInput?.addEventListener("keydown", (e) => {
 const sslHostInput = document.getElementById("ssl-host-input") as HTMLInputElement;
 const sslPortInput = document.getElementById("ssl-port-input") as HTMLInputElement;
 const sslCheckBtn = document.getElementById("ssl-check-btn") as HTMLButtonElement;
+const sslWatchBtn = document.getElementById("ssl-watch-btn") as HTMLButtonElement;
 const sslLoading = document.getElementById("ssl-loading") as HTMLDivElement;
 const sslError = document.getElementById("ssl-error") as HTMLDivElement;
 const sslResults = document.getElementById("ssl-results") as HTMLDivElement;
@@ -2071,8 +3416,29 @@ async function checkSslCert() {
   }
 }
 
+// 登记到期监控，由后台监控按间隔检查
+async function watchSslCert() {
+  const host = sslHostInput.value.trim();
+  const rawPort = sslPortInput.value.trim();
+  const port = rawPort ? parsePort(rawPort) : 443;
+  if (!host || !isValidHost(host) || port === null) {
+    showToast("⚠️ 请输入有效主机地址和 1-65535 的端口", "warning");
+    return;
+  }
+  sslWatchBtn.disabled = true;
+  try {
+    await invoke("tauri_watch_certificate", { host, port });
+    showToast(`✅ 已加入证书到期监控：${host}:${port}`, "success");
+  } catch (error) {
+    reportCommandError("添加证书监控", error);
+  } finally {
+    sslWatchBtn.disabled = false;
+  }
+}
+
 // 绑定 SSL 事件
 sslCheckBtn?.addEventListener("click", checkSslCert);
+sslWatchBtn?.addEventListener("click", watchSslCert);
 sslHostInput?.addEventListener("keydown", (e) => {
   if (e.key === "Enter") {
     checkSslCert();
@@ -2182,4 +3548,5 @@ dnsDomainInput?.addEventListener("keydown", (e) => {
 // ===== 初始化 =====
 window.addEventListener("DOMContentLoaded", () => {
   scanPorts();
+  loadInventory();
 });
@@ -82,6 +82,17 @@ export function buildSubnetScanEstimateMessage(subnet: string): string | null {
   return `当前范围约 ${hostCount} 台主机，当前范围估算耗时 ${estimateText}，${warningSuffix}`;
 }
 
+export function formatBytes(bytes: number): string {
+  const units = ["B", "KB", "MB", "GB", "TB"];
+  let value = bytes;
+  let unit = 0;
+  while (value >= 1024 && unit < units.length - 1) {
+    value /= 1024;
+    unit += 1;
+  }
+  return unit === 0 ? `${Math.round(value)} B` : `${value.toFixed(1)} ${units[unit]}`;
+}
+
 export function buildScanLoadingHtml(baseMessage: string, startAt: number, estimateSeconds: number | null): string {
   if (!estimateSeconds) return baseMessage;
 
@@ -120,12 +120,30 @@ const mockInvoke = vi.fn((cmd: string, args?: unknown) => {
         ip: "192.168.1.100",
         netmask: "255.255.255.0",
         subnet: "192.168.1.0/24",
+        prefix_len: 24,
+        kind: "wifi",
       },
     ]);
   }
   if (cmd === "tauri_get_current_subnet") {
     return Promise.resolve("192.168.1.0/24");
   }
+  if (cmd === "tauri_listener_ownership") {
+    return Promise.resolve({
+      generated_at: "2024-01-01 12:00:00",
+      total: 2,
+      buckets: [
+        { kind: "host", listeners: 1, ports: [3000] },
+        { kind: "container", listeners: 1, ports: [5432] },
+        { kind: "vm", listeners: 0, ports: [] },
+        { kind: "system", listeners: 0, ports: [] },
+      ],
+      listeners: [],
+    });
+  }
+  if (cmd === "tauri_list_vpn_peers") {
+    return Promise.resolve([]);
+  }
   if (cmd === "tauri_discover_devices") {
     return Promise.resolve([
       {
@@ -142,12 +160,241 @@ const mockInvoke = vi.fn((cmd: string, args?: unknown) => {
       },
     ]);
   }
+  if (cmd === "tauri_device_latency") {
+    return Promise.resolve({
+      generated_at: "2026-10-16 10:00:00",
+      devices: [
+        {
+          ip: "192.168.1.1",
+          hostname: "router",
+          samples: [
+            { at: "2026-10-16 09:50:00", rtt_ms: 1.2 },
+            { at: "2026-10-16 10:00:00", rtt_ms: 1.4 },
+          ],
+          last_ms: 1.4,
+          avg_ms: 1.3,
+          min_ms: 1.2,
+          max_ms: 1.4,
+          jitter_ms: 0.2,
+          loss_percent: 0,
+          level: "good",
+        },
+      ],
+    });
+  }
+  if (cmd === "tauri_smart_scan") {
+    return Promise.resolve({
+      devices: [
+        {
+          ip: "10.20.0.1",
+          mac: "00:11:22:33:44:20",
+          hostname: null,
+          is_online: true,
+        },
+      ],
+      scan_method: "ARP (高级, 802.1Q VLAN 20)",
+      scan_time_ms: 3000,
+      has_permission: true,
+      interface: "eth0",
+      vlan: args?.vlan || 20,
+    });
+  }
+  if (cmd === "tauri_discover_neighbors") {
+    return Promise.resolve({
+      interface: "eth0",
+      listened_secs: 12,
+      neighbors: [
+        {
+          protocol: "lldp",
+          interface: "eth0",
+          source_mac: "00:1b:54:aa:bb:0c",
+          chassis_id: "00:1b:54:aa:bb:00",
+          system_name: "sw-core-01",
+          port_id: "Gi1/0/12",
+          port_description: "desk-42",
+          vlan: 20,
+          management_address: "10.0.0.2",
+          platform: null,
+          system_description: null,
+          ttl_secs: 120,
+        },
+      ],
+    });
+  }
+  if (cmd === "tauri_list_shares") {
+    return Promise.resolve({
+      host: args?.ip || "192.168.1.20",
+      smb_shares: [
+        { name: "public", kind: "disk", comment: "Public files", public: true },
+        { name: "IPC$", kind: "ipc", comment: "IPC Service", public: false },
+      ],
+      nfs_exports: [{ path: "/srv/media", clients: ["192.168.1.0/24"], public: false }],
+      errors: [],
+    });
+  }
+  if (cmd === "tauri_detect_service") {
+    return Promise.resolve({
+      port: args?.port || 3000,
+      service: "Web (3000)",
+      service_type: "web",
+      server: "nginx/1.25.3",
+      content_type: "text/html",
+      security_headers: {
+        grade: "C",
+        checks: [
+          { header: "Strict-Transport-Security", status: "missing", detail: "未启用 HSTS，也没有跳转到 HTTPS" },
+          { header: "Content-Security-Policy", status: "ok" },
+          { header: "X-Frame-Options", status: "ok" },
+          { header: "X-Content-Type-Options", status: "ok" },
+        ],
+      },
+    });
+  }
+  if (cmd === "tauri_printer_info") {
+    return Promise.resolve({
+      host: args?.ip || "192.168.1.30",
+      port: args?.port || 631,
+      protocol: args?.port === 9100 ? "pjl" : "ipp",
+      make_and_model: "HP LaserJet M404dn",
+      name: "office",
+      location: "2F",
+      info: null,
+      state: "idle",
+      state_reasons: [],
+      status_message: null,
+      markers: [{ name: "Black Cartridge", level: 40 }],
+    });
+  }
+  if (cmd === "tauri_check_time_drift") {
+    const server = args?.servers?.[0] || "pool.ntp.org";
+    return Promise.resolve({
+      checked_at: "2026-01-01 12:00:00",
+      source: args?.servers?.length ? "given" : "default",
+      servers: [
+        {
+          server,
+          address: "192.168.1.1",
+          reachable: true,
+          stratum: 2,
+          reference_id: "10.0.0.1",
+          offset_ms: 12.5,
+          delay_ms: 1.8,
+          error: null,
+        },
+      ],
+      drift_ms: 12.5,
+      level: "ok",
+    });
+  }
+  if (cmd === "tauri_arp_table") {
+    return Promise.resolve([
+      {
+        ip: "192.168.1.1",
+        mac: "a4:91:b1:2c:7e:10",
+        hostname: "router.lan",
+        interface: "en0",
+        kind: "dynamic",
+        vendor: null,
+        randomized: false,
+      },
+    ]);
+  }
+  if (cmd === "tauri_flush_arp_cache") {
+    return Promise.resolve(true);
+  }
+  if (cmd === "tauri_interface_stats") {
+    return Promise.resolve([
+      {
+        name: "en0",
+        kind: "wifi",
+        is_up: true,
+        mtu: 1500,
+        speed_mbps: null,
+        rx_bytes: 9876543210,
+        tx_bytes: 612345678,
+        rx_packets: 7512043,
+        tx_packets: 4102233,
+        rx_errors: 3,
+        tx_errors: 0,
+      },
+    ]);
+  }
+  if (cmd === "tauri_start_interface_watch") {
+    return Promise.resolve(true);
+  }
+  if (cmd === "tauri_stop_interface_watch") {
+    return Promise.resolve(true);
+  }
+  if (cmd === "tauri_get_routes") {
+    return Promise.resolve([
+      { destination: "0.0.0.0/0", gateway: "192.168.1.1", interface: "en0", metric: null, ipv6: false, interface_kind: "wifi" },
+      { destination: "10.20.0.0/16", gateway: "10.8.0.1", interface: "utun4", metric: null, ipv6: false, interface_kind: "vpn" },
+    ]);
+  }
+  if (cmd === "tauri_route_to") {
+    return Promise.resolve({
+      target: args?.target,
+      ip: args?.target,
+      route: { destination: "10.20.0.0/16", gateway: "10.8.0.1", interface: "utun4", metric: null, ipv6: false, interface_kind: "vpn" },
+    });
+  }
+  if (cmd === "tauri_discover_dhcp") {
+    return Promise.resolve({
+      interface: "eth0",
+      listened_secs: 5,
+      passive: args?.passive || false,
+      servers: [
+        {
+          message_type: "offer",
+          server_id: "192.168.1.1",
+          server_mac: "00:11:32:00:00:01",
+          offered_ip: "192.168.1.57",
+          subnet_mask: "255.255.255.0",
+          network: "192.168.1.0/24",
+          routers: ["192.168.1.1"],
+          dns_servers: ["192.168.1.1"],
+          domain: "lan",
+          lease_secs: 86400,
+        },
+      ],
+      rogue_suspected: false,
+    });
+  }
+  if (cmd === "tauri_check_port_forward") {
+    return Promise.resolve({
+      port: args?.port || 8080,
+      reflector: "https://vps.example.com:7070",
+      public_host: "203.0.113.7",
+      reachable: true,
+      rtt_ms: 24.5,
+      verdict: "reachable",
+      listener: "node",
+      listener_addresses: ["0.0.0.0"],
+      error: null,
+    });
+  }
+  if (cmd === "tauri_resolve_host_pattern") {
+    return Promise.resolve([
+      {
+        name: "node01.lan",
+        resolved: {
+          original: "node01.lan",
+          ip: "192.168.1.21",
+          is_domain: true,
+          hostname: "node01.lan",
+          addresses: [{ ip: "192.168.1.21", family: "ipv4" }],
+        },
+      },
+      { name: "node02.lan", error: "node02.lan 不可达：DNS 解析失败" },
+    ]);
+  }
   if (cmd === "tauri_resolve_target") {
     return Promise.resolve({
       original: args?.target || "example.com",
       ip: "93.184.216.34",
       is_domain: true,
       hostname: "example.com",
+      addresses: [{ ip: "93.184.216.34", family: "ipv4" }],
     });
   }
   if (cmd === "tauri_quick_scan") {
@@ -200,6 +447,7 @@ const mockInvoke = vi.fn((cmd: string, args?: unknown) => {
         ports: [
           { host_port: 8080, container_port: 80, protocol: "tcp", host_ip: "0.0.0.0" },
         ],
+        health: "unhealthy",
       },
     ]);
   }
@@ -220,6 +468,10 @@ vi.mock("@tauri-apps/api/core", () => ({
   invoke: mockInvoke,
 }));
 
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: vi.fn(() => Promise.resolve(() => {})),
+}));
+
 // 模拟 Tauri OPENER plugin
 const mockOpenUrl = vi.fn();
 